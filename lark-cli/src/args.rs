@@ -0,0 +1,105 @@
+//! 命令行参数。
+
+/// 不带值的选项，其他 `--name` 选项都需要一个值
+const SWITCHES: &[&str] = &["card", "help"];
+
+/// 位置参数和 `--name value` 形式的选项，选项可以重复出现
+#[derive(Debug, Default)]
+pub struct Args {
+    positionals: Vec<String>,
+    options: Vec<(String, String)>,
+}
+
+impl Args {
+    pub fn parse(args: impl IntoIterator<Item = String>) -> Result<Args, String> {
+        let mut parsed = Args::default();
+        let mut args = args.into_iter();
+        while let Some(arg) = args.next() {
+            match arg.strip_prefix("--") {
+                Some(name) if SWITCHES.contains(&name) => {
+                    parsed.options.push((name.to_string(), String::new()))
+                }
+                Some(name) if !name.is_empty() => {
+                    let value = args
+                        .next()
+                        .ok_or_else(|| format!("option --{} requires a value", name))?;
+                    parsed.options.push((name.to_string(), value));
+                }
+                // `--` 之后都是位置参数
+                Some(_) => parsed.positionals.extend(args.by_ref()),
+                None => parsed.positionals.push(arg),
+            }
+        }
+        Ok(parsed)
+    }
+
+    pub fn positionals(&self) -> &[String] {
+        &self.positionals
+    }
+
+    /// 第一个位置参数作为子命令，其余的参数留给子命令
+    pub fn shift(&mut self) -> Option<String> {
+        if self.positionals.is_empty() {
+            None
+        } else {
+            Some(self.positionals.remove(0))
+        }
+    }
+
+    /// 恰好 `N` 个位置参数
+    pub fn positional<const N: usize>(&self) -> Result<[&str; N], String> {
+        if self.positionals.len() != N {
+            return Err(format!(
+                "expected {} arguments, got {}",
+                N,
+                self.positionals.len()
+            ));
+        }
+        let mut out = [""; N];
+        for (slot, arg) in out.iter_mut().zip(&self.positionals) {
+            *slot = arg;
+        }
+        Ok(out)
+    }
+
+    /// 选项最后一次出现时的值
+    pub fn option(&self, name: &str) -> Option<&str> {
+        self.options
+            .iter()
+            .rev()
+            .find(|(n, _)| n == name)
+            .map(|(_, value)| value.as_str())
+    }
+
+    pub fn options<'a>(&'a self, name: &'a str) -> impl Iterator<Item = &'a str> + 'a {
+        self.options
+            .iter()
+            .filter(move |(n, _)| n == name)
+            .map(|(_, value)| value.as_str())
+    }
+
+    pub fn switch(&self, name: &str) -> bool {
+        self.options.iter().any(|(n, _)| n == name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse() {
+        let args = [
+            "send", "--card", "ou_1", "--type", "open_id", "--", "--text",
+        ];
+        let mut args = Args::parse(args.iter().map(|arg| arg.to_string())).unwrap();
+        assert_eq!(args.shift().as_deref(), Some("send"));
+        assert_eq!(args.positional::<2>().unwrap(), ["ou_1", "--text"]);
+        assert_eq!(args.option("type"), Some("open_id"));
+        assert!(args.switch("card"));
+        assert!(args.positional::<1>().is_err());
+
+        let err = Args::parse(vec!["call".to_string(), "--query".to_string()]).unwrap_err();
+        assert_eq!(err, "option --query requires a value");
+    }
+}
@@ -0,0 +1,313 @@
+//! 各个子命令。
+
+use std::io::Read;
+
+use lark_sdk::im::SendMessage;
+use lark_sdk::{AuthType, Client, Method, RequestParts, DEFAULT_CONTENT_TYPE};
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+use crate::args::Args;
+
+pub type Result<T> = std::result::Result<T, Box<dyn std::error::Error>>;
+
+/// 由 ID 的前缀推断 ID 类型：`ou_` 为 open_id，`on_` 为 union_id，`oc_` 为 chat_id，包含 `@` 的为
+/// 邮箱，其他为 user_id
+pub fn id_type(id: &str) -> &'static str {
+    if id.starts_with("ou_") {
+        "open_id"
+    } else if id.starts_with("on_") {
+        "union_id"
+    } else if id.starts_with("oc_") {
+        "chat_id"
+    } else if id.contains('@') {
+        "email"
+    } else {
+        "user_id"
+    }
+}
+
+/// `lark send <receive_id> <text> [--type <id_type>] [--card]`
+pub async fn send(client: &Client, args: &Args) -> Result<()> {
+    let [receive_id, text] = args.positional::<2>()?;
+    let receive_id_type = args.option("type").unwrap_or_else(|| id_type(receive_id));
+    let (msg_type, content) = if args.switch("card") {
+        ("interactive", serde_json::from_str::<Value>(text)?)
+    } else {
+        ("text", json!({ "text": text }))
+    };
+    let request = SendMessage {
+        receive_id_type: receive_id_type.into(),
+        receive_id: receive_id.to_string(),
+        msg_type: msg_type.to_string(),
+        content: content.to_string(),
+        uuid: None,
+    };
+    let message = client.send(&request).await?.into_data()?;
+    println!("{}", message.message_id);
+    Ok(())
+}
+
+#[derive(Debug, Clone, lark_sdk::Request)]
+#[request(
+    method = "POST",
+    url = "/open-apis/contact/v3/users/batch_get_id",
+    auth = "tenant",
+    response = UserIds
+)]
+struct BatchGetUserId {
+    #[request(query)]
+    user_id_type: String,
+    #[request(body)]
+    emails: Option<Vec<String>>,
+    #[request(body)]
+    mobiles: Option<Vec<String>>,
+}
+
+#[derive(Debug, Deserialize, lark_sdk::Response)]
+struct UserIds {
+    #[serde(default)]
+    user_list: Vec<UserIdItem>,
+}
+
+#[derive(Debug, Deserialize)]
+struct UserIdItem {
+    user_id: Option<String>,
+}
+
+#[derive(Debug, Clone, lark_sdk::Request)]
+#[request(
+    method = "GET",
+    url = "/open-apis/contact/v3/users/:user_id",
+    auth = "tenant",
+    response = Value
+)]
+struct GetUser {
+    #[request(path)]
+    user_id: String,
+    #[request(query)]
+    user_id_type: String,
+}
+
+/// `lark user <open_id|user_id|union_id|email|mobile> [--type <id_type>]`
+pub async fn user(client: &Client, args: &Args) -> Result<()> {
+    let [id] = args.positional::<1>()?;
+    let is_mobile =
+        id.starts_with('+') || (id.len() >= 11 && id.chars().all(|c| c.is_ascii_digit()));
+    let (user_id, user_id_type) = if id.contains('@') || is_mobile {
+        let (emails, mobiles) = if is_mobile {
+            (None, Some(vec![id.to_string()]))
+        } else {
+            (Some(vec![id.to_string()]), None)
+        };
+        let request = BatchGetUserId {
+            user_id_type: "open_id".to_string(),
+            emails,
+            mobiles,
+        };
+        let ids = client.send(&request).await?.into_data()?;
+        let open_id = ids
+            .user_list
+            .into_iter()
+            .find_map(|user| user.user_id)
+            .ok_or_else(|| format!("user {} not found", id))?;
+        (open_id, "open_id")
+    } else {
+        let user_id_type = args.option("type").unwrap_or_else(|| id_type(id));
+        (id.to_string(), user_id_type)
+    };
+    let request = GetUser {
+        user_id,
+        user_id_type: user_id_type.to_string(),
+    };
+    let data = client.send(&request).await?.into_data()?;
+    println!("{}", serde_json::to_string_pretty(&data["user"])?);
+    Ok(())
+}
+
+#[derive(Debug, Clone, lark_sdk::Request)]
+#[request(
+    method = "GET",
+    url = "/open-apis/drive/v1/files/:file_token/download",
+    auth = "tenant",
+    empty
+)]
+struct DownloadFile {
+    #[request(path)]
+    file_token: String,
+}
+
+#[derive(Debug, Clone, lark_sdk::Request)]
+#[request(
+    method = "GET",
+    url = "/open-apis/im/v1/messages/:message_id/resources/:file_key",
+    auth = "tenant",
+    empty
+)]
+struct GetMessageResource {
+    #[request(path)]
+    message_id: String,
+    #[request(path)]
+    file_key: String,
+    /// `file` 或 `image`
+    #[request(query = "type")]
+    resource_type: String,
+}
+
+/// `lark download <file_token> <output>`，下载云空间中的文件
+pub async fn download(client: &Client, args: &Args) -> Result<()> {
+    let [file_token, output] = args.positional::<2>()?;
+    let request = DownloadFile {
+        file_token: file_token.to_string(),
+    };
+    write_output(output, &client.send_raw(&request).await?)
+}
+
+/// `lark resource <message_id> <file_key> <output> [--type file|image]`，下载消息中的文件或图片
+pub async fn resource(client: &Client, args: &Args) -> Result<()> {
+    let [message_id, file_key, output] = args.positional::<3>()?;
+    let request = GetMessageResource {
+        message_id: message_id.to_string(),
+        file_key: file_key.to_string(),
+        resource_type: args.option("type").unwrap_or("file").to_string(),
+    };
+    write_output(output, &client.send_raw(&request).await?)
+}
+
+fn write_output(output: &str, bytes: &[u8]) -> Result<()> {
+    std::fs::write(output, bytes).map_err(|err| format!("write {}: {}", output, err))?;
+    eprintln!("{} bytes written to {}", bytes.len(), output);
+    Ok(())
+}
+
+/// `lark call <method> <path> [body] [--query key=value]... [--auth tenant|app|none]
+/// [--user-token <token>]`，成功时返回 `true`
+pub async fn call(client: &Client, args: &Args) -> Result<bool> {
+    let parts = call_parts(args)?;
+    let response: Value = client.send_parts(parts).await?;
+    println!("{}", serde_json::to_string_pretty(&response)?);
+    Ok(response["code"].as_i64().unwrap_or(0) == 0)
+}
+
+fn call_parts(args: &Args) -> Result<RequestParts> {
+    let (method, path, body) = match args.positionals() {
+        [method, path] => (method, path, None),
+        [method, path, body] => (method, path, Some(body)),
+        _ => return Err("call expects <method> <path> [body]".into()),
+    };
+    let method: Method = serde_json::from_value(Value::String(method.to_ascii_uppercase()))
+        .map_err(|_| format!("unsupported method {}", method))?;
+    let url = if path.starts_with('/') {
+        path.to_string()
+    } else {
+        format!("/open-apis/{}", path)
+    };
+    let mut query = Vec::new();
+    for pair in args.options("query") {
+        let (key, value) = pair
+            .split_once('=')
+            .ok_or_else(|| format!("query {} is not key=value", pair))?;
+        query.push((key.to_string(), value.to_string()));
+    }
+    let mut headers = Vec::new();
+    let auth = match args.option("user-token") {
+        Some(token) => {
+            headers.push(("Authorization".to_string(), format!("Bearer {}", token)));
+            AuthType::None
+        }
+        None => match args.option("auth").unwrap_or("tenant") {
+            "tenant" => AuthType::Tenant,
+            "app" => AuthType::App,
+            "none" => AuthType::None,
+            other => return Err(format!("unsupported auth {}", other).into()),
+        },
+    };
+    let body = match body {
+        Some(body) => {
+            let body = read_body(body)?;
+            // 提前校验，避免把错误的 JSON 发给接口
+            serde_json::from_str::<Value>(&body)?;
+            Some(body.into_bytes())
+        }
+        None => None,
+    };
+    Ok(RequestParts {
+        method,
+        url,
+        query,
+        headers,
+        content_type: DEFAULT_CONTENT_TYPE.to_string(),
+        body,
+        endpoint: None,
+        auth,
+    })
+}
+
+/// 请求体：JSON 字符串，`@path` 读取文件，`-` 读取标准输入
+fn read_body(body: &str) -> Result<String> {
+    if body == "-" {
+        let mut content = String::new();
+        std::io::stdin().read_to_string(&mut content)?;
+        Ok(content)
+    } else if let Some(path) = body.strip_prefix('@') {
+        Ok(std::fs::read_to_string(path).map_err(|err| format!("read {}: {}", path, err))?)
+    } else {
+        Ok(body.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(args: &[&str]) -> Args {
+        Args::parse(args.iter().map(|arg| arg.to_string())).unwrap()
+    }
+
+    #[test]
+    fn infer_id_type() {
+        assert_eq!(id_type("ou_1"), "open_id");
+        assert_eq!(id_type("oc_1"), "chat_id");
+        assert_eq!(id_type("on_1"), "union_id");
+        assert_eq!(id_type("a@example.com"), "email");
+        assert_eq!(id_type("3f1a"), "user_id");
+    }
+
+    #[test]
+    fn call_request() {
+        let parts = call_parts(&args(&[
+            "post",
+            "im/v1/messages",
+            r#"{"content":"{}"}"#,
+            "--query",
+            "receive_id_type=open_id",
+            "--auth",
+            "app",
+        ]))
+        .unwrap();
+        assert_eq!(parts.method, Method::Post);
+        assert_eq!(parts.url, "/open-apis/im/v1/messages");
+        assert_eq!(
+            parts.query,
+            vec![("receive_id_type".to_string(), "open_id".to_string())]
+        );
+        assert_eq!(parts.auth, AuthType::App);
+        assert_eq!(parts.body.as_deref(), Some(&br#"{"content":"{}"}"#[..]));
+
+        let parts = call_parts(&args(&[
+            "GET",
+            "/open-apis/authen/v1/user_info",
+            "--user-token",
+            "u-1",
+        ]))
+        .unwrap();
+        assert_eq!(parts.auth, AuthType::None);
+        assert_eq!(
+            parts.headers,
+            vec![("Authorization".to_string(), "Bearer u-1".to_string())]
+        );
+
+        assert!(call_parts(&args(&["HEAD", "/open-apis/x"])).is_err());
+        assert!(call_parts(&args(&["POST", "/open-apis/x", "{"])).is_err());
+    }
+}
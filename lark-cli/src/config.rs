@@ -0,0 +1,86 @@
+//! 应用凭证和接口地址的配置。
+
+use std::path::PathBuf;
+
+use serde::Deserialize;
+
+/// 配置文件的内容，环境变量 `LARK_APP_ID`、`LARK_APP_SECRET`、`LARK_DOMAIN` 优先于配置文件
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+pub struct Config {
+    pub app_id: Option<String>,
+    pub app_secret: Option<String>,
+    /// 接口地址，`feishu`、`lark` 或完整的地址，默认为飞书
+    pub domain: Option<String>,
+}
+
+impl Config {
+    /// 读取 `path`、环境变量 `LARK_CONFIG` 或 `~/.config/lark/config.json` 中的配置，再使用环境变量
+    /// 覆盖。只有默认位置的配置文件可以不存在
+    pub fn load(path: Option<&str>) -> Result<Config, String> {
+        let explicit = path
+            .map(PathBuf::from)
+            .or_else(|| std::env::var_os("LARK_CONFIG").map(PathBuf::from));
+        let path = explicit.clone().or_else(|| {
+            std::env::var_os("HOME")
+                .map(|home| PathBuf::from(home).join(".config/lark/config.json"))
+        });
+        let file = match path {
+            Some(path) if explicit.is_some() || path.exists() => {
+                let content = std::fs::read_to_string(&path)
+                    .map_err(|err| format!("read {}: {}", path.display(), err))?;
+                serde_json::from_str(&content)
+                    .map_err(|err| format!("parse {}: {}", path.display(), err))?
+            }
+            _ => Config::default(),
+        };
+        Ok(file.merge_env(|name| std::env::var(name).ok()))
+    }
+
+    fn merge_env(self, env: impl Fn(&str) -> Option<String>) -> Config {
+        Config {
+            app_id: env("LARK_APP_ID").or(self.app_id),
+            app_secret: env("LARK_APP_SECRET").or(self.app_secret),
+            domain: env("LARK_DOMAIN").or(self.domain),
+        }
+    }
+
+    /// 完整的接口地址
+    pub fn domain(&self) -> &str {
+        match self.domain.as_deref() {
+            None | Some("feishu") => lark_sdk::FEISHU_DOMAIN,
+            Some("lark") => lark_sdk::LARK_DOMAIN,
+            Some(domain) => domain,
+        }
+    }
+
+    pub fn client(&self) -> lark_sdk::Result<lark_sdk::Client> {
+        let mut builder = lark_sdk::Client::builder().domain(self.domain());
+        if let (Some(app_id), Some(app_secret)) = (&self.app_id, &self.app_secret) {
+            builder = builder.app_credentials(app_id, app_secret);
+        }
+        builder.build()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn env_overrides_file() {
+        let file: Config =
+            serde_json::from_str(r#"{"app_id":"cli_1","app_secret":"s1","domain":"lark"}"#)
+                .unwrap();
+        assert_eq!(file.domain(), lark_sdk::LARK_DOMAIN);
+
+        let config = file.merge_env(|name| match name {
+            "LARK_APP_SECRET" => Some("s2".to_string()),
+            "LARK_DOMAIN" => Some("http://127.0.0.1:8080".to_string()),
+            _ => None,
+        });
+        assert_eq!(config.app_id.as_deref(), Some("cli_1"));
+        assert_eq!(config.app_secret.as_deref(), Some("s2"));
+        assert_eq!(config.domain(), "http://127.0.0.1:8080");
+        assert_eq!(Config::default().domain(), lark_sdk::FEISHU_DOMAIN);
+    }
+}
@@ -0,0 +1,79 @@
+//! 飞书开放平台的命令行工具，用于调试和运维脚本。
+//!
+//! 应用凭证读取自环境变量 `LARK_APP_ID`、`LARK_APP_SECRET`，或 `--config` 指定的 JSON 配置文件
+//! （默认为 `LARK_CONFIG` 或 `~/.config/lark/config.json`），见 [`config::Config`]。
+
+mod args;
+mod command;
+mod config;
+
+use std::process::ExitCode;
+
+use args::Args;
+use config::Config;
+
+const USAGE: &str = "usage: lark [--config <file>] <command> [args]
+
+commands:
+  send <receive_id> <text> [--type <id_type>] [--card]
+      send a text message, or a card when --card is given and <text> is the card json
+  user <open_id|user_id|union_id|email|mobile> [--type <id_type>]
+      print a user
+  download <file_token> <output>
+      download a file from drive
+  resource <message_id> <file_key> <output> [--type file|image]
+      download a file or image in a message
+  call <method> <path> [body] [--query key=value]... [--auth tenant|app|none] [--user-token <token>]
+      call any endpoint, <body> is json, @file or - for stdin
+
+id types are inferred from the prefix (ou_, on_, oc_, @) unless --type is given.
+credentials are read from LARK_APP_ID, LARK_APP_SECRET and LARK_DOMAIN, or from the config
+file ({\"app_id\": \"\", \"app_secret\": \"\", \"domain\": \"feishu|lark|<url>\"}).";
+
+#[tokio::main(flavor = "current_thread")]
+async fn main() -> ExitCode {
+    let mut args = match Args::parse(std::env::args().skip(1)) {
+        Ok(args) => args,
+        Err(err) => {
+            eprintln!("{}\n\n{}", err, USAGE);
+            return ExitCode::from(2);
+        }
+    };
+    let command = match args.shift() {
+        Some(command) if !args.switch("help") => command,
+        _ => {
+            eprintln!("{}", USAGE);
+            return ExitCode::from(2);
+        }
+    };
+    let client = match Config::load(args.option("config")).and_then(|config| {
+        config
+            .client()
+            .map_err(|err| format!("create client: {}", err))
+    }) {
+        Ok(client) => client,
+        Err(err) => {
+            eprintln!("{}", err);
+            return ExitCode::FAILURE;
+        }
+    };
+    let result = match command.as_str() {
+        "send" => command::send(&client, &args).await.map(|_| true),
+        "user" => command::user(&client, &args).await.map(|_| true),
+        "download" => command::download(&client, &args).await.map(|_| true),
+        "resource" => command::resource(&client, &args).await.map(|_| true),
+        "call" => command::call(&client, &args).await,
+        other => {
+            eprintln!("unknown command {}\n\n{}", other, USAGE);
+            return ExitCode::from(2);
+        }
+    };
+    match result {
+        Ok(true) => ExitCode::SUCCESS,
+        Ok(false) => ExitCode::FAILURE,
+        Err(err) => {
+            eprintln!("{}: {}", command, err);
+            ExitCode::FAILURE
+        }
+    }
+}
@@ -0,0 +1,236 @@
+//! 根据飞书开放平台的接口元数据生成 lark-sdk 的请求与响应类型。
+//!
+//! 元数据为 JSON，每个接口包含请求方法、路径、支持的访问凭证，以及路径参数、query 参数、请求体和
+//! 响应 `data` 的字段，结构见 [`metadata`]：
+//!
+//! ```json
+//! {
+//!   "apis": [{
+//!     "resource": "user", "name": "get", "httpMethod": "GET",
+//!     "url": "/open-apis/contact/v3/users/:user_id",
+//!     "supportedAccessToken": ["tenant_access_token", "user_access_token"],
+//!     "description": "获取单个用户信息",
+//!     "pathParams": [{"name": "user_id", "type": "string", "required": true}],
+//!     "queryParams": [{"name": "user_id_type", "type": "string"}],
+//!     "responseBody": [{"name": "user", "type": "object", "required": true, "properties": [
+//!       {"name": "open_id", "type": "string"}, {"name": "name", "type": "string"}
+//!     ]}]
+//!   }]
+//! }
+//! ```
+//!
+//! 每个接口生成一个 `#[derive(lark_sdk::Request)]` 的请求类型、一个 `#[derive(lark_sdk::Response)]`
+//! 的响应类型以及字段中的嵌套类型；响应只有 `items`、`page_token`、`has_more` 时使用
+//! `lark_sdk::ListData`，此时 `page_token` query 参数标记为 `#[request(query, page_token)]`，
+//! 可以直接用于 `Client::pages` 分页。所有接口的请求路径汇总在 `endpoints` 模块中。生成的代码使用 `serde` 和
+//! `lark_sdk`，所在的 crate 需要依赖这两个库。
+//!
+//! 可以使用命令行 `lark-codegen contact.json src/contact.rs` 生成后提交到仓库，也可以作为
+//! build-dependency 在 `build.rs` 中生成：
+//!
+//! ```no_run
+//! // build.rs
+//! let metadata = std::fs::read_to_string("api/contact.json").unwrap();
+//! let code = lark_codegen::generate(&metadata).unwrap();
+//! let out_dir = std::env::var("OUT_DIR").unwrap();
+//! std::fs::write(std::path::Path::new(&out_dir).join("contact.rs"), code).unwrap();
+//! println!("cargo:rerun-if-changed=api/contact.json");
+//! ```
+//!
+//! 之后在代码中使用 `include!(concat!(env!("OUT_DIR"), "/contact.rs"));` 引入。
+
+use std::fmt;
+
+pub mod metadata;
+mod render;
+
+use metadata::Metadata;
+
+/// 生成代码失败
+#[derive(Debug)]
+pub enum Error {
+    /// 元数据不是合法的 JSON 或结构不符
+    Json(serde_json::Error),
+    /// 元数据的内容无法生成代码，如不支持的字段类型、未声明的路径参数
+    Invalid(String),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Json(err) => write!(f, "invalid metadata: {}", err),
+            Error::Invalid(msg) => f.write_str(msg),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Json(err) => Some(err),
+            Error::Invalid(_) => None,
+        }
+    }
+}
+
+impl From<serde_json::Error> for Error {
+    fn from(err: serde_json::Error) -> Self {
+        Error::Json(err)
+    }
+}
+
+/// 由 JSON 格式的元数据生成代码
+pub fn generate(metadata: &str) -> Result<String, Error> {
+    generate_from(&serde_json::from_str(metadata)?)
+}
+
+/// 由解析后的元数据生成代码
+pub fn generate_from(metadata: &Metadata) -> Result<String, Error> {
+    let mut generator = render::Generator::default();
+    for api in &metadata.apis {
+        generator.api(api)?;
+    }
+    Ok(generator.finish())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CONTACT: &str = r#"{
+        "apis": [{
+            "resource": "user", "name": "get", "httpMethod": "get",
+            "url": "/open-apis/contact/v3/users/:user_id",
+            "supportedAccessToken": ["user_access_token", "tenant_access_token"],
+            "description": "获取单个用户信息",
+            "pathParams": [{"name": "user_id", "type": "string", "description": "用户 ID"}],
+            "queryParams": [{"name": "user_id_type", "type": "string"}],
+            "responseBody": [{"name": "user", "type": "object", "required": true, "properties": [
+                {"name": "open_id", "type": "string", "required": true},
+                {"name": "type", "type": "int"},
+                {"name": "department_ids", "type": "list", "items": {"type": "string"}}
+            ]}]
+        }, {
+            "structName": "ListUsers", "resource": "user", "name": "list", "httpMethod": "GET",
+            "url": "/open-apis/contact/v3/users",
+            "supportedAccessToken": ["tenant_access_token"],
+            "queryParams": [
+                {"name": "page_size", "type": "int"},
+                {"name": "page_token", "type": "string"},
+                {"name": "department_ids", "type": "list"}
+            ],
+            "responseBody": [
+                {"name": "items", "type": "list", "items": {"type": "object", "properties": [
+                    {"name": "open_id", "type": "string", "required": true}
+                ]}},
+                {"name": "page_token", "type": "string"},
+                {"name": "has_more", "type": "boolean"}
+            ]
+        }, {
+            "resource": "user", "name": "delete", "httpMethod": "DELETE",
+            "url": "/open-apis/contact/v3/users/:user_id",
+            "pathParams": [{"name": "user_id", "type": "string"}],
+            "requestBody": [{"name": "acceptorUserId", "type": "string"}]
+        }]
+    }"#;
+
+    #[test]
+    fn generate_contact() {
+        let code = generate(CONTACT).unwrap();
+        let expected = r#"// 由 lark-codegen 根据接口元数据生成，请勿手动修改。
+
+/// 获取单个用户信息
+#[derive(Debug, Clone, lark_sdk::Request)]
+#[request(
+    method = "GET",
+    url = "/open-apis/contact/v3/users/:user_id",
+    auth = "tenant",
+    response = GetUserData
+)]
+pub struct GetUser {
+    /// 用户 ID
+    #[request(path)]
+    pub user_id: String,
+    #[request(query)]
+    pub user_id_type: Option<String>,
+}
+
+#[derive(Debug, Clone, serde::Deserialize, lark_sdk::Response)]
+pub struct GetUserData {
+    pub user: User,
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct User {
+    pub open_id: String,
+    #[serde(rename = "type", default, skip_serializing_if = "Option::is_none")]
+    pub type_: Option<i32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub department_ids: Option<Vec<String>>,
+}
+
+#[derive(Debug, Clone, lark_sdk::Request)]
+#[request(
+    method = "GET",
+    url = "/open-apis/contact/v3/users",
+    auth = "tenant",
+    response = lark_sdk::ListData<ListUsersItem>
+)]
+pub struct ListUsers {
+    #[request(query)]
+    pub page_size: Option<i32>,
+    #[request(query, page_token)]
+    pub page_token: Option<String>,
+    #[request(query, style = "repeat")]
+    pub department_ids: Option<Vec<String>>,
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct ListUsersItem {
+    pub open_id: String,
+}
+
+#[derive(Debug, Clone, lark_sdk::Request)]
+#[request(
+    method = "DELETE",
+    url = "/open-apis/contact/v3/users/:user_id",
+    empty
+)]
+pub struct DeleteUser {
+    #[request(path)]
+    pub user_id: String,
+    #[request(body = "acceptorUserId")]
+    pub acceptor_user_id: Option<String>,
+}
+
+/// 各个接口替换路径参数之前的请求路径
+pub mod endpoints {
+    pub const GET_USER: &str = super::GetUser::ENDPOINT;
+    pub const LIST_USERS: &str = super::ListUsers::ENDPOINT;
+    pub const DELETE_USER: &str = super::DeleteUser::ENDPOINT;
+}
+"#;
+        assert_eq!(code, expected);
+    }
+
+    #[test]
+    fn invalid_metadata() {
+        let err = generate(
+            r#"{"apis": [{"resource": "user", "name": "get", "httpMethod": "GET",
+                "url": "/open-apis/contact/v3/users/:user_id"}]}"#,
+        )
+        .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "GetUser: path parameter `user_id` is not declared"
+        );
+
+        let err = generate(
+            r#"{"apis": [{"resource": "user", "name": "get", "httpMethod": "GET", "url": "/u",
+                "queryParams": [{"name": "at", "type": "date"}]}]}"#,
+        )
+        .unwrap_err();
+        assert_eq!(err.to_string(), "GetUser.at: unsupported type `date`");
+        assert!(matches!(generate("{}"), Err(Error::Json(_))));
+    }
+}
@@ -0,0 +1,39 @@
+//! `lark-codegen <metadata.json> [output.rs]`，未指定输出文件时输出到标准输出。
+
+use std::process::ExitCode;
+
+fn main() -> ExitCode {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let (input, output) = match args.as_slice() {
+        [input] => (input, None),
+        [input, output] => (input, Some(output)),
+        _ => {
+            eprintln!("usage: lark-codegen <metadata.json> [output.rs]");
+            return ExitCode::from(2);
+        }
+    };
+    let metadata = match std::fs::read_to_string(input) {
+        Ok(metadata) => metadata,
+        Err(err) => {
+            eprintln!("read {}: {}", input, err);
+            return ExitCode::FAILURE;
+        }
+    };
+    let code = match lark_codegen::generate(&metadata) {
+        Ok(code) => code,
+        Err(err) => {
+            eprintln!("{}: {}", input, err);
+            return ExitCode::FAILURE;
+        }
+    };
+    match output {
+        Some(output) => {
+            if let Err(err) = std::fs::write(output, code) {
+                eprintln!("write {}: {}", output, err);
+                return ExitCode::FAILURE;
+            }
+        }
+        None => print!("{}", code),
+    }
+    ExitCode::SUCCESS
+}
@@ -0,0 +1,63 @@
+//! 接口元数据的结构。
+
+use serde::Deserialize;
+
+/// 一组接口的元数据
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Metadata {
+    pub apis: Vec<Api>,
+}
+
+/// 一个接口
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Api {
+    /// 生成的请求类型名称，如 `GetUser`，为空时由 `name` 和 `resource` 生成
+    #[serde(default)]
+    pub struct_name: Option<String>,
+    /// 资源，如 `user`
+    pub resource: String,
+    /// 操作，如 `get`、`list`、`batch_get`
+    pub name: String,
+    /// 请求方法，如 `GET`
+    pub http_method: String,
+    /// 请求路径，路径参数为 `:name` 的形式，如 `/open-apis/contact/v3/users/:user_id`
+    pub url: String,
+    /// 支持的访问凭证，如 `tenant_access_token`、`user_access_token`
+    #[serde(default)]
+    pub supported_access_token: Vec<String>,
+    #[serde(default)]
+    pub description: String,
+    #[serde(default)]
+    pub path_params: Vec<Field>,
+    #[serde(default)]
+    pub query_params: Vec<Field>,
+    #[serde(default)]
+    pub request_body: Vec<Field>,
+    /// 响应中 `data` 的字段，为空时接口没有返回数据
+    #[serde(default)]
+    pub response_body: Vec<Field>,
+}
+
+/// 参数或字段
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Field {
+    /// 名称，`list` 的元素可以省略
+    #[serde(default)]
+    pub name: String,
+    /// `string`、`int`、`int64`、`float`、`boolean`、`object` 或 `list`
+    #[serde(rename = "type")]
+    pub field_type: String,
+    #[serde(default)]
+    pub required: bool,
+    #[serde(default)]
+    pub description: String,
+    /// `object` 的字段
+    #[serde(default)]
+    pub properties: Vec<Field>,
+    /// `list` 的元素，为空时为 `string`
+    #[serde(default)]
+    pub items: Option<Box<Field>>,
+}
@@ -0,0 +1,355 @@
+//! 由元数据生成 Rust 代码。
+
+use std::collections::HashSet;
+use std::fmt::Write;
+
+use crate::metadata::{Api, Field};
+use crate::Error;
+
+const METHODS: &[&str] = &["GET", "POST", "PUT", "PATCH", "DELETE"];
+
+/// 访问凭证的名称和对应的 `auth`，按优先级排列
+const ACCESS_TOKENS: &[(&str, &str)] = &[
+    ("tenant_access_token", "tenant"),
+    ("user_access_token", "user"),
+    ("app_access_token", "app"),
+];
+
+const KEYWORDS: &[&str] = &[
+    "abstract", "as", "async", "await", "become", "box", "const", "continue", "crate", "do", "dyn",
+    "else", "enum", "extern", "false", "final", "fn", "for", "if", "impl", "in", "let", "loop",
+    "macro", "match", "mod", "move", "mut", "override", "priv", "pub", "ref", "return", "self",
+    "static", "struct", "super", "trait", "true", "try", "type", "typeof", "unsafe", "unsized",
+    "use", "virtual", "where", "while", "yield",
+];
+
+/// 结构体的用途，决定派生的 trait 和字段属性
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Kind {
+    Response,
+    Nested,
+}
+
+#[derive(Default)]
+pub(crate) struct Generator {
+    names: HashSet<String>,
+    blocks: Vec<String>,
+    /// 请求类型的名称，用于生成 `endpoints` 模块
+    requests: Vec<String>,
+}
+
+impl Generator {
+    pub(crate) fn api(&mut self, api: &Api) -> Result<(), Error> {
+        let name = match &api.struct_name {
+            Some(name) => name.clone(),
+            None => format!("{}{}", pascal_case(&api.name), pascal_case(&api.resource)),
+        };
+        if !self.names.insert(name.clone()) {
+            return Err(Error::Invalid(format!("duplicate type name `{}`", name)));
+        }
+        let method = api.http_method.to_ascii_uppercase();
+        if !METHODS.contains(&method.as_str()) {
+            return Err(Error::Invalid(format!(
+                "{}: unsupported http method `{}`",
+                name, api.http_method
+            )));
+        }
+        for segment in api.url.split('/').filter_map(|s| s.strip_prefix(':')) {
+            if !api.path_params.iter().any(|field| field.name == segment) {
+                return Err(Error::Invalid(format!(
+                    "{}: path parameter `{}` is not declared",
+                    name, segment
+                )));
+            }
+        }
+
+        // 先占用请求的位置，响应和嵌套类型跟在请求之后
+        let index = self.blocks.len();
+        self.blocks.push(String::new());
+        let response = self.response(&name, &api.response_body)?;
+        let paged = list_items(&api.response_body).is_some();
+
+        let mut out = String::new();
+        doc(&mut out, "", &api.description);
+        out.push_str("#[derive(Debug, Clone, lark_sdk::Request)]\n#[request(\n");
+        let _ = writeln!(out, "    method = \"{}\",", method);
+        let _ = writeln!(out, "    url = \"{}\",", api.url);
+        let auth = ACCESS_TOKENS
+            .iter()
+            .find(|(token, _)| api.supported_access_token.iter().any(|t| t == token));
+        if let Some((_, auth)) = auth {
+            let _ = writeln!(out, "    auth = \"{}\",", auth);
+        }
+        match response {
+            Some(response) => {
+                let _ = writeln!(out, "    response = {}", response);
+            }
+            None => out.push_str("    empty\n"),
+        }
+        let _ = writeln!(out, ")]\npub struct {} {{", name);
+        let params = [
+            ("path", &api.path_params),
+            ("query", &api.query_params),
+            ("body", &api.request_body),
+        ];
+        for (location, fields) in params {
+            for field in fields {
+                let required = field.required || location == "path";
+                let ty = self.field_type(field, &name)?;
+                let ty = if required {
+                    ty
+                } else {
+                    format!("Option<{}>", ty)
+                };
+                let ident = ident(&field.name);
+                let mut attr = location.to_string();
+                if ident != field.name {
+                    let _ = write!(attr, " = \"{}\"", field.name);
+                }
+                if location == "query" && is_list(field) {
+                    attr.push_str(", style = \"repeat\"");
+                }
+                if location == "query" && paged && field.name == "page_token" {
+                    attr.push_str(", page_token");
+                }
+                doc(&mut out, "    ", &field.description);
+                let _ = writeln!(out, "    #[request({})]", attr);
+                let _ = writeln!(out, "    pub {}: {},", ident, ty);
+            }
+        }
+        out.push_str("}\n");
+        self.blocks[index] = out;
+        self.requests.push(name);
+        Ok(())
+    }
+
+    /// 响应类型，没有返回数据时为 `None`
+    fn response(&mut self, name: &str, fields: &[Field]) -> Result<Option<String>, Error> {
+        if fields.is_empty() {
+            return Ok(None);
+        }
+        if let Some(items) = list_items(fields) {
+            let item = match items.items.as_deref() {
+                Some(item) if item.field_type == "object" => {
+                    let item_name = self.type_name(&format!("{}Item", name), name);
+                    self.object(&item_name, &item.properties, Kind::Nested)?;
+                    item_name
+                }
+                Some(item) => self.field_type(item, name)?,
+                None => "String".to_string(),
+            };
+            return Ok(Some(format!("lark_sdk::ListData<{}>", item)));
+        }
+        let data = self.type_name(&format!("{}Data", name), name);
+        self.object(&data, fields, Kind::Response)?;
+        Ok(Some(data))
+    }
+
+    fn object(&mut self, name: &str, fields: &[Field], kind: Kind) -> Result<(), Error> {
+        let index = self.blocks.len();
+        self.blocks.push(String::new());
+        let mut out = String::new();
+        if kind == Kind::Response {
+            out.push_str("#[derive(Debug, Clone, serde::Deserialize, lark_sdk::Response)]\n");
+        } else {
+            out.push_str("#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]\n");
+        }
+        let _ = writeln!(out, "pub struct {} {{", name);
+        for field in fields {
+            let ty = self.field_type(field, name)?;
+            let ident = ident(&field.name);
+            let mut serde = Vec::new();
+            if ident != field.name {
+                serde.push(format!("rename = \"{}\"", field.name));
+            }
+            let ty = if field.required {
+                ty
+            } else {
+                serde.push("default".to_string());
+                if kind == Kind::Nested {
+                    serde.push("skip_serializing_if = \"Option::is_none\"".to_string());
+                }
+                format!("Option<{}>", ty)
+            };
+            doc(&mut out, "    ", &field.description);
+            if !serde.is_empty() {
+                let _ = writeln!(out, "    #[serde({})]", serde.join(", "));
+            }
+            let _ = writeln!(out, "    pub {}: {},", ident, ty);
+        }
+        out.push_str("}\n");
+        self.blocks[index] = out;
+        Ok(())
+    }
+
+    fn field_type(&mut self, field: &Field, parent: &str) -> Result<String, Error> {
+        let ty = match field.field_type.as_str() {
+            "string" => "String".to_string(),
+            "int" | "int32" | "integer" => "i32".to_string(),
+            "int64" => "i64".to_string(),
+            "float" | "double" | "number" => "f64".to_string(),
+            "boolean" | "bool" => "bool".to_string(),
+            "object" => {
+                let name = self.type_name(&pascal_case(&field.name), parent);
+                self.object(&name, &field.properties, Kind::Nested)?;
+                name
+            }
+            "list" | "array" => match field.items.as_deref() {
+                Some(item) => {
+                    let item = Field {
+                        name: singular(&field.name),
+                        ..item.clone()
+                    };
+                    format!("Vec<{}>", self.field_type(&item, parent)?)
+                }
+                None => "Vec<String>".to_string(),
+            },
+            other => {
+                return Err(Error::Invalid(format!(
+                    "{}.{}: unsupported type `{}`",
+                    parent, field.name, other
+                )))
+            }
+        };
+        Ok(ty)
+    }
+
+    /// 未被占用的类型名称，`name` 已被占用时加上 `parent` 作为前缀
+    fn type_name(&mut self, name: &str, parent: &str) -> String {
+        let mut candidate = name.to_string();
+        if self.names.contains(&candidate) && !name.starts_with(parent) {
+            candidate = format!("{}{}", parent, name);
+        }
+        let base = candidate.clone();
+        let mut n = 2;
+        while self.names.contains(&candidate) {
+            candidate = format!("{}{}", base, n);
+            n += 1;
+        }
+        self.names.insert(candidate.clone());
+        candidate
+    }
+
+    pub(crate) fn finish(self) -> String {
+        let mut out = String::from("// 由 lark-codegen 根据接口元数据生成，请勿手动修改。\n");
+        for block in self.blocks {
+            out.push('\n');
+            out.push_str(&block);
+        }
+        if !self.requests.is_empty() {
+            out.push_str("\n/// 各个接口替换路径参数之前的请求路径\npub mod endpoints {\n");
+            for name in &self.requests {
+                let _ = writeln!(
+                    out,
+                    "    pub const {}: &str = super::{}::ENDPOINT;",
+                    ident(name).to_ascii_uppercase(),
+                    name
+                );
+            }
+            out.push_str("}\n");
+        }
+        out
+    }
+}
+
+/// 分页接口的响应：`items`、`page_token`、`has_more`，以及可选的 `total`
+fn list_items(fields: &[Field]) -> Option<&Field> {
+    let known = ["items", "page_token", "has_more", "total"];
+    if fields
+        .iter()
+        .any(|field| !known.contains(&field.name.as_str()))
+        || !fields.iter().any(|field| field.name == "has_more")
+    {
+        return None;
+    }
+    fields
+        .iter()
+        .find(|field| field.name == "items" && is_list(field))
+}
+
+fn is_list(field: &Field) -> bool {
+    matches!(field.field_type.as_str(), "list" | "array")
+}
+
+fn doc(out: &mut String, indent: &str, description: &str) {
+    if let Some(line) = description.lines().map(str::trim).find(|l| !l.is_empty()) {
+        let _ = writeln!(out, "{}/// {}", indent, line);
+    }
+}
+
+/// 字段名转换为 snake_case 的标识符，关键字加上 `_` 后缀
+fn ident(name: &str) -> String {
+    let mut out = String::new();
+    let mut prev_lower = false;
+    for c in name.chars() {
+        if c.is_ascii_uppercase() {
+            if prev_lower {
+                out.push('_');
+            }
+            out.push(c.to_ascii_lowercase());
+            prev_lower = false;
+        } else if c.is_ascii_alphanumeric() {
+            out.push(c);
+            prev_lower = c.is_ascii_lowercase() || c.is_ascii_digit();
+        } else {
+            out.push('_');
+            prev_lower = false;
+        }
+    }
+    if out.is_empty() || out.starts_with(|c: char| c.is_ascii_digit()) {
+        out.insert(0, '_');
+    }
+    if KEYWORDS.contains(&out.as_str()) {
+        out.push('_');
+    }
+    out
+}
+
+fn pascal_case(name: &str) -> String {
+    ident(name)
+        .split('_')
+        .filter(|part| !part.is_empty())
+        .map(|part| {
+            let mut chars = part.chars();
+            match chars.next() {
+                Some(first) => first.to_ascii_uppercase().to_string() + chars.as_str(),
+                None => String::new(),
+            }
+        })
+        .collect()
+}
+
+/// 列表字段的元素名称，如 `user_list`、`users` 的元素为 `user`
+fn singular(name: &str) -> String {
+    if let Some(name) = name.strip_suffix("_list") {
+        return name.to_string();
+    }
+    if let Some(name) = name.strip_suffix("ies") {
+        return format!("{}y", name);
+    }
+    if name.ends_with("ss") || name.ends_with("us") {
+        return format!("{}_item", name);
+    }
+    match name.strip_suffix('s') {
+        Some(name) if !name.is_empty() => name.to_string(),
+        _ => format!("{}_item", name),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn names() {
+        assert_eq!(ident("pageToken"), "page_token");
+        assert_eq!(ident("user_id"), "user_id");
+        assert_eq!(ident("type"), "type_");
+        assert_eq!(ident("2fa"), "_2fa");
+        assert_eq!(pascal_case("batch_get"), "BatchGet");
+        assert_eq!(singular("items"), "item");
+        assert_eq!(singular("department_list"), "department");
+        assert_eq!(singular("status"), "status_item");
+        assert_eq!(singular("entries"), "entry");
+    }
+}
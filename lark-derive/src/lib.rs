@@ -0,0 +1,75 @@
+//! lark-sdk 的派生宏，请通过 `lark_sdk` 使用。
+
+use proc_macro::TokenStream;
+use syn::{parse_macro_input, DeriveInput};
+
+mod request;
+mod response;
+mod serialize;
+mod sheet_row;
+mod string_enum;
+mod ty;
+
+/// 生成 `lark_sdk::Request` 的实现，属性说明见 `lark_sdk::Request`。
+#[proc_macro_derive(Request, attributes(request))]
+pub fn derive_request(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    request::expand(input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// 为响应中 `data` 字段的类型实现 `Body`。
+///
+/// 支持结构体和枚举，在字段（包括枚举成员的字段）上使用 `#[response(body)]`
+/// 可以同时为字段的类型（会展开 `Option`、`Vec`、`Box`）实现 `Body`。
+/// 使用 `#[response(success_codes = [0, 200])]` 指定表示成功的错误码。
+#[proc_macro_derive(Response, attributes(response))]
+pub fn derive_response(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    response::expand(input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// 为类型实现 `Body`，适用于在多个响应中复用的类型。
+#[proc_macro_derive(Body)]
+pub fn derive_body(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    response::expand_body(&input).into()
+}
+
+/// 为无字段的枚举实现 `lark_sdk::RequestSerialize`，参数值遵循 serde 的
+/// `#[serde(rename_all = "...")]` 和 `#[serde(rename = "...")]`。
+#[proc_macro_derive(RequestSerialize)]
+pub fn derive_request_serialize(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    serialize::expand(input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// 为以字符串表示的枚举生成 `as_str`，并实现 `From<&str>`、`From<String>`、`Display`、serde 的
+/// `Serialize`、`Deserialize` 和 `lark_sdk::RequestSerialize`。
+///
+/// 枚举需要有一个 `Unknown(String)` 成员保存未知的值，服务端新增取值时反序列化不会失败。其他成员
+/// 不能有字段，取值遵循 `#[serde(rename_all = "...")]` 和 `#[serde(rename = "...")]`。
+#[proc_macro_derive(StringEnum, attributes(serde))]
+pub fn derive_string_enum(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    string_enum::expand(input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// 实现 `lark_sdk::sheets::SheetRow`，将结构体的字段映射为电子表格的列。
+///
+/// 列名默认为字段名，使用 `#[sheet(rename = "姓名")]` 指定表头中的列名，
+/// `#[sheet(skip)]` 的字段不读写，读取时使用 `Default::default()`。
+#[proc_macro_derive(SheetRow, attributes(sheet))]
+pub fn derive_sheet_row(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    sheet_row::expand(input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
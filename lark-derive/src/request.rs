@@ -0,0 +1,1170 @@
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote, quote_spanned, ToTokens};
+use syn::ext::IdentExt;
+use syn::meta::ParseNestedMeta;
+use syn::spanned::Spanned;
+use syn::{parse_quote, Data, DeriveInput, Expr, ExprPath, Fields, Ident, LitStr, Type};
+
+use crate::ty::option_inner;
+
+const METHODS: &[(&str, &str)] = &[
+    ("GET", "Get"),
+    ("POST", "Post"),
+    ("PUT", "Put"),
+    ("PATCH", "Patch"),
+    ("DELETE", "Delete"),
+];
+
+const AUTH_TYPES: &[(&str, &str)] = &[
+    ("tenant", "Tenant"),
+    ("user", "User"),
+    ("app", "App"),
+    ("none", "None"),
+];
+
+enum MethodSpec {
+    /// `method = "GET"`
+    Fixed(Ident),
+    /// `method_fn = "Self::pick_method"`
+    Fn(ExprPath),
+}
+
+struct Container {
+    method: MethodSpec,
+    url: LitStr,
+    response: Type,
+    content_type: Option<LitStr>,
+    /// `#[request(builder)]`
+    builder: bool,
+    /// `#[request(idempotent)]`
+    idempotent: bool,
+    /// `auth = "tenant"`，`AuthType` 的变体名
+    auth: Option<Ident>,
+    /// `#[request(validate)]` 为 `Some(None)`，`validate = "Self::check"` 为 `Some(Some(path))`
+    validate: Option<Option<ExprPath>>,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Location {
+    Path,
+    Query,
+    Header,
+    Body,
+    /// 使用 serde 将结构体展开为多个 query 参数
+    QueryStruct,
+}
+
+impl Location {
+    const ALL: [Location; 5] = [
+        Location::Path,
+        Location::Query,
+        Location::Header,
+        Location::Body,
+        Location::QueryStruct,
+    ];
+
+    fn as_str(&self) -> &'static str {
+        match self {
+            Location::Path => "path",
+            Location::Query => "query",
+            Location::Header => "header",
+            Location::Body => "body",
+            Location::QueryStruct => "query_struct",
+        }
+    }
+}
+
+struct Field {
+    ident: Ident,
+    ty: Type,
+    location: Location,
+    name: String,
+    serialize_with: Option<ExprPath>,
+    format: Option<LitStr>,
+    style: Style,
+    /// `#[request(idempotency_key)]`
+    idempotency_key: bool,
+    /// `#[request(max_len = 150)]`
+    max_len: Option<Expr>,
+    /// `#[request(query, page_token)]`
+    page_token: bool,
+}
+
+/// 列表类型 query 参数的序列化方式
+#[derive(Clone, Copy, PartialEq)]
+enum Style {
+    /// `key=a,b`
+    Csv,
+    /// `key=a&key=b`
+    Repeat,
+}
+
+pub fn expand(input: DeriveInput) -> syn::Result<TokenStream> {
+    let container = parse_container(&input)?;
+    let fields = parse_fields(&input)?;
+
+    let ident = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let method = match &container.method {
+        MethodSpec::Fixed(variant) => quote!(::lark_sdk::Method::#variant),
+        MethodSpec::Fn(path) => quote_spanned! {path.span()=>
+            {
+                let __method: ::lark_sdk::Method = #path(self);
+                __method
+            }
+        },
+    };
+    let response = &container.response;
+    let url = expand_url(&container.url, &fields)?;
+    let endpoint = &container.url;
+    let query = expand_params(&fields, Location::Query);
+    let headers = expand_params(&fields, Location::Header);
+    let body = expand_body(&fields);
+    let content_type = container.content_type.as_ref().map(|content_type| {
+        quote! {
+            fn content_type(&self) -> &str {
+                #content_type
+            }
+        }
+    });
+
+    let idempotent = if container.idempotent {
+        Some(quote! {
+            fn is_idempotent(&self) -> bool {
+                true
+            }
+        })
+    } else {
+        None
+    };
+    let auth = container.auth.as_ref().map(|variant| {
+        quote! {
+            fn auth_type(&self) -> ::lark_sdk::AuthType {
+                ::lark_sdk::AuthType::#variant
+            }
+        }
+    });
+    let idempotency_key = expand_idempotency_key(&fields)?;
+    let validate = expand_validate(&container, &fields);
+    let page_token = expand_page_token(&fields)?;
+    let builder = if container.builder {
+        Some(expand_builder(&input, &fields))
+    } else {
+        None
+    };
+
+    Ok(quote! {
+        #builder
+
+        impl #impl_generics #ident #ty_generics #where_clause {
+            /// 替换路径参数之前的请求路径
+            pub const ENDPOINT: &'static str = #endpoint;
+        }
+
+        impl #impl_generics ::lark_sdk::Request for #ident #ty_generics #where_clause {
+            type Target = ::lark_sdk::BodyResponse<#response>;
+
+            fn method(&self) -> ::lark_sdk::Method {
+                #method
+            }
+
+            fn url(&self) -> ::std::string::String {
+                #url
+            }
+
+            fn endpoint(&self) -> ::std::option::Option<&'static str> {
+                ::std::option::Option::Some(Self::ENDPOINT)
+            }
+
+            fn query(
+                &self,
+            ) -> ::lark_sdk::Result<::std::vec::Vec<(::std::string::String, ::std::string::String)>> {
+                ::std::result::Result::Ok({ #query })
+            }
+
+            fn headers(&self) -> ::std::vec::Vec<(::std::string::String, ::std::string::String)> {
+                #headers
+            }
+
+            #content_type
+
+            #idempotent
+
+            #auth
+
+            #idempotency_key
+
+            #validate
+
+            #page_token
+
+            fn body(&self) -> ::lark_sdk::Result<::std::option::Option<::std::vec::Vec<u8>>> {
+                #body
+            }
+        }
+    })
+}
+
+const CONTAINER_OPTIONS: &[&str] = &[
+    "method",
+    "method_fn",
+    "url",
+    "response",
+    "empty",
+    "content_type",
+    "builder",
+    "idempotent",
+    "auth",
+    "validate",
+];
+
+const FIELD_OPTIONS: &[&str] = &[
+    "path",
+    "query",
+    "header",
+    "body",
+    "query_struct",
+    "serialize_with",
+    "format",
+    "style",
+    "idempotency_key",
+    "max_len",
+    "page_token",
+];
+
+/// 未知选项的错误信息，列出所有支持的选项。
+fn unknown_option(meta: &ParseNestedMeta, options: &[&str]) -> syn::Error {
+    let path = meta.path.to_token_stream().to_string().replace(' ', "");
+    let expected = options
+        .iter()
+        .map(|option| format!("`{}`", option))
+        .collect::<Vec<_>>();
+    meta.error(format!(
+        "unknown request option `{}`, expected one of: {}",
+        path,
+        expected.join(", ")
+    ))
+}
+
+fn parse_container(input: &DeriveInput) -> syn::Result<Container> {
+    let mut method = None;
+    let mut url = None;
+    let mut response = None;
+    let mut content_type: Option<LitStr> = None;
+    let mut builder = false;
+    let mut idempotent = false;
+    let mut auth = None;
+    let mut validate = None;
+
+    for attr in input
+        .attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("request"))
+    {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("method") || meta.path.is_ident("method_fn") {
+                if method.is_some() {
+                    return Err(meta.error(
+                        "duplicate request method, use either `method = \"GET\"` or `method_fn = \"Self::method\"`",
+                    ));
+                }
+                let lit: LitStr = meta.value()?.parse()?;
+                if meta.path.is_ident("method_fn") {
+                    let path = lit.parse::<ExprPath>().map_err(|_| {
+                        syn::Error::new(
+                            lit.span(),
+                            "`method_fn` expects a function path like `method_fn = \"Self::method\"`",
+                        )
+                    })?;
+                    method = Some(MethodSpec::Fn(path));
+                } else {
+                    let value = lit.value().to_uppercase();
+                    match METHODS.iter().find(|(name, _)| *name == value) {
+                        Some((_, variant)) => {
+                            method = Some(MethodSpec::Fixed(Ident::new(variant, lit.span())))
+                        }
+                        None => {
+                            let expected = METHODS.iter().map(|(name, _)| *name).collect::<Vec<_>>();
+                            return Err(syn::Error::new(
+                                lit.span(),
+                                format!(
+                                    "unsupported HTTP method `{}`, expected one of: {}",
+                                    lit.value(),
+                                    expected.join(", ")
+                                ),
+                            ));
+                        }
+                    }
+                }
+            } else if meta.path.is_ident("url") {
+                if url.is_some() {
+                    return Err(meta.error("duplicate request option `url`"));
+                }
+                let lit: LitStr = meta.value()?.parse()?;
+                if !lit.value().starts_with('/') {
+                    return Err(syn::Error::new(
+                        lit.span(),
+                        "`url` must be an absolute path like `url = \"/open-apis/im/v1/messages\"`",
+                    ));
+                }
+                url = Some(lit);
+            } else if meta.path.is_ident("response") {
+                if response.is_some() {
+                    return Err(meta.error(
+                        "duplicate response type, use either `response = Type` or `empty`",
+                    ));
+                }
+                response = Some(meta.value()?.parse::<Type>()?);
+            } else if meta.path.is_ident("empty") {
+                if response.is_some() {
+                    return Err(meta.error(
+                        "duplicate response type, use either `response = Type` or `empty`",
+                    ));
+                }
+                response = Some(parse_quote!(::lark_sdk::Empty));
+            } else if meta.path.is_ident("content_type") {
+                if content_type.is_some() {
+                    return Err(meta.error("duplicate request option `content_type`"));
+                }
+                let lit: LitStr = meta.value()?.parse()?;
+                if lit.value().is_empty() {
+                    return Err(syn::Error::new(
+                        lit.span(),
+                        "`content_type` must not be empty, e.g. `content_type = \"multipart/form-data\"`",
+                    ));
+                }
+                content_type = Some(lit);
+            } else if meta.path.is_ident("builder") {
+                builder = true;
+            } else if meta.path.is_ident("idempotent") {
+                idempotent = true;
+            } else if meta.path.is_ident("auth") {
+                if auth.is_some() {
+                    return Err(meta.error("duplicate request option `auth`"));
+                }
+                let lit: LitStr = meta.value()?.parse()?;
+                match AUTH_TYPES.iter().find(|(name, _)| *name == lit.value()) {
+                    Some((_, variant)) => auth = Some(Ident::new(variant, lit.span())),
+                    None => {
+                        let expected = AUTH_TYPES.iter().map(|(name, _)| *name).collect::<Vec<_>>();
+                        return Err(syn::Error::new(
+                            lit.span(),
+                            format!(
+                                "unsupported auth type `{}`, expected one of: {}",
+                                lit.value(),
+                                expected.join(", ")
+                            ),
+                        ));
+                    }
+                }
+            } else if meta.path.is_ident("validate") {
+                if validate.is_some() {
+                    return Err(meta.error("duplicate request option `validate`"));
+                }
+                if meta.input.peek(syn::Token![=]) {
+                    let lit: LitStr = meta.value()?.parse()?;
+                    let path = lit.parse::<ExprPath>().map_err(|_| {
+                        syn::Error::new(
+                            lit.span(),
+                            "`validate` expects a function path like `validate = \"Self::check\"`",
+                        )
+                    })?;
+                    validate = Some(Some(path));
+                } else {
+                    validate = Some(None);
+                }
+            } else {
+                return Err(unknown_option(&meta, CONTAINER_OPTIONS));
+            }
+            Ok(())
+        })?;
+    }
+
+    let missing = |message: &str| syn::Error::new(input.ident.span(), message);
+    Ok(Container {
+        method: method.ok_or_else(|| {
+            missing("missing request method, add `#[request(method = \"GET\")]` or `#[request(method_fn = \"Self::method\")]`")
+        })?,
+        url: url.ok_or_else(|| {
+            missing("missing request url, add `#[request(url = \"/open-apis/...\")]`")
+        })?,
+        response: response.ok_or_else(|| {
+            missing("missing response type, add `#[request(response = Type)]` or `#[request(empty)]`")
+        })?,
+        content_type,
+        builder,
+        idempotent,
+        auth,
+        validate,
+    })
+}
+
+fn parse_fields(input: &DeriveInput) -> syn::Result<Vec<Field>> {
+    let fields = match &input.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Named(fields) => &fields.named,
+            Fields::Unit => return Ok(Vec::new()),
+            Fields::Unnamed(_) => {
+                return Err(syn::Error::new(
+                    input.ident.span(),
+                    "Request can only be derived for structs with named fields",
+                ))
+            }
+        },
+        _ => {
+            return Err(syn::Error::new(
+                input.ident.span(),
+                "Request can only be derived for structs",
+            ))
+        }
+    };
+
+    let mut parsed = Vec::new();
+    for field in fields {
+        if let Some(field) = parse_field(field)? {
+            parsed.push(field);
+        }
+    }
+    Ok(parsed)
+}
+
+fn parse_field(field: &syn::Field) -> syn::Result<Option<Field>> {
+    let ident = field.ident.clone().expect("named field");
+    let mut location: Option<Location> = None;
+    let mut name = None;
+    let mut serialize_with: Option<ExprPath> = None;
+    let mut format: Option<LitStr> = None;
+    let mut style: Option<(Style, LitStr)> = None;
+    let mut idempotency_key: Option<proc_macro2::Span> = None;
+    let mut max_len: Option<Expr> = None;
+    let mut page_token: Option<proc_macro2::Span> = None;
+
+    for attr in field
+        .attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("request"))
+    {
+        attr.parse_nested_meta(|meta| {
+            let kind = Location::ALL
+                .iter()
+                .find(|location| meta.path.is_ident(location.as_str()));
+
+            if let Some(kind) = kind {
+                if let Some(previous) = location {
+                    return Err(meta.error(format!(
+                        "field `{}` is already a `{}` parameter, a field can only have one of `path`, `query`, `header`, `body` or `query_struct`",
+                        ident,
+                        previous.as_str()
+                    )));
+                }
+                location = Some(*kind);
+                if *kind == Location::QueryStruct && meta.input.peek(syn::Token![=]) {
+                    return Err(meta.error(
+                        "`query_struct` can not be renamed, the names come from the struct's serde fields",
+                    ));
+                }
+                if meta.input.peek(syn::Token![=]) {
+                    let lit: LitStr = meta.value()?.parse()?;
+                    if lit.value().is_empty() {
+                        return Err(syn::Error::new(
+                            lit.span(),
+                            format!("parameter name must not be empty, e.g. `{} = \"name\"`", kind.as_str()),
+                        ));
+                    }
+                    name = Some(lit.value());
+                }
+            } else if meta.path.is_ident("serialize_with") {
+                let lit: LitStr = meta.value()?.parse()?;
+                let path = lit.parse::<ExprPath>().map_err(|_| {
+                    syn::Error::new(
+                        lit.span(),
+                        "`serialize_with` expects a function path like `serialize_with = \"path::to::fn\"`",
+                    )
+                })?;
+                serialize_with = Some(path);
+            } else if meta.path.is_ident("format") {
+                let lit: LitStr = meta.value()?.parse()?;
+                if lit.value().is_empty() {
+                    return Err(syn::Error::new(
+                        lit.span(),
+                        "`format` must not be empty, e.g. `format = \"%Y-%m-%d\"`",
+                    ));
+                }
+                format = Some(lit);
+            } else if meta.path.is_ident("style") {
+                let lit: LitStr = meta.value()?.parse()?;
+                let value = match lit.value().as_str() {
+                    "csv" => Style::Csv,
+                    "repeat" => Style::Repeat,
+                    other => {
+                        return Err(syn::Error::new(
+                            lit.span(),
+                            format!(
+                                "unsupported query style `{}`, expected `style = \"csv\"` or `style = \"repeat\"`",
+                                other
+                            ),
+                        ))
+                    }
+                };
+                style = Some((value, lit));
+            } else if meta.path.is_ident("idempotency_key") {
+                idempotency_key = Some(meta.path.span());
+            } else if meta.path.is_ident("page_token") {
+                page_token = Some(meta.path.span());
+            } else if meta.path.is_ident("max_len") {
+                if max_len.is_some() {
+                    return Err(meta.error("duplicate request option `max_len`"));
+                }
+                max_len = Some(meta.value()?.parse()?);
+            } else {
+                return Err(unknown_option(&meta, FIELD_OPTIONS));
+            }
+            Ok(())
+        })?;
+    }
+
+    let option_span = serialize_with
+        .as_ref()
+        .map(|path| path.span())
+        .or_else(|| format.as_ref().map(|lit| lit.span()));
+    let location = match location {
+        Some(Location::QueryStruct) if max_len.is_some() => {
+            return Err(syn::Error::new(
+                max_len.span(),
+                "`max_len` is not supported on `query_struct` fields, check the fields of the struct instead",
+            ));
+        }
+        Some(location @ (Location::Body | Location::QueryStruct)) if option_span.is_some() => {
+            return Err(syn::Error::new(
+                option_span.unwrap(),
+                format!(
+                    "`serialize_with` and `format` are not supported on `{}` fields, they are serialized by serde, use `#[serde(...)]` instead",
+                    location.as_str()
+                ),
+            ));
+        }
+        Some(location) => location,
+        None => match (option_span.or_else(|| max_len.as_ref().map(|max| max.span())), idempotency_key) {
+            (Some(span), _) => {
+                return Err(syn::Error::new(
+                    span,
+                    "missing field location, add one of `path`, `query` or `header`",
+                ))
+            }
+            (None, Some(span)) => {
+                return Err(syn::Error::new(
+                    span,
+                    "missing field location, the idempotency key must be sent, e.g. `#[request(body, idempotency_key)]`",
+                ))
+            }
+            (None, None) => return Ok(None),
+        },
+    };
+    if let Some(span) = page_token {
+        if location != Location::Query {
+            return Err(syn::Error::new(
+                span,
+                "`page_token` is only supported on `query` fields, e.g. `#[request(query, page_token)]`",
+            ));
+        }
+    }
+    if let (Some(_), Some(format)) = (&serialize_with, &format) {
+        return Err(syn::Error::new(
+            format.span(),
+            "`format` can not be used together with `serialize_with`",
+        ));
+    }
+    if let Some((_, lit)) = &style {
+        if location != Location::Query {
+            return Err(syn::Error::new(
+                lit.span(),
+                "`style` is only supported on `query` fields",
+            ));
+        }
+        if let Some(span) = option_span {
+            return Err(syn::Error::new(
+                span,
+                "`serialize_with` and `format` can not be used together with `style`",
+            ));
+        }
+    }
+
+    Ok(Some(Field {
+        name: name.unwrap_or_else(|| ident.unraw().to_string()),
+        ident,
+        ty: field.ty.clone(),
+        location,
+        serialize_with,
+        format,
+        style: style.map_or(Style::Csv, |(style, _)| style),
+        idempotency_key: idempotency_key.is_some(),
+        max_len,
+        page_token: page_token.is_some(),
+    }))
+}
+
+/// 生成 `receiver` 字段序列化后的值，类型为 `Option<String>`。
+fn expand_value(field: &Field, receiver: &TokenStream) -> TokenStream {
+    let ident = &field.ident;
+    if let Some(path) = &field.serialize_with {
+        quote_spanned! {path.span()=>
+            {
+                let __value: ::std::option::Option<::std::string::String> = #path(&#receiver.#ident);
+                __value
+            }
+        }
+    } else if let Some(format) = &field.format {
+        if option_inner(&field.ty).is_some() {
+            quote_spanned! {format.span()=>
+                #receiver.#ident.as_ref().map(|__value| __value.format(#format).to_string())
+            }
+        } else {
+            quote_spanned! {format.span()=>
+                ::std::option::Option::Some(#receiver.#ident.format(#format).to_string())
+            }
+        }
+    } else {
+        quote_spanned! {field.ty.span()=>
+            ::lark_sdk::RequestSerialize::to_param(&#receiver.#ident)
+        }
+    }
+}
+
+fn expand_url(template: &LitStr, fields: &[Field]) -> syn::Result<TokenStream> {
+    let path_fields: Vec<&Field> = fields
+        .iter()
+        .filter(|f| f.location == Location::Path)
+        .collect();
+    let url = template.value();
+
+    let mut pieces = Vec::new();
+    let mut literal = String::new();
+    let mut used = Vec::new();
+    for (i, segment) in url.split('/').enumerate() {
+        if i > 0 {
+            literal.push('/');
+        }
+        match segment.strip_prefix(':') {
+            Some(name) => {
+                let field = path_fields.iter().find(|f| f.name == name).ok_or_else(|| {
+                    syn::Error::new(
+                        template.span(),
+                        format!(
+                            "url parameter `:{}` has no matching field, add `#[request(path)]` or `#[request(path = \"{}\")]` to a field",
+                            name, name
+                        ),
+                    )
+                })?;
+                if !literal.is_empty() {
+                    pieces.push(quote!(__url.push_str(#literal);));
+                    literal.clear();
+                }
+                let value = expand_value(field, &quote!(self));
+                pieces.push(quote! {
+                    __url.push_str(&::lark_sdk::__private::encode_path_segment(
+                        &#value.unwrap_or_default(),
+                    ));
+                });
+                used.push(name.to_string());
+            }
+            None => literal.push_str(segment),
+        }
+    }
+    if !literal.is_empty() {
+        pieces.push(quote!(__url.push_str(#literal);));
+    }
+
+    if let Some(field) = path_fields.iter().find(|f| !used.contains(&f.name)) {
+        return Err(syn::Error::new(
+            field.ident.span(),
+            format!(
+                "path parameter `{}` does not appear in the url, expected `:{}` in `{}`",
+                field.name, field.name, url
+            ),
+        ));
+    }
+
+    Ok(quote! {
+        let mut __url = ::std::string::String::new();
+        #(#pieces)*
+        __url
+    })
+}
+
+fn expand_params(fields: &[Field], location: Location) -> TokenStream {
+    let pushes = fields
+        .iter()
+        .filter(|f| {
+            f.location == location
+                || (location == Location::Query && f.location == Location::QueryStruct)
+        })
+        .map(|field| {
+            let name = &field.name;
+            if field.location == Location::QueryStruct {
+                let ident = &field.ident;
+                return quote! {
+                    __params.extend(::lark_sdk::__private::query_pairs(#name, &self.#ident)?);
+                };
+            }
+            if field.style == Style::Repeat {
+                let ident = &field.ident;
+                return quote! {
+                    for __value in ::lark_sdk::RequestSerialize::to_params(&self.#ident) {
+                        __params.push((::std::string::String::from(#name), __value));
+                    }
+                };
+            }
+            let value = expand_value(field, &quote!(self));
+            quote! {
+                if let ::std::option::Option::Some(__value) = #value {
+                    __params.push((::std::string::String::from(#name), __value));
+                }
+            }
+        });
+    quote! {
+        #[allow(unused_mut)]
+        let mut __params = ::std::vec::Vec::new();
+        #(#pushes)*
+        __params
+    }
+}
+
+fn expand_body(fields: &[Field]) -> TokenStream {
+    let body_fields: Vec<&Field> = fields
+        .iter()
+        .filter(|f| f.location == Location::Body)
+        .collect();
+    if body_fields.is_empty() {
+        return quote!(::std::result::Result::Ok(::std::option::Option::None));
+    }
+    let inserts = body_fields.iter().map(|field| {
+        let ident = &field.ident;
+        let name = &field.name;
+        quote! {
+            let __value = ::lark_sdk::__private::serde_json::to_value(&self.#ident)?;
+            if !__value.is_null() {
+                __body.insert(::std::string::String::from(#name), __value);
+            }
+        }
+    });
+    quote! {
+        let mut __body = ::lark_sdk::__private::serde_json::Map::new();
+        #(#inserts)*
+        ::std::result::Result::Ok(::std::option::Option::Some(
+            ::lark_sdk::__private::serde_json::to_vec(&__body)?,
+        ))
+    }
+}
+
+/// 生成 `set_idempotency_key`，幂等键字段已经有值时保持不变。
+fn expand_idempotency_key(fields: &[Field]) -> syn::Result<Option<TokenStream>> {
+    let mut keys = fields.iter().filter(|field| field.idempotency_key);
+    let field = match keys.next() {
+        Some(field) => field,
+        None => return Ok(None),
+    };
+    if let Some(duplicate) = keys.next() {
+        return Err(syn::Error::new(
+            duplicate.ident.span(),
+            format!(
+                "duplicate `idempotency_key`, field `{}` is already the idempotency key",
+                field.ident
+            ),
+        ));
+    }
+    let ident = &field.ident;
+    let set = if option_inner(&field.ty).is_some() {
+        quote! {
+            if self.#ident.is_none() {
+                self.#ident = ::std::option::Option::Some(::std::convert::From::from(key));
+            }
+        }
+    } else {
+        quote! {
+            if self.#ident.is_empty() {
+                self.#ident = ::std::convert::From::from(key);
+            }
+        }
+    };
+    Ok(Some(quote_spanned! {field.ty.span()=>
+        fn set_idempotency_key(&mut self, key: &str) -> bool {
+            #set
+            true
+        }
+    }))
+}
+
+/// 生成 `set_page_token`，`String` 类型的字段在没有下一页时设置为空字符串。
+fn expand_page_token(fields: &[Field]) -> syn::Result<Option<TokenStream>> {
+    let mut tokens = fields.iter().filter(|field| field.page_token);
+    let field = match tokens.next() {
+        Some(field) => field,
+        None => return Ok(None),
+    };
+    if let Some(duplicate) = tokens.next() {
+        return Err(syn::Error::new(
+            duplicate.ident.span(),
+            format!(
+                "duplicate `page_token`, field `{}` is already the page token",
+                field.ident
+            ),
+        ));
+    }
+    let ident = &field.ident;
+    let set = if option_inner(&field.ty).is_some() {
+        quote! {
+            self.#ident = page_token.map(::std::convert::From::from);
+        }
+    } else {
+        quote! {
+            self.#ident = ::std::convert::From::from(page_token.unwrap_or_default());
+        }
+    };
+    Ok(Some(quote_spanned! {field.ty.span()=>
+        fn set_page_token(&mut self, page_token: ::std::option::Option<::std::string::String>) -> bool {
+            #set
+            true
+        }
+    }))
+}
+
+/// 生成 `validate`：`#[request(validate)]` 时检查路径参数不为空，再检查 `max_len` 和调用自定义的函数，
+/// 没有任何检查时使用默认实现。
+fn expand_validate(container: &Container, fields: &[Field]) -> Option<TokenStream> {
+    let receiver = quote!(self);
+    let paths = fields
+        .iter()
+        .filter(|field| container.validate.is_some() && field.location == Location::Path)
+        .map(|field| {
+            let value = expand_value(field, &receiver);
+            let message = format!("path parameter `{}` must not be empty", field.name);
+            quote! {
+                if #value.map_or(true, |__value| __value.is_empty()) {
+                    return ::std::result::Result::Err(::lark_sdk::LarkError::InvalidRequest(
+                        ::std::string::String::from(#message),
+                    ));
+                }
+            }
+        });
+    let lengths = fields.iter().filter_map(|field| {
+        let max = field.max_len.as_ref()?;
+        let ident = &field.ident;
+        let message = format!("`{}` exceeds the limit of {{}}, got {{}}", field.name);
+        Some(quote_spanned! {field.ty.span()=>
+            if let ::std::option::Option::Some(__len) =
+                ::lark_sdk::__private::ValidateLen::validate_len(&self.#ident)
+            {
+                let __max: usize = #max;
+                if __len > __max {
+                    return ::std::result::Result::Err(::lark_sdk::LarkError::InvalidRequest(
+                        ::std::format!(#message, __max, __len),
+                    ));
+                }
+            }
+        })
+    });
+    let checks: Vec<TokenStream> = paths.chain(lengths).collect();
+    let custom = match &container.validate {
+        Some(Some(path)) => Some(quote_spanned! {path.span()=>
+            {
+                let __result: ::lark_sdk::Result<()> = #path(self);
+                __result?;
+            }
+        }),
+        _ => None,
+    };
+    if checks.is_empty() && custom.is_none() {
+        return None;
+    }
+    Some(quote! {
+        fn validate(&self) -> ::lark_sdk::Result<()> {
+            #(#checks)*
+            #custom
+            ::std::result::Result::Ok(())
+        }
+    })
+}
+
+/// 生成 `#[request(builder)]` 的构建器：`Option` 字段默认为 `None`，其他字段必须设置，
+/// `build()` 时检查路径参数不为空。
+fn expand_builder(input: &DeriveInput, fields: &[Field]) -> TokenStream {
+    let ident = &input.ident;
+    let vis = &input.vis;
+    let builder = format_ident!("{}Builder", ident);
+    let generics = &input.generics;
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    let all_fields: Vec<&syn::Field> = match &input.data {
+        Data::Struct(data) => data.fields.iter().collect(),
+        _ => Vec::new(),
+    };
+
+    let names: Vec<&Ident> = all_fields
+        .iter()
+        .map(|field| field.ident.as_ref().expect("named field"))
+        .collect();
+    let storage = all_fields.iter().map(|field| {
+        let ty = &field.ty;
+        match option_inner(ty) {
+            Some(_) => quote!(#ty),
+            None => quote!(::std::option::Option<#ty>),
+        }
+    });
+    let setters = all_fields.iter().zip(&names).map(|(field, name)| {
+        let setter = format_ident!("with_{}", name.unraw());
+        let ty = option_inner(&field.ty).unwrap_or(&field.ty);
+        let doc = format!("设置 `{}`", name.unraw());
+        quote! {
+            #[doc = #doc]
+            #vis fn #setter(mut self, value: impl ::std::convert::Into<#ty>) -> Self {
+                self.#name = ::std::option::Option::Some(value.into());
+                self
+            }
+        }
+    });
+    let takes = all_fields.iter().zip(&names).map(|(field, name)| {
+        if option_inner(&field.ty).is_some() {
+            return quote!(let #name = self.#name;);
+        }
+        let message = format!("missing required field `{}`", name.unraw());
+        quote! {
+            let #name = self.#name.ok_or_else(|| {
+                ::lark_sdk::LarkError::InvalidRequest(::std::string::String::from(#message))
+            })?;
+        }
+    });
+    let receiver = quote!(__request);
+    let checks = fields
+        .iter()
+        .filter(|field| field.location == Location::Path)
+        .map(|field| {
+            let value = expand_value(field, &receiver);
+            let message = format!("path parameter `{}` must not be empty", field.name);
+            quote! {
+                if #value.map_or(true, |__value| __value.is_empty()) {
+                    return ::std::result::Result::Err(::lark_sdk::LarkError::InvalidRequest(
+                        ::std::string::String::from(#message),
+                    ));
+                }
+            }
+        });
+    let builder_doc = format!("[`{}`] 的构建器", ident);
+
+    quote! {
+        #[doc = #builder_doc]
+        #vis struct #builder #generics #where_clause {
+            #(#names: #storage,)*
+        }
+
+        impl #impl_generics ::std::default::Default for #builder #ty_generics #where_clause {
+            fn default() -> Self {
+                #builder {
+                    #(#names: ::std::option::Option::None,)*
+                }
+            }
+        }
+
+        impl #impl_generics #ident #ty_generics #where_clause {
+            #[doc = #builder_doc]
+            #vis fn builder() -> #builder #ty_generics {
+                ::std::default::Default::default()
+            }
+        }
+
+        impl #impl_generics #builder #ty_generics #where_clause {
+            #(#setters)*
+
+            /// 构建请求，缺少必填字段或路径参数为空时返回 [`LarkError::InvalidRequest`](::lark_sdk::LarkError::InvalidRequest)
+            #vis fn build(self) -> ::lark_sdk::Result<#ident #ty_generics> {
+                #(#takes)*
+                let #receiver = #ident {
+                    #(#names,)*
+                };
+                #(#checks)*
+                ::std::result::Result::Ok(#receiver)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 展开 `input` 并与 `snapshots/request/{name}.snap` 比较，变化使用 `cargo insta review` 审阅
+    fn assert_snapshot(name: &str, input: DeriveInput) {
+        let file: syn::File = syn::parse2(expand(input).unwrap()).unwrap();
+        insta::with_settings!({
+            snapshot_path => "../snapshots/request",
+            prepend_module_to_snapshot => false,
+            omit_expression => true,
+        }, {
+            insta::assert_snapshot!(name, prettyplease::unparse(&file));
+        });
+    }
+
+    #[test]
+    fn path_query_body() {
+        assert_snapshot(
+            "path_query_body",
+            parse_quote! {
+                #[request(
+                    method = "PATCH",
+                    url = "/open-apis/im/v1/chats/:chat_id",
+                    auth = "tenant",
+                    response = Chat
+                )]
+                pub struct UpdateChat {
+                    #[request(path)]
+                    pub chat_id: String,
+                    #[request(query)]
+                    pub user_id_type: Option<String>,
+                    #[request(query = "page_size")]
+                    pub size: u32,
+                    #[request(body)]
+                    pub name: Option<String>,
+                    #[request(body = "type")]
+                    pub chat_type: String,
+                    pub ignored: bool,
+                }
+            },
+        );
+    }
+
+    #[test]
+    fn header() {
+        assert_snapshot(
+            "header",
+            parse_quote! {
+                #[request(method = "GET", url = "/open-apis/helpdesk/v1/tickets", auth = "none", empty)]
+                pub struct ListTickets {
+                    #[request(header = "X-Lark-Helpdesk-Authorization")]
+                    pub helpdesk_auth: String,
+                    #[request(header = "X-Request-Id")]
+                    pub request_id: Option<String>,
+                }
+            },
+        );
+    }
+
+    #[test]
+    fn query_styles() {
+        assert_snapshot(
+            "query_styles",
+            parse_quote! {
+                #[request(method = "GET", url = "/open-apis/contact/v3/users/batch", response = Users)]
+                pub struct BatchGetUsers {
+                    #[request(query)]
+                    pub fields: Vec<String>,
+                    #[request(query, style = "repeat")]
+                    pub user_ids: Vec<String>,
+                    #[request(query, style = "repeat")]
+                    pub department_ids: Option<Vec<String>>,
+                    #[request(query_struct)]
+                    pub page: PageParams,
+                }
+            },
+        );
+    }
+
+    #[test]
+    fn serialize_with_and_format() {
+        assert_snapshot(
+            "serialize_with_and_format",
+            parse_quote! {
+                #[request(method = "GET", url = "/open-apis/attendance/v1/shifts/:date", response = Tasks)]
+                pub struct QueryTasks {
+                    #[request(path, format = "%Y%m%d")]
+                    pub date: NaiveDate,
+                    #[request(query, format = "%Y-%m-%d")]
+                    pub check_date: Option<NaiveDate>,
+                    #[request(query, serialize_with = "join_ids")]
+                    pub department_ids: Vec<u32>,
+                    #[request(header = "X-Timeout", serialize_with = "crate::param::seconds")]
+                    pub timeout: Duration,
+                }
+            },
+        );
+    }
+
+    #[test]
+    fn idempotency_and_content_type() {
+        assert_snapshot(
+            "idempotency_and_content_type",
+            parse_quote! {
+                #[request(
+                    method = "POST",
+                    url = "/open-apis/im/v1/messages",
+                    auth = "tenant",
+                    content_type = "application/json",
+                    idempotent,
+                    response = Message
+                )]
+                pub struct SendMessage {
+                    #[request(query)]
+                    pub receive_id_type: String,
+                    #[request(body)]
+                    pub content: String,
+                    #[request(body, idempotency_key)]
+                    pub uuid: Option<String>,
+                }
+            },
+        );
+    }
+
+    #[test]
+    fn validate() {
+        assert_snapshot(
+            "validate",
+            parse_quote! {
+                #[request(
+                    method = "POST",
+                    url = "/open-apis/corehr/v2/employees/:employee_id/notes",
+                    validate = "Self::check",
+                    empty
+                )]
+                pub struct CreateNote {
+                    #[request(path)]
+                    pub employee_id: String,
+                    #[request(body, max_len = 150)]
+                    pub title: Option<String>,
+                    #[request(body, max_len = NOTE_IDS_LIMIT)]
+                    pub note_ids: Vec<String>,
+                }
+            },
+        );
+    }
+
+    #[test]
+    fn page_token() {
+        assert_snapshot(
+            "page_token",
+            parse_quote! {
+                #[request(method = "GET", url = "/open-apis/im/v1/messages", response = ListData<Message>)]
+                pub struct ListMessages {
+                    #[request(query)]
+                    pub container_id: String,
+                    #[request(query)]
+                    pub page_size: Option<u32>,
+                    #[request(query, page_token)]
+                    pub page_token: Option<String>,
+                }
+            },
+        );
+    }
+
+    #[test]
+    fn method_fn_and_builder() {
+        assert_snapshot(
+            "method_fn_and_builder",
+            parse_quote! {
+                #[request(method_fn = "Self::method", url = "/open-apis/drive/v1/files/:file_token", builder, empty)]
+                pub struct FileRequest<'a> {
+                    #[request(path)]
+                    pub file_token: &'a str,
+                    #[request(body)]
+                    pub r#type: Option<String>,
+                }
+            },
+        );
+    }
+}
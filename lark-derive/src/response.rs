@@ -0,0 +1,151 @@
+use proc_macro2::TokenStream;
+use quote::{quote, quote_spanned, ToTokens};
+use syn::spanned::Spanned;
+use syn::{parse_quote, Data, DeriveInput, Expr, ExprArray, ExprLit, Lit, LitInt, Type};
+
+use crate::ty::generic_inner;
+
+/// 字段类型中会被展开的容器，`#[response(body)]` 为容器内的类型实现 `Body`。
+const CONTAINERS: &[&str] = &["Option", "Vec", "Box"];
+
+pub fn expand(input: DeriveInput) -> syn::Result<TokenStream> {
+    let ident = &input.ident;
+    let fields: Vec<&syn::Field> = match &input.data {
+        Data::Struct(data) => data.fields.iter().collect(),
+        Data::Enum(data) => data.variants.iter().flat_map(|v| &v.fields).collect(),
+        Data::Union(_) => {
+            return Err(syn::Error::new(
+                ident.span(),
+                "Response can only be derived for structs and enums",
+            ))
+        }
+    };
+
+    let mut nested = Vec::new();
+    for field in fields {
+        if let Some(ty) = parse_field(field)? {
+            nested.push(quote_spanned! {ty.span()=>
+                impl ::lark_sdk::Body for #ty {}
+            });
+        }
+    }
+
+    let success_codes = parse_container(&input)?;
+    let items = match success_codes {
+        Some(codes) => quote! {
+            fn is_success_code(code: i64) -> bool {
+                #(code == #codes)||*
+            }
+        },
+        None => TokenStream::new(),
+    };
+    let body = body_impl(&input, items);
+    Ok(quote! {
+        #body
+
+        #(#nested)*
+    })
+}
+
+/// 解析 `#[response(success_codes = [0, 200])]`。
+fn parse_container(input: &DeriveInput) -> syn::Result<Option<Vec<LitInt>>> {
+    let mut success_codes = None;
+    for attr in input
+        .attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("response"))
+    {
+        attr.parse_nested_meta(|meta| {
+            if !meta.path.is_ident("success_codes") {
+                let path = meta.path.to_token_stream().to_string().replace(' ', "");
+                return Err(meta.error(format!(
+                    "unknown response option `{}`, expected `success_codes = [0, ...]`",
+                    path
+                )));
+            }
+            let array: ExprArray = meta.value()?.parse()?;
+            let mut codes = Vec::new();
+            for expr in &array.elems {
+                match expr {
+                    Expr::Lit(ExprLit {
+                        lit: Lit::Int(code),
+                        ..
+                    }) => codes.push(code.clone()),
+                    _ => return Err(syn::Error::new(
+                        expr.span(),
+                        "`success_codes` expects integer literals, e.g. `success_codes = [0, 200]`",
+                    )),
+                }
+            }
+            if codes.is_empty() {
+                return Err(syn::Error::new(
+                    array.span(),
+                    "`success_codes` must not be empty",
+                ));
+            }
+            success_codes = Some(codes);
+            Ok(())
+        })?;
+    }
+    Ok(success_codes)
+}
+
+/// `#[derive(Body)]`，只为类型本身实现 `Body`。
+pub fn expand_body(input: &DeriveInput) -> TokenStream {
+    body_impl(input, TokenStream::new())
+}
+
+fn body_impl(input: &DeriveInput, items: TokenStream) -> TokenStream {
+    let ident = &input.ident;
+    let mut generics = input.generics.clone();
+    if !generics.params.is_empty() {
+        generics
+            .make_where_clause()
+            .predicates
+            .push(parse_quote!(Self: ::lark_sdk::__private::serde::de::DeserializeOwned));
+    }
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    quote! {
+        impl #impl_generics ::lark_sdk::Body for #ident #ty_generics #where_clause {
+            #items
+        }
+    }
+}
+
+/// 解析 `#[response(body)]`，返回需要实现 `Body` 的类型。
+fn parse_field(field: &syn::Field) -> syn::Result<Option<&Type>> {
+    let mut body = false;
+    for attr in field
+        .attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("response"))
+    {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("body") {
+                body = true;
+                Ok(())
+            } else {
+                let path = meta.path.to_token_stream().to_string().replace(' ', "");
+                Err(meta.error(format!(
+                    "unknown response option `{}`, expected `body`",
+                    path
+                )))
+            }
+        })?;
+    }
+    if !body {
+        return Ok(None);
+    }
+
+    let mut ty = &field.ty;
+    while let Some(inner) = generic_inner(ty, CONTAINERS) {
+        ty = inner;
+    }
+    match ty {
+        Type::Path(_) => Ok(Some(ty)),
+        _ => Err(syn::Error::new(
+            field.ty.span(),
+            "`#[response(body)]` expects a struct type, optionally wrapped in `Option`, `Vec` or `Box`",
+        )),
+    }
+}
@@ -0,0 +1,162 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::meta::ParseNestedMeta;
+use syn::{Attribute, Data, DeriveInput, Fields, Ident, LitStr};
+
+/// 与 serde 一致的 `rename_all` 规则
+const RENAME_RULES: &[&str] = &[
+    "lowercase",
+    "UPPERCASE",
+    "PascalCase",
+    "camelCase",
+    "snake_case",
+    "SCREAMING_SNAKE_CASE",
+    "kebab-case",
+    "SCREAMING-KEBAB-CASE",
+];
+
+pub fn expand(input: DeriveInput) -> syn::Result<TokenStream> {
+    let ident = &input.ident;
+    let (values, _) = variant_values(&input, "RequestSerialize", false)?;
+    let arms = values
+        .iter()
+        .map(|(variant, value)| quote!(#ident::#variant => #value));
+
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    Ok(quote! {
+        impl #impl_generics ::lark_sdk::RequestSerialize for #ident #ty_generics #where_clause {
+            fn to_param(&self) -> ::std::option::Option<::std::string::String> {
+                let __value = match self {
+                    #(#arms,)*
+                };
+                ::std::option::Option::Some(::std::string::String::from(__value))
+            }
+        }
+    })
+}
+
+/// 枚举的无字段成员及其取值，取值遵循 serde 的 `rename_all` 和 `rename`。
+///
+/// `allow_unknown` 时允许名为 `Unknown` 的成员带有字段，返回值中的 `bool` 表示是否存在该成员。
+pub(crate) fn variant_values<'a>(
+    input: &'a DeriveInput,
+    derive: &str,
+    allow_unknown: bool,
+) -> syn::Result<(Vec<(&'a Ident, String)>, bool)> {
+    let ident = &input.ident;
+    let variants = match &input.data {
+        Data::Enum(data) => &data.variants,
+        _ => {
+            let kind = if allow_unknown { "" } else { "fieldless " };
+            return Err(syn::Error::new(
+                ident.span(),
+                format!("{} can only be derived for {}enums", derive, kind),
+            ));
+        }
+    };
+
+    let mut rename_all = None;
+    parse_serde_attrs(&input.attrs, |meta| {
+        if meta.path.is_ident("rename_all") && meta.input.peek(syn::Token![=]) {
+            let lit: LitStr = meta.value()?.parse()?;
+            if !RENAME_RULES.contains(&lit.value().as_str()) {
+                return Err(syn::Error::new(
+                    lit.span(),
+                    format!(
+                        "unsupported rename rule `{}`, expected one of: {}",
+                        lit.value(),
+                        RENAME_RULES.join(", ")
+                    ),
+                ));
+            }
+            rename_all = Some(lit.value());
+            return Ok(());
+        }
+        skip_meta(meta)
+    })?;
+
+    let mut values = Vec::new();
+    let mut has_unknown = false;
+    for variant in variants {
+        if allow_unknown && variant.ident == "Unknown" {
+            if !matches!(&variant.fields, Fields::Unnamed(fields) if fields.unnamed.len() == 1) {
+                return Err(syn::Error::new(
+                    variant.ident.span(),
+                    "the `Unknown` variant must be `Unknown(String)`",
+                ));
+            }
+            has_unknown = true;
+            continue;
+        }
+        if !matches!(variant.fields, Fields::Unit) {
+            let message = if allow_unknown {
+                format!(
+                    "{} only supports fieldless variants and `Unknown(String)`",
+                    derive
+                )
+            } else {
+                format!("{} can only be derived for fieldless enums", derive)
+            };
+            return Err(syn::Error::new(variant.ident.span(), message));
+        }
+        let mut rename = None;
+        parse_serde_attrs(&variant.attrs, |meta| {
+            if meta.path.is_ident("rename") && meta.input.peek(syn::Token![=]) {
+                rename = Some(meta.value()?.parse::<LitStr>()?.value());
+                return Ok(());
+            }
+            skip_meta(meta)
+        })?;
+
+        let name = variant.ident.to_string();
+        let value = rename.unwrap_or_else(|| match &rename_all {
+            Some(rule) => apply_rename_rule(rule, &name),
+            None => name,
+        });
+        values.push((&variant.ident, value));
+    }
+    Ok((values, has_unknown))
+}
+
+fn parse_serde_attrs(
+    attrs: &[Attribute],
+    mut logic: impl FnMut(ParseNestedMeta) -> syn::Result<()>,
+) -> syn::Result<()> {
+    for attr in attrs.iter().filter(|attr| attr.path().is_ident("serde")) {
+        attr.parse_nested_meta(&mut logic)?;
+    }
+    Ok(())
+}
+
+/// 跳过不关心的 serde 属性，如 `tag = "type"` 或 `rename_all(serialize = "...")`。
+fn skip_meta(meta: ParseNestedMeta) -> syn::Result<()> {
+    if meta.input.peek(syn::Token![=]) {
+        meta.value()?.parse::<syn::Expr>()?;
+    } else if meta.input.peek(syn::token::Paren) {
+        meta.parse_nested_meta(skip_meta)?;
+    }
+    Ok(())
+}
+
+fn apply_rename_rule(rule: &str, variant: &str) -> String {
+    let snake = || {
+        let mut snake = String::new();
+        for (i, ch) in variant.char_indices() {
+            if i > 0 && ch.is_uppercase() {
+                snake.push('_');
+            }
+            snake.push(ch.to_ascii_lowercase());
+        }
+        snake
+    };
+    match rule {
+        "lowercase" => variant.to_ascii_lowercase(),
+        "UPPERCASE" => variant.to_ascii_uppercase(),
+        "camelCase" => variant[..1].to_ascii_lowercase() + &variant[1..],
+        "snake_case" => snake(),
+        "SCREAMING_SNAKE_CASE" => snake().to_ascii_uppercase(),
+        "kebab-case" => snake().replace('_', "-"),
+        "SCREAMING-KEBAB-CASE" => snake().to_ascii_uppercase().replace('_', "-"),
+        _ => variant.to_string(),
+    }
+}
@@ -0,0 +1,119 @@
+use proc_macro2::TokenStream;
+use quote::{quote, ToTokens};
+use syn::{Data, DeriveInput, Fields, LitStr};
+
+const FIELD_OPTIONS: &[&str] = &["rename", "skip"];
+
+struct Column {
+    ident: syn::Ident,
+    /// `None` 表示 `#[sheet(skip)]`
+    name: Option<String>,
+}
+
+pub fn expand(input: DeriveInput) -> syn::Result<TokenStream> {
+    let ident = &input.ident;
+    let fields = match &input.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Named(fields) => &fields.named,
+            _ => {
+                return Err(syn::Error::new(
+                    ident.span(),
+                    "SheetRow can only be derived for structs with named fields",
+                ))
+            }
+        },
+        _ => {
+            return Err(syn::Error::new(
+                ident.span(),
+                "SheetRow can only be derived for structs with named fields",
+            ))
+        }
+    };
+
+    let mut columns = Vec::new();
+    for field in fields {
+        columns.push(parse_field(field)?);
+    }
+
+    let names: Vec<&String> = columns.iter().filter_map(|c| c.name.as_ref()).collect();
+    let mut index = 0usize;
+    let mut initializers = Vec::new();
+    let mut values = Vec::new();
+    for column in &columns {
+        let field = &column.ident;
+        match &column.name {
+            Some(name) => {
+                initializers.push(quote! {
+                    #field: ::lark_sdk::__private::sheet_cell(__row, __columns[#index], #name)?
+                });
+                values.push(quote! {
+                    ::lark_sdk::__private::serde_json::to_value(&self.#field)?
+                });
+                index += 1;
+            }
+            None => initializers.push(quote! {
+                #field: ::std::default::Default::default()
+            }),
+        }
+    }
+
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    Ok(quote! {
+        impl #impl_generics ::lark_sdk::sheets::SheetRow for #ident #ty_generics #where_clause {
+            const COLUMNS: &'static [&'static str] = &[#(#names),*];
+
+            fn from_row(
+                __row: &[::lark_sdk::__private::serde_json::Value],
+                __columns: &[::std::option::Option<usize>],
+            ) -> ::lark_sdk::Result<Self> {
+                ::std::result::Result::Ok(#ident {
+                    #(#initializers,)*
+                })
+            }
+
+            fn to_row(&self) -> ::lark_sdk::Result<::std::vec::Vec<::lark_sdk::__private::serde_json::Value>> {
+                ::std::result::Result::Ok(::std::vec![#(#values),*])
+            }
+        }
+    })
+}
+
+/// 解析 `#[sheet(rename = "列名")]` 和 `#[sheet(skip)]`。
+fn parse_field(field: &syn::Field) -> syn::Result<Column> {
+    let ident = field.ident.clone().expect("named field");
+    let mut name = Some(ident.to_string());
+    for attr in field
+        .attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("sheet"))
+    {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("rename") {
+                let lit: LitStr = meta.value()?.parse()?;
+                if lit.value().trim().is_empty() {
+                    return Err(syn::Error::new(
+                        lit.span(),
+                        "column name must not be empty, e.g. `rename = \"name\"`",
+                    ));
+                }
+                name = Some(lit.value());
+                Ok(())
+            } else if meta.path.is_ident("skip") {
+                name = None;
+                Ok(())
+            } else {
+                let path = meta.path.to_token_stream().to_string().replace(' ', "");
+                let expected = FIELD_OPTIONS
+                    .iter()
+                    .map(|option| format!("`{}`", option))
+                    .collect::<Vec<_>>();
+                Err(meta.error(format!(
+                    "unknown sheet option `{}`, expected one of: {}",
+                    path,
+                    expected.join(", ")
+                )))
+            }
+        })?;
+    }
+    Ok(Column { ident, name })
+}
@@ -0,0 +1,88 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::spanned::Spanned;
+use syn::DeriveInput;
+
+use crate::serialize::variant_values;
+
+pub fn expand(input: DeriveInput) -> syn::Result<TokenStream> {
+    let ident = &input.ident;
+    if !input.generics.params.is_empty() {
+        return Err(syn::Error::new(
+            input.generics.span(),
+            "StringEnum does not support generic enums",
+        ));
+    }
+    let (values, has_unknown) = variant_values(&input, "StringEnum", true)?;
+    if !has_unknown {
+        return Err(syn::Error::new(
+            ident.span(),
+            "StringEnum requires an `Unknown(String)` variant for values added by the server",
+        ));
+    }
+    let variants: Vec<_> = values.iter().map(|(variant, _)| variant).collect();
+    let names: Vec<_> = values.iter().map(|(_, value)| value).collect();
+
+    Ok(quote! {
+        impl #ident {
+            /// 接口中的取值
+            pub fn as_str(&self) -> &str {
+                match self {
+                    #(#ident::#variants => #names,)*
+                    #ident::Unknown(__value) => __value,
+                }
+            }
+        }
+
+        impl ::std::convert::From<&str> for #ident {
+            fn from(__value: &str) -> Self {
+                match __value {
+                    #(#names => #ident::#variants,)*
+                    _ => #ident::Unknown(::std::string::String::from(__value)),
+                }
+            }
+        }
+
+        impl ::std::convert::From<::std::string::String> for #ident {
+            fn from(__value: ::std::string::String) -> Self {
+                match __value.as_str() {
+                    #(#names => #ident::#variants,)*
+                    _ => #ident::Unknown(__value),
+                }
+            }
+        }
+
+        impl ::std::fmt::Display for #ident {
+            fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+                f.write_str(self.as_str())
+            }
+        }
+
+        impl ::lark_sdk::__private::serde::Serialize for #ident {
+            fn serialize<__S>(&self, serializer: __S) -> ::std::result::Result<__S::Ok, __S::Error>
+            where
+                __S: ::lark_sdk::__private::serde::Serializer,
+            {
+                serializer.serialize_str(self.as_str())
+            }
+        }
+
+        impl<'de> ::lark_sdk::__private::serde::Deserialize<'de> for #ident {
+            fn deserialize<__D>(deserializer: __D) -> ::std::result::Result<Self, __D::Error>
+            where
+                __D: ::lark_sdk::__private::serde::Deserializer<'de>,
+            {
+                <::std::string::String as ::lark_sdk::__private::serde::Deserialize>::deserialize(
+                    deserializer,
+                )
+                .map(#ident::from)
+            }
+        }
+
+        impl ::lark_sdk::RequestSerialize for #ident {
+            fn to_param(&self) -> ::std::option::Option<::std::string::String> {
+                ::std::option::Option::Some(::std::string::String::from(self.as_str()))
+            }
+        }
+    })
+}
@@ -0,0 +1,25 @@
+use syn::{GenericArgument, PathArguments, Type};
+
+/// 如果类型是 `Option<T>`，返回 `T`。
+pub fn option_inner(ty: &Type) -> Option<&Type> {
+    generic_inner(ty, &["Option"])
+}
+
+/// 如果类型是 `names` 中的某个泛型容器（如 `Vec<T>`），返回 `T`。
+pub fn generic_inner<'a>(ty: &'a Type, names: &[&str]) -> Option<&'a Type> {
+    let path = match ty {
+        Type::Path(ty) if ty.qself.is_none() => &ty.path,
+        _ => return None,
+    };
+    let segment = path.segments.last()?;
+    if !names.iter().any(|name| segment.ident == name) {
+        return None;
+    }
+    match &segment.arguments {
+        PathArguments::AngleBracketed(args) if args.args.len() == 1 => match &args.args[0] {
+            GenericArgument::Type(ty) => Some(ty),
+            _ => None,
+        },
+        _ => None,
+    }
+}
@@ -0,0 +1,140 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use lark_sdk::{Request, RequestParts};
+
+#[derive(Request)]
+#[request(
+    method = "PUT",
+    url = "/open-apis/sheets/v2/spreadsheets/:spreadsheet_token/sheets/:sheet_id/values/:range",
+    empty
+)]
+struct UpdateRange {
+    #[request(path)]
+    spreadsheet_token: String,
+    #[request(path)]
+    sheet_id: String,
+    /// 含有需要编码的 `!`、`:`
+    #[request(path)]
+    range: String,
+}
+
+#[derive(Request)]
+#[request(
+    method = "GET",
+    url = "/open-apis/contact/v3/users/find_by_department",
+    auth = "tenant",
+    empty
+)]
+struct FindUsers {
+    #[request(query)]
+    department_id: String,
+    #[request(query)]
+    user_id_type: Option<String>,
+    #[request(query)]
+    department_id_type: Option<String>,
+    #[request(query)]
+    page_size: u32,
+    #[request(query)]
+    page_token: Option<String>,
+    /// 默认以逗号连接
+    #[request(query)]
+    fields: Vec<String>,
+    #[request(query, style = "repeat")]
+    user_ids: Vec<String>,
+}
+
+#[derive(Request)]
+#[request(
+    method = "POST",
+    url = "/open-apis/im/v1/messages/:message_id/reply",
+    auth = "tenant",
+    empty
+)]
+struct ReplyMessage {
+    #[request(path)]
+    message_id: String,
+    #[request(header = "X-Request-Id")]
+    request_id: String,
+    #[request(body)]
+    msg_type: String,
+    #[request(body)]
+    content: String,
+    #[request(body)]
+    reply_in_thread: bool,
+    #[request(body, idempotency_key)]
+    uuid: Option<String>,
+}
+
+fn update_range() -> UpdateRange {
+    UpdateRange {
+        spreadsheet_token: "shtcnmBA5HFz4JQKSBhT5XmNuyg".to_string(),
+        sheet_id: "0b12fa".to_string(),
+        range: "0b12fa!A1:Z1000".to_string(),
+    }
+}
+
+fn find_users() -> FindUsers {
+    FindUsers {
+        department_id: "od-4e6ac4d14bcd5071a37a39de902c7141".to_string(),
+        user_id_type: Some("open_id".to_string()),
+        department_id_type: Some("open_department_id".to_string()),
+        page_size: 50,
+        page_token: Some(
+            "AQD9/Rn9eij9Pm39ED40/dk53s4Ebp882DYfFaPFbz00L4CMZJrqGdzNyc8BcZtDbwVUvRmQTvyMYicnGWrde9X56TgdBuS+JKiSIkdexPw=".to_string(),
+        ),
+        fields: vec!["name".to_string(), "email".to_string(), "mobile".to_string()],
+        user_ids: (0..20).map(|i| format!("ou_{:032}", i)).collect(),
+    }
+}
+
+fn reply_message() -> ReplyMessage {
+    ReplyMessage {
+        message_id: "om_dc13264520392913993dd051dba21dcf".to_string(),
+        request_id: "e4f3c0a1-7b2d-4d1e-9b8a-2f6c5d4e3b2a".to_string(),
+        msg_type: "text".to_string(),
+        content: serde_json::json!({ "text": "你好".repeat(200) }).to_string(),
+        reply_in_thread: true,
+        uuid: Some("a0d69e20-1dd1-458b-b525-dfeca4015204".to_string()),
+    }
+}
+
+/// 替换并编码路径参数
+fn path_substitution(c: &mut Criterion) {
+    let request = update_range();
+    c.bench_function("url 3 path params", |b| {
+        b.iter(|| black_box(&request).url())
+    });
+}
+
+/// 序列化查询参数，包括逗号连接和重复出现的列表
+fn query_assembly(c: &mut Criterion) {
+    let request = find_users();
+    c.bench_function("query 7 params", |b| {
+        b.iter(|| black_box(&request).query().unwrap())
+    });
+}
+
+/// 生成发送请求需要的全部内容，以及持久化到队列时的序列化
+fn request_building(c: &mut Criterion) {
+    let request = reply_message();
+    c.bench_function("body 4 fields", |b| {
+        b.iter(|| black_box(&request).body().unwrap())
+    });
+    c.bench_function("to_parts reply message", |b| {
+        b.iter(|| black_box(&request).to_parts().unwrap())
+    });
+
+    let parts = request.to_parts().unwrap();
+    let queued = serde_json::to_vec(&parts).unwrap();
+    c.bench_function("request parts round trip", |b| {
+        b.iter(|| {
+            let queued = serde_json::to_vec(black_box(&parts)).unwrap();
+            serde_json::from_slice::<RequestParts>(&queued).unwrap()
+        })
+    });
+    c.bench_function("request parts from queue", |b| {
+        b.iter(|| serde_json::from_slice::<RequestParts>(black_box(&queued)).unwrap())
+    });
+}
+
+criterion_group!(benches, path_substitution, query_assembly, request_building);
+criterion_main!(benches);
@@ -0,0 +1,101 @@
+use std::borrow::Cow;
+
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use lark_sdk::{BodyResponse, ListData, RawBody};
+use serde::Deserialize;
+
+#[derive(Deserialize)]
+#[allow(dead_code)]
+struct User {
+    open_id: String,
+    name: String,
+    email: String,
+    department_ids: Vec<String>,
+}
+
+#[derive(Deserialize)]
+#[allow(dead_code)]
+struct BorrowedUser<'a> {
+    open_id: &'a str,
+    #[serde(borrow)]
+    name: Cow<'a, str>,
+    email: &'a str,
+    department_ids: Vec<&'a str>,
+}
+
+#[derive(Deserialize)]
+#[allow(dead_code)]
+struct BorrowedList<'a> {
+    #[serde(borrow)]
+    items: Vec<BorrowedUser<'a>>,
+    has_more: bool,
+}
+
+fn users_json(count: usize) -> String {
+    let items: Vec<String> = (0..count)
+        .map(|i| {
+            format!(
+                r#"{{"open_id":"ou_{i:032}","name":"user {i}","email":"user{i}@example.com","department_ids":["od-{i}","od-0"]}}"#,
+                i = i
+            )
+        })
+        .collect();
+    format!(
+        r#"{{"code":0,"msg":"success","data":{{"items":[{}],"has_more":false}}}}"#,
+        items.join(",")
+    )
+}
+
+fn deserialize_list(c: &mut Criterion) {
+    let json = users_json(5000);
+
+    c.bench_function("owned list 5000", |b| {
+        b.iter(|| {
+            let response: BodyResponse<ListData<User>> =
+                serde_json::from_str(black_box(&json)).unwrap();
+            response.into_data().unwrap()
+        })
+    });
+
+    c.bench_function("raw body borrowed list 5000", |b| {
+        b.iter(|| {
+            let response: BodyResponse<RawBody> = serde_json::from_str(black_box(&json)).unwrap();
+            let raw = response.into_data().unwrap();
+            let list: BorrowedList = raw.parse().unwrap();
+            list.items.len()
+        })
+    });
+}
+
+/// 不同长度的列表，按字节计算吞吐量
+fn list_sizes(c: &mut Criterion) {
+    let mut group = c.benchmark_group("owned list");
+    for count in [100, 1000, 20000] {
+        let json = users_json(count);
+        group.throughput(Throughput::Bytes(json.len() as u64));
+        group.bench_with_input(BenchmarkId::from_parameter(count), &json, |b, json| {
+            b.iter(|| {
+                let response: BodyResponse<ListData<User>> =
+                    serde_json::from_str(black_box(json)).unwrap();
+                response.into_data().unwrap()
+            })
+        });
+    }
+    group.finish();
+}
+
+/// 开启 `simd-json` 特性前后分别运行，比较两种解析方式。
+fn parse_bytes(c: &mut Criterion) {
+    let json = users_json(5000).into_bytes();
+
+    c.bench_function("json::from_slice list 5000", |b| {
+        b.iter(|| {
+            let response: BodyResponse<ListData<User>> =
+                lark_sdk::json::from_slice(black_box(&json)).unwrap();
+            response.into_data().unwrap()
+        })
+    });
+}
+
+criterion_group!(benches, deserialize_list, list_sizes, parse_bytes);
+criterion_main!(benches);
@@ -0,0 +1,556 @@
+//! 审批。
+//!
+//! 创建审批实例时，表单的内容是控件值数组序列化后的 JSON 字符串，每种控件的值格式不同。
+//! [`ApprovalForm`] 的每个方法对应一种控件，由参数类型保证值的格式：
+//!
+//! ```no_run
+//! use lark_sdk::approval::{ApprovalForm, CreateInstance, FormDate};
+//! use lark_sdk::id::OpenId;
+//!
+//! # async fn run(client: lark_sdk::Client) -> lark_sdk::Result<()> {
+//! let form = ApprovalForm::new()
+//!     .input("widget_reason", "出差")
+//!     .number("widget_days", 2.0)
+//!     .date_interval(
+//!         "widget_range",
+//!         FormDate::new("2023-06-01T09:00:00+08:00"),
+//!         FormDate::new("2023-06-02T18:00:00+08:00"),
+//!         2.0,
+//!     )
+//!     .contact_open_ids("widget_cc", vec![OpenId::new("ou_xxx")]);
+//! let request = CreateInstance::new("7C468A54-8745-2245-9675-08B7C63E7A85", &OpenId::new("ou_yyy"), &form);
+//! let instance = client.send(&request).await?.into_data()?;
+//! println!("{}", instance.instance_code);
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! 附件控件的值是审批文件的 code，需要先使用 [`UploadApprovalFile`] 上传。[`InstanceBuilder`]
+//! 会在创建实例前上传附件并填入表单：
+//!
+//! ```no_run
+//! use lark_sdk::approval::{ApprovalForm, InstanceBuilder};
+//! use lark_sdk::id::OpenId;
+//!
+//! # async fn run(client: lark_sdk::Client, pdf: Vec<u8>) -> lark_sdk::Result<()> {
+//! let form = ApprovalForm::new().input("widget_reason", "采购");
+//! let instance = InstanceBuilder::new("7C468A54-8745-2245-9675-08B7C63E7A85", &OpenId::new("ou_yyy"), form)
+//!     .attachment("widget_quote", "报价单.pdf", pdf)
+//!     .create(&client)
+//!     .await?;
+//! println!("{}", instance.instance_code);
+//! # Ok(())
+//! # }
+//! ```
+
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+use crate::id::{OpenId, UserId};
+use crate::multipart::Boundary;
+#[cfg(feature = "async")]
+use crate::Client;
+use crate::{AuthType, BodyResponse, Method, Request, Result};
+
+/// 审批文件上传接口的域名，不在开放平台的域名下
+pub const APPROVAL_FILE_DOMAIN: &str = "https://www.feishu.cn";
+
+/// 日期控件的值，RFC3339 格式，如 `2023-06-01T09:00:00+08:00`
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct FormDate(pub String);
+
+impl FormDate {
+    pub fn new(rfc3339: impl Into<String>) -> Self {
+        FormDate(rfc3339.into())
+    }
+}
+
+#[cfg(feature = "chrono")]
+impl<Tz: chrono::TimeZone> From<chrono::DateTime<Tz>> for FormDate
+where
+    Tz::Offset: std::fmt::Display,
+{
+    fn from(time: chrono::DateTime<Tz>) -> Self {
+        FormDate(time.to_rfc3339_opts(chrono::SecondsFormat::Secs, false))
+    }
+}
+
+/// 控件的值
+#[derive(Debug, Clone, PartialEq)]
+pub enum WidgetValue {
+    /// 单行文本，`input`
+    Input(String),
+    /// 多行文本，`textarea`
+    Textarea(String),
+    /// 数字，`number`
+    Number(f64),
+    /// 日期，`date`
+    Date(FormDate),
+    /// 日期区间，`dateInterval`，`interval` 为时长（天）
+    DateInterval {
+        start: FormDate,
+        end: FormDate,
+        interval: f64,
+    },
+    /// 附件，`attachmentV2`，值为上传审批文件得到的 code
+    Attachment(Vec<String>),
+    /// 联系人，`contact`
+    Contact {
+        user_ids: Vec<UserId>,
+        open_ids: Vec<OpenId>,
+    },
+}
+
+impl WidgetValue {
+    /// 控件的类型
+    pub fn widget_type(&self) -> &'static str {
+        match self {
+            WidgetValue::Input(_) => "input",
+            WidgetValue::Textarea(_) => "textarea",
+            WidgetValue::Number(_) => "number",
+            WidgetValue::Date(_) => "date",
+            WidgetValue::DateInterval { .. } => "dateInterval",
+            WidgetValue::Attachment(_) => "attachmentV2",
+            WidgetValue::Contact { .. } => "contact",
+        }
+    }
+}
+
+/// 表单中的一个控件
+#[derive(Debug, Clone, PartialEq)]
+pub struct FormWidget {
+    /// 审批定义中控件的 ID
+    pub id: String,
+    pub value: WidgetValue,
+}
+
+impl FormWidget {
+    /// 表单 JSON 中的控件
+    pub fn to_value(&self) -> Value {
+        let mut widget = json!({
+            "id": self.id,
+            "type": self.value.widget_type(),
+        });
+        let value = match &self.value {
+            WidgetValue::Input(text) | WidgetValue::Textarea(text) => json!(text),
+            WidgetValue::Number(number) => json!(number),
+            WidgetValue::Date(date) => json!(date),
+            WidgetValue::DateInterval {
+                start,
+                end,
+                interval,
+            } => json!({ "start": start, "end": end, "interval": interval }),
+            WidgetValue::Attachment(codes) => json!(codes),
+            WidgetValue::Contact { user_ids, open_ids } => {
+                if !open_ids.is_empty() {
+                    widget["open_ids"] = json!(open_ids);
+                }
+                json!(user_ids)
+            }
+        };
+        widget["value"] = value;
+        widget
+    }
+}
+
+/// 审批表单
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ApprovalForm {
+    pub widgets: Vec<FormWidget>,
+}
+
+impl ApprovalForm {
+    pub fn new() -> Self {
+        ApprovalForm::default()
+    }
+
+    /// 添加控件
+    pub fn widget(mut self, id: impl Into<String>, value: WidgetValue) -> Self {
+        self.widgets.push(FormWidget {
+            id: id.into(),
+            value,
+        });
+        self
+    }
+
+    /// 单行文本
+    pub fn input(self, id: impl Into<String>, value: impl Into<String>) -> Self {
+        self.widget(id, WidgetValue::Input(value.into()))
+    }
+
+    /// 多行文本
+    pub fn textarea(self, id: impl Into<String>, value: impl Into<String>) -> Self {
+        self.widget(id, WidgetValue::Textarea(value.into()))
+    }
+
+    /// 数字
+    pub fn number(self, id: impl Into<String>, value: f64) -> Self {
+        self.widget(id, WidgetValue::Number(value))
+    }
+
+    /// 日期
+    pub fn date(self, id: impl Into<String>, value: impl Into<FormDate>) -> Self {
+        self.widget(id, WidgetValue::Date(value.into()))
+    }
+
+    /// 日期区间，`interval` 为时长（天）
+    pub fn date_interval(
+        self,
+        id: impl Into<String>,
+        start: impl Into<FormDate>,
+        end: impl Into<FormDate>,
+        interval: f64,
+    ) -> Self {
+        let value = WidgetValue::DateInterval {
+            start: start.into(),
+            end: end.into(),
+            interval,
+        };
+        self.widget(id, value)
+    }
+
+    /// 附件，`codes` 为上传审批文件得到的 code
+    pub fn attachment(self, id: impl Into<String>, codes: Vec<String>) -> Self {
+        self.widget(id, WidgetValue::Attachment(codes))
+    }
+
+    /// 联系人，使用 `user_id`
+    pub fn contact(self, id: impl Into<String>, user_ids: Vec<UserId>) -> Self {
+        let value = WidgetValue::Contact {
+            user_ids,
+            open_ids: Vec::new(),
+        };
+        self.widget(id, value)
+    }
+
+    /// 联系人，使用 `open_id`
+    pub fn contact_open_ids(self, id: impl Into<String>, open_ids: Vec<OpenId>) -> Self {
+        let value = WidgetValue::Contact {
+            user_ids: Vec::new(),
+            open_ids,
+        };
+        self.widget(id, value)
+    }
+
+    /// 序列化为创建审批实例时的 `form` 字符串
+    pub fn to_json(&self) -> String {
+        let widgets: Vec<Value> = self.widgets.iter().map(FormWidget::to_value).collect();
+        Value::Array(widgets).to_string()
+    }
+}
+
+/// 创建的审批实例
+#[derive(Debug, Clone, Deserialize, lark_sdk::Response)]
+pub struct InstanceCode {
+    pub instance_code: String,
+}
+
+/// 创建审批实例，`user_id` 和 `open_id` 至少需要一个
+#[derive(Debug, Clone, lark_sdk::Request)]
+#[request(
+    method = "POST",
+    url = "/open-apis/approval/v4/instances",
+    auth = "tenant",
+    response = InstanceCode
+)]
+pub struct CreateInstance {
+    /// 审批定义的 code
+    #[request(body)]
+    pub approval_code: String,
+    /// 发起人
+    #[request(body)]
+    pub user_id: Option<String>,
+    #[request(body)]
+    pub open_id: Option<String>,
+    /// 发起人的部门，发起人属于多个部门时需要指定
+    #[request(body)]
+    pub department_id: Option<String>,
+    /// 表单内容，见 [`ApprovalForm::to_json`]
+    #[request(body)]
+    pub form: String,
+    /// 幂等键
+    #[request(body, idempotency_key)]
+    pub uuid: Option<String>,
+}
+
+impl CreateInstance {
+    /// 由 `open_id` 发起审批
+    pub fn new(approval_code: impl Into<String>, open_id: &OpenId, form: &ApprovalForm) -> Self {
+        CreateInstance {
+            approval_code: approval_code.into(),
+            user_id: None,
+            open_id: Some(open_id.0.clone()),
+            department_id: None,
+            form: form.to_json(),
+            uuid: None,
+        }
+    }
+}
+
+/// 审批文件的类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ApprovalFileType {
+    /// 附件控件
+    Attachment,
+    /// 图片控件
+    Image,
+}
+
+impl ApprovalFileType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ApprovalFileType::Attachment => "attachment",
+            ApprovalFileType::Image => "image",
+        }
+    }
+}
+
+/// 上传的审批文件
+#[derive(Debug, Clone, Deserialize, lark_sdk::Response)]
+pub struct ApprovalFile {
+    /// 用于表单控件的值
+    pub code: String,
+    #[serde(default)]
+    pub url: Option<String>,
+}
+
+/// 上传审批文件，得到的 code 用于附件或图片控件
+#[derive(Clone)]
+pub struct UploadApprovalFile {
+    pub name: String,
+    pub file_type: ApprovalFileType,
+    pub content: Vec<u8>,
+    /// 接口的域名，默认为 [`APPROVAL_FILE_DOMAIN`]
+    pub domain: String,
+    boundary: Boundary,
+}
+
+impl UploadApprovalFile {
+    /// 替换路径参数之前的请求路径
+    pub const ENDPOINT: &'static str = "/approval/openapi/v2/file/upload";
+
+    pub fn new(name: impl Into<String>, file_type: ApprovalFileType, content: Vec<u8>) -> Self {
+        UploadApprovalFile {
+            name: name.into(),
+            file_type,
+            content,
+            domain: APPROVAL_FILE_DOMAIN.to_string(),
+            boundary: Boundary::new(),
+        }
+    }
+
+    /// 附件控件的文件
+    pub fn attachment(name: impl Into<String>, content: Vec<u8>) -> Self {
+        UploadApprovalFile::new(name, ApprovalFileType::Attachment, content)
+    }
+}
+
+impl Request for UploadApprovalFile {
+    type Target = BodyResponse<ApprovalFile>;
+
+    fn method(&self) -> Method {
+        Method::Post
+    }
+
+    fn url(&self) -> String {
+        format!("{}{}", self.domain.trim_end_matches('/'), Self::ENDPOINT)
+    }
+
+    fn endpoint(&self) -> Option<&'static str> {
+        Some(Self::ENDPOINT)
+    }
+
+    fn content_type(&self) -> &str {
+        self.boundary.content_type()
+    }
+
+    fn body(&self) -> Result<Option<Vec<u8>>> {
+        let form = self
+            .boundary
+            .form()
+            .text("name", &self.name)
+            .text("type", self.file_type.as_str())
+            .file("content", &self.name, &self.content);
+        Ok(Some(form.finish()))
+    }
+
+    fn auth_type(&self) -> AuthType {
+        AuthType::Tenant
+    }
+}
+
+impl fmt::Debug for UploadApprovalFile {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("UploadApprovalFile")
+            .field("name", &self.name)
+            .field("file_type", &self.file_type)
+            .field("size", &self.content.len())
+            .field("domain", &self.domain)
+            .finish()
+    }
+}
+
+/// 创建审批实例，创建前上传表单中的附件
+#[cfg(feature = "async")]
+#[derive(Debug, Clone)]
+pub struct InstanceBuilder {
+    approval_code: String,
+    open_id: OpenId,
+    form: ApprovalForm,
+    attachments: Vec<(String, UploadApprovalFile)>,
+    file_domain: String,
+}
+
+#[cfg(feature = "async")]
+impl InstanceBuilder {
+    pub fn new(approval_code: impl Into<String>, open_id: &OpenId, form: ApprovalForm) -> Self {
+        InstanceBuilder {
+            approval_code: approval_code.into(),
+            open_id: open_id.clone(),
+            form,
+            attachments: Vec::new(),
+            file_domain: APPROVAL_FILE_DOMAIN.to_string(),
+        }
+    }
+
+    /// 上传文件作为附件控件 `id` 的值，同一个控件可以添加多个文件
+    pub fn attachment(
+        mut self,
+        id: impl Into<String>,
+        name: impl Into<String>,
+        content: Vec<u8>,
+    ) -> Self {
+        let file = UploadApprovalFile::attachment(name, content);
+        self.attachments.push((id.into(), file));
+        self
+    }
+
+    /// 上传文件使用的域名，默认为 [`APPROVAL_FILE_DOMAIN`]
+    pub fn file_domain(mut self, domain: impl Into<String>) -> Self {
+        self.file_domain = domain.into();
+        self
+    }
+
+    /// 依次上传附件，再创建审批实例
+    pub async fn create(&self, client: &Client) -> Result<InstanceCode> {
+        let mut widgets: Vec<(&str, Vec<String>)> = Vec::new();
+        for (id, file) in &self.attachments {
+            let file = UploadApprovalFile {
+                domain: self.file_domain.clone(),
+                ..file.clone()
+            };
+            let code = client.send(&file).await?.into_data()?.code;
+            match widgets.iter_mut().find(|(widget, _)| widget == id) {
+                Some((_, codes)) => codes.push(code),
+                None => widgets.push((id, vec![code])),
+            }
+        }
+        let mut form = self.form.clone();
+        for (id, codes) in widgets {
+            form = form.attachment(id, codes);
+        }
+        let request = CreateInstance::new(self.approval_code.clone(), &self.open_id, &form);
+        client.send(&request).await?.into_data()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Request;
+
+    #[test]
+    fn serialize_form() {
+        let form = ApprovalForm::new()
+            .input("w1", "出差")
+            .textarea("w2", "第一行\n第二行")
+            .number("w3", 1234.5)
+            .date("w4", FormDate::new("2019-10-01T08:12:01+08:00"))
+            .date_interval(
+                "w5",
+                FormDate::new("2019-10-01T08:12:01+08:00"),
+                FormDate::new("2019-10-02T08:12:01+08:00"),
+                1.0,
+            )
+            .attachment(
+                "w6",
+                vec!["D93653C3-2609-4EE0-8041-61DC1D84F0B5".to_string()],
+            )
+            .contact("w7", vec![UserId::new("f8ca557e")])
+            .contact_open_ids("w8", vec![OpenId::new("ou_1")]);
+        let value: Value = serde_json::from_str(&form.to_json()).unwrap();
+        assert_eq!(
+            value,
+            json!([
+                {"id": "w1", "type": "input", "value": "出差"},
+                {"id": "w2", "type": "textarea", "value": "第一行\n第二行"},
+                {"id": "w3", "type": "number", "value": 1234.5},
+                {"id": "w4", "type": "date", "value": "2019-10-01T08:12:01+08:00"},
+                {"id": "w5", "type": "dateInterval", "value": {
+                    "start": "2019-10-01T08:12:01+08:00",
+                    "end": "2019-10-02T08:12:01+08:00",
+                    "interval": 1.0
+                }},
+                {"id": "w6", "type": "attachmentV2", "value": ["D93653C3-2609-4EE0-8041-61DC1D84F0B5"]},
+                {"id": "w7", "type": "contact", "value": ["f8ca557e"]},
+                {"id": "w8", "type": "contact", "value": [], "open_ids": ["ou_1"]}
+            ])
+        );
+
+        let request = CreateInstance::new("code_1", &OpenId::new("ou_2"), &form);
+        let body: Value = serde_json::from_slice(&request.body().unwrap().unwrap()).unwrap();
+        assert_eq!(body["open_id"], "ou_2");
+        assert_eq!(body["form"], form.to_json());
+    }
+
+    #[tokio::test]
+    async fn create_with_attachment() {
+        let (address, handle) = crate::test_util::serve_all(vec![
+            r#"{"code":0,"msg":"success","data":{"code":"F1","url":"https://example.com/F1"}}"#,
+            r#"{"code":0,"msg":"success","data":{"code":"F2"}}"#,
+            r#"{"code":0,"msg":"success","data":{"instance_code":"I1"}}"#,
+        ]);
+        let client = Client::builder().domain(address.clone()).build().unwrap();
+        let form = ApprovalForm::new().input("w1", "采购");
+        let instance = InstanceBuilder::new("code_1", &OpenId::new("ou_1"), form)
+            .attachment("w2", "报价.pdf", b"%PDF".to_vec())
+            .file_domain(address)
+            .attachment("w2", "合同.pdf", b"%PDF".to_vec())
+            .create(&client)
+            .await
+            .unwrap();
+        assert_eq!(instance.instance_code, "I1");
+
+        let requests = handle.join().unwrap();
+        assert!(requests[0].starts_with("POST /approval/openapi/v2/file/upload "));
+        assert!(requests[0].contains("name=\"type\"\r\n\r\nattachment\r\n"));
+        assert!(requests[0].contains("filename=\"报价.pdf\""));
+        assert!(requests[2].starts_with("POST /open-apis/approval/v4/instances "));
+        let body: Value =
+            serde_json::from_str(requests[2].split("\r\n\r\n").nth(1).unwrap()).unwrap();
+        let form: Value = serde_json::from_str(body["form"].as_str().unwrap()).unwrap();
+        assert_eq!(
+            form,
+            json!([
+                {"id": "w1", "type": "input", "value": "采购"},
+                {"id": "w2", "type": "attachmentV2", "value": ["F1", "F2"]}
+            ])
+        );
+    }
+
+    #[cfg(feature = "chrono")]
+    #[test]
+    fn chrono_date() {
+        use chrono::TimeZone;
+
+        let time = chrono::FixedOffset::east_opt(8 * 3600)
+            .unwrap()
+            .with_ymd_and_hms(2019, 10, 1, 8, 12, 1)
+            .unwrap();
+        assert_eq!(FormDate::from(time).0, "2019-10-01T08:12:01+08:00");
+    }
+}
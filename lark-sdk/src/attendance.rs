@@ -0,0 +1,255 @@
+//! 考勤。
+//!
+//! 查询打卡结果的接口每次最多查询 [`QUERY_USERS_LIMIT`] 个用户、[`QUERY_DAYS_LIMIT`] 天，
+//! 开启 `chrono` 特性后，[`export_user_tasks`] 会将任意的日期范围和用户列表拆分为多次请求，
+//! 依次查询并合并为一个结果流。请求经过客户端的 [`Quota`](crate::quota::Quota)，
+//! 可以为 `/open-apis/attendance/` 单独配置频率限制。
+
+use serde::Deserialize;
+
+use crate::time::{self, LarkTimestamp};
+
+#[cfg(all(feature = "chrono", feature = "async"))]
+use futures_util::{stream, Stream};
+
+#[cfg(all(feature = "chrono", feature = "async"))]
+use crate::{Client, Result};
+
+/// 每次查询的最多用户数
+pub const QUERY_USERS_LIMIT: usize = 50;
+
+/// 每次查询的最多天数，包含开始和结束的日期
+pub const QUERY_DAYS_LIMIT: u32 = 30;
+
+/// 一次打卡
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct CheckRecord {
+    #[serde(default)]
+    pub record_id: Option<String>,
+    /// 打卡时间
+    #[serde(with = "time::seconds")]
+    pub check_time: LarkTimestamp,
+    #[serde(default)]
+    pub location_name: Option<String>,
+    #[serde(default)]
+    pub comment: Option<String>,
+}
+
+/// 一个班次的上下班打卡结果
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct TaskRecord {
+    #[serde(default)]
+    pub check_in_record: Option<CheckRecord>,
+    #[serde(default)]
+    pub check_out_record: Option<CheckRecord>,
+    /// 上班打卡结果，如 `Normal`、`Late`、`Lack`
+    #[serde(default)]
+    pub check_in_result: String,
+    /// 下班打卡结果，如 `Normal`、`Early`、`Lack`
+    #[serde(default)]
+    pub check_out_result: String,
+}
+
+/// 用户一天的打卡结果
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct UserTask {
+    pub result_id: String,
+    /// 查询时 `employee_type` 对应的用户 ID
+    pub user_id: String,
+    #[serde(default)]
+    pub employee_name: Option<String>,
+    /// 日期，格式为 `yyyyMMdd`
+    pub day: i32,
+    #[serde(default)]
+    pub group_id: Option<String>,
+    #[serde(default)]
+    pub shift_id: Option<String>,
+    #[serde(default)]
+    pub records: Vec<TaskRecord>,
+}
+
+/// [`QueryUserTasks`] 的结果
+#[derive(Debug, Clone, Deserialize, lark_sdk::Response)]
+pub struct UserTasks {
+    #[serde(default)]
+    pub user_task_results: Vec<UserTask>,
+    /// 不存在的用户
+    #[serde(default)]
+    pub invalid_user_ids: Vec<String>,
+    /// 没有权限查询的用户
+    #[serde(default)]
+    pub unauthorized_user_ids: Vec<String>,
+}
+
+/// 查询打卡结果
+#[derive(Debug, Clone, lark_sdk::Request)]
+#[request(
+    method = "POST",
+    url = "/open-apis/attendance/v1/user_tasks/query",
+    auth = "tenant",
+    response = UserTasks
+)]
+pub struct QueryUserTasks {
+    /// `user_ids` 的类型，`employee_id` 或 `employee_no`
+    #[request(query)]
+    pub employee_type: String,
+    /// 最多 [`QUERY_USERS_LIMIT`] 个
+    #[request(body, max_len = QUERY_USERS_LIMIT)]
+    pub user_ids: Vec<String>,
+    /// 开始日期，格式为 `yyyyMMdd`
+    #[request(body)]
+    pub check_date_from: i32,
+    /// 结束日期，与开始日期最多相隔 [`QUERY_DAYS_LIMIT`] 天
+    #[request(body)]
+    pub check_date_to: i32,
+}
+
+/// 将 `start` 到 `end`（包含）拆分为不超过 `days` 天的连续区间
+#[cfg(feature = "chrono")]
+pub fn date_windows(
+    start: chrono::NaiveDate,
+    end: chrono::NaiveDate,
+    days: u32,
+) -> Vec<(chrono::NaiveDate, chrono::NaiveDate)> {
+    let step = chrono::Days::new(u64::from(days.max(1)) - 1);
+    let mut windows = Vec::new();
+    let mut from = start;
+    while from <= end {
+        let to = from.checked_add_days(step).map_or(end, |to| to.min(end));
+        windows.push((from, to));
+        match to.succ_opt() {
+            Some(next) => from = next,
+            None => break,
+        }
+    }
+    windows
+}
+
+#[cfg(all(feature = "chrono", feature = "async"))]
+fn date_number(date: chrono::NaiveDate) -> i32 {
+    use chrono::Datelike;
+
+    date.year() * 10000 + date.month() as i32 * 100 + date.day() as i32
+}
+
+/// 查询 `user_ids`（`employee_id`）在 `start` 到 `end`（包含）之间的打卡结果。
+///
+/// 按 [`QUERY_DAYS_LIMIT`] 和 [`QUERY_USERS_LIMIT`] 拆分为多次请求依次发送，按日期区间的顺序返回，
+/// 某次请求失败时返回错误并结束。
+///
+/// ```no_run
+/// use chrono::NaiveDate;
+/// use futures_util::TryStreamExt;
+/// use lark_sdk::attendance::export_user_tasks;
+///
+/// # async fn run(client: lark_sdk::Client) -> lark_sdk::Result<()> {
+/// let start = NaiveDate::from_ymd_opt(2023, 1, 1).unwrap();
+/// let end = NaiveDate::from_ymd_opt(2023, 3, 31).unwrap();
+/// let users = vec!["abd754f7".to_string()];
+/// let mut tasks = Box::pin(export_user_tasks(&client, &users, start, end));
+/// while let Some(task) = tasks.try_next().await? {
+///     println!("{} {} {:?}", task.user_id, task.day, task.records);
+/// }
+/// # Ok(())
+/// # }
+/// ```
+#[cfg(all(feature = "chrono", feature = "async"))]
+pub fn export_user_tasks<'a>(
+    client: &'a Client,
+    user_ids: &[String],
+    start: chrono::NaiveDate,
+    end: chrono::NaiveDate,
+) -> impl Stream<Item = Result<UserTask>> + 'a {
+    let mut requests = Vec::new();
+    for (from, to) in date_windows(start, end, QUERY_DAYS_LIMIT) {
+        for users in user_ids.chunks(QUERY_USERS_LIMIT) {
+            requests.push(QueryUserTasks {
+                employee_type: "employee_id".to_string(),
+                user_ids: users.to_vec(),
+                check_date_from: date_number(from),
+                check_date_to: date_number(to),
+            });
+        }
+    }
+    let state = (requests.into_iter(), Vec::new().into_iter());
+    stream::unfold(Some(state), move |state| async move {
+        let (mut requests, mut tasks) = state?;
+        loop {
+            if let Some(task) = tasks.next() {
+                return Some((Ok(task), Some((requests, tasks))));
+            }
+            let request = requests.next()?;
+            match client
+                .send(&request)
+                .await
+                .and_then(|response| response.into_data())
+            {
+                Ok(data) => tasks = data.user_task_results.into_iter(),
+                Err(err) => return Some((Err(err), None)),
+            }
+        }
+    })
+}
+
+#[cfg(all(test, feature = "chrono"))]
+mod tests {
+    use chrono::NaiveDate;
+    use futures_util::TryStreamExt;
+
+    use super::*;
+    use crate::test_util::serve_all;
+
+    fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(year, month, day).unwrap()
+    }
+
+    #[test]
+    fn split_dates() {
+        assert_eq!(
+            date_windows(date(2023, 1, 1), date(2023, 3, 1), 30),
+            vec![
+                (date(2023, 1, 1), date(2023, 1, 30)),
+                (date(2023, 1, 31), date(2023, 3, 1)),
+            ]
+        );
+        assert_eq!(
+            date_windows(date(2023, 1, 1), date(2023, 1, 1), 30),
+            vec![(date(2023, 1, 1), date(2023, 1, 1))]
+        );
+        assert!(date_windows(date(2023, 1, 2), date(2023, 1, 1), 30).is_empty());
+    }
+
+    #[tokio::test]
+    async fn export_tasks() {
+        let (address, handle) = serve_all(vec![
+            r#"{"code":0,"msg":"success","data":{"user_task_results":[{"result_id":"r1","user_id":"u1","day":20230101,"records":[{"check_in_result":"Normal","check_out_result":"Early"}]},{"result_id":"r2","user_id":"u1","day":20230102}]}}"#,
+            r#"{"code":0,"msg":"success","data":{"user_task_results":[]}}"#,
+            r#"{"code":0,"msg":"success","data":{"user_task_results":[{"result_id":"r3","user_id":"u1","day":20230302}]}}"#,
+        ]);
+        let client = Client::builder().domain(address).build().unwrap();
+        let users = vec!["u1".to_string()];
+        let tasks: Vec<UserTask> =
+            export_user_tasks(&client, &users, date(2023, 1, 1), date(2023, 3, 31))
+                .try_collect()
+                .await
+                .unwrap();
+        let ids: Vec<&str> = tasks.iter().map(|task| task.result_id.as_str()).collect();
+        assert_eq!(ids, ["r1", "r2", "r3"]);
+        assert_eq!(tasks[0].records[0].check_out_result, "Early");
+
+        let requests = handle.join().unwrap();
+        assert!(requests[0].starts_with(
+            "POST /open-apis/attendance/v1/user_tasks/query?employee_type=employee_id "
+        ));
+        let body: serde_json::Value =
+            serde_json::from_str(requests[1].split("\r\n\r\n").nth(1).unwrap()).unwrap();
+        assert_eq!(
+            body,
+            serde_json::json!({
+                "user_ids": ["u1"],
+                "check_date_from": 20230131,
+                "check_date_to": 20230301
+            })
+        );
+    }
+}
@@ -0,0 +1,188 @@
+//! 写操作的审计记录。
+//!
+//! 配置了 [`AuditSink`] 的客户端在每次发送非 `GET` 请求后生成一条 [`AuditRecord`]，包含接口、
+//! 使用的访问凭证类型、请求体的 SHA-256 摘要和响应的 `log_id`，不包含请求体本身，可以放心写入
+//! 日志或审计系统，需要核对时再与业务侧保存的请求内容比较摘要。
+//!
+//! ```
+//! use lark_sdk::audit::LogAuditSink;
+//!
+//! # #[cfg(feature = "async")]
+//! let client = lark_sdk::Client::builder()
+//!     .audit_sink(LogAuditSink)
+//!     .build();
+//! ```
+
+use std::fmt;
+use std::fmt::Write;
+use std::sync::Arc;
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::time::LarkTimestamp;
+use crate::transport::HttpResponse;
+use crate::{AuthType, Method, RequestParts, Result};
+
+/// 一次写操作的审计记录
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AuditRecord {
+    /// 发送请求的时间
+    pub time: LarkTimestamp,
+    pub method: Method,
+    /// 替换路径参数之前的请求路径，没有 [`Request::endpoint`](crate::Request::endpoint) 时为实际的路径
+    pub endpoint: String,
+    /// 替换了路径参数之后的请求路径
+    pub url: String,
+    /// 请求使用的访问凭证类型
+    pub auth: AuthType,
+    /// 配置了应用凭证时为应用的 `app_id`
+    pub app_id: Option<String>,
+    /// 商店应用代表的租户
+    pub tenant_key: Option<String>,
+    /// 请求体（压缩之前）的 SHA-256 摘要，十六进制小写，没有请求体时为 `None`
+    pub body_sha256: Option<String>,
+    /// HTTP 状态码，请求没有发送成功时为 `None`
+    pub status: Option<u16>,
+    /// 响应头 `X-Tt-Logid` 的值
+    pub log_id: Option<String>,
+    /// 请求没有发送成功时的错误信息
+    pub error: Option<String>,
+}
+
+impl AuditRecord {
+    /// `parts` 为写操作时开始一条记录
+    pub(crate) fn begin(
+        parts: &RequestParts,
+        app_id: Option<&str>,
+        tenant_key: Option<&str>,
+    ) -> Option<AuditRecord> {
+        if parts.method == Method::Get {
+            return None;
+        }
+        Some(AuditRecord {
+            time: LarkTimestamp::now(),
+            method: parts.method,
+            endpoint: parts.endpoint.clone().unwrap_or_else(|| parts.url.clone()),
+            url: parts.url.clone(),
+            auth: parts.auth,
+            app_id: app_id.map(String::from),
+            tenant_key: tenant_key.map(String::from),
+            body_sha256: parts.body.as_deref().map(sha256_hex),
+            status: None,
+            log_id: None,
+            error: None,
+        })
+    }
+
+    /// 记录请求的结果
+    pub(crate) fn finish(mut self, result: &Result<HttpResponse>) -> AuditRecord {
+        match result {
+            Ok(response) => {
+                self.status = Some(response.status);
+                self.log_id = response.header("X-Tt-Logid").map(String::from);
+            }
+            Err(err) => self.error = Some(err.to_string()),
+        }
+        self
+    }
+}
+
+/// 接收审计记录，由客户端在请求完成后同步调用，耗时的写入（如数据库、消息队列）应该
+/// 转发到后台任务中完成，避免阻塞请求
+pub trait AuditSink: Send + Sync + fmt::Debug {
+    fn record(&self, record: &AuditRecord);
+}
+
+impl<T: AuditSink + ?Sized> AuditSink for Arc<T> {
+    fn record(&self, record: &AuditRecord) {
+        (**self).record(record)
+    }
+}
+
+/// 使用 `log` 输出审计记录，target 为 `lark_sdk::audit`
+#[derive(Debug, Clone, Copy, Default)]
+pub struct LogAuditSink;
+
+impl AuditSink for LogAuditSink {
+    fn record(&self, record: &AuditRecord) {
+        log::info!(
+            target: "lark_sdk::audit",
+            "{} {} url={} auth={:?} app_id={} tenant_key={} body_sha256={} status={} log_id={}{}",
+            record.method,
+            record.endpoint,
+            record.url,
+            record.auth,
+            record.app_id.as_deref().unwrap_or("-"),
+            record.tenant_key.as_deref().unwrap_or("-"),
+            record.body_sha256.as_deref().unwrap_or("-"),
+            record.status.map_or_else(|| "-".to_string(), |status| status.to_string()),
+            record.log_id.as_deref().unwrap_or("-"),
+            record
+                .error
+                .as_ref()
+                .map_or_else(String::new, |err| format!(" error={}", err)),
+        );
+    }
+}
+
+fn sha256_hex(body: &[u8]) -> String {
+    Sha256::digest(body)
+        .iter()
+        .fold(String::with_capacity(64), |mut hex, byte| {
+            let _ = write!(hex, "{:02x}", byte);
+            hex
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::LarkError;
+
+    fn parts(method: Method, body: Option<&[u8]>) -> RequestParts {
+        RequestParts {
+            method,
+            url: "/open-apis/im/v1/messages/om_1".to_string(),
+            query: Vec::new(),
+            headers: Vec::new(),
+            content_type: crate::DEFAULT_CONTENT_TYPE.to_string(),
+            body: body.map(Vec::from),
+            endpoint: Some("/open-apis/im/v1/messages/:message_id".to_string()),
+            auth: AuthType::Tenant,
+        }
+    }
+
+    #[test]
+    fn begin_and_finish() {
+        assert_eq!(
+            AuditRecord::begin(&parts(Method::Get, None), None, None),
+            None
+        );
+
+        let record = AuditRecord::begin(&parts(Method::Delete, None), Some("cli_1"), None).unwrap();
+        assert_eq!(record.endpoint, "/open-apis/im/v1/messages/:message_id");
+        assert_eq!(record.url, "/open-apis/im/v1/messages/om_1");
+        assert_eq!(record.auth, AuthType::Tenant);
+        assert_eq!(record.app_id.as_deref(), Some("cli_1"));
+        assert_eq!(record.body_sha256, None);
+        let record = record.finish(&Err(LarkError::Cancelled));
+        assert_eq!(record.status, None);
+        assert!(record.error.is_some());
+
+        let record =
+            AuditRecord::begin(&parts(Method::Put, Some(b"{}")), None, Some("t1")).unwrap();
+        assert_eq!(
+            record.body_sha256.as_deref(),
+            Some("44136fa355b3678a1146ad16f7e8649e94fb4fc21fe77e8310c060f61caaff8a")
+        );
+        let record = record.finish(&Ok(HttpResponse {
+            status: 200,
+            headers: vec![("x-tt-logid".to_string(), "log1".to_string())],
+            body: Vec::new(),
+        }));
+        assert_eq!(record.status, Some(200));
+        assert_eq!(record.log_id.as_deref(), Some("log1"));
+        assert_eq!(record.error, None);
+    }
+}
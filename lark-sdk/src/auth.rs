@@ -0,0 +1,651 @@
+//! 访问凭证。
+//!
+//! 配置了应用凭证（[`ClientBuilder::app_credentials`](crate::ClientBuilder::app_credentials)）的客户端会为
+//! `auth = "tenant"`、`auth = "app"` 的请求自动获取、缓存并添加 `tenant_access_token`、`app_access_token`。
+//! 商店应用的凭证按租户缓存，见 [`ClientRegistry`](crate::registry::ClientRegistry)。
+//!
+//! [`UserAuth`] 从 [`UserTokenStore`] 中取出用户的访问凭证发送请求，凭证即将过期或接口返回凭证过期时，
+//! 使用 `refresh_token` 刷新凭证、写回存储后重新发送请求。
+
+use std::collections::HashMap;
+use std::fmt;
+#[cfg(feature = "async")]
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::time::{Duration, Instant, SystemTime};
+
+#[cfg(feature = "async")]
+use futures_util::future::BoxFuture;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    endpoints, AuthType, Body, FlattenResponse, LarkError, Method, Request, RequestParts, Result,
+};
+#[cfg(feature = "async")]
+use crate::{Client, Response};
+
+/// 凭证在过期前多久就会被刷新
+const REFRESH_MARGIN: Duration = Duration::from_secs(60);
+
+/// 表示 `user_access_token` 无效或过期的错误码
+#[cfg(feature = "async")]
+const EXPIRED_CODES: &[i64] = &[99991668, 99991677];
+
+/// 表示 `tenant_access_token`、`app_access_token` 缺失或无效的错误码
+const INVALID_APP_TOKEN_CODES: &[i64] = &[99991661, 99991663, 99991664];
+
+/// 应用的 `tenant_access_token` 和 `app_access_token` 缓存。
+///
+/// 自建应用直接使用 App ID 和 App Secret 获取凭证；商店应用需要先使用平台推送的 `app_ticket`
+/// 获取 `app_access_token`，再为每个租户获取 `tenant_access_token`。
+pub(crate) struct TokenManager {
+    app_id: String,
+    app_secret: String,
+    marketplace: bool,
+    app_ticket: Mutex<Option<String>>,
+    tokens: Mutex<HashMap<TokenKey, CachedToken>>,
+}
+
+impl fmt::Debug for TokenManager {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TokenManager")
+            .field("app_id", &self.app_id)
+            .field("marketplace", &self.marketplace)
+            .finish_non_exhaustive()
+    }
+}
+
+/// 缓存的凭证，自建应用的 `tenant_key` 为空
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub(crate) struct TokenKey {
+    auth: AuthType,
+    tenant_key: String,
+}
+
+struct CachedToken {
+    token: String,
+    expires_at: Instant,
+}
+
+impl TokenManager {
+    pub(crate) fn new(app_id: String, app_secret: String) -> Self {
+        TokenManager {
+            app_id,
+            app_secret,
+            marketplace: false,
+            app_ticket: Mutex::new(None),
+            tokens: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// 商店应用
+    #[cfg(any(feature = "async", feature = "minimal"))]
+    pub(crate) fn marketplace(app_id: String, app_secret: String) -> Self {
+        TokenManager {
+            marketplace: true,
+            ..TokenManager::new(app_id, app_secret)
+        }
+    }
+
+    pub(crate) fn app_id(&self) -> &str {
+        &self.app_id
+    }
+
+    /// 更新商店应用的 `app_ticket`，之前获取的 `app_access_token` 仍然有效
+    #[cfg(any(feature = "async", feature = "minimal"))]
+    pub(crate) fn set_app_ticket(&self, app_ticket: String) {
+        *self.app_ticket.lock().unwrap() = Some(app_ticket);
+    }
+
+    /// 请求使用的凭证，商店应用的 `tenant_access_token` 需要指定租户
+    pub(crate) fn key(&self, auth: AuthType, tenant_key: Option<&str>) -> Result<TokenKey> {
+        let tenant_key = match (auth, self.marketplace, tenant_key) {
+            (AuthType::Tenant, true, Some(tenant_key)) => tenant_key.to_string(),
+            (AuthType::Tenant, true, None) => {
+                return Err(LarkError::InvalidRequest(format!(
+                    "{} is a marketplace app, use Client::tenant to choose the tenant",
+                    self.app_id
+                )))
+            }
+            _ => String::new(),
+        };
+        Ok(TokenKey { auth, tenant_key })
+    }
+
+    /// 依次需要获取的凭证，商店应用获取 `tenant_access_token` 前需要先获取 `app_access_token`
+    pub(crate) fn chain(&self, key: &TokenKey) -> Vec<TokenKey> {
+        let mut chain = Vec::with_capacity(2);
+        if self.marketplace && key.auth == AuthType::Tenant {
+            chain.push(TokenKey {
+                auth: AuthType::App,
+                tenant_key: String::new(),
+            });
+        }
+        chain.push(key.clone());
+        chain
+    }
+
+    /// 未过期的凭证
+    pub(crate) fn cached(&self, key: &TokenKey) -> Option<String> {
+        let tokens = self.tokens.lock().unwrap();
+        tokens
+            .get(key)
+            .filter(|cached| Instant::now() + REFRESH_MARGIN < cached.expires_at)
+            .map(|cached| cached.token.clone())
+    }
+
+    /// 获取凭证的请求，商店应用的 `tenant_access_token` 需要 `chain` 中前一个凭证
+    pub(crate) fn request(
+        &self,
+        key: &TokenKey,
+        app_access_token: Option<&str>,
+    ) -> Result<AppTokenRequest> {
+        let (endpoint, body) = match (key.auth, self.marketplace) {
+            (AuthType::App, false) => (
+                endpoints::auth::APP_ACCESS_TOKEN_INTERNAL,
+                serde_json::json!({ "app_id": self.app_id, "app_secret": self.app_secret }),
+            ),
+            (_, false) => (
+                endpoints::auth::TENANT_ACCESS_TOKEN_INTERNAL,
+                serde_json::json!({ "app_id": self.app_id, "app_secret": self.app_secret }),
+            ),
+            (AuthType::App, true) => {
+                let app_ticket = self.app_ticket.lock().unwrap().clone().ok_or_else(|| {
+                    LarkError::InvalidRequest(format!(
+                        "app_ticket of {} has not been received yet",
+                        self.app_id
+                    ))
+                })?;
+                (
+                    endpoints::auth::APP_ACCESS_TOKEN,
+                    serde_json::json!({
+                        "app_id": self.app_id,
+                        "app_secret": self.app_secret,
+                        "app_ticket": app_ticket,
+                    }),
+                )
+            }
+            (_, true) => (
+                endpoints::auth::TENANT_ACCESS_TOKEN,
+                serde_json::json!({
+                    "app_access_token": app_access_token.unwrap_or_default(),
+                    "tenant_key": key.tenant_key,
+                }),
+            ),
+        };
+        Ok(AppTokenRequest { endpoint, body })
+    }
+
+    /// 缓存获取到的凭证
+    pub(crate) fn update(
+        &self,
+        key: &TokenKey,
+        response: FlattenResponse<AppToken>,
+    ) -> Result<String> {
+        let data = response.into_data()?;
+        let token = match key.auth {
+            AuthType::App => data.app_access_token,
+            _ => data.tenant_access_token,
+        }
+        .ok_or(LarkError::MissingData)?;
+        self.tokens.lock().unwrap().insert(
+            key.clone(),
+            CachedToken {
+                token: token.clone(),
+                expires_at: Instant::now() + Duration::from_secs(data.expire),
+            },
+        );
+        Ok(token)
+    }
+
+    pub(crate) fn invalidate(&self, key: &TokenKey) {
+        self.tokens.lock().unwrap().remove(key);
+    }
+}
+
+/// 请求需要客户端添加的凭证类型，请求已经带有 `Authorization` 请求头时为 `None`
+pub(crate) fn injected_auth(parts: &RequestParts) -> Result<Option<AuthType>> {
+    if parts
+        .headers
+        .iter()
+        .any(|(name, _)| name.eq_ignore_ascii_case("authorization"))
+    {
+        return Ok(None);
+    }
+    match parts.auth {
+        AuthType::None => Ok(None),
+        AuthType::User => Err(LarkError::InvalidRequest(format!(
+            "{} requires a user_access_token, send it with auth::UserAuth",
+            parts.url
+        ))),
+        auth => Ok(Some(auth)),
+    }
+}
+
+/// 使用 `token` 作为请求的 `Authorization`
+pub(crate) fn with_bearer(mut parts: RequestParts, token: &str) -> RequestParts {
+    parts
+        .headers
+        .retain(|(name, _)| !name.eq_ignore_ascii_case("authorization"));
+    parts
+        .headers
+        .push(("Authorization".to_string(), format!("Bearer {}", token)));
+    parts
+}
+
+/// 响应是否表示应用凭证无效，此时需要丢弃缓存的凭证重新获取
+pub(crate) fn is_invalid_app_token(bytes: &[u8]) -> bool {
+    #[derive(Deserialize)]
+    struct Code {
+        #[serde(default)]
+        code: i64,
+    }
+    serde_json::from_slice::<Code>(bytes)
+        .is_ok_and(|response| INVALID_APP_TOKEN_CODES.contains(&response.code))
+}
+
+/// 获取 `tenant_access_token` 或 `app_access_token`
+pub(crate) struct AppTokenRequest {
+    endpoint: &'static str,
+    body: serde_json::Value,
+}
+
+impl Request for AppTokenRequest {
+    type Target = FlattenResponse<AppToken>;
+
+    fn method(&self) -> Method {
+        Method::Post
+    }
+
+    fn url(&self) -> String {
+        self.endpoint.to_string()
+    }
+
+    fn endpoint(&self) -> Option<&'static str> {
+        Some(self.endpoint)
+    }
+
+    fn body(&self) -> Result<Option<Vec<u8>>> {
+        Ok(Some(serde_json::to_vec(&self.body)?))
+    }
+}
+
+#[derive(Deserialize)]
+pub(crate) struct AppToken {
+    #[serde(default)]
+    tenant_access_token: Option<String>,
+    #[serde(default)]
+    app_access_token: Option<String>,
+    /// 有效期，秒
+    expire: u64,
+}
+
+impl Body for AppToken {}
+
+/// 用户的访问凭证
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UserToken {
+    pub access_token: String,
+    pub refresh_token: String,
+    /// `access_token` 的过期时间
+    pub expires_at: SystemTime,
+    /// `refresh_token` 的过期时间，未知时为 `None`
+    pub refresh_expires_at: Option<SystemTime>,
+}
+
+impl UserToken {
+    /// `access_token` 是否已经过期或即将过期
+    pub fn is_expired(&self) -> bool {
+        SystemTime::now() + REFRESH_MARGIN >= self.expires_at
+    }
+}
+
+/// 用户访问凭证的存储，`user` 为调用方自定义的用户标识，如 `open_id`。
+///
+/// 多个进程共享凭证时应使用外部存储（如 Redis），刷新后的 `refresh_token` 会替换旧的，旧的不再可用。
+/// 读写失败时 [`UserAuth::send`] 返回该错误，刷新后保存失败意味着新的 `refresh_token` 丢失，
+/// 需要用户重新授权。
+#[cfg(feature = "async")]
+pub trait UserTokenStore: Send + Sync {
+    /// 读取 `user` 的凭证，没有时返回 `None`
+    fn get<'a>(&'a self, user: &'a str) -> BoxFuture<'a, Result<Option<UserToken>>>;
+
+    /// 保存 `user` 的凭证，返回时凭证已经持久化
+    fn set<'a>(&'a self, user: &'a str, token: UserToken) -> BoxFuture<'a, Result<()>>;
+}
+
+/// 保存在内存中的 [`UserTokenStore`]
+#[cfg(feature = "async")]
+#[derive(Debug, Default)]
+pub struct MemoryUserTokenStore {
+    tokens: Mutex<HashMap<String, UserToken>>,
+}
+
+#[cfg(feature = "async")]
+impl MemoryUserTokenStore {
+    pub fn new() -> Self {
+        MemoryUserTokenStore::default()
+    }
+}
+
+#[cfg(feature = "async")]
+impl UserTokenStore for MemoryUserTokenStore {
+    fn get<'a>(&'a self, user: &'a str) -> BoxFuture<'a, Result<Option<UserToken>>> {
+        let token = self.tokens.lock().unwrap().get(user).cloned();
+        Box::pin(async { Ok(token) })
+    }
+
+    fn set<'a>(&'a self, user: &'a str, token: UserToken) -> BoxFuture<'a, Result<()>> {
+        self.tokens.lock().unwrap().insert(user.to_string(), token);
+        Box::pin(async { Ok(()) })
+    }
+}
+
+/// 以用户身份发送请求，自动刷新过期的 `user_access_token`。
+///
+/// ```no_run
+/// # use lark_sdk::auth::{MemoryUserTokenStore, UserAuth};
+/// # use lark_sdk::Client;
+/// #[derive(lark_sdk::Request)]
+/// #[request(method = "GET", url = "/open-apis/authen/v1/user_info", auth = "user", response = serde_json::Value)]
+/// pub struct GetUserInfo {}
+///
+/// # async fn run(client: Client) -> lark_sdk::Result<()> {
+/// let auth = UserAuth::new("cli_xxx", "secret", MemoryUserTokenStore::new());
+/// let response = auth.send(&client, "ou_xxx", &GetUserInfo {}).await?;
+/// # Ok(())
+/// # }
+/// ```
+#[cfg(feature = "async")]
+pub struct UserAuth {
+    app_id: String,
+    app_secret: String,
+    store: Arc<dyn UserTokenStore>,
+    /// 同一时间只刷新一次，避免并发刷新时旧的 `refresh_token` 被重复使用
+    refreshing: tokio::sync::Mutex<()>,
+}
+
+#[cfg(feature = "async")]
+impl UserAuth {
+    pub fn new(
+        app_id: impl Into<String>,
+        app_secret: impl Into<String>,
+        store: impl UserTokenStore + 'static,
+    ) -> Self {
+        UserAuth {
+            app_id: app_id.into(),
+            app_secret: app_secret.into(),
+            store: Arc::new(store),
+            refreshing: tokio::sync::Mutex::new(()),
+        }
+    }
+
+    pub fn store(&self) -> &dyn UserTokenStore {
+        &*self.store
+    }
+
+    /// 使用 `user` 的访问凭证发送请求，存储中没有该用户的凭证时返回
+    /// [`LarkError::InvalidRequest`]
+    pub async fn send<R: Request + ?Sized>(
+        &self,
+        client: &Client,
+        user: &str,
+        request: &R,
+    ) -> Result<R::Target> {
+        let mut token = self
+            .store
+            .get(user)
+            .await?
+            .ok_or_else(|| LarkError::InvalidRequest(format!("no user token for {}", user)))?;
+        if token.is_expired() {
+            token = self.refresh(client, user, &token).await?;
+        }
+        let response = self.send_with(client, request, &token).await?;
+        if !EXPIRED_CODES.contains(&response.code()) {
+            return Ok(response);
+        }
+        let token = self.refresh(client, user, &token).await?;
+        self.send_with(client, request, &token).await
+    }
+
+    async fn send_with<R: Request + ?Sized>(
+        &self,
+        client: &Client,
+        request: &R,
+        token: &UserToken,
+    ) -> Result<R::Target> {
+        let parts = with_bearer(request.to_parts()?, &token.access_token);
+        client.send_parts(parts).await
+    }
+
+    /// 刷新 `stale` 对应的凭证，其他请求已经刷新过时直接使用存储中的新凭证
+    async fn refresh(&self, client: &Client, user: &str, stale: &UserToken) -> Result<UserToken> {
+        let _guard = self.refreshing.lock().await;
+        if let Some(current) = self.store.get(user).await? {
+            if current.access_token != stale.access_token && !current.is_expired() {
+                return Ok(current);
+            }
+        }
+        let request = RefreshRequest {
+            client_id: &self.app_id,
+            client_secret: &self.app_secret,
+            refresh_token: &stale.refresh_token,
+        };
+        let refreshed = client.send(&request).await?.into_data()?;
+        let now = SystemTime::now();
+        let token = UserToken {
+            access_token: refreshed.access_token,
+            refresh_token: refreshed.refresh_token,
+            expires_at: now + Duration::from_secs(refreshed.expires_in),
+            refresh_expires_at: refreshed
+                .refresh_token_expires_in
+                .map(|expires_in| now + Duration::from_secs(expires_in)),
+        };
+        self.store.set(user, token.clone()).await?;
+        log::debug!("user access token of {} refreshed", user);
+        Ok(token)
+    }
+}
+
+/// 刷新 `user_access_token`
+#[cfg(feature = "async")]
+struct RefreshRequest<'a> {
+    client_id: &'a str,
+    client_secret: &'a str,
+    refresh_token: &'a str,
+}
+
+#[cfg(feature = "async")]
+impl Request for RefreshRequest<'_> {
+    type Target = FlattenResponse<RefreshedToken>;
+
+    fn method(&self) -> Method {
+        Method::Post
+    }
+
+    fn url(&self) -> String {
+        endpoints::auth::OAUTH_TOKEN.to_string()
+    }
+
+    fn endpoint(&self) -> Option<&'static str> {
+        Some(endpoints::auth::OAUTH_TOKEN)
+    }
+
+    fn body(&self) -> Result<Option<Vec<u8>>> {
+        let body = serde_json::json!({
+            "grant_type": "refresh_token",
+            "client_id": self.client_id,
+            "client_secret": self.client_secret,
+            "refresh_token": self.refresh_token,
+        });
+        Ok(Some(serde_json::to_vec(&body)?))
+    }
+}
+
+#[cfg(feature = "async")]
+#[derive(Deserialize)]
+struct RefreshedToken {
+    access_token: String,
+    expires_in: u64,
+    refresh_token: String,
+    #[serde(default)]
+    refresh_token_expires_in: Option<u64>,
+}
+
+#[cfg(feature = "async")]
+impl Body for RefreshedToken {}
+
+#[cfg(all(test, feature = "async"))]
+mod tests {
+    use super::*;
+    use crate::test_util::serve_all;
+
+    #[derive(lark_sdk::Request)]
+    #[request(method = "GET", url = "/open-apis/authen/v1/user_info", auth = "user", response = serde_json::Value)]
+    struct GetUserInfo {}
+
+    #[derive(lark_sdk::Request)]
+    #[request(method = "GET", url = "/open-apis/im/v1/chats", auth = "tenant", response = serde_json::Value)]
+    struct ListChats {}
+
+    const TENANT_TOKEN: &str = r#"{"code":0,"msg":"ok","tenant_access_token":"t-1","expire":7200}"#;
+
+    fn token(access_token: &str, expires_in: u64) -> UserToken {
+        UserToken {
+            access_token: access_token.to_string(),
+            refresh_token: format!("r-{}", access_token),
+            expires_at: SystemTime::now() + Duration::from_secs(expires_in),
+            refresh_expires_at: None,
+        }
+    }
+
+    const REFRESHED: &str = r#"{"code":0,"access_token":"u-2","expires_in":7200,"refresh_token":"r-2","refresh_token_expires_in":604800,"token_type":"Bearer"}"#;
+
+    #[tokio::test]
+    async fn refresh_on_expired_code() {
+        let (address, handle) = serve_all(vec![
+            r#"{"code":99991677,"msg":"token expired"}"#,
+            REFRESHED,
+            r#"{"code":0,"msg":"ok","data":{"name":"Tom"}}"#,
+        ]);
+        let client = Client::builder().domain(address).build().unwrap();
+        let store = MemoryUserTokenStore::new();
+        store.set("ou_1", token("u-1", 3600)).await.unwrap();
+        let auth = UserAuth::new("cli_1", "secret", store);
+
+        let response = auth.send(&client, "ou_1", &GetUserInfo {}).await.unwrap();
+        assert_eq!(response.data.unwrap()["name"], "Tom");
+
+        let requests = handle.join().unwrap();
+        assert!(requests[0].contains("authorization: Bearer u-1\r\n"));
+        assert!(requests[1].starts_with("POST /open-apis/authen/v2/oauth/token"));
+        assert!(requests[1].contains(r#""refresh_token":"r-u-1""#));
+        assert!(requests[2].contains("authorization: Bearer u-2\r\n"));
+
+        let stored = auth.store().get("ou_1").await.unwrap().unwrap();
+        assert_eq!(stored.refresh_token, "r-2");
+        assert!(!stored.is_expired());
+        assert!(stored.refresh_expires_at.is_some());
+    }
+
+    #[tokio::test]
+    async fn refresh_before_expiry() {
+        let (address, handle) = serve_all(vec![REFRESHED, r#"{"code":0,"msg":"ok","data":{}}"#]);
+        let client = Client::builder().domain(address).build().unwrap();
+        let store = MemoryUserTokenStore::new();
+        store.set("ou_1", token("u-1", 10)).await.unwrap();
+        let auth = UserAuth::new("cli_1", "secret", store);
+
+        auth.send(&client, "ou_1", &GetUserInfo {}).await.unwrap();
+        let requests = handle.join().unwrap();
+        assert!(requests[0].starts_with("POST /open-apis/authen/v2/oauth/token"));
+        assert!(requests[1].contains("authorization: Bearer u-2\r\n"));
+    }
+
+    /// 读取正常、保存失败的存储
+    struct ReadOnlyStore(MemoryUserTokenStore);
+
+    impl UserTokenStore for ReadOnlyStore {
+        fn get<'a>(&'a self, user: &'a str) -> BoxFuture<'a, Result<Option<UserToken>>> {
+            self.0.get(user)
+        }
+
+        fn set<'a>(&'a self, _: &'a str, _: UserToken) -> BoxFuture<'a, Result<()>> {
+            Box::pin(async { Err(LarkError::InvalidRequest("read only".to_string())) })
+        }
+    }
+
+    #[tokio::test]
+    async fn store_error() {
+        let (address, handle) = serve_all(vec![REFRESHED]);
+        let client = Client::builder().domain(address).build().unwrap();
+        let store = MemoryUserTokenStore::new();
+        store.set("ou_1", token("u-1", 10)).await.unwrap();
+        let auth = UserAuth::new("cli_1", "secret", ReadOnlyStore(store));
+
+        // 新的 refresh_token 没有保存时不能继续发送请求
+        let result = auth.send(&client, "ou_1", &GetUserInfo {}).await;
+        assert!(matches!(result, Err(LarkError::InvalidRequest(msg)) if msg == "read only"));
+        assert_eq!(handle.join().unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn missing_token() {
+        let auth = UserAuth::new("cli_1", "secret", MemoryUserTokenStore::new());
+        let result = auth.send(&Client::new(), "ou_1", &GetUserInfo {}).await;
+        assert!(matches!(result, Err(LarkError::InvalidRequest(_))));
+    }
+
+    #[tokio::test]
+    async fn tenant_token_cached() {
+        let (address, handle) = serve_all(vec![
+            TENANT_TOKEN,
+            r#"{"code":0,"msg":"ok","data":{}}"#,
+            r#"{"code":0,"msg":"ok","data":{}}"#,
+        ]);
+        let client = Client::builder()
+            .domain(address)
+            .app_credentials("cli_1", "secret")
+            .build()
+            .unwrap();
+        client.send(&ListChats {}).await.unwrap();
+        client.send(&ListChats {}).await.unwrap();
+
+        let requests = handle.join().unwrap();
+        assert!(requests[0].starts_with("POST /open-apis/auth/v3/tenant_access_token/internal"));
+        assert!(requests[0].contains(r#""app_secret":"secret""#));
+        assert!(requests[1].contains("authorization: Bearer t-1\r\n"));
+        assert!(requests[2].contains("authorization: Bearer t-1\r\n"));
+    }
+
+    #[tokio::test]
+    async fn refetch_invalid_tenant_token() {
+        let (address, handle) = serve_all(vec![
+            TENANT_TOKEN,
+            r#"{"code":99991663,"msg":"Invalid access token"}"#,
+            r#"{"code":0,"msg":"ok","tenant_access_token":"t-2","expire":7200}"#,
+            r#"{"code":0,"msg":"ok","data":{}}"#,
+        ]);
+        let client = Client::builder()
+            .domain(address)
+            .app_credentials("cli_1", "secret")
+            .build()
+            .unwrap();
+        let response = client.send(&ListChats {}).await.unwrap();
+        assert_eq!(response.code, 0);
+
+        let requests = handle.join().unwrap();
+        assert!(requests[2].starts_with("POST /open-apis/auth/v3/tenant_access_token/internal"));
+        assert!(requests[3].contains("authorization: Bearer t-2\r\n"));
+    }
+
+    #[tokio::test]
+    async fn user_request_without_token() {
+        let client = Client::builder()
+            .app_credentials("cli_1", "secret")
+            .build()
+            .unwrap();
+        let result = client.send(&GetUserInfo {}).await;
+        assert!(matches!(result, Err(LarkError::InvalidRequest(_))));
+    }
+}
@@ -0,0 +1,414 @@
+//! 同步客户端，需要开启 `blocking` 特性。
+//!
+//! 不能在异步运行时中使用，异步代码请使用 [`crate::Client`]。
+
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use crate::audit::AuditSink;
+use crate::auth::TokenManager;
+use crate::client::ProxyConfig;
+use crate::exchange::Pipeline;
+use crate::json::DecodeMode;
+use crate::metrics::LatencyTracker;
+use crate::poll::{next_delay, Backoff};
+use crate::quota::Quota;
+use crate::transport::{BlockingTransport, HttpResponse, ReqwestBlockingTransport};
+use crate::{
+    json, Body, BodyResponse, DynRequest, Request, RequestParts, Response, Result, FEISHU_DOMAIN,
+};
+
+/// 同步客户端，接口与 [`crate::Client`] 一致。
+#[derive(Debug, Clone)]
+pub struct Client {
+    transport: Arc<dyn BlockingTransport>,
+    pipeline: Pipeline,
+    decode_mode: DecodeMode,
+}
+
+impl Client {
+    /// 使用飞书开放平台的接口地址创建客户端
+    pub fn new() -> Self {
+        Client {
+            transport: Arc::new(ReqwestBlockingTransport::default()),
+            pipeline: Pipeline::new(FEISHU_DOMAIN.to_string()),
+            decode_mode: DecodeMode::Lenient,
+        }
+    }
+
+    pub fn builder() -> ClientBuilder {
+        ClientBuilder::new()
+    }
+
+    pub fn domain(&self) -> &str {
+        &self.pipeline.domain
+    }
+
+    /// 使用 `mode` 解析响应的客户端，见 [`crate::Client::decode_mode`]
+    pub fn decode_mode(&self, mode: DecodeMode) -> Client {
+        Client {
+            decode_mode: mode,
+            ..self.clone()
+        }
+    }
+
+    /// 发送请求并解析响应，接口返回的错误码需要调用响应的 `into_data` 等方法处理
+    pub fn send<R: Request + ?Sized>(&self, request: &R) -> Result<R::Target> {
+        self.send_parts(request.to_parts()?)
+    }
+
+    /// 发送类型擦除的请求，见 [`crate::Client::send_dyn`]
+    pub fn send_dyn(&self, request: &dyn DynRequest) -> Result<Value> {
+        let response = self.execute(request.parts()?)?;
+        json::with_mode(self.decode_mode, || request.parse_response(&response.body))
+    }
+
+    /// 发送由 [`Request::to_parts`] 生成的请求，见 [`crate::Client::send_parts`]
+    pub fn send_parts<T: DeserializeOwned>(&self, parts: RequestParts) -> Result<T> {
+        let endpoint = json::endpoint(&parts);
+        let response = self.execute(parts)?;
+        json::from_response(endpoint, &response, self.decode_mode)
+    }
+
+    /// 依次发送一批请求，每个请求完成后调用 `on_progress`，返回与 `requests` 顺序一致的结果。
+    ///
+    /// 某个请求失败不会中断后续的请求，适用于不想引入异步运行时的迁移脚本等场景。
+    pub fn execute_all<R, F>(&self, requests: &[R], mut on_progress: F) -> Vec<Result<R::Target>>
+    where
+        R: Request,
+        F: FnMut(&Progress),
+    {
+        let mut progress = Progress {
+            completed: 0,
+            failed: 0,
+            total: requests.len(),
+        };
+        requests
+            .iter()
+            .map(|request| {
+                let result = self.send(request);
+                progress.completed += 1;
+                if !matches!(&result, Ok(response) if response.is_success()) {
+                    progress.failed += 1;
+                }
+                on_progress(&progress);
+                result
+            })
+            .collect()
+    }
+
+    /// 发送请求，需要时添加应用凭证，见 [`crate::Client`]
+    fn execute(&self, parts: RequestParts) -> Result<HttpResponse> {
+        self.pipeline
+            .exchange(parts, None)
+            .run_blocking(&*self.transport)
+    }
+}
+
+impl Default for Client {
+    fn default() -> Self {
+        Client::new()
+    }
+}
+
+/// [`Client::execute_all`] 的进度
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Progress {
+    /// 已完成的请求数量，包括失败的请求
+    pub completed: usize,
+    /// 发送失败或接口返回失败的请求数量
+    pub failed: usize,
+    pub total: usize,
+}
+
+/// 同步客户端配置。
+#[derive(Debug)]
+pub struct ClientBuilder {
+    domain: String,
+    http: reqwest::blocking::ClientBuilder,
+    gzip_threshold: Option<usize>,
+    quota: Option<Arc<Quota>>,
+    latency: Option<Arc<LatencyTracker>>,
+    audit: Option<Arc<dyn AuditSink>>,
+    tokens: Option<Arc<TokenManager>>,
+    decode_mode: DecodeMode,
+    proxy: ProxyConfig,
+    transport: Option<Arc<dyn BlockingTransport>>,
+    #[cfg(feature = "rustls-tls")]
+    root_certificates: Vec<Vec<u8>>,
+}
+
+impl ClientBuilder {
+    pub fn new() -> Self {
+        ClientBuilder {
+            domain: FEISHU_DOMAIN.to_string(),
+            http: reqwest::blocking::Client::builder(),
+            gzip_threshold: None,
+            quota: None,
+            latency: None,
+            audit: None,
+            tokens: None,
+            decode_mode: DecodeMode::Lenient,
+            proxy: ProxyConfig::default(),
+            transport: None,
+            #[cfg(feature = "rustls-tls")]
+            root_certificates: Vec::new(),
+        }
+    }
+
+    /// 接口地址，默认为 [`FEISHU_DOMAIN`](crate::FEISHU_DOMAIN)
+    pub fn domain(mut self, domain: impl Into<String>) -> Self {
+        self.domain = domain.into();
+        self
+    }
+
+    pub fn timeout(mut self, timeout: std::time::Duration) -> Self {
+        self.http = self.http.timeout(timeout);
+        self
+    }
+
+    /// 请求体不小于 `min_len` 字节时使用 gzip 压缩，见 [`crate::ClientBuilder::gzip_request_body`]
+    pub fn gzip_request_body(mut self, min_len: usize) -> Self {
+        self.gzip_threshold = Some(min_len);
+        self
+    }
+
+    /// 请求频率限制，见 [`Quota`]，可以与异步客户端共享同一个 `Arc<Quota>`
+    pub fn quota(mut self, quota: impl Into<Arc<Quota>>) -> Self {
+        self.quota = Some(quota.into());
+        self
+    }
+
+    /// 统计接口耗时，见 [`LatencyTracker`]
+    pub fn latency_tracker(mut self, tracker: impl Into<Arc<LatencyTracker>>) -> Self {
+        self.latency = Some(tracker.into());
+        self
+    }
+
+    /// 为每个写操作生成审计记录，见 [`audit`](crate::audit)
+    pub fn audit_sink(mut self, sink: impl AuditSink + 'static) -> Self {
+        self.audit = Some(Arc::new(sink));
+        self
+    }
+
+    /// 自建应用的凭证，见 [`crate::ClientBuilder::app_credentials`]
+    pub fn app_credentials(
+        mut self,
+        app_id: impl Into<String>,
+        app_secret: impl Into<String>,
+    ) -> Self {
+        self.tokens = Some(Arc::new(TokenManager::new(
+            app_id.into(),
+            app_secret.into(),
+        )));
+        self
+    }
+
+    /// 将 `host` 解析到 `addr`，见 [`crate::ClientBuilder::resolve`]
+    pub fn resolve(mut self, host: &str, addr: std::net::SocketAddr) -> Self {
+        self.http = self.http.resolve(host, addr);
+        self
+    }
+
+    /// 信任 PEM 格式的 CA 证书，见 [`crate::ClientBuilder::add_root_certificate_pem`]
+    #[cfg(feature = "rustls-tls")]
+    pub fn add_root_certificate_pem(mut self, pem: impl Into<Vec<u8>>) -> Self {
+        self.root_certificates.push(pem.into());
+        self
+    }
+
+    /// 使用指定的代理，见 [`crate::ClientBuilder::proxy`]
+    pub fn proxy(mut self, url: impl Into<String>) -> Self {
+        self.proxy.url = Some(url.into());
+        self
+    }
+
+    /// 代理的认证信息，见 [`crate::ClientBuilder::proxy_auth`]
+    pub fn proxy_auth(mut self, username: impl Into<String>, password: impl Into<String>) -> Self {
+        self.proxy.auth = Some((username.into(), password.into()));
+        self
+    }
+
+    /// 不使用代理，见 [`crate::ClientBuilder::no_proxy`]
+    pub fn no_proxy(mut self) -> Self {
+        self.proxy.disabled = true;
+        self
+    }
+
+    /// 响应的解析模式，见 [`crate::ClientBuilder::decode_mode`]
+    pub fn decode_mode(mut self, mode: DecodeMode) -> Self {
+        self.decode_mode = mode;
+        self
+    }
+
+    /// 使用其他 HTTP 库发送接口请求，见 [`transport`](crate::transport)。超时、代理、证书等
+    /// 配置不再生效
+    pub fn transport(mut self, transport: impl BlockingTransport + 'static) -> Self {
+        self.transport = Some(Arc::new(transport));
+        self
+    }
+
+    pub fn build(self) -> Result<Client> {
+        let transport = match self.transport {
+            Some(transport) => transport,
+            None => {
+                let mut http = self.http;
+                match self.proxy.proxies()? {
+                    Some(proxies) => {
+                        for proxy in proxies {
+                            http = http.proxy(proxy);
+                        }
+                    }
+                    None => http = http.no_proxy(),
+                }
+                #[cfg(feature = "rustls-tls")]
+                for pem in &self.root_certificates {
+                    http = http.add_root_certificate(reqwest::Certificate::from_pem(pem)?);
+                }
+                Arc::new(ReqwestBlockingTransport::new(http.build()?))
+            }
+        };
+        Ok(Client {
+            transport,
+            pipeline: Pipeline {
+                domain: self.domain,
+                gzip_threshold: self.gzip_threshold,
+                quota: self.quota,
+                latency: self.latency,
+                audit: self.audit,
+                tokens: self.tokens,
+            },
+            decode_mode: self.decode_mode,
+        })
+    }
+}
+
+impl Default for ClientBuilder {
+    fn default() -> Self {
+        ClientBuilder::new()
+    }
+}
+
+/// 同步版本的 [`crate::poll::poll_until_done`]
+pub fn poll_until_done<R, T, F, D>(
+    client: &Client,
+    mut make_request: F,
+    mut is_done: D,
+    backoff: Backoff,
+    timeout: Duration,
+) -> Result<T>
+where
+    R: Request<Target = BodyResponse<T>>,
+    T: Body,
+    F: FnMut() -> R,
+    D: FnMut(&T) -> bool,
+{
+    let deadline = Instant::now() + timeout;
+    let mut attempt = 0;
+    loop {
+        let data = client.send(&make_request())?.into_data()?;
+        if is_done(&data) {
+            return Ok(data);
+        }
+        std::thread::sleep(next_delay(&backoff, attempt, deadline)?);
+        attempt += 1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::{serve, serve_all};
+
+    #[derive(lark_sdk::Request)]
+    #[request(method = "GET", url = "/open-apis/demo", response = serde_json::Value)]
+    struct Demo {}
+
+    #[test]
+    fn send_request() {
+        let (address, server) = serve(r#"{"code":0,"msg":"ok","data":{"id":1}}"#);
+        let client = Client::builder().domain(address).build().unwrap();
+        let response = client.send(&Demo {}).unwrap();
+        assert_eq!(response.into_data().unwrap()["id"], 1);
+        assert!(server
+            .join()
+            .unwrap()
+            .starts_with("GET /open-apis/demo HTTP/1.1\r\n"));
+    }
+
+    #[test]
+    fn poll() {
+        let (address, server) = serve_all(vec![
+            r#"{"code":0,"msg":"ok","data":{"done":false}}"#,
+            r#"{"code":0,"msg":"ok","data":{"done":true}}"#,
+        ]);
+        let client = Client::builder().domain(address).build().unwrap();
+        let data = poll_until_done(
+            &client,
+            || Demo {},
+            |data: &Value| data["done"] == true,
+            Backoff::fixed(Duration::from_millis(1)),
+            Duration::from_secs(5),
+        )
+        .unwrap();
+        assert_eq!(data["done"], true);
+        assert_eq!(server.join().unwrap().len(), 2);
+    }
+
+    #[derive(lark_sdk::Request)]
+    #[request(method = "GET", url = "/open-apis/demo/:id", response = serde_json::Value)]
+    struct GetItem {
+        #[request(path)]
+        id: u32,
+    }
+
+    #[test]
+    fn execute_all() {
+        let (address, server) = serve_all(vec![
+            r#"{"code":0,"msg":"ok","data":{"id":1}}"#,
+            r#"{"code":404,"msg":"not found"}"#,
+            r#"{"code":0,"msg":"ok","data":{"id":3}}"#,
+        ]);
+        let client = Client::builder().domain(address).build().unwrap();
+        let requests: Vec<GetItem> = (1..=3).map(|id| GetItem { id }).collect();
+        let mut reports = Vec::new();
+        let results = client.execute_all(&requests, |progress| reports.push(*progress));
+
+        assert_eq!(results.len(), 3);
+        assert_eq!(results[2].as_ref().unwrap().data.as_ref().unwrap()["id"], 3);
+        assert_eq!(results[1].as_ref().unwrap().code, 404);
+        assert_eq!(
+            reports.last(),
+            Some(&Progress {
+                completed: 3,
+                failed: 1,
+                total: 3
+            })
+        );
+        assert_eq!(server.join().unwrap().len(), 3);
+    }
+
+    #[derive(lark_sdk::Request)]
+    #[request(method = "GET", url = "/open-apis/demo", auth = "app", response = serde_json::Value)]
+    struct AppDemo {}
+
+    #[test]
+    fn app_token() {
+        let (address, server) = serve_all(vec![
+            r#"{"code":0,"msg":"ok","app_access_token":"a-1","expire":7200}"#,
+            r#"{"code":0,"msg":"ok","data":{}}"#,
+        ]);
+        let client = Client::builder()
+            .domain(address)
+            .app_credentials("cli_1", "secret")
+            .build()
+            .unwrap();
+        client.send(&AppDemo {}).unwrap();
+        let requests = server.join().unwrap();
+        assert!(requests[0].starts_with("POST /open-apis/auth/v3/app_access_token/internal"));
+        assert!(requests[1].contains("authorization: Bearer a-1\r\n"));
+    }
+}
@@ -0,0 +1,396 @@
+//! 卡片模板和 `lark_md` 文本。
+//!
+//! 在飞书卡片搭建工具中设计并发布的卡片可以通过模板 ID 引用，代码只需要提供模板中声明的变量，
+//! 卡片的布局由设计者在搭建工具中维护。[`CardTemplate::to_card`] 得到的卡片 JSON 可以用于发送消息、
+//! 仅特定人可见的卡片和卡片回传交互的响应。
+//!
+//! ```
+//! use lark_sdk::card::{CardTemplate, TemplateVariables};
+//!
+//! #[derive(serde::Serialize)]
+//! struct Approval<'a> {
+//!     applicant: &'a str,
+//!     days: u32,
+//! }
+//!
+//! let variables = TemplateVariables::from_serialize(&Approval { applicant: "张三", days: 3 })?;
+//! let template = CardTemplate::new("AAqk1234")
+//!     .version("1.0.2")
+//!     .variables(variables)
+//!     .variable("status", "待审批");
+//! assert_eq!(
+//!     template.to_card(),
+//!     serde_json::json!({
+//!         "type": "template",
+//!         "data": {
+//!             "template_id": "AAqk1234",
+//!             "template_version_name": "1.0.2",
+//!             "template_variable": { "applicant": "张三", "days": 3, "status": "待审批" }
+//!         }
+//!     })
+//! );
+//! # Ok::<(), lark_sdk::LarkError>(())
+//! ```
+//!
+//! 卡片中 `lark_md` 类型的文本只支持 Markdown 的一部分语法，[`lark_md`] 将服务端生成的 Markdown
+//! 转换为 `lark_md`，不支持的语法按原文显示。
+
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::{LarkError, Result};
+
+/// 卡片模板，序列化为卡片 JSON 中 `data` 的内容
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CardTemplate {
+    template_id: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    template_version_name: Option<String>,
+    #[serde(default)]
+    template_variable: TemplateVariables,
+}
+
+impl CardTemplate {
+    /// 使用模板的最新版本
+    pub fn new(template_id: impl Into<String>) -> Self {
+        CardTemplate {
+            template_id: template_id.into(),
+            template_version_name: None,
+            template_variable: TemplateVariables::new(),
+        }
+    }
+
+    /// 固定模板的版本，如 `1.0.2`，避免模板发布新版本后变量不兼容
+    pub fn version(mut self, version: impl Into<String>) -> Self {
+        self.template_version_name = Some(version.into());
+        self
+    }
+
+    /// 设置一个模板变量，已有的同名变量会被替换
+    pub fn variable(mut self, name: impl Into<String>, value: impl Into<Value>) -> Self {
+        self.template_variable.insert(name, value);
+        self
+    }
+
+    /// 合并模板变量，已有的同名变量会被替换
+    pub fn variables(mut self, variables: TemplateVariables) -> Self {
+        self.template_variable.0.extend(variables.0);
+        self
+    }
+
+    pub fn template_id(&self) -> &str {
+        &self.template_id
+    }
+
+    pub fn template_version(&self) -> Option<&str> {
+        self.template_version_name.as_deref()
+    }
+
+    pub fn template_variables(&self) -> &TemplateVariables {
+        &self.template_variable
+    }
+
+    /// `{"type": "template", "data": {...}}` 形式的卡片 JSON
+    pub fn to_card(&self) -> Value {
+        serde_json::json!({ "type": "template", "data": self })
+    }
+}
+
+/// 卡片模板的变量，以变量名为键
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct TemplateVariables(BTreeMap<String, Value>);
+
+impl TemplateVariables {
+    pub fn new() -> Self {
+        TemplateVariables::default()
+    }
+
+    /// 由结构体等序列化为 JSON 对象的值生成变量，每个字段为一个变量
+    pub fn from_serialize<T: Serialize + ?Sized>(variables: &T) -> Result<Self> {
+        match serde_json::to_value(variables)? {
+            Value::Object(map) => Ok(TemplateVariables(map.into_iter().collect())),
+            value => Err(LarkError::InvalidRequest(format!(
+                "template variables must be a JSON object, got {}",
+                value
+            ))),
+        }
+    }
+
+    /// 设置一个变量，已有的同名变量会被替换
+    pub fn insert(&mut self, name: impl Into<String>, value: impl Into<Value>) {
+        self.0.insert(name.into(), value.into());
+    }
+
+    /// 设置一个变量后返回自身，用于链式调用
+    pub fn with(mut self, name: impl Into<String>, value: impl Into<Value>) -> Self {
+        self.insert(name, value);
+        self
+    }
+
+    pub fn get(&self, name: &str) -> Option<&Value> {
+        self.0.get(name)
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+/// `lark_md` 中需要转义的字符，转义为 HTML 实体
+const LARK_MD_ESCAPES: &[(char, &str)] = &[
+    ('&', "&#38;"),
+    ('<', "&#60;"),
+    ('>', "&#62;"),
+    ('*', "&#42;"),
+    ('~', "&#126;"),
+    ('[', "&#91;"),
+    (']', "&#93;"),
+    ('(', "&#40;"),
+    (')', "&#41;"),
+    ('`', "&#96;"),
+    ('\\', "&#92;"),
+];
+
+/// 转义 `lark_md` 中的特殊字符，使 `text` 按原文显示
+///
+/// ```
+/// assert_eq!(lark_sdk::card::escape_lark_md("a*b*<c>"), "a&#42;b&#42;&#60;c&#62;");
+/// ```
+pub fn escape_lark_md(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    push_escaped(&mut escaped, text);
+    escaped
+}
+
+/// 将 Markdown 转换为 `lark_md` 文本。
+///
+/// 支持的语法：
+///
+/// - `**粗体**`、`*斜体*`、`~~删除线~~`，可以嵌套；
+/// - `[文本](链接)`，只保留 `http`、`https` 和 `lark` 协议的链接，图片 `![描述](链接)` 转换为链接；
+/// - `#` 开头的标题转换为粗体；
+/// - `-`、`*`、`+` 开头的无序列表统一为 `-`。
+///
+/// 行内代码和代码块、引用、表格等其他语法转义后按原文显示，反斜杠转义的字符按原文显示。
+///
+/// ```
+/// use lark_sdk::card::lark_md;
+///
+/// assert_eq!(
+///     lark_md("## 构建失败\n* 分支 **main**，见 [详情](https://ci.example.com/1)\n`a<b`"),
+///     "**构建失败**\n- 分支 **main**，见 [详情](https://ci.example.com/1)\na&#60;b"
+/// );
+/// ```
+pub fn lark_md(markdown: &str) -> String {
+    let mut lines = Vec::new();
+    let mut in_code_block = false;
+    for line in markdown.lines() {
+        let trimmed = line.trim_start();
+        // 代码块的标记不输出
+        if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+            in_code_block = !in_code_block;
+            continue;
+        }
+        let mut output = String::with_capacity(line.len());
+        if in_code_block {
+            push_escaped(&mut output, line);
+        } else if let Some(heading) = heading(trimmed) {
+            if heading.is_empty() {
+                lines.push(output);
+                continue;
+            }
+            output.push_str("**");
+            push_inline(&mut output, heading);
+            output.push_str("**");
+        } else {
+            output.push_str(&line[..line.len() - trimmed.len()]);
+            match ["- ", "* ", "+ "]
+                .iter()
+                .find_map(|marker| trimmed.strip_prefix(marker))
+            {
+                Some(item) => {
+                    output.push_str("- ");
+                    push_inline(&mut output, item);
+                }
+                None => push_inline(&mut output, trimmed),
+            }
+        }
+        lines.push(output);
+    }
+    lines.join("\n")
+}
+
+/// `# 标题` 到 `###### 标题` 的标题内容
+fn heading(line: &str) -> Option<&str> {
+    let level = line.bytes().take_while(|byte| *byte == b'#').count();
+    if !(1..=6).contains(&level) {
+        return None;
+    }
+    let rest = &line[level..];
+    if rest.is_empty() {
+        return Some(rest);
+    }
+    let title = rest.strip_prefix(' ')?.trim();
+    // 结尾的 `#` 前面有空格时才是标题的结束标记，`# C#` 中的 `#` 是标题的内容
+    let closed = title.trim_end_matches('#');
+    if closed.is_empty() || closed.ends_with(' ') {
+        return Some(closed.trim_end());
+    }
+    Some(title)
+}
+
+fn push_escaped(output: &mut String, text: &str) {
+    for c in text.chars() {
+        match LARK_MD_ESCAPES.iter().find(|(special, _)| *special == c) {
+            Some((_, entity)) => output.push_str(entity),
+            None => output.push(c),
+        }
+    }
+}
+
+/// 转换一行中的行内语法
+fn push_inline(output: &mut String, text: &str) {
+    let mut rest = text;
+    while let Some(c) = rest.chars().next() {
+        let consumed = match c {
+            '\\' => rest[1..].chars().next().map(|escaped| {
+                push_escaped(output, &rest[1..1 + escaped.len_utf8()]);
+                1 + escaped.len_utf8()
+            }),
+            '`' => {
+                let ticks = rest.bytes().take_while(|byte| *byte == b'`').count();
+                rest[ticks..].find(&rest[..ticks]).map(|end| {
+                    push_escaped(output, rest[ticks..ticks + end].trim());
+                    ticks * 2 + end
+                })
+            }
+            '*' if rest.starts_with("**") => push_delimited(output, rest, "**"),
+            '_' if rest.starts_with("__") => push_delimited(output, rest, "__"),
+            '~' if rest.starts_with("~~") => push_delimited(output, rest, "~~"),
+            '*' | '_' => push_delimited(output, rest, &rest[..1]),
+            '!' if rest.starts_with("![") => push_link(output, &rest[1..]).map(|len| len + 1),
+            '[' => push_link(output, rest),
+            _ => None,
+        };
+        match consumed {
+            Some(len) => rest = &rest[len..],
+            None => {
+                push_escaped(output, &rest[..c.len_utf8()]);
+                rest = &rest[c.len_utf8()..];
+            }
+        }
+    }
+}
+
+/// 转换 `delimiter` 包围的粗体、斜体或删除线，返回消耗的长度，没有结束标记时返回 `None`
+fn push_delimited(output: &mut String, text: &str, delimiter: &str) -> Option<usize> {
+    let inner = &text[delimiter.len()..];
+    if inner.starts_with(char::is_whitespace) {
+        return None;
+    }
+    let end = inner
+        .find(delimiter)
+        .filter(|end| *end > 0 && !inner[..*end].ends_with(char::is_whitespace))?;
+    if delimiter == "_" && text[delimiter.len() + end + 1..].starts_with(char::is_alphanumeric) {
+        // snake_case 中的下划线不是斜体
+        return None;
+    }
+    let lark_delimiter = match delimiter {
+        "__" => "**",
+        "_" => "*",
+        delimiter => delimiter,
+    };
+    output.push_str(lark_delimiter);
+    push_inline(output, &inner[..end]);
+    output.push_str(lark_delimiter);
+    Some(delimiter.len() * 2 + end)
+}
+
+/// 转换 `[文本](链接)`，返回消耗的长度，不是链接时返回 `None`
+fn push_link(output: &mut String, text: &str) -> Option<usize> {
+    let label_end = text.find("](")?;
+    let url_end = text[label_end + 2..].find(')')? + label_end + 2;
+    let label = &text[1..label_end];
+    let url = text[label_end + 2..url_end].trim();
+    let allowed = ["http://", "https://", "lark://"]
+        .iter()
+        .any(|scheme| url.starts_with(scheme));
+    if !allowed || url.contains(char::is_whitespace) {
+        return None;
+    }
+    output.push('[');
+    push_inline(output, label);
+    output.push_str("](");
+    output.push_str(&url.replace('(', "%28").replace(')', "%29"));
+    output.push(')');
+    Some(url_end + 1)
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn template() {
+        let template = CardTemplate::new("t1")
+            .variable("items", vec!["a", "b"])
+            .variables(
+                TemplateVariables::new()
+                    .with("items", json!([]))
+                    .with("n", 1),
+            );
+        assert_eq!(
+            template.to_card(),
+            json!({"type": "template", "data": {
+                "template_id": "t1", "template_variable": {"items": [], "n": 1}
+            }})
+        );
+        assert_eq!(template.template_version(), None);
+
+        let parsed: CardTemplate =
+            serde_json::from_value(json!({"template_id": "t1", "template_version_name": "1.0.0"}))
+                .unwrap();
+        assert_eq!(parsed.template_version(), Some("1.0.0"));
+        assert!(parsed.template_variables().is_empty());
+
+        assert!(TemplateVariables::from_serialize(&[1, 2]).is_err());
+    }
+
+    #[test]
+    fn markdown() {
+        assert_eq!(
+            lark_md("**a *b* c** ~~d~~ __e__ _f_"),
+            "**a *b* c** ~~d~~ **e** *f*"
+        );
+        assert_eq!(
+            lark_md("snake_case_name 2*3 a ** b"),
+            "snake_case_name 2&#42;3 a &#42;&#42; b"
+        );
+        assert_eq!(
+            lark_md("![logo](https://a.com/l.png) [x](javascript:alert(1)) \\*y\\*"),
+            "[logo](https://a.com/l.png) &#91;x&#93;&#40;javascript:alert&#40;1&#41;&#41; &#42;y&#42;"
+        );
+        assert_eq!(
+            lark_md("# Title #\n> quote\n  + item\n```rust\nlet a = *b;\n```"),
+            "**Title**\n&#62; quote\n  - item\nlet a = &#42;b;"
+        );
+        assert_eq!(
+            lark_md("# Learn C#\n## C# ##\n#\n# ###\ntext"),
+            "**Learn C#**\n**C#**\n\n\ntext"
+        );
+        assert_eq!(
+            lark_md("#hashtag <at id=all></at>"),
+            "#hashtag &#60;at id=all&#62;&#60;/at&#62;"
+        );
+        assert_eq!(lark_md("``a`b`` 中文"), "a&#96;b 中文");
+    }
+}
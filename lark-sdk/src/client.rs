@@ -0,0 +1,869 @@
+//! 调用飞书开放平台接口的客户端。
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Instant;
+
+use futures_util::{stream, StreamExt};
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+
+use crate::audit::AuditSink;
+use crate::auth::TokenManager;
+use crate::exchange::{Pipeline, Step};
+use crate::json::DecodeMode;
+use crate::metrics::LatencyTracker;
+use crate::quota::Quota;
+use crate::transport::{HttpResponse, HttpTransport, ReqwestTransport};
+use crate::version::ApiVersion;
+use crate::{
+    is_auth_error_code, json, BodyResponse, DynRequest, LarkError, Method, Request, RequestParts,
+    Response, Result, FEISHU_DOMAIN,
+};
+
+/// 异步客户端，可以被克隆并在多个任务之间共享。
+///
+/// ```no_run
+/// # async fn run(request: impl lark_sdk::Request) -> lark_sdk::Result<()> {
+/// let client = lark_sdk::Client::new();
+/// let response = client.send(&request).await?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct Client {
+    /// 用于流式下载文件
+    http: reqwest::Client,
+    transport: Arc<dyn HttpTransport>,
+    pipeline: Pipeline,
+    decode_mode: DecodeMode,
+    api_versions: Arc<HashMap<String, ApiVersion>>,
+    tenant_key: Option<String>,
+}
+
+impl Client {
+    /// 使用飞书开放平台的接口地址创建客户端
+    pub fn new() -> Self {
+        let http = reqwest::Client::new();
+        Client {
+            transport: Arc::new(ReqwestTransport::new(http.clone())),
+            http,
+            pipeline: Pipeline::new(FEISHU_DOMAIN.to_string()),
+            decode_mode: DecodeMode::Lenient,
+            api_versions: Arc::default(),
+            tenant_key: None,
+        }
+    }
+
+    pub fn builder() -> ClientBuilder {
+        ClientBuilder::new()
+    }
+
+    pub fn domain(&self) -> &str {
+        &self.pipeline.domain
+    }
+
+    /// 配置了应用凭证时为应用的 App ID
+    pub fn app_id(&self) -> Option<&str> {
+        self.pipeline.tokens.as_ref().map(|tokens| tokens.app_id())
+    }
+
+    /// 以 `tenant_key` 租户的身份发送请求的客户端，`auth = "tenant"` 的请求会使用该租户的
+    /// `tenant_access_token`，用于商店应用，见 [`ClientRegistry`](crate::registry::ClientRegistry)
+    pub fn tenant(&self, tenant_key: impl Into<String>) -> Client {
+        Client {
+            tenant_key: Some(tenant_key.into()),
+            ..self.clone()
+        }
+    }
+
+    /// 使用 `mode` 解析响应的客户端，用于单独为某些请求开启或关闭严格模式，见
+    /// [`ClientBuilder::decode_mode`]
+    ///
+    /// ```no_run
+    /// # async fn run(client: lark_sdk::Client, request: impl lark_sdk::Request) -> lark_sdk::Result<()> {
+    /// use lark_sdk::json::DecodeMode;
+    ///
+    /// let response = client.decode_mode(DecodeMode::Strict).send(&request).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn decode_mode(&self, mode: DecodeMode) -> Client {
+        Client {
+            decode_mode: mode,
+            ..self.clone()
+        }
+    }
+
+    /// 通过 [`ClientBuilder::max_api_version`] 为 `service` 设置的最高版本
+    pub fn max_api_version(&self, service: &str) -> Option<ApiVersion> {
+        self.api_versions.get(service).copied()
+    }
+
+    /// 通过 [`tenant`](Client::tenant) 指定的租户
+    pub fn tenant_key(&self) -> Option<&str> {
+        self.tenant_key.as_deref()
+    }
+
+    /// 共享连接池和其他配置，使用另一个应用的凭证和频率限制的客户端
+    pub(crate) fn with_app(&self, tokens: Arc<TokenManager>, quota: Option<Arc<Quota>>) -> Client {
+        Client {
+            pipeline: Pipeline {
+                tokens: Some(tokens),
+                quota,
+                ..self.pipeline.clone()
+            },
+            tenant_key: None,
+            ..self.clone()
+        }
+    }
+
+    pub(crate) fn token_manager(&self) -> Option<&TokenManager> {
+        self.pipeline.tokens.as_deref()
+    }
+
+    /// 发送请求并解析响应，接口返回的错误码需要调用响应的 `into_data` 等方法处理
+    pub async fn send<R: Request + ?Sized>(&self, request: &R) -> Result<R::Target> {
+        self.send_parts(request.to_parts()?).await
+    }
+
+    /// 发送类型擦除的请求，接口返回失败时返回 [`LarkError::Api`](crate::LarkError::Api)，
+    /// 成功时返回完整的响应内容
+    pub async fn send_dyn(&self, request: &dyn DynRequest) -> Result<Value> {
+        let response = self.execute(request.parts()?).await?;
+        json::with_mode(self.decode_mode, || request.parse_response(&response.body))
+    }
+
+    /// 发送由 [`Request::to_parts`] 生成的请求，`T` 为响应类型，如 `BodyResponse<serde_json::Value>`
+    pub async fn send_parts<T: DeserializeOwned>(&self, parts: RequestParts) -> Result<T> {
+        let endpoint = json::endpoint(&parts);
+        let response = self.execute(parts).await?;
+        json::from_response(endpoint, &response, self.decode_mode)
+    }
+
+    /// 发送请求并返回原始的响应内容，用于下载文件等不返回 JSON 的接口，接口返回失败时返回
+    /// [`LarkError::Api`](crate::LarkError::Api)
+    pub async fn send_raw<R: Request + ?Sized>(&self, request: &R) -> Result<Vec<u8>> {
+        let bytes = self.execute(request.to_parts()?).await?.body;
+        if bytes.first() == Some(&b'{') {
+            if let Ok(response) = json::from_slice::<BodyResponse<Value>>(&bytes) {
+                if !response.is_success() {
+                    return Err(LarkError::Api {
+                        code: response.code(),
+                        msg: response.msg().to_string(),
+                        data: response.error_data().cloned(),
+                        log_id: response.log_id().map(String::from),
+                    });
+                }
+            }
+        }
+        Ok(bytes)
+    }
+
+    /// 下载 `url` 的内容并逐块写入 `writer`，返回写入的字节数。
+    ///
+    /// 用于接口返回的临时下载链接，不添加访问凭证，也不经过频率限制
+    pub async fn download<W>(&self, url: &str, writer: &mut W) -> Result<u64>
+    where
+        W: tokio::io::AsyncWrite + Unpin + ?Sized,
+    {
+        use tokio::io::AsyncWriteExt;
+
+        let mut response = self.http.get(url).send().await?.error_for_status()?;
+        let mut written = 0;
+        while let Some(chunk) = response.chunk().await? {
+            writer.write_all(&chunk).await?;
+            written += chunk.len() as u64;
+        }
+        writer.flush().await?;
+        Ok(written)
+    }
+
+    /// 使用 `make_request` 为每个输入生成请求，最多同时发送 `concurrency` 个，返回与 `inputs`
+    /// 顺序一致的结果。
+    ///
+    /// 出现认证错误（见 [`is_auth_error_code`](crate::is_auth_error_code)），包括获取访问凭证失败后，
+    /// 尚未发送的请求不再发送，结果为 [`LarkError::Cancelled`](crate::LarkError::Cancelled)。
+    ///
+    /// ```no_run
+    /// # use lark_sdk::{Client, Request, Response};
+    /// #[derive(serde::Deserialize, Response)]
+    /// pub struct User {
+    ///     pub name: String,
+    /// }
+    ///
+    /// #[derive(Request)]
+    /// #[request(method = "GET", url = "/open-apis/contact/v3/users/:user_id", response = User)]
+    /// pub struct GetUser {
+    ///     #[request(path)]
+    ///     pub user_id: String,
+    /// }
+    ///
+    /// # async fn run(client: Client, user_ids: Vec<String>) {
+    /// let users = client
+    ///     .scatter_gather(user_ids, |user_id| GetUser { user_id }, 8)
+    ///     .await;
+    /// # }
+    /// ```
+    pub async fn scatter_gather<I, R, F>(
+        &self,
+        inputs: I,
+        mut make_request: F,
+        concurrency: usize,
+    ) -> Vec<Result<R::Target>>
+    where
+        I: IntoIterator,
+        R: Request,
+        F: FnMut(I::Item) -> R,
+    {
+        let fatal = AtomicBool::new(false);
+        let fatal = &fatal;
+        stream::iter(inputs)
+            .map(|input| {
+                let request = make_request(input);
+                async move {
+                    if fatal.load(Ordering::Relaxed) {
+                        return Err(LarkError::Cancelled);
+                    }
+                    let result = self.send(&request).await;
+                    let auth_error = match &result {
+                        Ok(response) => is_auth_error_code(response.code()),
+                        Err(err) => err.is_auth_error(),
+                    };
+                    if auth_error {
+                        fatal.store(true, Ordering::Relaxed);
+                    }
+                    result
+                }
+            })
+            .buffered(concurrency.max(1))
+            .collect()
+            .await
+    }
+
+    /// 发送请求，需要时添加应用凭证，见 [`exchange`](crate::exchange)
+    async fn execute(&self, parts: RequestParts) -> Result<HttpResponse> {
+        let mut exchange = self.pipeline.exchange(parts, self.tenant_key.as_deref());
+        let mut step = exchange.start();
+        loop {
+            match step {
+                Step::Send { wait, request } => {
+                    if !wait.is_zero() {
+                        tokio::time::sleep(wait).await;
+                    }
+                    let start = Instant::now();
+                    let result = self.transport.send(request).await;
+                    step = exchange.receive(result, start.elapsed());
+                }
+                Step::Done(result) => return result,
+            }
+        }
+    }
+}
+
+impl Default for Client {
+    fn default() -> Self {
+        Client::new()
+    }
+}
+
+/// 客户端配置。
+#[derive(Debug)]
+pub struct ClientBuilder {
+    domain: String,
+    http: reqwest::ClientBuilder,
+    gzip_threshold: Option<usize>,
+    quota: Option<Arc<Quota>>,
+    latency: Option<Arc<LatencyTracker>>,
+    audit: Option<Arc<dyn AuditSink>>,
+    tokens: Option<Arc<TokenManager>>,
+    decode_mode: DecodeMode,
+    api_versions: HashMap<String, ApiVersion>,
+    proxy: ProxyConfig,
+    #[cfg(feature = "rustls-tls")]
+    root_certificates: Vec<Vec<u8>>,
+    transport: Option<Arc<dyn HttpTransport>>,
+}
+
+impl ClientBuilder {
+    pub fn new() -> Self {
+        ClientBuilder {
+            domain: FEISHU_DOMAIN.to_string(),
+            http: reqwest::Client::builder(),
+            gzip_threshold: None,
+            quota: None,
+            latency: None,
+            audit: None,
+            tokens: None,
+            decode_mode: DecodeMode::Lenient,
+            api_versions: HashMap::new(),
+            proxy: ProxyConfig::default(),
+            #[cfg(feature = "rustls-tls")]
+            root_certificates: Vec::new(),
+            transport: None,
+        }
+    }
+
+    /// 接口地址，默认为 [`FEISHU_DOMAIN`]，海外版使用 [`LARK_DOMAIN`](crate::LARK_DOMAIN)
+    pub fn domain(mut self, domain: impl Into<String>) -> Self {
+        self.domain = domain.into();
+        self
+    }
+
+    pub fn timeout(mut self, timeout: std::time::Duration) -> Self {
+        self.http = self.http.timeout(timeout);
+        self
+    }
+
+    /// 请求体不小于 `min_len` 字节时使用 gzip 压缩，适用于多维表格批量写入等请求体较大的接口，
+    /// 需要网关支持 `Content-Encoding: gzip`。响应总是会自动解压
+    pub fn gzip_request_body(mut self, min_len: usize) -> Self {
+        self.gzip_threshold = Some(min_len);
+        self
+    }
+
+    /// 请求频率限制，见 [`Quota`]
+    pub fn quota(mut self, quota: impl Into<Arc<Quota>>) -> Self {
+        self.quota = Some(quota.into());
+        self
+    }
+
+    /// 统计接口耗时，见 [`LatencyTracker`]
+    pub fn latency_tracker(mut self, tracker: impl Into<Arc<LatencyTracker>>) -> Self {
+        self.latency = Some(tracker.into());
+        self
+    }
+
+    /// 为每个写操作（非 `GET` 请求）生成审计记录，见 [`audit`](crate::audit)
+    pub fn audit_sink(mut self, sink: impl AuditSink + 'static) -> Self {
+        self.audit = Some(Arc::new(sink));
+        self
+    }
+
+    /// 自建应用的凭证，配置后客户端会为 `auth = "tenant"`、`auth = "app"` 的请求自动获取并添加
+    /// `tenant_access_token`、`app_access_token`，凭证过期或失效时重新获取
+    pub fn app_credentials(
+        mut self,
+        app_id: impl Into<String>,
+        app_secret: impl Into<String>,
+    ) -> Self {
+        self.tokens = Some(Arc::new(TokenManager::new(
+            app_id.into(),
+            app_secret.into(),
+        )));
+        self
+    }
+
+    /// 将 `host` 解析到 `addr`，用于私有化部署时把开放平台的域名指向内部网关。
+    ///
+    /// 端口以接口地址中的端口为准，`addr` 中的端口会被忽略。
+    ///
+    /// ```no_run
+    /// # fn main() -> lark_sdk::Result<()> {
+    /// let client = lark_sdk::Client::builder()
+    ///     .domain("https://open.feishu.cn")
+    ///     .resolve("open.feishu.cn", "10.0.0.1:443".parse().unwrap())
+    ///     .add_root_certificate_pem(std::fs::read("/etc/lark/ca.pem").unwrap())
+    ///     .build()?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn resolve(mut self, host: &str, addr: std::net::SocketAddr) -> Self {
+        self.http = self.http.resolve(host, addr);
+        self
+    }
+
+    /// 信任 PEM 格式的 CA 证书，用于内部网关使用自签名证书的私有化部署，证书在 `build` 时解析
+    #[cfg(feature = "rustls-tls")]
+    pub fn add_root_certificate_pem(mut self, pem: impl Into<Vec<u8>>) -> Self {
+        self.root_certificates.push(pem.into());
+        self
+    }
+
+    /// 使用指定的代理，如 `http://proxy.example.com:8080`，`NO_PROXY` 环境变量仍然生效。
+    ///
+    /// 默认使用 `HTTPS_PROXY`、`HTTP_PROXY`、`ALL_PROXY` 环境变量中的代理。
+    pub fn proxy(mut self, url: impl Into<String>) -> Self {
+        self.proxy.url = Some(url.into());
+        self
+    }
+
+    /// 代理的认证信息，没有调用 [`proxy`](ClientBuilder::proxy) 时用于环境变量中的代理
+    pub fn proxy_auth(mut self, username: impl Into<String>, password: impl Into<String>) -> Self {
+        self.proxy.auth = Some((username.into(), password.into()));
+        self
+    }
+
+    /// 不使用代理，忽略代理相关的环境变量
+    pub fn no_proxy(mut self) -> Self {
+        self.proxy.disabled = true;
+        self
+    }
+
+    /// 响应的解析模式，默认为 [`DecodeMode::Lenient`]，测试中可以使用 [`DecodeMode::Strict`]
+    /// 检查响应类型是否缺少字段
+    pub fn decode_mode(mut self, mode: DecodeMode) -> Self {
+        self.decode_mode = mode;
+        self
+    }
+
+    /// [`Client::send_versioned`] 对 `service` 服务使用的最高接口版本，默认使用最新版本，见
+    /// [`version`](crate::version)
+    pub fn max_api_version(mut self, service: impl Into<String>, version: ApiVersion) -> Self {
+        self.api_versions.insert(service.into(), version);
+        self
+    }
+
+    /// 使用其他 HTTP 库发送接口请求，见 [`transport`](crate::transport)。超时、代理、证书等
+    /// 选项只对默认的传输层和 [`Client::download`] 生效
+    pub fn transport(mut self, transport: impl HttpTransport + 'static) -> Self {
+        self.transport = Some(Arc::new(transport));
+        self
+    }
+
+    pub fn build(self) -> Result<Client> {
+        let mut http = self.http;
+        match self.proxy.proxies()? {
+            Some(proxies) => {
+                for proxy in proxies {
+                    http = http.proxy(proxy);
+                }
+            }
+            None => http = http.no_proxy(),
+        }
+        #[cfg(feature = "rustls-tls")]
+        for pem in &self.root_certificates {
+            http = http.add_root_certificate(reqwest::Certificate::from_pem(pem)?);
+        }
+        let http = http.build()?;
+        let transport = match self.transport {
+            Some(transport) => transport,
+            None => Arc::new(ReqwestTransport::new(http.clone())),
+        };
+        Ok(Client {
+            http,
+            transport,
+            pipeline: Pipeline {
+                domain: self.domain,
+                gzip_threshold: self.gzip_threshold,
+                quota: self.quota,
+                latency: self.latency,
+                audit: self.audit,
+                tokens: self.tokens,
+            },
+            decode_mode: self.decode_mode,
+            api_versions: Arc::new(self.api_versions),
+            tenant_key: None,
+        })
+    }
+}
+
+impl Default for ClientBuilder {
+    fn default() -> Self {
+        ClientBuilder::new()
+    }
+}
+
+/// 代理配置
+#[derive(Debug, Default)]
+pub(crate) struct ProxyConfig {
+    pub(crate) disabled: bool,
+    pub(crate) url: Option<String>,
+    pub(crate) auth: Option<(String, String)>,
+}
+
+impl ProxyConfig {
+    /// 需要设置的代理，`None` 表示不使用代理，空列表表示使用 reqwest 默认的环境变量代理
+    pub(crate) fn proxies(&self) -> Result<Option<Vec<reqwest::Proxy>>> {
+        if self.disabled {
+            return Ok(None);
+        }
+        let with_auth = |proxy: reqwest::Proxy| {
+            let proxy = proxy.no_proxy(reqwest::NoProxy::from_env());
+            match &self.auth {
+                Some((username, password)) => proxy.basic_auth(username, password),
+                None => proxy,
+            }
+        };
+        if let Some(url) = &self.url {
+            return Ok(Some(vec![with_auth(reqwest::Proxy::all(url)?)]));
+        }
+        if self.auth.is_none() {
+            return Ok(Some(Vec::new()));
+        }
+        // 需要认证信息时只能自行读取环境变量
+        let env = |names: &[&str]| {
+            names
+                .iter()
+                .find_map(|name| std::env::var(name).ok().filter(|value| !value.is_empty()))
+        };
+        let mut proxies = Vec::new();
+        if let Some(url) = env(&["HTTPS_PROXY", "https_proxy"]) {
+            proxies.push(with_auth(reqwest::Proxy::https(url)?));
+        }
+        if let Some(url) = env(&["HTTP_PROXY", "http_proxy"]) {
+            proxies.push(with_auth(reqwest::Proxy::http(url)?));
+        }
+        if let Some(url) = env(&["ALL_PROXY", "all_proxy"]) {
+            proxies.push(with_auth(reqwest::Proxy::all(url)?));
+        }
+        Ok(Some(proxies))
+    }
+}
+
+pub(crate) fn http_method(method: Method) -> reqwest::Method {
+    match method {
+        Method::Get => reqwest::Method::GET,
+        Method::Post => reqwest::Method::POST,
+        Method::Put => reqwest::Method::PUT,
+        Method::Patch => reqwest::Method::PATCH,
+        Method::Delete => reqwest::Method::DELETE,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Read;
+
+    use super::*;
+    use crate::exchange::gzip;
+    use crate::test_util::{serve, serve_all, Records};
+    use crate::{BoxedRequest, Empty};
+
+    #[derive(lark_sdk::Request)]
+    #[request(method = "POST", url = "/open-apis/demo/:id", empty)]
+    struct Demo {
+        #[request(path)]
+        id: String,
+        #[request(query)]
+        page_size: u32,
+        #[request(body)]
+        name: String,
+    }
+
+    #[derive(lark_sdk::Request)]
+    #[request(
+        method = "POST",
+        url = "/open-apis/upload",
+        empty,
+        content_type = "multipart/form-data; boundary=x"
+    )]
+    struct Upload {}
+
+    #[derive(lark_sdk::Request)]
+    #[request(method = "GET", url = "/open-apis/demo", auth = "tenant", empty)]
+    struct TenantDemo {}
+
+    #[tokio::test]
+    async fn send_request() {
+        let (address, server) = serve(r#"{"code":0,"msg":"ok","data":{}}"#);
+        let client = Client::builder().domain(address).build().unwrap();
+        let request = Demo {
+            id: "a/b".to_string(),
+            page_size: 10,
+            name: "lark".to_string(),
+        };
+        let response = client.send(&request).await.unwrap();
+        assert_eq!(response.into_data().unwrap(), Empty);
+
+        let request = server.join().unwrap();
+        assert!(request.starts_with("POST /open-apis/demo/a%2Fb?page_size=10 HTTP/1.1\r\n"));
+        assert!(request
+            .to_ascii_lowercase()
+            .contains("content-type: application/json; charset=utf-8\r\n"));
+        assert!(request.ends_with(r#"{"name":"lark"}"#));
+    }
+
+    #[tokio::test]
+    async fn send_parts() {
+        let request = Demo {
+            id: "1".to_string(),
+            page_size: 20,
+            name: "lark".to_string(),
+        };
+        let queued = serde_json::to_string(&request.to_parts().unwrap()).unwrap();
+        let parts: RequestParts = serde_json::from_str(&queued).unwrap();
+        assert_eq!(parts.method, Method::Post);
+
+        let (address, server) = serve(r#"{"code":0,"msg":"ok","data":{"id":"1"}}"#);
+        let client = Client::builder().domain(address).build().unwrap();
+        let response: BodyResponse<Value> = client.send_parts(parts).await.unwrap();
+        assert_eq!(response.into_data().unwrap()["id"], "1");
+        let request = server.join().unwrap();
+        assert!(request.starts_with("POST /open-apis/demo/1?page_size=20 HTTP/1.1\r\n"));
+        assert!(request.ends_with(r#"{"name":"lark"}"#));
+    }
+
+    #[tokio::test]
+    async fn decode_error_context() {
+        let (address, server) = serve(r#"{"code":0,"msg":"ok","data":{"id":1}}"#);
+        let client = Client::builder().domain(address).build().unwrap();
+        let parts = Demo {
+            id: "1".to_string(),
+            page_size: 20,
+            name: "lark".to_string(),
+        }
+        .to_parts()
+        .unwrap();
+        let err = client
+            .send_parts::<BodyResponse<Vec<String>>>(parts)
+            .await
+            .unwrap_err();
+        server.join().unwrap();
+        match &err {
+            LarkError::Decode {
+                endpoint,
+                status,
+                body,
+                ..
+            } => {
+                assert_eq!(endpoint, "POST /open-apis/demo/:id");
+                assert_eq!(*status, 200);
+                assert_eq!(body, r#"{"code":0,"msg":"ok","data":{"id":1}}"#);
+            }
+            other => panic!("unexpected error {:?}", other),
+        }
+        assert!(err
+            .to_string()
+            .starts_with("failed to decode response of POST /open-apis/demo/:id: invalid type"));
+    }
+
+    #[tokio::test]
+    async fn strict_decode_mode() {
+        let body = r#"{"code":0,"msg":"ok","data":{"id":"1","name":"lark"}}"#;
+        let (address, server) = serve_all(vec![body, body]);
+        let client = Client::builder()
+            .domain(address)
+            .decode_mode(DecodeMode::Strict)
+            .build()
+            .unwrap();
+        let parts = Demo {
+            id: "1".to_string(),
+            page_size: 20,
+            name: "lark".to_string(),
+        }
+        .to_parts()
+        .unwrap();
+
+        #[derive(Debug, serde::Deserialize, lark_sdk::Body)]
+        struct Item {
+            id: String,
+        }
+
+        let err = client
+            .send_parts::<BodyResponse<Item>>(parts.clone())
+            .await
+            .unwrap_err();
+        assert!(matches!(err, LarkError::Decode { .. }));
+        assert!(err.to_string().contains("unknown fields: name"));
+        let response: BodyResponse<Item> = client
+            .decode_mode(DecodeMode::Lenient)
+            .send_parts(parts)
+            .await
+            .unwrap();
+        assert_eq!(response.into_data().unwrap().id, "1");
+        server.join().unwrap();
+    }
+
+    #[tokio::test]
+    async fn gzip_request_body() {
+        let (address, server) = serve_all(vec![
+            r#"{"code":0,"msg":"ok","data":{}}"#,
+            r#"{"code":0,"msg":"ok","data":{}}"#,
+        ]);
+        let client = Client::builder()
+            .domain(address)
+            .gzip_request_body(32)
+            .build()
+            .unwrap();
+        for name in ["lark", "lark".repeat(10).as_str()] {
+            let request = Demo {
+                id: "1".to_string(),
+                page_size: 10,
+                name: name.to_string(),
+            };
+            client.send(&request).await.unwrap();
+        }
+
+        let requests = server.join().unwrap();
+        let small = requests[0].to_ascii_lowercase();
+        assert!(small.contains("accept-encoding: gzip"));
+        assert!(!small.contains("content-encoding"));
+        assert!(requests[1]
+            .to_ascii_lowercase()
+            .contains("content-encoding: gzip\r\n"));
+
+        let mut decoded = String::new();
+        flate2::read::GzDecoder::new(&gzip(br#"{"name":"lark"}"#)[..])
+            .read_to_string(&mut decoded)
+            .unwrap();
+        assert_eq!(decoded, r#"{"name":"lark"}"#);
+    }
+
+    #[tokio::test]
+    async fn resolve() {
+        let (address, server) = serve(r#"{"code":0,"msg":"ok","data":{}}"#);
+        let port = address.rsplit(':').next().unwrap();
+        let client = Client::builder()
+            .domain(format!("http://open.feishu.cn:{}", port))
+            .resolve("open.feishu.cn", "127.0.0.1:0".parse().unwrap())
+            .build()
+            .unwrap();
+        client.send(&Upload {}).await.unwrap();
+        assert!(server
+            .join()
+            .unwrap()
+            .to_ascii_lowercase()
+            .contains(&format!("host: open.feishu.cn:{}\r\n", port)));
+    }
+
+    #[tokio::test]
+    async fn proxy() {
+        let (address, server) = serve(r#"{"code":0,"msg":"ok","data":{}}"#);
+        let client = Client::builder()
+            .domain("http://open.feishu.cn")
+            .proxy(address)
+            .proxy_auth("user", "pass")
+            .build()
+            .unwrap();
+        client.send(&Upload {}).await.unwrap();
+        let request = server.join().unwrap();
+        assert!(request.starts_with("POST http://open.feishu.cn/open-apis/upload HTTP/1.1\r\n"));
+        assert!(request
+            .to_ascii_lowercase()
+            .contains("proxy-authorization: basic dxnlcjpwyxnz\r\n"));
+
+        let proxy = ProxyConfig {
+            disabled: true,
+            ..ProxyConfig::default()
+        };
+        assert!(proxy.proxies().unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn scatter_gather() {
+        let (address, server) = serve_all(vec![
+            r#"{"code":0,"msg":"ok","data":{}}"#,
+            r#"{"code":0,"msg":"ok","data":{}}"#,
+            r#"{"code":0,"msg":"ok","data":{}}"#,
+        ]);
+        let client = Client::builder().domain(address).build().unwrap();
+        let results = client
+            .scatter_gather(
+                1..=3,
+                |id| Demo {
+                    id: id.to_string(),
+                    page_size: 10,
+                    name: "lark".to_string(),
+                },
+                2,
+            )
+            .await;
+        assert_eq!(results.len(), 3);
+        assert!(results.iter().all(|result| result.is_ok()));
+        let mut requests = server.join().unwrap();
+        requests.sort();
+        assert!(requests[2].starts_with("POST /open-apis/demo/3?"));
+
+        let (address, server) = serve_all(vec![
+            r#"{"code":99991663,"msg":"invalid tenant_access_token"}"#,
+        ]);
+        let client = Client::builder().domain(address).build().unwrap();
+        let results = client.scatter_gather(1..=3, |_| Upload {}, 1).await;
+        assert_eq!(results[0].as_ref().unwrap().code, 99991663);
+        assert!(matches!(results[1], Err(LarkError::Cancelled)));
+        assert!(matches!(results[2], Err(LarkError::Cancelled)));
+        assert_eq!(server.join().unwrap().len(), 1);
+
+        // 获取访问凭证失败
+        let (address, server) = serve_all(vec![r#"{"code":10014,"msg":"app secret invalid"}"#]);
+        let client = Client::builder()
+            .domain(address)
+            .app_credentials("cli_1", "wrong")
+            .build()
+            .unwrap();
+        let results = client.scatter_gather(1..=3, |_| TenantDemo {}, 1).await;
+        assert!(matches!(
+            results[0],
+            Err(LarkError::Api { code: 10014, .. })
+        ));
+        assert!(matches!(results[1], Err(LarkError::Cancelled)));
+        assert!(matches!(results[2], Err(LarkError::Cancelled)));
+        let requests = server.join().unwrap();
+        assert!(requests[0].starts_with("POST /open-apis/auth/v3/tenant_access_token/internal"));
+    }
+
+    #[tokio::test]
+    async fn latency_tracker() {
+        let (address, _) = serve(r#"{"code":0,"msg":"ok","data":{}}"#);
+        let tracker = Arc::new(LatencyTracker::new());
+        let client = Client::builder()
+            .domain(address)
+            .latency_tracker(tracker.clone())
+            .build()
+            .unwrap();
+        let request = Demo {
+            id: "1".to_string(),
+            page_size: 10,
+            name: "lark".to_string(),
+        };
+        client.send(&request).await.unwrap();
+        assert_eq!(tracker.snapshot()["POST /open-apis/demo/:id"].count, 1);
+    }
+
+    #[tokio::test]
+    async fn audit_sink() {
+        let (address, _) = serve(r#"{"code":0,"msg":"ok","data":{}}"#);
+        let records = Arc::new(Records::default());
+        let client = Client::builder()
+            .domain(address)
+            .audit_sink(records.clone())
+            .build()
+            .unwrap();
+        let request = Demo {
+            id: "1".to_string(),
+            page_size: 10,
+            name: "lark".to_string(),
+        };
+        client.tenant("t1").send(&request).await.unwrap();
+        let records = records.0.lock().unwrap();
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].endpoint, "/open-apis/demo/:id");
+        assert_eq!(records[0].url, "/open-apis/demo/1");
+        assert_eq!(records[0].tenant_key.as_deref(), Some("t1"));
+        assert_eq!(records[0].status, Some(200));
+        assert_eq!(records[0].body_sha256.as_ref().map(String::len), Some(64));
+    }
+
+    #[tokio::test]
+    async fn send_dyn() {
+        let (address, server) = serve(r#"{"code":0,"msg":"ok","data":{}}"#);
+        let client = Client::builder().domain(address).build().unwrap();
+        let request: BoxedRequest = Box::new(Upload {});
+        let response = client.send_dyn(request.as_ref()).await.unwrap();
+        assert_eq!(response["msg"], "ok");
+        assert!(server
+            .join()
+            .unwrap()
+            .to_ascii_lowercase()
+            .contains("content-type: multipart/form-data; boundary=x\r\n"));
+
+        let (address, _) = serve(r#"{"code":99991663,"msg":"invalid token"}"#);
+        let client = Client::builder().domain(address).build().unwrap();
+        let err = client.send_dyn(&Upload {}).await.unwrap_err();
+        assert!(matches!(err, LarkError::Api { code: 99991663, .. }));
+    }
+
+    #[tokio::test]
+    async fn send_raw() {
+        let (address, _) = serve("binary");
+        let client = Client::builder().domain(address).build().unwrap();
+        assert_eq!(client.send_raw(&Upload {}).await.unwrap(), b"binary");
+
+        let (address, _) = serve(r#"{"code":234003,"msg":"file not found"}"#);
+        let client = Client::builder().domain(address).build().unwrap();
+        let err = client.send_raw(&Upload {}).await.unwrap_err();
+        assert!(matches!(err, LarkError::Api { code: 234003, .. }));
+    }
+}
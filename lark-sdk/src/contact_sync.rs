@@ -0,0 +1,508 @@
+//! 通讯录同步。
+//!
+//! [`ContactSync`] 先全量拉取部门和用户，之后根据通讯录事件增量更新，所有变化以 [`ContactChange`]
+//! 发送给调用方。同步的进度保存在 [`CheckpointStore`] 中，全量同步中断后从上次完成的分页继续。
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use futures_util::future::BoxFuture;
+use serde::{Deserialize, Serialize};
+use tokio::sync::mpsc;
+
+use crate::enums::DepartmentIdType;
+use crate::event::contact::{
+    ContactDepartment, ContactUser, DepartmentCreatedEvent, DepartmentDeletedEvent,
+    DepartmentUpdatedEvent, UserCreatedEvent, UserDeletedEvent, UserUpdatedEvent,
+};
+use crate::event::{EventType, RawEvent};
+use crate::{Client, ListData, Result};
+
+/// 根部门的 ID
+const ROOT_DEPARTMENT: &str = "0";
+
+/// 全量同步时每页的数量
+const PAGE_SIZE: u32 = 50;
+
+/// 通讯录的一次变化
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ContactChange {
+    /// 新增或更新的用户，全量同步时每个用户所在的每个部门各出现一次
+    UserUpserted(ContactUser),
+    UserDeleted(ContactUser),
+    /// 新增或更新的部门
+    DepartmentUpserted(ContactDepartment),
+    DepartmentDeleted(ContactDepartment),
+}
+
+/// 同步所处的阶段
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SyncPhase {
+    /// 全量拉取部门
+    #[default]
+    Departments,
+    /// 全量拉取各个部门的用户
+    Users,
+    /// 全量同步已完成，只处理事件
+    Incremental,
+}
+
+/// 同步的进度
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Checkpoint {
+    pub phase: SyncPhase,
+    /// 全量同步开始的时间，毫秒时间戳，早于该时间的事件已经包含在全量数据中，不再处理
+    pub started_at: i64,
+    /// 全量同步中尚未拉取用户的部门，`open_department_id`
+    #[serde(default)]
+    pub pending_departments: Vec<String>,
+    /// 当前分页的 `page_token`
+    #[serde(default)]
+    pub page_token: Option<String>,
+    /// 最后处理的事件的时间，毫秒时间戳
+    #[serde(default)]
+    pub last_event_time: Option<i64>,
+}
+
+impl Checkpoint {
+    fn start() -> Self {
+        Checkpoint {
+            phase: SyncPhase::Departments,
+            started_at: now_millis(),
+            pending_departments: vec![ROOT_DEPARTMENT.to_string()],
+            page_token: None,
+            last_event_time: None,
+        }
+    }
+}
+
+/// 同步进度的存储，`key` 区分同一存储中的多个同步，如不同的应用或租户。
+///
+/// 进度在每一页完成后保存，多个进程同步同一个通讯录时应使用外部存储。保存失败时同步返回该错误，
+/// 避免进度丢失后下次同步悄悄地重新全量拉取。
+pub trait CheckpointStore: Send + Sync {
+    /// 读取 `key` 的进度，没有时返回 `None`
+    fn load<'a>(&'a self, key: &'a str) -> BoxFuture<'a, Result<Option<Checkpoint>>>;
+
+    /// 保存 `key` 的进度，返回时进度已经持久化
+    fn save<'a>(&'a self, key: &'a str, checkpoint: &'a Checkpoint) -> BoxFuture<'a, Result<()>>;
+}
+
+/// 保存在内存中的 [`CheckpointStore`]
+#[derive(Debug, Default)]
+pub struct MemoryCheckpointStore {
+    checkpoints: Mutex<HashMap<String, Checkpoint>>,
+}
+
+impl MemoryCheckpointStore {
+    pub fn new() -> Self {
+        MemoryCheckpointStore::default()
+    }
+}
+
+impl CheckpointStore for MemoryCheckpointStore {
+    fn load<'a>(&'a self, key: &'a str) -> BoxFuture<'a, Result<Option<Checkpoint>>> {
+        let checkpoint = self.checkpoints.lock().unwrap().get(key).cloned();
+        Box::pin(async { Ok(checkpoint) })
+    }
+
+    fn save<'a>(&'a self, key: &'a str, checkpoint: &'a Checkpoint) -> BoxFuture<'a, Result<()>> {
+        self.checkpoints
+            .lock()
+            .unwrap()
+            .insert(key.to_string(), checkpoint.clone());
+        Box::pin(async { Ok(()) })
+    }
+}
+
+/// 通讯录全量同步和增量同步。
+///
+/// 请求使用 `tenant_access_token`，客户端需要配置应用凭证，商店应用使用 [`Client::tenant`]。
+///
+/// ```no_run
+/// use std::sync::Arc;
+/// use lark_sdk::contact_sync::{ContactSync, MemoryCheckpointStore};
+/// use lark_sdk::event::EventDispatcher;
+///
+/// # async fn run(client: lark_sdk::Client) -> lark_sdk::Result<()> {
+/// let (sync, mut changes) =
+///     ContactSync::new(client, "cli_xxx", MemoryCheckpointStore::new()).await?;
+/// let sync = Arc::new(sync);
+///
+/// tokio::spawn(async move {
+///     while let Some(change) = changes.recv().await {
+///         println!("{:?}", change);
+///     }
+/// });
+///
+/// let events = sync.clone();
+/// let dispatcher = EventDispatcher::new().middleware(move |event| {
+///     let sync = events.clone();
+///     async move { sync.apply(&event).await.map(|_| ()) }
+/// });
+/// sync.full_sync().await?;
+/// # Ok(())
+/// # }
+/// ```
+pub struct ContactSync {
+    client: Client,
+    key: String,
+    store: Arc<dyn CheckpointStore>,
+    checkpoint: Mutex<Checkpoint>,
+    /// 同一时间只保存一次进度，保存成功后才更新 `checkpoint`
+    saving: tokio::sync::Mutex<()>,
+    changes: mpsc::Sender<ContactChange>,
+}
+
+impl ContactSync {
+    /// 创建同步并读取保存的进度，`key` 为进度在存储中的键。返回的接收端需要被持续消费，否则同步会等待
+    pub async fn new(
+        client: Client,
+        key: impl Into<String>,
+        store: impl CheckpointStore + 'static,
+    ) -> Result<(Self, mpsc::Receiver<ContactChange>)> {
+        let key = key.into();
+        let checkpoint = store.load(&key).await?.unwrap_or_else(Checkpoint::start);
+        let (changes, receiver) = mpsc::channel(256);
+        let sync = ContactSync {
+            client,
+            key,
+            store: Arc::new(store),
+            checkpoint: Mutex::new(checkpoint),
+            saving: tokio::sync::Mutex::new(()),
+            changes,
+        };
+        Ok((sync, receiver))
+    }
+
+    pub fn store(&self) -> &dyn CheckpointStore {
+        &*self.store
+    }
+
+    /// 当前的进度
+    pub fn checkpoint(&self) -> Checkpoint {
+        self.checkpoint.lock().unwrap().clone()
+    }
+
+    /// 执行全量同步，从上次保存的进度继续，全量同步已经完成时直接返回
+    pub async fn full_sync(&self) -> Result<()> {
+        loop {
+            let checkpoint = self.checkpoint();
+            match checkpoint.phase {
+                SyncPhase::Departments => self.sync_departments(checkpoint.page_token).await?,
+                SyncPhase::Users => match checkpoint.pending_departments.first() {
+                    Some(department_id) => {
+                        self.sync_users(department_id.clone(), checkpoint.page_token)
+                            .await?
+                    }
+                    None => {
+                        self.update(|checkpoint| checkpoint.phase = SyncPhase::Incremental)
+                            .await?
+                    }
+                },
+                SyncPhase::Incremental => return Ok(()),
+            }
+        }
+    }
+
+    /// 丢弃进度，重新执行全量同步
+    pub async fn resync(&self) -> Result<()> {
+        self.update(|checkpoint| {
+            *checkpoint = Checkpoint {
+                last_event_time: checkpoint.last_event_time,
+                ..Checkpoint::start()
+            }
+        })
+        .await?;
+        self.full_sync().await
+    }
+
+    /// 处理通讯录事件，返回事件是否产生了变化。不是通讯录事件或事件早于全量同步开始的时间时返回 `false`
+    pub async fn apply(&self, event: &RawEvent) -> Result<bool> {
+        let create_time = event.header.create_time.parse::<i64>().ok();
+        if matches!(create_time, Some(time) if time < self.checkpoint().started_at) {
+            return Ok(false);
+        }
+        let change = match event.event_type() {
+            UserCreatedEvent::EVENT_TYPE => {
+                ContactChange::UserUpserted(event.parse::<UserCreatedEvent>()?.event.object)
+            }
+            UserUpdatedEvent::EVENT_TYPE => {
+                ContactChange::UserUpserted(event.parse::<UserUpdatedEvent>()?.event.object)
+            }
+            UserDeletedEvent::EVENT_TYPE => {
+                ContactChange::UserDeleted(event.parse::<UserDeletedEvent>()?.event.object)
+            }
+            DepartmentCreatedEvent::EVENT_TYPE => ContactChange::DepartmentUpserted(
+                event.parse::<DepartmentCreatedEvent>()?.event.object,
+            ),
+            DepartmentUpdatedEvent::EVENT_TYPE => ContactChange::DepartmentUpserted(
+                event.parse::<DepartmentUpdatedEvent>()?.event.object,
+            ),
+            DepartmentDeletedEvent::EVENT_TYPE => ContactChange::DepartmentDeleted(
+                event.parse::<DepartmentDeletedEvent>()?.event.object,
+            ),
+            _ => return Ok(false),
+        };
+        self.emit(change).await;
+        if let Some(time) = create_time {
+            self.update(|checkpoint| {
+                checkpoint.last_event_time = checkpoint.last_event_time.max(Some(time))
+            })
+            .await?;
+        }
+        Ok(true)
+    }
+
+    async fn sync_departments(&self, page_token: Option<String>) -> Result<()> {
+        let request = ListChildDepartments {
+            department_id: ROOT_DEPARTMENT.to_string(),
+            department_id_type: DepartmentIdType::OpenDepartmentId,
+            fetch_child: true,
+            page_size: PAGE_SIZE,
+            page_token,
+        };
+        let data = self.client.send(&request).await?.into_data()?;
+        let mut department_ids = Vec::with_capacity(data.items.len());
+        for department in data.items {
+            department_ids.extend(department.open_department_id.clone());
+            self.emit(ContactChange::DepartmentUpserted(department))
+                .await;
+        }
+        let page_token = data.page_token.filter(|_| data.has_more);
+        self.update(|checkpoint| {
+            checkpoint.pending_departments.extend(department_ids);
+            if page_token.is_none() {
+                checkpoint.phase = SyncPhase::Users;
+            }
+            checkpoint.page_token = page_token;
+        })
+        .await
+    }
+
+    async fn sync_users(&self, department_id: String, page_token: Option<String>) -> Result<()> {
+        let request = FindUsersByDepartment {
+            department_id,
+            department_id_type: DepartmentIdType::OpenDepartmentId,
+            page_size: PAGE_SIZE,
+            page_token,
+        };
+        let data = self.client.send(&request).await?.into_data()?;
+        for user in data.items {
+            self.emit(ContactChange::UserUpserted(user)).await;
+        }
+        let page_token = data.page_token.filter(|_| data.has_more);
+        self.update(|checkpoint| {
+            if page_token.is_none() {
+                checkpoint.pending_departments.remove(0);
+            }
+            checkpoint.page_token = page_token;
+        })
+        .await
+    }
+
+    /// 发送变化，接收端已经关闭时丢弃
+    async fn emit(&self, change: ContactChange) {
+        let _ = self.changes.send(change).await;
+    }
+
+    /// 修改并保存进度，保存失败时进度不变
+    async fn update(&self, f: impl FnOnce(&mut Checkpoint)) -> Result<()> {
+        let _guard = self.saving.lock().await;
+        let mut checkpoint = self.checkpoint();
+        f(&mut checkpoint);
+        self.store.save(&self.key, &checkpoint).await?;
+        *self.checkpoint.lock().unwrap() = checkpoint;
+        Ok(())
+    }
+}
+
+/// 获取子部门列表
+#[derive(lark_sdk::Request)]
+#[request(
+    method = "GET",
+    url = "/open-apis/contact/v3/departments/:department_id/children",
+    auth = "tenant",
+    validate,
+    response = ListData<ContactDepartment>
+)]
+pub(crate) struct ListChildDepartments {
+    #[request(path)]
+    department_id: String,
+    #[request(query)]
+    department_id_type: DepartmentIdType,
+    #[request(query)]
+    fetch_child: bool,
+    #[request(query)]
+    page_size: u32,
+    #[request(query, page_token)]
+    page_token: Option<String>,
+}
+
+/// 获取部门直属用户列表
+#[derive(lark_sdk::Request)]
+#[request(
+    method = "GET",
+    url = "/open-apis/contact/v3/users/find_by_department",
+    auth = "tenant",
+    response = ListData<ContactUser>
+)]
+pub(crate) struct FindUsersByDepartment {
+    #[request(query)]
+    department_id: String,
+    #[request(query)]
+    department_id_type: DepartmentIdType,
+    #[request(query)]
+    page_size: u32,
+    #[request(query, page_token)]
+    page_token: Option<String>,
+}
+
+fn now_millis() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_millis() as i64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::event::EventHeader;
+    use crate::test_util::serve_all;
+    use crate::LarkError;
+
+    fn client(address: String) -> Client {
+        Client::builder().domain(address).build().unwrap()
+    }
+
+    fn raw_event(event_type: &str, create_time: &str, event: serde_json::Value) -> RawEvent {
+        let header = EventHeader {
+            event_id: "e1".to_string(),
+            event_type: event_type.to_string(),
+            create_time: create_time.to_string(),
+            token: "token".to_string(),
+            app_id: "cli_1".to_string(),
+            tenant_key: "t1".to_string(),
+        };
+        let payload = serde_json::json!({ "schema": "2.0", "header": header, "event": event });
+        RawEvent { header, payload }
+    }
+
+    #[tokio::test]
+    async fn full_then_incremental() {
+        let (address, handle) = serve_all(vec![
+            r#"{"code":0,"msg":"ok","data":{"items":[{"open_department_id":"od-1","name":"研发部"}],"has_more":false}}"#,
+            r#"{"code":0,"msg":"ok","data":{"items":[{"open_id":"ou_1"}],"has_more":false}}"#,
+            r#"{"code":0,"msg":"ok","data":{"items":[{"open_id":"ou_2"}],"page_token":"p2","has_more":true}}"#,
+            r#"{"code":0,"msg":"ok","data":{"items":[{"open_id":"ou_3"}],"has_more":false}}"#,
+        ]);
+        let (sync, mut changes) =
+            ContactSync::new(client(address), "cli_1", MemoryCheckpointStore::new())
+                .await
+                .unwrap();
+        sync.full_sync().await.unwrap();
+
+        let requests = handle.join().unwrap();
+        assert!(requests[0].starts_with("GET /open-apis/contact/v3/departments/0/children?"));
+        assert!(requests[0].contains("fetch_child=true"));
+        assert!(requests[1].contains("department_id=0&"));
+        assert!(requests[2].contains("department_id=od-1&"));
+        assert!(requests[3].contains("page_token=p2"));
+
+        let mut received = Vec::new();
+        while let Ok(change) = changes.try_recv() {
+            received.push(change);
+        }
+        assert_eq!(received.len(), 4);
+        assert!(
+            matches!(&received[0], ContactChange::DepartmentUpserted(department) if department.name.as_deref() == Some("研发部"))
+        );
+        assert!(
+            matches!(&received[3], ContactChange::UserUpserted(user) if user.open_id.as_deref() == Some("ou_3"))
+        );
+
+        let checkpoint = sync.store().load("cli_1").await.unwrap().unwrap();
+        assert_eq!(checkpoint.phase, SyncPhase::Incremental);
+        assert!(checkpoint.pending_departments.is_empty());
+
+        let stale = raw_event(
+            "contact.user.deleted_v3",
+            "1",
+            serde_json::json!({"object": {"open_id": "ou_1"}}),
+        );
+        assert!(!sync.apply(&stale).await.unwrap());
+        let deleted = raw_event(
+            "contact.user.deleted_v3",
+            "9999999999999",
+            serde_json::json!({"object": {"open_id": "ou_1"}}),
+        );
+        assert!(sync.apply(&deleted).await.unwrap());
+        assert!(
+            matches!(changes.try_recv().unwrap(), ContactChange::UserDeleted(user) if user.open_id.as_deref() == Some("ou_1"))
+        );
+        assert_eq!(
+            sync.store()
+                .load("cli_1")
+                .await
+                .unwrap()
+                .unwrap()
+                .last_event_time,
+            Some(9999999999999)
+        );
+    }
+
+    #[tokio::test]
+    async fn resume_from_checkpoint() {
+        let (address, handle) = serve_all(vec![
+            r#"{"code":0,"msg":"ok","data":{"items":[{"open_id":"ou_3"}],"has_more":false}}"#,
+        ]);
+        let store = MemoryCheckpointStore::new();
+        let checkpoint = Checkpoint {
+            phase: SyncPhase::Users,
+            started_at: 1,
+            pending_departments: vec!["od-1".to_string()],
+            page_token: Some("p2".to_string()),
+            last_event_time: None,
+        };
+        store.save("cli_1", &checkpoint).await.unwrap();
+        let (sync, _changes) = ContactSync::new(client(address), "cli_1", store)
+            .await
+            .unwrap();
+        sync.full_sync().await.unwrap();
+
+        let requests = handle.join().unwrap();
+        assert_eq!(requests.len(), 1);
+        assert!(requests[0].contains("department_id=od-1&"));
+        assert!(requests[0].contains("page_token=p2"));
+        assert_eq!(sync.checkpoint().phase, SyncPhase::Incremental);
+    }
+
+    /// 保存进度失败的存储
+    struct FailingStore;
+
+    impl CheckpointStore for FailingStore {
+        fn load<'a>(&'a self, _: &'a str) -> BoxFuture<'a, Result<Option<Checkpoint>>> {
+            Box::pin(async { Ok(None) })
+        }
+
+        fn save<'a>(&'a self, _: &'a str, _: &'a Checkpoint) -> BoxFuture<'a, Result<()>> {
+            Box::pin(async { Err(LarkError::InvalidRequest("save failed".to_string())) })
+        }
+    }
+
+    #[tokio::test]
+    async fn save_error() {
+        let (address, handle) = serve_all(vec![
+            r#"{"code":0,"msg":"ok","data":{"items":[],"page_token":"p2","has_more":true}}"#,
+        ]);
+        let (sync, _changes) = ContactSync::new(client(address), "cli_1", FailingStore)
+            .await
+            .unwrap();
+        let err = sync.full_sync().await.unwrap_err();
+        assert!(matches!(err, LarkError::InvalidRequest(msg) if msg == "save failed"));
+        assert_eq!(handle.join().unwrap().len(), 1);
+        assert_eq!(sync.checkpoint().page_token, None);
+    }
+}
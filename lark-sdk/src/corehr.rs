@@ -0,0 +1,264 @@
+//! 飞书人事（CoreHR）的只读接口，需要开启 `corehr` 特性。
+//!
+//! 人事系统中的员工以雇佣 ID（`employment_id`）标识，查询时可以用 `user_id_type` 指定传入和返回的 ID
+//! 类型，使用 `open_id` 等通讯录 ID 时可以直接与消息、通讯录中的用户关联：
+//!
+//! ```no_run
+//! use lark_sdk::corehr::BatchGetEmployees;
+//! use lark_sdk::id::OpenId;
+//!
+//! # async fn run(client: lark_sdk::Client) -> lark_sdk::Result<()> {
+//! let request = BatchGetEmployees::by_ids(&[OpenId::new("ou_xxx")]);
+//! for employee in client.send(&request).await?.into_data()?.items {
+//!     println!("{} {:?}", employee.employment_id, employee.department_id);
+//! }
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! 各版本的接口分别在 [`v1`]、[`v2`] 模块中，常用的请求也可以直接从本模块引用。同时提供两个版本的
+//! 查询实现了 [`VersionedRequest`]，如 [`GetDepartments`]，通过 [`Client::send_versioned`] 发送时
+//! 默认使用 v2，可以用 [`ClientBuilder::max_api_version`](crate::ClientBuilder::max_api_version)
+//! 限制为 v1。
+
+pub mod v1;
+pub mod v2;
+
+#[cfg(feature = "async")]
+use futures_util::future::BoxFuture;
+use serde::Deserialize;
+
+#[cfg(feature = "async")]
+use crate::enums::{DepartmentIdType, UserIdType};
+#[cfg(feature = "async")]
+use crate::version::{ApiVersion, VersionedRequest};
+#[cfg(feature = "async")]
+use crate::{Client, Result};
+pub use v1::{
+    Department, DepartmentDetail, GetDepartment, GetJobData, Hiberarchy, JobData, JobDataDetail,
+    ListDepartments, ListJobDatas,
+};
+pub use v2::{BatchGetEmployees, Employee, Employees, BATCH_GET_EMPLOYEES_LIMIT};
+
+/// 多语言文本中的一项
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct I18nText {
+    /// 语言，如 `zh-CN`、`en-US`
+    pub lang: String,
+    pub value: String,
+}
+
+/// 枚举字段的值
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct EnumValue {
+    /// 枚举值的 API 名称，如 `hired`
+    pub enum_name: String,
+    #[serde(default)]
+    pub display: Vec<I18nText>,
+}
+
+/// 员工的个人信息
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct PersonInfo {
+    pub person_id: String,
+    #[serde(default)]
+    pub legal_name: Option<String>,
+    #[serde(default)]
+    pub preferred_name: Option<String>,
+}
+
+/// 与版本无关的部门信息
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DepartmentInfo {
+    pub id: String,
+    pub name: Vec<I18nText>,
+    pub parent_id: Option<String>,
+    /// 部门负责人的雇佣 ID
+    pub manager: Option<String>,
+    pub active: bool,
+    pub code: Option<String>,
+}
+
+impl From<v1::Department> for DepartmentInfo {
+    fn from(department: v1::Department) -> Self {
+        let hiberarchy = department.hiberarchy_common;
+        DepartmentInfo {
+            id: department.id,
+            name: hiberarchy.name,
+            parent_id: hiberarchy.parent_id,
+            manager: department.manager,
+            active: hiberarchy.active,
+            code: hiberarchy.code,
+        }
+    }
+}
+
+impl From<v2::Department> for DepartmentInfo {
+    fn from(department: v2::Department) -> Self {
+        DepartmentInfo {
+            id: department.id,
+            name: department.department_name,
+            parent_id: department.parent_department_id,
+            manager: department.manager,
+            active: department.active,
+            code: department.code,
+        }
+    }
+}
+
+/// 按 ID 查询部门，v2 使用 [`v2::BatchGetDepartments`]，v1 使用 [`v1::ListDepartments`]
+#[cfg(feature = "async")]
+#[derive(Debug, Clone, Default)]
+pub struct GetDepartments {
+    pub department_ids: Vec<String>,
+    /// 负责人 ID 的类型
+    pub user_id_type: Option<UserIdType>,
+    pub department_id_type: Option<DepartmentIdType>,
+}
+
+#[cfg(feature = "async")]
+impl GetDepartments {
+    async fn send_v1(&self, client: &Client) -> Result<Vec<DepartmentInfo>> {
+        let mut departments = Vec::with_capacity(self.department_ids.len());
+        let mut request = ListDepartments {
+            page_size: Some(100),
+            page_token: None,
+            department_id_list: self.department_ids.clone(),
+            user_id_type: self.user_id_type.clone(),
+            department_id_type: self.department_id_type.clone(),
+        };
+        loop {
+            let page = client.send(&request).await?.into_data()?;
+            departments.extend(page.items.into_iter().map(DepartmentInfo::from));
+            match page.page_token {
+                Some(token) if page.has_more => request.page_token = Some(token),
+                _ => return Ok(departments),
+            }
+        }
+    }
+
+    async fn send_v2(&self, client: &Client) -> Result<Vec<DepartmentInfo>> {
+        let mut departments = Vec::with_capacity(self.department_ids.len());
+        for ids in self.department_ids.chunks(v2::BATCH_GET_DEPARTMENTS_LIMIT) {
+            let request = v2::BatchGetDepartments {
+                user_id_type: self.user_id_type.clone(),
+                department_id_type: self.department_id_type.clone(),
+                department_id_list: ids.to_vec(),
+                fields: None,
+            };
+            let items = client.send(&request).await?.into_data()?.items;
+            departments.extend(items.into_iter().map(DepartmentInfo::from));
+        }
+        Ok(departments)
+    }
+}
+
+#[cfg(feature = "async")]
+impl VersionedRequest for GetDepartments {
+    type Output = Vec<DepartmentInfo>;
+
+    const SERVICE: &'static str = "corehr";
+    const VERSIONS: &'static [ApiVersion] = &[ApiVersion::V1, ApiVersion::V2];
+
+    fn send_version<'a>(
+        &'a self,
+        client: &'a Client,
+        version: ApiVersion,
+    ) -> BoxFuture<'a, Result<Self::Output>> {
+        if version == ApiVersion::V1 {
+            Box::pin(self.send_v1(client))
+        } else {
+            Box::pin(self.send_v2(client))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::enums::EmploymentStatus;
+    use crate::id::OpenId;
+    use crate::test_util::serve_all;
+    use crate::Client;
+
+    #[tokio::test]
+    async fn read_employees_and_departments() {
+        let (address, handle) = serve_all(vec![
+            r#"{"code":0,"msg":"success","data":{"items":[{"employment_id":"ou_1","employee_number":"A001","department_id":"d1","employment_status":{"enum_name":"hired","display":[{"lang":"zh-CN","value":"在职"}]},"person_info":{"person_id":"p1","legal_name":"张三"}}]}}"#,
+            r#"{"code":0,"msg":"success","data":{"items":[{"id":"j1","employment_id":"ou_1","department_id":"d1","effective_time":"2020-05-01 00:00:00","primary_job_data":true}],"has_more":false}}"#,
+            r#"{"code":0,"msg":"success","data":{"department":{"id":"d1","hiberarchy_common":{"parent_id":"d0","name":[{"lang":"zh-CN","value":"研发部"}],"active":true}}}}"#,
+        ]);
+        let client = Client::builder().domain(address).build().unwrap();
+        let employees = client
+            .send(&BatchGetEmployees::by_ids(&[OpenId::new("ou_1")]))
+            .await
+            .unwrap()
+            .into_data()
+            .unwrap();
+        let employee = &employees.items[0];
+        assert_eq!(employee.employee_number.as_deref(), Some("A001"));
+        assert_eq!(employee.status(), Some(EmploymentStatus::Hired));
+
+        let request = ListJobDatas {
+            employment_id: Some("ou_1".to_string()),
+            user_id_type: Some(UserIdType::OpenId),
+            ..ListJobDatas::default()
+        };
+        let job_datas = client.send(&request).await.unwrap().into_data().unwrap();
+        assert!(job_datas.items[0].primary_job_data);
+
+        let request = GetDepartment {
+            department_id: "d1".to_string(),
+            user_id_type: None,
+            department_id_type: None,
+        };
+        let department = client
+            .send(&request)
+            .await
+            .unwrap()
+            .into_data()
+            .unwrap()
+            .department;
+        assert_eq!(department.hiberarchy_common.name[0].value, "研发部");
+
+        let requests = handle.join().unwrap();
+        assert!(requests[0]
+            .starts_with("POST /open-apis/corehr/v2/employees/batch_get?user_id_type=open_id "));
+        assert!(requests[0].ends_with(r#"{"employment_ids":["ou_1"]}"#));
+        assert!(requests[1].starts_with(
+            "GET /open-apis/corehr/v1/job_datas?employment_id=ou_1&user_id_type=open_id "
+        ));
+        assert!(requests[2].starts_with("GET /open-apis/corehr/v1/departments/d1 "));
+    }
+
+    #[tokio::test]
+    async fn departments_by_version() {
+        let (address, handle) = serve_all(vec![
+            r#"{"code":0,"msg":"success","data":{"items":[{"id":"d1","department_name":[{"lang":"zh-CN","value":"研发部"}],"parent_department_id":"d0","active":true}]}}"#,
+            r#"{"code":0,"msg":"success","data":{"items":[{"id":"d1","hiberarchy_common":{"parent_id":"d0","name":[{"lang":"zh-CN","value":"研发部"}],"active":true}}],"has_more":false}}"#,
+        ]);
+        let request = GetDepartments {
+            department_ids: vec!["d1".to_string()],
+            ..GetDepartments::default()
+        };
+        let client = Client::builder().domain(address.clone()).build().unwrap();
+        let latest = client.send_versioned(&request).await.unwrap();
+        let client = Client::builder()
+            .domain(address)
+            .max_api_version("corehr", ApiVersion::V1)
+            .build()
+            .unwrap();
+        assert_eq!(client.max_api_version("corehr"), Some(ApiVersion::V1));
+        let v1 = client.send_versioned(&request).await.unwrap();
+        assert_eq!(latest, v1);
+        assert_eq!(latest[0].parent_id.as_deref(), Some("d0"));
+        assert!(latest[0].active);
+
+        let requests = handle.join().unwrap();
+        assert!(requests[0].starts_with("POST /open-apis/corehr/v2/departments/batch_get "));
+        assert!(requests[0].ends_with(r#"{"department_id_list":["d1"]}"#));
+        assert!(requests[1].starts_with(
+            "GET /open-apis/corehr/v1/departments?page_size=100&department_id_list=d1 "
+        ));
+    }
+}
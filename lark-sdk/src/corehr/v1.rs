@@ -0,0 +1,146 @@
+//! 飞书人事 v1 版本的接口。
+
+use serde::Deserialize;
+
+use super::I18nText;
+use crate::enums::{DepartmentIdType, UserIdType};
+use crate::ListData;
+
+/// 任职信息
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct JobData {
+    pub id: String,
+    pub employment_id: String,
+    #[serde(default)]
+    pub department_id: Option<String>,
+    #[serde(default)]
+    pub job_id: Option<String>,
+    #[serde(default)]
+    pub job_level_id: Option<String>,
+    #[serde(default)]
+    pub work_location_id: Option<String>,
+    /// 生效时间，如 `2020-05-01 00:00:00`
+    #[serde(default)]
+    pub effective_time: Option<String>,
+    #[serde(default)]
+    pub expiration_time: Option<String>,
+    /// 是否为主任职
+    #[serde(default)]
+    pub primary_job_data: bool,
+}
+
+/// 查询任职信息
+#[derive(Debug, Clone, Default, lark_sdk::Request)]
+#[request(
+    method = "GET",
+    url = "/open-apis/corehr/v1/job_datas",
+    auth = "tenant",
+    response = ListData<JobData>
+)]
+pub struct ListJobDatas {
+    #[request(query)]
+    pub page_size: Option<u32>,
+    #[request(query, page_token)]
+    pub page_token: Option<String>,
+    /// 只查询该员工的任职信息
+    #[request(query)]
+    pub employment_id: Option<String>,
+    #[request(query)]
+    pub user_id_type: Option<UserIdType>,
+    #[request(query)]
+    pub department_id_type: Option<DepartmentIdType>,
+}
+
+/// [`GetJobData`] 的结果
+#[derive(Debug, Clone, Deserialize, lark_sdk::Response)]
+pub struct JobDataDetail {
+    pub job_data: JobData,
+}
+
+/// 查询单个任职信息
+#[derive(Debug, Clone, lark_sdk::Request)]
+#[request(
+    method = "GET",
+    url = "/open-apis/corehr/v1/job_datas/:job_data_id",
+    auth = "tenant",
+    validate,
+    response = JobDataDetail
+)]
+pub struct GetJobData {
+    #[request(path)]
+    pub job_data_id: String,
+    #[request(query)]
+    pub user_id_type: Option<UserIdType>,
+    #[request(query)]
+    pub department_id_type: Option<DepartmentIdType>,
+}
+
+/// 部门的层级信息
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct Hiberarchy {
+    #[serde(default)]
+    pub parent_id: Option<String>,
+    #[serde(default)]
+    pub name: Vec<I18nText>,
+    /// 是否启用
+    #[serde(default)]
+    pub active: bool,
+    #[serde(default)]
+    pub code: Option<String>,
+}
+
+/// 部门
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct Department {
+    pub id: String,
+    pub hiberarchy_common: Hiberarchy,
+    /// 部门负责人的雇佣 ID
+    #[serde(default)]
+    pub manager: Option<String>,
+}
+
+/// 查询部门列表
+#[derive(Debug, Clone, Default, lark_sdk::Request)]
+#[request(
+    method = "GET",
+    url = "/open-apis/corehr/v1/departments",
+    auth = "tenant",
+    response = ListData<Department>
+)]
+pub struct ListDepartments {
+    #[request(query)]
+    pub page_size: Option<u32>,
+    #[request(query, page_token)]
+    pub page_token: Option<String>,
+    /// 只查询这些部门
+    #[request(query, style = "repeat")]
+    pub department_id_list: Vec<String>,
+    #[request(query)]
+    pub user_id_type: Option<UserIdType>,
+    #[request(query)]
+    pub department_id_type: Option<DepartmentIdType>,
+}
+
+/// [`GetDepartment`] 的结果
+#[derive(Debug, Clone, Deserialize, lark_sdk::Response)]
+pub struct DepartmentDetail {
+    pub department: Department,
+}
+
+/// 查询单个部门
+#[derive(Debug, Clone, lark_sdk::Request)]
+#[request(
+    method = "GET",
+    url = "/open-apis/corehr/v1/departments/:department_id",
+    auth = "tenant",
+    validate,
+    response = DepartmentDetail
+)]
+pub struct GetDepartment {
+    #[request(path)]
+    pub department_id: String,
+    #[request(query)]
+    pub user_id_type: Option<UserIdType>,
+    #[request(query)]
+    pub department_id_type: Option<DepartmentIdType>,
+}
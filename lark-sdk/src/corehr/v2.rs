@@ -0,0 +1,137 @@
+//! 飞书人事 v2 版本的接口。
+
+use serde::Deserialize;
+
+use super::{EnumValue, I18nText, PersonInfo};
+use crate::enums::{DepartmentIdType, EmploymentStatus, UserIdType};
+use crate::id::IdType;
+
+/// 每次批量查询员工的最多个数
+pub const BATCH_GET_EMPLOYEES_LIMIT: usize = 100;
+
+/// 每次批量查询部门的最多个数
+pub const BATCH_GET_DEPARTMENTS_LIMIT: usize = 100;
+
+/// 员工
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct Employee {
+    /// 雇佣 ID，类型与请求的 `user_id_type` 一致
+    pub employment_id: String,
+    /// 工号
+    #[serde(default)]
+    pub employee_number: Option<String>,
+    #[serde(default)]
+    pub department_id: Option<String>,
+    #[serde(default)]
+    pub job_id: Option<String>,
+    #[serde(default)]
+    pub job_level_id: Option<String>,
+    /// 直属上级的雇佣 ID
+    #[serde(default)]
+    pub direct_manager_id: Option<String>,
+    #[serde(default)]
+    pub work_email: Option<String>,
+    /// 在职状态
+    #[serde(default)]
+    pub employment_status: Option<EnumValue>,
+    #[serde(default)]
+    pub person_info: Option<PersonInfo>,
+}
+
+impl Employee {
+    /// [`employment_status`](Employee::employment_status) 的枚举值
+    pub fn status(&self) -> Option<EmploymentStatus> {
+        self.employment_status
+            .as_ref()
+            .map(|status| EmploymentStatus::from(status.enum_name.as_str()))
+    }
+}
+
+/// [`BatchGetEmployees`] 的结果
+#[derive(Debug, Clone, Deserialize, lark_sdk::Response)]
+pub struct Employees {
+    #[serde(default)]
+    pub items: Vec<Employee>,
+}
+
+/// 批量查询员工
+#[derive(Debug, Clone, lark_sdk::Request)]
+#[request(
+    method = "POST",
+    url = "/open-apis/corehr/v2/employees/batch_get",
+    auth = "tenant",
+    response = Employees
+)]
+pub struct BatchGetEmployees {
+    /// `employment_ids` 的类型，如 `open_id`、`people_corehr_id`
+    #[request(query)]
+    pub user_id_type: Option<UserIdType>,
+    #[request(query)]
+    pub department_id_type: Option<DepartmentIdType>,
+    /// 最多 [`BATCH_GET_EMPLOYEES_LIMIT`] 个
+    #[request(body, max_len = BATCH_GET_EMPLOYEES_LIMIT)]
+    pub employment_ids: Vec<String>,
+    /// 返回的字段，为空时返回默认字段
+    #[request(body)]
+    pub fields: Option<Vec<String>>,
+}
+
+impl BatchGetEmployees {
+    /// 使用通讯录中的用户 ID 查询
+    pub fn by_ids<T: IdType>(ids: &[T]) -> Self {
+        BatchGetEmployees {
+            user_id_type: Some(UserIdType::of::<T>()),
+            department_id_type: None,
+            employment_ids: ids.iter().map(|id| id.as_str().to_string()).collect(),
+            fields: None,
+        }
+    }
+}
+/// 部门
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct Department {
+    pub id: String,
+    #[serde(default)]
+    pub department_name: Vec<I18nText>,
+    #[serde(default)]
+    pub parent_department_id: Option<String>,
+    /// 部门负责人的雇佣 ID
+    #[serde(default)]
+    pub manager: Option<String>,
+    /// 是否启用
+    #[serde(default)]
+    pub active: bool,
+    #[serde(default)]
+    pub code: Option<String>,
+    /// 是否为根部门
+    #[serde(default)]
+    pub is_root: bool,
+}
+
+/// [`BatchGetDepartments`] 的结果
+#[derive(Debug, Clone, Deserialize, lark_sdk::Response)]
+pub struct Departments {
+    #[serde(default)]
+    pub items: Vec<Department>,
+}
+
+/// 批量查询部门
+#[derive(Debug, Clone, Default, lark_sdk::Request)]
+#[request(
+    method = "POST",
+    url = "/open-apis/corehr/v2/departments/batch_get",
+    auth = "tenant",
+    response = Departments
+)]
+pub struct BatchGetDepartments {
+    #[request(query)]
+    pub user_id_type: Option<UserIdType>,
+    #[request(query)]
+    pub department_id_type: Option<DepartmentIdType>,
+    /// 最多 [`BATCH_GET_DEPARTMENTS_LIMIT`] 个
+    #[request(body, max_len = BATCH_GET_DEPARTMENTS_LIMIT)]
+    pub department_id_list: Vec<String>,
+    /// 返回的字段，为空时返回默认字段
+    #[request(body)]
+    pub fields: Option<Vec<String>>,
+}
@@ -0,0 +1,205 @@
+//! 将请求转换为 curl 命令，便于复制到终端或 Postman 中复现问题，反馈给飞书技术支持。
+//!
+//! 访问凭证、应用密钥等敏感信息会被替换为 `***`。开启 `curl` 特性后，客户端还会在发送每个请求前
+//! 以 `debug` 级别、`lark_sdk::curl` 为 target 输出对应的 curl 命令。
+
+use serde_json::Value;
+
+use crate::exchange::join_url;
+use crate::RequestParts;
+
+/// 敏感信息替换后的内容
+const REDACTED: &str = "***";
+
+/// 值需要隐藏的请求体字段和 query 参数
+const SECRET_KEYS: &[&str] = &[
+    "app_secret",
+    "app_ticket",
+    "app_access_token",
+    "tenant_access_token",
+    "user_access_token",
+    "access_token",
+    "refresh_token",
+    "code",
+    "password",
+];
+
+/// 请求头的名称包含这些内容时隐藏值
+const SECRET_HEADERS: &[&str] = &["authorization", "token", "secret"];
+
+impl RequestParts {
+    /// 生成 curl 命令，`domain` 为接口地址，如 [`FEISHU_DOMAIN`](crate::FEISHU_DOMAIN)
+    pub fn to_curl(&self, domain: &str) -> String {
+        let mut url = join_url(domain, &self.url);
+        if !self.query.is_empty() {
+            let query: Vec<(&str, &str)> = self
+                .query
+                .iter()
+                .map(|(name, value)| {
+                    let value = if is_secret_key(name) {
+                        REDACTED
+                    } else {
+                        value.as_str()
+                    };
+                    (name.as_str(), value)
+                })
+                .collect();
+            url.push('?');
+            url.push_str(&serde_urlencoded::to_string(query).unwrap_or_default());
+        }
+        let mut command = format!("curl -X {} {}", self.method, quote(&url));
+        let mut content_type = Some(&self.content_type);
+        for (name, value) in &self.headers {
+            if name.eq_ignore_ascii_case("content-type") {
+                content_type = None;
+            }
+            let lower = name.to_ascii_lowercase();
+            let value = if SECRET_HEADERS.iter().any(|secret| lower.contains(secret)) {
+                redact_header(value)
+            } else {
+                value.clone()
+            };
+            command.push_str(&format!(
+                " \\\n  -H {}",
+                quote(&format!("{}: {}", name, value))
+            ));
+        }
+        let body = match &self.body {
+            Some(body) => body,
+            None => return command,
+        };
+        if let Some(content_type) = content_type {
+            command.push_str(&format!(
+                " \\\n  -H {}",
+                quote(&format!("Content-Type: {}", content_type))
+            ));
+        }
+        match serde_json::from_slice::<Value>(body) {
+            Ok(mut json) => {
+                redact_json(&mut json);
+                command.push_str(&format!(" \\\n  --data-raw {}", quote(&json.to_string())));
+            }
+            // multipart 等二进制内容无法直接放在命令中
+            Err(_) => command.push_str(&format!(
+                " \\\n  --data-binary @body.bin  # {} bytes body omitted",
+                body.len()
+            )),
+        }
+        command
+    }
+}
+
+fn is_secret_key(name: &str) -> bool {
+    SECRET_KEYS.contains(&name)
+}
+
+/// 保留 `Bearer` 等认证方式，隐藏凭证
+fn redact_header(value: &str) -> String {
+    match value.split_once(' ') {
+        Some((scheme, _)) => format!("{} {}", scheme, REDACTED),
+        None => REDACTED.to_string(),
+    }
+}
+
+fn redact_json(value: &mut Value) {
+    match value {
+        Value::Object(map) => {
+            for (key, value) in map.iter_mut() {
+                if is_secret_key(key) && !value.is_null() {
+                    *value = Value::String(REDACTED.to_string());
+                } else {
+                    redact_json(value);
+                }
+            }
+        }
+        Value::Array(items) => items.iter_mut().for_each(redact_json),
+        _ => {}
+    }
+}
+
+/// 使用单引号转义 shell 参数
+fn quote(value: &str) -> String {
+    format!("'{}'", value.replace('\'', r"'\''"))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{AuthType, Method, Request, FEISHU_DOMAIN};
+
+    use super::*;
+
+    #[derive(lark_sdk::Request)]
+    #[request(
+        method = "POST",
+        url = "/open-apis/im/v1/messages",
+        auth = "tenant",
+        empty
+    )]
+    struct SendMessage {
+        #[request(query)]
+        receive_id_type: String,
+        #[request(body)]
+        receive_id: String,
+        #[request(body)]
+        content: String,
+    }
+
+    #[test]
+    fn request_to_curl() {
+        let request = SendMessage {
+            receive_id_type: "open_id".to_string(),
+            receive_id: "ou_1".to_string(),
+            content: r#"{"text":"it's ok"}"#.to_string(),
+        };
+        assert_eq!(
+            request.to_curl(),
+            r#"curl -X POST 'https://open.feishu.cn/open-apis/im/v1/messages?receive_id_type=open_id' \
+  -H 'Content-Type: application/json; charset=utf-8' \
+  --data-raw '{"content":"{\"text\":\"it'\''s ok\"}","receive_id":"ou_1"}'"#
+        );
+    }
+
+    #[test]
+    fn redact_secrets() {
+        let parts = RequestParts {
+            method: Method::Post,
+            url: "/open-apis/authen/v1/oidc/access_token".to_string(),
+            query: vec![("access_token".to_string(), "u-1".to_string())],
+            headers: vec![
+                ("Authorization".to_string(), "Bearer t-1".to_string()),
+                (
+                    "X-Lark-Helpdesk-Authorization".to_string(),
+                    "aDE6dDE=".to_string(),
+                ),
+                ("Content-Type".to_string(), "application/json".to_string()),
+            ],
+            content_type: "application/json; charset=utf-8".to_string(),
+            body: Some(
+                br#"{"grant_type":"authorization_code","code":"c-1","app":{"app_secret":"s"}}"#
+                    .to_vec(),
+            ),
+            endpoint: None,
+            auth: AuthType::None,
+        };
+        let curl = parts.to_curl("http://127.0.0.1:8080/");
+        assert_eq!(
+            curl,
+            r#"curl -X POST 'http://127.0.0.1:8080/open-apis/authen/v1/oidc/access_token?access_token=***' \
+  -H 'Authorization: Bearer ***' \
+  -H 'X-Lark-Helpdesk-Authorization: ***' \
+  -H 'Content-Type: application/json' \
+  --data-raw '{"app":{"app_secret":"***"},"code":"***","grant_type":"authorization_code"}'"#
+        );
+        assert!(!curl.contains("t-1") && !curl.contains("c-1"));
+
+        let parts = RequestParts {
+            body: Some(vec![0xff, 0x00]),
+            headers: Vec::new(),
+            query: Vec::new(),
+            ..parts
+        };
+        assert!(parts
+            .to_curl(FEISHU_DOMAIN)
+            .ends_with("--data-binary @body.bin  # 2 bytes body omitted"));
+    }
+}
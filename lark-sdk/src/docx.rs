@@ -0,0 +1,1164 @@
+//! 新版文档（docx）的块。
+//!
+//! 文档由块组成，每个块的 `block_type` 决定了内容所在的字段，如文本块的内容在 `text` 中、一级标题的内容在
+//! `heading1` 中。[`Block`] 的构造方法会同时设置两者。
+//!
+//! ```
+//! use lark_sdk::docx::{Block, TextBlock};
+//!
+//! let blocks = vec![
+//!     Block::heading(1, "周报"),
+//!     Block::text(TextBlock::new().text("本周完成了").bold("3 个").text("需求")),
+//!     Block::bullet("修复登录问题"),
+//!     Block::divider(),
+//! ];
+//! ```
+//!
+//! 表格、高亮块等包含子块的内容使用 [`BlockTree`] 表示，[`DocumentBuilder`] 使用它们生成创建文档和创建块的请求。
+//! 将文档转换为 Markdown 或纯文本见 [`render`]。
+
+pub mod render;
+
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::ListData;
+#[cfg(feature = "async")]
+use crate::{Client, LarkError, Result};
+
+/// 块的类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct BlockType(pub i32);
+
+impl BlockType {
+    pub const PAGE: BlockType = BlockType(1);
+    pub const TEXT: BlockType = BlockType(2);
+    pub const HEADING1: BlockType = BlockType(3);
+    pub const BULLET: BlockType = BlockType(12);
+    pub const ORDERED: BlockType = BlockType(13);
+    pub const CODE: BlockType = BlockType(14);
+    pub const QUOTE: BlockType = BlockType(15);
+    pub const TODO: BlockType = BlockType(17);
+    pub const CALLOUT: BlockType = BlockType(19);
+    pub const DIVIDER: BlockType = BlockType(22);
+    pub const IMAGE: BlockType = BlockType(27);
+    pub const TABLE: BlockType = BlockType(31);
+    pub const TABLE_CELL: BlockType = BlockType(32);
+    pub const QUOTE_CONTAINER: BlockType = BlockType(34);
+
+    /// `level` 级标题，`level` 为 1 到 9
+    pub fn heading(level: u8) -> BlockType {
+        BlockType(Self::HEADING1.0 + i32::from(level.clamp(1, 9)) - 1)
+    }
+}
+
+/// 文档中的一个块
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Block {
+    /// 创建时不需要填写
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub block_id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub parent_id: Option<String>,
+    /// 子块的 ID
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub children: Vec<String>,
+    pub block_type: BlockType,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub page: Option<TextBlock>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub text: Option<TextBlock>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub heading1: Option<TextBlock>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub heading2: Option<TextBlock>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub heading3: Option<TextBlock>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub heading4: Option<TextBlock>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub heading5: Option<TextBlock>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub heading6: Option<TextBlock>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub heading7: Option<TextBlock>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub heading8: Option<TextBlock>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub heading9: Option<TextBlock>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bullet: Option<TextBlock>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ordered: Option<TextBlock>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub code: Option<TextBlock>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub quote: Option<TextBlock>,
+    /// 待办事项，是否完成在 `style.done` 中
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub todo: Option<TextBlock>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub callout: Option<Callout>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub divider: Option<Divider>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub image: Option<Image>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub table: Option<Table>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub table_cell: Option<TableCell>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub quote_container: Option<QuoteContainer>,
+}
+
+impl Block {
+    fn empty(block_type: BlockType) -> Self {
+        Block {
+            block_id: None,
+            parent_id: None,
+            children: Vec::new(),
+            block_type,
+            page: None,
+            text: None,
+            heading1: None,
+            heading2: None,
+            heading3: None,
+            heading4: None,
+            heading5: None,
+            heading6: None,
+            heading7: None,
+            heading8: None,
+            heading9: None,
+            bullet: None,
+            ordered: None,
+            code: None,
+            quote: None,
+            todo: None,
+            callout: None,
+            divider: None,
+            image: None,
+            table: None,
+            table_cell: None,
+            quote_container: None,
+        }
+    }
+
+    /// 文本块
+    pub fn text(text: impl Into<TextBlock>) -> Self {
+        Block {
+            text: Some(text.into()),
+            ..Block::empty(BlockType::TEXT)
+        }
+    }
+
+    /// `level` 级标题，`level` 为 1 到 9
+    pub fn heading(level: u8, text: impl Into<TextBlock>) -> Self {
+        let mut block = Block::empty(BlockType::heading(level));
+        let text = Some(text.into());
+        match level.clamp(1, 9) {
+            1 => block.heading1 = text,
+            2 => block.heading2 = text,
+            3 => block.heading3 = text,
+            4 => block.heading4 = text,
+            5 => block.heading5 = text,
+            6 => block.heading6 = text,
+            7 => block.heading7 = text,
+            8 => block.heading8 = text,
+            _ => block.heading9 = text,
+        }
+        block
+    }
+
+    /// 无序列表项
+    pub fn bullet(text: impl Into<TextBlock>) -> Self {
+        Block {
+            bullet: Some(text.into()),
+            ..Block::empty(BlockType::BULLET)
+        }
+    }
+
+    /// 有序列表项
+    pub fn ordered(text: impl Into<TextBlock>) -> Self {
+        Block {
+            ordered: Some(text.into()),
+            ..Block::empty(BlockType::ORDERED)
+        }
+    }
+
+    /// 代码块
+    pub fn code(code: impl Into<String>) -> Self {
+        Block {
+            code: Some(TextBlock::new().text(code)),
+            ..Block::empty(BlockType::CODE)
+        }
+    }
+
+    /// 文本、标题、列表、代码等块的内容，其他类型的块返回 `None`
+    pub fn text_block(&self) -> Option<&TextBlock> {
+        [
+            &self.page,
+            &self.text,
+            &self.heading1,
+            &self.heading2,
+            &self.heading3,
+            &self.heading4,
+            &self.heading5,
+            &self.heading6,
+            &self.heading7,
+            &self.heading8,
+            &self.heading9,
+            &self.bullet,
+            &self.ordered,
+            &self.code,
+            &self.quote,
+            &self.todo,
+        ]
+        .into_iter()
+        .find_map(Option::as_ref)
+    }
+
+    /// 分割线
+    pub fn divider() -> Self {
+        Block {
+            divider: Some(Divider {}),
+            ..Block::empty(BlockType::DIVIDER)
+        }
+    }
+
+    /// 高亮块，内容为它的子块，见 [`BlockTree::callout`]
+    pub fn callout(callout: Callout) -> Self {
+        Block {
+            callout: Some(callout),
+            ..Block::empty(BlockType::CALLOUT)
+        }
+    }
+
+    /// 图片块。创建时只能创建空的图片块，之后需要以块 ID 为 `parent_node` 上传图片素材，
+    /// 再使用 [`ReplaceImage`] 设置块的图片
+    pub fn image(image: Image) -> Self {
+        Block {
+            image: Some(image),
+            ..Block::empty(BlockType::IMAGE)
+        }
+    }
+
+    /// `rows` 行 `columns` 列的表格，单元格为它的子块，见 [`BlockTree::table`]
+    pub fn table(rows: u32, columns: u32) -> Self {
+        Block {
+            table: Some(Table {
+                cells: Vec::new(),
+                property: TableProperty {
+                    row_size: rows,
+                    column_size: columns,
+                    header_row: None,
+                },
+            }),
+            ..Block::empty(BlockType::TABLE)
+        }
+    }
+
+    /// 表格的单元格
+    pub fn table_cell() -> Self {
+        Block {
+            table_cell: Some(TableCell {}),
+            ..Block::empty(BlockType::TABLE_CELL)
+        }
+    }
+}
+
+/// 块和它的子块
+#[derive(Debug, Clone, PartialEq)]
+pub struct BlockTree {
+    pub block: Block,
+    pub children: Vec<BlockTree>,
+}
+
+impl BlockTree {
+    pub fn new(block: Block, children: Vec<BlockTree>) -> Self {
+        BlockTree { block, children }
+    }
+
+    /// 包含 `children` 的高亮块
+    pub fn callout(callout: Callout, children: Vec<Block>) -> Self {
+        let children = children.into_iter().map(BlockTree::from).collect();
+        BlockTree::new(Block::callout(callout), children)
+    }
+
+    /// 使用每行单元格的文本创建表格，列数为最长的一行，`header_row` 为第一行是否为表头
+    pub fn table<R, C>(rows: R, header_row: bool) -> Self
+    where
+        R: IntoIterator,
+        R::Item: IntoIterator<Item = C>,
+        C: Into<TextBlock>,
+    {
+        let rows: Vec<Vec<TextBlock>> = rows
+            .into_iter()
+            .map(|row| row.into_iter().map(Into::into).collect())
+            .collect();
+        let columns = rows.iter().map(Vec::len).max().unwrap_or(0);
+        let mut table = Block::table(rows.len() as u32, columns as u32);
+        if let Some(table) = &mut table.table {
+            table.property.header_row = Some(header_row);
+        }
+        let cells = rows
+            .into_iter()
+            .flat_map(|mut row| {
+                row.resize(columns, TextBlock::new());
+                row
+            })
+            .map(|text| BlockTree::new(Block::table_cell(), vec![Block::text(text).into()]))
+            .collect();
+        BlockTree::new(table, cells)
+    }
+
+    /// 按照深度优先的顺序展开为块，为每个块设置 `block_id` 为 `{prefix}{序号}` 的临时 ID，
+    /// 序号从 `next` 开始，并设置父块的 `children`，返回根块的临时 ID
+    fn flatten(self, prefix: &str, next: &mut usize, blocks: &mut Vec<Block>) -> String {
+        let index = blocks.len();
+        let block_id = format!("{}{}", prefix, *next);
+        *next += 1;
+        let mut block = self.block;
+        block.block_id = Some(block_id.clone());
+        blocks.push(block);
+        let children = self
+            .children
+            .into_iter()
+            .map(|child| child.flatten(prefix, next, blocks))
+            .collect();
+        blocks[index].children = children;
+        block_id
+    }
+
+    /// 包括自己在内的块数量
+    #[cfg(feature = "async")]
+    fn size(&self) -> usize {
+        1 + self.children.iter().map(BlockTree::size).sum::<usize>()
+    }
+}
+
+impl From<Block> for BlockTree {
+    fn from(block: Block) -> Self {
+        BlockTree::new(block, Vec::new())
+    }
+}
+
+/// 文本、标题、列表等块的内容
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct TextBlock {
+    #[serde(default)]
+    pub elements: Vec<TextElement>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub style: Option<serde_json::Value>,
+}
+
+impl TextBlock {
+    pub fn new() -> Self {
+        TextBlock::default()
+    }
+
+    /// 追加文本
+    pub fn text(self, content: impl Into<String>) -> Self {
+        self.styled(content, TextElementStyle::default())
+    }
+
+    /// 追加加粗的文本
+    pub fn bold(self, content: impl Into<String>) -> Self {
+        let style = TextElementStyle {
+            bold: true,
+            ..TextElementStyle::default()
+        };
+        self.styled(content, style)
+    }
+
+    /// 追加链接
+    pub fn link(self, content: impl Into<String>, url: impl Into<String>) -> Self {
+        let style = TextElementStyle {
+            link: Some(Link { url: url.into() }),
+            ..TextElementStyle::default()
+        };
+        self.styled(content, style)
+    }
+
+    /// 追加指定样式的文本
+    pub fn styled(mut self, content: impl Into<String>, style: TextElementStyle) -> Self {
+        self.elements.push(TextElement {
+            text_run: Some(TextRun {
+                content: content.into(),
+                text_element_style: Some(style)
+                    .filter(|style| *style != TextElementStyle::default()),
+            }),
+            mention_user: None,
+        });
+        self
+    }
+
+    /// 追加 @ 用户
+    pub fn mention_user(mut self, user_id: impl Into<String>) -> Self {
+        self.elements.push(TextElement {
+            text_run: None,
+            mention_user: Some(MentionUser {
+                user_id: user_id.into(),
+            }),
+        });
+        self
+    }
+
+    /// 所有文本拼接后的内容
+    pub fn plain_text(&self) -> String {
+        self.elements
+            .iter()
+            .filter_map(|element| element.text_run.as_ref())
+            .map(|run| run.content.as_str())
+            .collect()
+    }
+}
+
+impl From<&str> for TextBlock {
+    fn from(content: &str) -> Self {
+        TextBlock::new().text(content)
+    }
+}
+
+impl From<String> for TextBlock {
+    fn from(content: String) -> Self {
+        TextBlock::new().text(content)
+    }
+}
+
+/// 文本中的一段
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TextElement {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub text_run: Option<TextRun>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mention_user: Option<MentionUser>,
+}
+
+/// 一段文字
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TextRun {
+    pub content: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub text_element_style: Option<TextElementStyle>,
+}
+
+/// 文字的样式
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TextElementStyle {
+    #[serde(skip_serializing_if = "is_false")]
+    pub bold: bool,
+    #[serde(skip_serializing_if = "is_false")]
+    pub italic: bool,
+    #[serde(skip_serializing_if = "is_false")]
+    pub strikethrough: bool,
+    #[serde(skip_serializing_if = "is_false")]
+    pub underline: bool,
+    #[serde(skip_serializing_if = "is_false")]
+    pub inline_code: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub link: Option<Link>,
+}
+
+/// 链接，`url` 需要进行 URL 编码
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Link {
+    pub url: String,
+}
+
+/// @ 用户
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MentionUser {
+    pub user_id: String,
+}
+
+/// 分割线没有内容
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Divider {}
+
+/// 高亮块的样式
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Callout {
+    /// 背景色，1 到 15
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub background_color: Option<i32>,
+    /// 边框色，1 到 7
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub border_color: Option<i32>,
+    /// 表情，如 `bulb`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub emoji_id: Option<String>,
+}
+
+impl Callout {
+    pub fn new() -> Self {
+        Callout::default()
+    }
+
+    pub fn emoji(mut self, emoji_id: impl Into<String>) -> Self {
+        self.emoji_id = Some(emoji_id.into());
+        self
+    }
+
+    pub fn background_color(mut self, color: i32) -> Self {
+        self.background_color = Some(color);
+        self
+    }
+}
+
+/// 图片
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Image {
+    /// 图片素材的 token，创建块时需要为空
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub token: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub width: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub height: Option<u32>,
+    /// 1：居左，2：居中，3：居右
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub align: Option<i32>,
+}
+
+/// 表格
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Table {
+    /// 单元格的块 ID，按行排列，创建时不需要填写
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub cells: Vec<String>,
+    pub property: TableProperty,
+}
+
+/// 表格的属性
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TableProperty {
+    pub row_size: u32,
+    pub column_size: u32,
+    /// 第一行是否为表头
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub header_row: Option<bool>,
+}
+
+/// 单元格没有内容，单元格中的内容为它的子块
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TableCell {}
+
+/// 引用容器没有内容，引用的内容为它的子块
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct QuoteContainer {}
+
+/// 文档
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct Document {
+    pub document_id: String,
+    pub revision_id: i64,
+    #[serde(default)]
+    pub title: Option<String>,
+}
+
+/// [`CreateDocument`] 创建的文档
+#[derive(Debug, Clone, Deserialize, lark_sdk::Response)]
+pub struct DocumentData {
+    pub document: Document,
+}
+
+/// 创建文档，文档的根块 ID 与文档 ID 相同
+#[derive(Debug, Clone, lark_sdk::Request)]
+#[request(
+    method = "POST",
+    url = "/open-apis/docx/v1/documents",
+    auth = "tenant",
+    response = DocumentData
+)]
+pub struct CreateDocument {
+    /// 所在的文件夹，为空时创建在应用的根目录
+    #[request(body)]
+    pub folder_token: Option<String>,
+    #[request(body)]
+    pub title: Option<String>,
+}
+
+/// 获取文档的所有块，第一个块为根块
+#[derive(Debug, Clone, lark_sdk::Request)]
+#[request(
+    method = "GET",
+    url = "/open-apis/docx/v1/documents/:document_id/blocks",
+    auth = "tenant",
+    validate,
+    response = ListData<Block>
+)]
+pub struct ListDocumentBlocks {
+    #[request(path)]
+    pub document_id: String,
+    /// 为 `-1` 时表示最新版本
+    #[request(query)]
+    pub document_revision_id: Option<i64>,
+    #[request(query)]
+    pub page_size: Option<u32>,
+    #[request(query, page_token)]
+    pub page_token: Option<String>,
+}
+
+impl ListDocumentBlocks {
+    /// 最新版本的第一页
+    pub fn new(document_id: impl Into<String>) -> Self {
+        ListDocumentBlocks {
+            document_id: document_id.into(),
+            document_revision_id: Some(-1),
+            page_size: Some(500),
+            page_token: None,
+        }
+    }
+}
+
+/// 文档的纯文本内容
+#[derive(Debug, Clone, Deserialize, lark_sdk::Response)]
+pub struct RawContent {
+    pub content: String,
+}
+
+/// 获取文档的纯文本内容
+#[derive(Debug, Clone, lark_sdk::Request)]
+#[request(
+    method = "GET",
+    url = "/open-apis/docx/v1/documents/:document_id/raw_content",
+    auth = "tenant",
+    validate,
+    response = RawContent
+)]
+pub struct GetRawContent {
+    #[request(path)]
+    pub document_id: String,
+    /// @ 用户显示的语言，0：名字，1：英文名，2：日文名
+    #[request(query)]
+    pub lang: Option<i32>,
+}
+
+/// 临时块 ID 与创建后的块 ID 的对应关系
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct BlockIdRelation {
+    pub temporary_block_id: String,
+    pub block_id: String,
+}
+
+/// [`CreateBlockDescendants`] 创建的块
+#[derive(Debug, Clone, Deserialize, lark_sdk::Response)]
+pub struct CreatedDescendants {
+    #[serde(default)]
+    pub children: Vec<Block>,
+    pub document_revision_id: i64,
+    #[serde(default)]
+    pub block_id_relations: Vec<BlockIdRelation>,
+}
+
+/// 一次 [`CreateBlockDescendants`] 最多可以创建的块数量
+pub const MAX_DESCENDANTS: usize = 1000;
+
+/// 在 `block_id` 下创建嵌套的块。`descendants` 中的块使用临时 ID 表示父子关系，
+/// `children_id` 为直接插入到 `block_id` 下的块，`descendants` 最多包含 [`MAX_DESCENDANTS`] 个块
+#[derive(Debug, Clone, lark_sdk::Request)]
+#[request(
+    method = "POST",
+    url = "/open-apis/docx/v1/documents/:document_id/blocks/:block_id/descendant",
+    auth = "tenant",
+    validate,
+    response = CreatedDescendants
+)]
+pub struct CreateBlockDescendants {
+    #[request(path)]
+    pub document_id: String,
+    #[request(path)]
+    pub block_id: String,
+    /// 为 `-1` 时表示最新版本
+    #[request(query)]
+    pub document_revision_id: Option<i64>,
+    /// 幂等键
+    #[request(query, idempotency_key)]
+    pub client_token: Option<String>,
+    #[request(body)]
+    pub children_id: Vec<String>,
+    #[request(body)]
+    pub descendants: Vec<Block>,
+    /// 插入的位置，为空时追加到最后
+    #[request(body)]
+    pub index: Option<i32>,
+}
+
+/// 替换图片块的图片
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReplaceImageToken {
+    /// 上传到图片块的素材，见 [`media`](crate::drive::media)
+    pub token: String,
+}
+
+/// 设置图片块的图片，图片需要先以图片块为 `parent_node` 上传
+#[derive(Debug, Clone, lark_sdk::Request)]
+#[request(
+    method = "PATCH",
+    url = "/open-apis/docx/v1/documents/:document_id/blocks/:block_id",
+    auth = "tenant",
+    validate,
+    response = serde_json::Value
+)]
+pub struct ReplaceImage {
+    #[request(path)]
+    pub document_id: String,
+    #[request(path)]
+    pub block_id: String,
+    #[request(query)]
+    pub document_revision_id: Option<i64>,
+    #[request(body)]
+    pub replace_image: ReplaceImageToken,
+}
+
+impl ReplaceImage {
+    /// 将最新版本中图片块 `block_id` 的图片设置为 `token`
+    pub fn new(
+        document_id: impl Into<String>,
+        block_id: impl Into<String>,
+        token: impl Into<String>,
+    ) -> Self {
+        ReplaceImage {
+            document_id: document_id.into(),
+            block_id: block_id.into(),
+            document_revision_id: Some(-1),
+            replace_image: ReplaceImageToken {
+                token: token.into(),
+            },
+        }
+    }
+}
+
+/// 程序化生成文档，如周报、巡检报告。
+///
+/// 先使用 [`create_request`](DocumentBuilder::create_request) 创建文档，再使用
+/// [`blocks_request`](DocumentBuilder::blocks_request) 在文档中创建所有块，或者直接调用
+/// [`create`](DocumentBuilder::create)。
+///
+/// ```no_run
+/// use lark_sdk::docx::{Block, BlockTree, Callout, DocumentBuilder};
+///
+/// # async fn run(client: lark_sdk::Client) -> lark_sdk::Result<()> {
+/// let created = DocumentBuilder::new("巡检报告")
+///     .block(Block::heading(1, "概览"))
+///     .block(BlockTree::callout(Callout::new().emoji("bulb"), vec![Block::text("全部正常")]))
+///     .block(BlockTree::table(vec![vec!["服务", "状态"], vec!["api", "正常"]], true))
+///     .create(&client)
+///     .await?;
+/// println!("{}", created.document.document_id);
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct DocumentBuilder {
+    title: Option<String>,
+    folder_token: Option<String>,
+    blocks: Vec<BlockTree>,
+}
+
+/// [`DocumentBuilder::create`] 创建的文档
+#[derive(Debug, Clone)]
+pub struct CreatedDocument {
+    pub document: Document,
+    /// 临时块 ID 对应的块 ID，用于向图片块上传图片等后续操作，临时块 ID 见 [`DocumentBuilder::blocks_request`]
+    pub block_ids: HashMap<String, String>,
+}
+
+impl DocumentBuilder {
+    pub fn new(title: impl Into<String>) -> Self {
+        DocumentBuilder {
+            title: Some(title.into()),
+            ..DocumentBuilder::default()
+        }
+    }
+
+    /// 文档所在的文件夹
+    pub fn folder_token(mut self, folder_token: impl Into<String>) -> Self {
+        self.folder_token = Some(folder_token.into());
+        self
+    }
+
+    /// 追加块
+    pub fn block(mut self, block: impl Into<BlockTree>) -> Self {
+        self.blocks.push(block.into());
+        self
+    }
+
+    /// 创建文档的请求
+    pub fn create_request(&self) -> CreateDocument {
+        CreateDocument {
+            folder_token: self.folder_token.clone(),
+            title: self.title.clone(),
+        }
+    }
+
+    /// 在文档 `document_id` 中创建所有块的请求，块的临时 ID 为 `block_{序号}`，序号为块按照深度优先
+    /// 顺序的位置。没有块时返回 `None`，块的数量超过 [`MAX_DESCENDANTS`] 时需要使用
+    /// [`create`](DocumentBuilder::create) 分批创建
+    pub fn blocks_request(&self, document_id: &str) -> Option<CreateBlockDescendants> {
+        if self.blocks.is_empty() {
+            return None;
+        }
+        let mut descendants = Vec::new();
+        let mut next = 0;
+        let children_id = self
+            .blocks
+            .iter()
+            .cloned()
+            .map(|tree| tree.flatten("block_", &mut next, &mut descendants))
+            .collect();
+        Some(CreateBlockDescendants {
+            document_id: document_id.to_string(),
+            block_id: document_id.to_string(),
+            document_revision_id: Some(-1),
+            client_token: None,
+            children_id,
+            descendants,
+            index: None,
+        })
+    }
+
+    /// 依次发送创建文档和创建块的请求。
+    ///
+    /// 块的数量超过 [`MAX_DESCENDANTS`] 时分成多个请求，依次追加到文档末尾，临时 ID 与
+    /// [`blocks_request`](DocumentBuilder::blocks_request) 一致。一个块和它的子块超过限制时，
+    /// 放不下的子块在之后的请求中追加到创建好的块下，表格的单元格不能分开创建
+    #[cfg(feature = "async")]
+    pub async fn create(&self, client: &Client) -> Result<CreatedDocument> {
+        let mut document = client
+            .send(&self.create_request())
+            .await?
+            .into_data()?
+            .document;
+        let mut block_ids: HashMap<String, String> = HashMap::new();
+        for batch in self.batches(MAX_DESCENDANTS) {
+            let block_id = match &batch.parent {
+                Some(parent) => block_ids.get(parent).cloned().ok_or_else(|| {
+                    LarkError::InvalidRequest(format!("block {} was not created", parent))
+                })?,
+                None => document.document_id.clone(),
+            };
+            let request = CreateBlockDescendants {
+                document_id: document.document_id.clone(),
+                block_id,
+                document_revision_id: Some(-1),
+                client_token: None,
+                children_id: batch.children_id,
+                descendants: batch.descendants,
+                index: None,
+            };
+            let created = client.send(&request).await?.into_data()?;
+            document.revision_id = created.document_revision_id;
+            block_ids.extend(
+                created
+                    .block_id_relations
+                    .into_iter()
+                    .map(|relation| (relation.temporary_block_id, relation.block_id)),
+            );
+        }
+        Ok(CreatedDocument {
+            document,
+            block_ids,
+        })
+    }
+
+    /// 将所有块分成每批不超过 `limit` 个块，临时 ID 的序号在所有批次中按照深度优先的顺序递增
+    #[cfg(feature = "async")]
+    fn batches(&self, limit: usize) -> Vec<DescendantBatch> {
+        let mut batches = Vec::new();
+        plan_batches(self.blocks.clone(), None, &mut 0, limit, &mut batches);
+        batches
+    }
+}
+
+/// [`DocumentBuilder::create`] 的一次创建块请求
+#[cfg(feature = "async")]
+#[derive(Debug)]
+struct DescendantBatch {
+    /// 插入位置的临时 ID，`None` 表示文档的根块
+    parent: Option<String>,
+    children_id: Vec<String>,
+    descendants: Vec<Block>,
+}
+
+#[cfg(feature = "async")]
+impl DescendantBatch {
+    fn new(parent: Option<String>) -> Self {
+        DescendantBatch {
+            parent,
+            children_id: Vec::new(),
+            descendants: Vec::new(),
+        }
+    }
+}
+
+/// 将插入到 `parent` 下的 `trees` 依次放入批次，超过 `limit` 的块先与放得下的子块一起创建，
+/// 剩下的子块放在之后的批次中插入到该块下
+#[cfg(feature = "async")]
+fn plan_batches(
+    trees: Vec<BlockTree>,
+    parent: Option<String>,
+    next: &mut usize,
+    limit: usize,
+    batches: &mut Vec<DescendantBatch>,
+) {
+    let mut batch = DescendantBatch::new(parent.clone());
+    for tree in trees {
+        let size = tree.size();
+        if !batch.descendants.is_empty() && batch.descendants.len() + size > limit {
+            let full = std::mem::replace(&mut batch, DescendantBatch::new(parent.clone()));
+            batches.push(full);
+        }
+        if size <= limit {
+            let block_id = tree.flatten("block_", next, &mut batch.descendants);
+            batch.children_id.push(block_id);
+            continue;
+        }
+        let BlockTree {
+            mut block,
+            children,
+        } = tree;
+        let block_id = format!("block_{}", *next);
+        *next += 1;
+        block.block_id = Some(block_id.clone());
+        block.children.clear();
+        batch.children_id.push(block_id.clone());
+        batch.descendants.push(block);
+        let mut children = children.into_iter().peekable();
+        while let Some(child) =
+            children.next_if(|child| batch.descendants.len() + child.size() <= limit)
+        {
+            let child_id = child.flatten("block_", next, &mut batch.descendants);
+            batch.descendants[0].children.push(child_id);
+        }
+        let full = std::mem::replace(&mut batch, DescendantBatch::new(parent.clone()));
+        batches.push(full);
+        plan_batches(children.collect(), Some(block_id), next, limit, batches);
+    }
+    if !batch.descendants.is_empty() {
+        batches.push(batch);
+    }
+}
+
+fn is_false(value: &bool) -> bool {
+    !*value
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn serialize_blocks() {
+        let heading = serde_json::to_value(Block::heading(2, "标题")).unwrap();
+        assert_eq!(
+            heading,
+            serde_json::json!({
+                "block_type": 4,
+                "heading2": {"elements": [{"text_run": {"content": "标题"}}]}
+            })
+        );
+
+        let text = Block::text(
+            TextBlock::new()
+                .text("见 ")
+                .link("文档", "https%3A%2F%2Fexample.com")
+                .bold("!"),
+        );
+        let value = serde_json::to_value(&text).unwrap();
+        assert_eq!(value["block_type"], 2);
+        assert_eq!(
+            value["text"]["elements"][1]["text_run"]["text_element_style"],
+            serde_json::json!({"link": {"url": "https%3A%2F%2Fexample.com"}})
+        );
+        assert_eq!(
+            value["text"]["elements"][2]["text_run"]["text_element_style"],
+            serde_json::json!({"bold": true})
+        );
+        assert_eq!(text.text.unwrap().plain_text(), "见 文档!");
+
+        let divider = serde_json::to_value(Block::divider()).unwrap();
+        assert_eq!(
+            divider,
+            serde_json::json!({"block_type": 22, "divider": {}})
+        );
+    }
+
+    #[test]
+    fn deserialize_block() {
+        let block: Block = serde_json::from_value(serde_json::json!({
+            "block_id": "doxcn1",
+            "parent_id": "oc_1",
+            "block_type": 12,
+            "bullet": {
+                "elements": [{"text_run": {"content": "item", "text_element_style": {"bold": false, "italic": true}}}],
+                "style": {"align": 1}
+            }
+        }))
+        .unwrap();
+        assert_eq!(block.block_type, BlockType::BULLET);
+        let run = block.bullet.unwrap().elements[0].text_run.clone().unwrap();
+        assert!(run.text_element_style.unwrap().italic);
+    }
+
+    #[test]
+    fn flatten_table() {
+        let builder = DocumentBuilder::new("报告")
+            .block(Block::heading(1, "概览"))
+            .block(BlockTree::table(
+                vec![vec!["服务", "状态"], vec!["api"]],
+                true,
+            ))
+            .block(BlockTree::callout(
+                Callout::new().emoji("bulb"),
+                vec![Block::text("提示")],
+            ));
+        let request = builder.blocks_request("doxcn_doc").unwrap();
+        assert_eq!(request.children_id, vec!["block_0", "block_1", "block_10"]);
+        assert_eq!(request.descendants.len(), 12);
+
+        let table = &request.descendants[1];
+        assert_eq!(table.block_type, BlockType::TABLE);
+        assert_eq!(
+            table.children,
+            vec!["block_2", "block_4", "block_6", "block_8"]
+        );
+        let property = &table.table.as_ref().unwrap().property;
+        assert_eq!((property.row_size, property.column_size), (2, 2));
+        assert_eq!(request.descendants[2].block_type, BlockType::TABLE_CELL);
+        assert_eq!(request.descendants[3].parent_id, None);
+        assert_eq!(
+            request.descendants[3].text.as_ref().unwrap().plain_text(),
+            "服务"
+        );
+        assert_eq!(
+            request.descendants[9].text.as_ref().unwrap().plain_text(),
+            ""
+        );
+        assert_eq!(request.descendants[10].children, vec!["block_11"]);
+
+        let body: serde_json::Value =
+            serde_json::from_slice(&crate::Request::body(&request).unwrap().unwrap()).unwrap();
+        assert_eq!(
+            body["descendants"][1]["table"],
+            serde_json::json!({"property": {"row_size": 2, "column_size": 2, "header_row": true}})
+        );
+        assert_eq!(
+            body["descendants"][10]["callout"],
+            serde_json::json!({"emoji_id": "bulb"})
+        );
+    }
+
+    #[cfg(feature = "async")]
+    #[test]
+    fn split_batches() {
+        let children = ["a", "b", "c", "d"].into_iter().map(Block::text).collect();
+        let builder = DocumentBuilder::new("报告")
+            .block(Block::heading(1, "概览"))
+            .block(BlockTree::callout(Callout::new(), children))
+            .block(Block::text("结束"));
+        let batches = builder.batches(3);
+        let ids = |batch: &DescendantBatch| -> Vec<String> {
+            batch
+                .descendants
+                .iter()
+                .map(|block| block.block_id.clone().unwrap())
+                .collect()
+        };
+        assert_eq!(batches.len(), 4);
+        assert!(batches.iter().all(|batch| batch.descendants.len() <= 3));
+
+        assert_eq!(batches[0].parent, None);
+        assert_eq!(batches[0].children_id, vec!["block_0"]);
+        // 高亮块与放得下的子块一起创建，剩下的子块插入到高亮块下
+        assert_eq!(batches[1].parent, None);
+        assert_eq!(batches[1].children_id, vec!["block_1"]);
+        assert_eq!(ids(&batches[1]), vec!["block_1", "block_2", "block_3"]);
+        assert_eq!(
+            batches[1].descendants[0].children,
+            vec!["block_2", "block_3"]
+        );
+        assert_eq!(batches[2].parent.as_deref(), Some("block_1"));
+        assert_eq!(batches[2].children_id, vec!["block_4", "block_5"]);
+        assert_eq!(batches[3].parent, None);
+        assert_eq!(batches[3].children_id, vec!["block_6"]);
+
+        // 临时 ID 与一次创建所有块时一致
+        let request = builder.blocks_request("doxcn_doc").unwrap();
+        let all: Vec<String> = batches.iter().flat_map(ids).collect();
+        let expected: Vec<String> = request
+            .descendants
+            .iter()
+            .map(|block| block.block_id.clone().unwrap())
+            .collect();
+        assert_eq!(all, expected);
+
+        // 不超过限制时只有一批
+        let batches = builder.batches(MAX_DESCENDANTS);
+        assert_eq!(batches.len(), 1);
+        assert_eq!(batches[0].children_id, request.children_id);
+        assert_eq!(batches[0].descendants, request.descendants);
+    }
+
+    #[cfg(feature = "async")]
+    #[tokio::test]
+    async fn create_large_document() {
+        let (address, handle) = crate::test_util::serve_all(vec![
+            r#"{"code":0,"msg":"ok","data":{"document":{"document_id":"doxcn_doc","revision_id":1,"title":"报告"}}}"#,
+            r#"{"code":0,"msg":"ok","data":{"document_revision_id":2,"block_id_relations":[{"temporary_block_id":"block_0","block_id":"doxcn_1"}]}}"#,
+            r#"{"code":0,"msg":"ok","data":{"document_revision_id":3,"block_id_relations":[{"temporary_block_id":"block_1000","block_id":"doxcn_2"}]}}"#,
+        ]);
+        let client = Client::builder().domain(address).build().unwrap();
+        let children = (0..MAX_DESCENDANTS)
+            .map(|i| Block::text(i.to_string()))
+            .collect();
+        let created = DocumentBuilder::new("报告")
+            .block(BlockTree::callout(Callout::new(), children))
+            .create(&client)
+            .await
+            .unwrap();
+        assert_eq!(created.document.revision_id, 3);
+        assert_eq!(created.block_ids["block_0"], "doxcn_1");
+        assert_eq!(created.block_ids["block_1000"], "doxcn_2");
+
+        let requests = handle.join().unwrap();
+        assert!(requests[1].starts_with(
+            "POST /open-apis/docx/v1/documents/doxcn_doc/blocks/doxcn_doc/descendant"
+        ));
+        // 放不下的子块插入到创建好的高亮块下
+        assert!(requests[2]
+            .starts_with("POST /open-apis/docx/v1/documents/doxcn_doc/blocks/doxcn_1/descendant"));
+        assert!(requests[2].contains(r#""children_id":["block_1000"]"#));
+    }
+
+    #[cfg(feature = "async")]
+    #[tokio::test]
+    async fn create_document() {
+        let (address, handle) = crate::test_util::serve_all(vec![
+            r#"{"code":0,"msg":"ok","data":{"document":{"document_id":"doxcn_doc","revision_id":1,"title":"报告"}}}"#,
+            r#"{"code":0,"msg":"ok","data":{"children":[],"document_revision_id":2,"block_id_relations":[{"temporary_block_id":"block_0","block_id":"doxcn_1"}]}}"#,
+        ]);
+        let client = Client::builder().domain(address).build().unwrap();
+        let created = DocumentBuilder::new("报告")
+            .folder_token("fld_1")
+            .block(Block::image(Image::default()))
+            .create(&client)
+            .await
+            .unwrap();
+        assert_eq!(created.document.revision_id, 2);
+        assert_eq!(created.block_ids["block_0"], "doxcn_1");
+
+        let requests = handle.join().unwrap();
+        assert!(requests[0].starts_with("POST /open-apis/docx/v1/documents "));
+        assert!(requests[0].contains(r#""folder_token":"fld_1""#));
+        assert!(requests[1].starts_with(
+            "POST /open-apis/docx/v1/documents/doxcn_doc/blocks/doxcn_doc/descendant?document_revision_id=-1 "
+        ));
+        assert!(requests[1].contains(r#""image":{}"#));
+    }
+}
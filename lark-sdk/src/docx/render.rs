@@ -0,0 +1,523 @@
+//! 将文档转换为 Markdown 或纯文本，用于搜索、知识库等索引场景。
+//!
+//! [`to_markdown`] 和 [`to_plain_text`] 从 [`ListDocumentBlocks`] 返回的所有块中找到根块，再按照子块的顺序
+//! 渲染。不支持的块（如多维表格、思维笔记）只渲染它们的子块；图片渲染为图片的 token，需要自行下载素材。
+//!
+//! ```no_run
+//! use lark_sdk::docx::render;
+//!
+//! # async fn run(client: lark_sdk::Client) -> lark_sdk::Result<()> {
+//! let markdown = render::document_markdown(&client, "doxcnxxx").await?;
+//! // 不需要格式时直接使用 raw_content 接口
+//! let text = render::document_plain_text(&client, "doxcnxxx").await?;
+//! # Ok(())
+//! # }
+//! ```
+
+use std::collections::HashMap;
+
+use super::{Block, BlockType, TextBlock, TextElementStyle};
+#[cfg(feature = "async")]
+use super::{GetRawContent, ListDocumentBlocks};
+#[cfg(feature = "async")]
+use crate::{Client, Result};
+
+/// 获取文档的所有块
+#[cfg(feature = "async")]
+pub async fn fetch_blocks(client: &Client, document_id: &str) -> Result<Vec<Block>> {
+    client
+        .pages(ListDocumentBlocks::new(document_id))
+        .collect_all()
+        .await
+}
+
+/// 获取文档并转换为 Markdown
+#[cfg(feature = "async")]
+pub async fn document_markdown(client: &Client, document_id: &str) -> Result<String> {
+    Ok(to_markdown(&fetch_blocks(client, document_id).await?))
+}
+
+/// 获取文档的纯文本内容，由 raw_content 接口生成，不包含任何格式
+#[cfg(feature = "async")]
+pub async fn document_plain_text(client: &Client, document_id: &str) -> Result<String> {
+    let request = GetRawContent {
+        document_id: document_id.to_string(),
+        lang: None,
+    };
+    Ok(client.send(&request).await?.into_data()?.content)
+}
+
+/// 将文档的所有块转换为 Markdown，文档标题为一级标题，文本中的 Markdown 格式字符会被转义
+pub fn to_markdown(blocks: &[Block]) -> String {
+    Renderer::new(blocks, true).render()
+}
+
+/// 将文档的所有块转换为纯文本，每个块一段，表格的单元格以制表符分隔
+pub fn to_plain_text(blocks: &[Block]) -> String {
+    Renderer::new(blocks, false).render()
+}
+
+struct Renderer<'a> {
+    blocks: HashMap<&'a str, &'a Block>,
+    root: Option<&'a Block>,
+    markdown: bool,
+}
+
+impl<'a> Renderer<'a> {
+    fn new(blocks: &'a [Block], markdown: bool) -> Self {
+        let root = blocks
+            .iter()
+            .find(|block| block.block_type == BlockType::PAGE)
+            .or_else(|| blocks.first());
+        let blocks = blocks
+            .iter()
+            .filter_map(|block| Some((block.block_id.as_deref()?, block)))
+            .collect();
+        Renderer {
+            blocks,
+            root,
+            markdown,
+        }
+    }
+
+    fn render(&self) -> String {
+        let root = match self.root {
+            Some(root) => root,
+            None => return String::new(),
+        };
+        let mut output = self.render_block(root);
+        if !output.is_empty() {
+            output.push('\n');
+        }
+        output
+    }
+
+    fn children(&self, block: &'a Block) -> impl Iterator<Item = &'a Block> + '_ {
+        block
+            .children
+            .iter()
+            .filter_map(move |id| self.blocks.get(id.as_str()).copied())
+    }
+
+    /// 渲染所有子块，列表项之间只换行，其他块之间空一行
+    fn render_children(&self, block: &'a Block) -> String {
+        let mut output = String::new();
+        let mut previous_list = false;
+        for child in self.children(block) {
+            let chunk = self.render_block(child);
+            if chunk.is_empty() {
+                continue;
+            }
+            let list = is_list(child);
+            if !output.is_empty() {
+                output.push_str(if list && previous_list { "\n" } else { "\n\n" });
+            }
+            output.push_str(&chunk);
+            previous_list = list;
+        }
+        output
+    }
+
+    fn render_block(&self, block: &'a Block) -> String {
+        let text = |text: &TextBlock| self.inline(text);
+        match block.block_type {
+            BlockType::PAGE => {
+                let title = block.page.as_ref().map(text).unwrap_or_default();
+                let children = self.render_children(block);
+                match (title.is_empty(), self.markdown) {
+                    (true, _) => children,
+                    (false, markdown) => {
+                        let title = if markdown {
+                            format!("# {}", title)
+                        } else {
+                            title
+                        };
+                        join_paragraphs(title, children)
+                    }
+                }
+            }
+            BlockType::BULLET | BlockType::ORDERED | BlockType::TODO => {
+                let content = block.text_block().map(text).unwrap_or_default();
+                let marker = match (self.markdown, block.block_type) {
+                    (false, _) => "",
+                    (true, BlockType::BULLET) => "- ",
+                    (true, BlockType::ORDERED) => "1. ",
+                    (true, _) if is_done(block) => "- [x] ",
+                    (true, _) => "- [ ] ",
+                };
+                let item = format!("{}{}", marker, content);
+                let children = self.render_children(block);
+                if children.is_empty() {
+                    item
+                } else {
+                    let indent = if self.markdown { "  " } else { "" };
+                    format!("{}\n{}", item, prefix_lines(&children, indent))
+                }
+            }
+            BlockType::CODE => {
+                let code = block.code.as_ref().map(TextBlock::plain_text);
+                let code = code.unwrap_or_default();
+                if self.markdown {
+                    format!("```\n{}\n```", code)
+                } else {
+                    code
+                }
+            }
+            BlockType::QUOTE => {
+                let content = block.quote.as_ref().map(text).unwrap_or_default();
+                self.quoted(content)
+            }
+            BlockType::QUOTE_CONTAINER | BlockType::CALLOUT => {
+                self.quoted(self.render_children(block))
+            }
+            BlockType::DIVIDER if self.markdown => "---".to_string(),
+            BlockType::DIVIDER => String::new(),
+            BlockType::IMAGE => match block.image.as_ref().and_then(|image| image.token.as_ref()) {
+                Some(token) if self.markdown => format!("![]({})", token),
+                _ => String::new(),
+            },
+            BlockType::TABLE => self.table(block),
+            _ => {
+                let content = block.text_block().map(text).unwrap_or_default();
+                let content = match heading_level(block.block_type) {
+                    // Markdown 只有六级标题，七到九级标题渲染为六级
+                    Some(level) if self.markdown && !content.is_empty() => {
+                        format!("{} {}", "#".repeat(level.min(6)), content)
+                    }
+                    _ => content,
+                };
+                join_paragraphs(content, self.render_children(block))
+            }
+        }
+    }
+
+    fn quoted(&self, content: String) -> String {
+        if self.markdown && !content.is_empty() {
+            prefix_lines(&content, "> ")
+        } else {
+            content
+        }
+    }
+
+    /// 表格按行渲染，Markdown 表格总是以第一行作为表头
+    fn table(&self, block: &'a Block) -> String {
+        let table = match &block.table {
+            Some(table) => table,
+            None => return String::new(),
+        };
+        let columns = table.property.column_size.max(1) as usize;
+        let (separator, line_break) = if self.markdown {
+            (" | ", "<br>")
+        } else {
+            ("\t", " ")
+        };
+        let rows: Vec<String> = table
+            .cells
+            .chunks(columns)
+            .map(|row| {
+                let cells: Vec<String> = row
+                    .iter()
+                    .map(|id| match self.blocks.get(id.as_str()) {
+                        Some(cell) => self.render_children(cell).replace("\n\n", "\n"),
+                        None => String::new(),
+                    })
+                    .map(|cell| {
+                        let cell = cell.replace('\n', line_break);
+                        if self.markdown {
+                            cell.replace('|', "\\|")
+                        } else {
+                            cell
+                        }
+                    })
+                    .collect();
+                cells.join(separator)
+            })
+            .collect();
+        if !self.markdown {
+            return rows.join("\n");
+        }
+        let mut lines = Vec::with_capacity(rows.len() + 1);
+        for (index, row) in rows.iter().enumerate() {
+            lines.push(format!("| {} |", row));
+            if index == 0 {
+                lines.push(format!("|{}", " --- |".repeat(columns)));
+            }
+        }
+        lines.join("\n")
+    }
+
+    fn inline(&self, text: &TextBlock) -> String {
+        let mut output = String::new();
+        for element in &text.elements {
+            if let Some(run) = &element.text_run {
+                if !self.markdown {
+                    output.push_str(&run.content);
+                    continue;
+                }
+                let line_start = output.is_empty() || output.ends_with('\n');
+                match &run.text_element_style {
+                    Some(style) => output.push_str(&styled(&run.content, style, line_start)),
+                    None => output.push_str(&escape(&run.content, line_start)),
+                }
+            } else if let Some(mention) = &element.mention_user {
+                output.push('@');
+                output.push_str(&mention.user_id);
+            }
+        }
+        output
+    }
+}
+
+fn styled(content: &str, style: &TextElementStyle, line_start: bool) -> String {
+    if content.trim().is_empty() {
+        return content.to_string();
+    }
+    let mut output = if style.inline_code {
+        code_span(content)
+    } else {
+        escape(content, line_start)
+    };
+    if style.bold {
+        output = format!("**{}**", output);
+    }
+    if style.italic {
+        output = format!("*{}*", output);
+    }
+    if style.strikethrough {
+        output = format!("~~{}~~", output);
+    }
+    if let Some(link) = &style.link {
+        output = format!("[{}]({})", output, percent_decode(&link.url));
+    }
+    output
+}
+
+/// 行内代码，使用比文本中最长的连续反引号更长的反引号包围；文本以反引号开始或结束时两侧加上
+/// 空格，两侧都是空格时也需要再加上一对空格，Markdown 解析时会去掉一对空格
+fn code_span(content: &str) -> String {
+    let mut longest = 0;
+    let mut run = 0;
+    for c in content.chars() {
+        run = if c == '`' { run + 1 } else { 0 };
+        longest = longest.max(run);
+    }
+    let fence = "`".repeat(longest + 1);
+    let pad = content.starts_with('`')
+        || content.ends_with('`')
+        || (content.starts_with(' ') && content.ends_with(' '));
+    if pad {
+        format!("{} {} {}", fence, content, fence)
+    } else {
+        format!("{}{}{}", fence, content, fence)
+    }
+}
+
+/// 转义文本中的 Markdown 格式字符，`line_start` 表示文本从一行的开头开始，此时还需要转义标题、
+/// 列表、引用等只在行首生效的标记
+fn escape(text: &str, line_start: bool) -> String {
+    let mut output = String::with_capacity(text.len());
+    for (index, line) in text.split('\n').enumerate() {
+        let mut rest = line;
+        if index > 0 {
+            output.push('\n');
+        }
+        if index > 0 || line_start {
+            let trimmed = line.trim_start();
+            output.push_str(&line[..line.len() - trimmed.len()]);
+            let digits = trimmed.bytes().take_while(u8::is_ascii_digit).count();
+            if trimmed.starts_with(['#', '>', '-', '+', '=']) {
+                output.push('\\');
+                rest = trimmed;
+            } else if digits > 0 && trimmed[digits..].starts_with(['.', ')']) {
+                output.push_str(&trimmed[..digits]);
+                output.push('\\');
+                rest = &trimmed[digits..];
+            } else {
+                rest = trimmed;
+            }
+        }
+        for c in rest.chars() {
+            if matches!(c, '\\' | '`' | '*' | '_' | '[' | ']' | '<' | '~') {
+                output.push('\\');
+            }
+            output.push(c);
+        }
+    }
+    output
+}
+
+fn is_list(block: &Block) -> bool {
+    matches!(
+        block.block_type,
+        BlockType::BULLET | BlockType::ORDERED | BlockType::TODO
+    )
+}
+
+fn is_done(block: &Block) -> bool {
+    block
+        .todo
+        .as_ref()
+        .and_then(|todo| todo.style.as_ref())
+        .and_then(|style| style.get("done"))
+        .and_then(serde_json::Value::as_bool)
+        .unwrap_or(false)
+}
+
+fn heading_level(block_type: BlockType) -> Option<usize> {
+    let level = block_type.0 - BlockType::HEADING1.0 + 1;
+    if (1..=9).contains(&level) {
+        Some(level as usize)
+    } else {
+        None
+    }
+}
+
+fn join_paragraphs(first: String, second: String) -> String {
+    match (first.is_empty(), second.is_empty()) {
+        (_, true) => first,
+        (true, false) => second,
+        (false, false) => format!("{}\n\n{}", first, second),
+    }
+}
+
+fn prefix_lines(content: &str, prefix: &str) -> String {
+    content
+        .lines()
+        .map(|line| format!("{}{}", prefix, line).trim_end().to_string())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// 链接的 `url` 是 URL 编码后的，解码失败时原样返回
+fn percent_decode(input: &str) -> String {
+    let bytes = input.as_bytes();
+    let mut output = Vec::with_capacity(bytes.len());
+    let mut index = 0;
+    while index < bytes.len() {
+        if bytes[index] == b'%' && index + 2 < bytes.len() {
+            let high = (bytes[index + 1] as char).to_digit(16);
+            let low = (bytes[index + 2] as char).to_digit(16);
+            if let (Some(high), Some(low)) = (high, low) {
+                output.push((high * 16 + low) as u8);
+                index += 3;
+                continue;
+            }
+        }
+        output.push(bytes[index]);
+        index += 1;
+    }
+    String::from_utf8(output).unwrap_or_else(|_| input.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn document() -> Vec<Block> {
+        serde_json::from_value(serde_json::json!([
+            {"block_id": "doc", "block_type": 1, "children": ["h", "p", "l1", "l2", "t", "c", "q", "d"],
+             "page": {"elements": [{"text_run": {"content": "周报"}}]}},
+            {"block_id": "h", "parent_id": "doc", "block_type": 4,
+             "heading2": {"elements": [{"text_run": {"content": "进展"}}]}},
+            {"block_id": "p", "parent_id": "doc", "block_type": 2, "text": {"elements": [
+                {"text_run": {"content": "完成 "}},
+                {"text_run": {"content": "3 个", "text_element_style": {"bold": true}}},
+                {"text_run": {"content": "需求，见", "text_element_style": {}}},
+                {"text_run": {"content": "文档", "text_element_style": {"link": {"url": "https%3A%2F%2Fexample.com%2Fdoc"}}}},
+                {"mention_user": {"user_id": "ou_1"}}
+            ]}},
+            {"block_id": "l1", "parent_id": "doc", "block_type": 12, "children": ["l1a"],
+             "bullet": {"elements": [{"text_run": {"content": "登录"}}]}},
+            {"block_id": "l1a", "parent_id": "l1", "block_type": 17,
+             "todo": {"elements": [{"text_run": {"content": "修复"}}], "style": {"done": true}}},
+            {"block_id": "l2", "parent_id": "doc", "block_type": 13,
+             "ordered": {"elements": [{"text_run": {"content": "发布"}}]}},
+            {"block_id": "t", "parent_id": "doc", "block_type": 31, "children": ["c1", "c2", "c3", "c4"],
+             "table": {"cells": ["c1", "c2", "c3", "c4"], "property": {"row_size": 2, "column_size": 2}}},
+            {"block_id": "c1", "block_type": 32, "children": ["c1t"], "table_cell": {}},
+            {"block_id": "c1t", "block_type": 2, "text": {"elements": [{"text_run": {"content": "服务"}}]}},
+            {"block_id": "c2", "block_type": 32, "children": ["c2t"], "table_cell": {}},
+            {"block_id": "c2t", "block_type": 2, "text": {"elements": [{"text_run": {"content": "状态"}}]}},
+            {"block_id": "c3", "block_type": 32, "children": ["c3t"], "table_cell": {}},
+            {"block_id": "c3t", "block_type": 2, "text": {"elements": [{"text_run": {"content": "a|b"}}]}},
+            {"block_id": "c4", "block_type": 32, "children": [], "table_cell": {}},
+            {"block_id": "c", "parent_id": "doc", "block_type": 14,
+             "code": {"elements": [{"text_run": {"content": "cargo build"}}], "style": {"language": 49}}},
+            {"block_id": "q", "parent_id": "doc", "block_type": 19, "children": ["qt"], "callout": {"emoji_id": "bulb"}},
+            {"block_id": "qt", "parent_id": "q", "block_type": 2, "text": {"elements": [{"text_run": {"content": "提示"}}]}},
+            {"block_id": "d", "parent_id": "doc", "block_type": 22, "divider": {}}
+        ]))
+        .unwrap()
+    }
+
+    #[test]
+    fn markdown() {
+        assert_eq!(
+            to_markdown(&document()),
+            "# 周报\n\n\
+             ## 进展\n\n\
+             完成 **3 个**需求，见[文档](https://example.com/doc)@ou_1\n\n\
+             - 登录\n  - [x] 修复\n1. 发布\n\n\
+             | 服务 | 状态 |\n| --- | --- |\n| a\\|b |  |\n\n\
+             ```\ncargo build\n```\n\n\
+             > 提示\n\n\
+             ---\n"
+        );
+    }
+
+    #[test]
+    fn plain_text() {
+        assert_eq!(
+            to_plain_text(&document()),
+            "周报\n\n进展\n\n完成 3 个需求，见文档@ou_1\n\n登录\n修复\n发布\n\n服务\t状态\na|b\t\n\ncargo build\n\n提示\n"
+        );
+    }
+
+    #[test]
+    fn markdown_escape() {
+        let blocks: Vec<Block> = serde_json::from_value(serde_json::json!([
+            {"block_id": "doc", "block_type": 1, "children": ["h7", "p1", "p2", "p3"]},
+            {"block_id": "h7", "parent_id": "doc", "block_type": 9,
+             "heading7": {"elements": [{"text_run": {"content": "附录"}}]}},
+            {"block_id": "p1", "parent_id": "doc", "block_type": 2, "text": {"elements": [
+                {"text_run": {"content": "# 不是标题 *a* _b_ [c]"}}
+            ]}},
+            {"block_id": "p2", "parent_id": "doc", "block_type": 2, "text": {"elements": [
+                {"text_run": {"content": "- 不是列表\n1. 也不是"}}
+            ]}},
+            {"block_id": "p3", "parent_id": "doc", "block_type": 2, "text": {"elements": [
+                {"text_run": {"content": "a_b", "text_element_style": {"bold": true}}},
+                {"text_run": {"content": "a_b", "text_element_style": {"inline_code": true}}}
+            ]}}
+        ]))
+        .unwrap();
+        assert_eq!(
+            to_markdown(&blocks),
+            "###### 附录\n\n\
+             \\# 不是标题 \\*a\\* \\_b\\_ \\[c\\]\n\n\
+             \\- 不是列表\n1\\. 也不是\n\n\
+             **a\\_b**`a_b`\n"
+        );
+    }
+
+    #[test]
+    fn inline_code_fence() {
+        assert_eq!(code_span("a_b"), "`a_b`");
+        assert_eq!(code_span("a`b"), "``a`b``");
+        assert_eq!(code_span("a``b`c"), "```a``b`c```");
+        assert_eq!(code_span("`a`"), "`` `a` ``");
+        assert_eq!(code_span("a`"), "`` a` ``");
+        assert_eq!(code_span(" a "), "`  a  `");
+        assert_eq!(code_span(" a"), "` a`");
+    }
+
+    #[test]
+    fn decode_link() {
+        assert_eq!(
+            percent_decode("https%3A%2F%2Fexample.com%2F%E6%96%87"),
+            "https://example.com/文"
+        );
+        assert_eq!(percent_decode("100%"), "100%");
+        assert_eq!(percent_decode("%zz"), "%zz");
+    }
+}
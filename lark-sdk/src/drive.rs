@@ -0,0 +1,26 @@
+//! 云文档。
+//!
+//! 文档、电子表格等云文档的评论见 [`comment`]，图片和附件等素材见 [`media`]，将本地文件导入为云文档见
+//! [`import_task`]，文档的内容见 [`docx`](crate::docx)。
+
+pub mod comment;
+pub mod import_task;
+pub mod media;
+
+use serde::{Deserialize, Serialize};
+
+/// 云文档的类型
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, lark_sdk::RequestSerialize,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum FileType {
+    /// 旧版文档
+    Doc,
+    /// 新版文档
+    Docx,
+    /// 电子表格
+    Sheet,
+    /// 上传的文件
+    File,
+}
@@ -0,0 +1,447 @@
+//! 云文档的评论。
+//!
+//! 评论是一组回复，第一条回复为评论的内容。开放平台只能添加全文评论，不能添加划词评论；
+//! 新的评论可以订阅 [`CommentAddEvent`](crate::event::drive::CommentAddEvent) 获得。
+//!
+//! ```no_run
+//! use lark_sdk::drive::comment::{CommentContent, CreateComment, SolveComment};
+//! use lark_sdk::drive::FileType;
+//!
+//! # async fn run(client: lark_sdk::Client) -> lark_sdk::Result<()> {
+//! let comment = CreateComment::new("doxcnxxx", FileType::Docx, CommentContent::text("请补充测试结果"));
+//! let comment = client.send(&comment).await?.into_data()?;
+//! let comment_id = comment.comment_id.unwrap_or_default();
+//! client.send(&SolveComment::new("doxcnxxx", FileType::Docx, comment_id, true)).await?.into_data()?;
+//! # Ok(())
+//! # }
+//! ```
+
+use serde::{Deserialize, Serialize};
+
+use super::FileType;
+use crate::enums::UserIdType;
+use crate::time::{self, LarkTimestamp};
+use crate::ListData;
+
+/// 评论或回复的内容
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CommentContent {
+    #[serde(default)]
+    pub elements: Vec<CommentElement>,
+}
+
+impl CommentContent {
+    /// 只包含文本的内容
+    pub fn text(text: impl Into<String>) -> Self {
+        CommentContent::default().push_text(text)
+    }
+
+    /// 追加文本
+    pub fn push_text(mut self, text: impl Into<String>) -> Self {
+        self.elements.push(CommentElement {
+            element_type: "text_run".to_string(),
+            text_run: Some(CommentText { text: text.into() }),
+            docs_link: None,
+            person: None,
+        });
+        self
+    }
+
+    /// 追加 @ 用户
+    pub fn push_person(mut self, user_id: impl Into<String>) -> Self {
+        self.elements.push(CommentElement {
+            element_type: "person".to_string(),
+            text_run: None,
+            docs_link: None,
+            person: Some(CommentPerson {
+                user_id: user_id.into(),
+            }),
+        });
+        self
+    }
+
+    /// 所有文本拼接后的内容，云文档链接使用链接地址，@ 用户使用 `@user_id`
+    pub fn plain_text(&self) -> String {
+        let mut output = String::new();
+        for element in &self.elements {
+            if let Some(text) = &element.text_run {
+                output.push_str(&text.text);
+            } else if let Some(link) = &element.docs_link {
+                output.push_str(&link.url);
+            } else if let Some(person) = &element.person {
+                output.push('@');
+                output.push_str(&person.user_id);
+            }
+        }
+        output
+    }
+}
+
+/// 内容中的一段
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CommentElement {
+    /// `text_run`、`docs_link` 或 `person`
+    #[serde(rename = "type")]
+    pub element_type: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub text_run: Option<CommentText>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub docs_link: Option<CommentDocsLink>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub person: Option<CommentPerson>,
+}
+
+/// 文本
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CommentText {
+    pub text: String,
+}
+
+/// 云文档链接
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CommentDocsLink {
+    pub url: String,
+}
+
+/// @ 用户，ID 类型与请求的 `user_id_type` 一致
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CommentPerson {
+    pub user_id: String,
+}
+
+/// 评论的一条回复
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CommentReply {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reply_id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub user_id: Option<String>,
+    #[serde(
+        default,
+        with = "time::seconds::option",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub create_time: Option<LarkTimestamp>,
+    #[serde(
+        default,
+        with = "time::seconds::option",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub update_time: Option<LarkTimestamp>,
+    pub content: CommentContent,
+}
+
+impl CommentReply {
+    /// 创建评论时使用的回复
+    pub fn new(content: CommentContent) -> Self {
+        CommentReply {
+            reply_id: None,
+            user_id: None,
+            create_time: None,
+            update_time: None,
+            content,
+        }
+    }
+}
+
+/// 评论的回复列表
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ReplyList {
+    #[serde(default)]
+    pub replies: Vec<CommentReply>,
+}
+
+/// 云文档的评论
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, lark_sdk::Response)]
+pub struct FileComment {
+    #[serde(default)]
+    pub comment_id: Option<String>,
+    /// 评论者
+    #[serde(default)]
+    pub user_id: Option<String>,
+    #[serde(default, with = "time::seconds::option")]
+    pub create_time: Option<LarkTimestamp>,
+    #[serde(default, with = "time::seconds::option")]
+    pub update_time: Option<LarkTimestamp>,
+    #[serde(default)]
+    pub is_solved: bool,
+    #[serde(default, with = "time::seconds::option")]
+    pub solved_time: Option<LarkTimestamp>,
+    #[serde(default)]
+    pub solver_user_id: Option<String>,
+    /// 是否为全文评论
+    #[serde(default)]
+    pub is_whole: Option<bool>,
+    /// 划词评论引用的文字
+    #[serde(default)]
+    pub quote: Option<String>,
+    /// 回复列表，回复较多时只包含第一页，其余的使用 [`ListCommentReplies`] 获取
+    #[serde(default)]
+    pub reply_list: Option<ReplyList>,
+    /// 是否还有更多回复
+    #[serde(default)]
+    pub has_more: bool,
+    #[serde(default)]
+    pub page_token: Option<String>,
+}
+
+impl FileComment {
+    /// 评论的所有回复，第一条为评论的内容
+    pub fn replies(&self) -> &[CommentReply] {
+        self.reply_list
+            .as_ref()
+            .map(|list| list.replies.as_slice())
+            .unwrap_or_default()
+    }
+}
+
+/// 获取云文档的评论
+#[derive(Debug, Clone, lark_sdk::Request)]
+#[request(
+    method = "GET",
+    url = "/open-apis/drive/v1/files/:file_token/comments",
+    auth = "tenant",
+    validate,
+    response = ListData<FileComment>
+)]
+pub struct ListComments {
+    #[request(path)]
+    pub file_token: String,
+    #[request(query)]
+    pub file_type: FileType,
+    /// 只获取全文评论或划词评论
+    #[request(query)]
+    pub is_whole: Option<bool>,
+    /// 只获取已解决或未解决的评论
+    #[request(query)]
+    pub is_solved: Option<bool>,
+    #[request(query)]
+    pub page_size: Option<u32>,
+    #[request(query, page_token)]
+    pub page_token: Option<String>,
+    /// 返回的用户 ID 类型，如 `open_id`
+    #[request(query)]
+    pub user_id_type: Option<UserIdType>,
+}
+
+impl ListComments {
+    /// 获取所有评论的第一页
+    pub fn new(file_token: impl Into<String>, file_type: FileType) -> Self {
+        ListComments {
+            file_token: file_token.into(),
+            file_type,
+            is_whole: None,
+            is_solved: None,
+            page_size: None,
+            page_token: None,
+            user_id_type: None,
+        }
+    }
+}
+
+/// 获取一条评论
+#[derive(Debug, Clone, lark_sdk::Request)]
+#[request(
+    method = "GET",
+    url = "/open-apis/drive/v1/files/:file_token/comments/:comment_id",
+    auth = "tenant",
+    validate,
+    response = FileComment
+)]
+pub struct GetComment {
+    #[request(path)]
+    pub file_token: String,
+    #[request(path)]
+    pub comment_id: String,
+    #[request(query)]
+    pub file_type: FileType,
+    #[request(query)]
+    pub user_id_type: Option<UserIdType>,
+}
+
+/// 添加全文评论
+#[derive(Debug, Clone, lark_sdk::Request)]
+#[request(
+    method = "POST",
+    url = "/open-apis/drive/v1/files/:file_token/comments",
+    auth = "tenant",
+    validate,
+    response = FileComment
+)]
+pub struct CreateComment {
+    #[request(path)]
+    pub file_token: String,
+    #[request(query)]
+    pub file_type: FileType,
+    #[request(query)]
+    pub user_id_type: Option<UserIdType>,
+    /// 只需要包含一条回复，即评论的内容
+    #[request(body)]
+    pub reply_list: ReplyList,
+}
+
+impl CreateComment {
+    /// 添加内容为 `content` 的全文评论
+    pub fn new(
+        file_token: impl Into<String>,
+        file_type: FileType,
+        content: CommentContent,
+    ) -> Self {
+        CreateComment {
+            file_token: file_token.into(),
+            file_type,
+            user_id_type: None,
+            reply_list: ReplyList {
+                replies: vec![CommentReply::new(content)],
+            },
+        }
+    }
+}
+
+/// 解决或恢复评论
+#[derive(Debug, Clone, lark_sdk::Request)]
+#[request(
+    method = "PATCH",
+    url = "/open-apis/drive/v1/files/:file_token/comments/:comment_id",
+    auth = "tenant",
+    validate,
+    empty
+)]
+pub struct SolveComment {
+    #[request(path)]
+    pub file_token: String,
+    #[request(path)]
+    pub comment_id: String,
+    #[request(query)]
+    pub file_type: FileType,
+    /// 为 `false` 时恢复已解决的评论
+    #[request(body)]
+    pub is_solved: bool,
+}
+
+impl SolveComment {
+    pub fn new(
+        file_token: impl Into<String>,
+        file_type: FileType,
+        comment_id: impl Into<String>,
+        is_solved: bool,
+    ) -> Self {
+        SolveComment {
+            file_token: file_token.into(),
+            comment_id: comment_id.into(),
+            file_type,
+            is_solved,
+        }
+    }
+}
+
+/// 获取评论的回复
+#[derive(Debug, Clone, lark_sdk::Request)]
+#[request(
+    method = "GET",
+    url = "/open-apis/drive/v1/files/:file_token/comments/:comment_id/replies",
+    auth = "tenant",
+    validate,
+    response = ListData<CommentReply>
+)]
+pub struct ListCommentReplies {
+    #[request(path)]
+    pub file_token: String,
+    #[request(path)]
+    pub comment_id: String,
+    #[request(query)]
+    pub file_type: FileType,
+    #[request(query)]
+    pub page_size: Option<u32>,
+    #[request(query, page_token)]
+    pub page_token: Option<String>,
+    #[request(query)]
+    pub user_id_type: Option<UserIdType>,
+}
+
+/// 更新回复的内容，只能更新应用自己添加的回复
+#[derive(Debug, Clone, lark_sdk::Request)]
+#[request(
+    method = "PUT",
+    url = "/open-apis/drive/v1/files/:file_token/comments/:comment_id/replies/:reply_id",
+    auth = "tenant",
+    validate,
+    empty
+)]
+pub struct UpdateCommentReply {
+    #[request(path)]
+    pub file_token: String,
+    #[request(path)]
+    pub comment_id: String,
+    #[request(path)]
+    pub reply_id: String,
+    #[request(query)]
+    pub file_type: FileType,
+    #[request(query)]
+    pub user_id_type: Option<UserIdType>,
+    #[request(body)]
+    pub content: CommentContent,
+}
+
+/// 删除回复，删除评论的所有回复后评论也会被删除
+#[derive(Debug, Clone, lark_sdk::Request)]
+#[request(
+    method = "DELETE",
+    url = "/open-apis/drive/v1/files/:file_token/comments/:comment_id/replies/:reply_id",
+    auth = "tenant",
+    validate,
+    empty
+)]
+pub struct DeleteCommentReply {
+    #[request(path)]
+    pub file_token: String,
+    #[request(path)]
+    pub comment_id: String,
+    #[request(path)]
+    pub reply_id: String,
+    #[request(query)]
+    pub file_type: FileType,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{BodyResponse, Request};
+
+    #[test]
+    fn create_comment() {
+        let request = CreateComment::new(
+            "doxcn_1",
+            FileType::Docx,
+            CommentContent::text("请看").push_person("ou_1"),
+        );
+        assert_eq!(request.url(), "/open-apis/drive/v1/files/doxcn_1/comments");
+        assert_eq!(
+            request.query().unwrap(),
+            vec![("file_type".to_string(), "docx".to_string())]
+        );
+        let body: serde_json::Value =
+            serde_json::from_slice(&request.body().unwrap().unwrap()).unwrap();
+        assert_eq!(
+            body,
+            serde_json::json!({"reply_list": {"replies": [{"content": {"elements": [
+                {"type": "text_run", "text_run": {"text": "请看"}},
+                {"type": "person", "person": {"user_id": "ou_1"}}
+            ]}}]}})
+        );
+    }
+
+    #[test]
+    fn list_comments() {
+        let response: BodyResponse<ListData<FileComment>> = serde_json::from_str(
+            r#"{"code":0,"msg":"success","data":{"has_more":false,"items":[{"comment_id":"6916106822734578184","user_id":"ou_1","create_time":1610281603,"update_time":1610281603,"is_solved":false,"is_whole":false,"quote":"划词","reply_list":{"replies":[{"reply_id":"6916106822734594568","user_id":"ou_1","create_time":1610281603,"update_time":1610281603,"content":{"elements":[{"type":"text_run","text_run":{"text":"需要修改"}},{"type":"docs_link","docs_link":{"url":"https://example.feishu.cn/docx/1"}}]}}]}}]}}"#,
+        )
+        .unwrap();
+        let comments = response.into_data().unwrap().items;
+        assert_eq!(comments[0].quote.as_deref(), Some("划词"));
+        assert_eq!(
+            comments[0].replies()[0].content.plain_text(),
+            "需要修改https://example.feishu.cn/docx/1"
+        );
+    }
+}
@@ -0,0 +1,329 @@
+//! 导入任务，将本地的 xlsx、docx、csv 等文件转换为云文档。
+//!
+//! 导入分为三步：以 [`ParentType::CcmImportOpen`] 上传素材、使用 [`CreateImportTask`] 创建导入任务、
+//! 使用 [`GetImportTask`] 查询任务直到完成。[`ImportFile::import`] 会依次完成这些步骤。
+//!
+//! ```no_run
+//! use std::time::Duration;
+//!
+//! use lark_sdk::drive::import_task::{ImportFile, ImportObjType};
+//!
+//! # async fn run(client: lark_sdk::Client, xlsx: Vec<u8>) -> lark_sdk::Result<()> {
+//! let result = ImportFile::new("销售数据.xlsx", xlsx, ImportObjType::Sheet)
+//!     .folder_token("fldcnxxx")
+//!     .timeout(Duration::from_secs(120))
+//!     .import(&client)
+//!     .await?;
+//! println!("{:?}", result.url);
+//! # Ok(())
+//! # }
+//! ```
+
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+use super::media::{ParentType, UploadMedia};
+#[cfg(feature = "async")]
+use crate::poll::poll_until_done;
+use crate::poll::Backoff;
+#[cfg(feature = "async")]
+use crate::{Client, LarkError, Result};
+
+/// 导入后的云文档类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ImportObjType {
+    /// 新版文档，支持 docx、doc、txt、md、html 等
+    Docx,
+    /// 电子表格，支持 xlsx、xls、csv
+    Sheet,
+    /// 多维表格，支持 xlsx、csv
+    Bitable,
+}
+
+impl ImportObjType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ImportObjType::Docx => "docx",
+            ImportObjType::Sheet => "sheet",
+            ImportObjType::Bitable => "bitable",
+        }
+    }
+}
+
+/// 导入后云文档的位置
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ImportPoint {
+    /// 1：云空间
+    pub mount_type: i32,
+    /// 文件夹的 token，为空时导入到云空间的根目录
+    pub mount_key: String,
+}
+
+impl ImportPoint {
+    /// 云空间中的文件夹
+    pub fn folder(folder_token: impl Into<String>) -> Self {
+        ImportPoint {
+            mount_type: 1,
+            mount_key: folder_token.into(),
+        }
+    }
+}
+
+/// 导入任务的 ID
+#[derive(Debug, Clone, Deserialize, lark_sdk::Response)]
+pub struct ImportTicket {
+    pub ticket: String,
+}
+
+/// 创建导入任务
+#[derive(Debug, Clone, lark_sdk::Request)]
+#[request(
+    method = "POST",
+    url = "/open-apis/drive/v1/import_tasks",
+    auth = "tenant",
+    response = ImportTicket
+)]
+pub struct CreateImportTask {
+    /// 文件的扩展名，如 `xlsx`
+    #[request(body)]
+    pub file_extension: String,
+    /// 上传的素材
+    #[request(body)]
+    pub file_token: String,
+    #[request(body = "type")]
+    pub obj_type: ImportObjType,
+    /// 导入后的文件名，为空时使用上传的文件名
+    #[request(body)]
+    pub file_name: Option<String>,
+    #[request(body)]
+    pub point: ImportPoint,
+}
+
+/// 导入任务的状态
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct ImportTaskResult {
+    #[serde(default)]
+    pub ticket: Option<String>,
+    #[serde(default, rename = "type")]
+    pub obj_type: Option<String>,
+    /// 0：成功，1：初始化，2：处理中，其他为失败
+    pub job_status: i32,
+    #[serde(default)]
+    pub job_error_msg: Option<String>,
+    /// 导入后云文档的 token
+    #[serde(default)]
+    pub token: Option<String>,
+    #[serde(default)]
+    pub url: Option<String>,
+    /// 导入时的提示，如部分内容不支持
+    #[serde(default)]
+    pub extra: Vec<String>,
+}
+
+impl ImportTaskResult {
+    /// 任务是否已经结束，成功或失败
+    pub fn is_finished(&self) -> bool {
+        !matches!(self.job_status, 1 | 2)
+    }
+
+    pub fn is_success(&self) -> bool {
+        self.job_status == 0
+    }
+}
+
+/// [`GetImportTask`] 返回的任务
+#[derive(Debug, Clone, Deserialize, lark_sdk::Response)]
+pub struct ImportTaskData {
+    pub result: ImportTaskResult,
+}
+
+/// 查询导入任务
+#[derive(Debug, Clone, lark_sdk::Request)]
+#[request(
+    method = "GET",
+    url = "/open-apis/drive/v1/import_tasks/:ticket",
+    auth = "tenant",
+    validate,
+    response = ImportTaskData
+)]
+pub struct GetImportTask {
+    #[request(path)]
+    pub ticket: String,
+}
+
+/// 导入一个文件
+#[derive(Debug, Clone)]
+pub struct ImportFile {
+    file_name: String,
+    file: Vec<u8>,
+    obj_type: ImportObjType,
+    point: ImportPoint,
+    backoff: Backoff,
+    timeout: Duration,
+}
+
+impl ImportFile {
+    /// 导入 `file`，文件类型由 `file_name` 的扩展名决定
+    pub fn new(file_name: impl Into<String>, file: Vec<u8>, obj_type: ImportObjType) -> Self {
+        ImportFile {
+            file_name: file_name.into(),
+            file,
+            obj_type,
+            point: ImportPoint::folder(""),
+            backoff: Backoff::default(),
+            timeout: Duration::from_secs(300),
+        }
+    }
+
+    /// 导入到文件夹 `folder_token`，默认为云空间的根目录
+    pub fn folder_token(mut self, folder_token: impl Into<String>) -> Self {
+        self.point = ImportPoint::folder(folder_token);
+        self
+    }
+
+    /// 查询任务的间隔，默认为 [`Backoff::default`]
+    pub fn backoff(mut self, backoff: Backoff) -> Self {
+        self.backoff = backoff;
+        self
+    }
+
+    /// 等待任务完成的时间，默认 5 分钟
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// 文件的扩展名，没有扩展名时为空
+    pub fn file_extension(&self) -> &str {
+        match self.file_name.rsplit_once('.') {
+            Some((_, extension)) => extension,
+            None => "",
+        }
+    }
+
+    /// 上传文件的请求
+    pub fn upload_request(&self) -> UploadMedia {
+        let extra = serde_json::json!({
+            "obj_type": self.obj_type.as_str(),
+            "file_extension": self.file_extension(),
+        });
+        let mut upload = UploadMedia::new(
+            self.file_name.clone(),
+            ParentType::CcmImportOpen,
+            "",
+            self.file.clone(),
+        );
+        upload.extra = Some(extra.to_string());
+        upload
+    }
+
+    /// 使用上传后的 `file_token` 创建导入任务的请求
+    pub fn create_request(&self, file_token: impl Into<String>) -> CreateImportTask {
+        let file_name = match self.file_name.rsplit_once('.') {
+            Some((name, _)) => name,
+            None => &self.file_name,
+        };
+        CreateImportTask {
+            file_extension: self.file_extension().to_string(),
+            file_token: file_token.into(),
+            obj_type: self.obj_type,
+            file_name: Some(file_name.to_string()),
+            point: self.point.clone(),
+        }
+    }
+
+    /// 上传文件、创建导入任务并等待任务结束。
+    ///
+    /// 导入失败时返回 [`LarkError::Api`]，`code` 为任务的 `job_status`；超过等待时间时返回
+    /// [`LarkError::Timeout`]，任务仍会在后台继续执行
+    #[cfg(feature = "async")]
+    pub async fn import(&self, client: &Client) -> Result<ImportTaskResult> {
+        let file_token = super::media::upload(client, &self.upload_request()).await?;
+        let ticket = client
+            .send(&self.create_request(file_token))
+            .await?
+            .into_data()?
+            .ticket;
+        let task = poll_until_done(
+            client,
+            || GetImportTask {
+                ticket: ticket.clone(),
+            },
+            |task: &ImportTaskData| task.result.is_finished(),
+            self.backoff,
+            self.timeout,
+        )
+        .await?
+        .result;
+        if task.is_success() {
+            return Ok(task);
+        }
+        Err(LarkError::Api {
+            code: i64::from(task.job_status),
+            msg: task
+                .job_error_msg
+                .unwrap_or_else(|| "import task failed".to_string()),
+            data: None,
+            log_id: None,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn import_file() {
+        let (address, handle) = crate::test_util::serve_all(vec![
+            r#"{"code":0,"msg":"ok","data":{"file_token":"boxcn_1"}}"#,
+            r#"{"code":0,"msg":"ok","data":{"ticket":"t1"}}"#,
+            r#"{"code":0,"msg":"ok","data":{"result":{"ticket":"t1","type":"sheet","job_status":2,"extra":[]}}}"#,
+            r#"{"code":0,"msg":"ok","data":{"result":{"ticket":"t1","type":"sheet","job_status":0,"job_error_msg":"success","token":"shtcn_1","url":"https://example.feishu.cn/sheets/shtcn_1","extra":["2000"]}}}"#,
+        ]);
+        let client = Client::builder().domain(address).build().unwrap();
+        let result = ImportFile::new("销售.xlsx", b"PK".to_vec(), ImportObjType::Sheet)
+            .folder_token("fldcn_1")
+            .backoff(Backoff::fixed(Duration::from_millis(1)))
+            .import(&client)
+            .await
+            .unwrap();
+        assert_eq!(result.token.as_deref(), Some("shtcn_1"));
+
+        let requests = handle.join().unwrap();
+        assert!(requests[0].contains("name=\"parent_type\"\r\n\r\nccm_import_open\r\n"));
+        assert!(requests[0].contains(
+            "name=\"extra\"\r\n\r\n{\"file_extension\":\"xlsx\",\"obj_type\":\"sheet\"}\r\n"
+        ));
+        let body: serde_json::Value =
+            serde_json::from_str(requests[1].split("\r\n\r\n").nth(1).unwrap()).unwrap();
+        assert_eq!(
+            body,
+            serde_json::json!({
+                "file_extension": "xlsx",
+                "file_token": "boxcn_1",
+                "type": "sheet",
+                "file_name": "销售",
+                "point": {"mount_type": 1, "mount_key": "fldcn_1"}
+            })
+        );
+        assert!(requests[3].starts_with("GET /open-apis/drive/v1/import_tasks/t1 "));
+    }
+
+    #[tokio::test]
+    async fn import_failed() {
+        let (address, _) = crate::test_util::serve_all(vec![
+            r#"{"code":0,"msg":"ok","data":{"file_token":"boxcn_1"}}"#,
+            r#"{"code":0,"msg":"ok","data":{"ticket":"t1"}}"#,
+            r#"{"code":0,"msg":"ok","data":{"result":{"job_status":108,"job_error_msg":"file too large"}}}"#,
+        ]);
+        let client = Client::builder().domain(address).build().unwrap();
+        let err = ImportFile::new("a.docx", Vec::new(), ImportObjType::Docx)
+            .import(&client)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, LarkError::Api { code: 108, .. }), "{:?}", err);
+    }
+}
@@ -0,0 +1,406 @@
+//! 云文档的素材。
+//!
+//! 素材是文档、电子表格、多维表格中的图片和附件，与消息中的图片、文件不同，需要使用这里的接口上传。
+//! 上传后得到的 `file_token` 可以用于文档的图片块（见 [`ReplaceImage`](crate::docx::ReplaceImage)）
+//! 或多维表格的附件字段。
+//!
+//! 不超过 20 MB 的素材使用 [`UploadMedia`] 一次上传，更大的素材需要依次调用 [`PrepareMediaUpload`]、
+//! [`UploadMediaPart`] 和 [`FinishMediaUpload`] 分片上传，[`upload`] 会根据大小自动选择。
+//!
+//! ```no_run
+//! use lark_sdk::docx::ReplaceImage;
+//! use lark_sdk::drive::media::{self, UploadMedia};
+//!
+//! # async fn run(client: lark_sdk::Client, png: Vec<u8>) -> lark_sdk::Result<()> {
+//! // 上传到文档 doxcnxxx 中已经创建的图片块 doxcnimg
+//! let upload = UploadMedia::docx_image("doxcnxxx", "doxcnimg", "chart.png", png);
+//! let file_token = media::upload(&client, &upload).await?;
+//! let request = ReplaceImage::new("doxcnxxx", "doxcnimg", file_token);
+//! client.send(&request).await?.into_data()?;
+//! # Ok(())
+//! # }
+//! ```
+
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use crate::multipart::Boundary;
+#[cfg(feature = "async")]
+use crate::Client;
+use crate::{AuthType, BodyResponse, Method, Request, Result};
+
+/// 一次上传的大小上限
+pub const UPLOAD_ALL_LIMIT: usize = 20 * 1024 * 1024;
+
+/// 素材所在的位置
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ParentType {
+    /// 旧版文档的图片
+    DocImage,
+    /// 旧版文档的附件
+    DocFile,
+    /// 新版文档的图片，`parent_node` 为图片块的 ID
+    DocxImage,
+    /// 新版文档的附件，`parent_node` 为附件块的 ID
+    DocxFile,
+    /// 电子表格的图片
+    SheetImage,
+    /// 电子表格的附件
+    SheetFile,
+    /// 多维表格的图片，`parent_node` 为多维表格的 `app_token`
+    BitableImage,
+    /// 多维表格的附件，`parent_node` 为多维表格的 `app_token`
+    BitableFile,
+    /// 导入任务的源文件，`parent_node` 为空，见 [`import_task`](super::import_task)
+    CcmImportOpen,
+}
+
+impl ParentType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ParentType::DocImage => "doc_image",
+            ParentType::DocFile => "doc_file",
+            ParentType::DocxImage => "docx_image",
+            ParentType::DocxFile => "docx_file",
+            ParentType::SheetImage => "sheet_image",
+            ParentType::SheetFile => "sheet_file",
+            ParentType::BitableImage => "bitable_image",
+            ParentType::BitableFile => "bitable_file",
+            ParentType::CcmImportOpen => "ccm_import_open",
+        }
+    }
+}
+
+/// 新版文档的素材需要在 `extra` 中指定所在的文档
+fn docx_extra(document_id: &str) -> String {
+    serde_json::json!({ "drive_route_token": document_id }).to_string()
+}
+
+/// 上传的素材
+#[derive(Debug, Clone, Deserialize, lark_sdk::Response)]
+pub struct MediaToken {
+    pub file_token: String,
+}
+
+/// 一次上传素材，`file` 不能超过 [`UPLOAD_ALL_LIMIT`]
+#[derive(Clone)]
+pub struct UploadMedia {
+    pub file_name: String,
+    pub parent_type: ParentType,
+    /// 素材所在的节点，如图片块的 ID、多维表格的 `app_token`
+    pub parent_node: String,
+    /// 额外的参数，JSON 字符串，新版文档的素材需要指定 `drive_route_token`
+    pub extra: Option<String>,
+    pub file: Vec<u8>,
+    boundary: Boundary,
+}
+
+impl UploadMedia {
+    /// 替换路径参数之前的请求路径
+    pub const ENDPOINT: &'static str = "/open-apis/drive/v1/medias/upload_all";
+
+    pub fn new(
+        file_name: impl Into<String>,
+        parent_type: ParentType,
+        parent_node: impl Into<String>,
+        file: Vec<u8>,
+    ) -> Self {
+        UploadMedia {
+            file_name: file_name.into(),
+            parent_type,
+            parent_node: parent_node.into(),
+            extra: None,
+            file,
+            boundary: Boundary::new(),
+        }
+    }
+
+    /// 上传到新版文档 `document_id` 的图片块 `block_id`
+    pub fn docx_image(
+        document_id: &str,
+        block_id: impl Into<String>,
+        file_name: impl Into<String>,
+        file: Vec<u8>,
+    ) -> Self {
+        UploadMedia {
+            extra: Some(docx_extra(document_id)),
+            ..UploadMedia::new(file_name, ParentType::DocxImage, block_id, file)
+        }
+    }
+
+    /// 上传到新版文档 `document_id` 的附件块 `block_id`
+    pub fn docx_file(
+        document_id: &str,
+        block_id: impl Into<String>,
+        file_name: impl Into<String>,
+        file: Vec<u8>,
+    ) -> Self {
+        UploadMedia {
+            extra: Some(docx_extra(document_id)),
+            ..UploadMedia::new(file_name, ParentType::DocxFile, block_id, file)
+        }
+    }
+
+    /// 上传为多维表格 `app_token` 的附件，用于附件字段
+    pub fn bitable_file(
+        app_token: impl Into<String>,
+        file_name: impl Into<String>,
+        file: Vec<u8>,
+    ) -> Self {
+        UploadMedia::new(file_name, ParentType::BitableFile, app_token, file)
+    }
+
+    /// 分片上传的准备请求
+    pub fn prepare(&self) -> PrepareMediaUpload {
+        PrepareMediaUpload {
+            file_name: self.file_name.clone(),
+            parent_type: self.parent_type,
+            parent_node: self.parent_node.clone(),
+            size: self.file.len() as u64,
+            extra: self.extra.clone(),
+        }
+    }
+}
+
+impl fmt::Debug for UploadMedia {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("UploadMedia")
+            .field("file_name", &self.file_name)
+            .field("parent_type", &self.parent_type)
+            .field("parent_node", &self.parent_node)
+            .field("extra", &self.extra)
+            .field("size", &self.file.len())
+            .finish()
+    }
+}
+
+impl Request for UploadMedia {
+    type Target = BodyResponse<MediaToken>;
+
+    fn method(&self) -> Method {
+        Method::Post
+    }
+
+    fn url(&self) -> String {
+        Self::ENDPOINT.to_string()
+    }
+
+    fn endpoint(&self) -> Option<&'static str> {
+        Some(Self::ENDPOINT)
+    }
+
+    fn content_type(&self) -> &str {
+        self.boundary.content_type()
+    }
+
+    fn body(&self) -> Result<Option<Vec<u8>>> {
+        let mut form = self
+            .boundary
+            .form()
+            .text("file_name", &self.file_name)
+            .text("parent_type", self.parent_type.as_str())
+            .text("parent_node", &self.parent_node)
+            .text("size", self.file.len().to_string());
+        if let Some(extra) = &self.extra {
+            form = form.text("extra", extra);
+        }
+        Ok(Some(
+            form.file("file", &self.file_name, &self.file).finish(),
+        ))
+    }
+
+    fn auth_type(&self) -> AuthType {
+        AuthType::Tenant
+    }
+}
+
+/// 分片上传的信息
+#[derive(Debug, Clone, Deserialize, lark_sdk::Response)]
+pub struct MediaUploadSession {
+    pub upload_id: String,
+    /// 每个分片的大小，最后一个分片可以更小
+    pub block_size: u64,
+    pub block_num: u32,
+}
+
+/// 分片上传的第一步，获取分片的大小和数量
+#[derive(Debug, Clone, lark_sdk::Request)]
+#[request(
+    method = "POST",
+    url = "/open-apis/drive/v1/medias/upload_prepare",
+    auth = "tenant",
+    response = MediaUploadSession
+)]
+pub struct PrepareMediaUpload {
+    #[request(body)]
+    pub file_name: String,
+    #[request(body)]
+    pub parent_type: ParentType,
+    #[request(body)]
+    pub parent_node: String,
+    /// 文件的总大小
+    #[request(body)]
+    pub size: u64,
+    #[request(body)]
+    pub extra: Option<String>,
+}
+
+/// 上传一个分片，分片可以并发上传
+#[derive(Clone)]
+pub struct UploadMediaPart {
+    pub upload_id: String,
+    /// 分片的序号，从 0 开始
+    pub seq: u32,
+    pub file: Vec<u8>,
+    boundary: Boundary,
+}
+
+impl UploadMediaPart {
+    /// 替换路径参数之前的请求路径
+    pub const ENDPOINT: &'static str = "/open-apis/drive/v1/medias/upload_part";
+
+    pub fn new(upload_id: impl Into<String>, seq: u32, file: Vec<u8>) -> Self {
+        UploadMediaPart {
+            upload_id: upload_id.into(),
+            seq,
+            file,
+            boundary: Boundary::new(),
+        }
+    }
+}
+
+impl fmt::Debug for UploadMediaPart {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("UploadMediaPart")
+            .field("upload_id", &self.upload_id)
+            .field("seq", &self.seq)
+            .field("size", &self.file.len())
+            .finish()
+    }
+}
+
+impl Request for UploadMediaPart {
+    type Target = BodyResponse<crate::Empty>;
+
+    fn method(&self) -> Method {
+        Method::Post
+    }
+
+    fn url(&self) -> String {
+        Self::ENDPOINT.to_string()
+    }
+
+    fn endpoint(&self) -> Option<&'static str> {
+        Some(Self::ENDPOINT)
+    }
+
+    fn content_type(&self) -> &str {
+        self.boundary.content_type()
+    }
+
+    fn body(&self) -> Result<Option<Vec<u8>>> {
+        let form = self
+            .boundary
+            .form()
+            .text("upload_id", &self.upload_id)
+            .text("seq", self.seq.to_string())
+            .text("size", self.file.len().to_string())
+            .file("file", &self.seq.to_string(), &self.file);
+        Ok(Some(form.finish()))
+    }
+
+    fn auth_type(&self) -> AuthType {
+        AuthType::Tenant
+    }
+
+    fn is_idempotent(&self) -> bool {
+        true
+    }
+}
+
+/// 分片上传的最后一步，所有分片上传完成后调用
+#[derive(Debug, Clone, lark_sdk::Request)]
+#[request(
+    method = "POST",
+    url = "/open-apis/drive/v1/medias/upload_finish",
+    auth = "tenant",
+    response = MediaToken
+)]
+pub struct FinishMediaUpload {
+    #[request(body)]
+    pub upload_id: String,
+    #[request(body)]
+    pub block_num: u32,
+}
+
+/// 上传素材，返回 `file_token`。超过 [`UPLOAD_ALL_LIMIT`] 时依次上传各个分片
+#[cfg(feature = "async")]
+pub async fn upload(client: &Client, media: &UploadMedia) -> Result<String> {
+    if media.file.len() <= UPLOAD_ALL_LIMIT {
+        return Ok(client.send(media).await?.into_data()?.file_token);
+    }
+    let session = client.send(&media.prepare()).await?.into_data()?;
+    let block_size = session.block_size.max(1) as usize;
+    for (seq, chunk) in media.file.chunks(block_size).enumerate() {
+        let part = UploadMediaPart::new(session.upload_id.clone(), seq as u32, chunk.to_vec());
+        client.send(&part).await?.into_data()?;
+    }
+    let finish = FinishMediaUpload {
+        upload_id: session.upload_id,
+        block_num: session.block_num,
+    };
+    Ok(client.send(&finish).await?.into_data()?.file_token)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn upload_all_body() {
+        let mut upload =
+            UploadMedia::docx_image("doxcn_doc", "doxcn_img", "a.png", b"PNG".to_vec());
+        upload.boundary = Boundary::with_value("b");
+        assert_eq!(upload.content_type(), "multipart/form-data; boundary=b");
+        let body = String::from_utf8(upload.body().unwrap().unwrap()).unwrap();
+        assert_eq!(
+            body,
+            "--b\r\nContent-Disposition: form-data; name=\"file_name\"\r\n\r\na.png\r\n\
+             --b\r\nContent-Disposition: form-data; name=\"parent_type\"\r\n\r\ndocx_image\r\n\
+             --b\r\nContent-Disposition: form-data; name=\"parent_node\"\r\n\r\ndoxcn_img\r\n\
+             --b\r\nContent-Disposition: form-data; name=\"size\"\r\n\r\n3\r\n\
+             --b\r\nContent-Disposition: form-data; name=\"extra\"\r\n\r\n{\"drive_route_token\":\"doxcn_doc\"}\r\n\
+             --b\r\nContent-Disposition: form-data; name=\"file\"; filename=\"a.png\"\r\n\
+             Content-Type: application/octet-stream\r\n\r\nPNG\r\n--b--\r\n"
+        );
+    }
+
+    #[tokio::test]
+    async fn upload_parts() {
+        let (address, handle) = crate::test_util::serve_all(vec![
+            r#"{"code":0,"msg":"ok","data":{"upload_id":"u1","block_size":4194304,"block_num":6}}"#,
+            r#"{"code":0,"msg":"ok","data":{}}"#,
+            r#"{"code":0,"msg":"ok","data":{}}"#,
+            r#"{"code":0,"msg":"ok","data":{}}"#,
+            r#"{"code":0,"msg":"ok","data":{}}"#,
+            r#"{"code":0,"msg":"ok","data":{}}"#,
+            r#"{"code":0,"msg":"ok","data":{}}"#,
+            r#"{"code":0,"msg":"ok","data":{"file_token":"boxcn_1"}}"#,
+        ]);
+        let client = Client::builder().domain(address).build().unwrap();
+        let media = UploadMedia::bitable_file("bascn_1", "big.bin", vec![0; UPLOAD_ALL_LIMIT + 1]);
+        assert_eq!(upload(&client, &media).await.unwrap(), "boxcn_1");
+
+        let requests = handle.join().unwrap();
+        assert_eq!(requests.len(), 8);
+        assert!(requests[0].starts_with("POST /open-apis/drive/v1/medias/upload_prepare "));
+        assert!(requests[0].contains(r#""parent_type":"bitable_file""#));
+        assert!(requests[0].contains(r#""size":20971521"#));
+        assert!(requests[6].starts_with("POST /open-apis/drive/v1/medias/upload_part "));
+        assert!(requests[6].contains("name=\"seq\"\r\n\r\n5\r\n"));
+        assert!(requests[6].contains("name=\"size\"\r\n\r\n1\r\n"));
+        assert!(requests[7].contains(r#""block_num":6"#));
+    }
+}
@@ -0,0 +1,205 @@
+//! 各个接口替换路径参数之前的请求路径，路径中包含接口的版本。
+//!
+//! 与 [`Request::endpoint`](crate::Request::endpoint) 一致，可以用作日志字段、指标的标签或者
+//! 权限配置中的接口名称，避免复制字符串。各个请求类型还可以通过关联常量 `ENDPOINT`
+//! 获取，如 `lark_sdk::im::SendMessage::ENDPOINT`。
+//!
+//! ```
+//! # #[cfg(feature = "im")]
+//! assert_eq!(lark_sdk::endpoints::im::SEND_MESSAGE, "/open-apis/im/v1/messages");
+//! ```
+
+/// 由请求类型的 `ENDPOINT` 生成常量，每个服务一个模块
+macro_rules! endpoints {
+    ($(
+        $(#[$meta:meta])*
+        $service:ident {
+            $($name:ident = $($request:ident)::+,)*
+        }
+    )*) => {
+        $(
+            $(#[$meta])*
+            pub mod $service {
+                $(
+                    #[doc = concat!("[`", stringify!($($request)::+), "`] 的请求路径")]
+                    pub const $name: &str = $($request)::+::ENDPOINT;
+                )*
+            }
+        )*
+    };
+}
+
+endpoints! {
+    #[cfg(feature = "approval")]
+    approval {
+        CREATE_INSTANCE = crate::approval::CreateInstance,
+        UPLOAD_APPROVAL_FILE = crate::approval::UploadApprovalFile,
+    }
+    #[cfg(feature = "attendance")]
+    attendance {
+        QUERY_USER_TASKS = crate::attendance::QueryUserTasks,
+    }
+    #[cfg(all(feature = "contact", feature = "async"))]
+    contact {
+        LIST_CHILD_DEPARTMENTS = crate::contact_sync::ListChildDepartments,
+        FIND_USERS_BY_DEPARTMENT = crate::contact_sync::FindUsersByDepartment,
+    }
+    #[cfg(feature = "corehr")]
+    corehr {
+        BATCH_GET_EMPLOYEES = crate::corehr::v2::BatchGetEmployees,
+        BATCH_GET_DEPARTMENTS = crate::corehr::v2::BatchGetDepartments,
+        LIST_JOB_DATAS = crate::corehr::v1::ListJobDatas,
+        GET_JOB_DATA = crate::corehr::v1::GetJobData,
+        LIST_DEPARTMENTS = crate::corehr::v1::ListDepartments,
+        GET_DEPARTMENT = crate::corehr::v1::GetDepartment,
+    }
+    #[cfg(feature = "docx")]
+    docx {
+        CREATE_DOCUMENT = crate::docx::CreateDocument,
+        LIST_DOCUMENT_BLOCKS = crate::docx::ListDocumentBlocks,
+        GET_RAW_CONTENT = crate::docx::GetRawContent,
+        CREATE_BLOCK_DESCENDANTS = crate::docx::CreateBlockDescendants,
+        REPLACE_IMAGE = crate::docx::ReplaceImage,
+    }
+    #[cfg(feature = "drive")]
+    drive {
+        LIST_COMMENTS = crate::drive::comment::ListComments,
+        GET_COMMENT = crate::drive::comment::GetComment,
+        CREATE_COMMENT = crate::drive::comment::CreateComment,
+        SOLVE_COMMENT = crate::drive::comment::SolveComment,
+        LIST_COMMENT_REPLIES = crate::drive::comment::ListCommentReplies,
+        UPDATE_COMMENT_REPLY = crate::drive::comment::UpdateCommentReply,
+        DELETE_COMMENT_REPLY = crate::drive::comment::DeleteCommentReply,
+        CREATE_IMPORT_TASK = crate::drive::import_task::CreateImportTask,
+        GET_IMPORT_TASK = crate::drive::import_task::GetImportTask,
+        UPLOAD_MEDIA = crate::drive::media::UploadMedia,
+        PREPARE_MEDIA_UPLOAD = crate::drive::media::PrepareMediaUpload,
+        UPLOAD_MEDIA_PART = crate::drive::media::UploadMediaPart,
+        FINISH_MEDIA_UPLOAD = crate::drive::media::FinishMediaUpload,
+    }
+    #[cfg(feature = "helpdesk")]
+    helpdesk {
+        SEND_TICKET_MESSAGE = crate::helpdesk::SendTicketMessage,
+    }
+    #[cfg(feature = "im")]
+    im {
+        SEND_MESSAGE = crate::im::SendMessage,
+        LIST_READ_USERS = crate::im::ListReadUsers,
+        SEND_EPHEMERAL_CARD = crate::im::SendEphemeralCard,
+        DELETE_EPHEMERAL_CARD = crate::im::DeleteEphemeralCard,
+        LIST_MESSAGES = crate::im::ListMessages,
+        GET_CHAT_ANNOUNCEMENT = crate::im::announcement::GetChatAnnouncement,
+        LIST_CHAT_ANNOUNCEMENT_BLOCKS = crate::im::announcement::ListChatAnnouncementBlocks,
+        CREATE_CHAT_ANNOUNCEMENT_BLOCKS = crate::im::announcement::CreateChatAnnouncementBlocks,
+        DELETE_CHAT_ANNOUNCEMENT_BLOCKS = crate::im::announcement::DeleteChatAnnouncementBlocks,
+        GET_CHAT_MENU = crate::im::menu::GetChatMenu,
+        CREATE_CHAT_MENU = crate::im::menu::CreateChatMenu,
+        UPDATE_CHAT_MENU_ITEM = crate::im::menu::UpdateChatMenuItem,
+        DELETE_CHAT_MENU = crate::im::menu::DeleteChatMenu,
+        SORT_CHAT_MENU = crate::im::menu::SortChatMenu,
+        LIST_CHAT_TABS = crate::im::tab::ListChatTabs,
+        CREATE_CHAT_TABS = crate::im::tab::CreateChatTabs,
+        UPDATE_CHAT_TABS = crate::im::tab::UpdateChatTabs,
+        DELETE_CHAT_TABS = crate::im::tab::DeleteChatTabs,
+        SORT_CHAT_TABS = crate::im::tab::SortChatTabs,
+    }
+    #[cfg(feature = "moments")]
+    moments {
+        GET_POST = crate::moments::GetPost,
+    }
+    #[cfg(feature = "performance")]
+    performance {
+        LIST_SEMESTERS = crate::performance::ListSemesters,
+        QUERY_ACTIVITIES = crate::performance::QueryActivities,
+        QUERY_REVIEW_DATA = crate::performance::QueryReviewData,
+    }
+    #[cfg(feature = "sheets")]
+    sheets {
+        READ_RANGE = crate::sheets::ReadRange,
+        WRITE_RANGE = crate::sheets::WriteRange,
+        APPEND_RANGE = crate::sheets::AppendRange,
+    }
+    #[cfg(feature = "vc")]
+    vc {
+        GET_RECORDING = crate::vc::GetRecording,
+        SET_RECORDING_PERMISSION = crate::vc::SetRecordingPermission,
+        GET_MINUTE_MEDIA = crate::vc::GetMinuteMedia,
+        LIST_ROOMS = crate::vc::ListRooms,
+        GET_ROOM_FREE_BUSY = crate::vc::GetRoomFreeBusy,
+    }
+}
+
+/// 获取访问凭证的接口
+pub mod auth {
+    /// 自建应用获取 `app_access_token`
+    pub const APP_ACCESS_TOKEN_INTERNAL: &str = "/open-apis/auth/v3/app_access_token/internal";
+    /// 自建应用获取 `tenant_access_token`
+    pub const TENANT_ACCESS_TOKEN_INTERNAL: &str =
+        "/open-apis/auth/v3/tenant_access_token/internal";
+    /// 商店应用获取 `app_access_token`
+    pub const APP_ACCESS_TOKEN: &str = "/open-apis/auth/v3/app_access_token";
+    /// 商店应用获取 `tenant_access_token`
+    pub const TENANT_ACCESS_TOKEN: &str = "/open-apis/auth/v3/tenant_access_token";
+    /// 获取和刷新 `user_access_token`
+    pub const OAUTH_TOKEN: &str = "/open-apis/authen/v2/oauth/token";
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    fn name(rest: &str) -> String {
+        rest.split(|c: char| !c.is_alphanumeric())
+            .next()
+            .unwrap()
+            .to_string()
+    }
+
+    /// 返回 `dir` 下所有派生或者手动实现了 `Request` 的公开顶层类型，不包括测试和文档中的示例
+    fn derived_requests(dir: &Path, found: &mut Vec<String>) {
+        for entry in std::fs::read_dir(dir).unwrap() {
+            let path = entry.unwrap().path();
+            if path.is_dir() {
+                derived_requests(&path, found);
+                continue;
+            }
+            let source = std::fs::read_to_string(&path).unwrap();
+            let mut derived = false;
+            let mut public = Vec::new();
+            let mut implemented = Vec::new();
+            for line in source.lines() {
+                if line.starts_with("#[derive(") {
+                    derived = line.contains("Request");
+                } else if let Some(rest) = line
+                    .strip_prefix("pub struct ")
+                    .or_else(|| line.strip_prefix("pub(crate) struct "))
+                {
+                    if derived {
+                        found.push(name(rest));
+                    } else if line.starts_with("pub struct ") {
+                        public.push(name(rest));
+                    }
+                    derived = false;
+                } else if let Some(rest) = line.strip_prefix("impl Request for ") {
+                    implemented.push(name(rest));
+                }
+            }
+            found.extend(implemented.into_iter().filter(|name| public.contains(name)));
+        }
+    }
+
+    #[test]
+    fn every_request_has_endpoint() {
+        let src = Path::new(env!("CARGO_MANIFEST_DIR")).join("src");
+        let mut requests = Vec::new();
+        derived_requests(&src, &mut requests);
+        assert!(requests.len() > 50);
+
+        let endpoints = include_str!("endpoints.rs");
+        let missing: Vec<&String> = requests
+            .iter()
+            .filter(|name| !endpoints.contains(&format!("::{},", name)))
+            .collect();
+        assert!(missing.is_empty(), "no endpoint constant for {:?}", missing);
+    }
+}
@@ -0,0 +1,137 @@
+//! 接口中常用的枚举参数和字段。
+//!
+//! 从接口和事件中读取的枚举都派生 [`StringEnum`](crate::StringEnum)，`Unknown` 成员保存 SDK 尚未
+//! 收录的值，服务端新增取值时反序列化不会失败，序列化时原样输出。
+//!
+//! ```
+//! use lark_sdk::enums::UserIdType;
+//!
+//! let id_type: UserIdType = serde_json::from_str(r#""open_id""#).unwrap();
+//! assert_eq!(id_type, UserIdType::OpenId);
+//! let id_type: UserIdType = serde_json::from_str(r#""new_id""#).unwrap();
+//! assert_eq!(id_type.as_str(), "new_id");
+//! ```
+
+use crate::id::IdType;
+
+/// 用户 ID 的类型，即 `user_id_type` 参数
+#[derive(Debug, Clone, PartialEq, Eq, Hash, lark_sdk::StringEnum)]
+#[serde(rename_all = "snake_case")]
+#[non_exhaustive]
+pub enum UserIdType {
+    OpenId,
+    UnionId,
+    UserId,
+    /// 飞书人事中的雇佣 ID
+    PeopleCorehrId,
+    Unknown(String),
+}
+
+/// 部门 ID 的类型，即 `department_id_type` 参数
+#[derive(Debug, Clone, PartialEq, Eq, Hash, lark_sdk::StringEnum)]
+#[serde(rename_all = "snake_case")]
+#[non_exhaustive]
+pub enum DepartmentIdType {
+    OpenDepartmentId,
+    DepartmentId,
+    /// 飞书人事中的部门 ID
+    PeopleCorehrDepartmentId,
+    Unknown(String),
+}
+
+/// 消息接收者 ID 的类型，即 `receive_id_type` 参数
+#[derive(Debug, Clone, PartialEq, Eq, Hash, lark_sdk::StringEnum)]
+#[serde(rename_all = "snake_case")]
+#[non_exhaustive]
+pub enum ReceiveIdType {
+    OpenId,
+    UnionId,
+    UserId,
+    Email,
+    ChatId,
+    Unknown(String),
+}
+
+/// 消息所在容器的类型，即 `container_id_type` 参数
+#[derive(Debug, Clone, PartialEq, Eq, Hash, lark_sdk::StringEnum)]
+#[serde(rename_all = "snake_case")]
+#[non_exhaustive]
+pub enum ContainerIdType {
+    Chat,
+    Thread,
+    Unknown(String),
+}
+
+/// 会话类型
+#[derive(Debug, Clone, PartialEq, Eq, Hash, lark_sdk::StringEnum)]
+#[serde(rename_all = "snake_case")]
+#[non_exhaustive]
+pub enum ChatType {
+    /// 单聊
+    P2p,
+    /// 群聊
+    Group,
+    Unknown(String),
+}
+
+/// 飞书人事中员工的在职状态
+#[derive(Debug, Clone, PartialEq, Eq, Hash, lark_sdk::StringEnum)]
+#[serde(rename_all = "snake_case")]
+#[non_exhaustive]
+pub enum EmploymentStatus {
+    /// 在职
+    Hired,
+    /// 离职
+    Terminated,
+    Unknown(String),
+}
+
+impl UserIdType {
+    /// `T` 对应的 ID 类型
+    pub fn of<T: IdType>() -> Self {
+        UserIdType::from(T::ID_TYPE)
+    }
+}
+
+impl ReceiveIdType {
+    /// `T` 对应的 ID 类型
+    pub fn of<T: IdType>() -> Self {
+        ReceiveIdType::from(T::ID_TYPE)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::id::{ChatId, Email, OpenId};
+    use crate::RequestSerialize;
+
+    #[test]
+    fn known_and_unknown_values() {
+        assert_eq!(ReceiveIdType::of::<ChatId>(), ReceiveIdType::ChatId);
+        assert_eq!(ReceiveIdType::of::<Email>().to_string(), "email");
+        assert_eq!(UserIdType::of::<OpenId>(), UserIdType::OpenId);
+
+        let types: Vec<ChatType> = serde_json::from_str(r#"["p2p","group","topic"]"#).unwrap();
+        assert_eq!(
+            types,
+            [
+                ChatType::P2p,
+                ChatType::Group,
+                ChatType::Unknown("topic".to_string())
+            ]
+        );
+        assert_eq!(
+            serde_json::to_string(&types).unwrap(),
+            r#"["p2p","group","topic"]"#
+        );
+        assert_eq!(
+            DepartmentIdType::from("department_id".to_string()),
+            DepartmentIdType::DepartmentId
+        );
+        assert_eq!(
+            ContainerIdType::Thread.to_param(),
+            Some("thread".to_string())
+        );
+    }
+}
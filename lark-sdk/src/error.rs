@@ -0,0 +1,163 @@
+use std::fmt;
+
+pub type Result<T> = std::result::Result<T, LarkError>;
+
+/// SDK 的错误类型。
+///
+/// 不同特性会增加新的错误，如开启 `async` 后的 [`LarkError::Http`]，匹配时需要保留 `_` 分支。
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum LarkError {
+    /// 发送请求或读取响应失败
+    #[cfg(feature = "async")]
+    Http(reqwest::Error),
+    /// JSON 序列化或反序列化失败
+    Json(serde_json::Error),
+    /// 响应内容无法解析为响应类型，`body` 为截断后的响应内容，用于判断是飞书修改了响应结构还是
+    /// 使用了错误的响应类型
+    Decode {
+        /// 请求方法和接口地址，如 `POST /open-apis/im/v1/messages`
+        endpoint: String,
+        status: u16,
+        log_id: Option<String>,
+        body: String,
+        source: serde_json::Error,
+    },
+    /// 接口返回了非成功的状态码，`data` 为响应中附带的错误详情
+    Api {
+        code: i64,
+        msg: String,
+        data: Option<serde_json::Value>,
+        log_id: Option<String>,
+    },
+    /// 接口返回成功，但是没有 `data` 字段
+    MissingData,
+    /// 请求参数不合法，如缺少必填字段
+    InvalidRequest(String),
+    /// 超过了等待时间，如轮询任务未在指定时间内完成
+    Timeout,
+    /// 请求没有发送，如批量请求中出现了认证错误，后续的请求被取消
+    Cancelled,
+    /// 分页读取超过了 [`Pages::max_pages`](crate::page::Pages::max_pages) 或
+    /// [`Pages::max_items`](crate::page::Pages::max_items) 的限制
+    PageLimit {
+        /// 请求的接口，见 [`Request::endpoint`](crate::Request::endpoint)
+        endpoint: String,
+        /// 已经读取的页数
+        pages: usize,
+        /// 已经读取的条数
+        items: usize,
+    },
+    /// 事件推送的内容不合法，如 Verification Token 不匹配
+    InvalidEvent(String),
+    /// 事件转发到消息队列失败
+    Sink(String),
+    /// 长连接建立失败或连接中断
+    WebSocket(String),
+    /// 读写文件失败，如保存下载的文件
+    Io(std::io::Error),
+}
+
+/// 访问凭证缺失、无效、过期或权限不足的错误码，出现后重试或继续发送其他请求都没有意义。
+/// `10003`、`10014` 为获取访问凭证时 `app_id`、`app_secret` 错误
+const AUTH_ERROR_CODES: &[i64] = &[
+    10003, 10014, 99991661, 99991663, 99991664, 99991665, 99991668, 99991671, 99991672, 99991677,
+    99991679,
+];
+
+/// 状态码是否为认证错误，如 `tenant_access_token` 无效
+pub fn is_auth_error_code(code: i64) -> bool {
+    AUTH_ERROR_CODES.contains(&code)
+}
+
+impl LarkError {
+    /// 是否为认证错误，见 [`is_auth_error_code`]
+    pub fn is_auth_error(&self) -> bool {
+        matches!(self, LarkError::Api { code, .. } if is_auth_error_code(*code))
+    }
+}
+
+impl fmt::Display for LarkError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            #[cfg(feature = "async")]
+            LarkError::Http(err) => write!(f, "http error: {}", err),
+            LarkError::Json(err) => write!(f, "json error: {}", err),
+            LarkError::Decode {
+                endpoint,
+                status,
+                log_id,
+                body,
+                source,
+            } => {
+                write!(
+                    f,
+                    "failed to decode response of {}: {}, status={}",
+                    endpoint, source, status
+                )?;
+                if let Some(log_id) = log_id {
+                    write!(f, ", log_id={}", log_id)?;
+                }
+                write!(f, ", body={}", body)
+            }
+            LarkError::Api {
+                code, msg, log_id, ..
+            } => {
+                write!(f, "api error: code={}, msg={}", code, msg)?;
+                if let Some(log_id) = log_id {
+                    write!(f, ", log_id={}", log_id)?;
+                }
+                Ok(())
+            }
+            LarkError::MissingData => f.write_str("response data is null"),
+            LarkError::InvalidRequest(msg) => write!(f, "invalid request: {}", msg),
+            LarkError::Timeout => f.write_str("timed out"),
+            LarkError::Cancelled => f.write_str("request cancelled"),
+            LarkError::PageLimit {
+                endpoint,
+                pages,
+                items,
+            } => write!(
+                f,
+                "pagination of {} exceeded the limit after {} pages and {} items",
+                endpoint, pages, items
+            ),
+            LarkError::InvalidEvent(msg) => write!(f, "invalid event: {}", msg),
+            LarkError::Sink(msg) => write!(f, "event sink error: {}", msg),
+            LarkError::WebSocket(msg) => write!(f, "websocket error: {}", msg),
+            LarkError::Io(err) => write!(f, "io error: {}", err),
+        }
+    }
+}
+
+impl std::error::Error for LarkError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            #[cfg(feature = "async")]
+            LarkError::Http(err) => Some(err),
+            LarkError::Json(err) => Some(err),
+            LarkError::Decode { source, .. } => Some(source),
+            LarkError::Io(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<serde_json::Error> for LarkError {
+    fn from(err: serde_json::Error) -> Self {
+        LarkError::Json(err)
+    }
+}
+
+#[cfg(feature = "async")]
+impl From<reqwest::Error> for LarkError {
+    fn from(err: reqwest::Error) -> Self {
+        LarkError::Http(err)
+    }
+}
+
+impl From<std::io::Error> for LarkError {
+    fn from(err: std::io::Error) -> Self {
+        LarkError::Io(err)
+    }
+}
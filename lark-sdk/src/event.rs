@@ -0,0 +1,139 @@
+//! 事件订阅。
+//!
+//! 飞书开放平台以 2.0 版本的结构推送事件：公共的 `header` 加上各个事件自己的 `event`。
+//! 早期的应用仍会收到 1.0 版本的事件，[`EventDispatcher`] 会先将其转换为 2.0 版本的结构，
+//! 同一个处理函数可以同时处理两种版本。
+//!
+//! 不使用 [`EventDispatcher`] 时，可以用 [`parse_http`] 解析事件推送的请求后自行处理。
+
+/// 为同一结构的不同事件定义新类型
+macro_rules! newtype_events {
+    ($($(#[$doc:meta])* $name:ident($inner:ty) = $event_type:literal;)*) => {
+        $(
+            $(#[$doc])*
+            #[derive(Debug, Clone, serde::Deserialize)]
+            #[serde(transparent)]
+            pub struct $name(pub $inner);
+
+            impl $crate::event::EventType for $name {
+                const EVENT_TYPE: &'static str = $event_type;
+            }
+
+            impl std::ops::Deref for $name {
+                type Target = $inner;
+
+                fn deref(&self) -> &$inner {
+                    &self.0
+                }
+            }
+        )*
+    };
+}
+
+pub mod application;
+pub mod approval;
+pub mod attendance;
+pub mod calendar;
+mod callback;
+pub mod card;
+mod compat;
+pub mod contact;
+mod crypto;
+#[cfg(feature = "async")]
+mod dispatcher;
+pub mod drive;
+pub mod helpdesk;
+pub mod im;
+pub mod moments;
+#[cfg(feature = "async")]
+pub mod sink;
+
+pub use callback::{parse_http, CallbackConfig, ParsedCallback};
+#[cfg(feature = "async")]
+pub use dispatcher::{Dispatched, EventDispatcher};
+
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// 事件的公共头部
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EventHeader {
+    pub event_id: String,
+    /// 事件类型，如 `im.message.receive_v1`
+    pub event_type: String,
+    /// 事件发送的时间，毫秒时间戳
+    pub create_time: String,
+    /// 事件订阅的 Verification Token
+    pub token: String,
+    pub app_id: String,
+    pub tenant_key: String,
+}
+
+/// 一个事件，`T` 为事件内容，如 [`im::MessageReceiveEvent`]。
+#[derive(Debug, Clone, Deserialize)]
+#[serde(bound = "T: DeserializeOwned")]
+pub struct Event<T> {
+    /// 事件格式的版本，为 `2.0`，由 1.0 版本转换而来的事件为 `1.0`
+    pub schema: String,
+    pub header: EventHeader,
+    pub event: T,
+}
+
+/// 解密并转换为 2.0 版本结构后、尚未按照类型解析的事件，交给中间件和兜底的处理函数
+#[derive(Debug, Clone)]
+pub struct RawEvent {
+    pub header: EventHeader,
+    /// 完整的事件，包括 `schema`、`header` 和 `event`
+    pub payload: Value,
+}
+
+impl RawEvent {
+    pub fn event_type(&self) -> &str {
+        &self.header.event_type
+    }
+
+    pub fn tenant_key(&self) -> &str {
+        &self.header.tenant_key
+    }
+
+    /// 按照 `T` 解析事件
+    pub fn parse<T: EventType>(&self) -> Result<Event<T>, serde_json::Error> {
+        Event::deserialize(&self.payload)
+    }
+}
+
+/// 有类型的事件内容。
+pub trait EventType: DeserializeOwned {
+    /// 事件类型，如 `im.message.receive_v1`
+    const EVENT_TYPE: &'static str;
+}
+
+/// 用户在不同维度下的 ID
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UserId {
+    #[serde(default)]
+    pub union_id: Option<String>,
+    #[serde(default)]
+    pub user_id: Option<String>,
+    #[serde(default)]
+    pub open_id: Option<String>,
+}
+
+/// 测试用的 2.0 版本事件推送，`header` 使用固定的内容，Verification Token 为 `token`
+#[cfg(test)]
+pub(crate) fn envelope(event_type: &str, event: Value) -> Vec<u8> {
+    serde_json::to_vec(&serde_json::json!({
+        "schema": "2.0",
+        "header": {
+            "event_id": "e1",
+            "event_type": event_type,
+            "create_time": "1608725989000",
+            "token": "token",
+            "app_id": "cli_1",
+            "tenant_key": "t1"
+        },
+        "event": event
+    }))
+    .unwrap()
+}
@@ -0,0 +1,123 @@
+//! 应用相关的事件。
+
+use serde::{Deserialize, Serialize};
+
+use super::{EventType, UserId};
+use crate::time::{self, LarkTimestamp};
+
+/// 机器人自定义菜单被点击，`application.bot.menu_v6`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BotMenuEvent {
+    pub operator: MenuOperator,
+    /// 菜单上配置的事件 key
+    pub event_key: String,
+    /// 点击的时间
+    #[serde(default, with = "time::millis::option")]
+    pub timestamp: Option<LarkTimestamp>,
+}
+
+impl EventType for BotMenuEvent {
+    const EVENT_TYPE: &'static str = "application.bot.menu_v6";
+}
+
+/// 点击菜单的用户
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MenuOperator {
+    #[serde(default)]
+    pub operator_name: Option<String>,
+    pub operator_id: UserId,
+}
+
+/// 首次启用应用，`app_open`
+///
+/// 该事件以 1.0 版本的结构推送，由 [`EventDispatcher`](super::EventDispatcher) 转换后分发。
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AppOpenEvent {
+    pub app_id: String,
+    pub tenant_key: String,
+    /// 应用的申请者，可能有多个
+    #[serde(default)]
+    pub applicants: Vec<UserId>,
+    /// 安装应用的管理员，用户申请时为空
+    #[serde(default)]
+    pub installer: Option<UserId>,
+    /// 安装应用的员工，用户申请时为空
+    #[serde(default)]
+    pub installer_employee: Option<UserId>,
+}
+
+impl EventType for AppOpenEvent {
+    const EVENT_TYPE: &'static str = "app_open";
+}
+
+/// 平台推送商店应用的 `app_ticket`，`app_ticket`
+///
+/// 平台每小时推送一次，商店应用需要使用最新的 `app_ticket` 获取 `app_access_token`，见
+/// [`ClientRegistry::set_app_ticket`](crate::registry::ClientRegistry::set_app_ticket)。
+/// 该事件以 1.0 版本的结构推送，由 [`EventDispatcher`](super::EventDispatcher) 转换后分发。
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AppTicketEvent {
+    pub app_id: String,
+    pub app_ticket: String,
+}
+
+impl EventType for AppTicketEvent {
+    const EVENT_TYPE: &'static str = "app_ticket";
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::event::{envelope, Event};
+
+    #[test]
+    fn bot_menu() {
+        let event: Event<BotMenuEvent> = serde_json::from_slice(&envelope(
+            "application.bot.menu_v6",
+            serde_json::json!({
+                "operator": {
+                    "operator_name": "Tom",
+                    "operator_id": {"union_id": "on_1", "user_id": "u_1", "open_id": "ou_1"}
+                },
+                "event_key": "start_onboarding",
+                "timestamp": 1669364110000i64
+            }),
+        ))
+        .unwrap();
+        assert_eq!(event.event.event_key, "start_onboarding");
+        assert_eq!(
+            event.event.timestamp,
+            Some(LarkTimestamp::from_millis(1669364110000))
+        );
+        assert_eq!(
+            event.event.operator.operator_id.open_id.as_deref(),
+            Some("ou_1")
+        );
+    }
+
+    #[test]
+    fn app_open() {
+        let event: AppOpenEvent = serde_json::from_value(serde_json::json!({
+            "app_id": "cli_1",
+            "tenant_key": "t1",
+            "type": "app_open",
+            "applicants": [{"open_id": "ou_1"}],
+            "installer": {"open_id": "ou_2"},
+            "installer_employee": {"open_id": "ou_2"}
+        }))
+        .unwrap();
+        assert_eq!(event.applicants.len(), 1);
+        assert_eq!(event.installer.unwrap().open_id.as_deref(), Some("ou_2"));
+    }
+
+    #[test]
+    fn app_ticket() {
+        let event: AppTicketEvent = serde_json::from_value(serde_json::json!({
+            "app_id": "cli_1",
+            "app_ticket": "ticket-1",
+            "type": "app_ticket"
+        }))
+        .unwrap();
+        assert_eq!(event.app_ticket, "ticket-1");
+    }
+}
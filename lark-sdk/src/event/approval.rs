@@ -0,0 +1,70 @@
+//! 审批相关的事件。
+
+use serde::{Deserialize, Serialize};
+
+use super::EventType;
+use crate::time::{self, LarkTimestamp};
+
+/// 审批实例状态变更，`approval_instance`
+///
+/// 该事件以 1.0 版本的结构推送，`event` 中的字段即为本结构体的字段，
+/// [`EventDispatcher`](super::EventDispatcher) 会将其转换为 [`Event`](super::Event)。
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ApprovalInstanceEvent {
+    pub app_id: String,
+    pub tenant_key: String,
+    /// 审批定义 code
+    pub approval_code: String,
+    /// 审批实例 code
+    pub instance_code: String,
+    /// 实例状态：`PENDING`、`APPROVED`、`REJECTED`、`CANCELED`、`DELETED`、`REVERTED`、
+    /// `OVERTIME_CLOSE`、`OVERTIME_RECOVER`
+    pub status: String,
+    /// 事件发生的时间
+    #[serde(default, with = "time::millis::option")]
+    pub operate_time: Option<LarkTimestamp>,
+    /// 实例操作的时间
+    #[serde(default, with = "time::millis::option")]
+    pub instance_operate_time: Option<LarkTimestamp>,
+    /// 审批实例的自定义 ID
+    #[serde(default)]
+    pub uuid: Option<String>,
+}
+
+impl EventType for ApprovalInstanceEvent {
+    const EVENT_TYPE: &'static str = "approval_instance";
+}
+
+impl ApprovalInstanceEvent {
+    /// 审批是否已经结束（通过、拒绝、撤回、删除等）
+    pub fn is_finished(&self) -> bool {
+        !matches!(self.status.as_str(), "PENDING" | "OVERTIME_RECOVER")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn approval_instance() {
+        let event: ApprovalInstanceEvent = serde_json::from_value(serde_json::json!({
+            "app_id": "cli_1",
+            "approval_code": "7C468A54-8745-2245-9675-08B7C63E7A85",
+            "instance_code": "81D31358-93AF-92D6-7425-01A5D67C4E71",
+            "instance_operate_time": "1666079207003",
+            "operate_time": "1666079207003",
+            "status": "APPROVED",
+            "tenant_key": "t1",
+            "type": "approval_instance",
+            "uuid": "uuid_1"
+        }))
+        .unwrap();
+        assert!(event.is_finished());
+        assert_eq!(event.instance_code, "81D31358-93AF-92D6-7425-01A5D67C4E71");
+        assert_eq!(
+            event.operate_time,
+            Some(LarkTimestamp::from_millis(1666079207003))
+        );
+    }
+}
@@ -0,0 +1,117 @@
+//! 考勤相关的事件。
+
+use serde::{Deserialize, Serialize};
+
+use super::EventType;
+use crate::time::{self, LarkTimestamp};
+
+/// 员工打卡，`attendance.user_flow.created_v1`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct UserFlowCreatedEvent {
+    /// 员工的 employee_id
+    pub employee_id: String,
+    #[serde(default)]
+    pub employee_no: Option<String>,
+    /// 打卡记录 ID
+    pub record_id: String,
+    /// 打卡时间
+    #[serde(with = "time::seconds")]
+    pub check_time: LarkTimestamp,
+    #[serde(default)]
+    pub comment: Option<String>,
+    /// 打卡类型：0 为用户打卡，1 为管理员修改，2 为用户补卡，3 为系统自动生成，4 为下班免打卡，
+    /// 5 为考勤机打卡，6 为极速打卡，7 为考勤开放平台导入
+    #[serde(rename = "type", default)]
+    pub flow_type: Option<i32>,
+    #[serde(default)]
+    pub location_name: Option<String>,
+    #[serde(default)]
+    pub latitude: Option<f64>,
+    #[serde(default)]
+    pub longitude: Option<f64>,
+    #[serde(default)]
+    pub is_field: Option<bool>,
+    #[serde(default)]
+    pub is_wifi: Option<bool>,
+    #[serde(default)]
+    pub ssid: Option<String>,
+    #[serde(default)]
+    pub bssid: Option<String>,
+    #[serde(default)]
+    pub photo_urls: Vec<String>,
+    /// 打卡结果的变化，如正常、迟到、早退
+    #[serde(default)]
+    pub status_changes: Vec<StatusChange>,
+}
+
+impl EventType for UserFlowCreatedEvent {
+    const EVENT_TYPE: &'static str = "attendance.user_flow.created_v1";
+}
+
+/// 打卡结果的变化
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct StatusChange {
+    /// 班次中的第几段
+    pub index: i32,
+    /// `on` 为上班，`off` 为下班
+    pub before_status: String,
+    pub current_status: String,
+    pub before_supplement: String,
+    pub current_supplement: String,
+    pub work_type: String,
+}
+
+/// 考勤任务（一天的打卡结果）更新，`attendance.user_task.updated_v1`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UserTaskUpdatedEvent {
+    pub employee_id: String,
+    #[serde(default)]
+    pub employee_no: Option<String>,
+    /// 考勤组 ID
+    #[serde(default)]
+    pub group_id: Option<String>,
+    /// 班次 ID
+    #[serde(default)]
+    pub shift_id: Option<String>,
+    /// 日期，如 20230102
+    pub date: i64,
+    #[serde(default)]
+    pub status_changes: Vec<StatusChange>,
+}
+
+impl EventType for UserTaskUpdatedEvent {
+    const EVENT_TYPE: &'static str = "attendance.user_task.updated_v1";
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn user_flow_created() {
+        let event: UserFlowCreatedEvent = serde_json::from_value(serde_json::json!({
+            "bssid": "b0:b8:67:5c:1d:72",
+            "check_time": "1610430220",
+            "comment": "",
+            "employee_id": "112233",
+            "employee_no": "235689",
+            "is_field": true,
+            "is_wifi": true,
+            "latitude": 30.28991,
+            "location_name": "西溪八方城",
+            "longitude": 120.04513,
+            "photo_urls": ["https://time.clockin.biz/manage/download/6840389754748502021"],
+            "record_id": "6709359313699356941",
+            "ssid": "faxxxxxx",
+            "type": 0,
+            "status_changes": [
+                {"index": 0, "before_status": "", "current_status": "Normal", "work_type": "on"}
+            ]
+        }))
+        .unwrap();
+        assert_eq!(event.flow_type, Some(0));
+        assert_eq!(event.check_time.seconds(), 1610430220);
+        assert_eq!(event.status_changes[0].current_status, "Normal");
+    }
+}
@@ -0,0 +1,54 @@
+//! 日历相关的事件。
+//!
+//! 日程变更事件需要先调用「订阅日程变更事件」接口订阅具体的日历才会推送，
+//! 事件只告知变更的日历，变更的内容需要再调用接口查询。
+
+use serde::{Deserialize, Serialize};
+
+use super::{EventType, UserId};
+
+/// 日程变更，`calendar.calendar.event.changed_v4`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CalendarEventChangedEvent {
+    pub calendar_id: String,
+    /// 需要推送事件的用户
+    #[serde(default)]
+    pub user_id_list: Vec<UserId>,
+    /// 变更的日程
+    #[serde(default)]
+    pub calendar_event_id: Option<String>,
+    /// 变更类型，如 `create`、`update`、`delete`
+    #[serde(default)]
+    pub change_type: Option<String>,
+}
+
+impl EventType for CalendarEventChangedEvent {
+    const EVENT_TYPE: &'static str = "calendar.calendar.event.changed_v4";
+}
+
+/// 日历变更，`calendar.calendar.changed_v4`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CalendarChangedEvent {
+    #[serde(default)]
+    pub user_id_list: Vec<UserId>,
+}
+
+impl EventType for CalendarChangedEvent {
+    const EVENT_TYPE: &'static str = "calendar.calendar.changed_v4";
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn event_changed() {
+        let event: CalendarEventChangedEvent = serde_json::from_value(serde_json::json!({
+            "calendar_id": "feishu.cn_xxxxxxxxxx@group.calendar.feishu.cn",
+            "user_id_list": [{"open_id": "ou_1", "union_id": "on_1", "user_id": "u_1"}]
+        }))
+        .unwrap();
+        assert_eq!(event.user_id_list[0].user_id.as_deref(), Some("u_1"));
+        assert_eq!(event.change_type, None);
+    }
+}
@@ -0,0 +1,244 @@
+//! 不依赖 Web 框架解析事件推送的请求。
+//!
+//! [`EventDispatcher`](super::EventDispatcher) 需要在异步运行时中分发事件，在 Lambda、CGI 等环境中
+//! 可以只用 [`parse_http`] 完成签名校验、解密和分类，再自行处理事件和响应。
+
+use http::HeaderMap;
+use serde_json::Value;
+
+use super::card::CardActionTriggerEvent;
+use super::crypto::{self, EncryptKey};
+use super::{EventHeader, EventType, RawEvent};
+use crate::{LarkError, Result};
+
+/// 解析事件推送的配置
+#[derive(Default)]
+pub struct CallbackConfig {
+    verification_token: Option<String>,
+    encrypt_keys: Vec<EncryptKey>,
+    verify_signature: bool,
+}
+
+/// 解析后的事件推送
+#[derive(Debug, Clone)]
+pub enum ParsedCallback {
+    /// 配置订阅地址时的 URL 验证请求，需要以 `{"challenge": "..."}` 原样返回
+    Challenge(String),
+    /// 卡片回传交互 `card.action.trigger`，响应体为 [`CardActionResponse`](super::card::CardActionResponse)
+    CardAction(RawEvent),
+    /// 其他事件，响应体为 `{}`
+    Event(RawEvent),
+}
+
+impl CallbackConfig {
+    pub fn new() -> Self {
+        CallbackConfig::default()
+    }
+
+    /// 校验事件中的 Verification Token
+    pub fn verification_token(mut self, token: impl Into<String>) -> Self {
+        self.verification_token = Some(token.into());
+        self
+    }
+
+    /// 添加用于解密事件和校验签名的 Encrypt Key，可以多次调用，按照添加的顺序依次尝试
+    pub fn encrypt_key(mut self, key: impl AsRef<str>) -> Self {
+        self.encrypt_keys.push(EncryptKey::new(key.as_ref()));
+        self
+    }
+
+    /// 按照添加的顺序返回每个 Encrypt Key 解密成功的次数
+    pub fn encrypt_key_hits(&self) -> Vec<u64> {
+        self.encrypt_keys.iter().map(EncryptKey::hits).collect()
+    }
+
+    /// [`parse_http`] 校验请求头中的签名，缺少签名的请求视为不合法，需要配置 Encrypt Key
+    pub fn verify_signature(mut self) -> Self {
+        self.verify_signature = true;
+        self
+    }
+
+    /// 解密、校验 Verification Token，并将 1.0 版本的事件转换为 2.0 版本的结构
+    pub(crate) fn parse(&self, body: &[u8]) -> Result<ParsedCallback> {
+        let mut payload: Value = serde_json::from_slice(body)?;
+        if let Some(encrypted) = payload["encrypt"].as_str() {
+            if self.encrypt_keys.is_empty() {
+                return Err(LarkError::InvalidEvent(
+                    "event is encrypted but no encrypt key is configured".to_string(),
+                ));
+            }
+            payload = crypto::decrypt(&self.encrypt_keys, encrypted)?;
+        }
+        if payload["type"] == "url_verification" {
+            self.verify_token(payload["token"].as_str())?;
+            let challenge = payload["challenge"].as_str().unwrap_or_default();
+            return Ok(ParsedCallback::Challenge(challenge.to_string()));
+        }
+
+        let payload = super::compat::normalize(payload)?;
+        let header = &payload["header"];
+        self.verify_token(header["token"].as_str())?;
+        if !header["event_type"].is_string() {
+            return Err(LarkError::InvalidEvent(
+                "missing header.event_type".to_string(),
+            ));
+        }
+        let header: EventHeader = serde_json::from_value(header.clone())
+            .map_err(|err| LarkError::InvalidEvent(format!("invalid header: {}", err)))?;
+        let event = RawEvent { header, payload };
+        if event.event_type() == CardActionTriggerEvent::EVENT_TYPE {
+            return Ok(ParsedCallback::CardAction(event));
+        }
+        Ok(ParsedCallback::Event(event))
+    }
+
+    fn verify_token(&self, token: Option<&str>) -> Result<()> {
+        match &self.verification_token {
+            Some(expected) if token != Some(expected.as_str()) => Err(LarkError::InvalidEvent(
+                "verification token mismatch".to_string(),
+            )),
+            _ => Ok(()),
+        }
+    }
+
+    fn verify_signature_headers(&self, headers: &HeaderMap, body: &[u8]) -> Result<()> {
+        let header = |name: &str| {
+            headers
+                .get(name)
+                .and_then(|value| value.to_str().ok())
+                .ok_or_else(|| LarkError::InvalidEvent(format!("missing header {}", name)))
+        };
+        let signature = header("X-Lark-Signature")?;
+        let timestamp = header("X-Lark-Request-Timestamp")?;
+        let nonce = header("X-Lark-Request-Nonce")?;
+        if self.encrypt_keys.iter().any(|key| {
+            constant_time_eq(
+                key.signature(timestamp, nonce, body).as_bytes(),
+                signature.as_bytes(),
+            )
+        }) {
+            return Ok(());
+        }
+        Err(LarkError::InvalidEvent("signature mismatch".to_string()))
+    }
+}
+
+/// 解析一次事件推送的 HTTP 请求：校验签名（见 [`CallbackConfig::verify_signature`]）、解密、
+/// 校验 Verification Token 并区分 URL 验证、卡片回传交互和其他事件。
+///
+/// `body` 为原始的请求体，校验签名时不能使用重新序列化后的内容。`HeaderMap` 为 `http` 1.x 的
+/// `http::HeaderMap`，与 reqwest、axum 等使用的类型相同。
+///
+/// ```
+/// use lark_sdk::event::{parse_http, CallbackConfig, ParsedCallback};
+///
+/// # fn handle(headers: &http::HeaderMap, body: &[u8]) -> lark_sdk::Result<String> {
+/// let config = CallbackConfig::new()
+///     .verification_token("rvaYgkND1GOiu5MM0E1rncYC6PLtF7JV")
+///     .encrypt_key("encrypt key")
+///     .verify_signature();
+/// let response = match parse_http(headers, body, &config)? {
+///     ParsedCallback::Challenge(challenge) => serde_json::json!({ "challenge": challenge }),
+///     ParsedCallback::CardAction(_) => serde_json::json!({}),
+///     ParsedCallback::Event(event) => {
+///         println!("{} from {}", event.event_type(), event.tenant_key());
+///         serde_json::json!({})
+///     }
+/// };
+/// Ok(response.to_string())
+/// # }
+/// ```
+pub fn parse_http(
+    headers: &HeaderMap,
+    body: &[u8],
+    config: &CallbackConfig,
+) -> Result<ParsedCallback> {
+    if config.verify_signature {
+        if config.encrypt_keys.is_empty() {
+            return Err(LarkError::InvalidEvent(
+                "signature verification requires an encrypt key".to_string(),
+            ));
+        }
+        config.verify_signature_headers(headers, body)?;
+    }
+    config.parse(body)
+}
+
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0, |diff, (x, y)| diff | (x ^ y)) == 0
+}
+
+#[cfg(test)]
+mod tests {
+    use http::HeaderValue;
+
+    use super::*;
+    use crate::event::crypto::tests::encrypt;
+    use crate::event::envelope;
+
+    fn request(encrypt_key: &str, plaintext: &str) -> (HeaderMap, Vec<u8>) {
+        let body = serde_json::json!({ "encrypt": encrypt(encrypt_key, plaintext) }).to_string();
+        let signature = EncryptKey::new(encrypt_key).signature("1700000000", "n1", body.as_bytes());
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            "x-lark-request-timestamp",
+            HeaderValue::from_static("1700000000"),
+        );
+        headers.insert("x-lark-request-nonce", HeaderValue::from_static("n1"));
+        headers.insert("x-lark-signature", signature.parse().unwrap());
+        (headers, body.into_bytes())
+    }
+
+    fn event(event_type: &str) -> String {
+        String::from_utf8(envelope(event_type, serde_json::json!({}))).unwrap()
+    }
+
+    #[test]
+    fn parse_signed() {
+        let config = CallbackConfig::new()
+            .verification_token("token")
+            .encrypt_key("new")
+            .encrypt_key("old")
+            .verify_signature();
+
+        let challenge = r#"{"challenge":"c1","token":"token","type":"url_verification"}"#;
+        let (headers, body) = request("old", challenge);
+        assert!(matches!(
+            parse_http(&headers, &body, &config).unwrap(),
+            ParsedCallback::Challenge(challenge) if challenge == "c1"
+        ));
+
+        let (headers, body) = request("new", &event("card.action.trigger"));
+        assert!(matches!(
+            parse_http(&headers, &body, &config).unwrap(),
+            ParsedCallback::CardAction(_)
+        ));
+        let (headers, body) = request("new", &event("im.chat.disbanded_v1"));
+        match parse_http(&headers, &body, &config).unwrap() {
+            ParsedCallback::Event(event) => assert_eq!(event.event_type(), "im.chat.disbanded_v1"),
+            parsed => panic!("unexpected {:?}", parsed),
+        }
+        assert_eq!(config.encrypt_key_hits(), [2, 1]);
+
+        let mut tampered = body.clone();
+        tampered.push(b' ');
+        assert!(parse_http(&headers, &tampered, &config).is_err());
+        assert!(parse_http(&HeaderMap::new(), &body, &config).is_err());
+        let (headers, body) = request("other", &event("im.chat.disbanded_v1"));
+        assert!(parse_http(&headers, &body, &config).is_err());
+    }
+
+    #[test]
+    fn parse_without_signature() {
+        let config = CallbackConfig::new().verification_token("token");
+        let body = event("im.chat.disbanded_v1");
+        assert!(matches!(
+            parse_http(&HeaderMap::new(), body.as_bytes(), &config).unwrap(),
+            ParsedCallback::Event(_)
+        ));
+        let config = CallbackConfig::new().verification_token("other");
+        assert!(parse_http(&HeaderMap::new(), body.as_bytes(), &config).is_err());
+        let config = CallbackConfig::new().verify_signature();
+        assert!(parse_http(&HeaderMap::new(), body.as_bytes(), &config).is_err());
+    }
+}
@@ -0,0 +1,267 @@
+//! 卡片回传交互。
+//!
+//! 用户点击卡片上的按钮等组件后，开放平台推送 `card.action.trigger` 事件，
+//! 响应中可以带上 toast 提示或用于替换原卡片的新卡片，见 [`CardActionResponse`]。
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use super::EventType;
+use crate::card::CardTemplate;
+use crate::i18n::I18nText;
+
+/// 卡片回传交互，`card.action.trigger`
+#[derive(Debug, Clone, Deserialize)]
+pub struct CardActionTriggerEvent {
+    /// 操作者
+    pub operator: CardOperator,
+    /// 更新卡片用的凭证，30 分钟内有效，最多使用 2 次
+    #[serde(default)]
+    pub token: Option<String>,
+    pub action: CardAction,
+    /// 卡片所在的位置，如 `im_message`
+    #[serde(default)]
+    pub host: Option<String>,
+    #[serde(default)]
+    pub context: CardContext,
+}
+
+impl EventType for CardActionTriggerEvent {
+    const EVENT_TYPE: &'static str = "card.action.trigger";
+}
+
+/// 卡片的操作者
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(default)]
+pub struct CardOperator {
+    pub tenant_key: Option<String>,
+    pub open_id: Option<String>,
+    pub union_id: Option<String>,
+    pub user_id: Option<String>,
+}
+
+/// 用户操作的组件
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(default)]
+pub struct CardAction {
+    /// 组件上配置的回传参数
+    pub value: Value,
+    /// 组件的标签，如 `button`、`select_static`
+    pub tag: String,
+    /// 组件的名称
+    pub name: Option<String>,
+    /// 下拉选择等组件选中的选项
+    pub option: Option<String>,
+    /// 多选组件选中的选项
+    pub options: Option<Vec<String>>,
+    /// 日期组件所在的时区
+    pub timezone: Option<String>,
+    /// 输入框的内容
+    pub input_value: Option<String>,
+    /// 表单容器提交的内容
+    pub form_value: Option<Value>,
+    pub checked: Option<bool>,
+}
+
+/// 卡片所在的上下文
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(default)]
+pub struct CardContext {
+    pub open_message_id: Option<String>,
+    pub open_chat_id: Option<String>,
+    pub url: Option<String>,
+    pub preview_token: Option<String>,
+}
+
+/// 卡片回传交互的响应
+///
+/// ```
+/// use lark_sdk::event::card::{CardActionResponse, ToastType};
+///
+/// let response = CardActionResponse::new()
+///     .toast(ToastType::Success, "已审批")
+///     .template("AAqk1234", serde_json::json!({ "status": "已通过" }));
+/// assert_eq!(
+///     serde_json::to_value(&response).unwrap(),
+///     serde_json::json!({
+///         "toast": { "type": "success", "content": "已审批" },
+///         "card": {
+///             "type": "template",
+///             "data": { "template_id": "AAqk1234", "template_variable": { "status": "已通过" } }
+///         }
+///     })
+/// );
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct CardActionResponse {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    toast: Option<Toast>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    card: Option<CardBody>,
+}
+
+/// toast 提示的类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ToastType {
+    Info,
+    Success,
+    Error,
+    Warning,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+struct Toast {
+    #[serde(rename = "type")]
+    toast_type: ToastType,
+    content: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    i18n: Option<I18nText>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "type", content = "data", rename_all = "lowercase")]
+enum CardBody {
+    Raw(Value),
+    Template {
+        template_id: String,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        template_version_name: Option<String>,
+        template_variable: Value,
+    },
+}
+
+impl CardActionResponse {
+    /// 空响应，不提示也不更新卡片
+    pub fn new() -> Self {
+        CardActionResponse::default()
+    }
+
+    /// 弹出 toast 提示
+    pub fn toast(mut self, toast_type: ToastType, content: impl Into<String>) -> Self {
+        self.toast = Some(Toast {
+            toast_type,
+            content: content.into(),
+            i18n: None,
+        });
+        self
+    }
+
+    /// toast 提示的多语言内容，需要先调用 [`toast`](Self::toast)
+    pub fn toast_i18n(mut self, i18n: I18nText) -> Self {
+        if let Some(toast) = &mut self.toast {
+            toast.i18n = Some(i18n);
+        }
+        self
+    }
+
+    /// 使用卡片 JSON 替换原卡片
+    pub fn card(mut self, card: Value) -> Self {
+        self.card = Some(CardBody::Raw(card));
+        self
+    }
+
+    /// 使用卡片模板替换原卡片
+    pub fn template(mut self, template_id: impl Into<String>, variables: Value) -> Self {
+        self.card = Some(CardBody::Template {
+            template_id: template_id.into(),
+            template_version_name: None,
+            template_variable: variables,
+        });
+        self
+    }
+
+    /// 使用 [`CardTemplate`] 替换原卡片
+    pub fn card_template(mut self, template: &CardTemplate) -> Self {
+        let variables = template.template_variables();
+        self.card = Some(CardBody::Template {
+            template_id: template.template_id().to_string(),
+            template_version_name: template.template_version().map(String::from),
+            template_variable: serde_json::to_value(variables).unwrap_or_default(),
+        });
+        self
+    }
+
+    /// 指定卡片模板的版本，需要先调用 [`template`](Self::template)
+    pub fn template_version(mut self, version: impl Into<String>) -> Self {
+        if let Some(CardBody::Template {
+            template_version_name,
+            ..
+        }) = &mut self.card
+        {
+            *template_version_name = Some(version.into());
+        }
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    #[cfg(feature = "async")]
+    use crate::event::{envelope, Event, EventDispatcher};
+
+    #[test]
+    fn response() {
+        let response = CardActionResponse::new()
+            .toast(ToastType::Error, "failed")
+            .toast_i18n(I18nText::zh_cn("失败"))
+            .card(json!({"elements": []}));
+        assert_eq!(
+            serde_json::to_value(&response).unwrap(),
+            json!({
+                "toast": {"type": "error", "content": "failed", "i18n": {"zh_cn": "失败"}},
+                "card": {"type": "raw", "data": {"elements": []}}
+            })
+        );
+        let response = CardActionResponse::new()
+            .template("t1", json!({}))
+            .template_version("1.0.0");
+        assert_eq!(
+            serde_json::to_value(&response).unwrap(),
+            json!({"card": {"type": "template", "data": {
+                "template_id": "t1", "template_version_name": "1.0.0", "template_variable": {}
+            }}})
+        );
+        let template = CardTemplate::new("t1")
+            .version("1.0.0")
+            .variable("status", "ok");
+        assert_eq!(
+            serde_json::to_value(CardActionResponse::new().card_template(&template)).unwrap(),
+            json!({"card": template.to_card()})
+        );
+        assert_eq!(
+            serde_json::to_value(CardActionResponse::new()).unwrap(),
+            json!({})
+        );
+    }
+
+    #[cfg(feature = "async")]
+    #[tokio::test]
+    async fn dispatch_card_action() {
+        let dispatcher = EventDispatcher::new()
+            .process_in_background()
+            .on_card_action(|event: Event<CardActionTriggerEvent>| async move {
+                let approved = event.event.action.value["approve"] == true;
+                assert_eq!(event.event.context.open_chat_id.as_deref(), Some("oc_1"));
+                Ok(CardActionResponse::new().toast(ToastType::Success, approved.to_string()))
+            });
+        let body = envelope(
+            "card.action.trigger",
+            json!({
+                "operator": {"tenant_key": "t1", "open_id": "ou_1"},
+                "token": "c-1",
+                "action": {"value": {"approve": true}, "tag": "button"},
+                "host": "im_message",
+                "context": {"open_message_id": "om_1", "open_chat_id": "oc_1"}
+            }),
+        );
+        let dispatched = dispatcher.dispatch(&body).await.unwrap();
+        assert_eq!(
+            dispatched.response_body(),
+            json!({"toast": {"type": "success", "content": "true"}})
+        );
+    }
+}
@@ -0,0 +1,365 @@
+//! 1.0 版本事件的兼容。
+//!
+//! 1.0 版本的事件没有 `header`，公共字段散落在外层和 `event` 中：
+//!
+//! ```json
+//! {
+//!     "uuid": "41b5f371157e3d5341b38b20396e77a3",
+//!     "token": "rvaYgkND1GOiu5MM0E1rncYC6PLtF7JV",
+//!     "ts": "1502199207.7171419",
+//!     "type": "event_callback",
+//!     "event": { "type": "approval_instance", "app_id": "cli_xxx", "tenant_key": "xxx", ... }
+//! }
+//! ```
+//!
+//! 这里将其转换为 2.0 版本的结构，`schema` 为 `1.0`。有 2.0 版本对应事件的（见 `MAPPINGS`），
+//! `header.event_type` 为 2.0 版本的事件类型，`event` 也转换为 2.0 版本的结构，可以直接使用
+//! 2.0 版本的事件类型处理，如 1.0 版本的 `message` 可以解析为
+//! [`MessageReceiveEvent`](crate::event::im::MessageReceiveEvent)；其他事件的
+//! `header.event_type` 为 `event.type`，`event` 保持不变。
+
+use serde_json::{json, Value};
+
+use crate::{LarkError, Result};
+
+/// 如果是 1.0 版本的事件，转换为 2.0 版本的结构，否则原样返回
+pub(crate) fn normalize(payload: Value) -> Result<Value> {
+    if payload.get("header").is_some() || payload["type"] != "event_callback" {
+        return Ok(payload);
+    }
+    let event = &payload["event"];
+    let event_type = event["type"]
+        .as_str()
+        .ok_or_else(|| LarkError::InvalidEvent("missing event.type".to_string()))?;
+    let create_time = payload["ts"]
+        .as_str()
+        .and_then(|ts| ts.parse::<f64>().ok())
+        .map(|ts| ((ts * 1000.0) as u64).to_string())
+        .unwrap_or_default();
+    let (event_type, body) = match MAPPINGS.iter().find(|(v1, _, _)| *v1 == event_type) {
+        Some((_, v2, convert)) => (*v2, convert(event, &create_time)),
+        None => (event_type, event.clone()),
+    };
+    Ok(json!({
+        "schema": "1.0",
+        "header": {
+            "event_id": payload["uuid"].as_str().unwrap_or_default(),
+            "event_type": event_type,
+            "create_time": create_time,
+            "token": payload["token"].as_str().unwrap_or_default(),
+            "app_id": event["app_id"].as_str().unwrap_or_default(),
+            "tenant_key": event["tenant_key"].as_str().unwrap_or_default(),
+        },
+        "event": body,
+    }))
+}
+
+/// 将 1.0 版本的 `event` 转换为 2.0 版本的结构，第二个参数为毫秒时间戳格式的事件发送时间
+type Convert = fn(&Value, &str) -> Value;
+
+/// 有 2.0 版本对应事件的 1.0 版本事件：1.0 版本的事件类型、2.0 版本的事件类型和 `event` 的转换
+const MAPPINGS: &[(&str, &str, Convert)] = &[
+    ("message", "im.message.receive_v1", message),
+    (
+        "p2p_chat_create",
+        "im.chat.access_event.bot_p2p_chat_entered_v1",
+        p2p_chat_create,
+    ),
+    ("chat_disband", "im.chat.disbanded_v1", chat_disband),
+    ("user_add", "contact.user.created_v3", user),
+    ("user_update", "contact.user.updated_v3", user),
+    ("user_leave", "contact.user.deleted_v3", user),
+    ("dept_add", "contact.department.created_v3", department),
+    ("dept_update", "contact.department.updated_v3", department),
+    ("dept_delete", "contact.department.deleted_v3", department),
+];
+
+/// 1.0 版本的 `employee_id` 即 2.0 版本的 `user_id`
+fn user_id(value: &Value) -> Value {
+    let user_id = match &value["user_id"] {
+        Value::Null => &value["employee_id"],
+        user_id => user_id,
+    };
+    json!({
+        "open_id": value["open_id"],
+        "union_id": value["union_id"],
+        "user_id": user_id,
+    })
+}
+
+/// 消息内容为 1.0 版本 `event` 的全部字段，`text`、`image_key`、`file_key` 等字段与 2.0 版本的
+/// 消息内容同名；1.0 版本没有消息的发送时间，使用事件的发送时间
+fn message(event: &Value, create_time: &str) -> Value {
+    let chat_type = match event["chat_type"].as_str() {
+        Some("private") => "p2p",
+        Some(chat_type) => chat_type,
+        None => "",
+    };
+    json!({
+        "sender": {
+            "sender_id": user_id(event),
+            "sender_type": "user",
+            "tenant_key": event["tenant_key"],
+        },
+        "message": {
+            "message_id": event["open_message_id"],
+            "root_id": event["root_id"],
+            "parent_id": event["parent_id"],
+            "create_time": create_time,
+            "chat_id": event["open_chat_id"],
+            "chat_type": chat_type,
+            "message_type": event["msg_type"],
+            "content": event.to_string(),
+        },
+    })
+}
+
+/// 1.0 版本只在第一次打开单聊时推送，单聊中还没有消息
+fn p2p_chat_create(event: &Value, _: &str) -> Value {
+    json!({
+        "chat_id": event["chat_id"],
+        "operator_id": user_id(&event["operator"]),
+    })
+}
+
+fn chat_disband(event: &Value, _: &str) -> Value {
+    json!({
+        "chat_id": event["chat_id"],
+        "operator_id": user_id(&event["operator"]),
+    })
+}
+
+/// 1.0 版本只有用户的 ID
+fn user(event: &Value, _: &str) -> Value {
+    json!({ "object": user_id(event) })
+}
+
+/// 1.0 版本只有部门的 `open_department_id`
+fn department(event: &Value, _: &str) -> Value {
+    json!({ "object": { "open_department_id": event["open_department_id"] } })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::enums::ChatType;
+    use crate::event::approval::ApprovalInstanceEvent;
+    use crate::event::contact::{
+        DepartmentCreatedEvent, DepartmentDeletedEvent, DepartmentUpdatedEvent, UserCreatedEvent,
+        UserDeletedEvent, UserUpdatedEvent,
+    };
+    use crate::event::im::{
+        BotP2pChatEnteredEvent, ChatDisbandedEvent, MessageContent, MessageReceiveEvent,
+    };
+    #[cfg(feature = "async")]
+    use crate::event::{Dispatched, EventDispatcher};
+    use crate::event::{Event, EventType};
+
+    #[test]
+    fn normalize_v1() {
+        let payload = normalize(json!({
+            "uuid": "41b5f371157e3d5341b38b20396e77a3",
+            "token": "token",
+            "ts": "1502199207.7171419",
+            "type": "event_callback",
+            "event": {
+                "type": "approval_instance",
+                "app_id": "cli_1",
+                "tenant_key": "t1",
+                "approval_code": "A",
+                "instance_code": "I",
+                "status": "PENDING"
+            }
+        }))
+        .unwrap();
+        let event: Event<ApprovalInstanceEvent> = serde_json::from_value(payload).unwrap();
+        assert_eq!(event.schema, "1.0");
+        assert_eq!(event.header.event_id, "41b5f371157e3d5341b38b20396e77a3");
+        assert_eq!(event.header.event_type, "approval_instance");
+        assert_eq!(event.header.create_time, "1502199207717");
+        assert_eq!(event.header.app_id, "cli_1");
+        assert_eq!(event.event.instance_code, "I");
+    }
+
+    #[cfg(feature = "async")]
+    #[tokio::test]
+    async fn dispatch_v1() {
+        let dispatcher = EventDispatcher::new().verification_token("token").on(
+            |event: Event<ApprovalInstanceEvent>| async move {
+                assert_eq!(event.event.status, "APPROVED");
+                Ok(())
+            },
+        );
+        let body =
+            br#"{"uuid":"u1","token":"token","ts":"1502199207.7171419","type":"event_callback",
+            "event":{"type":"approval_instance","app_id":"cli_1","tenant_key":"t1",
+            "approval_code":"A","instance_code":"I","status":"APPROVED"}}"#;
+        assert_eq!(
+            dispatcher.dispatch(body).await.unwrap(),
+            Dispatched::Handled
+        );
+        let forged = br#"{"uuid":"u1","token":"other","type":"event_callback","event":{"type":"approval_instance"}}"#;
+        assert!(dispatcher.dispatch(forged).await.is_err());
+    }
+
+    /// 转换 1.0 版本的事件，检查事件类型并解析为 2.0 版本的事件
+    fn convert<T: EventType + serde::de::DeserializeOwned>(event: Value) -> T {
+        let payload = normalize(json!({
+            "uuid": "u1",
+            "token": "token",
+            "ts": "1502199207.7171419",
+            "type": "event_callback",
+            "event": event,
+        }))
+        .unwrap();
+        assert_eq!(payload["schema"], "1.0");
+        assert_eq!(payload["header"]["event_type"], T::EVENT_TYPE);
+        assert_eq!(payload["header"]["tenant_key"], "t1");
+        let event: Event<T> = serde_json::from_value(payload).unwrap();
+        event.event
+    }
+
+    #[test]
+    fn message() {
+        let event: MessageReceiveEvent = convert(json!({
+            "type": "message",
+            "app_id": "cli_1",
+            "tenant_key": "t1",
+            "root_id": "",
+            "parent_id": "",
+            "open_chat_id": "oc_1",
+            "chat_type": "private",
+            "msg_type": "text",
+            "open_id": "ou_1",
+            "employee_id": "e1",
+            "union_id": "on_1",
+            "open_message_id": "om_1",
+            "is_mention": false,
+            "text": "hello",
+            "text_without_at_bot": "hello"
+        }));
+        assert_eq!(event.sender.sender_id.open_id.as_deref(), Some("ou_1"));
+        assert_eq!(event.sender.sender_id.user_id.as_deref(), Some("e1"));
+        assert_eq!(event.sender.tenant_key.as_deref(), Some("t1"));
+        let message = event.message;
+        assert_eq!(message.message_id, "om_1");
+        assert_eq!(message.root_id, None);
+        assert_eq!(message.chat_id, "oc_1");
+        assert_eq!(message.chat_type, ChatType::P2p);
+        assert_eq!(message.create_time.millis(), 1502199207717);
+        match message.content {
+            MessageContent::Text(text) => assert_eq!(text.text, "hello"),
+            other => panic!("unexpected content {:?}", other),
+        }
+
+        let event: MessageReceiveEvent = convert(json!({
+            "type": "message",
+            "tenant_key": "t1",
+            "open_chat_id": "oc_1",
+            "chat_type": "group",
+            "msg_type": "image",
+            "open_id": "ou_1",
+            "open_message_id": "om_2",
+            "image_key": "img_1"
+        }));
+        assert_eq!(event.message.chat_type, ChatType::Group);
+        match event.message.content {
+            MessageContent::Image(image) => assert_eq!(image.image_key, "img_1"),
+            other => panic!("unexpected content {:?}", other),
+        }
+    }
+
+    #[test]
+    fn p2p_chat_create() {
+        let event: BotP2pChatEnteredEvent = convert(json!({
+            "type": "p2p_chat_create",
+            "app_id": "cli_1",
+            "tenant_key": "t1",
+            "chat_id": "oc_1",
+            "operator": {"open_id": "ou_1", "user_id": "e1"},
+            "user": {"name": "张三", "open_id": "ou_1", "user_id": "e1"}
+        }));
+        assert_eq!(event.chat_id, "oc_1");
+        assert_eq!(event.operator_id.open_id.as_deref(), Some("ou_1"));
+        assert_eq!(event.operator_id.user_id.as_deref(), Some("e1"));
+        assert!(event.is_first_visit());
+    }
+
+    #[test]
+    fn chat_disband() {
+        let event: ChatDisbandedEvent = convert(json!({
+            "type": "chat_disband",
+            "app_id": "cli_1",
+            "tenant_key": "t1",
+            "chat_id": "oc_1",
+            "operator": {"open_id": "ou_1", "user_id": "e1"}
+        }));
+        assert_eq!(event.chat_id, "oc_1");
+        assert_eq!(event.operator_id.open_id.as_deref(), Some("ou_1"));
+    }
+
+    fn v1_user(event_type: &str) -> Value {
+        json!({
+            "type": event_type,
+            "app_id": "cli_1",
+            "tenant_key": "t1",
+            "open_id": "ou_1",
+            "employee_id": "e1",
+            "union_id": "on_1"
+        })
+    }
+
+    #[test]
+    fn user_add() {
+        let event: UserCreatedEvent = convert(v1_user("user_add"));
+        assert_eq!(event.object.open_id.as_deref(), Some("ou_1"));
+        assert_eq!(event.object.user_id.as_deref(), Some("e1"));
+        assert_eq!(event.object.union_id.as_deref(), Some("on_1"));
+    }
+
+    #[test]
+    fn user_update() {
+        let event: UserUpdatedEvent = convert(v1_user("user_update"));
+        assert_eq!(event.object.user_id.as_deref(), Some("e1"));
+        assert_eq!(event.old_object, Default::default());
+    }
+
+    #[test]
+    fn user_leave() {
+        let event: UserDeletedEvent = convert(v1_user("user_leave"));
+        assert_eq!(event.object.open_id.as_deref(), Some("ou_1"));
+    }
+
+    fn v1_department(event_type: &str) -> Value {
+        json!({
+            "type": event_type,
+            "app_id": "cli_1",
+            "tenant_key": "t1",
+            "open_department_id": "od_1"
+        })
+    }
+
+    #[test]
+    fn dept_add() {
+        let event: DepartmentCreatedEvent = convert(v1_department("dept_add"));
+        assert_eq!(event.object.open_department_id.as_deref(), Some("od_1"));
+    }
+
+    #[test]
+    fn dept_update() {
+        let event: DepartmentUpdatedEvent = convert(v1_department("dept_update"));
+        assert_eq!(event.object.open_department_id.as_deref(), Some("od_1"));
+    }
+
+    #[test]
+    fn dept_delete() {
+        let event: DepartmentDeletedEvent = convert(v1_department("dept_delete"));
+        assert_eq!(event.object.open_department_id.as_deref(), Some("od_1"));
+    }
+
+    #[test]
+    fn keep_v2() {
+        let payload = json!({"schema": "2.0", "header": {"event_type": "x"}, "event": {}});
+        assert_eq!(normalize(payload.clone()).unwrap(), payload);
+    }
+}
@@ -0,0 +1,199 @@
+//! 通讯录相关的事件。
+
+use serde::{Deserialize, Serialize};
+
+use super::EventType;
+
+/// 事件中的用户信息，字段是否存在取决于应用的权限，修改事件的 `old_object` 只包含修改了的字段
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ContactUser {
+    pub open_id: Option<String>,
+    pub union_id: Option<String>,
+    pub user_id: Option<String>,
+    pub name: Option<String>,
+    pub en_name: Option<String>,
+    pub nickname: Option<String>,
+    pub email: Option<String>,
+    pub enterprise_email: Option<String>,
+    pub mobile: Option<String>,
+    pub job_title: Option<String>,
+    /// 0：保密，1：男，2：女
+    pub gender: Option<i32>,
+    pub avatar: Option<Avatar>,
+    pub status: Option<UserStatus>,
+    pub department_ids: Option<Vec<String>>,
+    pub leader_user_id: Option<String>,
+    pub city: Option<String>,
+    pub country: Option<String>,
+    pub work_station: Option<String>,
+    /// 入职时间，秒级时间戳
+    pub join_time: Option<i64>,
+    pub is_tenant_manager: Option<bool>,
+    pub employee_no: Option<String>,
+    pub employee_type: Option<i32>,
+}
+
+/// 用户头像
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Avatar {
+    pub avatar_72: Option<String>,
+    pub avatar_240: Option<String>,
+    pub avatar_640: Option<String>,
+    pub avatar_origin: Option<String>,
+}
+
+/// 用户状态
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct UserStatus {
+    pub is_frozen: bool,
+    pub is_resigned: bool,
+    pub is_activated: bool,
+    pub is_exited: bool,
+    pub is_unjoin: bool,
+}
+
+/// 员工入职，`contact.user.created_v3`
+#[derive(Debug, Clone, Deserialize)]
+pub struct UserCreatedEvent {
+    pub object: ContactUser,
+}
+
+impl EventType for UserCreatedEvent {
+    const EVENT_TYPE: &'static str = "contact.user.created_v3";
+}
+
+/// 员工信息变化，`contact.user.updated_v3`
+#[derive(Debug, Clone, Deserialize)]
+pub struct UserUpdatedEvent {
+    pub object: ContactUser,
+    /// 修改前的信息，只包含修改了的字段
+    #[serde(default)]
+    pub old_object: ContactUser,
+}
+
+impl EventType for UserUpdatedEvent {
+    const EVENT_TYPE: &'static str = "contact.user.updated_v3";
+}
+
+/// 员工离职，`contact.user.deleted_v3`
+#[derive(Debug, Clone, Deserialize)]
+pub struct UserDeletedEvent {
+    pub object: ContactUser,
+    /// 离职前的部门等信息
+    #[serde(default)]
+    pub old_object: ContactUser,
+}
+
+impl EventType for UserDeletedEvent {
+    const EVENT_TYPE: &'static str = "contact.user.deleted_v3";
+}
+
+/// 事件中的部门信息，修改事件的 `old_object` 只包含修改了的字段
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ContactDepartment {
+    pub name: Option<String>,
+    pub department_id: Option<String>,
+    pub open_department_id: Option<String>,
+    pub parent_department_id: Option<String>,
+    pub leader_user_id: Option<String>,
+    pub chat_id: Option<String>,
+    pub order: Option<i64>,
+    pub status: Option<DepartmentStatus>,
+}
+
+/// 部门状态
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DepartmentStatus {
+    pub is_deleted: bool,
+}
+
+/// 部门新建，`contact.department.created_v3`
+#[derive(Debug, Clone, Deserialize)]
+pub struct DepartmentCreatedEvent {
+    pub object: ContactDepartment,
+}
+
+impl EventType for DepartmentCreatedEvent {
+    const EVENT_TYPE: &'static str = "contact.department.created_v3";
+}
+
+/// 部门信息变化，`contact.department.updated_v3`
+#[derive(Debug, Clone, Deserialize)]
+pub struct DepartmentUpdatedEvent {
+    pub object: ContactDepartment,
+    /// 修改前的信息，只包含修改了的字段
+    #[serde(default)]
+    pub old_object: ContactDepartment,
+}
+
+impl EventType for DepartmentUpdatedEvent {
+    const EVENT_TYPE: &'static str = "contact.department.updated_v3";
+}
+
+/// 部门被删除，`contact.department.deleted_v3`
+#[derive(Debug, Clone, Deserialize)]
+pub struct DepartmentDeletedEvent {
+    pub object: ContactDepartment,
+    #[serde(default)]
+    pub old_object: ContactDepartment,
+}
+
+impl EventType for DepartmentDeletedEvent {
+    const EVENT_TYPE: &'static str = "contact.department.deleted_v3";
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn user_updated() {
+        let event: UserUpdatedEvent = serde_json::from_value(serde_json::json!({
+            "object": {
+                "open_id": "ou_1",
+                "union_id": "on_1",
+                "user_id": "e33ggbyz",
+                "name": "张三",
+                "department_ids": ["od-1"],
+                "status": {"is_frozen": false, "is_resigned": false, "is_activated": true},
+                "join_time": 1615381702,
+                "unknown_field": "ignored"
+            },
+            "old_object": {
+                "department_ids": ["od-2"]
+            }
+        }))
+        .unwrap();
+        assert_eq!(event.object.name.as_deref(), Some("张三"));
+        assert!(event.object.status.unwrap().is_activated);
+        assert_eq!(
+            event.old_object.department_ids,
+            Some(vec!["od-2".to_string()])
+        );
+        assert_eq!(event.old_object.name, None);
+    }
+
+    #[test]
+    fn department_deleted() {
+        let event: DepartmentDeletedEvent = serde_json::from_value(serde_json::json!({
+            "object": {
+                "open_department_id": "od-1",
+                "department_id": "d1",
+                "status": {"is_deleted": true}
+            },
+            "old_object": {"name": "研发部", "parent_department_id": "0"}
+        }))
+        .unwrap();
+        assert!(event.object.status.unwrap().is_deleted);
+        assert_eq!(event.old_object.name.as_deref(), Some("研发部"));
+        assert_eq!(
+            DepartmentDeletedEvent::EVENT_TYPE,
+            "contact.department.deleted_v3"
+        );
+    }
+}
@@ -0,0 +1,139 @@
+//! 加密事件的解密。
+//!
+//! 配置了 Encrypt Key 后事件以 `{"encrypt": "..."}` 推送：base64 解码后前 16 字节为 IV，
+//! 其余为使用 `SHA256(encrypt_key)` 作为密钥、AES-256-CBC 加密的事件内容。
+//!
+//! 同时请求头 `X-Lark-Signature` 为 `SHA256(timestamp + nonce + encrypt_key + body)` 的十六进制，
+//! `timestamp` 和 `nonce` 分别来自请求头 `X-Lark-Request-Timestamp` 和 `X-Lark-Request-Nonce`。
+
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use aes::cipher::block_padding::Pkcs7;
+use aes::cipher::{BlockDecryptMut, KeyIvInit};
+use base64::Engine;
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+
+use crate::{LarkError, Result};
+
+type Aes256CbcDec = cbc::Decryptor<aes::Aes256>;
+
+pub(crate) struct EncryptKey {
+    secret: String,
+    key: [u8; 32],
+    hits: AtomicU64,
+}
+
+impl EncryptKey {
+    pub(crate) fn new(encrypt_key: &str) -> Self {
+        EncryptKey {
+            secret: encrypt_key.to_string(),
+            key: Sha256::digest(encrypt_key.as_bytes()).into(),
+            hits: AtomicU64::new(0),
+        }
+    }
+
+    pub(crate) fn hits(&self) -> u64 {
+        self.hits.load(Ordering::Relaxed)
+    }
+
+    /// 请求的签名
+    pub(crate) fn signature(&self, timestamp: &str, nonce: &str, body: &[u8]) -> String {
+        let mut hasher = Sha256::new();
+        hasher.update(timestamp.as_bytes());
+        hasher.update(nonce.as_bytes());
+        hasher.update(self.secret.as_bytes());
+        hasher.update(body);
+        hex(&hasher.finalize())
+    }
+
+    fn decrypt(&self, data: &[u8]) -> Option<Value> {
+        if data.len() <= 16 {
+            return None;
+        }
+        let (iv, ciphertext) = data.split_at(16);
+        let plaintext = Aes256CbcDec::new(&self.key.into(), iv.into())
+            .decrypt_padded_vec_mut::<Pkcs7>(ciphertext)
+            .ok()?;
+        serde_json::from_slice(&plaintext).ok()
+    }
+}
+
+/// 依次尝试每个 Encrypt Key 解密，返回解密后的事件
+pub(crate) fn decrypt(keys: &[EncryptKey], encrypted: &str) -> Result<Value> {
+    let data = base64::engine::general_purpose::STANDARD
+        .decode(encrypted)
+        .map_err(|err| LarkError::InvalidEvent(format!("invalid encrypted event: {}", err)))?;
+    for (index, key) in keys.iter().enumerate() {
+        if let Some(payload) = key.decrypt(&data) {
+            key.hits.fetch_add(1, Ordering::Relaxed);
+            log::debug!("event decrypted with encrypt key #{}", index);
+            return Ok(payload);
+        }
+    }
+    Err(LarkError::InvalidEvent(
+        "no encrypt key can decrypt the event".to_string(),
+    ))
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use aes::cipher::BlockEncryptMut;
+
+    use super::*;
+
+    /// 使用 `encrypt_key` 加密事件，IV 固定
+    pub(crate) fn encrypt(encrypt_key: &str, plaintext: &str) -> String {
+        let key: [u8; 32] = Sha256::digest(encrypt_key.as_bytes()).into();
+        let iv = [7u8; 16];
+        let mut data = iv.to_vec();
+        data.extend(
+            cbc::Encryptor::<aes::Aes256>::new(&key.into(), &iv.into())
+                .encrypt_padded_vec_mut::<Pkcs7>(plaintext.as_bytes()),
+        );
+        base64::engine::general_purpose::STANDARD.encode(data)
+    }
+
+    #[test]
+    fn decrypt_with_rotated_keys() {
+        let keys = [EncryptKey::new("new"), EncryptKey::new("old")];
+        let encrypted = encrypt("old", r#"{"type":"url_verification"}"#);
+        let payload = decrypt(&keys, &encrypted).unwrap();
+        assert_eq!(payload["type"], "url_verification");
+        assert_eq!((keys[0].hits(), keys[1].hits()), (0, 1));
+
+        let encrypted = encrypt("other", r#"{"type":"url_verification"}"#);
+        assert!(decrypt(&keys, &encrypted).is_err());
+        assert!(decrypt(&keys, "not base64!").is_err());
+    }
+
+    #[cfg(feature = "async")]
+    #[tokio::test]
+    async fn dispatch_encrypted() {
+        use crate::event::{Dispatched, EventDispatcher};
+
+        let dispatcher = EventDispatcher::new()
+            .verification_token("token")
+            .encrypt_key("new")
+            .encrypt_key("old");
+        let plaintext = r#"{"challenge":"c1","token":"token","type":"url_verification"}"#;
+        for key in ["old", "new", "new"] {
+            let body = serde_json::json!({ "encrypt": encrypt(key, plaintext) }).to_string();
+            assert_eq!(
+                dispatcher.dispatch(body.as_bytes()).await.unwrap(),
+                Dispatched::Challenge("c1".to_string())
+            );
+        }
+        assert_eq!(dispatcher.encrypt_key_hits(), [2, 1]);
+
+        let body = serde_json::json!({ "encrypt": encrypt("new", plaintext) }).to_string();
+        assert!(EventDispatcher::new()
+            .dispatch(body.as_bytes())
+            .await
+            .is_err());
+    }
+}
@@ -0,0 +1,371 @@
+use std::collections::HashMap;
+use std::future::Future;
+use std::time::Duration;
+
+use futures_util::future::BoxFuture;
+use serde_json::Value;
+
+use super::callback::{CallbackConfig, ParsedCallback};
+use super::card::{CardActionResponse, CardActionTriggerEvent};
+use super::sink::EventSink;
+use super::{Event, EventType, RawEvent};
+use crate::{LarkError, Result};
+
+type Handler = Box<dyn Fn(Value) -> BoxFuture<'static, Result<()>> + Send + Sync>;
+type CardHandler = Box<dyn Fn(Value) -> BoxFuture<'static, Result<Value>> + Send + Sync>;
+type RawHandler = Box<dyn Fn(RawEvent) -> BoxFuture<'static, Result<()>> + Send + Sync>;
+
+/// 按照事件类型将事件分发给注册的处理函数，配置了 Encrypt Key 时先解密事件。
+///
+/// ```
+/// use lark_sdk::event::im::{ChatDisbandedEvent, MessageReceiveEvent};
+/// use lark_sdk::event::{Event, EventDispatcher};
+///
+/// let dispatcher = EventDispatcher::new()
+///     .verification_token("rvaYgkND1GOiu5MM0E1rncYC6PLtF7JV")
+///     .on(|event: Event<MessageReceiveEvent>| async move {
+///         println!("{:?}", event.event.message.content);
+///         Ok(())
+///     })
+///     .on(|event: Event<ChatDisbandedEvent>| async move {
+///         println!("chat {} disbanded", event.event.chat_id);
+///         Ok(())
+///     });
+/// ```
+#[derive(Default)]
+pub struct EventDispatcher {
+    callback: CallbackConfig,
+    handlers: HashMap<&'static str, Handler>,
+    middlewares: Vec<RawHandler>,
+    fallback: Option<RawHandler>,
+    card_handler: Option<CardHandler>,
+    handler_timeout: Option<Duration>,
+    background: bool,
+}
+
+/// 事件的处理结果
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Dispatched {
+    /// 配置订阅地址时的 URL 验证请求，需要原样返回 `challenge`
+    Challenge(String),
+    /// 事件已经由处理函数处理
+    Handled,
+    /// 没有注册该类型事件的处理函数
+    Unhandled(String),
+    /// 事件已经交给后台任务处理，见 [`EventDispatcher::process_in_background`]
+    Queued,
+    /// 处理函数超时，事件被丢弃，见 [`EventDispatcher::handler_timeout`]
+    TimedOut,
+    /// 处理函数给出了响应内容，如卡片回传交互的 [`CardActionResponse`]
+    Responded(Value),
+}
+
+impl Dispatched {
+    /// 返回给开放平台的响应内容
+    pub fn response_body(&self) -> Value {
+        match self {
+            Dispatched::Challenge(challenge) => serde_json::json!({ "challenge": challenge }),
+            Dispatched::Responded(body) => body.clone(),
+            _ => serde_json::json!({}),
+        }
+    }
+}
+
+impl EventDispatcher {
+    pub fn new() -> Self {
+        EventDispatcher::default()
+    }
+
+    /// 校验事件中的 Verification Token
+    pub fn verification_token(mut self, token: impl Into<String>) -> Self {
+        self.callback = self.callback.verification_token(token);
+        self
+    }
+
+    /// 添加用于解密事件的 Encrypt Key，可以多次调用。
+    ///
+    /// 更换 Encrypt Key 期间同时配置新旧两个，解密时按照添加的顺序依次尝试，
+    /// 每个 Key 解密成功的次数可以通过 [`encrypt_key_hits`](Self::encrypt_key_hits) 查看，
+    /// 旧 Key 不再命中后即可移除。
+    pub fn encrypt_key(mut self, key: impl AsRef<str>) -> Self {
+        self.callback = self.callback.encrypt_key(key);
+        self
+    }
+
+    /// 按照添加的顺序返回每个 Encrypt Key 解密成功的次数
+    pub fn encrypt_key_hits(&self) -> Vec<u64> {
+        self.callback.encrypt_key_hits()
+    }
+
+    /// 注册 `T` 类型事件的处理函数，同一类型重复注册时使用最后一次注册的函数
+    pub fn on<T, F, Fut>(mut self, handler: F) -> Self
+    where
+        T: EventType + Send + 'static,
+        F: Fn(Event<T>) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<()>> + Send + 'static,
+    {
+        let handler: Handler =
+            Box::new(
+                move |payload| match serde_json::from_value::<Event<T>>(payload) {
+                    Ok(event) => Box::pin(handler(event)),
+                    Err(err) => Box::pin(async move { Err(LarkError::Json(err)) }),
+                },
+            );
+        self.handlers.insert(T::EVENT_TYPE, handler);
+        self
+    }
+
+    /// 注册中间件，在事件交给处理函数之前按照注册的顺序调用，可用于审计日志、转发到消息队列等。
+    ///
+    /// 中间件返回错误时不再继续处理该事件，`dispatch` 返回该错误。
+    pub fn middleware<F, Fut>(mut self, middleware: F) -> Self
+    where
+        F: Fn(RawEvent) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<()>> + Send + 'static,
+    {
+        self.middlewares
+            .push(Box::new(move |event| Box::pin(middleware(event))));
+        self
+    }
+
+    /// 注册卡片回传交互的处理函数，返回的 [`CardActionResponse`] 通过 [`Dispatched::response_body`] 响应给开放平台。
+    ///
+    /// 由于需要响应内容，该处理函数总是在 `dispatch` 中执行，不受
+    /// [`process_in_background`](Self::process_in_background) 影响。
+    pub fn on_card_action<F, Fut>(mut self, handler: F) -> Self
+    where
+        F: Fn(Event<CardActionTriggerEvent>) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<CardActionResponse>> + Send + 'static,
+    {
+        let handler: CardHandler = Box::new(move |payload| {
+            match serde_json::from_value::<Event<CardActionTriggerEvent>>(payload) {
+                Ok(event) => {
+                    let response = handler(event);
+                    Box::pin(async move { Ok(serde_json::to_value(response.await?)?) })
+                }
+                Err(err) => Box::pin(async move { Err(LarkError::Json(err)) }),
+            }
+        });
+        self.card_handler = Some(handler);
+        self
+    }
+
+    /// 将事件转发到消息队列，见 [`sink`](super::sink)。转发作为一个中间件，与其他中间件按照注册的顺序执行
+    pub fn sink(self, sink: impl EventSink) -> Self {
+        let sink = std::sync::Arc::new(sink);
+        self.middleware(move |event| {
+            let sink = sink.clone();
+            async move { sink.publish(&event).await }
+        })
+    }
+
+    /// 注册没有对应处理函数的事件的处理函数，注册后这类事件的结果为 [`Dispatched::Handled`]
+    pub fn fallback<F, Fut>(mut self, handler: F) -> Self
+    where
+        F: Fn(RawEvent) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<()>> + Send + 'static,
+    {
+        self.fallback = Some(Box::new(move |event| Box::pin(handler(event))));
+        self
+    }
+
+    /// 处理函数的最长执行时间，超时后记录日志并丢弃该事件，不再阻塞对开放平台的响应
+    pub fn handler_timeout(mut self, timeout: Duration) -> Self {
+        self.handler_timeout = Some(timeout);
+        self
+    }
+
+    /// 解密、校验并执行完中间件后立即返回 [`Dispatched::Queued`]，处理函数在 tokio 的后台任务中执行，
+    /// 处理函数返回的错误只记录日志。
+    ///
+    /// 开放平台要求在几秒内响应事件推送，否则会重新推送，处理函数耗时较长时使用。
+    /// 需要在 tokio 运行时中调用 `dispatch`。
+    pub fn process_in_background(mut self) -> Self {
+        self.background = true;
+        self
+    }
+
+    /// 处理一次事件推送的请求体
+    pub async fn dispatch(&self, body: &[u8]) -> Result<Dispatched> {
+        let event = match self.callback.parse(body)? {
+            ParsedCallback::Challenge(challenge) => return Ok(Dispatched::Challenge(challenge)),
+            ParsedCallback::CardAction(event) | ParsedCallback::Event(event) => event,
+        };
+        for middleware in &self.middlewares {
+            middleware(event.clone()).await?;
+        }
+
+        let event_type = event.header.event_type.clone();
+        if let Some(handler) = &self.card_handler {
+            if event_type == CardActionTriggerEvent::EVENT_TYPE {
+                let handling = handler(event.payload);
+                return run(
+                    handling,
+                    self.handler_timeout,
+                    &event_type,
+                    Dispatched::Responded,
+                )
+                .await;
+            }
+        }
+        let handling = match self.handlers.get(event_type.as_str()) {
+            Some(handler) => handler(event.payload),
+            None => match &self.fallback {
+                Some(fallback) => fallback(event),
+                None => return Ok(Dispatched::Unhandled(event_type)),
+            },
+        };
+        let timeout = self.handler_timeout;
+        if self.background {
+            tokio::spawn(async move {
+                if let Err(err) = run(handling, timeout, &event_type, |_| Dispatched::Handled).await
+                {
+                    log::error!("failed to handle event {}: {}", event_type, err);
+                }
+            });
+            return Ok(Dispatched::Queued);
+        }
+        run(handling, timeout, &event_type, |_| Dispatched::Handled).await
+    }
+}
+
+/// 执行处理函数，超过 `timeout` 时记录日志并返回 [`Dispatched::TimedOut`]
+async fn run<T>(
+    handling: BoxFuture<'static, Result<T>>,
+    timeout: Option<Duration>,
+    event_type: &str,
+    dispatched: fn(T) -> Dispatched,
+) -> Result<Dispatched> {
+    let timeout = match timeout {
+        Some(timeout) => timeout,
+        None => return handling.await.map(dispatched),
+    };
+    match tokio::time::timeout(timeout, handling).await {
+        Ok(result) => result.map(dispatched),
+        Err(_) => {
+            log::warn!(
+                "handler of event {} did not finish in {:?}, dropped",
+                event_type,
+                timeout
+            );
+            Ok(Dispatched::TimedOut)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+
+    use super::*;
+    use crate::event::envelope;
+    use crate::event::im::ChatDisbandedEvent;
+
+    fn chat_disbanded(event_type: &str) -> Vec<u8> {
+        envelope(
+            event_type,
+            serde_json::json!({"chat_id": "oc_1", "operator_id": {"open_id": "ou_1"}}),
+        )
+    }
+
+    #[tokio::test]
+    async fn middleware_and_fallback() {
+        let seen = Arc::new(Mutex::new(Vec::new()));
+        let (audit, unknown) = (seen.clone(), seen.clone());
+        let dispatcher = EventDispatcher::new()
+            .middleware(move |event| {
+                let seen = audit.clone();
+                async move {
+                    let entry = format!("audit {} {}", event.tenant_key(), event.event_type());
+                    seen.lock().unwrap().push(entry);
+                    Ok(())
+                }
+            })
+            .on(|_: Event<ChatDisbandedEvent>| async { Ok(()) })
+            .fallback(move |event| {
+                let seen = unknown.clone();
+                async move {
+                    let chat = event.parse::<ChatDisbandedEvent>()?.event.chat_id;
+                    seen.lock().unwrap().push(format!("fallback {}", chat));
+                    Ok(())
+                }
+            });
+
+        for event_type in ["im.chat.disbanded_v1", "im.chat.updated_v1"] {
+            let dispatched = dispatcher
+                .dispatch(&chat_disbanded(event_type))
+                .await
+                .unwrap();
+            assert_eq!(dispatched, Dispatched::Handled);
+        }
+        assert_eq!(
+            *seen.lock().unwrap(),
+            [
+                "audit t1 im.chat.disbanded_v1",
+                "audit t1 im.chat.updated_v1",
+                "fallback oc_1"
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn middleware_rejects() {
+        let handled = Arc::new(Mutex::new(false));
+        let flag = handled.clone();
+        let dispatcher = EventDispatcher::new()
+            .middleware(|event| async move {
+                match event.tenant_key() {
+                    "t1" => Err(LarkError::InvalidEvent("tenant blocked".to_string())),
+                    _ => Ok(()),
+                }
+            })
+            .on(move |_: Event<ChatDisbandedEvent>| {
+                let flag = flag.clone();
+                async move {
+                    *flag.lock().unwrap() = true;
+                    Ok(())
+                }
+            });
+        let result = dispatcher
+            .dispatch(&chat_disbanded("im.chat.disbanded_v1"))
+            .await;
+        assert!(matches!(result, Err(LarkError::InvalidEvent(_))));
+        assert!(!*handled.lock().unwrap());
+    }
+
+    #[tokio::test]
+    async fn handler_timeout() {
+        let dispatcher = EventDispatcher::new()
+            .handler_timeout(Duration::from_millis(20))
+            .on(|_: Event<ChatDisbandedEvent>| async {
+                tokio::time::sleep(Duration::from_secs(10)).await;
+                Ok(())
+            });
+        let dispatched = dispatcher
+            .dispatch(&chat_disbanded("im.chat.disbanded_v1"))
+            .await
+            .unwrap();
+        assert_eq!(dispatched, Dispatched::TimedOut);
+    }
+
+    #[tokio::test]
+    async fn process_in_background() {
+        let (tx, rx) = tokio::sync::oneshot::channel();
+        let tx = Mutex::new(Some(tx));
+        let dispatcher = EventDispatcher::new().process_in_background().on(
+            move |event: Event<ChatDisbandedEvent>| {
+                let tx = tx.lock().unwrap().take();
+                async move {
+                    tx.unwrap().send(event.event.chat_id).unwrap();
+                    Err(LarkError::InvalidEvent("only logged".to_string()))
+                }
+            },
+        );
+        let dispatched = dispatcher
+            .dispatch(&chat_disbanded("im.chat.disbanded_v1"))
+            .await
+            .unwrap();
+        assert_eq!(dispatched, Dispatched::Queued);
+        assert_eq!(rx.await.unwrap(), "oc_1");
+    }
+}
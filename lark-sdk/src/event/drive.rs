@@ -0,0 +1,167 @@
+//! 云文档相关的事件。
+//!
+//! 云文档事件需要先调用「订阅云文档事件」接口订阅具体的文件才会推送。
+
+use serde::{Deserialize, Serialize};
+
+use super::{EventType, UserId};
+use crate::time::{self, LarkTimestamp};
+
+/// 文件协作者变更的事件内容
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PermissionMemberEvent {
+    pub file_token: String,
+    /// 文件类型，如 `doc`、`docx`、`sheet`、`bitable`、`file`
+    pub file_type: String,
+    /// 操作者
+    pub operator_id: UserId,
+    /// 变更的用户
+    #[serde(default)]
+    pub user_list: Vec<UserId>,
+    /// 变更的群
+    #[serde(default)]
+    pub chat_list: Vec<String>,
+    /// 变更的部门
+    #[serde(default)]
+    pub open_department_id_list: Vec<String>,
+}
+
+newtype_events! {
+    /// 添加文件协作者，`drive.file.permission_member_added_v1`
+    PermissionMemberAddedEvent(PermissionMemberEvent) = "drive.file.permission_member_added_v1";
+    /// 移除文件协作者，`drive.file.permission_member_removed_v1`
+    PermissionMemberRemovedEvent(PermissionMemberEvent) = "drive.file.permission_member_removed_v1";
+}
+
+/// 文件编辑，`drive.file.edit_v1`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FileEditEvent {
+    pub file_token: String,
+    pub file_type: String,
+    /// 编辑者
+    #[serde(default)]
+    pub operator_id_list: Vec<UserId>,
+    /// 订阅了该文件的用户
+    #[serde(default)]
+    pub subscriber_id_list: Vec<UserId>,
+    /// 电子表格中被编辑的工作表
+    #[serde(default)]
+    pub sheet_id: Option<String>,
+}
+
+impl EventType for FileEditEvent {
+    const EVENT_TYPE: &'static str = "drive.file.edit_v1";
+}
+
+/// 文件标题变更，`drive.file.title_updated_v1`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FileTitleUpdatedEvent {
+    pub file_token: String,
+    pub file_type: String,
+    pub operator_id: UserId,
+    #[serde(default)]
+    pub subscriber_id_list: Vec<UserId>,
+}
+
+impl EventType for FileTitleUpdatedEvent {
+    const EVENT_TYPE: &'static str = "drive.file.title_updated_v1";
+}
+
+/// 评论的通知信息
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CommentNoticeMeta {
+    pub file_token: String,
+    pub file_type: String,
+    /// 通知类型，如 `add_comment`、`add_reply`
+    #[serde(default)]
+    pub notice_type: Option<String>,
+    /// 评论者
+    pub from_user_id: UserId,
+    /// 被通知的用户
+    #[serde(default)]
+    pub to_user_id: Option<UserId>,
+    /// 通知的时间
+    #[serde(default, with = "time::millis::option")]
+    pub timestamp: Option<LarkTimestamp>,
+}
+
+/// 添加评论或回复，`drive.notice.comment_add_v1`。评论的内容使用
+/// [`GetComment`](crate::drive::comment::GetComment) 获取
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CommentAddEvent {
+    pub comment_id: String,
+    /// 添加回复时为回复的 ID
+    #[serde(default)]
+    pub reply_id: Option<String>,
+    /// 是否 @ 了应用
+    #[serde(default)]
+    pub is_mentioned: bool,
+    pub notice_meta: CommentNoticeMeta,
+}
+
+impl EventType for CommentAddEvent {
+    const EVENT_TYPE: &'static str = "drive.notice.comment_add_v1";
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn permission_member_added() {
+        let event: PermissionMemberAddedEvent = serde_json::from_value(serde_json::json!({
+            "file_type": "doc",
+            "file_token": "doccnxxxxxx",
+            "operator_id": {"open_id": "ou_1", "union_id": "on_1", "user_id": "u_1"},
+            "user_list": [{"open_id": "ou_2"}],
+            "chat_list": ["oc_1"],
+            "open_department_id_list": []
+        }))
+        .unwrap();
+        assert_eq!(event.file_token, "doccnxxxxxx");
+        assert_eq!(event.user_list[0].open_id.as_deref(), Some("ou_2"));
+        assert_eq!(event.chat_list, ["oc_1"]);
+    }
+
+    #[test]
+    fn file_edit() {
+        let event: FileEditEvent = serde_json::from_value(serde_json::json!({
+            "file_type": "sheet",
+            "file_token": "shtcnxxxxxx",
+            "operator_id_list": [{"open_id": "ou_1"}],
+            "subscriber_id_list": [{"open_id": "ou_2"}],
+            "sheet_id": "6Ngkqx"
+        }))
+        .unwrap();
+        assert_eq!(event.sheet_id.as_deref(), Some("6Ngkqx"));
+        assert_eq!(event.operator_id_list.len(), 1);
+    }
+
+    #[test]
+    fn comment_add() {
+        let event: CommentAddEvent = serde_json::from_value(serde_json::json!({
+            "comment_id": "7093172364063375380",
+            "reply_id": "7093172364063391764",
+            "is_mentioned": true,
+            "notice_meta": {
+                "file_token": "doxcnxxxxxx",
+                "file_type": "docx",
+                "notice_type": "add_comment",
+                "from_user_id": {"open_id": "ou_1"},
+                "to_user_id": {"open_id": "ou_2"},
+                "timestamp": "1651494396000"
+            }
+        }))
+        .unwrap();
+        assert!(event.is_mentioned);
+        assert_eq!(event.notice_meta.file_type, "docx");
+        assert_eq!(
+            event.notice_meta.timestamp,
+            Some(LarkTimestamp::from_millis(1651494396000))
+        );
+        assert_eq!(
+            event.notice_meta.from_user_id.open_id.as_deref(),
+            Some("ou_1")
+        );
+    }
+}
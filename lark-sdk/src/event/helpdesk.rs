@@ -0,0 +1,72 @@
+//! 服务台相关的事件。
+
+use serde::{Deserialize, Serialize};
+
+use super::{EventType, UserId};
+use crate::time::{self, LarkTimestamp};
+
+/// 工单的用户
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TicketUser {
+    pub id: UserId,
+    #[serde(default)]
+    pub name: Option<String>,
+}
+
+/// 创建工单，`helpdesk.ticket.created_v1`。回复工单使用
+/// [`Helpdesk::reply_to_ticket`](crate::helpdesk::Helpdesk::reply_to_ticket)
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TicketCreatedEvent {
+    pub ticket_id: String,
+    pub helpdesk_id: String,
+    /// 提交工单的用户
+    #[serde(default)]
+    pub guest: Option<TicketUser>,
+    /// 1：机器人服务，2：人工服务
+    #[serde(default)]
+    pub stage: Option<i32>,
+    /// 工单状态，如 1：已创建，50：机器人关闭
+    #[serde(default)]
+    pub status: Option<i32>,
+    /// 创建时间
+    #[serde(default, with = "time::millis::option")]
+    pub created_at: Option<LarkTimestamp>,
+    /// 工单所在的群
+    #[serde(default)]
+    pub chat_id: Option<String>,
+}
+
+impl EventType for TicketCreatedEvent {
+    const EVENT_TYPE: &'static str = "helpdesk.ticket.created_v1";
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ticket_created() {
+        let event: TicketCreatedEvent = serde_json::from_value(serde_json::json!({
+            "ticket_id": "6626871355780366331",
+            "helpdesk_id": "6626260912531570952",
+            "guest": {
+                "id": {"user_id": "u_1", "open_id": "ou_1", "union_id": "on_1"},
+                "name": "Tom"
+            },
+            "stage": 1,
+            "status": 1,
+            "score": 0,
+            "created_at": 1616920429000i64,
+            "chat_id": "oc_1",
+            "customized_fields": []
+        }))
+        .unwrap();
+        assert_eq!(event.ticket_id, "6626871355780366331");
+        assert_eq!(event.guest.unwrap().id.open_id.as_deref(), Some("ou_1"));
+        assert_eq!(event.chat_id.as_deref(), Some("oc_1"));
+        assert_eq!(
+            event.created_at,
+            Some(LarkTimestamp::from_millis(1616920429000))
+        );
+    }
+}
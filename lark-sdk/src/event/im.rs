@@ -0,0 +1,637 @@
+//! 消息与群组相关的事件。
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use super::{EventType, UserId};
+use crate::enums::ChatType;
+use crate::i18n::I18nText;
+use crate::time::{self, LarkTimestamp};
+
+/// 接收消息，`im.message.receive_v1`
+#[derive(Debug, Clone, Deserialize)]
+pub struct MessageReceiveEvent {
+    pub sender: EventSender,
+    pub message: EventMessage,
+}
+
+impl EventType for MessageReceiveEvent {
+    const EVENT_TYPE: &'static str = "im.message.receive_v1";
+}
+
+/// 消息的发送者
+#[derive(Debug, Clone, Deserialize)]
+pub struct EventSender {
+    pub sender_id: UserId,
+    /// 发送者类型，目前只有 `user`
+    pub sender_type: String,
+    #[serde(default)]
+    pub tenant_key: Option<String>,
+}
+
+/// 事件中的消息，`content` 已经按照消息类型解析。
+#[derive(Debug, Clone, Deserialize)]
+#[serde(try_from = "RawEventMessage")]
+pub struct EventMessage {
+    pub message_id: String,
+    pub root_id: Option<String>,
+    pub parent_id: Option<String>,
+    /// 消息发送的时间
+    pub create_time: LarkTimestamp,
+    pub update_time: Option<LarkTimestamp>,
+    pub chat_id: String,
+    pub thread_id: Option<String>,
+    pub chat_type: ChatType,
+    pub message_type: String,
+    pub content: MessageContent,
+    pub mentions: Vec<Mention>,
+}
+
+/// 推送的消息中 `content` 是 JSON 字符串
+#[derive(Deserialize)]
+struct RawEventMessage {
+    message_id: String,
+    #[serde(default)]
+    root_id: Option<String>,
+    #[serde(default)]
+    parent_id: Option<String>,
+    #[serde(with = "time::millis")]
+    create_time: LarkTimestamp,
+    #[serde(default, with = "time::millis::option")]
+    update_time: Option<LarkTimestamp>,
+    chat_id: String,
+    #[serde(default)]
+    thread_id: Option<String>,
+    chat_type: ChatType,
+    message_type: String,
+    content: String,
+    #[serde(default)]
+    mentions: Option<Vec<Mention>>,
+}
+
+impl TryFrom<RawEventMessage> for EventMessage {
+    type Error = serde_json::Error;
+
+    fn try_from(raw: RawEventMessage) -> Result<Self, Self::Error> {
+        Ok(EventMessage {
+            content: MessageContent::parse(&raw.message_type, &raw.content)?,
+            message_id: raw.message_id,
+            root_id: raw.root_id.filter(|id| !id.is_empty()),
+            parent_id: raw.parent_id.filter(|id| !id.is_empty()),
+            create_time: raw.create_time,
+            update_time: raw.update_time,
+            chat_id: raw.chat_id,
+            thread_id: raw.thread_id.filter(|id| !id.is_empty()),
+            chat_type: raw.chat_type,
+            message_type: raw.message_type,
+            mentions: raw.mentions.unwrap_or_default(),
+        })
+    }
+}
+
+/// 消息中 @ 的用户，`key` 为消息内容中的占位符，如 `@_user_1`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Mention {
+    pub key: String,
+    pub id: UserId,
+    pub name: String,
+    #[serde(default)]
+    pub tenant_key: Option<String>,
+}
+
+/// 按照消息类型解析后的消息内容。
+#[derive(Debug, Clone, PartialEq)]
+pub enum MessageContent {
+    Text(TextContent),
+    Post(PostContent),
+    Image(ImageContent),
+    File(FileContent),
+    Audio(AudioContent),
+    Media(MediaContent),
+    Sticker(StickerContent),
+    ShareChat(ShareChatContent),
+    ShareUser(ShareUserContent),
+    /// 卡片等没有单独建模的消息类型，保留原始内容
+    Other(Value),
+}
+
+impl MessageContent {
+    /// 按照 `message_type` 解析 JSON 字符串格式的消息内容
+    pub fn parse(message_type: &str, content: &str) -> serde_json::Result<Self> {
+        Ok(match message_type {
+            "text" => MessageContent::Text(serde_json::from_str(content)?),
+            "post" => MessageContent::Post(serde_json::from_str(content)?),
+            "image" => MessageContent::Image(serde_json::from_str(content)?),
+            "file" => MessageContent::File(serde_json::from_str(content)?),
+            "audio" => MessageContent::Audio(serde_json::from_str(content)?),
+            "media" => MessageContent::Media(serde_json::from_str(content)?),
+            "sticker" => MessageContent::Sticker(serde_json::from_str(content)?),
+            "share_chat" => MessageContent::ShareChat(serde_json::from_str(content)?),
+            "share_user" => MessageContent::ShareUser(serde_json::from_str(content)?),
+            _ => MessageContent::Other(serde_json::from_str(content)?),
+        })
+    }
+}
+
+/// 文本消息，@ 用户以 `@_user_1` 形式的占位符出现在文本中
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TextContent {
+    pub text: String,
+}
+
+impl TextContent {
+    /// 将文本中的占位符替换为 `@用户名`
+    pub fn resolve_mentions(&self, mentions: &[Mention]) -> String {
+        // 从长到短替换，避免 `@_user_1` 替换掉 `@_user_10` 的前缀
+        let mut sorted: Vec<&Mention> = mentions.iter().collect();
+        sorted.sort_by_key(|mention| std::cmp::Reverse(mention.key.len()));
+        sorted.iter().fold(self.text.clone(), |text, mention| {
+            text.replace(&mention.key, &format!("@{}", mention.name))
+        })
+    }
+}
+
+/// 富文本消息
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PostContent {
+    #[serde(default)]
+    pub title: String,
+    /// 段落列表，每个段落由多个元素组成
+    #[serde(default)]
+    pub content: Vec<Vec<PostElement>>,
+}
+
+/// 富文本消息中的元素
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "tag", rename_all = "snake_case")]
+pub enum PostElement {
+    Text {
+        text: String,
+        #[serde(default)]
+        style: Vec<String>,
+    },
+    A {
+        text: String,
+        href: String,
+        #[serde(default)]
+        style: Vec<String>,
+    },
+    At {
+        user_id: String,
+        #[serde(default)]
+        user_name: Option<String>,
+        #[serde(default)]
+        style: Vec<String>,
+    },
+    Img {
+        image_key: String,
+    },
+    Media {
+        file_key: String,
+        #[serde(default)]
+        image_key: Option<String>,
+    },
+    Emotion {
+        emoji_type: String,
+    },
+    CodeBlock {
+        #[serde(default)]
+        language: Option<String>,
+        text: String,
+    },
+    Hr,
+    /// 未支持的元素
+    #[serde(other)]
+    Unknown,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ImageContent {
+    pub image_key: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FileContent {
+    pub file_key: String,
+    #[serde(default)]
+    pub file_name: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AudioContent {
+    pub file_key: String,
+    /// 时长，毫秒
+    #[serde(default)]
+    pub duration: Option<u64>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MediaContent {
+    pub file_key: String,
+    #[serde(default)]
+    pub image_key: Option<String>,
+    #[serde(default)]
+    pub file_name: Option<String>,
+    /// 时长，毫秒
+    #[serde(default)]
+    pub duration: Option<u64>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StickerContent {
+    pub file_key: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ShareChatContent {
+    pub chat_id: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ShareUserContent {
+    pub user_id: String,
+}
+
+/// 群名称等在不同语言下的名称，即 [`I18nText`]
+pub type I18nNames = I18nText;
+
+/// 群配置修改，`im.chat.updated_v1`
+#[derive(Debug, Clone, Deserialize)]
+pub struct ChatUpdatedEvent {
+    pub chat_id: String,
+    pub operator_id: UserId,
+    /// 是否为外部群
+    #[serde(default)]
+    pub external: bool,
+    #[serde(default)]
+    pub operator_tenant_key: Option<String>,
+    /// 修改后的群配置，只包含修改了的字段
+    #[serde(default)]
+    pub after_change: ChatChange,
+    /// 修改前的群配置，只包含修改了的字段
+    #[serde(default)]
+    pub before_change: ChatChange,
+}
+
+impl EventType for ChatUpdatedEvent {
+    const EVENT_TYPE: &'static str = "im.chat.updated_v1";
+}
+
+/// 群配置，群配置修改事件中只包含修改了的字段
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChatChange {
+    #[serde(default)]
+    pub avatar: Option<String>,
+    #[serde(default)]
+    pub name: Option<String>,
+    #[serde(default)]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub i18n_names: Option<I18nNames>,
+    #[serde(default)]
+    pub add_member_permission: Option<String>,
+    #[serde(default)]
+    pub share_card_permission: Option<String>,
+    #[serde(default)]
+    pub at_all_permission: Option<String>,
+    #[serde(default)]
+    pub edit_permission: Option<String>,
+    #[serde(default)]
+    pub membership_approval: Option<String>,
+    #[serde(default)]
+    pub join_message_visibility: Option<String>,
+    #[serde(default)]
+    pub leave_message_visibility: Option<String>,
+    #[serde(default)]
+    pub moderation_permission: Option<String>,
+    #[serde(default)]
+    pub owner_id: Option<UserId>,
+}
+
+/// 群解散，`im.chat.disbanded_v1`
+#[derive(Debug, Clone, Deserialize)]
+pub struct ChatDisbandedEvent {
+    pub chat_id: String,
+    pub operator_id: UserId,
+    #[serde(default)]
+    pub external: bool,
+    #[serde(default)]
+    pub operator_tenant_key: Option<String>,
+    #[serde(default)]
+    pub name: Option<String>,
+    #[serde(default)]
+    pub i18n_names: Option<I18nNames>,
+}
+
+impl EventType for ChatDisbandedEvent {
+    const EVENT_TYPE: &'static str = "im.chat.disbanded_v1";
+}
+
+/// 用户进入与机器人的单聊，`im.chat.access_event.bot_p2p_chat_entered_v1`
+///
+/// 可以通过 `last_message_id` 是否为空判断用户是否第一次打开机器人。
+#[derive(Debug, Clone, Deserialize)]
+pub struct BotP2pChatEnteredEvent {
+    pub chat_id: String,
+    pub operator_id: UserId,
+    /// 单聊中最后一条消息的 ID，没有消息时为空
+    #[serde(default)]
+    pub last_message_id: Option<String>,
+    /// 最后一条消息的发送时间，没有消息时为 `None`
+    #[serde(default, with = "time::millis::option")]
+    pub last_message_create_time: Option<LarkTimestamp>,
+}
+
+impl EventType for BotP2pChatEnteredEvent {
+    const EVENT_TYPE: &'static str = "im.chat.access_event.bot_p2p_chat_entered_v1";
+}
+
+impl BotP2pChatEnteredEvent {
+    /// 单聊中还没有消息，即用户第一次打开机器人
+    pub fn is_first_visit(&self) -> bool {
+        self.last_message_id.as_deref().is_none_or(str::is_empty)
+    }
+}
+
+/// 群成员变更事件中的用户
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChatMemberUser {
+    #[serde(default)]
+    pub name: Option<String>,
+    #[serde(default)]
+    pub tenant_key: Option<String>,
+    pub user_id: UserId,
+}
+
+/// 用户进群、出群、被撤销进群事件的内容
+#[derive(Debug, Clone, Deserialize)]
+pub struct ChatMemberUserEvent {
+    pub chat_id: String,
+    pub operator_id: UserId,
+    #[serde(default)]
+    pub external: bool,
+    #[serde(default)]
+    pub operator_tenant_key: Option<String>,
+    #[serde(default)]
+    pub users: Vec<ChatMemberUser>,
+    #[serde(default)]
+    pub name: Option<String>,
+    #[serde(default)]
+    pub i18n_names: Option<I18nNames>,
+}
+
+/// 机器人进群、被移出群事件的内容
+#[derive(Debug, Clone, Deserialize)]
+pub struct ChatMemberBotEvent {
+    pub chat_id: String,
+    pub operator_id: UserId,
+    #[serde(default)]
+    pub external: bool,
+    #[serde(default)]
+    pub operator_tenant_key: Option<String>,
+    #[serde(default)]
+    pub name: Option<String>,
+    #[serde(default)]
+    pub i18n_names: Option<I18nNames>,
+}
+
+newtype_events! {
+    /// 用户进群，`im.chat.member.user.added_v1`
+    ChatMemberUserAddedEvent(ChatMemberUserEvent) = "im.chat.member.user.added_v1";
+    /// 用户出群，`im.chat.member.user.deleted_v1`
+    ChatMemberUserDeletedEvent(ChatMemberUserEvent) = "im.chat.member.user.deleted_v1";
+    /// 撤销拉用户进群，`im.chat.member.user.withdrawn_v1`
+    ChatMemberUserWithdrawnEvent(ChatMemberUserEvent) = "im.chat.member.user.withdrawn_v1";
+    /// 机器人进群，`im.chat.member.bot.added_v1`
+    ChatMemberBotAddedEvent(ChatMemberBotEvent) = "im.chat.member.bot.added_v1";
+    /// 机器人被移出群，`im.chat.member.bot.deleted_v1`
+    ChatMemberBotDeletedEvent(ChatMemberBotEvent) = "im.chat.member.bot.deleted_v1";
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::event::{envelope, Event};
+
+    fn receive(message_type: &str, content: &str, mentions: Value) -> Event<MessageReceiveEvent> {
+        let payload = serde_json::json!({
+            "schema": "2.0",
+            "header": {
+                "event_id": "5e3702a84e847582be8db7fb73283c02",
+                "event_type": "im.message.receive_v1",
+                "create_time": "1608725989000",
+                "token": "rvaYgkND1GOiu5MM0E1rncYC6PLtF7JV",
+                "app_id": "cli_9f5343c580712544",
+                "tenant_key": "2ca1d211f64f6438"
+            },
+            "event": {
+                "sender": {
+                    "sender_id": {"union_id": "on_1", "user_id": "e33ggbyz", "open_id": "ou_1"},
+                    "sender_type": "user",
+                    "tenant_key": "736588c9260f175e"
+                },
+                "message": {
+                    "message_id": "om_1",
+                    "root_id": "",
+                    "parent_id": "",
+                    "create_time": "1609073151345",
+                    "chat_id": "oc_1",
+                    "chat_type": "group",
+                    "message_type": message_type,
+                    "content": content,
+                    "mentions": mentions
+                }
+            }
+        });
+        serde_json::from_value(payload).unwrap()
+    }
+
+    #[test]
+    fn bot_p2p_chat_entered() {
+        let event: BotP2pChatEnteredEvent = serde_json::from_value(serde_json::json!({
+            "chat_id": "oc_1",
+            "operator_id": {"open_id": "ou_1"},
+            "last_message_id": "",
+            "last_message_create_time": ""
+        }))
+        .unwrap();
+        assert!(event.is_first_visit());
+        assert_eq!(event.last_message_create_time, None);
+        let event: BotP2pChatEnteredEvent = serde_json::from_value(serde_json::json!({
+            "chat_id": "oc_1",
+            "operator_id": {"open_id": "ou_1"},
+            "last_message_id": "om_1"
+        }))
+        .unwrap();
+        assert!(!event.is_first_visit());
+    }
+
+    #[test]
+    fn text_with_mentions() {
+        let event = receive(
+            "text",
+            r#"{"text":"@_user_1 @_user_10 hello"}"#,
+            serde_json::json!([
+                {"key": "@_user_1", "id": {"open_id": "ou_2"}, "name": "Tom", "tenant_key": "t"},
+                {"key": "@_user_10", "id": {"open_id": "ou_3"}, "name": "Jerry"}
+            ]),
+        );
+        assert_eq!(event.header.event_type, MessageReceiveEvent::EVENT_TYPE);
+        let message = &event.event.message;
+        assert_eq!(message.root_id, None);
+        assert_eq!(message.create_time.millis(), 1609073151345);
+        assert_eq!(message.mentions[1].id.open_id.as_deref(), Some("ou_3"));
+        match &message.content {
+            MessageContent::Text(text) => {
+                assert_eq!(
+                    text.resolve_mentions(&message.mentions),
+                    "@Tom @Jerry hello"
+                )
+            }
+            other => panic!("unexpected content {:?}", other),
+        }
+    }
+
+    #[cfg(feature = "async")]
+    #[tokio::test]
+    async fn chat_events() {
+        use std::sync::{Arc, Mutex};
+
+        use crate::event::{Dispatched, EventDispatcher};
+
+        let seen = Arc::new(Mutex::new(Vec::new()));
+        let (added, disbanded) = (seen.clone(), seen.clone());
+        let dispatcher = EventDispatcher::new()
+            .verification_token("token")
+            .on(move |event: Event<ChatMemberUserAddedEvent>| {
+                let seen = added.clone();
+                async move {
+                    let names = event
+                        .event
+                        .users
+                        .iter()
+                        .filter_map(|user| user.name.clone());
+                    seen.lock().unwrap().extend(names);
+                    Ok(())
+                }
+            })
+            .on(move |event: Event<ChatDisbandedEvent>| {
+                let seen = disbanded.clone();
+                async move {
+                    seen.lock().unwrap().push(event.event.chat_id);
+                    Ok(())
+                }
+            });
+
+        let body = envelope(
+            "im.chat.member.user.added_v1",
+            serde_json::json!({
+                "chat_id": "oc_1",
+                "operator_id": {"open_id": "ou_1"},
+                "external": false,
+                "users": [{"name": "Tom", "tenant_key": "t1", "user_id": {"open_id": "ou_2"}}]
+            }),
+        );
+        assert_eq!(
+            dispatcher.dispatch(&body).await.unwrap(),
+            Dispatched::Handled
+        );
+        let body = envelope(
+            "im.chat.disbanded_v1",
+            serde_json::json!({"chat_id": "oc_1", "operator_id": {"open_id": "ou_1"}}),
+        );
+        assert_eq!(
+            dispatcher.dispatch(&body).await.unwrap(),
+            Dispatched::Handled
+        );
+        let body = envelope(
+            "im.chat.updated_v1",
+            serde_json::json!({"chat_id": "oc_1", "operator_id": {"open_id": "ou_1"}}),
+        );
+        assert_eq!(
+            dispatcher.dispatch(&body).await.unwrap(),
+            Dispatched::Unhandled("im.chat.updated_v1".to_string())
+        );
+        assert_eq!(*seen.lock().unwrap(), vec!["Tom", "oc_1"]);
+
+        let challenge = br#"{"challenge":"c1","token":"token","type":"url_verification"}"#;
+        let dispatched = dispatcher.dispatch(challenge).await.unwrap();
+        assert_eq!(
+            dispatched.response_body(),
+            serde_json::json!({"challenge": "c1"})
+        );
+        let forged = br#"{"challenge":"c1","token":"other","type":"url_verification"}"#;
+        assert!(dispatcher.dispatch(forged).await.is_err());
+    }
+
+    #[test]
+    fn chat_updated() {
+        let event: Event<ChatUpdatedEvent> = serde_json::from_slice(&envelope(
+            "im.chat.updated_v1",
+            serde_json::json!({
+                "chat_id": "oc_1",
+                "operator_id": {"open_id": "ou_1"},
+                "after_change": {
+                    "name": "新群名",
+                    "owner_id": {"open_id": "ou_3"},
+                    "i18n_names": {"zh_cn": "新群名", "en_us": "New name", "ja_jp": ""}
+                },
+                "before_change": {"name": "旧群名"}
+            }),
+        ))
+        .unwrap();
+        assert_eq!(event.event.after_change.name.as_deref(), Some("新群名"));
+        assert_eq!(event.event.before_change.owner_id, None);
+        let names = event.event.after_change.i18n_names.unwrap();
+        assert_eq!(names.resolve(&["ja_jp", "en_us"]), Some("New name"));
+    }
+
+    #[test]
+    fn post_and_others() {
+        let event = receive(
+            "post",
+            r#"{"title":"日报","content":[[{"tag":"text","text":"完成","style":["bold"]},{"tag":"at","user_id":"ou_2","user_name":"Tom"}],[{"tag":"img","image_key":"img_1"},{"tag":"new_tag"}]]}"#,
+            Value::Null,
+        );
+        let message = event.event.message;
+        assert!(message.mentions.is_empty());
+        assert_eq!(
+            message.content,
+            MessageContent::Post(PostContent {
+                title: "日报".to_string(),
+                content: vec![
+                    vec![
+                        PostElement::Text {
+                            text: "完成".to_string(),
+                            style: vec!["bold".to_string()]
+                        },
+                        PostElement::At {
+                            user_id: "ou_2".to_string(),
+                            user_name: Some("Tom".to_string()),
+                            style: vec![]
+                        },
+                    ],
+                    vec![
+                        PostElement::Img {
+                            image_key: "img_1".to_string()
+                        },
+                        PostElement::Unknown
+                    ],
+                ],
+            })
+        );
+
+        let event = receive("image", r#"{"image_key":"img_2"}"#, Value::Null);
+        assert_eq!(
+            event.event.message.content,
+            MessageContent::Image(ImageContent {
+                image_key: "img_2".to_string()
+            })
+        );
+        let event = receive("interactive", r#"{"elements":[]}"#, Value::Null);
+        assert_eq!(
+            event.event.message.content,
+            MessageContent::Other(serde_json::json!({"elements": []}))
+        );
+    }
+}
@@ -0,0 +1,93 @@
+//! 公司圈相关的事件。
+
+use serde::{Deserialize, Serialize};
+
+use super::{EventType, UserId};
+use crate::time::{self, LarkTimestamp};
+
+/// 发布帖子，`moments.post.created_v1`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PostCreatedEvent {
+    /// 帖子 ID
+    pub id: String,
+    pub author_id: UserId,
+    #[serde(default)]
+    pub category_ids: Vec<String>,
+    #[serde(default)]
+    pub link: Option<String>,
+    /// 发布时间
+    #[serde(default, with = "time::rfc3339::option")]
+    pub create_time: Option<LarkTimestamp>,
+}
+
+impl EventType for PostCreatedEvent {
+    const EVENT_TYPE: &'static str = "moments.post.created_v1";
+}
+
+/// 删除帖子，`moments.post.deleted_v1`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PostDeletedEvent {
+    pub id: String,
+    pub author_id: UserId,
+}
+
+impl EventType for PostDeletedEvent {
+    const EVENT_TYPE: &'static str = "moments.post.deleted_v1";
+}
+
+/// 发布评论，`moments.comment.created_v1`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CommentCreatedEvent {
+    /// 评论 ID
+    pub id: String,
+    pub user_id: UserId,
+    pub post_id: String,
+    /// 回复的评论
+    #[serde(default)]
+    pub reply_comment_id: Option<String>,
+    #[serde(default)]
+    pub root_comment_id: Option<String>,
+    /// 发布时间
+    #[serde(default, with = "time::rfc3339::option")]
+    pub create_time: Option<LarkTimestamp>,
+}
+
+impl EventType for CommentCreatedEvent {
+    const EVENT_TYPE: &'static str = "moments.comment.created_v1";
+}
+
+/// 删除评论，`moments.comment.deleted_v1`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CommentDeletedEvent {
+    pub id: String,
+    pub user_id: UserId,
+    pub post_id: String,
+}
+
+impl EventType for CommentDeletedEvent {
+    const EVENT_TYPE: &'static str = "moments.comment.deleted_v1";
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn comment_created() {
+        let event: CommentCreatedEvent = serde_json::from_value(serde_json::json!({
+            "id": "7007340876374687764",
+            "user_id": {"union_id": "on_1", "user_id": "u_1", "open_id": "ou_1"},
+            "post_id": "6934510454161014804",
+            "reply_comment_id": "7007340876374687700",
+            "create_time": "2022-05-23T00:00:00+08:00"
+        }))
+        .unwrap();
+        assert_eq!(event.post_id, "6934510454161014804");
+        assert_eq!(event.user_id.open_id.as_deref(), Some("ou_1"));
+        assert_eq!(event.root_comment_id, None);
+        assert_eq!(
+            event.create_time.map(LarkTimestamp::to_rfc3339),
+            Some("2022-05-22T16:00:00Z".to_string())
+        );
+    }
+}
@@ -0,0 +1,172 @@
+//! 将事件转发到消息队列。
+//!
+//! 事件推送要求在几秒内响应，耗时的处理可以通过 [`EventDispatcher::sink`](super::EventDispatcher::sink)
+//! 转发到消息队列后由其他服务消费。转发在处理函数之前进行，消息队列确认收到之后 `dispatch` 才会返回成功，
+//! 转发失败时 `dispatch` 返回错误，开放平台会重新推送该事件，因此消息至少投递一次，消费者需要按照
+//! `header.event_id` 去重。
+
+use futures_util::future::BoxFuture;
+
+use super::RawEvent;
+use crate::Result;
+
+/// 事件的转发目标
+pub trait EventSink: Send + Sync + 'static {
+    /// 转发一个事件，返回时消息队列已经确认收到
+    fn publish<'a>(&'a self, event: &'a RawEvent) -> BoxFuture<'a, Result<()>>;
+}
+
+/// 转发到 NATS JetStream，主题为 `{prefix}.{event_type}`，使用 `event_id` 作为 `Nats-Msg-Id` 去重
+#[cfg(feature = "nats")]
+pub struct NatsSink {
+    jetstream: async_nats::jetstream::Context,
+    prefix: String,
+}
+
+#[cfg(feature = "nats")]
+impl NatsSink {
+    pub fn new(client: async_nats::Client, prefix: impl Into<String>) -> Self {
+        NatsSink {
+            jetstream: async_nats::jetstream::new(client),
+            prefix: prefix.into(),
+        }
+    }
+
+    fn subject(&self, event: &RawEvent) -> String {
+        format!("{}.{}", self.prefix, event.event_type())
+    }
+}
+
+#[cfg(feature = "nats")]
+impl EventSink for NatsSink {
+    fn publish<'a>(&'a self, event: &'a RawEvent) -> BoxFuture<'a, Result<()>> {
+        Box::pin(async move {
+            let payload = serde_json::to_vec(&event.payload)?;
+            let mut headers = async_nats::HeaderMap::new();
+            headers.insert("Nats-Msg-Id", event.header.event_id.as_str());
+            let ack = self
+                .jetstream
+                .publish_with_headers(self.subject(event), headers, payload.into())
+                .await
+                .map_err(sink_error)?;
+            ack.await.map_err(sink_error)?;
+            Ok(())
+        })
+    }
+}
+
+/// 转发到 Kafka，使用 `tenant_key` 作为消息的 key，保证同一租户的事件有序，
+/// 消息头中包含 `event_id` 和 `event_type`
+#[cfg(feature = "kafka")]
+pub struct KafkaSink {
+    producer: rdkafka::producer::FutureProducer,
+    topic: String,
+    queue_timeout: std::time::Duration,
+}
+
+#[cfg(feature = "kafka")]
+impl KafkaSink {
+    pub fn new(producer: rdkafka::producer::FutureProducer, topic: impl Into<String>) -> Self {
+        KafkaSink {
+            producer,
+            topic: topic.into(),
+            queue_timeout: std::time::Duration::from_secs(5),
+        }
+    }
+
+    /// 生产者队列已满时的最长等待时间，默认 5 秒
+    pub fn queue_timeout(mut self, timeout: std::time::Duration) -> Self {
+        self.queue_timeout = timeout;
+        self
+    }
+}
+
+#[cfg(feature = "kafka")]
+impl EventSink for KafkaSink {
+    fn publish<'a>(&'a self, event: &'a RawEvent) -> BoxFuture<'a, Result<()>> {
+        use rdkafka::message::{Header, OwnedHeaders};
+        use rdkafka::producer::FutureRecord;
+
+        Box::pin(async move {
+            let payload = serde_json::to_vec(&event.payload)?;
+            let headers = OwnedHeaders::new()
+                .insert(Header {
+                    key: "event_id",
+                    value: Some(event.header.event_id.as_str()),
+                })
+                .insert(Header {
+                    key: "event_type",
+                    value: Some(event.event_type()),
+                });
+            let record = FutureRecord::to(&self.topic)
+                .key(event.tenant_key())
+                .payload(&payload)
+                .headers(headers);
+            self.producer
+                .send(record, self.queue_timeout)
+                .await
+                .map_err(|(err, _)| sink_error(err))?;
+            Ok(())
+        })
+    }
+}
+
+#[cfg(any(feature = "nats", feature = "kafka"))]
+fn sink_error(err: impl std::fmt::Display) -> crate::LarkError {
+    crate::LarkError::Sink(err.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+
+    use super::*;
+    use crate::event::{envelope, Dispatched, EventDispatcher};
+    use crate::LarkError;
+
+    #[derive(Default)]
+    struct Memory {
+        events: Mutex<Vec<String>>,
+        fail: bool,
+    }
+
+    impl EventSink for Arc<Memory> {
+        fn publish<'a>(&'a self, event: &'a RawEvent) -> BoxFuture<'a, Result<()>> {
+            Box::pin(async move {
+                if self.fail {
+                    return Err(LarkError::Sink("broker unavailable".to_string()));
+                }
+                let entry = event.header.event_id.clone();
+                self.events.lock().unwrap().push(entry);
+                Ok(())
+            })
+        }
+    }
+
+    fn chat_disbanded() -> Vec<u8> {
+        envelope(
+            "im.chat.disbanded_v1",
+            serde_json::json!({"chat_id": "oc_1"}),
+        )
+    }
+
+    #[tokio::test]
+    async fn forward() {
+        let memory = Arc::new(Memory::default());
+        let dispatcher = EventDispatcher::new().sink(memory.clone());
+        let dispatched = dispatcher.dispatch(&chat_disbanded()).await.unwrap();
+        assert_eq!(
+            dispatched,
+            Dispatched::Unhandled("im.chat.disbanded_v1".to_string())
+        );
+        assert_eq!(*memory.events.lock().unwrap(), ["e1"]);
+
+        let broken = Arc::new(Memory {
+            fail: true,
+            ..Memory::default()
+        });
+        let dispatcher = EventDispatcher::new().sink(broken);
+        let result = dispatcher.dispatch(&chat_disbanded()).await;
+        assert!(matches!(result, Err(LarkError::Sink(_))));
+    }
+}
@@ -0,0 +1,381 @@
+//! 各个客户端共用的请求处理。
+//!
+//! 添加应用凭证、凭证失效时重新获取、频率限制、gzip 压缩、统计耗时和生成审计记录都在这里完成，
+//! 不涉及任何 IO：客户端按照 [`Exchange`] 返回的 [`Step`] 等待、发送请求并交回结果，异步客户端
+//! 通过 [`HttpTransport`](crate::transport::HttpTransport) 发送，同步客户端通过
+//! [`BlockingTransport`](crate::transport::BlockingTransport) 发送，见 [`Exchange::run_blocking`]。
+
+use std::io::Write;
+use std::sync::Arc;
+use std::time::Duration;
+#[cfg(any(feature = "blocking", feature = "minimal"))]
+use std::time::Instant;
+
+use flate2::write::GzEncoder;
+use flate2::Compression;
+
+use crate::audit::{AuditRecord, AuditSink};
+use crate::auth::{self, TokenKey, TokenManager};
+use crate::metrics::LatencyTracker;
+use crate::quota::Quota;
+#[cfg(any(feature = "blocking", feature = "minimal"))]
+use crate::transport::BlockingTransport;
+use crate::transport::{HttpRequest, HttpResponse};
+use crate::{json, LarkError, Method, Request, RequestParts, Result};
+
+/// 飞书开放平台的接口地址
+pub const FEISHU_DOMAIN: &str = "https://open.feishu.cn";
+/// Lark（海外版）开放平台的接口地址
+pub const LARK_DOMAIN: &str = "https://open.larksuite.com";
+
+/// 客户端发送请求时使用的配置
+#[derive(Debug, Clone)]
+pub(crate) struct Pipeline {
+    pub(crate) domain: String,
+    pub(crate) gzip_threshold: Option<usize>,
+    pub(crate) quota: Option<Arc<Quota>>,
+    pub(crate) latency: Option<Arc<LatencyTracker>>,
+    pub(crate) audit: Option<Arc<dyn AuditSink>>,
+    pub(crate) tokens: Option<Arc<TokenManager>>,
+}
+
+impl Pipeline {
+    pub(crate) fn new(domain: String) -> Self {
+        Pipeline {
+            domain,
+            gzip_threshold: None,
+            quota: None,
+            latency: None,
+            audit: None,
+            tokens: None,
+        }
+    }
+
+    /// 开始发送 `parts`，`tenant_key` 为商店应用代表的租户
+    pub(crate) fn exchange(&self, parts: RequestParts, tenant_key: Option<&str>) -> Exchange<'_> {
+        let record = self.audit.as_ref().and_then(|_| {
+            let app_id = self.tokens.as_ref().map(|tokens| tokens.app_id());
+            AuditRecord::begin(&parts, app_id, tenant_key)
+        });
+        Exchange {
+            pipeline: self,
+            parts,
+            tenant_key: tenant_key.map(String::from),
+            record,
+            auth: None,
+            pending: Pending::Request,
+            sending: None,
+        }
+    }
+}
+
+/// 客户端需要执行的下一步
+pub(crate) enum Step {
+    /// 等待 `wait` 后发送 `request`，再将结果和发送耗时交给 [`Exchange::receive`]
+    Send {
+        wait: Duration,
+        request: HttpRequest,
+    },
+    /// 处理完成
+    Done(Result<HttpResponse>),
+}
+
+/// 一次请求的处理过程，需要凭证时会先发送获取凭证的请求，凭证失效时重新获取后再发送一次
+pub(crate) struct Exchange<'a> {
+    pipeline: &'a Pipeline,
+    parts: RequestParts,
+    tenant_key: Option<String>,
+    record: Option<AuditRecord>,
+    auth: Option<Auth<'a>>,
+    pending: Pending,
+    /// 正在发送的请求的方法和接口，用于统计耗时
+    sending: Option<(Method, String)>,
+}
+
+/// 请求使用的应用凭证
+struct Auth<'a> {
+    tokens: &'a TokenManager,
+    key: TokenKey,
+    /// 依次需要获取的凭证，见 [`TokenManager::chain`]
+    chain: Vec<TokenKey>,
+    /// `chain` 中下一个需要的凭证
+    next: usize,
+    token: Option<String>,
+    retried: bool,
+}
+
+/// 正在等待响应的请求
+enum Pending {
+    /// 获取 `chain[next]` 的请求
+    Token,
+    Request,
+}
+
+impl Exchange<'_> {
+    pub(crate) fn start(&mut self) -> Step {
+        let auth = match auth::injected_auth(&self.parts) {
+            Ok(Some(auth)) => auth,
+            Ok(None) => return self.send_request(self.parts.clone()),
+            Err(err) => return self.finish(Err(err)),
+        };
+        let tokens = match &self.pipeline.tokens {
+            Some(tokens) => tokens,
+            None => return self.send_request(self.parts.clone()),
+        };
+        let key = match tokens.key(auth, self.tenant_key.as_deref()) {
+            Ok(key) => key,
+            Err(err) => return self.finish(Err(err)),
+        };
+        self.auth = Some(Auth {
+            tokens,
+            chain: tokens.chain(&key),
+            key,
+            next: 0,
+            token: None,
+            retried: false,
+        });
+        self.next_token()
+    }
+
+    /// 处理 [`Step::Send`] 的结果，`elapsed` 为发送请求的耗时
+    pub(crate) fn receive(&mut self, result: Result<HttpResponse>, elapsed: Duration) -> Step {
+        let response = match result {
+            Ok(response) => response,
+            Err(err) => return self.finish(Err(err)),
+        };
+        if let (Some(tracker), Some((method, endpoint))) =
+            (&self.pipeline.latency, self.sending.take())
+        {
+            tracker.record(method, &endpoint, elapsed, response.header("X-Tt-Logid"));
+        }
+        let auth = match (&self.pending, &mut self.auth) {
+            (Pending::Request, Some(auth))
+                if !auth.retried && auth::is_invalid_app_token(&response.body) =>
+            {
+                auth
+            }
+            (Pending::Request, _) => return self.finish(Ok(response)),
+            (Pending::Token, auth) => {
+                let auth = auth
+                    .as_mut()
+                    .expect("token requests are only sent with auth");
+                let updated = json::from_slice(&response.body)
+                    .and_then(|response| auth.tokens.update(&auth.chain[auth.next], response));
+                match updated {
+                    Ok(token) => {
+                        auth.token = Some(token);
+                        auth.next += 1;
+                        return self.next_token();
+                    }
+                    Err(err) => return self.finish(Err(err)),
+                }
+            }
+        };
+        auth.tokens.invalidate(&auth.key);
+        auth.retried = true;
+        auth.next = 0;
+        auth.token = None;
+        self.next_token()
+    }
+
+    /// 依次获取凭证，缓存中没有时发送获取凭证的请求，都准备好后发送请求
+    fn next_token(&mut self) -> Step {
+        let auth = self
+            .auth
+            .as_mut()
+            .expect("next_token is only called with auth");
+        while let Some(key) = auth.chain.get(auth.next) {
+            match auth.tokens.cached(key) {
+                Some(token) => {
+                    auth.token = Some(token);
+                    auth.next += 1;
+                }
+                None => {
+                    let parts = auth
+                        .tokens
+                        .request(key, auth.token.as_deref())
+                        .and_then(|request| request.to_parts());
+                    return match parts {
+                        Ok(parts) => {
+                            self.pending = Pending::Token;
+                            self.prepare(parts)
+                        }
+                        Err(err) => self.finish(Err(err)),
+                    };
+                }
+            }
+        }
+        match auth.token.clone() {
+            Some(token) => self.send_request(auth::with_bearer(self.parts.clone(), &token)),
+            None => self.finish(Err(LarkError::MissingData)),
+        }
+    }
+
+    fn send_request(&mut self, parts: RequestParts) -> Step {
+        self.pending = Pending::Request;
+        self.prepare(parts)
+    }
+
+    /// 频率限制、压缩请求体并生成完整的请求
+    fn prepare(&mut self, parts: RequestParts) -> Step {
+        let pipeline = self.pipeline;
+        #[cfg(feature = "curl")]
+        if log::log_enabled!(target: "lark_sdk::curl", log::Level::Debug) {
+            log::debug!(target: "lark_sdk::curl", "{}", parts.to_curl(&pipeline.domain));
+        }
+        let wait = match &pipeline.quota {
+            Some(quota) => quota.acquire(&parts.url),
+            None => Duration::ZERO,
+        };
+        let mut headers = Vec::with_capacity(parts.headers.len() + 2);
+        headers.push(("Content-Type".to_string(), parts.content_type));
+        headers.extend(parts.headers);
+        let body = match parts.body {
+            Some(body)
+                if pipeline
+                    .gzip_threshold
+                    .is_some_and(|threshold| body.len() >= threshold) =>
+            {
+                headers.push(("Content-Encoding".to_string(), "gzip".to_string()));
+                Some(gzip(&body))
+            }
+            body => body,
+        };
+        let endpoint = parts.endpoint.unwrap_or_else(|| parts.url.clone());
+        self.sending = Some((parts.method, endpoint));
+        let request = HttpRequest {
+            method: parts.method,
+            url: join_url(&pipeline.domain, &parts.url),
+            query: parts.query,
+            headers,
+            body,
+        };
+        Step::Send { wait, request }
+    }
+
+    /// 写操作完成后生成审计记录
+    fn finish(&mut self, result: Result<HttpResponse>) -> Step {
+        if let (Some(sink), Some(record)) = (&self.pipeline.audit, self.record.take()) {
+            sink.record(&record.finish(&result));
+        }
+        Step::Done(result)
+    }
+
+    /// 在当前线程中通过 `transport` 完成处理，同步客户端使用
+    #[cfg(any(feature = "blocking", feature = "minimal"))]
+    pub(crate) fn run_blocking(
+        mut self,
+        transport: &dyn BlockingTransport,
+    ) -> Result<HttpResponse> {
+        let mut step = self.start();
+        loop {
+            match step {
+                Step::Send { wait, request } => {
+                    if !wait.is_zero() {
+                        std::thread::sleep(wait);
+                    }
+                    let start = Instant::now();
+                    let result = transport.send(request);
+                    step = self.receive(result, start.elapsed());
+                }
+                Step::Done(result) => return result,
+            }
+        }
+    }
+}
+
+/// 使用 gzip 压缩请求体
+pub(crate) fn gzip(body: &[u8]) -> Vec<u8> {
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+    encoder
+        .write_all(body)
+        .expect("writing to a Vec can not fail");
+    encoder.finish().expect("writing to a Vec can not fail")
+}
+
+/// 拼接请求地址，`url` 为完整地址时不使用客户端的域名
+pub(crate) fn join_url(domain: &str, url: &str) -> String {
+    if url.starts_with("https://") || url.starts_with("http://") {
+        return url.to_string();
+    }
+    format!("{}{}", domain.trim_end_matches('/'), url)
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::Value;
+
+    use super::*;
+
+    #[derive(lark_sdk::Request)]
+    #[request(method = "POST", url = "/open-apis/demo", auth = "tenant", response = Value)]
+    struct Demo {
+        #[request(body)]
+        name: String,
+    }
+
+    fn ok(body: &str) -> Result<HttpResponse> {
+        Ok(HttpResponse {
+            status: 200,
+            headers: Vec::new(),
+            body: body.as_bytes().to_vec(),
+        })
+    }
+
+    fn sent(step: Step) -> HttpRequest {
+        match step {
+            Step::Send { request, .. } => request,
+            Step::Done(result) => panic!("unexpected result {:?}", result),
+        }
+    }
+
+    #[test]
+    fn refetch_invalid_token() {
+        let mut pipeline = Pipeline::new("https://open.feishu.cn/".to_string());
+        pipeline.tokens = Some(Arc::new(TokenManager::new(
+            "cli_1".to_string(),
+            "secret".to_string(),
+        )));
+        let parts = Demo {
+            name: "lark".to_string(),
+        }
+        .to_parts()
+        .unwrap();
+        let token = r#"{"code":0,"msg":"ok","tenant_access_token":"t-1","expire":7200}"#;
+        let mut exchange = pipeline.exchange(parts, None);
+
+        let request = sent(exchange.start());
+        assert_eq!(
+            request.url,
+            "https://open.feishu.cn/open-apis/auth/v3/tenant_access_token/internal"
+        );
+        let request = sent(exchange.receive(ok(token), Duration::ZERO));
+        assert_eq!(request.url, "https://open.feishu.cn/open-apis/demo");
+        assert!(request
+            .headers
+            .contains(&("Authorization".to_string(), "Bearer t-1".to_string())));
+
+        // 凭证失效时重新获取后再发送一次
+        let invalid = r#"{"code":99991663,"msg":"invalid token"}"#;
+        let request = sent(exchange.receive(ok(invalid), Duration::ZERO));
+        assert!(request.url.ends_with("/tenant_access_token/internal"));
+        let token = token.replace("t-1", "t-2");
+        let request = sent(exchange.receive(ok(&token), Duration::ZERO));
+        assert!(request
+            .headers
+            .contains(&("Authorization".to_string(), "Bearer t-2".to_string())));
+        match exchange.receive(ok(invalid), Duration::ZERO) {
+            Step::Done(Ok(response)) => assert_eq!(response.body, invalid.as_bytes()),
+            _ => panic!("the request should only be retried once"),
+        }
+
+        // 缓存中的凭证不需要再获取
+        let parts = Demo {
+            name: "lark".to_string(),
+        }
+        .to_parts()
+        .unwrap();
+        let request = sent(pipeline.exchange(parts, None).start());
+        assert_eq!(request.url, "https://open.feishu.cn/open-apis/demo");
+    }
+}
@@ -0,0 +1,159 @@
+//! 服务台。
+//!
+//! 服务台的接口除了 `tenant_access_token`，还需要在 `X-Lark-Helpdesk-Authorization` 头部传递服务台的
+//! ID 和令牌，[`HelpdeskAuth`] 生成该头部，[`Client::helpdesk`] 返回的 [`Helpdesk`] 会自动添加：
+//!
+//! ```no_run
+//! use lark_sdk::event::helpdesk::TicketCreatedEvent;
+//! use lark_sdk::helpdesk::HelpdeskAuth;
+//!
+//! # async fn run(client: lark_sdk::Client, event: TicketCreatedEvent) -> lark_sdk::Result<()> {
+//! let auth = HelpdeskAuth::new("6626260912531570952", "ht-c82db92b-xxxx");
+//! client
+//!     .helpdesk(&auth)
+//!     .reply_to_ticket(&event.ticket_id, "已转交给值班同学处理")
+//!     .await?;
+//! # Ok(())
+//! # }
+//! ```
+
+use base64::Engine;
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+#[cfg(feature = "async")]
+use crate::{Client, Result};
+
+/// 服务台的身份，在服务台的「设置 - 开发者」中获取
+#[derive(Clone, PartialEq, Eq)]
+pub struct HelpdeskAuth {
+    pub helpdesk_id: String,
+    pub helpdesk_token: String,
+}
+
+impl HelpdeskAuth {
+    pub fn new(helpdesk_id: impl Into<String>, helpdesk_token: impl Into<String>) -> Self {
+        HelpdeskAuth {
+            helpdesk_id: helpdesk_id.into(),
+            helpdesk_token: helpdesk_token.into(),
+        }
+    }
+
+    /// `X-Lark-Helpdesk-Authorization` 头部的值，为 `helpdesk_id:helpdesk_token` 的 base64 编码
+    pub fn header_value(&self) -> String {
+        base64::engine::general_purpose::STANDARD
+            .encode(format!("{}:{}", self.helpdesk_id, self.helpdesk_token))
+    }
+}
+
+impl std::fmt::Debug for HelpdeskAuth {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("HelpdeskAuth")
+            .field("helpdesk_id", &self.helpdesk_id)
+            .finish_non_exhaustive()
+    }
+}
+
+/// 发送的工单消息
+#[derive(Debug, Clone, Deserialize, lark_sdk::Response)]
+pub struct TicketMessageId {
+    pub message_id: String,
+}
+
+/// 以服务台的身份在工单中发送消息
+#[derive(Debug, Clone, lark_sdk::Request)]
+#[request(
+    method = "POST",
+    url = "/open-apis/helpdesk/v1/tickets/:ticket_id/messages",
+    auth = "tenant",
+    validate,
+    response = TicketMessageId
+)]
+pub struct SendTicketMessage {
+    #[request(path)]
+    pub ticket_id: String,
+    /// [`HelpdeskAuth::header_value`]
+    #[request(header = "X-Lark-Helpdesk-Authorization")]
+    pub helpdesk_auth: String,
+    /// 消息类型，目前只支持 `post`
+    #[request(body)]
+    pub msg_type: String,
+    #[request(body)]
+    pub content: Value,
+}
+
+impl SendTicketMessage {
+    /// 发送纯文本，每行为富文本的一个段落
+    pub fn text(auth: &HelpdeskAuth, ticket_id: impl Into<String>, text: &str) -> Self {
+        let paragraphs: Vec<Value> = text
+            .lines()
+            .map(|line| json!([{"tag": "text", "text": line}]))
+            .collect();
+        SendTicketMessage {
+            ticket_id: ticket_id.into(),
+            helpdesk_auth: auth.header_value(),
+            msg_type: "post".to_string(),
+            content: json!({ "post": { "zh_cn": { "content": paragraphs } } }),
+        }
+    }
+}
+
+/// 服务台接口的快捷方法，见 [`Client::helpdesk`]
+#[cfg(feature = "async")]
+#[derive(Debug, Clone, Copy)]
+pub struct Helpdesk<'a> {
+    client: &'a Client,
+    auth: &'a HelpdeskAuth,
+}
+
+#[cfg(feature = "async")]
+impl Client {
+    /// 以服务台 `auth` 的身份调用服务台接口的快捷方法
+    pub fn helpdesk<'a>(&'a self, auth: &'a HelpdeskAuth) -> Helpdesk<'a> {
+        Helpdesk { client: self, auth }
+    }
+}
+
+#[cfg(feature = "async")]
+impl<'a> Helpdesk<'a> {
+    /// 在工单中回复纯文本，返回消息的 ID
+    pub async fn reply_to_ticket(&self, ticket_id: &str, text: &str) -> Result<String> {
+        let request = SendTicketMessage::text(self.auth, ticket_id, text);
+        Ok(self.client.send(&request).await?.into_data()?.message_id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::serve;
+
+    #[tokio::test]
+    async fn reply_to_ticket() {
+        let (address, handle) = serve(r#"{"code":0,"msg":"success","data":{"message_id":"m1"}}"#);
+        let client = Client::builder().domain(address).build().unwrap();
+        let auth = HelpdeskAuth::new("h1", "t1");
+        let message_id = client
+            .helpdesk(&auth)
+            .reply_to_ticket("6626871355780366331", "收到\n处理中")
+            .await
+            .unwrap();
+        assert_eq!(message_id, "m1");
+
+        let request = handle.join().unwrap();
+        assert!(request
+            .starts_with("POST /open-apis/helpdesk/v1/tickets/6626871355780366331/messages "));
+        assert!(request.contains("x-lark-helpdesk-authorization: aDE6dDE=\r\n"));
+        let body: Value = serde_json::from_str(request.split("\r\n\r\n").nth(1).unwrap()).unwrap();
+        assert_eq!(
+            body,
+            json!({
+                "msg_type": "post",
+                "content": {"post": {"zh_cn": {"content": [
+                    [{"tag": "text", "text": "收到"}],
+                    [{"tag": "text", "text": "处理中"}]
+                ]}}}
+            })
+        );
+    }
+}
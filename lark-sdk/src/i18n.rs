@@ -0,0 +1,158 @@
+//! 多语言文本。
+//!
+//! 群名称、菜单名称、卡片提示等字段使用 `{"zh_cn": "...", "en_us": "...", "ja_jp": "..."}` 形式的
+//! 多语言内容，对应 [`I18nText`]。飞书人事接口中 `[{"lang": "zh-CN", "value": "..."}]` 形式的列表
+//! 由各服务模块单独定义。
+//!
+//! ```
+//! use lark_sdk::i18n::{I18nText, EN_US, JA_JP};
+//!
+//! let text = I18nText::zh_cn("研发部").with(EN_US, "R&D");
+//! assert_eq!(text.resolve(&[JA_JP]), Some("研发部"));
+//! assert_eq!(text.resolve(&["en-US"]), Some("R&D"));
+//! ```
+
+use std::collections::BTreeMap;
+use std::fmt;
+
+use serde::{Deserialize, Deserializer, Serialize};
+
+/// 简体中文
+pub const ZH_CN: &str = "zh_cn";
+/// 英文
+pub const EN_US: &str = "en_us";
+/// 日文
+pub const JA_JP: &str = "ja_jp";
+
+/// 指定的语言都没有内容时依次尝试的语言
+const FALLBACK: &[&str] = &[ZH_CN, EN_US, JA_JP];
+
+/// 以语言为键的多语言文本，语言统一为 `zh_cn` 形式的小写下划线格式，忽略为空的内容
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash, Serialize)]
+#[serde(transparent)]
+pub struct I18nText(BTreeMap<String, String>);
+
+impl I18nText {
+    pub fn new() -> Self {
+        I18nText::default()
+    }
+
+    /// 只有简体中文的文本
+    pub fn zh_cn(text: impl Into<String>) -> Self {
+        I18nText::new().with(ZH_CN, text)
+    }
+
+    /// 只有英文的文本
+    pub fn en_us(text: impl Into<String>) -> Self {
+        I18nText::new().with(EN_US, text)
+    }
+
+    /// 添加 `lang` 语言的内容，`lang` 可以是 `zh_cn` 或 `zh-CN`
+    pub fn with(mut self, lang: &str, text: impl Into<String>) -> Self {
+        self.insert(lang, text);
+        self
+    }
+
+    /// 设置 `lang` 语言的内容，内容为空时删除该语言
+    pub fn insert(&mut self, lang: &str, text: impl Into<String>) {
+        let text = text.into();
+        let lang = normalize(lang);
+        if text.is_empty() {
+            self.0.remove(&lang);
+        } else {
+            self.0.insert(lang, text);
+        }
+    }
+
+    /// `lang` 语言的内容
+    pub fn get(&self, lang: &str) -> Option<&str> {
+        self.0.get(&normalize(lang)).map(String::as_str)
+    }
+
+    /// 依次查找 `preferred` 中的语言，都没有时依次使用简体中文、英文、日文和其他任意语言
+    pub fn resolve(&self, preferred: &[&str]) -> Option<&str> {
+        preferred
+            .iter()
+            .chain(FALLBACK)
+            .find_map(|lang| self.get(lang))
+            .or_else(|| self.0.values().next().map(String::as_str))
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// 所有语言和对应的内容
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.0
+            .iter()
+            .map(|(lang, text)| (lang.as_str(), text.as_str()))
+    }
+}
+
+/// 使用 [`resolve`](I18nText::resolve) 的默认顺序
+impl fmt::Display for I18nText {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.resolve(&[]).unwrap_or_default())
+    }
+}
+
+impl<'de> Deserialize<'de> for I18nText {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let map = Option::<BTreeMap<String, Option<String>>>::deserialize(deserializer)?;
+        let mut text = I18nText::new();
+        for (lang, value) in map.into_iter().flatten() {
+            text.insert(&lang, value.unwrap_or_default());
+        }
+        Ok(text)
+    }
+}
+
+impl<L: AsRef<str>, T: Into<String>> FromIterator<(L, T)> for I18nText {
+    fn from_iter<I: IntoIterator<Item = (L, T)>>(iter: I) -> Self {
+        let mut text = I18nText::new();
+        for (lang, value) in iter {
+            text.insert(lang.as_ref(), value);
+        }
+        text
+    }
+}
+
+/// `zh-CN`、`ZH_CN` 都转换为 `zh_cn`
+fn normalize(lang: &str) -> String {
+    lang.replace('-', "_").to_ascii_lowercase()
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn serde_and_fallback() {
+        let text: I18nText = serde_json::from_value(
+            json!({"zh_cn": "研发部", "en_us": "", "ja_jp": null, "zh_hk": "研發部"}),
+        )
+        .unwrap();
+        assert_eq!(
+            text,
+            [("zh_cn", "研发部"), ("zh-HK", "研發部")]
+                .into_iter()
+                .collect()
+        );
+        assert_eq!(
+            serde_json::to_value(&text).unwrap(),
+            json!({"zh_cn": "研发部", "zh_hk": "研發部"})
+        );
+        assert_eq!(text.get("ZH-HK"), Some("研發部"));
+        assert_eq!(text.resolve(&[EN_US]), Some("研发部"));
+        assert_eq!(text.to_string(), "研发部");
+
+        let text = I18nText::new().with("zh_hk", "研發部");
+        assert_eq!(text.resolve(&[EN_US]), Some("研發部"));
+        let text: I18nText = serde_json::from_value(json!(null)).unwrap();
+        assert!(text.is_empty());
+        assert_eq!(text.resolve(&[ZH_CN]), None);
+    }
+}
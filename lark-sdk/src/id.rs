@@ -0,0 +1,71 @@
+//! 用户、群组等对象的 ID。
+//!
+//! 同一个用户在不同维度下有不同的 ID，接口需要通过 `user_id_type`、`receive_id_type` 等参数说明传入的是哪一种，
+//! 使用这里的新类型可以由类型决定参数的值，避免 ID 与参数不匹配。参数本身的类型见 [`enums`](crate::enums)。
+
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+/// 一种 ID，`ID_TYPE` 为接口中表示该类型的参数值，如 `open_id`
+pub trait IdType {
+    const ID_TYPE: &'static str;
+
+    fn as_str(&self) -> &str;
+}
+
+macro_rules! id_types {
+    ($($(#[$doc:meta])* $name:ident = $id_type:literal;)*) => {
+        $(
+            $(#[$doc])*
+            #[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+            #[serde(transparent)]
+            pub struct $name(pub String);
+
+            impl $name {
+                pub fn new(id: impl Into<String>) -> Self {
+                    $name(id.into())
+                }
+            }
+
+            impl IdType for $name {
+                const ID_TYPE: &'static str = $id_type;
+
+                fn as_str(&self) -> &str {
+                    &self.0
+                }
+            }
+
+            impl From<String> for $name {
+                fn from(id: String) -> Self {
+                    $name(id)
+                }
+            }
+
+            impl From<&str> for $name {
+                fn from(id: &str) -> Self {
+                    $name(id.to_string())
+                }
+            }
+
+            impl fmt::Display for $name {
+                fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                    f.write_str(&self.0)
+                }
+            }
+        )*
+    };
+}
+
+id_types! {
+    /// 用户在应用内的 ID，以 `ou_` 开头
+    OpenId = "open_id";
+    /// 用户在同一开发者的所有应用内的 ID，以 `on_` 开头
+    UnionId = "union_id";
+    /// 用户在租户内的 ID
+    UserId = "user_id";
+    /// 用户的邮箱
+    Email = "email";
+    /// 群组的 ID，以 `oc_` 开头
+    ChatId = "chat_id";
+}
@@ -0,0 +1,610 @@
+//! 消息和群组。
+//!
+//! 群公告、群标签页和群菜单见 [`announcement`]、[`tab`]、[`menu`]。[`Client::im`] 提供发送消息的
+//! 快捷方法，`receive_id_type` 由接收者 ID 的类型（见 [`id`](crate::id)）决定。
+//!
+//! ```no_run
+//! use lark_sdk::id::ChatId;
+//!
+//! # async fn run(client: lark_sdk::Client) -> lark_sdk::Result<()> {
+//! client.im().send_text_to_open_id("ou_xxx", "你好").await?;
+//! client.im().send_text(&ChatId::new("oc_xxx"), "大家好").await?;
+//! # Ok(())
+//! # }
+//! ```
+
+pub mod announcement;
+pub mod menu;
+pub mod tab;
+
+#[cfg(feature = "async")]
+use futures_util::Stream;
+use serde::Deserialize;
+use serde_json::Value;
+
+use crate::card::CardTemplate;
+#[cfg(feature = "async")]
+use crate::docx::Block;
+use crate::enums::{ContainerIdType, ReceiveIdType, UserIdType};
+use crate::id::{ChatId, IdType, OpenId};
+use crate::time::{self, LarkTimestamp};
+#[cfg(feature = "async")]
+use crate::{Client, Result};
+use crate::{ListData, RequestSerialize};
+#[cfg(feature = "async")]
+use announcement::{
+    CreateChatAnnouncementBlocks, DeleteChatAnnouncementBlocks, ListChatAnnouncementBlocks,
+    LATEST_REVISION,
+};
+
+/// 发送消息，`receive_id_type` 需要与 `receive_id` 一致，使用 [`SendMessage::new`] 可以由类型决定
+#[derive(Debug, Clone, lark_sdk::Request)]
+#[request(
+    method = "POST",
+    url = "/open-apis/im/v1/messages",
+    auth = "tenant",
+    response = Message
+)]
+pub struct SendMessage {
+    #[request(query)]
+    pub receive_id_type: ReceiveIdType,
+    #[request(body)]
+    pub receive_id: String,
+    /// 消息类型，如 `text`、`interactive`
+    #[request(body)]
+    pub msg_type: String,
+    /// 消息内容，JSON 字符串
+    #[request(body)]
+    pub content: String,
+    #[request(body, idempotency_key)]
+    pub uuid: Option<String>,
+}
+
+impl SendMessage {
+    /// 发送给 `receive_id`，`content` 为消息内容
+    pub fn new<I: IdType>(receive_id: &I, msg_type: impl Into<String>, content: &Value) -> Self {
+        SendMessage {
+            receive_id_type: ReceiveIdType::of::<I>(),
+            receive_id: receive_id.as_str().to_string(),
+            msg_type: msg_type.into(),
+            content: content.to_string(),
+            uuid: None,
+        }
+    }
+
+    /// 文本消息
+    pub fn text<I: IdType>(receive_id: &I, text: &str) -> Self {
+        SendMessage::new(receive_id, "text", &serde_json::json!({ "text": text }))
+    }
+
+    /// 卡片消息，`card` 为卡片的 JSON
+    pub fn card<I: IdType>(receive_id: &I, card: &Value) -> Self {
+        SendMessage::new(receive_id, "interactive", card)
+    }
+
+    /// 使用卡片模板的卡片消息
+    pub fn card_template<I: IdType>(receive_id: &I, template: &CardTemplate) -> Self {
+        SendMessage::card(receive_id, &template.to_card())
+    }
+}
+
+/// 一条消息
+#[derive(Debug, Clone, Deserialize, lark_sdk::Response)]
+pub struct Message {
+    pub message_id: String,
+    #[serde(default)]
+    pub root_id: Option<String>,
+    #[serde(default)]
+    pub parent_id: Option<String>,
+    pub msg_type: String,
+    #[serde(default, with = "time::millis::option")]
+    pub create_time: Option<LarkTimestamp>,
+    #[serde(default, with = "time::millis::option")]
+    pub update_time: Option<LarkTimestamp>,
+    #[serde(default)]
+    pub deleted: bool,
+    #[serde(default)]
+    pub updated: bool,
+    #[serde(default)]
+    pub chat_id: Option<String>,
+    #[serde(default)]
+    pub sender: Option<MessageSender>,
+    #[serde(default)]
+    pub body: Option<MessageBody>,
+}
+
+/// 消息的发送者
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct MessageSender {
+    pub id: String,
+    /// `id` 的类型，如 `open_id`、`app_id`
+    pub id_type: String,
+    /// `user` 或 `app`
+    pub sender_type: String,
+    #[serde(default)]
+    pub tenant_key: Option<String>,
+}
+
+/// 消息内容
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct MessageBody {
+    /// 消息内容，JSON 字符串
+    pub content: String,
+}
+
+/// 查询消息的已读用户，只能查询机器人自己发送的、7 天内的消息
+#[derive(Debug, Clone, lark_sdk::Request)]
+#[request(
+    method = "GET",
+    url = "/open-apis/im/v1/messages/:message_id/read_users",
+    auth = "tenant",
+    validate,
+    response = ListData<ReadUser>
+)]
+pub struct ListReadUsers {
+    #[request(path)]
+    pub message_id: String,
+    /// 返回的用户 ID 类型，如 `open_id`
+    #[request(query)]
+    pub user_id_type: UserIdType,
+    #[request(query)]
+    pub page_size: Option<u32>,
+    #[request(query, page_token)]
+    pub page_token: Option<String>,
+}
+
+/// 已读消息的用户
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct ReadUser {
+    pub user_id_type: UserIdType,
+    pub user_id: String,
+    /// 阅读的时间
+    #[serde(with = "time::millis")]
+    pub timestamp: LarkTimestamp,
+    #[serde(default)]
+    pub tenant_key: Option<String>,
+}
+
+/// 在群组中发送仅指定用户可见的卡片，用于斜杠命令等只回复给操作者的场景，机器人需要在群组中
+#[derive(Debug, Clone, lark_sdk::Request)]
+#[request(
+    method = "POST",
+    url = "/open-apis/ephemeral/v1/send",
+    auth = "tenant",
+    response = EphemeralMessage
+)]
+pub struct SendEphemeralCard {
+    #[request(body)]
+    pub chat_id: String,
+    /// 可见的用户，`open_id`、`user_id`、`email` 三选一
+    #[request(body)]
+    pub open_id: Option<String>,
+    #[request(body)]
+    pub user_id: Option<String>,
+    #[request(body)]
+    pub email: Option<String>,
+    /// 固定为 `interactive`
+    #[request(body)]
+    pub msg_type: String,
+    /// 卡片的 JSON
+    #[request(body)]
+    pub card: Value,
+}
+
+impl SendEphemeralCard {
+    /// 在 `chat_id` 中发送只有 `open_id` 可见的卡片
+    pub fn new(chat_id: &ChatId, open_id: &OpenId, card: Value) -> Self {
+        SendEphemeralCard {
+            chat_id: chat_id.as_str().to_string(),
+            open_id: Some(open_id.as_str().to_string()),
+            user_id: None,
+            email: None,
+            msg_type: "interactive".to_string(),
+            card,
+        }
+    }
+}
+
+/// 发送成功的仅特定人可见的卡片
+#[derive(Debug, Clone, Deserialize, lark_sdk::Response)]
+pub struct EphemeralMessage {
+    pub message_id: String,
+}
+
+/// 删除仅特定人可见的卡片
+#[derive(Debug, Clone, lark_sdk::Request)]
+#[request(
+    method = "POST",
+    url = "/open-apis/ephemeral/v1/delete",
+    auth = "tenant",
+    empty
+)]
+pub struct DeleteEphemeralCard {
+    #[request(body)]
+    pub message_id: String,
+}
+
+/// 消息排序方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SortType {
+    /// 按创建时间从早到晚
+    ByCreateTimeAsc,
+    /// 按创建时间从晚到早
+    ByCreateTimeDesc,
+}
+
+impl RequestSerialize for SortType {
+    fn to_param(&self) -> Option<String> {
+        let value = match self {
+            SortType::ByCreateTimeAsc => "ByCreateTimeAsc",
+            SortType::ByCreateTimeDesc => "ByCreateTimeDesc",
+        };
+        Some(value.to_string())
+    }
+}
+
+/// 获取会话中的历史消息
+#[derive(Debug, Clone, lark_sdk::Request)]
+#[request(
+    method = "GET",
+    url = "/open-apis/im/v1/messages",
+    auth = "tenant",
+    response = ListData<Message>
+)]
+pub struct ListMessages {
+    #[request(query)]
+    pub container_id_type: ContainerIdType,
+    #[request(query)]
+    pub container_id: String,
+    /// 起始时间，包含该时间，精确到秒
+    #[request(query, serialize_with = "crate::param::unix_seconds")]
+    pub start_time: Option<LarkTimestamp>,
+    /// 结束时间，包含该时间，精确到秒
+    #[request(query, serialize_with = "crate::param::unix_seconds")]
+    pub end_time: Option<LarkTimestamp>,
+    #[request(query)]
+    pub sort_type: Option<SortType>,
+    #[request(query)]
+    pub page_size: Option<u32>,
+    #[request(query, page_token)]
+    pub page_token: Option<String>,
+}
+
+impl ListMessages {
+    /// 群组中 `[start_time, end_time]` 内的消息
+    pub fn chat(
+        chat_id: &ChatId,
+        start_time: Option<LarkTimestamp>,
+        end_time: Option<LarkTimestamp>,
+    ) -> Self {
+        ListMessages {
+            container_id_type: ContainerIdType::Chat,
+            container_id: chat_id.as_str().to_string(),
+            start_time,
+            end_time,
+            sort_type: None,
+            page_size: None,
+            page_token: None,
+        }
+    }
+}
+
+/// 消息接口的快捷方法，见 [`Client::im`]
+#[cfg(feature = "async")]
+#[derive(Debug, Clone, Copy)]
+pub struct Im<'a> {
+    client: &'a Client,
+}
+
+#[cfg(feature = "async")]
+impl Client {
+    /// 消息相关的快捷方法
+    pub fn im(&self) -> Im<'_> {
+        Im { client: self }
+    }
+}
+
+#[cfg(feature = "async")]
+impl<'a> Im<'a> {
+    /// 发送消息，接口返回失败时返回 [`LarkError::Api`](crate::LarkError::Api)
+    pub async fn send(&self, request: &SendMessage) -> Result<Message> {
+        self.client.send(request).await?.into_data()
+    }
+
+    /// 发送文本消息
+    pub async fn send_text<I: IdType>(&self, receive_id: &I, text: &str) -> Result<Message> {
+        self.send(&SendMessage::text(receive_id, text)).await
+    }
+
+    /// 发送卡片消息
+    pub async fn send_card<I: IdType>(&self, receive_id: &I, card: &Value) -> Result<Message> {
+        self.send(&SendMessage::card(receive_id, card)).await
+    }
+
+    /// 发送使用卡片模板的卡片消息
+    pub async fn send_card_template<I: IdType>(
+        &self,
+        receive_id: &I,
+        template: &CardTemplate,
+    ) -> Result<Message> {
+        self.send(&SendMessage::card_template(receive_id, template))
+            .await
+    }
+
+    /// 发送文本消息给 `open_id` 对应的用户
+    pub async fn send_text_to_open_id(&self, open_id: &str, text: &str) -> Result<Message> {
+        self.send_text(&OpenId::from(open_id), text).await
+    }
+
+    /// 发送文本消息到群组
+    pub async fn send_text_to_chat(&self, chat_id: &str, text: &str) -> Result<Message> {
+        self.send_text(&ChatId::from(chat_id), text).await
+    }
+
+    /// 发送卡片消息给 `open_id` 对应的用户
+    pub async fn send_card_to_open_id(&self, open_id: &str, card: &Value) -> Result<Message> {
+        self.send_card(&OpenId::from(open_id), card).await
+    }
+
+    /// 发送卡片消息到群组
+    pub async fn send_card_to_chat(&self, chat_id: &str, card: &Value) -> Result<Message> {
+        self.send_card(&ChatId::from(chat_id), card).await
+    }
+
+    /// 在群组中发送只有 `open_id` 可见的卡片，返回卡片的 `message_id`
+    pub async fn send_ephemeral_card(
+        &self,
+        chat_id: &ChatId,
+        open_id: &OpenId,
+        card: Value,
+    ) -> Result<String> {
+        let request = SendEphemeralCard::new(chat_id, open_id, card);
+        let message = self.client.send(&request).await?.into_data()?;
+        Ok(message.message_id)
+    }
+
+    /// 删除仅特定人可见的卡片
+    pub async fn delete_ephemeral_card(&self, message_id: &str) -> Result<()> {
+        let request = DeleteEphemeralCard {
+            message_id: message_id.to_string(),
+        };
+        self.client.send(&request).await?.into_data()?;
+        Ok(())
+    }
+
+    /// 群公告的所有块，第一个块为根块
+    pub async fn announcement_blocks(&self, chat_id: &ChatId) -> Result<Vec<Block>> {
+        let request = ListChatAnnouncementBlocks {
+            chat_id: chat_id.as_str().to_string(),
+            revision_id: Some(LATEST_REVISION),
+            page_size: Some(500),
+            page_token: None,
+        };
+        self.client.pages(request).collect_all().await
+    }
+
+    /// 使用 `blocks` 替换群公告的全部内容，返回新的版本
+    pub async fn replace_announcement(&self, chat_id: &ChatId, blocks: Vec<Block>) -> Result<i64> {
+        let children = self
+            .announcement_blocks(chat_id)
+            .await?
+            .iter()
+            .find(|block| block.block_id.as_deref() == Some(chat_id.as_str()))
+            .map_or(0, |root| root.children.len());
+        let chat_id = chat_id.as_str().to_string();
+        if children > 0 {
+            let request = DeleteChatAnnouncementBlocks {
+                chat_id: chat_id.clone(),
+                block_id: chat_id.clone(),
+                revision_id: Some(LATEST_REVISION),
+                start_index: 0,
+                end_index: children as i32,
+            };
+            self.client.send(&request).await?.into_data()?;
+        }
+        let request = CreateChatAnnouncementBlocks {
+            chat_id: chat_id.clone(),
+            block_id: chat_id,
+            revision_id: Some(LATEST_REVISION),
+            children: blocks,
+            index: None,
+        };
+        Ok(self
+            .client
+            .send(&request)
+            .await?
+            .into_data()?
+            .document_revision_id)
+    }
+
+    /// 消息的所有已读用户，用户 ID 为 `open_id`
+    pub async fn read_users(&self, message_id: &str) -> Result<Vec<ReadUser>> {
+        let request = ListReadUsers {
+            message_id: message_id.to_string(),
+            user_id_type: UserIdType::OpenId,
+            page_size: Some(100),
+            page_token: None,
+        };
+        self.client.pages(request).collect_all().await
+    }
+
+    /// 从 `end_time` 开始向前逐页获取群组的历史消息，直到 `start_time`，用于导出、归档。
+    ///
+    /// ```no_run
+    /// use futures_util::TryStreamExt;
+    /// use lark_sdk::id::ChatId;
+    ///
+    /// # async fn run(client: lark_sdk::Client) -> lark_sdk::Result<()> {
+    /// let mut pages = Box::pin(client.im().history(&ChatId::new("oc_xxx"), None, None));
+    /// while let Some(messages) = pages.try_next().await? {
+    ///     for message in messages {
+    ///         println!("{}", message.message_id);
+    ///     }
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn history(
+        &self,
+        chat_id: &ChatId,
+        start_time: Option<LarkTimestamp>,
+        end_time: Option<LarkTimestamp>,
+    ) -> impl Stream<Item = Result<Vec<Message>>> + 'a {
+        let request = ListMessages {
+            sort_type: Some(SortType::ByCreateTimeDesc),
+            page_size: Some(50),
+            ..ListMessages::chat(chat_id, start_time, end_time)
+        };
+        self.client.pages(request).into_stream()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use futures_util::TryStreamExt;
+
+    use super::*;
+    use crate::id::Email;
+    use crate::test_util::serve_all;
+    use crate::LarkError;
+
+    #[tokio::test]
+    async fn receive_id_type() {
+        let (address, handle) = serve_all(vec![
+            r#"{"code":0,"msg":"ok","data":{"message_id":"om_1","msg_type":"text","chat_id":"oc_1"}}"#,
+            r#"{"code":0,"msg":"ok","data":{"message_id":"om_2","msg_type":"interactive"}}"#,
+            r#"{"code":230002,"msg":"Bot is not in the chat"}"#,
+        ]);
+        let client = Client::builder().domain(address).build().unwrap();
+        let message = client
+            .im()
+            .send_text_to_open_id("ou_1", "hi")
+            .await
+            .unwrap();
+        assert_eq!(message.message_id, "om_1");
+        client
+            .im()
+            .send_card_to_chat("oc_1", &serde_json::json!({"elements": []}))
+            .await
+            .unwrap();
+        let err = client
+            .im()
+            .send_text(&Email::new("tom@example.com"), "hi")
+            .await
+            .unwrap_err();
+        assert!(matches!(err, LarkError::Api { code: 230002, .. }));
+
+        let requests = handle.join().unwrap();
+        assert!(requests[0].starts_with("POST /open-apis/im/v1/messages?receive_id_type=open_id "));
+        assert!(requests[0].contains(r#""receive_id":"ou_1""#));
+        assert!(requests[0].contains(r#""content":"{\"text\":\"hi\"}""#));
+        assert!(requests[1].contains("receive_id_type=chat_id"));
+        assert!(requests[1].contains(r#""msg_type":"interactive""#));
+        assert!(requests[2].contains("receive_id_type=email"));
+    }
+
+    #[test]
+    fn card_template() {
+        let template = CardTemplate::new("t1").version("1.0.0").variable("n", 1);
+        let request = SendMessage::card_template(&ChatId::new("oc_1"), &template);
+        assert_eq!(request.msg_type, "interactive");
+        assert_eq!(
+            request.content,
+            r#"{"data":{"template_id":"t1","template_variable":{"n":1},"template_version_name":"1.0.0"},"type":"template"}"#
+        );
+    }
+
+    #[tokio::test]
+    async fn history_backwards() {
+        let (address, handle) = serve_all(vec![
+            r#"{"code":0,"msg":"ok","data":{"items":[{"message_id":"om_3","msg_type":"text","sender":{"id":"ou_1","id_type":"open_id","sender_type":"user"},"body":{"content":"{\"text\":\"hi\"}"}}],"page_token":"p2","has_more":true}}"#,
+            r#"{"code":0,"msg":"ok","data":{"items":[{"message_id":"om_1","msg_type":"text"}],"has_more":false}}"#,
+        ]);
+        let client = Client::builder().domain(address).build().unwrap();
+        let pages: Vec<Vec<Message>> = client
+            .im()
+            .history(
+                &ChatId::new("oc_1"),
+                Some(LarkTimestamp::from_seconds(1700000000)),
+                None,
+            )
+            .try_collect()
+            .await
+            .unwrap();
+        assert_eq!(pages.len(), 2);
+        assert_eq!(pages[0][0].sender.as_ref().unwrap().id, "ou_1");
+        assert_eq!(pages[1][0].message_id, "om_1");
+
+        let requests = handle.join().unwrap();
+        assert!(requests[0].contains("container_id_type=chat&container_id=oc_1&start_time=1700000000&sort_type=ByCreateTimeDesc"));
+        assert!(requests[1].contains("page_token=p2"));
+    }
+
+    #[tokio::test]
+    async fn read_users() {
+        let (address, handle) = serve_all(vec![
+            r#"{"code":0,"msg":"ok","data":{"items":[{"user_id_type":"open_id","user_id":"ou_1","timestamp":"1609484183000","tenant_key":"t1"}],"has_more":false}}"#,
+        ]);
+        let client = Client::builder().domain(address).build().unwrap();
+        let users = client.im().read_users("om_1").await.unwrap();
+        assert_eq!(users[0].user_id, "ou_1");
+        assert_eq!(users[0].timestamp.millis(), 1609484183000);
+        assert!(handle.join().unwrap()[0]
+            .starts_with("GET /open-apis/im/v1/messages/om_1/read_users?user_id_type=open_id"));
+    }
+
+    #[tokio::test]
+    async fn ephemeral_card() {
+        let (address, handle) = serve_all(vec![
+            r#"{"code":0,"msg":"ok","data":{"message_id":"om_1"}}"#,
+            r#"{"code":0,"msg":"ok","data":{}}"#,
+        ]);
+        let client = Client::builder().domain(address).build().unwrap();
+        let card = serde_json::json!({"elements": [{"tag": "markdown", "content": "only you"}]});
+        let message_id = client
+            .im()
+            .send_ephemeral_card(&ChatId::new("oc_1"), &OpenId::new("ou_1"), card)
+            .await
+            .unwrap();
+        assert_eq!(message_id, "om_1");
+        client
+            .im()
+            .delete_ephemeral_card(&message_id)
+            .await
+            .unwrap();
+
+        let requests = handle.join().unwrap();
+        assert!(requests[0].starts_with("POST /open-apis/ephemeral/v1/send "));
+        assert!(requests[0].contains(r#""chat_id":"oc_1""#));
+        assert!(requests[0].contains(r#""open_id":"ou_1""#));
+        assert!(requests[0].contains(r#""card":{"elements""#));
+        assert!(!requests[0].contains("user_id"));
+        assert!(requests[1].contains(r#"{"message_id":"om_1"}"#));
+    }
+
+    #[tokio::test]
+    async fn replace_announcement() {
+        let (address, handle) = serve_all(vec![
+            r#"{"code":0,"msg":"ok","data":{"items":[{"block_id":"oc_1","block_type":1,"children":["doxcn1","doxcn2"],"page":{"elements":[]}},{"block_id":"doxcn1","parent_id":"oc_1","block_type":2,"text":{"elements":[{"text_run":{"content":"old"}}]}},{"block_id":"doxcn2","parent_id":"oc_1","block_type":22,"divider":{}}],"has_more":false}}"#,
+            r#"{"code":0,"msg":"ok","data":{"document_revision_id":5}}"#,
+            r#"{"code":0,"msg":"ok","data":{"children":[{"block_id":"doxcn3","block_type":2}],"document_revision_id":6}}"#,
+        ]);
+        let client = Client::builder().domain(address).build().unwrap();
+        let revision = client
+            .im()
+            .replace_announcement(&ChatId::new("oc_1"), vec![Block::text("new")])
+            .await
+            .unwrap();
+        assert_eq!(revision, 6);
+
+        let requests = handle.join().unwrap();
+        assert!(requests[0]
+            .starts_with("GET /open-apis/docx/v1/chats/oc_1/announcement/blocks?revision_id=-1"));
+        assert!(requests[1].starts_with("DELETE /open-apis/docx/v1/chats/oc_1/announcement/blocks/oc_1/children/batch_delete?revision_id=-1 "));
+        assert!(requests[1].contains(r#""end_index":2"#));
+        assert!(requests[2].starts_with(
+            "POST /open-apis/docx/v1/chats/oc_1/announcement/blocks/oc_1/children?revision_id=-1 "
+        ));
+        assert!(requests[2].contains(r#""content":"new""#));
+    }
+}
@@ -0,0 +1,123 @@
+//! 群公告。
+//!
+//! 新版群公告是一篇 docx 文档，根块的 ID 为群组的 `chat_id`，内容由 [`docx`](crate::docx) 中的块组成。
+//! 旧版群公告（`announcement_type` 为 `doc`）不能使用这些接口。
+
+use serde::Deserialize;
+
+use crate::docx::Block;
+use crate::time::{self, LarkTimestamp};
+use crate::ListData;
+
+/// 最新版本的 `revision_id`
+pub const LATEST_REVISION: i64 = -1;
+
+/// 群公告的基本信息
+#[derive(Debug, Clone, Deserialize, lark_sdk::Response)]
+pub struct ChatAnnouncement {
+    pub revision_id: i64,
+    #[serde(default, with = "time::seconds::option")]
+    pub create_time: Option<LarkTimestamp>,
+    #[serde(default, with = "time::seconds::option")]
+    pub update_time: Option<LarkTimestamp>,
+    #[serde(default)]
+    pub owner_id: Option<String>,
+    #[serde(default)]
+    pub modifier_id: Option<String>,
+    /// `docx` 或旧版的 `doc`
+    #[serde(default)]
+    pub announcement_type: Option<String>,
+}
+
+/// 获取群公告的基本信息
+#[derive(Debug, Clone, lark_sdk::Request)]
+#[request(
+    method = "GET",
+    url = "/open-apis/docx/v1/chats/:chat_id/announcement",
+    auth = "tenant",
+    validate,
+    response = ChatAnnouncement
+)]
+pub struct GetChatAnnouncement {
+    #[request(path)]
+    pub chat_id: String,
+}
+
+/// 获取群公告的所有块，第一个块为根块
+#[derive(Debug, Clone, lark_sdk::Request)]
+#[request(
+    method = "GET",
+    url = "/open-apis/docx/v1/chats/:chat_id/announcement/blocks",
+    auth = "tenant",
+    validate,
+    response = ListData<Block>
+)]
+pub struct ListChatAnnouncementBlocks {
+    #[request(path)]
+    pub chat_id: String,
+    #[request(query)]
+    pub revision_id: Option<i64>,
+    #[request(query)]
+    pub page_size: Option<u32>,
+    #[request(query, page_token)]
+    pub page_token: Option<String>,
+}
+
+/// 创建的块
+#[derive(Debug, Clone, Deserialize, lark_sdk::Response)]
+pub struct CreatedBlocks {
+    #[serde(default)]
+    pub children: Vec<Block>,
+    pub document_revision_id: i64,
+}
+
+/// 在 `block_id` 的第 `index` 个子块之前插入块，`index` 为空时追加到最后
+#[derive(Debug, Clone, lark_sdk::Request)]
+#[request(
+    method = "POST",
+    url = "/open-apis/docx/v1/chats/:chat_id/announcement/blocks/:block_id/children",
+    auth = "tenant",
+    validate,
+    response = CreatedBlocks
+)]
+pub struct CreateChatAnnouncementBlocks {
+    #[request(path)]
+    pub chat_id: String,
+    /// 父块，根块为 `chat_id`
+    #[request(path)]
+    pub block_id: String,
+    #[request(query)]
+    pub revision_id: Option<i64>,
+    #[request(body)]
+    pub children: Vec<Block>,
+    #[request(body)]
+    pub index: Option<i32>,
+}
+
+/// 删除后的版本
+#[derive(Debug, Clone, Deserialize, lark_sdk::Response)]
+pub struct DeletedBlocks {
+    pub document_revision_id: i64,
+}
+
+/// 删除 `block_id` 的第 `[start_index, end_index)` 个子块
+#[derive(Debug, Clone, lark_sdk::Request)]
+#[request(
+    method = "DELETE",
+    url = "/open-apis/docx/v1/chats/:chat_id/announcement/blocks/:block_id/children/batch_delete",
+    auth = "tenant",
+    validate,
+    response = DeletedBlocks
+)]
+pub struct DeleteChatAnnouncementBlocks {
+    #[request(path)]
+    pub chat_id: String,
+    #[request(path)]
+    pub block_id: String,
+    #[request(query)]
+    pub revision_id: Option<i64>,
+    #[request(body)]
+    pub start_index: i32,
+    #[request(body)]
+    pub end_index: i32,
+}
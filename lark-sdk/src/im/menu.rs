@@ -0,0 +1,266 @@
+//! 群菜单。
+//!
+//! 群菜单显示在群组的输入框上方，最多 3 个一级菜单，每个一级菜单最多 5 个二级菜单。
+
+use serde::{Deserialize, Serialize};
+
+use crate::i18n::I18nText;
+
+/// 点击菜单后的行为
+#[derive(Debug, Clone, PartialEq, Eq, lark_sdk::StringEnum)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+#[non_exhaustive]
+pub enum MenuActionType {
+    /// 没有行为，用于有二级菜单的一级菜单
+    None,
+    /// 打开链接
+    RedirectLink,
+    Unknown(String),
+}
+
+/// 菜单树
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChatMenuTree {
+    #[serde(default)]
+    pub chat_menu_top_levels: Vec<ChatMenuTopLevel>,
+}
+
+/// 一级菜单
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChatMenuTopLevel {
+    /// 添加时不需要填写
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub chat_menu_top_level_id: Option<String>,
+    pub chat_menu_item: ChatMenuItem,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub children: Vec<ChatMenuSecondLevel>,
+}
+
+impl ChatMenuTopLevel {
+    pub fn new(item: ChatMenuItem) -> Self {
+        ChatMenuTopLevel {
+            chat_menu_top_level_id: None,
+            chat_menu_item: item,
+            children: Vec::new(),
+        }
+    }
+
+    /// 添加二级菜单
+    pub fn child(mut self, item: ChatMenuItem) -> Self {
+        self.children.push(ChatMenuSecondLevel {
+            chat_menu_second_level_id: None,
+            chat_menu_item: item,
+        });
+        self
+    }
+}
+
+/// 二级菜单
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChatMenuSecondLevel {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub chat_menu_second_level_id: Option<String>,
+    pub chat_menu_item: ChatMenuItem,
+}
+
+/// 菜单项
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChatMenuItem {
+    pub action_type: MenuActionType,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub redirect_link: Option<RedirectLink>,
+    /// 图标，上传图片获得的 `image_key`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub image_key: Option<String>,
+    pub name: String,
+    /// 多语言的名称
+    #[serde(default, skip_serializing_if = "I18nText::is_empty")]
+    pub i18n_names: I18nText,
+}
+
+impl ChatMenuItem {
+    /// 没有行为的菜单项，用于有二级菜单的一级菜单
+    pub fn group(name: impl Into<String>) -> Self {
+        ChatMenuItem {
+            action_type: MenuActionType::None,
+            redirect_link: None,
+            image_key: None,
+            name: name.into(),
+            i18n_names: I18nText::new(),
+        }
+    }
+
+    /// 打开链接的菜单项
+    pub fn link(name: impl Into<String>, url: impl Into<String>) -> Self {
+        ChatMenuItem {
+            action_type: MenuActionType::RedirectLink,
+            redirect_link: Some(RedirectLink {
+                common_url: Some(url.into()),
+                ..RedirectLink::default()
+            }),
+            ..ChatMenuItem::group(name)
+        }
+    }
+}
+
+/// 菜单的链接，各端的链接为空时使用 `common_url`
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RedirectLink {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub common_url: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ios_url: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub android_url: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pc_url: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub web_url: Option<String>,
+}
+
+/// 修改菜单项时需要修改的字段
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum MenuItemField {
+    Icon,
+    Name,
+    I18nName,
+    RedirectLink,
+}
+
+/// 群组当前的菜单树
+#[derive(Debug, Clone, Deserialize, lark_sdk::Response)]
+pub struct ChatMenu {
+    #[serde(default)]
+    pub menu_tree: ChatMenuTree,
+}
+
+/// 添加一级菜单及其二级菜单，添加到已有菜单之后
+#[derive(Debug, Clone, lark_sdk::Request)]
+#[request(
+    method = "POST",
+    url = "/open-apis/im/v1/chats/:chat_id/menu_tree",
+    auth = "tenant",
+    validate,
+    response = ChatMenu
+)]
+pub struct CreateChatMenu {
+    #[request(path)]
+    pub chat_id: String,
+    #[request(body)]
+    pub menu_tree: ChatMenuTree,
+}
+
+/// 删除一级菜单
+#[derive(Debug, Clone, lark_sdk::Request)]
+#[request(
+    method = "DELETE",
+    url = "/open-apis/im/v1/chats/:chat_id/menu_tree",
+    auth = "tenant",
+    validate,
+    response = ChatMenu
+)]
+pub struct DeleteChatMenu {
+    #[request(path)]
+    pub chat_id: String,
+    #[request(body)]
+    pub chat_menu_top_level_ids: Vec<String>,
+}
+
+/// 修改一级或二级菜单项的 `update_fields` 字段
+#[derive(Debug, Clone, lark_sdk::Request)]
+#[request(
+    method = "PATCH",
+    url = "/open-apis/im/v1/chats/:chat_id/menu_items/:menu_item_id",
+    auth = "tenant",
+    validate,
+    response = serde_json::Value
+)]
+pub struct UpdateChatMenuItem {
+    #[request(path)]
+    pub chat_id: String,
+    #[request(path)]
+    pub menu_item_id: String,
+    #[request(body)]
+    pub update_fields: Vec<MenuItemField>,
+    #[request(body)]
+    pub chat_menu_item: ChatMenuItem,
+}
+
+/// 调整一级菜单的顺序
+#[derive(Debug, Clone, lark_sdk::Request)]
+#[request(
+    method = "POST",
+    url = "/open-apis/im/v1/chats/:chat_id/menu_tree/sort",
+    auth = "tenant",
+    validate,
+    response = ChatMenu
+)]
+pub struct SortChatMenu {
+    #[request(path)]
+    pub chat_id: String,
+    #[request(body)]
+    pub chat_menu_top_level_ids: Vec<String>,
+}
+
+/// 获取群组的菜单树
+#[derive(Debug, Clone, lark_sdk::Request)]
+#[request(
+    method = "GET",
+    url = "/open-apis/im/v1/chats/:chat_id/menu_tree",
+    auth = "tenant",
+    validate,
+    response = ChatMenu
+)]
+pub struct GetChatMenu {
+    #[request(path)]
+    pub chat_id: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Method, Request};
+
+    #[test]
+    fn create_menu() {
+        let menu = ChatMenuTopLevel::new(ChatMenuItem::group("入职"))
+            .child(ChatMenuItem::link("手册", "https://example.com/handbook"));
+        let request = CreateChatMenu {
+            chat_id: "oc_1".to_string(),
+            menu_tree: ChatMenuTree {
+                chat_menu_top_levels: vec![menu],
+            },
+        };
+        let body: serde_json::Value =
+            serde_json::from_slice(&request.body().unwrap().unwrap()).unwrap();
+        assert_eq!(
+            body,
+            serde_json::json!({
+                "menu_tree": {
+                    "chat_menu_top_levels": [{
+                        "chat_menu_item": {"action_type": "NONE", "name": "入职"},
+                        "children": [{
+                            "chat_menu_item": {
+                                "action_type": "REDIRECT_LINK",
+                                "redirect_link": {"common_url": "https://example.com/handbook"},
+                                "name": "手册"
+                            }
+                        }]
+                    }]
+                }
+            })
+        );
+
+        let update = UpdateChatMenuItem {
+            chat_id: "oc_1".to_string(),
+            menu_item_id: "7156553273518882844".to_string(),
+            update_fields: vec![MenuItemField::Name, MenuItemField::I18nName],
+            chat_menu_item: ChatMenuItem::group("新人"),
+        };
+        assert_eq!(update.method(), Method::Patch);
+        assert!(String::from_utf8(update.body().unwrap().unwrap())
+            .unwrap()
+            .contains(r#""update_fields":["NAME","I18N_NAME"]"#));
+    }
+}
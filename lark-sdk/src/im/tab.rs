@@ -0,0 +1,214 @@
+//! 群标签页。
+//!
+//! 标签页显示在群组顶部，可以是云文档、链接等，机器人需要是群组的成员。
+
+use serde::{Deserialize, Serialize};
+
+/// 标签页的类型
+#[derive(Debug, Clone, PartialEq, Eq, lark_sdk::StringEnum)]
+#[serde(rename_all = "snake_case")]
+#[non_exhaustive]
+pub enum TabType {
+    Message,
+    DocList,
+    Doc,
+    Pin,
+    MeetingMinute,
+    ChatAnnouncement,
+    Url,
+    File,
+    Unknown(String),
+}
+
+/// 群标签页
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChatTab {
+    /// 添加时不需要填写
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tab_id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tab_name: Option<String>,
+    pub tab_type: TabType,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tab_content: Option<TabContent>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tab_config: Option<TabConfig>,
+}
+
+impl ChatTab {
+    /// 链接类型的标签页
+    pub fn url(name: impl Into<String>, url: impl Into<String>) -> Self {
+        ChatTab {
+            tab_id: None,
+            tab_name: Some(name.into()),
+            tab_type: TabType::Url,
+            tab_content: Some(TabContent {
+                url: Some(url.into()),
+                ..TabContent::default()
+            }),
+            tab_config: None,
+        }
+    }
+
+    /// 云文档类型的标签页，`doc` 为文档的链接
+    pub fn doc(name: impl Into<String>, doc: impl Into<String>) -> Self {
+        ChatTab {
+            tab_id: None,
+            tab_name: Some(name.into()),
+            tab_type: TabType::Doc,
+            tab_content: Some(TabContent {
+                doc: Some(doc.into()),
+                ..TabContent::default()
+            }),
+            tab_config: None,
+        }
+    }
+}
+
+/// 标签页的内容，与标签页的类型对应
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TabContent {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub doc: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub meeting_minute: Option<String>,
+}
+
+/// 标签页的配置
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TabConfig {
+    /// 图标，上传图片获得的 `image_key`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub icon_key: Option<String>,
+    /// 是否为内置的标签页
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub is_built_in: Option<bool>,
+}
+
+/// 群组当前的所有标签页
+#[derive(Debug, Clone, Deserialize, lark_sdk::Response)]
+pub struct ChatTabs {
+    #[serde(default)]
+    pub chat_tabs: Vec<ChatTab>,
+}
+
+/// 添加标签页
+#[derive(Debug, Clone, lark_sdk::Request)]
+#[request(
+    method = "POST",
+    url = "/open-apis/im/v1/chats/:chat_id/chat_tabs",
+    auth = "tenant",
+    validate,
+    response = ChatTabs
+)]
+pub struct CreateChatTabs {
+    #[request(path)]
+    pub chat_id: String,
+    #[request(body)]
+    pub chat_tabs: Vec<ChatTab>,
+}
+
+/// 删除标签页
+#[derive(Debug, Clone, lark_sdk::Request)]
+#[request(
+    method = "DELETE",
+    url = "/open-apis/im/v1/chats/:chat_id/chat_tabs/delete_tabs",
+    auth = "tenant",
+    validate,
+    response = ChatTabs
+)]
+pub struct DeleteChatTabs {
+    #[request(path)]
+    pub chat_id: String,
+    #[request(body)]
+    pub tab_ids: Vec<String>,
+}
+
+/// 更新标签页，`chat_tabs` 需要包含 `tab_id`
+#[derive(Debug, Clone, lark_sdk::Request)]
+#[request(
+    method = "POST",
+    url = "/open-apis/im/v1/chats/:chat_id/chat_tabs/update_tabs",
+    auth = "tenant",
+    validate,
+    response = ChatTabs
+)]
+pub struct UpdateChatTabs {
+    #[request(path)]
+    pub chat_id: String,
+    #[request(body)]
+    pub chat_tabs: Vec<ChatTab>,
+}
+
+/// 调整标签页的顺序，消息标签页总是第一个
+#[derive(Debug, Clone, lark_sdk::Request)]
+#[request(
+    method = "POST",
+    url = "/open-apis/im/v1/chats/:chat_id/chat_tabs/sort_tabs",
+    auth = "tenant",
+    validate,
+    response = ChatTabs
+)]
+pub struct SortChatTabs {
+    #[request(path)]
+    pub chat_id: String,
+    #[request(body)]
+    pub tab_ids: Vec<String>,
+}
+
+/// 获取群组的所有标签页
+#[derive(Debug, Clone, lark_sdk::Request)]
+#[request(
+    method = "GET",
+    url = "/open-apis/im/v1/chats/:chat_id/chat_tabs/list_tabs",
+    auth = "tenant",
+    validate,
+    response = ChatTabs
+)]
+pub struct ListChatTabs {
+    #[request(path)]
+    pub chat_id: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Request;
+
+    #[test]
+    fn create_tabs() {
+        let request = CreateChatTabs {
+            chat_id: "oc_1".to_string(),
+            chat_tabs: vec![ChatTab::url("Wiki", "https://example.com/wiki")],
+        };
+        assert_eq!(request.url(), "/open-apis/im/v1/chats/oc_1/chat_tabs");
+        let body: serde_json::Value =
+            serde_json::from_slice(&request.body().unwrap().unwrap()).unwrap();
+        assert_eq!(
+            body,
+            serde_json::json!({
+                "chat_tabs": [{
+                    "tab_name": "Wiki",
+                    "tab_type": "url",
+                    "tab_content": {"url": "https://example.com/wiki"}
+                }]
+            })
+        );
+
+        let tabs: ChatTabs = serde_json::from_str(
+            r#"{"chat_tabs":[{"tab_id":"7101214603622940672","tab_type":"message"},{"tab_id":"7101214603622940674","tab_type":"calendar"},{"tab_id":"7101214603622940673","tab_name":"Wiki","tab_type":"url","tab_content":{"url":"https://example.com/wiki"},"tab_config":{"icon_key":"img_v2_1","is_built_in":false}}]}"#,
+        )
+        .unwrap();
+        assert_eq!(tabs.chat_tabs[0].tab_type, TabType::Message);
+        assert_eq!(
+            tabs.chat_tabs[1].tab_type,
+            TabType::Unknown("calendar".to_string())
+        );
+        assert_eq!(
+            tabs.chat_tabs[2].tab_config.as_ref().unwrap().is_built_in,
+            Some(false)
+        );
+    }
+}
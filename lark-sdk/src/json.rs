@@ -0,0 +1,238 @@
+//! 响应的 JSON 解析。
+//!
+//! 开启 `simd-json` 特性后优先使用 simd-json 解析，适用于每分钟需要解析大量响应的服务；
+//! simd-json 解析失败时（如 `data` 出现在 `code` 之前，需要使用 serde_json 的 `RawValue`）
+//! 回退到 serde_json。
+//!
+//! 默认使用宽松模式解析，忽略响应类型中没有的字段。测试中可以使用 [`DecodeMode::Strict`]
+//! 及时发现飞书响应结构的变化，见 [`ClientBuilder::decode_mode`](crate::ClientBuilder::decode_mode)。
+
+use std::cell::Cell;
+
+use serde::de::{self, DeserializeOwned};
+use serde::{Deserialize, Deserializer};
+use serde_ignored::Path;
+
+use crate::transport::HttpResponse;
+use crate::{LarkError, RequestParts, Result};
+
+/// 错误信息中保留的响应内容长度
+const SNIPPET_LEN: usize = 512;
+
+/// 响应的解析模式
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum DecodeMode {
+    /// 忽略 `data` 中响应类型没有的字段
+    #[default]
+    Lenient,
+    /// `data` 中出现响应类型没有的字段，或者接口返回成功但缺少 `data` 时解析失败。
+    ///
+    /// 无法检查 `#[serde(flatten)]` 字段和 `#[serde(untagged)]` 枚举中的未知字段
+    Strict,
+}
+
+thread_local! {
+    static MODE: Cell<DecodeMode> = const { Cell::new(DecodeMode::Lenient) };
+}
+
+/// 在 `f` 中使用 `mode` 解析响应，`f` 返回后恢复之前的模式。
+///
+/// ```
+/// use lark_sdk::json::{self, DecodeMode};
+/// use lark_sdk::BodyResponse;
+///
+/// #[derive(Debug, serde::Deserialize, lark_sdk::Body)]
+/// struct User {
+///     open_id: String,
+/// }
+///
+/// let body = br#"{"code":0,"msg":"ok","data":{"open_id":"ou_1","name":"lark"}}"#;
+/// assert!(json::from_slice::<BodyResponse<User>>(body).is_ok());
+/// let err = json::with_mode(DecodeMode::Strict, || {
+///     json::from_slice::<BodyResponse<User>>(body).unwrap_err()
+/// });
+/// assert!(err.to_string().contains("unknown fields: name"));
+/// ```
+pub fn with_mode<T>(mode: DecodeMode, f: impl FnOnce() -> T) -> T {
+    struct Restore(DecodeMode);
+
+    impl Drop for Restore {
+        fn drop(&mut self) {
+            MODE.with(|current| current.set(self.0));
+        }
+    }
+
+    let _restore = Restore(MODE.with(|current| current.replace(mode)));
+    f()
+}
+
+/// 当前的解析模式
+pub(crate) fn mode() -> DecodeMode {
+    MODE.with(Cell::get)
+}
+
+/// 解析响应中的 `data`，严格模式下出现未知字段时返回错误，`envelope` 为允许出现的顶层字段
+pub(crate) fn deserialize_data<'de, T, D>(
+    deserializer: D,
+    envelope: &[&str],
+) -> std::result::Result<T, D::Error>
+where
+    T: Deserialize<'de>,
+    D: Deserializer<'de>,
+{
+    if mode() == DecodeMode::Lenient {
+        return T::deserialize(deserializer);
+    }
+    let mut unknown = Vec::new();
+    let data = serde_ignored::deserialize(deserializer, |path| {
+        let mut segments = Vec::new();
+        path_segments(&path, &mut segments);
+        if !(segments.len() == 1 && envelope.contains(&segments[0].as_str())) {
+            unknown.push(segments.join("."));
+        }
+    })?;
+    if !unknown.is_empty() {
+        return Err(de::Error::custom(format!(
+            "unknown fields: {}",
+            unknown.join(", ")
+        )));
+    }
+    Ok(data)
+}
+
+/// 字段名和数组下标，忽略 `Option` 等包装
+fn path_segments(path: &Path, segments: &mut Vec<String>) {
+    match path {
+        Path::Root => {}
+        Path::Seq { parent, index } => {
+            path_segments(parent, segments);
+            segments.push(index.to_string());
+        }
+        Path::Map { parent, key } => {
+            path_segments(parent, segments);
+            segments.push(key.clone());
+        }
+        Path::Some { parent }
+        | Path::NewtypeStruct { parent }
+        | Path::NewtypeVariant { parent } => path_segments(parent, segments),
+    }
+}
+
+/// 解析响应内容。
+pub fn from_slice<T: DeserializeOwned>(bytes: &[u8]) -> Result<T> {
+    #[cfg(feature = "simd-json")]
+    {
+        // simd-json 会修改输入的内容，保留原始内容用于回退
+        let mut buffer = bytes.to_vec();
+        if let Ok(value) = simd_json::serde::from_slice(&mut buffer) {
+            return Ok(value);
+        }
+    }
+    Ok(serde_json::from_slice(bytes)?)
+}
+
+/// 解析接口的响应，失败时返回带有接口、状态码、log_id 和部分响应内容的
+/// [`LarkError::Decode`]，`endpoint` 由 [`endpoint`] 生成
+pub(crate) fn from_response<T: DeserializeOwned>(
+    endpoint: String,
+    response: &HttpResponse,
+    mode: DecodeMode,
+) -> Result<T> {
+    with_mode(mode, || from_slice(&response.body)).map_err(|err| match err {
+        LarkError::Json(source) => LarkError::Decode {
+            endpoint,
+            status: response.status,
+            log_id: response.header("X-Tt-Logid").map(String::from),
+            body: snippet(&response.body),
+            source,
+        },
+        err => err,
+    })
+}
+
+/// 请求方法和接口地址，有路径参数时使用替换前的地址
+pub(crate) fn endpoint(parts: &RequestParts) -> String {
+    format!(
+        "{} {}",
+        parts.method,
+        parts.endpoint.as_deref().unwrap_or(&parts.url)
+    )
+}
+
+/// 截断过长的响应内容
+fn snippet(body: &[u8]) -> String {
+    let text = String::from_utf8_lossy(body);
+    if text.len() <= SNIPPET_LEN {
+        return text.into_owned();
+    }
+    let mut end = SNIPPET_LEN;
+    while !text.is_char_boundary(end) {
+        end -= 1;
+    }
+    format!("{}... ({} bytes)", &text[..end], body.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{BodyResponse, ListData};
+
+    #[derive(Debug, serde::Deserialize, lark_sdk::Body)]
+    struct User {
+        open_id: String,
+    }
+
+    #[test]
+    fn parse_response() {
+        let response: BodyResponse<ListData<User>> = from_slice(
+            br#"{"code":0,"data":{"items":[{"open_id":"ou_1"}],"has_more":false},"msg":"ok"}"#,
+        )
+        .unwrap();
+        assert_eq!(response.into_data().unwrap().items[0].open_id, "ou_1");
+
+        let response: BodyResponse<User> =
+            from_slice(br#"{"data":{"open_id":"ou_2"},"code":0,"msg":"ok"}"#).unwrap();
+        assert_eq!(response.into_data().unwrap().open_id, "ou_2");
+
+        let response: BodyResponse<User> =
+            from_slice(br#"{"code":1,"data":{"reason":"x"},"msg":"failed"}"#).unwrap();
+        assert_eq!(response.error_data.unwrap()["reason"], "x");
+
+        assert!(from_slice::<BodyResponse<User>>(b"{").is_err());
+    }
+
+    #[test]
+    fn response_context() {
+        let response = HttpResponse {
+            status: 400,
+            headers: vec![("x-tt-logid".to_string(), "log-1".to_string())],
+            body: format!(
+                r#"{{"code":0,"msg":"{}","data":{{"open_id":1}}}}"#,
+                "飞".repeat(200)
+            )
+            .into_bytes(),
+        };
+        let err = from_response::<BodyResponse<User>>(
+            "GET /open-apis/demo".to_string(),
+            &response,
+            DecodeMode::Lenient,
+        )
+        .unwrap_err();
+        match err {
+            LarkError::Decode {
+                endpoint,
+                status,
+                log_id,
+                body,
+                ..
+            } => {
+                assert_eq!(endpoint, "GET /open-apis/demo");
+                assert_eq!(status, 400);
+                assert_eq!(log_id.as_deref(), Some("log-1"));
+                assert!(body.starts_with(r#"{"code":0,"msg":"飞"#));
+                assert!(body.ends_with(&format!("... ({} bytes)", response.body.len())));
+            }
+            other => panic!("unexpected error {:?}", other),
+        }
+    }
+}
@@ -1,14 +1,94 @@
-pub fn add(left: usize, right: usize) -> usize {
-    left + right
-}
+//! 飞书开放平台SDK。包含了飞书开放平台的所有API，以及飞书开放平台的所有事件类型。
+// 不开启任何客户端时只使用请求、响应和事件类型，发送请求的公共部分不会被用到
+#![cfg_attr(not(any(feature = "async", feature = "minimal")), allow(dead_code))]
+
+extern crate self as lark_sdk;
 
+#[cfg(feature = "approval")]
+pub mod approval;
+#[cfg(feature = "attendance")]
+pub mod attendance;
+pub mod audit;
+pub mod auth;
+#[cfg(feature = "blocking")]
+pub mod blocking;
+pub mod card;
+#[cfg(feature = "async")]
+mod client;
+#[cfg(all(feature = "contact", feature = "async"))]
+pub mod contact_sync;
+#[cfg(feature = "corehr")]
+pub mod corehr;
+#[cfg(feature = "curl")]
+mod curl;
+#[cfg(feature = "docx")]
+pub mod docx;
+#[cfg(feature = "drive")]
+pub mod drive;
+pub mod endpoints;
+pub mod enums;
+mod error;
+pub mod event;
+mod exchange;
+#[cfg(feature = "helpdesk")]
+pub mod helpdesk;
+pub mod i18n;
+pub mod id;
+#[cfg(feature = "im")]
+pub mod im;
+pub mod json;
+pub mod metrics;
+#[cfg(feature = "minimal")]
+pub mod minimal;
+#[cfg(feature = "moments")]
+pub mod moments;
+pub mod multipart;
+#[cfg(feature = "outbox")]
+pub mod outbox;
+#[cfg(feature = "async")]
+pub mod page;
+pub mod param;
+#[cfg(feature = "performance")]
+pub mod performance;
+pub mod poll;
+pub mod quota;
+#[cfg(feature = "async")]
+pub mod registry;
+mod request;
+mod response;
+pub mod retry;
+#[cfg(feature = "sheets")]
+pub mod sheets;
 #[cfg(test)]
-mod tests {
-    use super::*;
-
-    #[test]
-    fn it_works() {
-        let result = add(2, 2);
-        assert_eq!(result, 4);
-    }
+#[cfg_attr(not(feature = "async"), allow(dead_code))]
+mod test_util;
+pub mod time;
+pub mod transport;
+#[cfg(feature = "vc")]
+pub mod vc;
+pub mod version;
+#[cfg(feature = "ws")]
+pub mod ws;
+
+#[cfg(feature = "async")]
+pub use client::{Client, ClientBuilder};
+pub use error::{is_auth_error_code, LarkError, Result};
+pub use exchange::{FEISHU_DOMAIN, LARK_DOMAIN};
+pub use lark_derive::{Body, Request, RequestSerialize, Response, StringEnum};
+pub use request::{
+    AuthType, BoxedRequest, DynRequest, Method, Request, RequestParts, RequestSerialize,
+    DEFAULT_CONTENT_TYPE,
+};
+pub use response::{
+    Body, BodyResponse, Empty, FlattenResponse, ListData, ListResponse, RawBody, Response,
+};
+
+/// 派生宏生成代码所使用的内部依赖，不属于公开API。
+#[doc(hidden)]
+pub mod __private {
+    pub use crate::request::{encode_path_segment, query_pairs, ValidateLen};
+    #[cfg(feature = "sheets")]
+    pub use crate::sheets::sheet_cell;
+    pub use serde;
+    pub use serde_json;
 }
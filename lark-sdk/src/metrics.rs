@@ -0,0 +1,173 @@
+//! 接口耗时统计。
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use crate::Method;
+
+/// 默认的耗时分桶上限
+const DEFAULT_BOUNDS: [Duration; 8] = [
+    Duration::from_millis(50),
+    Duration::from_millis(100),
+    Duration::from_millis(250),
+    Duration::from_millis(500),
+    Duration::from_secs(1),
+    Duration::from_millis(2500),
+    Duration::from_secs(5),
+    Duration::from_secs(10),
+];
+
+/// 按接口统计请求耗时的直方图，并在请求耗时超过阈值时使用 `log` 输出带有 `log_id` 的警告，
+/// 用于排查飞书开放平台一侧的响应变慢。
+///
+/// 接口使用 `方法 路径` 区分，如 `GET /open-apis/im/v1/messages/:message_id`，
+/// 没有 [`Request::endpoint`](crate::Request::endpoint) 的请求使用实际的请求路径。
+///
+/// ```
+/// use std::sync::Arc;
+/// use std::time::Duration;
+/// use lark_sdk::metrics::LatencyTracker;
+///
+/// let tracker = Arc::new(LatencyTracker::new().slow_threshold(Duration::from_secs(2)));
+/// # #[cfg(feature = "async")]
+/// let client = lark_sdk::Client::builder()
+///     .latency_tracker(tracker.clone())
+///     .build();
+/// for (endpoint, histogram) in tracker.snapshot() {
+///     println!("{} {} {:?}", endpoint, histogram.count, histogram.max);
+/// }
+/// ```
+#[derive(Debug)]
+pub struct LatencyTracker {
+    bounds: Vec<Duration>,
+    slow_threshold: Option<Duration>,
+    endpoints: Mutex<HashMap<String, Histogram>>,
+}
+
+impl LatencyTracker {
+    pub fn new() -> Self {
+        LatencyTracker {
+            bounds: DEFAULT_BOUNDS.to_vec(),
+            slow_threshold: None,
+            endpoints: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// 自定义分桶上限，需要从小到大排列
+    pub fn bounds(mut self, bounds: Vec<Duration>) -> Self {
+        self.bounds = bounds;
+        self
+    }
+
+    /// 请求耗时超过 `threshold` 时输出警告
+    pub fn slow_threshold(mut self, threshold: Duration) -> Self {
+        self.slow_threshold = Some(threshold);
+        self
+    }
+
+    /// 记录一次请求的耗时，`log_id` 为响应头 `X-Tt-Logid` 的值
+    pub fn record(&self, method: Method, endpoint: &str, elapsed: Duration, log_id: Option<&str>) {
+        let key = format!("{} {}", method, endpoint);
+        if matches!(self.slow_threshold, Some(threshold) if elapsed > threshold) {
+            log::warn!(
+                "slow request: {} took {:?}, log_id={}",
+                key,
+                elapsed,
+                log_id.unwrap_or("-")
+            );
+        }
+        let mut endpoints = self.endpoints.lock().unwrap_or_else(|err| err.into_inner());
+        endpoints
+            .entry(key)
+            .or_insert_with(|| Histogram::new(self.bounds.clone()))
+            .record(elapsed);
+    }
+
+    /// 当前所有接口的统计数据
+    pub fn snapshot(&self) -> HashMap<String, Histogram> {
+        self.endpoints
+            .lock()
+            .unwrap_or_else(|err| err.into_inner())
+            .clone()
+    }
+}
+
+impl Default for LatencyTracker {
+    fn default() -> Self {
+        LatencyTracker::new()
+    }
+}
+
+/// 一个接口的耗时分布。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Histogram {
+    /// 分桶上限，`counts[i]` 为耗时不超过 `bounds[i]` 且超过上一个上限的请求数量，
+    /// `counts` 的最后一个元素为超过所有上限的请求数量
+    pub bounds: Vec<Duration>,
+    pub counts: Vec<u64>,
+    pub count: u64,
+    pub sum: Duration,
+    pub max: Duration,
+}
+
+impl Histogram {
+    fn new(bounds: Vec<Duration>) -> Self {
+        Histogram {
+            counts: vec![0; bounds.len() + 1],
+            bounds,
+            count: 0,
+            sum: Duration::ZERO,
+            max: Duration::ZERO,
+        }
+    }
+
+    fn record(&mut self, elapsed: Duration) {
+        let index = self
+            .bounds
+            .iter()
+            .position(|bound| elapsed <= *bound)
+            .unwrap_or(self.bounds.len());
+        self.counts[index] += 1;
+        self.count += 1;
+        self.sum += elapsed;
+        self.max = self.max.max(elapsed);
+    }
+
+    /// 平均耗时
+    pub fn mean(&self) -> Duration {
+        match u32::try_from(self.count) {
+            Ok(0) => Duration::ZERO,
+            Ok(count) => self.sum / count,
+            Err(_) => Duration::from_secs_f64(self.sum.as_secs_f64() / self.count as f64),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn record() {
+        let tracker = LatencyTracker::new()
+            .bounds(vec![Duration::from_millis(100), Duration::from_secs(1)])
+            .slow_threshold(Duration::from_secs(1));
+        let endpoint = "/open-apis/im/v1/messages/:message_id";
+        tracker.record(Method::Get, endpoint, Duration::from_millis(30), None);
+        tracker.record(Method::Get, endpoint, Duration::from_millis(570), None);
+        tracker.record(Method::Get, endpoint, Duration::from_secs(3), Some("log_1"));
+        tracker.record(Method::Delete, endpoint, Duration::from_millis(20), None);
+
+        let snapshot = tracker.snapshot();
+        let histogram = &snapshot["GET /open-apis/im/v1/messages/:message_id"];
+        assert_eq!(histogram.counts, vec![1, 1, 1]);
+        assert_eq!(histogram.count, 3);
+        assert_eq!(histogram.max, Duration::from_secs(3));
+        assert_eq!(histogram.mean(), Duration::from_millis(1200));
+        assert_eq!(
+            snapshot["DELETE /open-apis/im/v1/messages/:message_id"].count,
+            1
+        );
+    }
+}
@@ -0,0 +1,751 @@
+//! 基于 [ureq](https://docs.rs/ureq) 的同步客户端，需要开启 `minimal` 特性。
+//!
+//! 不需要异步运行时，也不依赖 reqwest 的同步客户端，适合只调用少量接口的命令行工具。访问 HTTPS
+//! 地址需要同时开启 `rustls-tls` 特性。
+//!
+//! ```toml
+//! lark-sdk = { version = "0.1", default-features = false, features = ["minimal", "rustls-tls", "im"] }
+//! ```
+
+use std::collections::HashMap;
+use std::error::Error as _;
+use std::io::{self, Read};
+use std::net::IpAddr;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+
+use crate::audit::AuditSink;
+use crate::auth::TokenManager;
+use crate::exchange::Pipeline;
+use crate::json::DecodeMode;
+use crate::metrics::LatencyTracker;
+use crate::quota::Quota;
+use crate::transport::{BlockingTransport, HttpRequest, HttpResponse};
+use crate::{json, DynRequest, LarkError, Request, RequestParts, Result, FEISHU_DOMAIN};
+
+/// 同步客户端，接口与 [`crate::Client`] 一致。
+#[derive(Debug, Clone)]
+pub struct Client {
+    transport: Arc<dyn BlockingTransport>,
+    pipeline: Pipeline,
+    decode_mode: DecodeMode,
+    tenant_key: Option<String>,
+}
+
+impl Client {
+    /// 使用飞书开放平台的接口地址创建客户端
+    pub fn new() -> Self {
+        // 环境变量中的代理不合法时不使用代理，与 ureq 的行为一致
+        let mut proxy = ProxyConfig::default();
+        proxy.disabled = proxy.proxy(FEISHU_DOMAIN).is_err();
+        Client {
+            transport: Arc::new(ProxyTransport::new(proxy, None)),
+            pipeline: Pipeline::new(FEISHU_DOMAIN.to_string()),
+            decode_mode: DecodeMode::Lenient,
+            tenant_key: None,
+        }
+    }
+
+    pub fn builder() -> ClientBuilder {
+        ClientBuilder::new()
+    }
+
+    pub fn domain(&self) -> &str {
+        &self.pipeline.domain
+    }
+
+    /// 以 `tenant_key` 租户的身份发送请求的客户端，见 [`crate::Client::tenant`]
+    pub fn tenant(&self, tenant_key: impl Into<String>) -> Client {
+        Client {
+            tenant_key: Some(tenant_key.into()),
+            ..self.clone()
+        }
+    }
+
+    /// 通过 [`tenant`](Client::tenant) 指定的租户
+    pub fn tenant_key(&self) -> Option<&str> {
+        self.tenant_key.as_deref()
+    }
+
+    /// 更新商店应用的 `app_ticket`，通过 [`ClientBuilder::marketplace_credentials`] 创建的客户端
+    /// 需要在收到 [`AppTicketEvent`](crate::event::application::AppTicketEvent) 后调用，没有配置
+    /// 应用凭证时返回 `false`
+    pub fn set_app_ticket(&self, app_ticket: impl Into<String>) -> bool {
+        match &self.pipeline.tokens {
+            Some(tokens) => {
+                tokens.set_app_ticket(app_ticket.into());
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// 使用 `mode` 解析响应的客户端，见 [`crate::Client::decode_mode`]
+    pub fn decode_mode(&self, mode: DecodeMode) -> Client {
+        Client {
+            decode_mode: mode,
+            ..self.clone()
+        }
+    }
+
+    /// 发送请求并解析响应，接口返回的错误码需要调用响应的 `into_data` 等方法处理
+    pub fn send<R: Request + ?Sized>(&self, request: &R) -> Result<R::Target> {
+        self.send_parts(request.to_parts()?)
+    }
+
+    /// 发送类型擦除的请求，见 [`crate::Client::send_dyn`]
+    pub fn send_dyn(&self, request: &dyn DynRequest) -> Result<Value> {
+        let response = self.execute(request.parts()?)?;
+        json::with_mode(self.decode_mode, || request.parse_response(&response.body))
+    }
+
+    /// 发送由 [`Request::to_parts`] 生成的请求，见 [`crate::Client::send_parts`]
+    pub fn send_parts<T: DeserializeOwned>(&self, parts: RequestParts) -> Result<T> {
+        let endpoint = json::endpoint(&parts);
+        let response = self.execute(parts)?;
+        json::from_response(endpoint, &response, self.decode_mode)
+    }
+
+    /// 发送请求，需要时添加应用凭证，见 [`crate::Client`]
+    fn execute(&self, parts: RequestParts) -> Result<HttpResponse> {
+        self.pipeline
+            .exchange(parts, self.tenant_key.as_deref())
+            .run_blocking(&*self.transport)
+    }
+}
+
+impl Default for Client {
+    fn default() -> Self {
+        Client::new()
+    }
+}
+
+/// 基于 ureq 的传输层，超时、代理等使用 ureq 的配置
+#[derive(Debug, Clone)]
+pub struct UreqTransport {
+    agent: ureq::Agent,
+}
+
+impl UreqTransport {
+    pub fn new(agent: ureq::Agent) -> Self {
+        UreqTransport { agent }
+    }
+}
+
+impl BlockingTransport for UreqTransport {
+    /// 非 2xx 的响应同样返回响应内容，由客户端解析接口的错误码
+    fn send(&self, request: HttpRequest) -> Result<HttpResponse> {
+        send(&self.agent, request)
+    }
+}
+
+/// 默认的传输层，按照每个请求的完整地址选择代理，同一个代理的请求共用一个 [`ureq::Agent`]
+#[derive(Debug)]
+struct ProxyTransport {
+    proxy: ProxyConfig,
+    timeout: Option<Duration>,
+    /// 按照代理地址缓存，`None` 为直接连接
+    agents: Mutex<HashMap<Option<String>, ureq::Agent>>,
+}
+
+impl ProxyTransport {
+    fn new(proxy: ProxyConfig, timeout: Option<Duration>) -> Self {
+        ProxyTransport {
+            proxy,
+            timeout,
+            agents: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// 访问 `url` 使用的 [`ureq::Agent`]
+    fn agent(&self, url: &str) -> Result<ureq::Agent> {
+        let proxy_url = self.proxy.proxy_url(url)?;
+        let mut agents = self.agents.lock().unwrap();
+        if let Some(agent) = agents.get(&proxy_url) {
+            return Ok(agent.clone());
+        }
+        let mut builder = ureq::AgentBuilder::new().try_proxy_from_env(false);
+        if let Some(timeout) = self.timeout {
+            builder = builder.timeout(timeout);
+        }
+        if let Some(proxy_url) = &proxy_url {
+            builder = builder.proxy(parse_proxy(proxy_url)?);
+        }
+        let agent = builder.build();
+        agents.insert(proxy_url, agent.clone());
+        Ok(agent)
+    }
+}
+
+impl BlockingTransport for ProxyTransport {
+    fn send(&self, request: HttpRequest) -> Result<HttpResponse> {
+        send(&self.agent(&request.url)?, request)
+    }
+}
+
+/// 使用 ureq 发送请求
+fn send(agent: &ureq::Agent, request: HttpRequest) -> Result<HttpResponse> {
+    let mut builder = agent.request(request.method.as_str(), &request.url);
+    for (name, value) in &request.query {
+        builder = builder.query(name, value);
+    }
+    for (name, value) in &request.headers {
+        builder = builder.set(name, value);
+    }
+    let result = match &request.body {
+        Some(body) => builder.send_bytes(body),
+        None => builder.call(),
+    };
+    let response = match result {
+        Ok(response) | Err(ureq::Error::Status(_, response)) => response,
+        Err(ureq::Error::Transport(err)) => return Err(transport_error(err)),
+    };
+    let status = response.status();
+    let headers = response
+        .headers_names()
+        .into_iter()
+        .filter_map(|name| {
+            let value = response.header(&name)?.to_string();
+            Some((name, value))
+        })
+        .collect();
+    let mut body = Vec::new();
+    response.into_reader().read_to_end(&mut body)?;
+    Ok(HttpResponse {
+        status,
+        headers,
+        body,
+    })
+}
+
+/// 转换为 [`LarkError::Io`]，连接失败和超时可以被重试
+fn transport_error(err: ureq::Transport) -> LarkError {
+    let kind = match err.kind() {
+        ureq::ErrorKind::Dns | ureq::ErrorKind::ConnectionFailed => {
+            io::ErrorKind::ConnectionRefused
+        }
+        _ => err
+            .source()
+            .and_then(|source| source.downcast_ref::<io::Error>())
+            .map_or(io::ErrorKind::Other, io::Error::kind),
+    };
+    LarkError::Io(io::Error::new(kind, err))
+}
+
+/// 同步客户端配置。
+#[derive(Debug)]
+pub struct ClientBuilder {
+    domain: String,
+    timeout: Option<Duration>,
+    gzip_threshold: Option<usize>,
+    quota: Option<Arc<Quota>>,
+    latency: Option<Arc<LatencyTracker>>,
+    audit: Option<Arc<dyn AuditSink>>,
+    tokens: Option<Arc<TokenManager>>,
+    decode_mode: DecodeMode,
+    proxy: ProxyConfig,
+    transport: Option<Arc<dyn BlockingTransport>>,
+}
+
+impl ClientBuilder {
+    pub fn new() -> Self {
+        ClientBuilder {
+            domain: FEISHU_DOMAIN.to_string(),
+            timeout: None,
+            gzip_threshold: None,
+            quota: None,
+            latency: None,
+            audit: None,
+            tokens: None,
+            decode_mode: DecodeMode::Lenient,
+            proxy: ProxyConfig::default(),
+            transport: None,
+        }
+    }
+
+    /// 接口地址，默认为 [`FEISHU_DOMAIN`](crate::FEISHU_DOMAIN)
+    pub fn domain(mut self, domain: impl Into<String>) -> Self {
+        self.domain = domain.into();
+        self
+    }
+
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// 请求体不小于 `min_len` 字节时使用 gzip 压缩，见 [`crate::ClientBuilder::gzip_request_body`]
+    pub fn gzip_request_body(mut self, min_len: usize) -> Self {
+        self.gzip_threshold = Some(min_len);
+        self
+    }
+
+    /// 请求频率限制，见 [`Quota`]
+    pub fn quota(mut self, quota: impl Into<Arc<Quota>>) -> Self {
+        self.quota = Some(quota.into());
+        self
+    }
+
+    /// 统计接口耗时，见 [`LatencyTracker`]
+    pub fn latency_tracker(mut self, tracker: impl Into<Arc<LatencyTracker>>) -> Self {
+        self.latency = Some(tracker.into());
+        self
+    }
+
+    /// 为每个写操作生成审计记录，见 [`audit`](crate::audit)
+    pub fn audit_sink(mut self, sink: impl AuditSink + 'static) -> Self {
+        self.audit = Some(Arc::new(sink));
+        self
+    }
+
+    /// 自建应用的凭证，见 [`crate::ClientBuilder::app_credentials`]
+    pub fn app_credentials(
+        mut self,
+        app_id: impl Into<String>,
+        app_secret: impl Into<String>,
+    ) -> Self {
+        self.tokens = Some(Arc::new(TokenManager::new(
+            app_id.into(),
+            app_secret.into(),
+        )));
+        self
+    }
+
+    /// 商店应用的凭证，使用平台推送的 `app_ticket` 获取凭证，见 [`Client::set_app_ticket`]，
+    /// 发送 `auth = "tenant"` 的请求前需要通过 [`Client::tenant`] 指定租户
+    pub fn marketplace_credentials(
+        mut self,
+        app_id: impl Into<String>,
+        app_secret: impl Into<String>,
+    ) -> Self {
+        self.tokens = Some(Arc::new(TokenManager::marketplace(
+            app_id.into(),
+            app_secret.into(),
+        )));
+        self
+    }
+
+    /// 响应的解析模式，见 [`crate::ClientBuilder::decode_mode`]
+    pub fn decode_mode(mut self, mode: DecodeMode) -> Self {
+        self.decode_mode = mode;
+        self
+    }
+
+    /// 使用指定的代理，如 `http://proxy.example.com:8080`，`NO_PROXY` 环境变量仍然生效，
+    /// 见 [`crate::ClientBuilder::proxy`]。
+    ///
+    /// 默认使用 `HTTPS_PROXY`、`HTTP_PROXY`、`ALL_PROXY` 环境变量中的代理。
+    pub fn proxy(mut self, url: impl Into<String>) -> Self {
+        self.proxy.url = Some(url.into());
+        self
+    }
+
+    /// 代理的认证信息，见 [`crate::ClientBuilder::proxy_auth`]，用户名不能包含 `:`
+    pub fn proxy_auth(mut self, username: impl Into<String>, password: impl Into<String>) -> Self {
+        self.proxy.auth = Some((username.into(), password.into()));
+        self
+    }
+
+    /// 不使用代理，忽略代理相关的环境变量
+    pub fn no_proxy(mut self) -> Self {
+        self.proxy.disabled = true;
+        self
+    }
+
+    /// 使用其他 HTTP 库发送接口请求，见 [`transport`](crate::transport)。超时、代理等配置不再
+    /// 生效
+    pub fn transport(mut self, transport: impl BlockingTransport + 'static) -> Self {
+        self.transport = Some(Arc::new(transport));
+        self
+    }
+
+    pub fn build(self) -> Result<Client> {
+        let transport = match self.transport {
+            Some(transport) => transport,
+            None => {
+                // 代理在发送时按照请求的完整地址选择，这里提前检查代理配置
+                self.proxy.proxy(&self.domain)?;
+                Arc::new(ProxyTransport::new(self.proxy, self.timeout))
+            }
+        };
+        Ok(Client {
+            transport,
+            pipeline: Pipeline {
+                domain: self.domain,
+                gzip_threshold: self.gzip_threshold,
+                quota: self.quota,
+                latency: self.latency,
+                audit: self.audit,
+                tokens: self.tokens,
+            },
+            decode_mode: self.decode_mode,
+            tenant_key: None,
+        })
+    }
+}
+
+impl Default for ClientBuilder {
+    fn default() -> Self {
+        ClientBuilder::new()
+    }
+}
+
+/// 代理配置。
+///
+/// ureq 只支持一个代理，并且不读取 `NO_PROXY`，这里按照接口地址自行选择代理。
+#[derive(Debug, Default)]
+struct ProxyConfig {
+    disabled: bool,
+    url: Option<String>,
+    auth: Option<(String, String)>,
+    /// 不使用代理的地址，`None` 时使用 `NO_PROXY` 环境变量
+    no_proxy: Option<String>,
+}
+
+impl ProxyConfig {
+    /// 访问 `url` 使用的代理，`None` 表示直接连接
+    fn proxy(&self, url: &str) -> Result<Option<ureq::Proxy>> {
+        self.proxy_url(url)?.as_deref().map(parse_proxy).transpose()
+    }
+
+    /// 访问 `url` 使用的代理地址，包含认证信息
+    fn proxy_url(&self, url: &str) -> Result<Option<String>> {
+        if self.disabled {
+            return Ok(None);
+        }
+        let (scheme, host) = match url.split_once("://") {
+            Some((scheme, rest)) => (scheme, rest),
+            None => ("http", url),
+        };
+        let host = host_name(host.split('/').next().unwrap_or_default());
+        let no_proxy = match &self.no_proxy {
+            Some(no_proxy) => no_proxy.clone(),
+            None => env(&["NO_PROXY", "no_proxy"]).unwrap_or_default(),
+        };
+        if no_proxy_matches(&no_proxy, host) {
+            return Ok(None);
+        }
+        let url = match &self.url {
+            Some(url) => url.clone(),
+            None => {
+                let names: &[&str] = if scheme.eq_ignore_ascii_case("https") {
+                    &["HTTPS_PROXY", "https_proxy", "ALL_PROXY", "all_proxy"]
+                } else {
+                    &["HTTP_PROXY", "http_proxy", "ALL_PROXY", "all_proxy"]
+                };
+                match env(names) {
+                    Some(url) => url,
+                    None => return Ok(None),
+                }
+            }
+        };
+        let url = match &self.auth {
+            Some((username, password)) => {
+                if username.contains(':') {
+                    return Err(LarkError::InvalidRequest(
+                        "proxy username must not contain ':'".to_string(),
+                    ));
+                }
+                // 替换地址中已有的认证信息
+                let (scheme, rest) = match url.split_once("://") {
+                    Some((scheme, rest)) => (format!("{}://", scheme), rest),
+                    None => (String::new(), url.as_str()),
+                };
+                let address = rest.rsplit_once('@').map_or(rest, |(_, address)| address);
+                format!("{}{}:{}@{}", scheme, username, password, address)
+            }
+            None => url,
+        };
+        Ok(Some(url))
+    }
+}
+
+fn parse_proxy(url: &str) -> Result<ureq::Proxy> {
+    ureq::Proxy::new(url)
+        .map_err(|err| LarkError::InvalidRequest(format!("invalid proxy {}: {}", url, err)))
+}
+
+/// 第一个非空的环境变量
+fn env(names: &[&str]) -> Option<String> {
+    names
+        .iter()
+        .find_map(|name| std::env::var(name).ok().filter(|value| !value.is_empty()))
+}
+
+/// 去掉地址中的端口和 IPv6 地址的方括号
+fn host_name(address: &str) -> &str {
+    if let Some(rest) = address.strip_prefix('[') {
+        return rest.split(']').next().unwrap_or_default();
+    }
+    address.split(':').next().unwrap_or_default()
+}
+
+/// `host` 是否在 `NO_PROXY` 中，支持 `*`、域名后缀（`example.com` 或 `.example.com`）和
+/// 完整的 IP 地址，忽略条目中的端口
+fn no_proxy_matches(no_proxy: &str, host: &str) -> bool {
+    let host = host.to_ascii_lowercase();
+    no_proxy
+        .split(',')
+        .map(str::trim)
+        .filter(|entry| !entry.is_empty())
+        .any(|entry| {
+            if entry == "*" {
+                return true;
+            }
+            let entry = match entry.parse::<IpAddr>() {
+                Ok(_) => entry.to_ascii_lowercase(),
+                Err(_) => host_name(entry).to_ascii_lowercase(),
+            };
+            let entry = entry.trim_start_matches("*.").trim_start_matches('.');
+            host == entry || host.ends_with(&format!(".{}", entry))
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write;
+    use std::thread;
+
+    use super::*;
+    use crate::test_util::{accept, bind, serve, serve_all, Records};
+
+    #[derive(lark_sdk::Request)]
+    #[request(method = "POST", url = "/open-apis/demo", auth = "tenant", response = Value)]
+    struct Demo {
+        #[request(query)]
+        id_type: String,
+        #[request(body)]
+        name: String,
+    }
+
+    #[test]
+    fn send_request() {
+        let (address, server) = serve_all(vec![
+            r#"{"code":0,"msg":"ok","tenant_access_token":"t-1","expire":7200}"#,
+            r#"{"code":0,"msg":"ok","data":{"id":1}}"#,
+        ]);
+        let client = Client::builder()
+            .domain(address)
+            .app_credentials("cli_1", "secret")
+            .no_proxy()
+            .build()
+            .unwrap();
+        let request = Demo {
+            id_type: "open_id".to_string(),
+            name: "demo".to_string(),
+        };
+        let response = client.send(&request).unwrap();
+        assert_eq!(response.into_data().unwrap()["id"], 1);
+
+        let requests = server.join().unwrap();
+        assert!(requests[0].starts_with("POST /open-apis/auth/v3/tenant_access_token/internal "));
+        let request = requests[1].to_ascii_lowercase();
+        assert!(request.starts_with("post /open-apis/demo?id_type=open_id http/1.1\r\n"));
+        assert!(request.contains("authorization: bearer t-1\r\n"));
+        assert!(request.ends_with(r#"{"name":"demo"}"#));
+    }
+
+    #[test]
+    fn audit_sink() {
+        let (address, _) = serve_all(vec![
+            r#"{"code":0,"msg":"ok","tenant_access_token":"t-1","expire":7200}"#,
+            r#"{"code":0,"msg":"ok","data":{}}"#,
+        ]);
+        let records = Arc::new(Records::default());
+        let client = Client::builder()
+            .domain(address)
+            .app_credentials("cli_1", "secret")
+            .audit_sink(records.clone())
+            .no_proxy()
+            .build()
+            .unwrap();
+        let request = Demo {
+            id_type: "open_id".to_string(),
+            name: "demo".to_string(),
+        };
+        client.send(&request).unwrap();
+        let records = records.0.lock().unwrap();
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].endpoint, "/open-apis/demo");
+        assert_eq!(records[0].app_id.as_deref(), Some("cli_1"));
+        assert_eq!(records[0].status, Some(200));
+        assert_eq!(records[0].body_sha256.as_ref().map(String::len), Some(64));
+    }
+
+    #[test]
+    fn error_status() {
+        let (listener, address) = bind();
+        let server = thread::spawn(move || {
+            let (mut stream, _) = accept(&listener);
+            let body = r#"{"code":99991400,"msg":"request trigger frequency limit"}"#;
+            write!(
+                stream,
+                "HTTP/1.1 429 Too Many Requests\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                body.len(),
+                body
+            )
+            .unwrap();
+        });
+        let client = Client::builder()
+            .domain(address)
+            .no_proxy()
+            .build()
+            .unwrap();
+        let request = Demo {
+            id_type: "open_id".to_string(),
+            name: "demo".to_string(),
+        };
+        let err = client.send(&request).unwrap().into_data().unwrap_err();
+        assert!(matches!(err, LarkError::Api { code: 99991400, .. }));
+        server.join().unwrap();
+
+        // 连接失败时返回可以重试的 io 错误
+        let (listener, address) = bind();
+        drop(listener);
+        let client = Client::builder()
+            .domain(address)
+            .no_proxy()
+            .build()
+            .unwrap();
+        match client.send(&request) {
+            Err(LarkError::Io(err)) => assert_eq!(err.kind(), io::ErrorKind::ConnectionRefused),
+            other => panic!("unexpected result {:?}", other),
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "rustls-tls")]
+    fn proxy() {
+        let (listener, address) = bind();
+        let server = thread::spawn(move || {
+            // ureq 通过 CONNECT 建立到 HTTPS 地址的隧道，认证信息只在 CONNECT 中发送
+            let (mut stream, connect) = accept(&listener);
+            stream
+                .write_all(
+                    b"HTTP/1.1 407 Proxy Authentication Required\r\nContent-Length: 0\r\n\r\n",
+                )
+                .unwrap();
+            connect
+        });
+        let client = Client::builder()
+            .proxy(address)
+            .proxy_auth("user", "pass")
+            .build()
+            .unwrap();
+        let request = Demo {
+            id_type: "open_id".to_string(),
+            name: "demo".to_string(),
+        };
+        assert!(client.send(&request).is_err());
+        let connect = server.join().unwrap();
+        assert!(connect.starts_with("CONNECT open.feishu.cn:443 HTTP/1.1\r\n"));
+        assert!(connect
+            .to_ascii_lowercase()
+            .contains("proxy-authorization: basic dxnlcjpwyxnz\r\n"));
+    }
+
+    /// 完整地址的请求按照自己的地址选择代理，不使用接口地址的选择
+    #[test]
+    fn proxy_per_request() {
+        let (address, server) = serve(r#"{"code":0,"msg":"ok","data":{}}"#);
+        let (proxy, proxy_server) = serve(r#"{"code":0,"msg":"ok","data":{}}"#);
+        let mut builder = Client::builder().domain(address).proxy(proxy);
+        builder.proxy.no_proxy = Some("127.0.0.1".to_string());
+        let client = builder.build().unwrap();
+
+        let parts = |url: &str| RequestParts {
+            method: crate::Method::Get,
+            url: url.to_string(),
+            query: Vec::new(),
+            headers: Vec::new(),
+            content_type: "application/json; charset=utf-8".to_string(),
+            body: None,
+            endpoint: None,
+            auth: crate::AuthType::None,
+        };
+        let _: crate::BodyResponse<Value> = client.send_parts(parts("/open-apis/demo")).unwrap();
+        let _: crate::BodyResponse<Value> = client
+            .send_parts(parts("http://files.lark.test/upload"))
+            .unwrap();
+        assert!(server
+            .join()
+            .unwrap()
+            .starts_with("GET /open-apis/demo HTTP/1.1\r\n"));
+        assert!(proxy_server
+            .join()
+            .unwrap()
+            .starts_with("GET http://files.lark.test/upload HTTP/1.1\r\n"));
+    }
+
+    #[test]
+    fn marketplace_tenant() {
+        let (address, server) = serve_all(vec![
+            r#"{"code":0,"msg":"ok","app_access_token":"a-1","expire":7200}"#,
+            r#"{"code":0,"msg":"ok","tenant_access_token":"t-1","expire":7200}"#,
+            r#"{"code":0,"msg":"ok","data":{}}"#,
+        ]);
+        let client = Client::builder()
+            .domain(address)
+            .marketplace_credentials("cli_isv", "secret")
+            .no_proxy()
+            .build()
+            .unwrap();
+        let request = Demo {
+            id_type: "open_id".to_string(),
+            name: "demo".to_string(),
+        };
+        // 商店应用需要指定租户
+        assert!(matches!(
+            client.send(&request),
+            Err(LarkError::InvalidRequest(_))
+        ));
+
+        assert!(client.set_app_ticket("ticket-1"));
+        let client = client.tenant("t1");
+        assert_eq!(client.tenant_key(), Some("t1"));
+        client.send(&request).unwrap().into_data().unwrap();
+
+        let requests = server.join().unwrap();
+        assert!(requests[0].starts_with("POST /open-apis/auth/v3/app_access_token "));
+        assert!(requests[0].contains(r#""app_ticket":"ticket-1""#));
+        assert!(requests[1].starts_with("POST /open-apis/auth/v3/tenant_access_token "));
+        assert!(requests[1].contains(r#""tenant_key":"t1""#));
+        assert!(requests[2]
+            .to_ascii_lowercase()
+            .contains("authorization: bearer t-1\r\n"));
+    }
+
+    #[test]
+    fn proxy_config() {
+        let proxy = ProxyConfig {
+            disabled: true,
+            url: Some("http://proxy:8080".to_string()),
+            auth: None,
+            no_proxy: None,
+        };
+        assert!(proxy.proxy("https://open.feishu.cn").unwrap().is_none());
+        let proxy = ProxyConfig {
+            disabled: false,
+            auth: Some(("a:b".to_string(), "c".to_string())),
+            ..proxy
+        };
+        assert!(proxy.proxy("https://open.feishu.cn").is_err());
+    }
+
+    #[test]
+    fn no_proxy() {
+        assert!(no_proxy_matches("*", "open.feishu.cn"));
+        assert!(no_proxy_matches("localhost, feishu.cn", "open.feishu.cn"));
+        assert!(no_proxy_matches(".feishu.cn", "open.feishu.cn"));
+        assert!(no_proxy_matches("*.feishu.cn", "open.feishu.cn"));
+        assert!(no_proxy_matches("OPEN.feishu.cn:443", "open.feishu.cn"));
+        assert!(no_proxy_matches("127.0.0.1,::1", "::1"));
+        assert!(!no_proxy_matches("", "open.feishu.cn"));
+        assert!(!no_proxy_matches("shu.cn,larksuite.com", "open.feishu.cn"));
+        assert_eq!(host_name("[::1]:8080"), "::1");
+        assert_eq!(host_name("open.feishu.cn:443"), "open.feishu.cn");
+    }
+}
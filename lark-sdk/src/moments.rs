@@ -0,0 +1,102 @@
+//! 公司圈。
+//!
+//! 开放平台目前只提供查询帖子的接口，不能通过接口发布帖子或评论；帖子、评论的发布和删除可以订阅
+//! [`event::moments`](crate::event::moments) 中的事件获得，再使用 [`GetPost`] 查询帖子内容。
+
+use serde::Deserialize;
+
+use crate::enums::UserIdType;
+use crate::id::OpenId;
+use crate::time::{self, LarkTimestamp};
+
+/// 公司圈的帖子
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct Post {
+    #[serde(default)]
+    pub id: Option<String>,
+    /// 发布者的 ID，类型与请求的 `user_id_type` 一致
+    #[serde(default)]
+    pub user_id: Option<String>,
+    /// 帖子内容，富文本的 JSON 字符串
+    #[serde(default)]
+    pub content: Option<String>,
+    #[serde(default)]
+    pub image_key_list: Vec<String>,
+    #[serde(default)]
+    pub media_file_token: Option<String>,
+    #[serde(default)]
+    pub media_cover_image_key: Option<String>,
+    /// 所在的板块
+    #[serde(default)]
+    pub category_ids: Vec<String>,
+    /// 帖子的链接
+    #[serde(default)]
+    pub link: Option<String>,
+    /// 发布时间
+    #[serde(default, with = "time::rfc3339::option")]
+    pub create_time: Option<LarkTimestamp>,
+}
+
+/// [`GetPost`] 返回的帖子
+#[derive(Debug, Clone, Deserialize, lark_sdk::Response)]
+pub struct PostData {
+    pub post: Post,
+}
+
+/// 查询帖子
+#[derive(Debug, Clone, lark_sdk::Request)]
+#[request(
+    method = "GET",
+    url = "/open-apis/moments/v1/posts/:post_id",
+    auth = "tenant",
+    validate,
+    response = PostData
+)]
+pub struct GetPost {
+    #[request(path)]
+    pub post_id: String,
+    /// 返回的用户 ID 类型，如 `open_id`
+    #[request(query)]
+    pub user_id_type: Option<UserIdType>,
+}
+
+impl GetPost {
+    /// 查询帖子，发布者的 ID 为 `open_id`
+    pub fn new(post_id: impl Into<String>) -> Self {
+        GetPost {
+            post_id: post_id.into(),
+            user_id_type: Some(UserIdType::of::<OpenId>()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{BodyResponse, Request};
+
+    #[test]
+    fn get_post() {
+        let request = GetPost::new("6934510454161014804");
+        assert_eq!(
+            request.url(),
+            "/open-apis/moments/v1/posts/6934510454161014804"
+        );
+        assert_eq!(
+            request.query().unwrap(),
+            vec![("user_id_type".to_string(), "open_id".to_string())]
+        );
+
+        let response: BodyResponse<PostData> = serde_json::from_str(
+            r#"{"code":0,"msg":"success","data":{"post":{"user_id":"ou_1","content":"[[{\"tag\":\"text\",\"text\":\"hi\"}]]","image_key_list":["img_1"],"category_ids":["6975"],"link":"https://example.com/post","create_time":"2022-05-23T00:00:00+08:00"}}}"#,
+        )
+        .unwrap();
+        let post = response.into_data().unwrap().post;
+        assert_eq!(post.user_id.as_deref(), Some("ou_1"));
+        assert_eq!(post.image_key_list, vec!["img_1".to_string()]);
+        assert_eq!(
+            post.create_time.unwrap().to_rfc3339(),
+            "2022-05-22T16:00:00Z"
+        );
+    }
+}
@@ -0,0 +1,126 @@
+//! `multipart/form-data` 请求体，用于上传文件的接口。
+//!
+//! 派生的请求只能生成 JSON 请求体，上传接口需要手动实现 [`Request`](crate::Request)，
+//! 在请求中保存一个 [`Boundary`]，[`content_type`](crate::Request::content_type) 返回
+//! [`Boundary::content_type`]，[`body`](crate::Request::body) 使用 [`Boundary::form`] 生成请求体。
+
+use crate::retry::new_idempotency_key;
+
+/// 表单的分隔符，同一个请求每次编码时需要使用相同的分隔符，通常保存在请求中
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Boundary {
+    boundary: String,
+    content_type: String,
+}
+
+impl Default for Boundary {
+    fn default() -> Self {
+        Boundary::new()
+    }
+}
+
+impl Boundary {
+    /// 生成随机的分隔符
+    pub fn new() -> Self {
+        Boundary::with_value(format!("lark-sdk-{}", new_idempotency_key()))
+    }
+
+    /// 使用指定的分隔符，分隔符不能出现在任何字段的内容中
+    pub fn with_value(boundary: impl Into<String>) -> Self {
+        let boundary = boundary.into();
+        Boundary {
+            content_type: format!("multipart/form-data; boundary={}", boundary),
+            boundary,
+        }
+    }
+
+    /// 请求的 `Content-Type`
+    pub fn content_type(&self) -> &str {
+        &self.content_type
+    }
+
+    /// 使用该分隔符的空表单
+    pub fn form(&self) -> Form {
+        Form {
+            boundary: self.boundary.clone(),
+            body: Vec::new(),
+        }
+    }
+}
+
+/// 表单，字段按照添加的顺序编码
+#[derive(Debug, Clone)]
+pub struct Form {
+    boundary: String,
+    body: Vec<u8>,
+}
+
+impl Form {
+    /// 添加文本字段
+    pub fn text(mut self, name: &str, value: impl AsRef<str>) -> Self {
+        self.part_header(name, None);
+        self.body.extend_from_slice(value.as_ref().as_bytes());
+        self.body.extend_from_slice(b"\r\n");
+        self
+    }
+
+    /// 添加文件字段
+    pub fn file(mut self, name: &str, file_name: &str, content: &[u8]) -> Self {
+        self.part_header(name, Some(file_name));
+        self.body.extend_from_slice(content);
+        self.body.extend_from_slice(b"\r\n");
+        self
+    }
+
+    /// 结束表单，返回请求体
+    pub fn finish(mut self) -> Vec<u8> {
+        self.body
+            .extend_from_slice(format!("--{}--\r\n", self.boundary).as_bytes());
+        self.body
+    }
+
+    fn part_header(&mut self, name: &str, file_name: Option<&str>) {
+        let mut header = format!(
+            "--{}\r\nContent-Disposition: form-data; name=\"{}\"",
+            self.boundary,
+            escape(name)
+        );
+        if let Some(file_name) = file_name {
+            header.push_str(&format!("; filename=\"{}\"", escape(file_name)));
+            header.push_str("\r\nContent-Type: application/octet-stream");
+        }
+        header.push_str("\r\n\r\n");
+        self.body.extend_from_slice(header.as_bytes());
+    }
+}
+
+/// 字段名和文件名中的引号和换行需要转义
+fn escape(value: &str) -> String {
+    value
+        .replace('"', "%22")
+        .replace('\r', "%0D")
+        .replace('\n', "%0A")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn encode_form() {
+        let boundary = Boundary::with_value("b");
+        assert_eq!(boundary.content_type(), "multipart/form-data; boundary=b");
+        let body = boundary
+            .form()
+            .text("parent_type", "docx_image")
+            .file("file", "a\"b.png", b"\x89PNG")
+            .finish();
+        assert_eq!(
+            body,
+            b"--b\r\nContent-Disposition: form-data; name=\"parent_type\"\r\n\r\ndocx_image\r\n\
+              --b\r\nContent-Disposition: form-data; name=\"file\"; filename=\"a%22b.png\"\r\n\
+              Content-Type: application/octet-stream\r\n\r\n\x89PNG\r\n--b--\r\n"
+                .to_vec()
+        );
+    }
+}
@@ -0,0 +1,528 @@
+//! 可靠发送消息的发件箱，需要开启 `outbox` 特性。
+//!
+//! [`Outbox::enqueue`] 将请求（通常是发送消息）保存到 [`OutboxStore`] 后立即返回，
+//! [`Outbox::run`] 从存储中取出到期的请求依次发送：
+//!
+//! - 入队时为支持幂等键的请求生成幂等键并随请求一起保存，重试和进程重启后再次发送时使用相同的
+//!   幂等键，由服务端去重；
+//! - 超时、连接失败和频率限制的错误按 [`Backoff`] 等待后重试，其他错误不再重试；
+//! - 不幂等也不支持幂等键的请求只发送一次：发送前先在存储中记录发送次数，进程在发送之后、
+//!   删除请求之前退出时，再次取出的请求不会重新发送，结果为 [`LarkError::Cancelled`]；
+//! - 可以通过 [`Outbox::qps`] 限制发件箱的发送频率，客户端的 [`Quota`] 同样生效；
+//! - 每次发送的结果通过 [`Outbox::on_status`] 通知调用方。
+//!
+//! ```no_run
+//! use lark_sdk::id::ChatId;
+//! use lark_sdk::im::SendMessage;
+//! use lark_sdk::outbox::{DeliveryStatus, MemoryOutboxStore, Outbox};
+//!
+//! # async fn run(client: lark_sdk::Client) -> lark_sdk::Result<()> {
+//! let outbox = Outbox::new(client, MemoryOutboxStore::new())
+//!     .qps(5)
+//!     .on_status(|entry, status| {
+//!         if let DeliveryStatus::Failed(err) = status {
+//!             eprintln!("{} failed: {}", entry.id, err);
+//!         }
+//!     });
+//! outbox
+//!     .enqueue(SendMessage::text(&ChatId::new("oc_xxx"), "hello"))
+//!     .await?;
+//! outbox.run().await
+//! # }
+//! ```
+
+use std::collections::BTreeMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use futures_util::future::BoxFuture;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::poll::Backoff;
+use crate::quota::Quota;
+use crate::retry::{is_transient, new_idempotency_key};
+use crate::time::LarkTimestamp;
+use crate::{BodyResponse, Client, LarkError, Request, RequestParts, Result};
+
+/// 频率限制的错误码
+const RATE_LIMITED: i64 = 99991400;
+
+/// [`MemoryOutboxStore`] 取出记录后的租约时间，租约内不会再次取出。发送前更新发送次数时
+/// 同样将下次发送的时间推迟一个租约
+const LEASE: Duration = Duration::from_secs(60);
+
+/// 发件箱中的一个请求
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OutboxEntry {
+    pub id: String,
+    pub parts: RequestParts,
+    /// 请求幂等或带有幂等键，可以安全地重试
+    pub retryable: bool,
+    /// 已经发送的次数
+    pub attempts: u32,
+    /// 下次发送的时间
+    pub next_attempt_at: LarkTimestamp,
+    pub created_at: LarkTimestamp,
+}
+
+/// 一次发送的结果
+#[derive(Debug)]
+pub enum DeliveryStatus {
+    /// 发送成功，包含响应的 `data`
+    Delivered(Value),
+    /// 发送失败，将在 `next_attempt_at` 重试
+    Retrying {
+        error: LarkError,
+        next_attempt_at: LarkTimestamp,
+    },
+    /// 发送失败且不再重试，请求已经从发件箱中删除
+    Failed(LarkError),
+}
+
+/// 发件箱的持久化存储，如数据库中的一张表。
+///
+/// [`due`](OutboxStore::due) 返回的记录在 [`update`](OutboxStore::update) 或
+/// [`remove`](OutboxStore::remove) 之前不应该再次返回（如设置一段租约），否则多个进程同时
+/// 运行发件箱时同一个请求会被并发发送。
+pub trait OutboxStore: Send + Sync {
+    /// 保存新的请求，返回时请求已经持久化
+    fn push<'a>(&'a self, entry: &'a OutboxEntry) -> BoxFuture<'a, Result<()>>;
+
+    /// 最多 `limit` 个 `next_attempt_at` 不晚于 `now` 的请求，按 `next_attempt_at` 排序
+    fn due(&self, now: LarkTimestamp, limit: usize) -> BoxFuture<'_, Result<Vec<OutboxEntry>>>;
+
+    /// 更新请求的发送次数和下次发送的时间，在每次发送前和发送失败、等待重试时调用
+    fn update<'a>(&'a self, entry: &'a OutboxEntry) -> BoxFuture<'a, Result<()>>;
+
+    /// 删除发送成功或不再重试的请求
+    fn remove<'a>(&'a self, id: &'a str) -> BoxFuture<'a, Result<()>>;
+}
+
+/// 保存在内存中的 [`OutboxStore`]，进程退出后未发送的请求会丢失，用于测试和不需要持久化的场景
+#[derive(Debug, Default)]
+pub struct MemoryOutboxStore {
+    entries: Mutex<BTreeMap<String, OutboxEntry>>,
+}
+
+impl MemoryOutboxStore {
+    pub fn new() -> Self {
+        MemoryOutboxStore::default()
+    }
+
+    /// 尚未发送成功的请求数量
+    pub fn len(&self) -> usize {
+        self.entries.lock().unwrap().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl OutboxStore for MemoryOutboxStore {
+    fn push<'a>(&'a self, entry: &'a OutboxEntry) -> BoxFuture<'a, Result<()>> {
+        self.entries
+            .lock()
+            .unwrap()
+            .insert(entry.id.clone(), entry.clone());
+        Box::pin(async { Ok(()) })
+    }
+
+    fn due(&self, now: LarkTimestamp, limit: usize) -> BoxFuture<'_, Result<Vec<OutboxEntry>>> {
+        let mut entries = self.entries.lock().unwrap();
+        let mut due: Vec<OutboxEntry> = entries
+            .values()
+            .filter(|entry| entry.next_attempt_at <= now)
+            .cloned()
+            .collect();
+        due.sort_by_key(|entry| entry.next_attempt_at);
+        due.truncate(limit);
+        for entry in &due {
+            if let Some(stored) = entries.get_mut(&entry.id) {
+                stored.next_attempt_at = after(now, LEASE);
+            }
+        }
+        Box::pin(async { Ok(due) })
+    }
+
+    fn update<'a>(&'a self, entry: &'a OutboxEntry) -> BoxFuture<'a, Result<()>> {
+        self.push(entry)
+    }
+
+    fn remove<'a>(&'a self, id: &'a str) -> BoxFuture<'a, Result<()>> {
+        self.entries.lock().unwrap().remove(id);
+        Box::pin(async { Ok(()) })
+    }
+}
+
+impl<S: OutboxStore + ?Sized> OutboxStore for Arc<S> {
+    fn push<'a>(&'a self, entry: &'a OutboxEntry) -> BoxFuture<'a, Result<()>> {
+        (**self).push(entry)
+    }
+
+    fn due(&self, now: LarkTimestamp, limit: usize) -> BoxFuture<'_, Result<Vec<OutboxEntry>>> {
+        (**self).due(now, limit)
+    }
+
+    fn update<'a>(&'a self, entry: &'a OutboxEntry) -> BoxFuture<'a, Result<()>> {
+        (**self).update(entry)
+    }
+
+    fn remove<'a>(&'a self, id: &'a str) -> BoxFuture<'a, Result<()>> {
+        (**self).remove(id)
+    }
+}
+
+type StatusCallback = Arc<dyn Fn(&OutboxEntry, &DeliveryStatus) + Send + Sync>;
+
+/// 发件箱，可以被克隆，克隆共享同一个存储
+#[derive(Clone)]
+pub struct Outbox {
+    client: Client,
+    store: Arc<dyn OutboxStore>,
+    retries: u32,
+    backoff: Backoff,
+    batch: usize,
+    poll_interval: Duration,
+    quota: Option<Arc<Quota>>,
+    on_status: Option<StatusCallback>,
+}
+
+impl Outbox {
+    /// 默认最多重试 5 次，每次取出 100 个请求，没有到期的请求时每秒检查一次
+    pub fn new(client: Client, store: impl OutboxStore + 'static) -> Self {
+        Outbox {
+            client,
+            store: Arc::new(store),
+            retries: 5,
+            backoff: Backoff::default(),
+            batch: 100,
+            poll_interval: Duration::from_secs(1),
+            quota: None,
+            on_status: None,
+        }
+    }
+
+    /// 最多重试的次数
+    pub fn retries(mut self, retries: u32) -> Self {
+        self.retries = retries;
+        self
+    }
+
+    /// 重试前的等待时间
+    pub fn backoff(mut self, backoff: Backoff) -> Self {
+        self.backoff = backoff;
+        self
+    }
+
+    /// 每次从存储中取出的最多请求数
+    pub fn batch(mut self, batch: usize) -> Self {
+        self.batch = batch.max(1);
+        self
+    }
+
+    /// 没有到期的请求时，[`run`](Outbox::run) 再次检查之前等待的时间
+    pub fn poll_interval(mut self, interval: Duration) -> Self {
+        self.poll_interval = interval;
+        self
+    }
+
+    /// 发件箱每秒最多发送的请求数，与客户端的 [`Quota`] 同时生效
+    pub fn qps(mut self, qps: u32) -> Self {
+        self.quota = Some(Arc::new(Quota::new().global(qps)));
+        self
+    }
+
+    /// 每次发送之后调用，由发送请求的任务同步调用，不应该阻塞
+    pub fn on_status<F>(mut self, f: F) -> Self
+    where
+        F: Fn(&OutboxEntry, &DeliveryStatus) + Send + Sync + 'static,
+    {
+        self.on_status = Some(Arc::new(f));
+        self
+    }
+
+    /// 保存请求，返回发件箱中的 ID，支持幂等键的请求在保存前生成幂等键
+    pub async fn enqueue<R: Request>(&self, mut request: R) -> Result<String> {
+        let id = new_idempotency_key();
+        let retryable = request.is_idempotent() || request.set_idempotency_key(&id);
+        let now = LarkTimestamp::now();
+        let entry = OutboxEntry {
+            parts: request.to_parts()?,
+            id,
+            retryable,
+            attempts: 0,
+            next_attempt_at: now,
+            created_at: now,
+        };
+        self.store.push(&entry).await?;
+        Ok(entry.id)
+    }
+
+    /// 发送当前所有到期的请求，返回发送的请求数
+    pub async fn drain(&self) -> Result<usize> {
+        let mut sent = 0;
+        loop {
+            let entries = self.store.due(LarkTimestamp::now(), self.batch).await?;
+            if entries.is_empty() {
+                return Ok(sent);
+            }
+            sent += entries.len();
+            for entry in entries {
+                self.deliver(entry).await?;
+            }
+        }
+    }
+
+    /// 持续发送到期的请求，只在存储出错时返回
+    pub async fn run(&self) -> Result<()> {
+        loop {
+            if self.drain().await? == 0 {
+                tokio::time::sleep(self.poll_interval).await;
+            }
+        }
+    }
+
+    async fn deliver(&self, mut entry: OutboxEntry) -> Result<()> {
+        if !entry.retryable && entry.attempts > 0 {
+            // 已经发送过但没有记录结果，无法确定服务端是否已经执行，不再发送
+            self.store.remove(&entry.id).await?;
+            self.notify(&entry, &DeliveryStatus::Failed(LarkError::Cancelled));
+            return Ok(());
+        }
+        if let Some(quota) = &self.quota {
+            let wait = quota.acquire(&entry.parts.url);
+            if !wait.is_zero() {
+                tokio::time::sleep(wait).await;
+            }
+        }
+        let next_attempt_at = entry.next_attempt_at;
+        entry.attempts += 1;
+        entry.next_attempt_at = after(LarkTimestamp::now(), LEASE);
+        self.store.update(&entry).await?;
+        entry.next_attempt_at = next_attempt_at;
+
+        let result = self
+            .client
+            .send_parts::<BodyResponse<Value>>(entry.parts.clone())
+            .await
+            .and_then(|response| response.into_data());
+        let status = match result {
+            Ok(data) => {
+                self.store.remove(&entry.id).await?;
+                DeliveryStatus::Delivered(data)
+            }
+            Err(error)
+                if entry.retryable && entry.attempts <= self.retries && retryable(&error) =>
+            {
+                entry.next_attempt_at =
+                    after(LarkTimestamp::now(), self.backoff.delay(entry.attempts - 1));
+                self.store.update(&entry).await?;
+                DeliveryStatus::Retrying {
+                    error,
+                    next_attempt_at: entry.next_attempt_at,
+                }
+            }
+            Err(error) => {
+                self.store.remove(&entry.id).await?;
+                DeliveryStatus::Failed(error)
+            }
+        };
+        self.notify(&entry, &status);
+        Ok(())
+    }
+
+    fn notify(&self, entry: &OutboxEntry, status: &DeliveryStatus) {
+        if let Some(on_status) = &self.on_status {
+            on_status(entry, status);
+        }
+    }
+}
+
+/// 超时、连接失败、频率限制和服务端错误可以重试
+fn retryable(err: &LarkError) -> bool {
+    match err {
+        LarkError::Api { code, .. } => *code == RATE_LIMITED,
+        LarkError::Decode { status, .. } => *status >= 500,
+        err => is_transient(err),
+    }
+}
+
+fn after(time: LarkTimestamp, delay: Duration) -> LarkTimestamp {
+    LarkTimestamp::from_millis(time.millis().saturating_add(delay.as_millis() as i64))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::thread;
+
+    use super::*;
+    use crate::test_util::{accept, bind, serve_all, write_response};
+
+    #[derive(lark_sdk::Request)]
+    #[request(method = "POST", url = "/open-apis/im/v1/messages", response = Value)]
+    struct SendMessage {
+        #[request(body)]
+        content: String,
+        #[request(body, idempotency_key)]
+        uuid: Option<String>,
+    }
+
+    #[derive(lark_sdk::Request)]
+    #[request(method = "POST", url = "/open-apis/demo/create", response = Value)]
+    struct Create {}
+
+    fn outbox(address: String, store: Arc<MemoryOutboxStore>) -> (Outbox, Arc<Mutex<Vec<String>>>) {
+        let client = Client::builder().domain(address).build().unwrap();
+        let statuses = Arc::new(Mutex::new(Vec::new()));
+        let recorded = statuses.clone();
+        let outbox = Outbox::new(client, store)
+            .backoff(Backoff::fixed(Duration::ZERO))
+            .on_status(move |entry, status| {
+                let status = match status {
+                    DeliveryStatus::Delivered(data) => format!("delivered {}", data),
+                    DeliveryStatus::Retrying { .. } => "retrying".to_string(),
+                    DeliveryStatus::Failed(_) => "failed".to_string(),
+                };
+                recorded
+                    .lock()
+                    .unwrap()
+                    .push(format!("{} {}", entry.attempts, status));
+            });
+        (outbox, statuses)
+    }
+
+    #[tokio::test]
+    async fn retry_with_idempotency_key() {
+        let (address, server) = serve_all(vec![
+            r#"{"code":99991400,"msg":"request trigger frequency limit"}"#,
+            r#"{"code":0,"msg":"ok","data":{"message_id":"om_1"}}"#,
+        ]);
+        let store = Arc::new(MemoryOutboxStore::new());
+        let (outbox, statuses) = outbox(address, store.clone());
+        let request = SendMessage {
+            content: "hi".to_string(),
+            uuid: None,
+        };
+        let id = outbox.enqueue(request).await.unwrap();
+        assert_eq!(store.len(), 1);
+
+        assert_eq!(outbox.drain().await.unwrap(), 2);
+        assert!(store.is_empty());
+        assert_eq!(
+            *statuses.lock().unwrap(),
+            ["1 retrying", r#"2 delivered {"message_id":"om_1"}"#]
+        );
+        let requests = server.join().unwrap();
+        assert!(requests[0].contains(&format!(r#""uuid":"{}""#, id)));
+        assert!(requests[1].contains(&format!(r#""uuid":"{}""#, id)));
+    }
+
+    #[tokio::test]
+    async fn fail_without_retry() {
+        let (address, server) = serve_all(vec![
+            r#"{"code":99991400,"msg":"request trigger frequency limit"}"#,
+            r#"{"code":230002,"msg":"bot is not in the chat"}"#,
+        ]);
+        let store = Arc::new(MemoryOutboxStore::new());
+        let (outbox, statuses) = outbox(address, store.clone());
+        outbox.enqueue(Create {}).await.unwrap();
+        outbox
+            .enqueue(SendMessage {
+                content: "hi".to_string(),
+                uuid: None,
+            })
+            .await
+            .unwrap();
+
+        assert_eq!(outbox.drain().await.unwrap(), 2);
+        assert!(store.is_empty());
+        assert_eq!(*statuses.lock().unwrap(), ["1 failed", "1 failed"]);
+        server.join().unwrap();
+    }
+
+    #[tokio::test]
+    async fn record_attempt_before_send() {
+        let store = Arc::new(MemoryOutboxStore::new());
+        let (listener, address) = bind();
+        let stored = store.clone();
+        let server = thread::spawn(move || {
+            let (mut stream, _) = accept(&listener);
+            let attempts: Vec<u32> = stored
+                .entries
+                .lock()
+                .unwrap()
+                .values()
+                .map(|entry| entry.attempts)
+                .collect();
+            write_response(&mut stream, r#"{"code":0,"msg":"ok","data":{}}"#);
+            attempts
+        });
+        let (outbox, statuses) = outbox(address, store.clone());
+        outbox.enqueue(Create {}).await.unwrap();
+
+        assert_eq!(outbox.drain().await.unwrap(), 1);
+        assert_eq!(server.join().unwrap(), [1]);
+        assert_eq!(*statuses.lock().unwrap(), ["1 delivered {}"]);
+    }
+
+    #[tokio::test]
+    async fn no_resend_after_crash() {
+        // 发送前记录了发送次数，发送后、删除前进程退出，租约到期后再次取出
+        let store = Arc::new(MemoryOutboxStore::new());
+        let now = LarkTimestamp::now();
+        let entry = OutboxEntry {
+            id: "1".to_string(),
+            parts: Create {}.to_parts().unwrap(),
+            retryable: false,
+            attempts: 1,
+            next_attempt_at: now,
+            created_at: now,
+        };
+        store.push(&entry).await.unwrap();
+        let client = Client::builder()
+            .domain("http://127.0.0.1:9")
+            .build()
+            .unwrap();
+        let failed = Arc::new(Mutex::new(Vec::new()));
+        let recorded = failed.clone();
+        let outbox = Outbox::new(client, store.clone()).on_status(move |entry, status| {
+            if let DeliveryStatus::Failed(err) = status {
+                recorded
+                    .lock()
+                    .unwrap()
+                    .push((entry.attempts, matches!(err, LarkError::Cancelled)));
+            }
+        });
+
+        assert_eq!(outbox.drain().await.unwrap(), 1);
+        assert!(store.is_empty());
+        assert_eq!(*failed.lock().unwrap(), [(1, true)]);
+    }
+
+    #[test]
+    fn lease() {
+        let store = MemoryOutboxStore::new();
+        let now = LarkTimestamp::now();
+        let entry = OutboxEntry {
+            id: "1".to_string(),
+            parts: Create {}.to_parts().unwrap(),
+            retryable: false,
+            attempts: 0,
+            next_attempt_at: now,
+            created_at: now,
+        };
+        futures_util::FutureExt::now_or_never(store.push(&entry))
+            .unwrap()
+            .unwrap();
+        let due = |now| {
+            futures_util::FutureExt::now_or_never(store.due(now, 10))
+                .unwrap()
+                .unwrap()
+        };
+        assert_eq!(due(now), [entry]);
+        assert!(due(now).is_empty());
+        assert_eq!(due(after(now, LEASE)).len(), 1);
+    }
+}
@@ -0,0 +1,518 @@
+//! 分页读取列表接口。
+//!
+//! 使用 `#[request(query, page_token)]` 标记了 `page_token` 字段的列表请求（响应为
+//! [`ListData`]）可以通过 [`Client::pages`] 逐页读取，每一页都经过客户端配置的
+//! [`Quota`]；遍历上百页的任务还可以使用 [`Pages::qps`] 单独限制这次遍历的频率，避免占满整个
+//! 应用的配额，并使用 [`Pages::max_pages`]、[`Pages::max_items`] 限制读取的总量，超过时返回
+//! [`LarkError::PageLimit`]。
+//!
+//! 长时间运行的导出任务可以通过 [`Pages::resume`] 将进度保存到 [`CursorStore`] 中，进程重启后
+//! 从中断的页继续，或者使用 [`Pages::into_page_stream`] 自行记录每一页的 `page_token`。
+//!
+//! ```no_run
+//! use lark_sdk::enums::UserIdType;
+//! use lark_sdk::im::ListReadUsers;
+//!
+//! # async fn run(client: lark_sdk::Client) -> lark_sdk::Result<()> {
+//! let request = ListReadUsers {
+//!     message_id: "om_xxx".to_string(),
+//!     user_id_type: UserIdType::OpenId,
+//!     page_size: Some(100),
+//!     page_token: None,
+//! };
+//! let users = client
+//!     .pages(request)
+//!     .qps(5)
+//!     .max_items(10_000)
+//!     .collect_all()
+//!     .await?;
+//! # Ok(())
+//! # }
+//! ```
+
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::{Arc, Mutex};
+
+use futures_util::future::BoxFuture;
+use futures_util::{stream, Stream, StreamExt, TryStreamExt};
+use serde::de::DeserializeOwned;
+
+use crate::quota::Quota;
+use crate::{Client, LarkError, ListData, ListResponse, Request, Result};
+
+/// 分页读取的进度（下一页的 `page_token`）的存储，`key` 区分同一存储中的多次遍历，如不同的导出任务。
+///
+/// 进度在调用方处理完一页、请求下一页时保存，全部读完后清除，中断后从最后一页未处理完的页继续，
+/// 该页可能被处理两次。读取、保存或清除失败时返回该错误并停止遍历，避免进度没有保存时继续读取，
+/// 重启后又从头开始。
+pub trait CursorStore: Send + Sync + fmt::Debug {
+    /// 读取 `key` 的进度，没有时返回 `None`
+    fn load<'a>(&'a self, key: &'a str) -> BoxFuture<'a, Result<Option<String>>>;
+
+    /// 保存 `key` 的进度，返回时进度已经持久化
+    fn save<'a>(&'a self, key: &'a str, page_token: &'a str) -> BoxFuture<'a, Result<()>>;
+
+    /// 清除已经读完的 `key` 的进度
+    fn clear<'a>(&'a self, key: &'a str) -> BoxFuture<'a, Result<()>>;
+}
+
+impl<S: CursorStore + ?Sized> CursorStore for Arc<S> {
+    fn load<'a>(&'a self, key: &'a str) -> BoxFuture<'a, Result<Option<String>>> {
+        (**self).load(key)
+    }
+
+    fn save<'a>(&'a self, key: &'a str, page_token: &'a str) -> BoxFuture<'a, Result<()>> {
+        (**self).save(key, page_token)
+    }
+
+    fn clear<'a>(&'a self, key: &'a str) -> BoxFuture<'a, Result<()>> {
+        (**self).clear(key)
+    }
+}
+
+/// 保存在内存中的 [`CursorStore`]
+#[derive(Debug, Default)]
+pub struct MemoryCursorStore {
+    cursors: Mutex<HashMap<String, String>>,
+}
+
+impl MemoryCursorStore {
+    pub fn new() -> Self {
+        MemoryCursorStore::default()
+    }
+}
+
+impl CursorStore for MemoryCursorStore {
+    fn load<'a>(&'a self, key: &'a str) -> BoxFuture<'a, Result<Option<String>>> {
+        let page_token = self.cursors.lock().unwrap().get(key).cloned();
+        Box::pin(async { Ok(page_token) })
+    }
+
+    fn save<'a>(&'a self, key: &'a str, page_token: &'a str) -> BoxFuture<'a, Result<()>> {
+        self.cursors
+            .lock()
+            .unwrap()
+            .insert(key.to_string(), page_token.to_string());
+        Box::pin(async { Ok(()) })
+    }
+
+    fn clear<'a>(&'a self, key: &'a str) -> BoxFuture<'a, Result<()>> {
+        self.cursors.lock().unwrap().remove(key);
+        Box::pin(async { Ok(()) })
+    }
+}
+
+/// 一页的内容
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Page<T> {
+    pub items: Vec<T>,
+    /// 读取这一页使用的 `page_token`，第一页为 `None`
+    pub page_token: Option<String>,
+    /// 下一页的 `page_token`，最后一页为 `None`，可以保存下来之后通过 [`Pages::start_from`] 继续
+    pub next_page_token: Option<String>,
+}
+
+/// 逐页读取列表接口，由 [`Client::pages`] 创建
+#[derive(Debug)]
+pub struct Pages<'a, R> {
+    client: &'a Client,
+    request: R,
+    max_pages: Option<usize>,
+    max_items: Option<usize>,
+    quota: Option<Arc<Quota>>,
+    start: Option<String>,
+    cursor: Option<(Arc<dyn CursorStore>, String)>,
+}
+
+impl Client {
+    /// 从第一页开始逐页读取 `request`，见 [`page`](crate::page)
+    pub fn pages<R, T>(&self, request: R) -> Pages<'_, R>
+    where
+        R: Request<Target = ListResponse<T>>,
+        T: DeserializeOwned,
+    {
+        Pages {
+            client: self,
+            request,
+            max_pages: None,
+            max_items: None,
+            quota: None,
+            start: None,
+            cursor: None,
+        }
+    }
+}
+
+/// [`Pages::into_page_stream`] 的状态
+struct State<R> {
+    request: Option<Result<R>>,
+    page_token: Option<String>,
+    pages: usize,
+    items: usize,
+    /// 已经从 [`CursorStore`] 中读取了进度
+    resumed: bool,
+    /// 已经返回了最后一页
+    finished: bool,
+}
+
+impl<'a, R, T> Pages<'a, R>
+where
+    R: Request<Target = ListResponse<T>> + Send + Sync + 'a,
+    T: DeserializeOwned + Send + 'a,
+{
+    /// 最多读取的页数，读完 `max_pages` 页后仍有下一页时，先返回已经读取的页，再返回
+    /// [`LarkError::PageLimit`]
+    pub fn max_pages(mut self, max_pages: usize) -> Self {
+        self.max_pages = Some(max_pages);
+        self
+    }
+
+    /// 最多读取的条数，在请求下一页之前检查，已经读取 `max_items` 条且仍有下一页时，先返回已经读取的页，
+    /// 再返回 [`LarkError::PageLimit`]，最后一页可能使总条数超过 `max_items`
+    pub fn max_items(mut self, max_items: usize) -> Self {
+        self.max_items = Some(max_items);
+        self
+    }
+
+    /// 这次遍历每秒最多请求的页数，与客户端的 [`Quota`] 同时生效
+    pub fn qps(mut self, qps: u32) -> Self {
+        self.quota = Some(Arc::new(Quota::new().global(qps)));
+        self
+    }
+
+    /// 从 `page_token` 对应的页开始读取，即之前某一页的 [`Page::next_page_token`]
+    pub fn start_from(mut self, page_token: impl Into<String>) -> Self {
+        self.start = Some(page_token.into());
+        self
+    }
+
+    /// 将进度保存到 `store` 中，`store` 中已经有 `key` 的进度时从该进度继续，优先于
+    /// [`start_from`](Pages::start_from)
+    pub fn resume(mut self, store: impl CursorStore + 'static, key: impl Into<String>) -> Self {
+        self.cursor = Some((Arc::new(store), key.into()));
+        self
+    }
+
+    /// 依次返回每一页的内容，出现错误后结束
+    pub fn into_stream(self) -> impl Stream<Item = Result<Vec<T>>> + 'a {
+        self.into_page_stream()
+            .map(|page| page.map(|page| page.items))
+    }
+
+    /// 依次返回每一页的内容和 `page_token`，出现错误后结束
+    pub fn into_page_stream(self) -> impl Stream<Item = Result<Page<T>>> + 'a {
+        let Pages {
+            client,
+            mut request,
+            max_pages,
+            max_items,
+            quota,
+            start,
+            cursor,
+        } = self;
+        let page_token = start;
+        let first = if request.set_page_token(page_token.clone()) {
+            Ok(request)
+        } else {
+            Err(LarkError::InvalidRequest(format!(
+                "{} does not support pagination, mark the page token field with `#[request(query, page_token)]`",
+                endpoint(&request)
+            )))
+        };
+        let state = State {
+            request: Some(first),
+            page_token,
+            pages: 0,
+            items: 0,
+            resumed: false,
+            finished: false,
+        };
+        stream::unfold(state, move |mut state| {
+            let quota = quota.clone();
+            let cursor = cursor.clone();
+            async move {
+                if state.finished {
+                    if let Some((store, key)) = &cursor {
+                        if let Err(err) = store.clear(key).await {
+                            state.finished = false;
+                            return Some((Err(err), state));
+                        }
+                    }
+                    return None;
+                }
+                let mut request = match state.request.take()? {
+                    Ok(request) => request,
+                    Err(err) => return Some((Err(err), state)),
+                };
+                if !state.resumed {
+                    state.resumed = true;
+                    if let Some((store, key)) = &cursor {
+                        match store.load(key).await {
+                            Ok(Some(page_token)) => {
+                                request.set_page_token(Some(page_token.clone()));
+                                state.page_token = Some(page_token);
+                            }
+                            Ok(None) => {}
+                            Err(err) => return Some((Err(err), state)),
+                        }
+                    }
+                }
+                // 已经读取的页交给调用方之后，在请求下一页之前检查限制
+                let exceeded = state.pages > 0
+                    && (max_pages.is_some_and(|max| state.pages >= max)
+                        || max_items.is_some_and(|max| state.items >= max));
+                if exceeded {
+                    let err = LarkError::PageLimit {
+                        endpoint: endpoint(&request),
+                        pages: state.pages,
+                        items: state.items,
+                    };
+                    return Some((Err(err), state));
+                }
+                if let (Some((store, key)), Some(page_token)) = (&cursor, &state.page_token) {
+                    if let Err(err) = store.save(key, page_token).await {
+                        return Some((Err(err), state));
+                    }
+                }
+                if let Some(quota) = quota {
+                    let wait = quota.acquire(&request.url());
+                    if !wait.is_zero() {
+                        tokio::time::sleep(wait).await;
+                    }
+                }
+                let data: ListData<T> = match client
+                    .send(&request)
+                    .await
+                    .and_then(|response| response.into_data())
+                {
+                    Ok(data) => data,
+                    Err(err) => return Some((Err(err), state)),
+                };
+                state.pages += 1;
+                state.items += data.items.len();
+                let next = data.page_token.filter(|_| data.has_more);
+                let page = Page {
+                    items: data.items,
+                    page_token: std::mem::replace(&mut state.page_token, next.clone()),
+                    next_page_token: next,
+                };
+                match &page.next_page_token {
+                    Some(page_token) => {
+                        request.set_page_token(Some(page_token.clone()));
+                        state.request = Some(Ok(request));
+                    }
+                    None => state.finished = true,
+                }
+                Some((Ok(page), state))
+            }
+        })
+    }
+
+    /// 读取所有页并合并为一个列表
+    pub async fn collect_all(self) -> Result<Vec<T>> {
+        self.into_stream().try_concat().await
+    }
+}
+
+fn endpoint<R: Request + ?Sized>(request: &R) -> String {
+    request
+        .endpoint()
+        .map_or_else(|| request.url(), String::from)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::serve_all;
+
+    #[derive(lark_sdk::Request)]
+    #[request(method = "GET", url = "/open-apis/demo/v1/items", response = ListData<u32>)]
+    struct ListItems {
+        #[request(query)]
+        page_size: u32,
+        #[request(query, page_token)]
+        page_token: String,
+    }
+
+    #[derive(lark_sdk::Request)]
+    #[request(method = "GET", url = "/open-apis/demo/v1/all", response = ListData<u32>)]
+    struct ListAll {}
+
+    const PAGE_1: &str =
+        r#"{"code":0,"msg":"ok","data":{"items":[1,2],"page_token":"p2","has_more":true}}"#;
+    const PAGE_2: &str =
+        r#"{"code":0,"msg":"ok","data":{"items":[3],"page_token":"","has_more":false}}"#;
+
+    fn request() -> ListItems {
+        ListItems {
+            page_size: 2,
+            page_token: "stale".to_string(),
+        }
+    }
+
+    #[tokio::test]
+    async fn collect_all() {
+        let (address, server) = serve_all(vec![PAGE_1, PAGE_2]);
+        let client = Client::builder().domain(address).build().unwrap();
+        let items = client
+            .pages(request())
+            .qps(100)
+            .collect_all()
+            .await
+            .unwrap();
+        assert_eq!(items, [1, 2, 3]);
+        let requests = server.join().unwrap();
+        assert!(requests[0].starts_with("GET /open-apis/demo/v1/items?page_size=2&page_token= "));
+        assert!(requests[1].starts_with("GET /open-apis/demo/v1/items?page_size=2&page_token=p2 "));
+    }
+
+    #[tokio::test]
+    async fn limits() {
+        let (address, _) = serve_all(vec![PAGE_1]);
+        let client = Client::builder().domain(address).build().unwrap();
+        let err = client
+            .pages(request())
+            .max_pages(1)
+            .collect_all()
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            LarkError::PageLimit {
+                pages: 1,
+                items: 2,
+                ..
+            }
+        ));
+
+        let (address, _) = serve_all(vec![PAGE_1, PAGE_2]);
+        let client = Client::builder().domain(address).build().unwrap();
+        let err = client
+            .pages(request())
+            .max_items(2)
+            .collect_all()
+            .await
+            .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "pagination of /open-apis/demo/v1/items exceeded the limit after 1 pages and 2 items"
+        );
+
+        // 没有下一页时不受限制
+        let (address, _) = serve_all(vec![PAGE_2]);
+        let client = Client::builder().domain(address).build().unwrap();
+        let items = client
+            .pages(request())
+            .max_pages(1)
+            .max_items(1)
+            .collect_all()
+            .await
+            .unwrap();
+        assert_eq!(items, [3]);
+
+        let client = Client::new();
+        let err = client.pages(ListAll {}).collect_all().await.unwrap_err();
+        assert!(matches!(err, LarkError::InvalidRequest(_)));
+    }
+
+    #[tokio::test]
+    async fn limits_deliver_pages_first() {
+        for (max_pages, max_items) in [(Some(1), None), (None, Some(2))] {
+            // 只响应第一页，请求第二页时测试会失败
+            let (address, server) = serve_all(vec![PAGE_1]);
+            let client = Client::builder().domain(address).build().unwrap();
+            let mut pages = client.pages(request());
+            if let Some(max_pages) = max_pages {
+                pages = pages.max_pages(max_pages);
+            }
+            if let Some(max_items) = max_items {
+                pages = pages.max_items(max_items);
+            }
+            let results: Vec<Result<Vec<u32>>> = pages.into_stream().collect().await;
+            assert_eq!(results.len(), 2);
+            assert_eq!(results[0].as_ref().unwrap(), &[1, 2]);
+            assert!(matches!(
+                results[1],
+                Err(LarkError::PageLimit {
+                    pages: 1,
+                    items: 2,
+                    ..
+                })
+            ));
+            server.join().unwrap();
+        }
+    }
+
+    #[tokio::test]
+    async fn resume() {
+        let store = Arc::new(MemoryCursorStore::new());
+        let (address, server) = serve_all(vec![PAGE_1, PAGE_2]);
+        let client = Client::builder().domain(address).build().unwrap();
+        let mut pages = Box::pin(
+            client
+                .pages(request())
+                .resume(store.clone(), "export")
+                .into_page_stream(),
+        );
+        let page = pages.try_next().await.unwrap().unwrap();
+        assert_eq!(page.page_token, None);
+        assert_eq!(page.next_page_token.as_deref(), Some("p2"));
+        assert_eq!(store.load("export").await.unwrap(), None);
+        // 请求第二页时保存进度，处理第二页时中断
+        let page = pages.try_next().await.unwrap().unwrap();
+        assert_eq!(page.page_token.as_deref(), Some("p2"));
+        assert_eq!(page.items, [3]);
+        drop(pages);
+        assert_eq!(store.load("export").await.unwrap().as_deref(), Some("p2"));
+        server.join().unwrap();
+
+        let (address, server) = serve_all(vec![PAGE_2]);
+        let client = Client::builder().domain(address).build().unwrap();
+        let items = client
+            .pages(request())
+            .start_from("ignored")
+            .resume(store.clone(), "export")
+            .collect_all()
+            .await
+            .unwrap();
+        assert_eq!(items, [3]);
+        assert_eq!(store.load("export").await.unwrap(), None);
+        assert!(server.join().unwrap()[0].contains("page_token=p2 "));
+    }
+
+    /// 保存进度失败的存储
+    #[derive(Debug)]
+    struct FailingStore;
+
+    impl CursorStore for FailingStore {
+        fn load<'a>(&'a self, _: &'a str) -> BoxFuture<'a, Result<Option<String>>> {
+            Box::pin(async { Ok(None) })
+        }
+
+        fn save<'a>(&'a self, _: &'a str, _: &'a str) -> BoxFuture<'a, Result<()>> {
+            Box::pin(async { Err(LarkError::InvalidRequest("save failed".to_string())) })
+        }
+
+        fn clear<'a>(&'a self, _: &'a str) -> BoxFuture<'a, Result<()>> {
+            Box::pin(async { Ok(()) })
+        }
+    }
+
+    #[tokio::test]
+    async fn resume_save_error() {
+        // 只响应第一页，保存进度失败后不再请求第二页
+        let (address, server) = serve_all(vec![PAGE_1]);
+        let client = Client::builder().domain(address).build().unwrap();
+        let results: Vec<Result<Vec<u32>>> = client
+            .pages(request())
+            .resume(FailingStore, "export")
+            .into_stream()
+            .collect()
+            .await;
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0].as_ref().unwrap(), &[1, 2]);
+        assert!(matches!(&results[1], Err(LarkError::InvalidRequest(msg)) if msg == "save failed"));
+        server.join().unwrap();
+    }
+}
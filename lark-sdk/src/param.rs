@@ -0,0 +1,77 @@
+//! 用于 `#[request(serialize_with = "...")]` 的序列化函数。
+
+use crate::time::LarkTimestamp;
+
+/// 可以转换为 unix 时间戳的类型，`NaiveDateTime` 视为 UTC 时间。
+pub trait Timestamp {
+    fn timestamp_millis(&self) -> Option<i64>;
+}
+
+impl Timestamp for LarkTimestamp {
+    fn timestamp_millis(&self) -> Option<i64> {
+        Some(self.millis())
+    }
+}
+
+#[cfg(feature = "chrono")]
+impl<Tz: chrono::TimeZone> Timestamp for chrono::DateTime<Tz> {
+    fn timestamp_millis(&self) -> Option<i64> {
+        Some(chrono::DateTime::timestamp_millis(self))
+    }
+}
+
+#[cfg(feature = "chrono")]
+impl Timestamp for chrono::NaiveDateTime {
+    fn timestamp_millis(&self) -> Option<i64> {
+        Some(self.and_utc().timestamp_millis())
+    }
+}
+
+impl<T: Timestamp> Timestamp for Option<T> {
+    fn timestamp_millis(&self) -> Option<i64> {
+        self.as_ref().and_then(Timestamp::timestamp_millis)
+    }
+}
+
+/// 序列化为 unix 时间戳（秒），如 `start_time=1672628645`。
+pub fn unix_seconds<T: Timestamp>(value: &T) -> Option<String> {
+    value
+        .timestamp_millis()
+        .map(|millis| millis.div_euclid(1000).to_string())
+}
+
+/// 序列化为 unix 时间戳（毫秒）。
+pub fn unix_millis<T: Timestamp>(value: &T) -> Option<String> {
+    value.timestamp_millis().map(|millis| millis.to_string())
+}
+
+/// 序列化为 UTC 时区的 RFC3339 格式，如 `2023-01-02T03:04:05Z`。
+pub fn rfc3339<T: Timestamp>(value: &T) -> Option<String> {
+    value
+        .timestamp_millis()
+        .map(|millis| LarkTimestamp::from_millis(millis).to_rfc3339())
+}
+
+#[cfg(all(test, feature = "chrono"))]
+mod tests {
+    use chrono::{NaiveDate, TimeZone, Utc};
+
+    use super::*;
+    use crate::RequestSerialize;
+
+    #[test]
+    fn serialize_chrono() {
+        let time = Utc.with_ymd_and_hms(2023, 1, 2, 3, 4, 5).unwrap();
+        assert_eq!(time.to_param(), Some("2023-01-02T03:04:05Z".to_string()));
+        assert_eq!(unix_seconds(&time), Some("1672628645".to_string()));
+        assert_eq!(unix_millis(&Some(time)), Some("1672628645000".to_string()));
+        assert_eq!(unix_seconds(&None::<chrono::NaiveDateTime>), None);
+        assert_eq!(rfc3339(&time), Some("2023-01-02T03:04:05Z".to_string()));
+
+        let date = NaiveDate::from_ymd_opt(2023, 1, 2).unwrap();
+        assert_eq!(date.to_param(), Some("2023-01-02".to_string()));
+        let naive = date.and_hms_opt(3, 4, 5).unwrap();
+        assert_eq!(naive.to_param(), Some("2023-01-02T03:04:05".to_string()));
+        assert_eq!(unix_seconds(&naive), Some("1672628645".to_string()));
+    }
+}
@@ -0,0 +1,229 @@
+//! 绩效。
+//!
+//! 绩效数据按「周期 - 项目 - 环节」组织：[`ListSemesters`] 查询周期，[`QueryActivities`] 查询周期中的
+//! 绩效评估项目，[`QueryReviewData`] 查询被评估人在各个环节中的评估内容和结果。
+//!
+//! ```no_run
+//! use lark_sdk::performance::{ListSemesters, QueryReviewData};
+//!
+//! # async fn run(client: lark_sdk::Client) -> lark_sdk::Result<()> {
+//! let semesters = client.send(&ListSemesters::year(2023)).await?.into_data()?;
+//! for semester in semesters.items {
+//!     let request = QueryReviewData::semester(&semester.semester_id, vec!["ou_xxx".to_string()]);
+//!     let data = client.send(&request).await?.into_data()?;
+//!     println!("{:?}", data.datas);
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+use serde::Deserialize;
+use serde_json::Value;
+
+use crate::enums::UserIdType;
+use crate::event::UserId;
+use crate::i18n::I18nText;
+use crate::time::{self, LarkTimestamp};
+
+/// 每次查询评估内容的最多被评估人数
+pub const REVIEWEES_LIMIT: usize = 50;
+
+/// 绩效周期
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct Semester {
+    pub semester_id: String,
+    #[serde(default)]
+    pub name: I18nText,
+    #[serde(default, with = "time::millis::option")]
+    pub start_time: Option<LarkTimestamp>,
+    #[serde(default, with = "time::millis::option")]
+    pub end_time: Option<LarkTimestamp>,
+    #[serde(default)]
+    pub year: Option<i32>,
+    /// 周期类型，如 `Annual`、`Semi-annual`、`Quarter`、`Bimonthly`、`Monthly`
+    #[serde(default)]
+    pub type_group: Option<String>,
+}
+
+/// [`ListSemesters`] 的结果
+#[derive(Debug, Clone, Deserialize, lark_sdk::Response)]
+pub struct Semesters {
+    #[serde(default)]
+    pub items: Vec<Semester>,
+}
+
+/// 查询绩效周期，所有条件都为空时返回全部周期
+#[derive(Debug, Clone, Default, lark_sdk::Request)]
+#[request(
+    method = "GET",
+    url = "/open-apis/performance/v1/semesters",
+    auth = "tenant",
+    response = Semesters
+)]
+pub struct ListSemesters {
+    /// 周期的开始时间不早于 `start_time`
+    #[request(query)]
+    pub start_time: Option<LarkTimestamp>,
+    /// 周期的结束时间不晚于 `end_time`
+    #[request(query)]
+    pub end_time: Option<LarkTimestamp>,
+    #[request(query)]
+    pub year: Option<i32>,
+    #[request(query)]
+    pub user_id_type: Option<UserIdType>,
+}
+
+impl ListSemesters {
+    /// `year` 年的周期
+    pub fn year(year: i32) -> Self {
+        ListSemesters {
+            year: Some(year),
+            ..ListSemesters::default()
+        }
+    }
+}
+
+/// 绩效评估项目
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct Activity {
+    pub activity_id: String,
+    #[serde(default)]
+    pub name: I18nText,
+    #[serde(default)]
+    pub description: I18nText,
+    pub semester_id: String,
+    /// 项目进度，如 `Initiating`、`Ongoing`、`Finished`
+    #[serde(default)]
+    pub progress: Option<String>,
+    /// 项目创建时间
+    #[serde(default, with = "time::millis::option")]
+    pub create_time: Option<LarkTimestamp>,
+}
+
+/// [`QueryActivities`] 的结果
+#[derive(Debug, Clone, Deserialize, lark_sdk::Response)]
+pub struct Activities {
+    #[serde(default)]
+    pub activities: Vec<Activity>,
+}
+
+/// 查询绩效评估项目，两个条件都为空时返回全部项目
+#[derive(Debug, Clone, Default, lark_sdk::Request)]
+#[request(
+    method = "POST",
+    url = "/open-apis/performance/v2/activity/query",
+    auth = "tenant",
+    response = Activities
+)]
+pub struct QueryActivities {
+    #[request(body)]
+    pub semester_ids: Vec<String>,
+    #[request(body)]
+    pub activity_ids: Vec<String>,
+}
+
+/// 被评估人在一个项目中的评估内容
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct RevieweeData {
+    /// 被评估人
+    pub user_id: UserId,
+    pub semester_id: String,
+    #[serde(default)]
+    pub activity_id: Option<String>,
+    /// 各个环节的评估内容，结构随环节的类型（如自评、上级评估、结果校准）不同
+    #[serde(default)]
+    pub stage_records: Vec<Value>,
+}
+
+/// [`QueryReviewData`] 的结果
+#[derive(Debug, Clone, Deserialize, lark_sdk::Response)]
+pub struct ReviewDatas {
+    #[serde(default)]
+    pub datas: Vec<RevieweeData>,
+}
+
+/// 查询被评估人在指定周期中的评估内容和结果
+#[derive(Debug, Clone, lark_sdk::Request)]
+#[request(
+    method = "POST",
+    url = "/open-apis/performance/v1/review_datas/query",
+    auth = "tenant",
+    response = ReviewDatas
+)]
+pub struct QueryReviewData {
+    /// `reviewee_user_id_list` 的类型，默认为 `open_id`
+    #[request(query)]
+    pub user_id_type: Option<UserIdType>,
+    #[request(body)]
+    pub semester_id_list: Vec<String>,
+    /// 被评估人，最多 [`REVIEWEES_LIMIT`] 个
+    #[request(body, max_len = REVIEWEES_LIMIT)]
+    pub reviewee_user_id_list: Vec<String>,
+    /// 只查询这些项目，为空时查询周期中的全部项目
+    #[request(body)]
+    pub activity_id_list: Option<Vec<String>>,
+    /// 只查询这些类型的环节，如 `leader_review`、`calibration`
+    #[request(body)]
+    pub stage_types: Option<Vec<String>>,
+}
+
+impl QueryReviewData {
+    /// 查询 `reviewees`（`open_id`）在周期 `semester_id` 中的评估内容
+    pub fn semester(semester_id: &str, reviewees: Vec<String>) -> Self {
+        QueryReviewData {
+            user_id_type: None,
+            semester_id_list: vec![semester_id.to_string()],
+            reviewee_user_id_list: reviewees,
+            activity_id_list: None,
+            stage_types: None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::i18n::ZH_CN;
+    use crate::test_util::serve_all;
+    use crate::Client;
+
+    #[tokio::test]
+    async fn semesters_and_review_data() {
+        let (address, handle) = serve_all(vec![
+            r#"{"code":0,"msg":"success","data":{"items":[{"semester_id":"s1","name":{"zh_cn":"2023 年上半年"},"start_time":"1672502400000","end_time":"1688140799000","year":2023,"type_group":"Semi-annual"}]}}"#,
+            r#"{"code":0,"msg":"success","data":{"datas":[{"user_id":{"open_id":"ou_1"},"semester_id":"s1","activity_id":"a1","stage_records":[{"stage_id":"st1","stage_type":"leader_review"}]}]}}"#,
+        ]);
+        let client = Client::builder().domain(address).build().unwrap();
+        let semesters = client
+            .send(&ListSemesters::year(2023))
+            .await
+            .unwrap()
+            .into_data()
+            .unwrap();
+        assert_eq!(semesters.items[0].name.get(ZH_CN), Some("2023 年上半年"));
+
+        let request = QueryReviewData {
+            stage_types: Some(vec!["leader_review".to_string()]),
+            ..QueryReviewData::semester("s1", vec!["ou_1".to_string()])
+        };
+        let data = client.send(&request).await.unwrap().into_data().unwrap();
+        assert_eq!(data.datas[0].user_id.open_id.as_deref(), Some("ou_1"));
+        assert_eq!(
+            data.datas[0].stage_records[0]["stage_type"],
+            "leader_review"
+        );
+
+        let requests = handle.join().unwrap();
+        assert!(requests[0].starts_with("GET /open-apis/performance/v1/semesters?year=2023 "));
+        let body: Value =
+            serde_json::from_str(requests[1].split("\r\n\r\n").nth(1).unwrap()).unwrap();
+        assert_eq!(
+            body,
+            serde_json::json!({
+                "semester_id_list": ["s1"],
+                "reviewee_user_id_list": ["ou_1"],
+                "stage_types": ["leader_review"]
+            })
+        );
+    }
+}
@@ -0,0 +1,193 @@
+//! 轮询异步任务。
+//!
+//! 导出、导入、批量操作等接口会先返回任务 ID（ticket），需要反复查询直到任务完成。
+
+use std::time::Duration;
+#[cfg(feature = "async")]
+use std::time::Instant;
+
+#[cfg(feature = "async")]
+use crate::{Body, BodyResponse, Client, LarkError, Request, Result};
+
+/// 两次查询之间的等待时间，每次等待后乘以 `factor`，最多为 `max`。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Backoff {
+    pub initial: Duration,
+    pub max: Duration,
+    pub factor: u32,
+}
+
+impl Backoff {
+    /// 固定的等待时间
+    pub fn fixed(delay: Duration) -> Self {
+        Backoff {
+            initial: delay,
+            max: delay,
+            factor: 1,
+        }
+    }
+
+    /// 从 `initial` 开始每次翻倍，最多为 `max`
+    pub fn exponential(initial: Duration, max: Duration) -> Self {
+        Backoff {
+            initial,
+            max,
+            factor: 2,
+        }
+    }
+
+    /// 第 `attempt` 次（从 0 开始）查询之后的等待时间
+    pub fn delay(&self, attempt: u32) -> Duration {
+        let factor = self.factor.checked_pow(attempt).unwrap_or(u32::MAX);
+        self.initial
+            .checked_mul(factor)
+            .map_or(self.max, |delay| delay.min(self.max))
+    }
+}
+
+impl Default for Backoff {
+    /// 从 500 毫秒开始翻倍，最多 10 秒
+    fn default() -> Self {
+        Backoff::exponential(Duration::from_millis(500), Duration::from_secs(10))
+    }
+}
+
+/// 计算下一次查询前的等待时间，超过 `deadline` 时返回 [`LarkError::Timeout`]。
+#[cfg(feature = "async")]
+pub(crate) fn next_delay(backoff: &Backoff, attempt: u32, deadline: Instant) -> Result<Duration> {
+    let delay = backoff.delay(attempt);
+    if Instant::now() + delay > deadline {
+        return Err(LarkError::Timeout);
+    }
+    Ok(delay)
+}
+
+/// 使用 `make_request` 生成的请求反复查询，直到 `is_done` 返回 `true`，返回最后一次查询的数据。
+///
+/// 接口返回失败时立即返回错误，超过 `timeout` 仍未完成时返回 [`LarkError::Timeout`]。
+///
+/// ```no_run
+/// # use std::time::Duration;
+/// # use lark_sdk::poll::{poll_until_done, Backoff};
+/// # use lark_sdk::{Client, Request, Response};
+/// #[derive(serde::Deserialize, Response)]
+/// pub struct ExportTask {
+///     pub job_status: i32,
+///     pub file_token: Option<String>,
+/// }
+///
+/// #[derive(Request)]
+/// #[request(method = "GET", url = "/open-apis/drive/v1/export_tasks/:ticket", response = ExportTask)]
+/// pub struct GetExportTask {
+///     #[request(path)]
+///     pub ticket: String,
+/// }
+///
+/// # async fn run(client: Client, ticket: String) -> lark_sdk::Result<()> {
+/// let task = poll_until_done(
+///     &client,
+///     || GetExportTask { ticket: ticket.clone() },
+///     |task: &ExportTask| task.job_status == 0,
+///     Backoff::default(),
+///     Duration::from_secs(60),
+/// )
+/// .await?;
+/// # Ok(())
+/// # }
+/// ```
+#[cfg(feature = "async")]
+pub async fn poll_until_done<R, T, F, D>(
+    client: &Client,
+    mut make_request: F,
+    mut is_done: D,
+    backoff: Backoff,
+    timeout: Duration,
+) -> Result<T>
+where
+    R: Request<Target = BodyResponse<T>>,
+    T: Body,
+    F: FnMut() -> R,
+    D: FnMut(&T) -> bool,
+{
+    let deadline = Instant::now() + timeout;
+    let mut attempt = 0;
+    loop {
+        let data = client.send(&make_request()).await?.into_data()?;
+        if is_done(&data) {
+            return Ok(data);
+        }
+        tokio::time::sleep(next_delay(&backoff, attempt, deadline)?).await;
+        attempt += 1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[cfg(feature = "async")]
+    use crate::test_util::serve_all;
+
+    #[cfg(feature = "async")]
+    #[derive(Debug, serde::Deserialize, lark_sdk::Body)]
+    struct Task {
+        status: String,
+    }
+
+    #[cfg(feature = "async")]
+    #[derive(lark_sdk::Request)]
+    #[request(method = "GET", url = "/open-apis/demo/tasks/:ticket", response = Task)]
+    struct GetTask {
+        #[request(path)]
+        ticket: String,
+    }
+
+    #[test]
+    fn backoff() {
+        let backoff = Backoff::exponential(Duration::from_millis(100), Duration::from_secs(1));
+        assert_eq!(backoff.delay(0), Duration::from_millis(100));
+        assert_eq!(backoff.delay(3), Duration::from_millis(800));
+        assert_eq!(backoff.delay(4), Duration::from_secs(1));
+        assert_eq!(backoff.delay(100), Duration::from_secs(1));
+        let backoff = Backoff::fixed(Duration::from_millis(10));
+        assert_eq!(backoff.delay(5), Duration::from_millis(10));
+    }
+
+    #[cfg(feature = "async")]
+    #[tokio::test]
+    async fn poll() {
+        let (address, server) = serve_all(vec![
+            r#"{"code":0,"msg":"ok","data":{"status":"running"}}"#,
+            r#"{"code":0,"msg":"ok","data":{"status":"running"}}"#,
+            r#"{"code":0,"msg":"ok","data":{"status":"done"}}"#,
+        ]);
+        let client = Client::builder().domain(address).build().unwrap();
+        let task = poll_until_done(
+            &client,
+            || GetTask {
+                ticket: "t1".to_string(),
+            },
+            |task: &Task| task.status == "done",
+            Backoff::fixed(Duration::from_millis(1)),
+            Duration::from_secs(5),
+        )
+        .await
+        .unwrap();
+        assert_eq!(task.status, "done");
+        assert_eq!(server.join().unwrap().len(), 3);
+
+        let (address, _) = serve_all(vec![r#"{"code":0,"msg":"ok","data":{"status":"running"}}"#]);
+        let client = Client::builder().domain(address).build().unwrap();
+        let err = poll_until_done(
+            &client,
+            || GetTask {
+                ticket: "t1".to_string(),
+            },
+            |task: &Task| task.status == "done",
+            Backoff::fixed(Duration::from_secs(10)),
+            Duration::from_secs(1),
+        )
+        .await
+        .unwrap_err();
+        assert!(matches!(err, LarkError::Timeout));
+    }
+}
@@ -0,0 +1,124 @@
+//! 请求频率限制。
+//!
+//! 飞书开放平台对应用整体和不同业务的接口（如消息、通讯录、云文档）分别有频率限制，
+//! [`Quota`] 在客户端发送请求前等待，使请求频率不超过限制。
+
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// 应用整体和按接口路径前缀分组的频率限制，配置到客户端后由客户端的所有克隆共享。
+///
+/// ```
+/// use lark_sdk::quota::Quota;
+///
+/// let quota = Quota::from_table(&[
+///     ("*", 100),
+///     ("/open-apis/im/", 50),
+///     ("/open-apis/contact/", 20),
+/// ]);
+/// # #[cfg(feature = "async")]
+/// let client = lark_sdk::Client::builder().quota(quota).build();
+/// ```
+#[derive(Debug, Default)]
+pub struct Quota {
+    global: Option<Limiter>,
+    groups: Vec<(String, Limiter)>,
+}
+
+impl Quota {
+    pub fn new() -> Self {
+        Quota::default()
+    }
+
+    /// 使用 `(路径前缀, 每秒请求数)` 的列表创建，路径前缀为 `*` 时表示应用整体的限制
+    pub fn from_table(table: &[(&str, u32)]) -> Self {
+        table.iter().fold(Quota::new(), |quota, (prefix, qps)| {
+            if *prefix == "*" {
+                quota.global(*qps)
+            } else {
+                quota.group(*prefix, *qps)
+            }
+        })
+    }
+
+    /// 应用整体每秒最多的请求数
+    pub fn global(mut self, qps: u32) -> Self {
+        self.global = Some(Limiter::new(qps));
+        self
+    }
+
+    /// 路径以 `prefix` 开头的接口每秒最多的请求数，多个前缀匹配时使用最长的前缀
+    pub fn group(mut self, prefix: impl Into<String>, qps: u32) -> Self {
+        self.groups.push((prefix.into(), Limiter::new(qps)));
+        self
+    }
+
+    /// 为路径为 `url` 的请求预留配额，返回发送前需要等待的时间
+    pub fn acquire(&self, url: &str) -> Duration {
+        let now = Instant::now();
+        let group = self
+            .groups
+            .iter()
+            .filter(|(prefix, _)| url.starts_with(prefix.as_str()))
+            .max_by_key(|(prefix, _)| prefix.len())
+            .map(|(_, limiter)| limiter);
+        self.global
+            .iter()
+            .chain(group)
+            .map(|limiter| limiter.acquire(now))
+            .max()
+            .unwrap_or_default()
+    }
+}
+
+/// 按固定间隔放行请求
+#[derive(Debug)]
+struct Limiter {
+    interval: Duration,
+    next: Mutex<Option<Instant>>,
+}
+
+impl Limiter {
+    fn new(qps: u32) -> Self {
+        Limiter {
+            interval: Duration::from_secs(1) / qps.max(1),
+            next: Mutex::new(None),
+        }
+    }
+
+    fn acquire(&self, now: Instant) -> Duration {
+        let mut next = self.next.lock().unwrap_or_else(|err| err.into_inner());
+        let at = next.map_or(now, |next| next.max(now));
+        *next = Some(at + self.interval);
+        at - now
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn acquire() {
+        let quota = Quota::from_table(&[
+            ("*", 100),
+            ("/open-apis/im/", 10),
+            ("/open-apis/im/v1/chats", 1000),
+        ]);
+        assert_eq!(quota.acquire("/open-apis/im/v1/messages"), Duration::ZERO);
+        let wait = quota.acquire("/open-apis/im/v1/messages");
+        assert!(wait > Duration::from_millis(90) && wait <= Duration::from_millis(100));
+
+        // 最长前缀的分组单独计算，但仍受应用整体的限制
+        let wait = quota.acquire("/open-apis/im/v1/chats");
+        assert!(wait > Duration::from_millis(10) && wait <= Duration::from_millis(20));
+
+        let wait = quota.acquire("/open-apis/contact/v3/users");
+        assert!(wait > Duration::from_millis(20) && wait <= Duration::from_millis(30));
+
+        assert_eq!(
+            Quota::new().acquire("/open-apis/im/v1/messages"),
+            Duration::ZERO
+        );
+    }
+}
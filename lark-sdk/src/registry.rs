@@ -0,0 +1,241 @@
+//! 在同一个进程中管理多个应用。
+//!
+//! 托管多个机器人的平台通常需要同时以多个应用的身份调用接口，[`ClientRegistry`] 按照 App ID
+//! 管理每个应用的客户端：所有客户端共享同一个连接池和客户端配置，应用凭证和频率限制则相互独立。
+//!
+//! 商店应用（ISV）需要以安装了应用的各个租户的身份调用接口，[`ClientRegistry::tenant`] 返回使用该租户
+//! `tenant_access_token` 的客户端，不需要在每次调用时传递 `tenant_key`。
+
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::{Arc, RwLock};
+
+use crate::auth::TokenManager;
+use crate::quota::Quota;
+use crate::Client;
+
+/// 注册到 [`ClientRegistry`] 的应用
+pub struct AppConfig {
+    app_id: String,
+    app_secret: String,
+    marketplace: bool,
+    quota: Option<Arc<Quota>>,
+}
+
+impl AppConfig {
+    pub fn new(app_id: impl Into<String>, app_secret: impl Into<String>) -> Self {
+        AppConfig {
+            app_id: app_id.into(),
+            app_secret: app_secret.into(),
+            marketplace: false,
+            quota: None,
+        }
+    }
+
+    /// 商店应用，使用平台推送的 `app_ticket` 获取凭证，见 [`ClientRegistry::set_app_ticket`]
+    pub fn marketplace(mut self) -> Self {
+        self.marketplace = true;
+        self
+    }
+
+    /// 应用的请求频率限制，见 [`Quota`]
+    pub fn quota(mut self, quota: impl Into<Arc<Quota>>) -> Self {
+        self.quota = Some(quota.into());
+        self
+    }
+}
+
+impl fmt::Debug for AppConfig {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("AppConfig")
+            .field("app_id", &self.app_id)
+            .field("marketplace", &self.marketplace)
+            .field("quota", &self.quota)
+            .finish_non_exhaustive()
+    }
+}
+
+/// 按照 App ID 管理多个应用的客户端。
+///
+/// 飞书开放平台的频率限制以应用为单位，因此 `base` 客户端上配置的 [`Quota`] 不会被注册的应用继承，
+/// 需要时通过 [`AppConfig::quota`] 为每个应用单独配置。
+///
+/// ```no_run
+/// use lark_sdk::quota::Quota;
+/// use lark_sdk::registry::{AppConfig, ClientRegistry};
+///
+/// # fn run() {
+/// let registry = ClientRegistry::new(lark_sdk::Client::new());
+/// registry.register(AppConfig::new("cli_a", "secret_a").quota(Quota::new().global(50)));
+/// registry.register(AppConfig::new("cli_b", "secret_b"));
+///
+/// let client = registry.get("cli_a").unwrap();
+/// # }
+/// ```
+///
+/// 商店应用需要把平台推送的 `app_ticket` 交给注册表：
+///
+/// ```no_run
+/// use std::sync::Arc;
+/// use lark_sdk::event::application::AppTicketEvent;
+/// use lark_sdk::event::{Event, EventDispatcher};
+/// use lark_sdk::registry::{AppConfig, ClientRegistry};
+///
+/// let registry = Arc::new(ClientRegistry::new(lark_sdk::Client::new()));
+/// registry.register(AppConfig::new("cli_isv", "secret").marketplace());
+///
+/// let tickets = registry.clone();
+/// let dispatcher = EventDispatcher::new().on(move |event: Event<AppTicketEvent>| {
+///     tickets.set_app_ticket(&event.event.app_id, event.event.app_ticket);
+///     async { Ok(()) }
+/// });
+///
+/// let client = registry.tenant("cli_isv", "2ed263bf32cf1651").unwrap();
+/// ```
+#[derive(Debug)]
+pub struct ClientRegistry {
+    base: Client,
+    clients: RwLock<HashMap<String, Client>>,
+}
+
+impl ClientRegistry {
+    /// 使用 `base` 的连接池和配置创建注册表
+    pub fn new(base: Client) -> Self {
+        ClientRegistry {
+            base,
+            clients: RwLock::new(HashMap::new()),
+        }
+    }
+
+    /// 注册应用并返回它的客户端，App ID 已经注册时替换原来的客户端，已缓存的凭证会被丢弃
+    pub fn register(&self, app: AppConfig) -> Client {
+        let tokens = match app.marketplace {
+            true => TokenManager::marketplace(app.app_id.clone(), app.app_secret),
+            false => TokenManager::new(app.app_id.clone(), app.app_secret),
+        };
+        let client = self.base.with_app(Arc::new(tokens), app.quota);
+        self.clients
+            .write()
+            .unwrap()
+            .insert(app.app_id, client.clone());
+        client
+    }
+
+    /// 应用的客户端
+    pub fn get(&self, app_id: &str) -> Option<Client> {
+        self.clients.read().unwrap().get(app_id).cloned()
+    }
+
+    /// 商店应用在 `tenant_key` 租户下的客户端，见 [`Client::tenant`]
+    pub fn tenant(&self, app_id: &str, tenant_key: impl Into<String>) -> Option<Client> {
+        self.get(app_id).map(|client| client.tenant(tenant_key))
+    }
+
+    /// 更新商店应用的 `app_ticket`，通常在收到 [`AppTicketEvent`] 时调用，应用没有注册时返回 `false`
+    ///
+    /// [`AppTicketEvent`]: crate::event::application::AppTicketEvent
+    pub fn set_app_ticket(&self, app_id: &str, app_ticket: impl Into<String>) -> bool {
+        let clients = self.clients.read().unwrap();
+        match clients.get(app_id).and_then(Client::token_manager) {
+            Some(tokens) => {
+                tokens.set_app_ticket(app_ticket.into());
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// 移除应用，返回它的客户端
+    pub fn remove(&self, app_id: &str) -> Option<Client> {
+        self.clients.write().unwrap().remove(app_id)
+    }
+
+    /// 所有已注册应用的 App ID
+    pub fn app_ids(&self) -> Vec<String> {
+        self.clients.read().unwrap().keys().cloned().collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::serve_all;
+    use crate::LarkError;
+
+    #[derive(lark_sdk::Request)]
+    #[request(method = "GET", url = "/open-apis/im/v1/chats", auth = "tenant", response = serde_json::Value)]
+    struct ListChats {}
+
+    #[tokio::test]
+    async fn separate_tokens() {
+        let (address, handle) = serve_all(vec![
+            r#"{"code":0,"msg":"ok","tenant_access_token":"t-a","expire":7200}"#,
+            r#"{"code":0,"msg":"ok","data":{}}"#,
+            r#"{"code":0,"msg":"ok","tenant_access_token":"t-b","expire":7200}"#,
+            r#"{"code":0,"msg":"ok","data":{}}"#,
+        ]);
+        let registry = ClientRegistry::new(Client::builder().domain(address).build().unwrap());
+        registry.register(AppConfig::new("cli_a", "secret_a"));
+        registry.register(AppConfig::new("cli_b", "secret_b"));
+
+        let client_a = registry.get("cli_a").unwrap();
+        assert_eq!(client_a.app_id(), Some("cli_a"));
+        client_a.send(&ListChats {}).await.unwrap();
+        registry
+            .get("cli_b")
+            .unwrap()
+            .send(&ListChats {})
+            .await
+            .unwrap();
+
+        let requests = handle.join().unwrap();
+        assert!(requests[0].contains(r#""app_id":"cli_a""#));
+        assert!(requests[1].contains("authorization: Bearer t-a\r\n"));
+        assert!(requests[2].contains(r#""app_id":"cli_b""#));
+        assert!(requests[3].contains("authorization: Bearer t-b\r\n"));
+
+        assert!(registry.remove("cli_a").is_some());
+        assert_eq!(registry.app_ids(), vec!["cli_b".to_string()]);
+    }
+
+    #[tokio::test]
+    async fn marketplace_tenant() {
+        let (address, handle) = serve_all(vec![
+            r#"{"code":0,"msg":"ok","app_access_token":"a-1","expire":7200}"#,
+            r#"{"code":0,"msg":"ok","tenant_access_token":"t-1","expire":7200}"#,
+            r#"{"code":0,"msg":"ok","data":{}}"#,
+            r#"{"code":0,"msg":"ok","tenant_access_token":"t-2","expire":7200}"#,
+            r#"{"code":0,"msg":"ok","data":{}}"#,
+        ]);
+        let registry = ClientRegistry::new(Client::builder().domain(address).build().unwrap());
+        registry.register(AppConfig::new("cli_isv", "secret").marketplace());
+
+        let client = registry.get("cli_isv").unwrap();
+        let result = client.send(&ListChats {}).await;
+        assert!(matches!(result, Err(LarkError::InvalidRequest(_))));
+        let result = client.tenant("t1").send(&ListChats {}).await;
+        assert!(matches!(result, Err(LarkError::InvalidRequest(_))));
+
+        assert!(registry.set_app_ticket("cli_isv", "ticket-1"));
+        assert!(!registry.set_app_ticket("cli_unknown", "ticket-1"));
+        let tenant = registry.tenant("cli_isv", "t1").unwrap();
+        assert_eq!(tenant.tenant_key(), Some("t1"));
+        tenant.send(&ListChats {}).await.unwrap();
+        registry
+            .tenant("cli_isv", "t2")
+            .unwrap()
+            .send(&ListChats {})
+            .await
+            .unwrap();
+
+        let requests = handle.join().unwrap();
+        assert!(requests[0].starts_with("POST /open-apis/auth/v3/app_access_token "));
+        assert!(requests[0].contains(r#""app_ticket":"ticket-1""#));
+        assert!(requests[1].starts_with("POST /open-apis/auth/v3/tenant_access_token "));
+        assert!(requests[1].contains(r#""app_access_token":"a-1""#));
+        assert!(requests[1].contains(r#""tenant_key":"t1""#));
+        assert!(requests[2].contains("authorization: Bearer t-1\r\n"));
+        assert!(requests[3].contains(r#""tenant_key":"t2""#));
+        assert!(requests[4].contains("authorization: Bearer t-2\r\n"));
+    }
+}
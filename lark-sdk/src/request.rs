@@ -0,0 +1,837 @@
+use std::borrow::Cow;
+use std::collections::{BTreeSet, HashSet};
+use std::fmt;
+
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+
+use crate::{json, LarkError, Response, Result};
+
+/// HTTP 请求方法
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum Method {
+    Get,
+    Post,
+    Put,
+    Patch,
+    Delete,
+}
+
+impl Method {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Method::Get => "GET",
+            Method::Post => "POST",
+            Method::Put => "PUT",
+            Method::Patch => "PATCH",
+            Method::Delete => "DELETE",
+        }
+    }
+}
+
+impl fmt::Display for Method {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// 请求使用的访问凭证，客户端据此自动添加 `Authorization` 请求头，见
+/// [`ClientBuilder::app_credentials`](crate::ClientBuilder::app_credentials)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AuthType {
+    /// 不添加访问凭证，由请求自己的请求头决定
+    #[default]
+    None,
+    /// `tenant_access_token`
+    Tenant,
+    /// `user_access_token`，需要通过 [`UserAuth`](crate::auth::UserAuth) 发送
+    User,
+    /// `app_access_token`
+    App,
+}
+
+/// 飞书开放平台的一个接口请求，通常使用 `#[derive(Request)]` 生成。
+///
+/// ```
+/// use lark_sdk::{Request, Response};
+/// use serde::Deserialize;
+///
+/// #[derive(Deserialize, Response)]
+/// pub struct Message {
+///     pub message_id: String,
+/// }
+///
+/// #[derive(Request)]
+/// #[request(method = "POST", url = "/open-apis/im/v1/messages", response = Message)]
+/// pub struct SendMessage {
+///     #[request(query)]
+///     pub receive_id_type: String,
+///     #[request(body)]
+///     pub receive_id: String,
+///     #[request(body)]
+///     pub msg_type: String,
+///     #[request(body)]
+///     pub content: String,
+/// }
+/// ```
+///
+/// 没有返回数据的接口可以使用 `#[request(empty)]` 代替 `response = Type`，响应类型为 [`Empty`](crate::Empty)。
+///
+/// 请求体不是 JSON 的接口（如上传文件）可以使用 `content_type = "multipart/form-data"` 指定请求的
+/// `Content-Type`。
+///
+/// 使用 `#[request(builder)]` 会额外生成 `XxxBuilder`，通过 `Xxx::builder().with_field(value).build()`
+/// 构造请求，`Option` 字段可以不设置，其他字段缺失或路径参数为空时 `build()` 返回错误。
+///
+/// 如果请求方法需要根据请求内容决定，可以使用 `method_fn = "Self::pick_method"` 代替 `method`，
+/// 该函数的签名为 `fn(&Self) -> Method`。
+///
+/// 字段属性：
+///
+/// - `path`、`query`、`header`、`body`：字段所在的位置，可以用 `query = "name"` 的形式重命名；
+/// - `query_struct`：使用 serde 将字段（如公共的分页、过滤参数结构体）展开为多个 query 参数，
+///   该结构体只能包含简单类型的字段；
+/// - `serialize_with = "path"`：使用 `fn(&T) -> Option<String>` 序列化字段；
+/// - `format = "%Y-%m-%d"`：使用字段类型的 `format` 方法（如 chrono 的时间类型）格式化字段；
+/// - `style = "csv"` 或 `style = "repeat"`：列表类型 query 参数的格式，默认 `csv` 即 `key=a,b`，
+///   `repeat` 为 `key=a&key=b`；
+/// - `idempotency_key`：幂等键字段（如发送消息的 `uuid`），见 [`Request::set_idempotency_key`]；
+/// - `page_token`：分页列表接口的 `page_token` 字段，见 [`Request::set_page_token`]。
+///
+/// 非 `GET`、`PUT`、`DELETE` 的幂等接口可以使用 `#[request(idempotent)]` 标记，见
+/// [`Request::is_idempotent`]。
+///
+/// 使用 `#[request(auth = "tenant")]` 声明接口需要的访问凭证，客户端配置了应用凭证时会自动添加，
+/// 见 [`Request::auth_type`]。
+///
+/// 使用 `#[request(validate)]` 生成 [`Request::validate`]，检查路径参数不为空；字段上的
+/// `max_len = N` 检查字符串的字符数或列表的元素个数不超过 `N`（可以是常量）；
+/// `validate = "Self::check"` 在上述检查之后再调用 `fn(&Self) -> Result<()>` 做其他检查。
+pub trait Request {
+    /// 响应类型
+    type Target: Response + DeserializeOwned;
+
+    fn method(&self) -> Method;
+
+    /// 替换了路径参数之后的请求路径，如 `/open-apis/im/v1/messages`。少数接口不在开放平台的域名下，
+    /// 可以返回完整的地址
+    fn url(&self) -> String;
+
+    /// 替换路径参数之前的请求路径，如 `/open-apis/im/v1/messages/:message_id`，用于按接口统计，
+    /// 派生时自动生成，同时生成关联常量 `ENDPOINT`，见 [`endpoints`](crate::endpoints)
+    fn endpoint(&self) -> Option<&'static str> {
+        None
+    }
+
+    /// query 参数，`query_struct` 字段无法表示为 query 参数时返回 [`LarkError::InvalidRequest`]
+    fn query(&self) -> Result<Vec<(String, String)>> {
+        Ok(Vec::new())
+    }
+
+    fn headers(&self) -> Vec<(String, String)> {
+        Vec::new()
+    }
+
+    /// 请求的 `Content-Type`，部分接口对字符集有要求，上传接口需要使用 multipart 类型
+    fn content_type(&self) -> &str {
+        DEFAULT_CONTENT_TYPE
+    }
+
+    /// 请求体，格式由 [`content_type`](Request::content_type) 决定，默认为 JSON
+    fn body(&self) -> Result<Option<Vec<u8>>> {
+        Ok(None)
+    }
+
+    /// 请求使用的访问凭证，派生时使用 `#[request(auth = "tenant")]` 指定，可选 `tenant`、`user`、
+    /// `app`、`none`
+    fn auth_type(&self) -> AuthType {
+        AuthType::None
+    }
+
+    /// 请求是否幂等，幂等的请求可以安全地重试。默认 `GET`、`PUT`、`DELETE` 请求是幂等的，
+    /// 派生时可以使用 `#[request(idempotent)]` 标记
+    fn is_idempotent(&self) -> bool {
+        matches!(self.method(), Method::Get | Method::Put | Method::Delete)
+    }
+
+    /// 设置幂等键（如发送消息的 `uuid`），已经设置过时保持不变，接口不支持幂等键时返回 `false`。
+    ///
+    /// 派生时使用 `#[request(body, idempotency_key)]` 标记幂等键字段，字段类型为 `String` 或
+    /// `Option<String>`
+    fn set_idempotency_key(&mut self, key: &str) -> bool {
+        let _ = key;
+        false
+    }
+
+    /// 设置下一页的 `page_token`，`None` 表示第一页，接口不支持分页时返回 `false`，见
+    /// [`page`](crate::page)。
+    ///
+    /// 派生时使用 `#[request(query, page_token)]` 标记该字段，字段类型为 `String` 或
+    /// `Option<String>`
+    fn set_page_token(&mut self, page_token: Option<String>) -> bool {
+        let _ = page_token;
+        false
+    }
+
+    /// 发送之前检查请求内容，明显无效的请求（如路径参数为空、超过接口的长度限制）在本地返回
+    /// [`LarkError::InvalidRequest`](crate::LarkError::InvalidRequest)，不再发送到服务端。
+    /// [`to_parts`](Request::to_parts) 会先调用该方法
+    fn validate(&self) -> Result<()> {
+        Ok(())
+    }
+
+    /// 生成可以序列化的请求内容，用于持久化到队列中，之后再由 `Client::send_parts` 发送
+    fn to_parts(&self) -> Result<RequestParts> {
+        RequestParts::new(self)
+    }
+
+    /// 生成等价的 curl 命令，访问凭证等敏感信息会被隐藏，见 [`RequestParts::to_curl`]。
+    ///
+    /// 使用飞书的接口地址，请求不带 `Authorization`，需要自行补充访问凭证
+    #[cfg(feature = "curl")]
+    fn to_curl(&self) -> String {
+        match self.to_parts() {
+            Ok(parts) => parts.to_curl(crate::FEISHU_DOMAIN),
+            Err(err) => format!("# {}", err),
+        }
+    }
+}
+
+/// 请求默认的 `Content-Type`
+pub const DEFAULT_CONTENT_TYPE: &str = "application/json; charset=utf-8";
+
+/// 请求的各个组成部分，由 [`Request::to_parts`] 生成，客户端据此发送请求。
+///
+/// 可以使用 serde 序列化后保存到持久化队列（如 Kafka、SQS）中，由其他进程重放。
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RequestParts {
+    pub method: Method,
+    /// 替换了路径参数之后的请求路径
+    pub url: String,
+    pub query: Vec<(String, String)>,
+    pub headers: Vec<(String, String)>,
+    pub content_type: String,
+    pub body: Option<Vec<u8>>,
+    /// 见 [`Request::endpoint`]
+    #[serde(default)]
+    pub endpoint: Option<String>,
+    /// 见 [`Request::auth_type`]
+    #[serde(default)]
+    pub auth: AuthType,
+}
+
+impl RequestParts {
+    pub fn new<R: Request + ?Sized>(request: &R) -> Result<Self> {
+        request.validate()?;
+        Ok(RequestParts {
+            method: request.method(),
+            url: request.url(),
+            query: request.query()?,
+            headers: request.headers(),
+            content_type: request.content_type().to_string(),
+            body: request.body()?,
+            endpoint: request.endpoint().map(String::from),
+            auth: request.auth_type(),
+        })
+    }
+}
+
+/// 擦除了响应类型的 [`Request`]，所有 `Send + Sync` 的请求都实现了该 trait。
+///
+/// `Request::Target` 使得不同接口的请求无法放在同一个集合中，调度、重放队列可以使用
+/// [`BoxedRequest`] 保存不同类型的请求，再通过 `Client::send_dyn` 发送。
+///
+/// ```
+/// use lark_sdk::{BoxedRequest, Request};
+///
+/// #[derive(Request)]
+/// #[request(method = "DELETE", url = "/open-apis/im/v1/messages/:message_id", empty)]
+/// pub struct DeleteMessage {
+///     #[request(path)]
+///     pub message_id: String,
+/// }
+///
+/// #[derive(Request)]
+/// #[request(method = "GET", url = "/open-apis/im/v1/chats/:chat_id", response = serde_json::Value)]
+/// pub struct GetChat {
+///     #[request(path)]
+///     pub chat_id: String,
+/// }
+///
+/// let queue: Vec<BoxedRequest> = vec![
+///     Box::new(DeleteMessage { message_id: "om_1".to_string() }),
+///     Box::new(GetChat { chat_id: "oc_1".to_string() }),
+/// ];
+/// assert_eq!(queue[1].parts().unwrap().url, "/open-apis/im/v1/chats/oc_1");
+/// ```
+pub trait DynRequest: Send + Sync {
+    fn parts(&self) -> Result<RequestParts>;
+
+    /// 使用请求的响应类型解析响应并检查状态码，成功时返回完整的响应内容。
+    fn parse_response(&self, bytes: &[u8]) -> Result<serde_json::Value>;
+}
+
+impl<R: Request + Send + Sync + ?Sized> DynRequest for R {
+    fn parts(&self) -> Result<RequestParts> {
+        self.to_parts()
+    }
+
+    fn parse_response(&self, bytes: &[u8]) -> Result<serde_json::Value> {
+        let response: R::Target = json::from_slice(bytes)?;
+        if !response.is_success() {
+            return Err(LarkError::Api {
+                code: response.code(),
+                msg: response.msg().to_string(),
+                data: response.error_data().cloned(),
+                log_id: response.log_id().map(String::from),
+            });
+        }
+        json::from_slice(bytes)
+    }
+}
+
+/// 可以保存在集合中的任意请求
+pub type BoxedRequest = Box<dyn DynRequest>;
+
+/// `#[request(max_len = N)]` 检查的长度，字符串为字符数，列表为元素个数，`None` 时不检查
+pub trait ValidateLen {
+    fn validate_len(&self) -> Option<usize>;
+}
+
+impl ValidateLen for str {
+    fn validate_len(&self) -> Option<usize> {
+        Some(self.chars().count())
+    }
+}
+
+impl ValidateLen for String {
+    fn validate_len(&self) -> Option<usize> {
+        self.as_str().validate_len()
+    }
+}
+
+impl<T> ValidateLen for [T] {
+    fn validate_len(&self) -> Option<usize> {
+        Some(self.len())
+    }
+}
+
+impl<T> ValidateLen for Vec<T> {
+    fn validate_len(&self) -> Option<usize> {
+        Some(self.len())
+    }
+}
+
+impl<T: ValidateLen + ?Sized> ValidateLen for &T {
+    fn validate_len(&self) -> Option<usize> {
+        (**self).validate_len()
+    }
+}
+
+impl<T: ValidateLen> ValidateLen for Option<T> {
+    fn validate_len(&self) -> Option<usize> {
+        self.as_ref().and_then(ValidateLen::validate_len)
+    }
+}
+
+/// 序列化 path、query、header 参数的值，返回 `None` 时忽略该参数。
+///
+/// 无字段的枚举可以使用 `#[derive(RequestSerialize)]`，参数值与 serde 的重命名规则一致：
+///
+/// ```
+/// use lark_sdk::RequestSerialize;
+/// use serde::{Deserialize, Serialize};
+///
+/// #[derive(Serialize, RequestSerialize)]
+/// #[serde(rename_all = "snake_case")]
+/// pub enum UserIdType {
+///     OpenId,
+///     UnionId,
+///     UserId,
+/// }
+///
+/// assert_eq!(UserIdType::OpenId.to_param(), Some("open_id".to_string()));
+/// ```
+pub trait RequestSerialize {
+    fn to_param(&self) -> Option<String>;
+
+    /// 序列化为多个值，用于 `style = "repeat"` 的 query 参数。
+    fn to_params(&self) -> Vec<String> {
+        self.to_param().into_iter().collect()
+    }
+}
+
+macro_rules! impl_request_serialize {
+    ($($ty:ty),* $(,)?) => {
+        $(
+            impl RequestSerialize for $ty {
+                fn to_param(&self) -> Option<String> {
+                    Some(self.to_string())
+                }
+            }
+        )*
+    };
+}
+
+impl_request_serialize!(
+    str, String, bool, char, i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize, f32,
+    f64
+);
+
+impl<T: RequestSerialize + ?Sized> RequestSerialize for &T {
+    fn to_param(&self) -> Option<String> {
+        (**self).to_param()
+    }
+
+    fn to_params(&self) -> Vec<String> {
+        (**self).to_params()
+    }
+}
+
+impl<T: RequestSerialize> RequestSerialize for Option<T> {
+    fn to_param(&self) -> Option<String> {
+        self.as_ref().and_then(RequestSerialize::to_param)
+    }
+
+    fn to_params(&self) -> Vec<String> {
+        self.as_ref()
+            .map(RequestSerialize::to_params)
+            .unwrap_or_default()
+    }
+}
+
+impl<B: RequestSerialize + ToOwned + ?Sized> RequestSerialize for Cow<'_, B> {
+    fn to_param(&self) -> Option<String> {
+        (**self).to_param()
+    }
+
+    fn to_params(&self) -> Vec<String> {
+        (**self).to_params()
+    }
+}
+
+/// 列表类型的参数使用逗号连接各个元素，空列表时忽略该参数。
+macro_rules! impl_request_serialize_list {
+    ($([$($generics:tt)*] $ty:ty),* $(,)?) => {
+        $(
+            impl<$($generics)*> RequestSerialize for $ty {
+                fn to_param(&self) -> Option<String> {
+                    join_params(self.iter())
+                }
+
+                fn to_params(&self) -> Vec<String> {
+                    self.iter().filter_map(RequestSerialize::to_param).collect()
+                }
+            }
+        )*
+    };
+}
+
+impl_request_serialize_list!(
+    [T: RequestSerialize] [T],
+    [T: RequestSerialize, const N: usize] [T; N],
+    [T: RequestSerialize] Vec<T>,
+    [T: RequestSerialize, S] HashSet<T, S>,
+    [T: RequestSerialize] BTreeSet<T>,
+);
+
+fn join_params<'a, T: RequestSerialize + 'a>(
+    values: impl Iterator<Item = &'a T>,
+) -> Option<String> {
+    let values: Vec<String> = values.filter_map(RequestSerialize::to_param).collect();
+    if values.is_empty() {
+        None
+    } else {
+        Some(values.join(","))
+    }
+}
+
+/// 序列化为 RFC3339 格式，如 `2023-01-02T03:04:05Z`，需要 unix 时间戳时请使用
+/// [`param::unix_seconds`](crate::param::unix_seconds)。
+#[cfg(feature = "chrono")]
+impl<Tz: chrono::TimeZone> RequestSerialize for chrono::DateTime<Tz>
+where
+    Tz::Offset: fmt::Display,
+{
+    fn to_param(&self) -> Option<String> {
+        Some(self.to_rfc3339_opts(chrono::SecondsFormat::AutoSi, true))
+    }
+}
+
+/// 序列化为 `2023-01-02` 格式。
+#[cfg(feature = "chrono")]
+impl RequestSerialize for chrono::NaiveDate {
+    fn to_param(&self) -> Option<String> {
+        Some(self.format("%Y-%m-%d").to_string())
+    }
+}
+
+/// 序列化为 `2023-01-02T03:04:05` 格式。
+#[cfg(feature = "chrono")]
+impl RequestSerialize for chrono::NaiveDateTime {
+    fn to_param(&self) -> Option<String> {
+        Some(self.format("%Y-%m-%dT%H:%M:%S").to_string())
+    }
+}
+
+#[cfg(feature = "uuid")]
+impl RequestSerialize for uuid::Uuid {
+    fn to_param(&self) -> Option<String> {
+        Some(self.hyphenated().to_string())
+    }
+}
+
+/// 将 `query_struct` 字段展开为 query 参数。
+///
+/// 字段的值无法表示为 query 参数（如包含嵌套结构体或列表）时返回 [`LarkError::InvalidRequest`]。
+pub fn query_pairs<T: Serialize + ?Sized>(field: &str, value: &T) -> Result<Vec<(String, String)>> {
+    let invalid = |err: &dyn fmt::Display| {
+        LarkError::InvalidRequest(format!(
+            "query_struct field `{}` can not be serialized as query parameters: {}",
+            field, err
+        ))
+    };
+    let query = serde_urlencoded::to_string(value).map_err(|err| invalid(&err))?;
+    serde_urlencoded::from_str(&query).map_err(|err| invalid(&err))
+}
+
+/// 对路径参数进行百分号编码。
+pub fn encode_path_segment(value: &str) -> String {
+    let mut encoded = String::with_capacity(value.len());
+    for byte in value.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                encoded.push(byte as char)
+            }
+            _ => encoded.push_str(&format!("%{:02X}", byte)),
+        }
+    }
+    encoded
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::NaiveDate;
+
+    use super::*;
+
+    fn department_ids(ids: &[u32]) -> Option<String> {
+        Some(
+            ids.iter()
+                .map(|id| format!("od-{}", id))
+                .collect::<Vec<_>>()
+                .join("|"),
+        )
+    }
+
+    #[derive(lark_sdk::Request)]
+    #[request(
+        method = "GET",
+        url = "/open-apis/demo/v1/users/:user_id/records",
+        auth = "tenant",
+        empty
+    )]
+    struct ListRecords {
+        #[request(path)]
+        user_id: String,
+        #[request(query = "page_size")]
+        size: Option<u32>,
+        #[request(query, format = "%Y-%m-%d")]
+        start: NaiveDate,
+        #[request(query, format = "%Y%m%d")]
+        end: Option<NaiveDate>,
+        #[request(query, serialize_with = "department_ids")]
+        departments: Vec<u32>,
+        #[request(header = "X-Request-Id")]
+        request_id: String,
+        #[request(body)]
+        note: Option<String>,
+    }
+
+    #[test]
+    fn derive_request() {
+        let req = ListRecords {
+            user_id: "ou 1".to_string(),
+            size: None,
+            start: NaiveDate::from_ymd_opt(2023, 1, 2).unwrap(),
+            end: NaiveDate::from_ymd_opt(2023, 2, 3),
+            departments: vec![1, 2],
+            request_id: "abc".to_string(),
+            note: Some("hi".to_string()),
+        };
+        assert_eq!(req.method(), Method::Get);
+        assert_eq!(req.url(), "/open-apis/demo/v1/users/ou%201/records");
+        assert_eq!(
+            req.query().unwrap(),
+            vec![
+                ("start".to_string(), "2023-01-02".to_string()),
+                ("end".to_string(), "20230203".to_string()),
+                ("departments".to_string(), "od-1|od-2".to_string()),
+            ]
+        );
+        assert_eq!(
+            req.headers(),
+            vec![("X-Request-Id".to_string(), "abc".to_string())]
+        );
+        assert_eq!(req.body().unwrap().unwrap(), br#"{"note":"hi"}"#);
+        assert_eq!(req.auth_type(), AuthType::Tenant);
+        assert_eq!(req.to_parts().unwrap().auth, AuthType::Tenant);
+    }
+
+    #[derive(lark_sdk::Request)]
+    #[request(
+        method = "PATCH",
+        url = "/open-apis/demo/v1/chats/:chat_id",
+        empty,
+        builder
+    )]
+    pub struct UpdateChat {
+        #[request(path)]
+        chat_id: String,
+        #[request(query)]
+        user_id_type: Option<String>,
+        #[request(body)]
+        name: Option<String>,
+        #[request(body)]
+        r#type: String,
+    }
+
+    #[test]
+    fn derive_builder() {
+        let req = UpdateChat::builder()
+            .with_chat_id("oc_1")
+            .with_name("lark")
+            .with_type("group")
+            .build()
+            .unwrap();
+        assert_eq!(req.url(), "/open-apis/demo/v1/chats/oc_1");
+        assert_eq!(req.user_id_type, None);
+        assert_eq!(
+            req.body().unwrap().unwrap(),
+            br#"{"name":"lark","type":"group"}"#
+        );
+
+        let err = UpdateChat::builder().with_type("group").build().err();
+        assert_eq!(
+            err.unwrap().to_string(),
+            "invalid request: missing required field `chat_id`"
+        );
+        let err = UpdateChat::builder()
+            .with_chat_id("")
+            .with_type("group")
+            .build()
+            .err();
+        assert_eq!(
+            err.unwrap().to_string(),
+            "invalid request: path parameter `chat_id` must not be empty"
+        );
+    }
+
+    const TAGS_LIMIT: usize = 2;
+
+    #[derive(lark_sdk::Request)]
+    #[request(
+        method = "POST",
+        url = "/open-apis/demo/v1/chats/:chat_id/notes",
+        validate = "Self::check",
+        empty
+    )]
+    struct CreateNote {
+        #[request(path)]
+        chat_id: String,
+        #[request(body, max_len = 5)]
+        title: Option<String>,
+        #[request(body, max_len = TAGS_LIMIT)]
+        tags: Vec<String>,
+    }
+
+    impl CreateNote {
+        fn check(&self) -> Result<()> {
+            if self.tags.iter().any(String::is_empty) {
+                return Err(LarkError::InvalidRequest("empty tag".to_string()));
+            }
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn derive_validate() {
+        let mut req = CreateNote {
+            chat_id: "oc_1".to_string(),
+            title: Some("飞书文档".to_string()),
+            tags: vec!["a".to_string(), "b".to_string()],
+        };
+        assert!(req.to_parts().is_ok());
+
+        let message = |req: &CreateNote| req.to_parts().unwrap_err().to_string();
+        req.title = Some("飞书云文档!".to_string());
+        assert_eq!(
+            message(&req),
+            "invalid request: `title` exceeds the limit of 5, got 6"
+        );
+        req.title = None;
+        req.tags.push(String::new());
+        assert_eq!(
+            message(&req),
+            "invalid request: `tags` exceeds the limit of 2, got 3"
+        );
+        req.tags.truncate(1);
+        req.tags[0].clear();
+        assert_eq!(message(&req), "invalid request: empty tag");
+        req.chat_id.clear();
+        assert_eq!(
+            message(&req),
+            "invalid request: path parameter `chat_id` must not be empty"
+        );
+    }
+
+    #[derive(lark_sdk::Request)]
+    #[request(
+        method_fn = "Self::pick_method",
+        url = "/open-apis/demo/v1/search",
+        empty
+    )]
+    struct Search {
+        #[request(body)]
+        keywords: Vec<String>,
+    }
+
+    impl Search {
+        fn pick_method(&self) -> Method {
+            if self.keywords.len() > 2 {
+                Method::Post
+            } else {
+                Method::Get
+            }
+        }
+    }
+
+    #[test]
+    fn derive_method_fn() {
+        let mut search = Search {
+            keywords: vec!["a".to_string()],
+        };
+        assert_eq!(search.method(), Method::Get);
+        search.keywords = vec!["a".to_string(); 3];
+        assert_eq!(search.method(), Method::Post);
+    }
+
+    #[derive(Serialize)]
+    struct Pagination {
+        page_size: u32,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        page_token: Option<String>,
+    }
+
+    #[derive(lark_sdk::Request)]
+    #[request(method = "GET", url = "/open-apis/demo/v1/users", empty)]
+    struct ListUsers {
+        #[request(query)]
+        department_id: String,
+        #[request(query_struct)]
+        page: Pagination,
+        #[request(query_struct)]
+        filter: Option<Pagination>,
+        #[request(query = "user_ids", style = "repeat")]
+        users: Vec<String>,
+        #[request(query, style = "csv")]
+        fields: Vec<String>,
+    }
+
+    #[test]
+    fn derive_query_struct_and_style() {
+        let req = ListUsers {
+            department_id: "od-1".to_string(),
+            page: Pagination {
+                page_size: 20,
+                page_token: Some("a b".to_string()),
+            },
+            filter: None,
+            users: vec!["ou_1".to_string(), "ou_2".to_string()],
+            fields: vec!["name".to_string(), "email".to_string()],
+        };
+        assert_eq!(
+            req.query().unwrap(),
+            vec![
+                ("department_id".to_string(), "od-1".to_string()),
+                ("page_size".to_string(), "20".to_string()),
+                ("page_token".to_string(), "a b".to_string()),
+                ("user_ids".to_string(), "ou_1".to_string()),
+                ("user_ids".to_string(), "ou_2".to_string()),
+                ("fields".to_string(), "name,email".to_string()),
+            ]
+        );
+    }
+
+    #[derive(lark_sdk::Request)]
+    #[request(method = "GET", url = "/open-apis/demo/v1/users/filter", empty)]
+    struct FilterUsers {
+        #[request(query_struct)]
+        filter: serde_json::Value,
+    }
+
+    #[test]
+    fn derive_query_struct_invalid() {
+        let req = FilterUsers {
+            filter: serde_json::json!({ "range": { "from": 1 } }),
+        };
+        let err = req.to_parts().unwrap_err();
+        assert!(
+            matches!(&err, LarkError::InvalidRequest(msg) if msg.contains("query_struct field `filter`")),
+            "{}",
+            err
+        );
+        let req = FilterUsers {
+            filter: serde_json::json!({ "from": 1 }),
+        };
+        assert_eq!(
+            req.query().unwrap(),
+            [("from".to_string(), "1".to_string())]
+        );
+    }
+
+    #[derive(Serialize, lark_sdk::RequestSerialize)]
+    #[serde(rename_all = "SCREAMING-KEBAB-CASE", deny_unknown_fields)]
+    enum Kind {
+        OpenChat,
+        #[serde(rename = "p2p", alias = "single")]
+        Private,
+    }
+
+    #[test]
+    fn derive_request_serialize() {
+        assert_eq!(Kind::OpenChat.to_param(), Some("OPEN-CHAT".to_string()));
+        assert_eq!(Kind::Private.to_param(), Some("p2p".to_string()));
+        assert_eq!(
+            vec![Kind::OpenChat, Kind::Private].to_param(),
+            Some("OPEN-CHAT,p2p".to_string())
+        );
+    }
+
+    #[cfg(feature = "uuid")]
+    #[test]
+    fn serialize_uuid() {
+        let id = uuid::Uuid::from_u128(0x1234_5678_9abc_def0_1234_5678_9abc_def0);
+        assert_eq!(
+            id.to_param(),
+            Some("12345678-9abc-def0-1234-56789abcdef0".to_string())
+        );
+    }
+
+    #[test]
+    fn serialize_vec() {
+        assert_eq!(vec![1, 2, 3].to_param(), Some("1,2,3".to_string()));
+        assert_eq!([1, 2].to_param(), Some("1,2".to_string()));
+        assert_eq!(["a", "b"][..].to_params(), vec!["a", "b"]);
+        assert_eq!(
+            BTreeSet::from(["b", "a"]).to_param(),
+            Some("a,b".to_string())
+        );
+        assert_eq!(HashSet::from([7]).to_param(), Some("7".to_string()));
+        assert_eq!(Cow::Borrowed("x").to_param(), Some("x".to_string()));
+        assert_eq!(Vec::<String>::new().to_param(), None);
+        assert_eq!(Some("a").to_param(), Some("a".to_string()));
+    }
+}
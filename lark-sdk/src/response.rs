@@ -0,0 +1,770 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::marker::PhantomData;
+
+use serde::de::{self, DeserializeOwned, DeserializeSeed, IgnoredAny, MapAccess, Visitor};
+use serde::{Deserialize, Deserializer};
+use serde_json::value::RawValue;
+use serde_json::{Map, Value};
+
+use crate::json::{self, DecodeMode};
+use crate::{LarkError, Result};
+
+/// 飞书开放平台接口的响应。
+pub trait Response {
+    /// 错误码，0 表示成功
+    fn code(&self) -> i64;
+
+    /// 错误信息
+    fn msg(&self) -> &str;
+
+    fn is_success(&self) -> bool {
+        self.code() == 0
+    }
+
+    /// 接口返回失败时附带的数据，部分接口会在其中返回错误详情
+    fn error_data(&self) -> Option<&Value> {
+        None
+    }
+
+    /// 飞书的请求日志 ID，来自响应中的 `log_id` 或 `error.log_id`，反馈问题时需要提供
+    fn log_id(&self) -> Option<&str> {
+        None
+    }
+
+    /// 部分接口返回的 `request_id`
+    fn request_id(&self) -> Option<&str> {
+        None
+    }
+}
+
+/// 可以作为响应中 `data` 字段的类型，通常使用 `#[derive(Response)]` 实现。
+///
+/// ```
+/// use lark_sdk::Response;
+/// use serde::Deserialize;
+///
+/// #[derive(Deserialize, Response)]
+/// pub struct ListUser {
+///     #[response(body)]
+///     pub items: Vec<User>,
+///     pub has_more: bool,
+/// }
+///
+/// #[derive(Deserialize)]
+/// pub struct User {
+///     pub open_id: String,
+/// }
+/// ```
+///
+/// 根据请求参数返回不同结构的接口，可以在枚举上使用 `#[derive(Response)]`（通常配合
+/// `#[serde(untagged)]`）。字段上的 `#[response(body)]` 会同时为字段的类型（展开 `Option`、`Vec`、`Box`）实现 `Body`，
+/// 这样嵌套的类型也可以直接作为其他接口的响应使用。在多个响应中复用的类型请使用
+/// `#[derive(Body)]`，避免重复实现。
+pub trait Body: DeserializeOwned {
+    /// 判断错误码是否表示成功，默认只有 0 表示成功，可以使用
+    /// `#[response(success_codes = [0, 200])]` 修改。
+    fn is_success_code(code: i64) -> bool {
+        code == 0
+    }
+
+    /// 接口返回成功但没有 `data` 字段时使用的值，默认为 `None`，即返回 [`LarkError::MissingData`]。
+    fn default_data() -> Option<Self> {
+        None
+    }
+}
+
+/// 数据与 `code`、`msg` 在同一层级的响应，如
+/// `{"code":0,"msg":"ok","tenant_access_token":"t-xxx","expire":7200}`。
+///
+/// 与 [`BodyResponse`] 相同，`msg` 兼容 `message` 字段，接口返回失败时不会解析 `data`。
+#[derive(Debug, Clone)]
+pub struct FlattenResponse<T> {
+    pub code: i64,
+    pub msg: String,
+    pub data: Option<T>,
+    pub log_id: Option<String>,
+    pub request_id: Option<String>,
+}
+
+impl<'de, T: Body> Deserialize<'de> for FlattenResponse<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> std::result::Result<Self, D::Error> {
+        let mut map = Map::<String, Value>::deserialize(deserializer)?;
+        let code = match map.remove("code") {
+            Some(code) => i64::deserialize(code).map_err(de::Error::custom)?,
+            None => return Err(de::Error::missing_field("code")),
+        };
+        let msg = match map.remove("msg").or_else(|| map.remove("message")) {
+            Some(msg) => Option::<String>::deserialize(msg)
+                .map_err(de::Error::custom)?
+                .unwrap_or_default(),
+            None => String::new(),
+        };
+        let (log_id, request_id) = envelope_ids(&map);
+        let data = if T::is_success_code(code) {
+            let data = json::deserialize_data(Value::Object(map), FLATTEN_ENVELOPE);
+            Some(data.map_err(de::Error::custom)?)
+        } else {
+            None
+        };
+        Ok(FlattenResponse {
+            code,
+            msg,
+            data,
+            log_id,
+            request_id,
+        })
+    }
+}
+
+/// [`FlattenResponse`] 中与数据同一层级、严格模式下不视为未知字段的字段
+const FLATTEN_ENVELOPE: &[&str] = &["log_id", "request_id", "error"];
+
+impl<T: Body> Response for FlattenResponse<T> {
+    fn code(&self) -> i64 {
+        self.code
+    }
+
+    fn msg(&self) -> &str {
+        &self.msg
+    }
+
+    fn is_success(&self) -> bool {
+        T::is_success_code(self.code)
+    }
+
+    fn log_id(&self) -> Option<&str> {
+        self.log_id.as_deref()
+    }
+
+    fn request_id(&self) -> Option<&str> {
+        self.request_id.as_deref()
+    }
+}
+
+impl<T: Body> FlattenResponse<T> {
+    /// 取出数据，接口返回失败时返回 [`LarkError::Api`]。
+    pub fn into_data(self) -> Result<T> {
+        if !self.is_success() {
+            return Err(LarkError::Api {
+                code: self.code,
+                msg: self.msg,
+                data: None,
+                log_id: self.log_id,
+            });
+        }
+        self.data
+            .or_else(T::default_data)
+            .ok_or(LarkError::MissingData)
+    }
+}
+
+/// 从响应的其他字段中提取 `log_id`、`request_id`，兼容放在 `error` 对象中的情况。
+fn envelope_ids(map: &Map<String, Value>) -> (Option<String>, Option<String>) {
+    let find = |key: &str| {
+        map.get(key)
+            .or_else(|| map.get("error").and_then(|error| error.get(key)))
+            .and_then(Value::as_str)
+            .map(String::from)
+    };
+    (find("log_id"), find("request_id"))
+}
+
+/// 未定义具体类型时，以 JSON 获取 `data`，没有 `data` 字段时为 `Value::Null`。
+impl Body for Value {
+    fn default_data() -> Option<Self> {
+        Some(Value::Null)
+    }
+}
+
+/// `data` 为数组的接口。
+impl<T: DeserializeOwned> Body for Vec<T> {}
+
+impl<V: DeserializeOwned> Body for HashMap<String, V> {}
+
+/// 延迟解析的 `data`，只保存原始 JSON。
+///
+/// 用于数据量很大的接口（如通讯录、多维表格导出）：可以使用 [`RawBody::parse`] 解析为
+/// 借用原始 JSON 的类型（`&str` 或 `#[serde(borrow)] Cow<str>` 字段），避免为每个字符串分配内存。
+///
+/// ```
+/// use lark_sdk::{BodyResponse, RawBody};
+/// use serde::Deserialize;
+///
+/// #[derive(Deserialize)]
+/// struct User<'a> {
+///     name: &'a str,
+/// }
+///
+/// #[derive(Deserialize)]
+/// struct Users<'a> {
+///     #[serde(borrow)]
+///     items: Vec<User<'a>>,
+/// }
+///
+/// let json = r#"{"code":0,"msg":"success","data":{"items":[{"name":"lark"}]}}"#;
+/// let raw = serde_json::from_str::<BodyResponse<RawBody>>(json)?.into_data()?;
+/// let users: Users = raw.parse()?;
+/// assert_eq!(users.items[0].name, "lark");
+/// # Ok::<(), lark_sdk::LarkError>(())
+/// ```
+#[derive(Debug, Clone, Deserialize)]
+#[serde(transparent)]
+pub struct RawBody(Box<RawValue>);
+
+impl RawBody {
+    /// 原始 JSON
+    pub fn get(&self) -> &str {
+        self.0.get()
+    }
+
+    /// 解析为借用原始 JSON 的类型。
+    pub fn parse<'a, T: Deserialize<'a>>(&'a self) -> Result<T> {
+        Ok(serde_json::from_str(self.0.get())?)
+    }
+}
+
+impl Body for RawBody {}
+
+/// 没有返回数据的接口的 `data`，接受 `{}`、`null` 或者缺失的 `data` 字段。
+///
+/// 请求上可以使用 `#[request(empty)]` 代替 `#[request(response = Empty)]`。
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Empty;
+
+impl<'de> Deserialize<'de> for Empty {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> std::result::Result<Self, D::Error> {
+        IgnoredAny::deserialize(deserializer)?;
+        Ok(Empty)
+    }
+}
+
+impl Body for Empty {
+    fn default_data() -> Option<Self> {
+        Some(Empty)
+    }
+}
+
+/// 形如 `{"code":0,"msg":"success","data":{...}}` 的响应。
+///
+/// 部分接口使用 `message` 代替 `msg`，两者都会作为 `msg`，都缺失时为空字符串。
+/// 除 `code`、`msg`、`data` 以外的字段（如 `error`）不会导致反序列化失败，而是保存在 `extra` 中。
+/// 接口返回失败时，`data` 不会按 `T` 解析，而是原样保存在 `error_data` 中。
+#[derive(Debug, Clone)]
+pub struct BodyResponse<T> {
+    pub code: i64,
+    pub msg: String,
+    pub data: Option<T>,
+    pub error_data: Option<Value>,
+    pub log_id: Option<String>,
+    pub request_id: Option<String>,
+    pub extra: Map<String, Value>,
+}
+
+impl<'de, T: Body> Deserialize<'de> for BodyResponse<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> std::result::Result<Self, D::Error> {
+        deserializer.deserialize_map(BodyResponseVisitor(PhantomData))
+    }
+}
+
+struct BodyResponseVisitor<T>(PhantomData<T>);
+
+impl<'de, T: Body> Visitor<'de> for BodyResponseVisitor<T> {
+    type Value = BodyResponse<T>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a lark response")
+    }
+
+    fn visit_map<A: MapAccess<'de>>(
+        self,
+        mut map: A,
+    ) -> std::result::Result<Self::Value, A::Error> {
+        let mut code: Option<i64> = None;
+        let mut msg: Option<String> = None;
+        let mut data = None;
+        let mut error_data = None;
+        let mut raw_data: Option<Box<RawValue>> = None;
+        let mut extra = Map::new();
+        while let Some(key) = map.next_key::<String>()? {
+            match key.as_str() {
+                "code" => code = Some(map.next_value()?),
+                // 与 FlattenResponse 一致，`"msg": null` 视为空字符串
+                "msg" | "message" => msg = map.next_value()?,
+                "data" => match code {
+                    // 失败时 data 的结构与 T 无关，只保存原始值
+                    Some(code) if !T::is_success_code(code) => {
+                        error_data = map.next_value::<Option<Value>>()?
+                    }
+                    Some(_) => data = map.next_value_seed(DataSeed(PhantomData))?,
+                    // data 出现在 code 之前，先保存原始 JSON，读取完整个响应之后再解析
+                    None => raw_data = Some(map.next_value()?),
+                },
+                _ => {
+                    let value = map.next_value()?;
+                    extra.insert(key, value);
+                }
+            }
+        }
+        let code = code.ok_or_else(|| de::Error::missing_field("code"))?;
+        let msg = msg.unwrap_or_default();
+        if let Some(raw) = raw_data.filter(|raw| raw.get() != "null") {
+            if T::is_success_code(code) {
+                let mut deserializer = serde_json::Deserializer::from_str(raw.get());
+                data = Some(
+                    json::deserialize_data(&mut deserializer, &[]).map_err(de::Error::custom)?,
+                );
+            } else {
+                error_data = Some(serde_json::from_str(raw.get()).map_err(de::Error::custom)?);
+            }
+        }
+        if data.is_none()
+            && T::is_success_code(code)
+            && json::mode() == DecodeMode::Strict
+            && T::default_data().is_none()
+        {
+            return Err(de::Error::missing_field("data"));
+        }
+        let (log_id, request_id) = envelope_ids(&extra);
+        Ok(BodyResponse {
+            code,
+            msg,
+            data,
+            error_data: error_data.filter(|data| !data.is_null()),
+            log_id,
+            request_id,
+            extra,
+        })
+    }
+}
+
+/// 按照解析模式解析 `data`
+struct DataSeed<T>(PhantomData<T>);
+
+impl<'de, T: Body> DeserializeSeed<'de> for DataSeed<T> {
+    type Value = Option<T>;
+
+    fn deserialize<D: Deserializer<'de>>(
+        self,
+        deserializer: D,
+    ) -> std::result::Result<Self::Value, D::Error> {
+        json::deserialize_data(deserializer, &[])
+    }
+}
+
+impl<T: Body> Response for BodyResponse<T> {
+    fn code(&self) -> i64 {
+        self.code
+    }
+
+    fn msg(&self) -> &str {
+        &self.msg
+    }
+
+    fn is_success(&self) -> bool {
+        T::is_success_code(self.code)
+    }
+
+    fn error_data(&self) -> Option<&Value> {
+        self.error_data.as_ref()
+    }
+
+    fn log_id(&self) -> Option<&str> {
+        self.log_id.as_deref()
+    }
+
+    fn request_id(&self) -> Option<&str> {
+        self.request_id.as_deref()
+    }
+}
+
+impl<T: Body> BodyResponse<T> {
+    /// 取出 `data`，接口返回失败时返回 [`LarkError::Api`]。
+    pub fn into_data(self) -> Result<T> {
+        if !self.is_success() {
+            return Err(LarkError::Api {
+                code: self.code,
+                msg: self.msg,
+                data: self.error_data,
+                log_id: self.log_id,
+            });
+        }
+        self.data
+            .or_else(T::default_data)
+            .ok_or(LarkError::MissingData)
+    }
+}
+
+/// 分页列表接口通用的 `data`。
+///
+/// 兼容 `has_more`/`hasMore`、`page_token`/`pageToken` 两种写法，`items` 为 `null`
+/// 或缺失时为空列表，`page_token` 为空字符串时为 `None`。
+#[derive(Debug, Clone, Deserialize)]
+#[serde(bound = "T: DeserializeOwned")]
+pub struct ListData<T> {
+    #[serde(default, deserialize_with = "null_as_default")]
+    pub items: Vec<T>,
+    #[serde(default, alias = "pageToken", deserialize_with = "empty_as_none")]
+    pub page_token: Option<String>,
+    #[serde(default, alias = "hasMore", deserialize_with = "null_as_default")]
+    pub has_more: bool,
+    #[serde(default)]
+    pub total: Option<u64>,
+}
+
+impl<T: DeserializeOwned> Body for ListData<T> {}
+
+/// 分页列表接口的响应。
+pub type ListResponse<T> = BodyResponse<ListData<T>>;
+
+fn null_as_default<'de, D, T>(deserializer: D) -> std::result::Result<T, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Deserialize<'de> + Default,
+{
+    Ok(Option::<T>::deserialize(deserializer)?.unwrap_or_default())
+}
+
+fn empty_as_none<'de, D>(deserializer: D) -> std::result::Result<Option<String>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    Ok(Option::<String>::deserialize(deserializer)?.filter(|value| !value.is_empty()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, Deserialize, lark_sdk::Response)]
+    struct ListUser {
+        #[response(body)]
+        items: Vec<User>,
+        #[response(body)]
+        leader: Option<Box<Leader>>,
+    }
+
+    #[derive(Debug, Deserialize)]
+    struct User {
+        open_id: String,
+    }
+
+    #[derive(Debug, Deserialize)]
+    struct Leader {
+        name: String,
+    }
+
+    #[derive(Debug, Deserialize, lark_sdk::Body)]
+    struct Department {
+        department_id: String,
+    }
+
+    #[derive(Debug, Deserialize, lark_sdk::Response)]
+    struct GetUser {
+        department: Department,
+    }
+
+    #[derive(Debug, Deserialize, lark_sdk::Response)]
+    struct ListDepartment {
+        items: Vec<Department>,
+    }
+
+    #[derive(Debug, Deserialize, lark_sdk::Body)]
+    struct Page<T> {
+        items: Vec<T>,
+    }
+
+    #[derive(Debug, Deserialize, lark_sdk::Response)]
+    #[serde(untagged)]
+    enum Member {
+        Bot(#[response(body)] Bot),
+        Chat { chat_id: String },
+    }
+
+    #[derive(Debug, Deserialize)]
+    struct Bot {
+        app_id: String,
+    }
+
+    fn parse<T: Body>(json: &str) -> Result<T> {
+        serde_json::from_str::<BodyResponse<T>>(json)?.into_data()
+    }
+
+    #[test]
+    fn derive_response() {
+        let list: ListUser = parse(
+            r#"{"code":0,"msg":"success","data":{"items":[{"open_id":"ou_1"}],"leader":null}}"#,
+        )
+        .unwrap();
+        assert_eq!(list.items[0].open_id, "ou_1");
+        assert!(list.leader.is_none());
+
+        let user: User = parse(r#"{"code":0,"msg":"success","data":{"open_id":"ou_2"}}"#).unwrap();
+        assert_eq!(user.open_id, "ou_2");
+        let leader: Leader = parse(r#"{"code":0,"msg":"success","data":{"name":"a"}}"#).unwrap();
+        assert_eq!(leader.name, "a");
+    }
+
+    #[test]
+    fn derive_body() {
+        let department: Department =
+            parse(r#"{"code":0,"msg":"success","data":{"department_id":"od-1"}}"#).unwrap();
+        assert_eq!(department.department_id, "od-1");
+        let user: GetUser =
+            parse(r#"{"code":0,"msg":"","data":{"department":{"department_id":"od-2"}}}"#).unwrap();
+        assert_eq!(user.department.department_id, "od-2");
+        let list: ListDepartment =
+            parse(r#"{"code":0,"msg":"","data":{"items":[{"department_id":"od-3"}]}}"#).unwrap();
+        assert_eq!(list.items.len(), 1);
+        let page: Page<Department> =
+            parse(r#"{"code":0,"msg":"","data":{"items":[{"department_id":"od-4"}]}}"#).unwrap();
+        assert_eq!(page.items[0].department_id, "od-4");
+    }
+
+    #[test]
+    fn derive_response_enum() {
+        let member: Member = parse(r#"{"code":0,"msg":"","data":{"chat_id":"oc_1"}}"#).unwrap();
+        assert!(matches!(member, Member::Chat { chat_id } if chat_id == "oc_1"));
+        let member: Member = parse(r#"{"code":0,"msg":"","data":{"app_id":"cli_1"}}"#).unwrap();
+        assert!(matches!(member, Member::Bot(bot) if bot.app_id == "cli_1"));
+        let bot: Bot = parse(r#"{"code":0,"msg":"","data":{"app_id":"cli_2"}}"#).unwrap();
+        assert_eq!(bot.app_id, "cli_2");
+    }
+
+    #[test]
+    fn list_data() {
+        let list: ListData<User> = parse(
+            r#"{"code":0,"msg":"","data":{"items":[{"open_id":"ou_1"}],"page_token":"next","has_more":true,"total":3}}"#,
+        )
+        .unwrap();
+        assert_eq!(list.items.len(), 1);
+        assert_eq!(list.page_token.as_deref(), Some("next"));
+        assert!(list.has_more);
+        assert_eq!(list.total, Some(3));
+
+        let list: ListData<User> =
+            parse(r#"{"code":0,"msg":"","data":{"items":null,"pageToken":"","hasMore":false}}"#)
+                .unwrap();
+        assert!(list.items.is_empty());
+        assert_eq!(list.page_token, None);
+        assert!(!list.has_more);
+        assert_eq!(list.total, None);
+    }
+
+    #[test]
+    fn empty() {
+        parse::<Empty>(r#"{"code":0,"msg":"success","data":{}}"#).unwrap();
+        parse::<Empty>(r#"{"code":0,"msg":"success","data":null}"#).unwrap();
+        parse::<Empty>(r#"{"code":0,"msg":"success"}"#).unwrap();
+        let err = parse::<Empty>(r#"{"code":230002,"msg":"bot not in chat"}"#).unwrap_err();
+        assert!(matches!(err, LarkError::Api { code: 230002, .. }));
+    }
+
+    #[test]
+    fn untyped_body() {
+        let value: serde_json::Value =
+            parse(r#"{"code":0,"msg":"","data":{"chat_id":"oc_1"}}"#).unwrap();
+        assert_eq!(value["chat_id"], "oc_1");
+        let value: serde_json::Value = parse(r#"{"code":0,"msg":""}"#).unwrap();
+        assert!(value.is_null());
+
+        let users: Vec<User> = parse(r#"{"code":0,"msg":"","data":[{"open_id":"ou_1"}]}"#).unwrap();
+        assert_eq!(users[0].open_id, "ou_1");
+
+        let map: HashMap<String, serde_json::Value> =
+            parse(r#"{"code":0,"msg":"","data":{"a":1,"b":"2"}}"#).unwrap();
+        assert_eq!(map["a"], 1);
+    }
+
+    #[test]
+    fn unknown_fields() {
+        let response: BodyResponse<User> = serde_json::from_str(
+            r#"{"code":0,"msg":"","data":{"open_id":"ou_1"},"error":{"log_id":"2023"},"log_id":"2023"}"#,
+        )
+        .unwrap();
+        assert_eq!(response.extra["log_id"], "2023");
+        assert_eq!(response.log_id(), Some("2023"));
+        assert_eq!(response.extra["error"]["log_id"], "2023");
+        assert_eq!(response.into_data().unwrap().open_id, "ou_1");
+    }
+
+    #[test]
+    fn error_data() {
+        let response: BodyResponse<User> = serde_json::from_str(
+            r#"{"code":1254043,"msg":"invalid params","data":{"field_violations":[{"field":"open_id"}]}}"#,
+        )
+        .unwrap();
+        assert!(response.data.is_none());
+        assert_eq!(
+            response.error_data().unwrap()["field_violations"][0]["field"],
+            "open_id"
+        );
+        match response.into_data().unwrap_err() {
+            LarkError::Api {
+                data: Some(data), ..
+            } => {
+                assert_eq!(data["field_violations"][0]["field"], "open_id")
+            }
+            err => panic!("unexpected error: {}", err),
+        }
+    }
+
+    #[test]
+    fn data_before_code() {
+        let user: User = parse(r#"{"data":{"open_id":"ou_1"},"msg":"success","code":0}"#).unwrap();
+        assert_eq!(user.open_id, "ou_1");
+
+        let response: BodyResponse<User> =
+            serde_json::from_str(r#"{"data":{"reason":1},"code":10,"msg":"failed"}"#).unwrap();
+        assert!(response.data.is_none());
+        assert_eq!(response.error_data.unwrap()["reason"], 1);
+    }
+
+    #[test]
+    fn log_id() {
+        let response: BodyResponse<User> = serde_json::from_str(
+            r#"{"code":99991400,"msg":"too many requests","error":{"log_id":"20230101"}}"#,
+        )
+        .unwrap();
+        assert_eq!(response.log_id(), Some("20230101"));
+        assert_eq!(response.request_id(), None);
+        match response.into_data().unwrap_err() {
+            LarkError::Api { log_id, .. } => assert_eq!(log_id.as_deref(), Some("20230101")),
+            err => panic!("unexpected error: {}", err),
+        }
+
+        let response: FlattenResponse<Empty> =
+            serde_json::from_str(r#"{"code":0,"msg":"ok","request_id":"r-1"}"#).unwrap();
+        assert_eq!(response.request_id(), Some("r-1"));
+    }
+
+    #[test]
+    fn raw_body() {
+        #[derive(Deserialize)]
+        struct Borrowed<'a> {
+            items: Vec<&'a str>,
+            #[serde(borrow)]
+            name: std::borrow::Cow<'a, str>,
+        }
+
+        let raw: RawBody =
+            parse(r#"{"code":0,"msg":"","data":{"items":["a","b"],"name":"c\"d"}}"#).unwrap();
+        assert_eq!(raw.get(), r#"{"items":["a","b"],"name":"c\"d"}"#);
+        let borrowed: Borrowed = raw.parse().unwrap();
+        assert_eq!(borrowed.items, vec!["a", "b"]);
+        assert_eq!(borrowed.name, "c\"d");
+    }
+
+    #[test]
+    fn message_alias() {
+        let response: BodyResponse<User> =
+            serde_json::from_str(r#"{"code":1,"message":"failed"}"#).unwrap();
+        assert_eq!(response.msg, "failed");
+        let response: BodyResponse<Empty> = serde_json::from_str(r#"{"code":0}"#).unwrap();
+        assert_eq!(response.msg, "");
+        let response: BodyResponse<Empty> =
+            serde_json::from_str(r#"{"code":0,"msg":null,"data":{}}"#).unwrap();
+        assert_eq!(response.msg, "");
+        let response: FlattenResponse<TenantAccessToken> =
+            serde_json::from_str(r#"{"code":1,"msg":null}"#).unwrap();
+        assert_eq!(response.msg(), "");
+    }
+
+    #[derive(Debug, Deserialize, lark_sdk::Response)]
+    struct TenantAccessToken {
+        tenant_access_token: String,
+        expire: u64,
+    }
+
+    #[test]
+    fn flatten_response() {
+        let response: FlattenResponse<TenantAccessToken> = serde_json::from_str(
+            r#"{"code":0,"msg":"ok","tenant_access_token":"t-1","expire":7200}"#,
+        )
+        .unwrap();
+        let token = response.into_data().unwrap();
+        assert_eq!(token.tenant_access_token, "t-1");
+        assert_eq!(token.expire, 7200);
+
+        let response: FlattenResponse<TenantAccessToken> =
+            serde_json::from_str(r#"{"code":10003,"message":"invalid param"}"#).unwrap();
+        assert_eq!(response.msg(), "invalid param");
+        assert!(matches!(
+            response.into_data().unwrap_err(),
+            LarkError::Api { code: 10003, .. }
+        ));
+    }
+
+    #[test]
+    fn strict_mode() {
+        let strict = |json: &str| {
+            json::with_mode(DecodeMode::Strict, || {
+                serde_json::from_str::<BodyResponse<ListUser>>(json)
+            })
+        };
+        let json = r#"{"code":0,"msg":"ok","data":{"items":[{"open_id":"ou_1","name":"a"}],"leader":{"name":"b","id":1},"total":1}}"#;
+        assert!(serde_json::from_str::<BodyResponse<ListUser>>(json).is_ok());
+        let err = strict(json).unwrap_err().to_string();
+        assert!(
+            err.starts_with("unknown fields: items.0.name, leader.id, total"),
+            "{}",
+            err
+        );
+        // data 在 code 之前
+        let err = strict(r#"{"data":{"items":[],"total":1},"code":0}"#).unwrap_err();
+        assert!(err.to_string().contains("unknown fields: total"));
+
+        assert!(strict(r#"{"code":0,"msg":"ok","data":{"items":[]},"error":{}}"#).is_ok());
+        assert!(strict(r#"{"code":1,"msg":"failed","data":{"extra":1}}"#).is_ok());
+        let err = strict(r#"{"code":0,"msg":"ok"}"#).unwrap_err();
+        assert!(err.to_string().starts_with("missing field `data`"));
+        let empty = json::with_mode(DecodeMode::Strict, || {
+            serde_json::from_str::<BodyResponse<Empty>>(r#"{"code":0,"msg":"ok"}"#)
+        });
+        assert!(empty.is_ok());
+
+        let flatten = |json: &str| {
+            json::with_mode(DecodeMode::Strict, || {
+                serde_json::from_str::<FlattenResponse<TenantAccessToken>>(json)
+            })
+        };
+        assert!(flatten(
+            r#"{"code":0,"msg":"ok","tenant_access_token":"t-1","expire":7200,"log_id":"1"}"#
+        )
+        .is_ok());
+        assert!(
+            flatten(r#"{"code":0,"tenant_access_token":"t-1","expire":7200,"app":1}"#)
+                .unwrap_err()
+                .to_string()
+                .contains("unknown fields: app")
+        );
+        assert_eq!(json::mode(), DecodeMode::Lenient);
+    }
+
+    #[derive(Debug, Deserialize, lark_sdk::Response)]
+    #[response(success_codes = [0, 200])]
+    struct Legacy {
+        ok: bool,
+    }
+
+    #[test]
+    fn success_codes() {
+        let response: BodyResponse<Legacy> =
+            serde_json::from_str(r#"{"code":200,"msg":"ok","data":{"ok":true}}"#).unwrap();
+        assert!(response.is_success());
+        assert!(response.into_data().unwrap().ok);
+
+        let response: BodyResponse<Legacy> =
+            serde_json::from_str(r#"{"code":1,"msg":"failed","data":{"reason":"x"}}"#).unwrap();
+        assert!(!response.is_success());
+        assert_eq!(response.error_data().unwrap()["reason"], "x");
+    }
+
+    #[test]
+    fn into_data_error() {
+        let err = parse::<User>(r#"{"code":99991663,"msg":"invalid token"}"#).unwrap_err();
+        assert!(matches!(err, LarkError::Api { code: 99991663, .. }));
+        let err = parse::<User>(r#"{"code":0,"msg":"success"}"#).unwrap_err();
+        assert!(matches!(err, LarkError::MissingData));
+    }
+}
@@ -0,0 +1,222 @@
+//! 可以安全重试的请求发送。
+
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+#[cfg(feature = "async")]
+use crate::poll::Backoff;
+#[cfg(feature = "async")]
+use crate::{Client, LarkError, Request, Result};
+
+/// 在超时、连接失败时重试请求，不会导致非幂等接口（如发送消息）重复执行。
+///
+/// - 幂等的请求（[`Request::is_idempotent`]）直接重试；
+/// - 支持幂等键的请求（[`Request::set_idempotency_key`]）在第一次发送前生成幂等键，
+///   之后的重试使用相同的幂等键，由服务端去重；
+/// - 其他请求只发送一次，不会重试。
+///
+/// ```no_run
+/// # use lark_sdk::retry::SafeSend;
+/// # use lark_sdk::{Client, Request};
+/// #[derive(Request)]
+/// #[request(method = "POST", url = "/open-apis/im/v1/messages", empty)]
+/// pub struct SendMessage {
+///     #[request(query)]
+///     pub receive_id_type: String,
+///     #[request(body)]
+///     pub receive_id: String,
+///     #[request(body)]
+///     pub msg_type: String,
+///     #[request(body)]
+///     pub content: String,
+///     #[request(body, idempotency_key)]
+///     pub uuid: Option<String>,
+/// }
+///
+/// # async fn run(client: Client, mut request: SendMessage) -> lark_sdk::Result<()> {
+/// let response = SafeSend::new(&client).retries(5).send(&mut request).await?;
+/// # Ok(())
+/// # }
+/// ```
+#[cfg(feature = "async")]
+#[derive(Debug, Clone)]
+pub struct SafeSend<'a> {
+    client: &'a Client,
+    retries: u32,
+    backoff: Backoff,
+}
+
+#[cfg(feature = "async")]
+impl<'a> SafeSend<'a> {
+    /// 默认最多重试 3 次，等待时间为 [`Backoff::default`]
+    pub fn new(client: &'a Client) -> Self {
+        SafeSend {
+            client,
+            retries: 3,
+            backoff: Backoff::default(),
+        }
+    }
+
+    /// 最多重试的次数
+    pub fn retries(mut self, retries: u32) -> Self {
+        self.retries = retries;
+        self
+    }
+
+    pub fn backoff(mut self, backoff: Backoff) -> Self {
+        self.backoff = backoff;
+        self
+    }
+
+    /// 发送请求，需要时会为请求设置幂等键
+    pub async fn send<R: Request>(&self, request: &mut R) -> Result<R::Target> {
+        let retryable =
+            request.is_idempotent() || request.set_idempotency_key(&new_idempotency_key());
+        let mut attempt = 0;
+        loop {
+            match self.client.send(&*request).await {
+                Err(err) if retryable && attempt < self.retries && is_transient(&err) => {
+                    tokio::time::sleep(self.backoff.delay(attempt)).await;
+                    attempt += 1;
+                }
+                result => return result,
+            }
+        }
+    }
+}
+
+/// 超时、连接失败等可以重试的错误
+#[cfg(feature = "async")]
+pub(crate) fn is_transient(err: &LarkError) -> bool {
+    use std::io::ErrorKind;
+    match err {
+        LarkError::Http(err) => err.is_timeout() || err.is_connect(),
+        // 自定义传输层的连接错误
+        LarkError::Io(err) => matches!(
+            err.kind(),
+            ErrorKind::TimedOut
+                | ErrorKind::ConnectionRefused
+                | ErrorKind::ConnectionReset
+                | ErrorKind::ConnectionAborted
+        ),
+        _ => false,
+    }
+}
+
+/// 生成进程内唯一的幂等键
+pub fn new_idempotency_key() -> String {
+    static COUNTER: AtomicU32 = AtomicU32::new(0);
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |duration| duration.as_nanos() as u64);
+    format!(
+        "{:016x}{:08x}{:08x}",
+        nanos,
+        std::process::id(),
+        COUNTER.fetch_add(1, Ordering::Relaxed)
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    #[cfg(feature = "async")]
+    use std::thread;
+    #[cfg(feature = "async")]
+    use std::time::Duration;
+
+    use super::*;
+    #[cfg(feature = "async")]
+    use crate::test_util::{accept, bind, write_response};
+    use crate::{Method, Request};
+
+    #[derive(lark_sdk::Request)]
+    #[request(method = "POST", url = "/open-apis/im/v1/messages", empty)]
+    struct SendMessage {
+        #[request(body)]
+        content: String,
+        #[request(body, idempotency_key)]
+        uuid: Option<String>,
+    }
+
+    #[derive(lark_sdk::Request)]
+    #[request(method = "POST", url = "/open-apis/demo/search", empty, idempotent)]
+    struct Search {}
+
+    #[derive(lark_sdk::Request)]
+    #[request(method = "POST", url = "/open-apis/demo/create", empty)]
+    struct Create {}
+
+    #[test]
+    fn idempotency() {
+        assert!(Search {}.is_idempotent());
+        assert!(!Create {}.is_idempotent());
+        assert!(!Create {}.set_idempotency_key("key"));
+
+        let mut request = SendMessage {
+            content: "hi".to_string(),
+            uuid: None,
+        };
+        assert_eq!(request.method(), Method::Post);
+        assert!(!request.is_idempotent());
+        assert!(request.set_idempotency_key("first"));
+        assert!(request.set_idempotency_key("second"));
+        assert_eq!(request.uuid.as_deref(), Some("first"));
+        assert_ne!(new_idempotency_key(), new_idempotency_key());
+    }
+
+    #[cfg(feature = "async")]
+    #[tokio::test]
+    async fn retry_with_idempotency_key() {
+        let (listener, address) = bind();
+        let server = thread::spawn(move || {
+            // 第一次请求不响应，使客户端超时
+            let (_stream, first) = accept(&listener);
+            let (mut stream, second) = accept(&listener);
+            write_response(&mut stream, r#"{"code":0,"msg":"ok","data":{}}"#);
+            (first, second)
+        });
+        let client = Client::builder()
+            .domain(address)
+            .timeout(Duration::from_millis(200))
+            .build()
+            .unwrap();
+        let mut request = SendMessage {
+            content: "hi".to_string(),
+            uuid: None,
+        };
+        SafeSend::new(&client)
+            .backoff(Backoff::fixed(Duration::from_millis(1)))
+            .send(&mut request)
+            .await
+            .unwrap();
+
+        let (first, second) = server.join().unwrap();
+        let uuid = request.uuid.unwrap();
+        assert!(first.contains(&uuid));
+        assert!(second.contains(&uuid));
+    }
+
+    #[cfg(feature = "async")]
+    #[tokio::test]
+    async fn no_retry_without_key() {
+        let (listener, address) = bind();
+        let server = thread::spawn(move || {
+            let (_stream, _) = accept(&listener);
+            thread::sleep(Duration::from_millis(300));
+            listener.set_nonblocking(true).unwrap();
+            listener.accept().is_err()
+        });
+        let client = Client::builder()
+            .domain(address)
+            .timeout(Duration::from_millis(100))
+            .build()
+            .unwrap();
+        let err = SafeSend::new(&client)
+            .backoff(Backoff::fixed(Duration::from_millis(1)))
+            .send(&mut Create {})
+            .await
+            .unwrap_err();
+        assert!(is_transient(&err));
+        assert!(server.join().unwrap(), "the request must not be retried");
+    }
+}
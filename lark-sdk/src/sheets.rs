@@ -0,0 +1,553 @@
+//! 电子表格。
+//!
+//! [`ReadRange`]、[`WriteRange`]、[`AppendRange`] 读写单元格的原始值。实现了 [`SheetRow`] 的结构体可以按照
+//! 表头读写整行：读取和追加时按列名匹配表头，不需要与字段的顺序一致；写入时连同表头一起按照字段的
+//! 顺序写入：
+//!
+//! ```no_run
+//! use lark_sdk::sheets::SheetRow;
+//! use serde::{Deserialize, Serialize};
+//!
+//! #[derive(Debug, Serialize, Deserialize, SheetRow)]
+//! struct Employee {
+//!     #[sheet(rename = "姓名")]
+//!     name: String,
+//!     #[sheet(rename = "年龄")]
+//!     age: Option<u32>,
+//! }
+//!
+//! # async fn run(client: lark_sdk::Client) -> lark_sdk::Result<()> {
+//! // 第一行为表头
+//! let rows: Vec<Employee> = client.sheets().read_rows("shtcnxxx", "0b12!A1:C100").await?;
+//! client.sheets().write_rows("shtcnxxx", "0b12!A1:C100", &rows).await?;
+//! client.sheets().append_rows("shtcnxxx", "0b12!A1:C100", &rows).await?;
+//! # Ok(())
+//! # }
+//! ```
+
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+#[cfg(feature = "async")]
+use crate::Client;
+use crate::{LarkError, Result};
+
+pub use lark_derive::SheetRow;
+
+/// 与表头对应的一行数据，使用 `#[derive(SheetRow)]` 实现
+pub trait SheetRow: Sized {
+    /// 每个字段对应的列名
+    const COLUMNS: &'static [&'static str];
+
+    /// 从一行单元格中读取，`columns[i]` 为第 `i` 列在 `row` 中的位置，表头中没有该列时为 `None`
+    fn from_row(row: &[Value], columns: &[Option<usize>]) -> Result<Self>;
+
+    /// 按照 [`COLUMNS`](SheetRow::COLUMNS) 的顺序转换为单元格
+    fn to_row(&self) -> Result<Vec<Value>>;
+}
+
+/// 表头中每一列的位置，与 [`SheetRow::COLUMNS`] 一一对应，列名会去掉首尾的空白
+pub fn header_columns<R: SheetRow>(header: &[Value]) -> Vec<Option<usize>> {
+    let header: Vec<Option<String>> = header.iter().map(cell_text).collect();
+    R::COLUMNS
+        .iter()
+        .map(|column| {
+            header
+                .iter()
+                .position(|name| name.as_deref().map(str::trim) == Some(*column))
+        })
+        .collect()
+}
+
+/// 将第一行作为表头读取其余的行，忽略所有单元格都为空的行
+pub fn rows_from_values<R: SheetRow>(values: &[Vec<Value>]) -> Result<Vec<R>> {
+    let (header, rows) = match values.split_first() {
+        Some(split) => split,
+        None => return Ok(Vec::new()),
+    };
+    let columns = header_columns::<R>(header);
+    rows.iter()
+        .filter(|row| row.iter().any(|cell| cell_text(cell).is_some()))
+        .map(|row| R::from_row(row, &columns))
+        .collect()
+}
+
+/// 将多行转换为单元格，`header` 为 `true` 时第一行为表头
+pub fn values_from_rows<R: SheetRow>(rows: &[R], header: bool) -> Result<Vec<Vec<Value>>> {
+    let mut values = Vec::with_capacity(rows.len() + 1);
+    if header {
+        values.push(R::COLUMNS.iter().map(|&name| Value::from(name)).collect());
+    }
+    for row in rows {
+        values.push(row.to_row()?);
+    }
+    Ok(values)
+}
+
+/// 按照表头中列的位置将多行转换为单元格，表头中没有的列为空，用于在已有数据之后追加。
+///
+/// 字段对应的列不在表头中时返回错误，避免数据写入错误的列
+pub fn values_by_header<R: SheetRow>(rows: &[R], header: &[Value]) -> Result<Vec<Vec<Value>>> {
+    let columns = header_columns::<R>(header);
+    let columns = R::COLUMNS
+        .iter()
+        .zip(columns)
+        .map(|(name, index)| {
+            index.ok_or_else(|| {
+                LarkError::InvalidRequest(format!("column `{}` is not in the header", name))
+            })
+        })
+        .collect::<Result<Vec<usize>>>()?;
+    rows.iter()
+        .map(|row| {
+            let mut values = vec![Value::Null; header.len()];
+            for (index, cell) in columns.iter().zip(row.to_row()?) {
+                values[*index] = cell;
+            }
+            Ok(values)
+        })
+        .collect()
+}
+
+/// 区域的第一行，如 `0b12!A1:C100` 的第一行为 `0b12!A1:C1`，
+/// 不是 `起始单元格:结束单元格` 的形式时返回 `None`
+#[cfg(feature = "async")]
+fn first_row(range: &str) -> Option<String> {
+    let (sheet, cells) = range.split_once('!')?;
+    let (start, end) = cells.split_once(':')?;
+    let row = start.trim_start_matches(|c: char| c.is_ascii_alphabetic());
+    let end_column = end.trim_end_matches(|c: char| c.is_ascii_digit());
+    if row.is_empty() || end_column.is_empty() || !row.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    Some(format!("{}!{}:{}{}", sheet, start, end_column, row))
+}
+
+/// 单元格的文本，富文本单元格会拼接所有片段，空单元格返回 `None`
+fn cell_text(cell: &Value) -> Option<String> {
+    let text = match cell {
+        Value::Null => return None,
+        Value::String(text) => text.clone(),
+        Value::Array(segments) => segments
+            .iter()
+            .filter_map(|segment| segment.get("text").and_then(Value::as_str))
+            .collect(),
+        other => other.to_string(),
+    };
+    Some(text).filter(|text| !text.is_empty())
+}
+
+/// 读取单元格，派生宏生成的代码使用。
+///
+/// 单元格的类型与字段不一致时会尝试转换，如数字单元格读取为 `String`、文本单元格 `"42"` 读取为数字，
+/// 空单元格读取为 `None` 或空字符串。
+#[doc(hidden)]
+pub fn sheet_cell<T: DeserializeOwned>(
+    row: &[Value],
+    index: Option<usize>,
+    column: &str,
+) -> Result<T> {
+    let cell = match index.and_then(|index| row.get(index)) {
+        Some(Value::Array(segments)) => {
+            Value::from(cell_text(&Value::Array(segments.clone())).unwrap_or_default())
+        }
+        Some(Value::String(text)) if text.is_empty() => Value::Null,
+        Some(cell) => cell.clone(),
+        None => Value::Null,
+    };
+    let err = match serde_json::from_value(cell.clone()) {
+        Ok(value) => return Ok(value),
+        Err(err) => err,
+    };
+    let fallback = match &cell {
+        Value::Null => Some(serde_json::from_value(Value::from(""))),
+        Value::String(text) => Some(serde_json::from_str(text)),
+        Value::Number(_) | Value::Bool(_) => {
+            Some(serde_json::from_value(Value::from(cell.to_string())))
+        }
+        _ => None,
+    };
+    match fallback {
+        Some(Ok(value)) => Ok(value),
+        _ => Err(crate::LarkError::Json(serde::de::Error::custom(format!(
+            "column `{}`: {}",
+            column, err
+        )))),
+    }
+}
+
+/// 单元格区域的值
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ValueRange {
+    /// 如 `0b12!A1:C3`
+    pub range: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub major_dimension: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub revision: Option<i64>,
+    #[serde(default)]
+    pub values: Vec<Vec<Value>>,
+}
+
+/// [`ReadRange`] 读取的区域
+#[derive(Debug, Clone, Deserialize, lark_sdk::Response)]
+#[serde(rename_all = "camelCase")]
+pub struct RangeData {
+    #[serde(default)]
+    pub revision: Option<i64>,
+    #[serde(default)]
+    pub spreadsheet_token: Option<String>,
+    pub value_range: ValueRange,
+}
+
+/// 读取单个区域
+#[derive(Debug, Clone, lark_sdk::Request)]
+#[request(
+    method = "GET",
+    url = "/open-apis/sheets/v2/spreadsheets/:spreadsheetToken/values/:range",
+    auth = "tenant",
+    validate,
+    response = RangeData
+)]
+pub struct ReadRange {
+    #[request(path = "spreadsheetToken")]
+    pub spreadsheet_token: String,
+    /// 如 `0b12!A1:C100`，`0b12` 为工作表 ID
+    #[request(path)]
+    pub range: String,
+    /// `ToString`、`FormattedValue`、`Formula` 或 `UnformattedValue`
+    #[request(query = "valueRenderOption")]
+    pub value_render_option: Option<String>,
+    /// 为 `FormattedString` 时日期返回格式化后的字符串
+    #[request(query = "dateTimeRenderOption")]
+    pub date_time_render_option: Option<String>,
+}
+
+impl ReadRange {
+    pub fn new(spreadsheet_token: impl Into<String>, range: impl Into<String>) -> Self {
+        ReadRange {
+            spreadsheet_token: spreadsheet_token.into(),
+            range: range.into(),
+            value_render_option: None,
+            date_time_render_option: None,
+        }
+    }
+}
+
+/// 写入的结果
+#[derive(Debug, Clone, Default, Deserialize, lark_sdk::Response)]
+#[serde(rename_all = "camelCase", default)]
+pub struct UpdatedRange {
+    pub revision: Option<i64>,
+    pub spreadsheet_token: Option<String>,
+    pub updated_range: Option<String>,
+    pub updated_rows: Option<u32>,
+    pub updated_columns: Option<u32>,
+    pub updated_cells: Option<u32>,
+}
+
+/// 向单个区域写入，覆盖原有的值
+#[derive(Debug, Clone, lark_sdk::Request)]
+#[request(
+    method = "PUT",
+    url = "/open-apis/sheets/v2/spreadsheets/:spreadsheetToken/values",
+    auth = "tenant",
+    validate,
+    response = UpdatedRange
+)]
+pub struct WriteRange {
+    #[request(path = "spreadsheetToken")]
+    pub spreadsheet_token: String,
+    #[request(body = "valueRange")]
+    pub value_range: ValueRange,
+}
+
+/// [`AppendRange`] 追加的结果
+#[derive(Debug, Clone, Deserialize, lark_sdk::Response)]
+#[serde(rename_all = "camelCase")]
+pub struct AppendedRange {
+    #[serde(default)]
+    pub revision: Option<i64>,
+    #[serde(default)]
+    pub spreadsheet_token: Option<String>,
+    /// 写入后数据所在的区域
+    #[serde(default)]
+    pub table_range: Option<String>,
+    #[serde(default)]
+    pub updates: UpdatedRange,
+}
+
+/// 在区域内数据的下一行追加
+#[derive(Debug, Clone, lark_sdk::Request)]
+#[request(
+    method = "POST",
+    url = "/open-apis/sheets/v2/spreadsheets/:spreadsheetToken/values_append",
+    auth = "tenant",
+    validate,
+    response = AppendedRange
+)]
+pub struct AppendRange {
+    #[request(path = "spreadsheetToken")]
+    pub spreadsheet_token: String,
+    /// `OVERWRITE` 覆盖空行，`INSERT_ROWS` 插入新行
+    #[request(query = "insertDataOption")]
+    pub insert_data_option: Option<String>,
+    #[request(body = "valueRange")]
+    pub value_range: ValueRange,
+}
+
+/// 电子表格的快捷方法，通过 [`Client::sheets`] 获取
+#[cfg(feature = "async")]
+#[derive(Debug, Clone, Copy)]
+pub struct Sheets<'a> {
+    client: &'a Client,
+}
+
+#[cfg(feature = "async")]
+impl Client {
+    /// 电子表格相关的快捷方法
+    pub fn sheets(&self) -> Sheets<'_> {
+        Sheets { client: self }
+    }
+}
+
+#[cfg(feature = "async")]
+impl Sheets<'_> {
+    /// 读取 `range`，第一行为表头
+    pub async fn read_rows<R: SheetRow>(
+        &self,
+        spreadsheet_token: &str,
+        range: &str,
+    ) -> Result<Vec<R>> {
+        let request = ReadRange::new(spreadsheet_token, range);
+        let data = self.client.send(&request).await?.into_data()?;
+        rows_from_values(&data.value_range.values)
+    }
+
+    /// 写入表头和所有行，`range` 需要从表头所在的行开始
+    pub async fn write_rows<R: SheetRow>(
+        &self,
+        spreadsheet_token: &str,
+        range: &str,
+        rows: &[R],
+    ) -> Result<UpdatedRange> {
+        let request = WriteRange {
+            spreadsheet_token: spreadsheet_token.to_string(),
+            value_range: ValueRange {
+                range: range.to_string(),
+                major_dimension: None,
+                revision: None,
+                values: values_from_rows(rows, true)?,
+            },
+        };
+        self.client.send(&request).await?.into_data()
+    }
+
+    /// 在 `range` 已有数据之后追加行，不写入表头。
+    ///
+    /// 先读取 `range` 的第一行作为表头，每个字段写入表头中同名的列，见 [`values_by_header`]
+    pub async fn append_rows<R: SheetRow>(
+        &self,
+        spreadsheet_token: &str,
+        range: &str,
+        rows: &[R],
+    ) -> Result<AppendedRange> {
+        let header_range = first_row(range).unwrap_or_else(|| range.to_string());
+        let request = ReadRange::new(spreadsheet_token, header_range);
+        let data = self.client.send(&request).await?.into_data()?;
+        let header = data
+            .value_range
+            .values
+            .first()
+            .map_or(&[][..], Vec::as_slice);
+        let values = values_by_header(rows, header)?;
+        let request = AppendRange {
+            spreadsheet_token: spreadsheet_token.to_string(),
+            insert_data_option: Some("INSERT_ROWS".to_string()),
+            value_range: ValueRange {
+                range: range.to_string(),
+                major_dimension: None,
+                revision: None,
+                values,
+            },
+        };
+        self.client.send(&request).await?.into_data()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::{Deserialize, Serialize};
+    use serde_json::json;
+
+    use super::*;
+    use crate::Request;
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize, SheetRow)]
+    struct Employee {
+        #[sheet(rename = "姓名")]
+        name: String,
+        age: Option<u32>,
+        #[sheet(rename = "工号")]
+        id: String,
+        #[sheet(skip)]
+        note: String,
+    }
+
+    #[test]
+    fn read_rows() {
+        let values = vec![
+            vec![json!("工号"), json!(" 姓名 "), json!("age"), json!("部门")],
+            vec![json!(1001), json!("张三"), json!("30"), json!("研发")],
+            vec![
+                json!("1002"),
+                json!([{"type": "text", "text": "李"}, {"type": "text", "text": "四"}]),
+                json!(null),
+                json!(null),
+            ],
+            vec![json!(null), json!(""), json!(null), json!(null)],
+        ];
+        let rows: Vec<Employee> = rows_from_values(&values).unwrap();
+        assert_eq!(
+            rows,
+            vec![
+                Employee {
+                    name: "张三".to_string(),
+                    age: Some(30),
+                    id: "1001".to_string(),
+                    note: String::new(),
+                },
+                Employee {
+                    name: "李四".to_string(),
+                    age: None,
+                    id: "1002".to_string(),
+                    note: String::new(),
+                },
+            ]
+        );
+
+        let values = vec![
+            vec![json!("姓名"), json!("age")],
+            vec![json!("王五"), json!("abc")],
+        ];
+        let err = rows_from_values::<Employee>(&values).unwrap_err();
+        assert!(err.to_string().contains("column `age`"), "{}", err);
+    }
+
+    #[test]
+    fn write_rows() {
+        let rows = vec![Employee {
+            name: "张三".to_string(),
+            age: None,
+            id: "1001".to_string(),
+            note: "ignored".to_string(),
+        }];
+        assert_eq!(
+            values_from_rows(&rows, true).unwrap(),
+            vec![
+                vec![json!("姓名"), json!("age"), json!("工号")],
+                vec![json!("张三"), json!(null), json!("1001")],
+            ]
+        );
+
+        let request = AppendRange {
+            spreadsheet_token: "shtcn_1".to_string(),
+            insert_data_option: Some("INSERT_ROWS".to_string()),
+            value_range: ValueRange {
+                range: "0b12!A1:C1".to_string(),
+                major_dimension: None,
+                revision: None,
+                values: values_from_rows(&rows, false).unwrap(),
+            },
+        };
+        assert_eq!(
+            request.url(),
+            "/open-apis/sheets/v2/spreadsheets/shtcn_1/values_append"
+        );
+        let body: Value = serde_json::from_slice(&request.body().unwrap().unwrap()).unwrap();
+        assert_eq!(
+            body,
+            json!({"valueRange": {"range": "0b12!A1:C1", "values": [["张三", null, "1001"]]}})
+        );
+    }
+
+    #[test]
+    fn append_by_header() {
+        let rows = vec![Employee {
+            name: "张三".to_string(),
+            age: Some(30),
+            id: "1001".to_string(),
+            note: String::new(),
+        }];
+        let header = vec![json!("工号"), json!("部门"), json!(" 姓名 "), json!("age")];
+        assert_eq!(
+            values_by_header(&rows, &header).unwrap(),
+            vec![vec![json!("1001"), json!(null), json!("张三"), json!(30)]]
+        );
+
+        let err = values_by_header(&rows, &[json!("工号"), json!("姓名")]).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "invalid request: column `age` is not in the header"
+        );
+    }
+
+    #[cfg(feature = "async")]
+    #[test]
+    fn header_range() {
+        assert_eq!(first_row("0b12!A1:C100").as_deref(), Some("0b12!A1:C1"));
+        assert_eq!(first_row("0b12!B3:AA9").as_deref(), Some("0b12!B3:AA3"));
+        assert_eq!(first_row("0b12"), None);
+    }
+
+    #[cfg(feature = "async")]
+    #[tokio::test]
+    async fn append_rows_from_client() {
+        let (address, handle) = crate::test_util::serve_all(vec![
+            r#"{"code":0,"msg":"success","data":{"valueRange":{"range":"0b12!A1:C1","values":[["age","工号","姓名"]]}}}"#,
+            r#"{"code":0,"msg":"success","data":{"tableRange":"0b12!A2:C2","updates":{"updatedRows":1}}}"#,
+        ]);
+        let client = Client::builder().domain(address).build().unwrap();
+        let rows = vec![Employee {
+            name: "张三".to_string(),
+            age: None,
+            id: "1001".to_string(),
+            note: String::new(),
+        }];
+        let appended = client
+            .sheets()
+            .append_rows("shtcn_1", "0b12!A1:C100", &rows)
+            .await
+            .unwrap();
+        assert_eq!(appended.updates.updated_rows, Some(1));
+
+        let requests = handle.join().unwrap();
+        assert!(requests[0]
+            .starts_with("GET /open-apis/sheets/v2/spreadsheets/shtcn_1/values/0b12%21A1%3AC1 "));
+        assert!(requests[1].ends_with(
+            r#"{"valueRange":{"range":"0b12!A1:C100","values":[[null,"1001","张三"]]}}"#
+        ));
+    }
+
+    #[cfg(feature = "async")]
+    #[tokio::test]
+    async fn read_rows_from_client() {
+        let (address, handle) = crate::test_util::serve(
+            r#"{"code":0,"msg":"success","data":{"revision":3,"spreadsheetToken":"shtcn_1","valueRange":{"majorDimension":"ROWS","range":"0b12!A1:C2","revision":3,"values":[["姓名","工号"],["张三",1001]]}}}"#,
+        );
+        let client = Client::builder().domain(address).build().unwrap();
+        let rows: Vec<Employee> = client
+            .sheets()
+            .read_rows("shtcn_1", "0b12!A1:C2")
+            .await
+            .unwrap();
+        assert_eq!(rows[0].id, "1001");
+        assert_eq!(rows[0].age, None);
+
+        let request = handle.join().unwrap();
+        assert!(request
+            .starts_with("GET /open-apis/sheets/v2/spreadsheets/shtcn_1/values/0b12%21A1%3AC2 "));
+    }
+}
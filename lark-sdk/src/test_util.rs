@@ -0,0 +1,82 @@
+//! 测试使用的 HTTP 服务，不依赖任何特性，异步客户端和 `minimal` 客户端的测试共用。
+
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::sync::Mutex;
+use std::thread::{self, JoinHandle};
+
+use crate::audit::{AuditRecord, AuditSink};
+
+/// 启动只处理一次请求的 HTTP 服务，返回服务地址和收到的请求内容。
+pub(crate) fn serve(body: &'static str) -> (String, JoinHandle<String>) {
+    let (listener, address) = bind();
+    (address, thread::spawn(move || respond(&listener, body)))
+}
+
+/// 启动依次返回 `bodies` 的 HTTP 服务，返回服务地址和收到的所有请求内容。
+pub(crate) fn serve_all(bodies: Vec<&'static str>) -> (String, JoinHandle<Vec<String>>) {
+    let (listener, address) = bind();
+    let handle = thread::spawn(move || {
+        bodies
+            .into_iter()
+            .map(|body| respond(&listener, body))
+            .collect()
+    });
+    (address, handle)
+}
+
+pub(crate) fn bind() -> (TcpListener, String) {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let address = format!("http://{}", listener.local_addr().unwrap());
+    (listener, address)
+}
+
+/// 接收一个请求，返回连接和请求内容。
+pub(crate) fn accept(listener: &TcpListener) -> (TcpStream, String) {
+    let (stream, _) = listener.accept().unwrap();
+    let mut reader = BufReader::new(stream);
+    let mut request = String::new();
+    let mut length = 0;
+    loop {
+        let mut line = String::new();
+        reader.read_line(&mut line).unwrap();
+        if let Some(value) = line.to_ascii_lowercase().strip_prefix("content-length:") {
+            length = value.trim().parse().unwrap();
+        }
+        request.push_str(&line);
+        if line == "\r\n" {
+            break;
+        }
+    }
+    let mut content = vec![0; length];
+    reader.read_exact(&mut content).unwrap();
+    request.push_str(&String::from_utf8_lossy(&content));
+    (reader.into_inner(), request)
+}
+
+fn respond(listener: &TcpListener, body: &str) -> String {
+    let (mut stream, request) = accept(listener);
+    write_response(&mut stream, body);
+    request
+}
+
+/// 返回状态码为 200 的 JSON 响应。
+pub(crate) fn write_response(stream: &mut TcpStream, body: &str) {
+    write!(
+        stream,
+        "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        body.len(),
+        body
+    )
+    .unwrap();
+}
+
+/// 保存收到的所有审计记录
+#[derive(Debug, Default)]
+pub(crate) struct Records(pub(crate) Mutex<Vec<AuditRecord>>);
+
+impl AuditSink for Records {
+    fn record(&self, record: &AuditRecord) {
+        self.0.lock().unwrap().push(record.clone());
+    }
+}
@@ -0,0 +1,538 @@
+//! 时间戳。
+//!
+//! 飞书接口中的时间有秒级时间戳、以字符串表示的毫秒时间戳和 RFC3339 等格式，统一使用
+//! [`LarkTimestamp`] 表示。字段的格式固定时使用对应的 serde 模块：
+//!
+//! | 模块 | 序列化 | 反序列化 |
+//! | --- | --- | --- |
+//! | [`seconds`] | `1672628645` | 数字或数字字符串 |
+//! | [`millis`] | `"1672628645000"` | 数字或数字字符串 |
+//! | [`rfc3339`] | `"2023-01-02T03:04:05Z"` | RFC3339 字符串 |
+//!
+//! 每个模块中的 `option` 子模块用于 `Option<LarkTimestamp>`，`null` 和空字符串为 `None`。
+//! 不指定模块时序列化为毫秒字符串，反序列化时自动识别格式，数字按大小区分秒和毫秒。
+//!
+//! ```
+//! use lark_sdk::time::{self, LarkTimestamp};
+//! use serde::Deserialize;
+//!
+//! #[derive(Deserialize)]
+//! struct Message {
+//!     #[serde(with = "time::millis")]
+//!     create_time: LarkTimestamp,
+//!     #[serde(default, with = "time::rfc3339::option")]
+//!     read_time: Option<LarkTimestamp>,
+//! }
+//!
+//! let message: Message = serde_json::from_str(
+//!     r#"{"create_time":"1672628645000","read_time":"2023-01-02T11:04:05+08:00"}"#,
+//! )
+//! .unwrap();
+//! assert_eq!(message.create_time.seconds(), 1672628645);
+//! assert_eq!(message.read_time, Some(message.create_time));
+//! ```
+
+use std::fmt;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use serde::de::{self, Deserializer, Visitor};
+use serde::{Deserialize, Serialize, Serializer};
+
+use crate::RequestSerialize;
+
+/// 绝对值不小于该值的数字视为毫秒，对应秒级时间戳的 5138 年和毫秒时间戳的 1973 年
+const MILLIS_THRESHOLD: i64 = 100_000_000_000;
+
+/// unix 时间戳，精确到毫秒
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct LarkTimestamp(i64);
+
+impl LarkTimestamp {
+    pub const fn from_millis(millis: i64) -> Self {
+        LarkTimestamp(millis)
+    }
+
+    pub const fn from_seconds(seconds: i64) -> Self {
+        LarkTimestamp(seconds.saturating_mul(1000))
+    }
+
+    pub fn now() -> Self {
+        LarkTimestamp::from(SystemTime::now())
+    }
+
+    pub const fn millis(self) -> i64 {
+        self.0
+    }
+
+    /// 秒级时间戳，向下取整
+    pub const fn seconds(self) -> i64 {
+        self.0.div_euclid(1000)
+    }
+
+    /// 解析 RFC3339 格式的时间，如 `2023-01-02T03:04:05Z`、`2023-01-02T11:04:05.123+08:00`
+    pub fn parse_rfc3339(value: &str) -> Option<Self> {
+        parse_rfc3339(value).map(LarkTimestamp)
+    }
+
+    /// UTC 时区的 RFC3339 格式，毫秒不为 0 时包含毫秒
+    pub fn to_rfc3339(self) -> String {
+        let days = self.0.div_euclid(86_400_000);
+        let millis = self.0.rem_euclid(86_400_000);
+        let (year, month, day) = civil_from_days(days);
+        let seconds = millis / 1000;
+        let mut value = format!(
+            "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}",
+            year,
+            month,
+            day,
+            seconds / 3600,
+            seconds / 60 % 60,
+            seconds % 60
+        );
+        if millis % 1000 != 0 {
+            value.push_str(&format!(".{:03}", millis % 1000));
+        }
+        value.push('Z');
+        value
+    }
+
+    pub fn to_system_time(self) -> SystemTime {
+        let offset = Duration::from_millis(self.0.unsigned_abs());
+        if self.0 >= 0 {
+            UNIX_EPOCH + offset
+        } else {
+            UNIX_EPOCH - offset
+        }
+    }
+}
+
+impl From<SystemTime> for LarkTimestamp {
+    fn from(time: SystemTime) -> Self {
+        let millis = match time.duration_since(UNIX_EPOCH) {
+            Ok(duration) => duration.as_millis() as i64,
+            Err(err) => -(err.duration().as_millis() as i64),
+        };
+        LarkTimestamp(millis)
+    }
+}
+
+impl From<LarkTimestamp> for SystemTime {
+    fn from(timestamp: LarkTimestamp) -> Self {
+        timestamp.to_system_time()
+    }
+}
+
+#[cfg(feature = "chrono")]
+impl LarkTimestamp {
+    /// 超出 chrono 范围时为 `None`
+    pub fn to_chrono(self) -> Option<chrono::DateTime<chrono::Utc>> {
+        use chrono::TimeZone;
+
+        chrono::Utc.timestamp_millis_opt(self.0).single()
+    }
+}
+
+#[cfg(feature = "chrono")]
+impl<Tz: chrono::TimeZone> From<chrono::DateTime<Tz>> for LarkTimestamp {
+    fn from(time: chrono::DateTime<Tz>) -> Self {
+        LarkTimestamp(time.timestamp_millis())
+    }
+}
+
+/// RFC3339 格式
+impl fmt::Display for LarkTimestamp {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.to_rfc3339())
+    }
+}
+
+/// 毫秒时间戳，秒级参数使用 [`param::unix_seconds`](crate::param::unix_seconds)
+impl RequestSerialize for LarkTimestamp {
+    fn to_param(&self) -> Option<String> {
+        Some(self.0.to_string())
+    }
+}
+
+/// 毫秒时间戳字符串
+impl Serialize for LarkTimestamp {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        millis::serialize(self, serializer)
+    }
+}
+
+/// 自动识别格式
+impl<'de> Deserialize<'de> for LarkTimestamp {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        required::<D>(decode(Raw::deserialize(deserializer)?, Format::Auto))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Format {
+    Seconds,
+    Millis,
+    Rfc3339,
+    Auto,
+}
+
+/// 反序列化得到的原始值
+enum Raw {
+    Null,
+    Int(i64),
+    Str(String),
+}
+
+impl<'de> Deserialize<'de> for Raw {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_any(RawVisitor)
+    }
+}
+
+struct RawVisitor;
+
+impl<'de> Visitor<'de> for RawVisitor {
+    type Value = Raw;
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("a timestamp number or string")
+    }
+
+    fn visit_i64<E: de::Error>(self, value: i64) -> Result<Raw, E> {
+        Ok(Raw::Int(value))
+    }
+
+    fn visit_u64<E: de::Error>(self, value: u64) -> Result<Raw, E> {
+        i64::try_from(value)
+            .map(Raw::Int)
+            .map_err(|_| E::custom(format!("timestamp {} out of range", value)))
+    }
+
+    fn visit_f64<E: de::Error>(self, value: f64) -> Result<Raw, E> {
+        Ok(Raw::Int(value as i64))
+    }
+
+    fn visit_str<E: de::Error>(self, value: &str) -> Result<Raw, E> {
+        Ok(Raw::Str(value.to_string()))
+    }
+
+    fn visit_string<E: de::Error>(self, value: String) -> Result<Raw, E> {
+        Ok(Raw::Str(value))
+    }
+
+    fn visit_none<E: de::Error>(self) -> Result<Raw, E> {
+        Ok(Raw::Null)
+    }
+
+    fn visit_unit<E: de::Error>(self) -> Result<Raw, E> {
+        Ok(Raw::Null)
+    }
+
+    fn visit_some<D: Deserializer<'de>>(self, deserializer: D) -> Result<Raw, D::Error> {
+        Raw::deserialize(deserializer)
+    }
+}
+
+/// `null` 和空字符串为 `None`
+fn decode(raw: Raw, format: Format) -> Result<Option<LarkTimestamp>, String> {
+    let number = match raw {
+        Raw::Null => return Ok(None),
+        Raw::Int(number) => number,
+        Raw::Str(value) => {
+            let value = value.trim();
+            if value.is_empty() {
+                return Ok(None);
+            }
+            match value.parse::<i64>() {
+                Ok(number) => number,
+                Err(_) if format == Format::Rfc3339 || format == Format::Auto => {
+                    return LarkTimestamp::parse_rfc3339(value)
+                        .map(Some)
+                        .ok_or_else(|| format!("invalid RFC3339 time `{}`", value));
+                }
+                Err(_) => return Err(format!("invalid unix timestamp `{}`", value)),
+            }
+        }
+    };
+    match format {
+        Format::Seconds => Ok(Some(LarkTimestamp::from_seconds(number))),
+        Format::Millis => Ok(Some(LarkTimestamp::from_millis(number))),
+        Format::Rfc3339 => Err(format!("expected RFC3339 time, found {}", number)),
+        Format::Auto if number.abs() >= MILLIS_THRESHOLD => {
+            Ok(Some(LarkTimestamp::from_millis(number)))
+        }
+        Format::Auto => Ok(Some(LarkTimestamp::from_seconds(number))),
+    }
+}
+
+fn required<'de, D: Deserializer<'de>>(
+    value: Result<Option<LarkTimestamp>, String>,
+) -> Result<LarkTimestamp, D::Error> {
+    use de::Error;
+
+    value
+        .map_err(D::Error::custom)?
+        .ok_or_else(|| D::Error::custom("missing timestamp"))
+}
+
+/// 生成一种格式的 serde 模块
+macro_rules! wire_format {
+    ($(#[$meta:meta])* $name:ident, $format:ident, |$value:ident, $serializer:ident| $serialize:expr) => {
+        $(#[$meta])*
+        pub mod $name {
+            use serde::{Deserialize, Deserializer, Serializer};
+
+            use super::{decode, required, Format, LarkTimestamp, Raw};
+
+            pub fn serialize<S: Serializer>(
+                $value: &LarkTimestamp,
+                $serializer: S,
+            ) -> Result<S::Ok, S::Error> {
+                $serialize
+            }
+
+            pub fn deserialize<'de, D: Deserializer<'de>>(
+                deserializer: D,
+            ) -> Result<LarkTimestamp, D::Error> {
+                required::<D>(decode(Raw::deserialize(deserializer)?, Format::$format))
+            }
+
+            /// 用于 `Option<LarkTimestamp>`，需要同时使用 `#[serde(default)]`
+            pub mod option {
+                use serde::de::Error;
+                use serde::{Deserialize, Deserializer, Serializer};
+
+                use super::super::{decode, Format, LarkTimestamp, Raw};
+
+                pub fn serialize<S: Serializer>(
+                    value: &Option<LarkTimestamp>,
+                    serializer: S,
+                ) -> Result<S::Ok, S::Error> {
+                    match value {
+                        Some(value) => super::serialize(value, serializer),
+                        None => serializer.serialize_none(),
+                    }
+                }
+
+                pub fn deserialize<'de, D: Deserializer<'de>>(
+                    deserializer: D,
+                ) -> Result<Option<LarkTimestamp>, D::Error> {
+                    decode(Raw::deserialize(deserializer)?, Format::$format).map_err(D::Error::custom)
+                }
+            }
+        }
+    };
+}
+
+wire_format!(
+    /// 秒级时间戳，序列化为数字
+    seconds,
+    Seconds,
+    |value, serializer| serializer.serialize_i64(value.seconds())
+);
+
+wire_format!(
+    /// 毫秒时间戳，序列化为字符串
+    millis,
+    Millis,
+    |value, serializer| serializer.collect_str(&value.millis())
+);
+
+wire_format!(
+    /// RFC3339 格式，序列化为 UTC 时间
+    rfc3339,
+    Rfc3339,
+    |value, serializer| serializer.serialize_str(&value.to_rfc3339())
+);
+
+fn parse_rfc3339(value: &str) -> Option<i64> {
+    let bytes = value.as_bytes();
+    if bytes.len() < 20 || !matches!(bytes[10], b'T' | b't' | b' ') {
+        return None;
+    }
+    let number = |range: std::ops::Range<usize>| -> Option<i64> {
+        let digits = value.get(range)?;
+        if !digits.bytes().all(|b| b.is_ascii_digit()) {
+            return None;
+        }
+        digits.parse().ok()
+    };
+    if bytes[4] != b'-' || bytes[7] != b'-' || bytes[13] != b':' || bytes[16] != b':' {
+        return None;
+    }
+    let (year, month, day) = (number(0..4)?, number(5..7)?, number(8..10)?);
+    let (hour, minute, second) = (number(11..13)?, number(14..16)?, number(17..19)?);
+    if !(1..=12).contains(&month) || day < 1 || day > days_in_month(year, month) {
+        return None;
+    }
+    if hour > 23 || minute > 59 || second > 60 {
+        return None;
+    }
+
+    let mut rest = &value[19..];
+    let mut millis = 0;
+    if let Some(fraction) = rest.strip_prefix('.') {
+        let len = fraction.bytes().take_while(u8::is_ascii_digit).count();
+        if len == 0 {
+            return None;
+        }
+        // 只保留毫秒
+        let digits = &fraction[..len.min(3)];
+        millis = digits.parse::<i64>().ok()? * 10_i64.pow(3 - digits.len() as u32);
+        rest = &fraction[len..];
+    }
+    let offset = match rest {
+        "Z" | "z" => 0,
+        _ => {
+            let sign = match rest.as_bytes().first()? {
+                b'+' => 1,
+                b'-' => -1,
+                _ => return None,
+            };
+            let offset = rest.get(1..)?;
+            if offset.len() != 5 || offset.as_bytes()[2] != b':' {
+                return None;
+            }
+            let hours: i64 = offset[..2].parse().ok()?;
+            let minutes: i64 = offset[3..].parse().ok()?;
+            sign * (hours * 3600 + minutes * 60)
+        }
+    };
+    let seconds = days_from_civil(year, month, day) * 86_400 + hour * 3600 + minute * 60 + second;
+    Some((seconds - offset) * 1000 + millis)
+}
+
+fn days_in_month(year: i64, month: i64) -> i64 {
+    match month {
+        2 if year % 4 == 0 && (year % 100 != 0 || year % 400 == 0) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+/// 公历日期距 1970-01-01 的天数
+fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let day_of_year = (153 * ((month + 9) % 12) + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146_097 + day_of_era - 719_468
+}
+
+/// [`days_from_civil`] 的逆运算
+fn civil_from_days(days: i64) -> (i64, i64, i64) {
+    let days = days + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days - era * 146_097;
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month + 2) / 5 + 1;
+    let month = if month < 10 { month + 3 } else { month - 9 };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+    (year, month, day)
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Times {
+        #[serde(with = "seconds")]
+        seconds: LarkTimestamp,
+        #[serde(with = "millis")]
+        millis: LarkTimestamp,
+        #[serde(with = "rfc3339")]
+        rfc3339: LarkTimestamp,
+        #[serde(default, with = "millis::option")]
+        optional: Option<LarkTimestamp>,
+        auto: LarkTimestamp,
+    }
+
+    #[test]
+    fn wire_formats() {
+        let time = LarkTimestamp::from_seconds(1672628645);
+        let times: Times = serde_json::from_value(json!({
+            "seconds": "1672628645",
+            "millis": 1672628645000_i64,
+            "rfc3339": "2023-01-02T11:04:05+08:00",
+            "optional": "",
+            "auto": "1672628645000",
+        }))
+        .unwrap();
+        assert_eq!(
+            times,
+            Times {
+                seconds: time,
+                millis: time,
+                rfc3339: time,
+                optional: None,
+                auto: time,
+            }
+        );
+        assert_eq!(
+            serde_json::to_value(&times).unwrap(),
+            json!({
+                "seconds": 1672628645,
+                "millis": "1672628645000",
+                "rfc3339": "2023-01-02T03:04:05Z",
+                "optional": null,
+                "auto": "1672628645000",
+            })
+        );
+        for auto in [json!(1672628645), json!("2023-01-02T03:04:05.000Z")] {
+            assert_eq!(serde_json::from_value::<LarkTimestamp>(auto).unwrap(), time);
+        }
+
+        let err = serde_json::from_value::<Times>(json!({
+            "seconds": "yesterday", "millis": 0, "rfc3339": "1970-01-01T00:00:00Z", "auto": 0,
+        }))
+        .unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("invalid unix timestamp `yesterday`"));
+        let err = serde_json::from_value::<Times>(json!({
+            "seconds": 0, "millis": 0, "rfc3339": 1672628645, "auto": 0,
+        }))
+        .unwrap_err();
+        assert!(err.to_string().contains("expected RFC3339 time"));
+    }
+
+    #[test]
+    fn rfc3339_round_trip() {
+        let time = LarkTimestamp::parse_rfc3339("2024-02-29T23:59:59.5-01:30").unwrap();
+        assert_eq!(time.to_rfc3339(), "2024-03-01T01:29:59.500Z");
+        assert_eq!(LarkTimestamp::parse_rfc3339(&time.to_string()), Some(time));
+        assert_eq!(
+            LarkTimestamp::from_millis(-1).to_rfc3339(),
+            "1969-12-31T23:59:59.999Z"
+        );
+        assert_eq!(LarkTimestamp::from_millis(-1).seconds(), -1);
+        for invalid in [
+            "2023-02-29T00:00:00Z",
+            "2023-01-02 03:04:05",
+            "2023-01-02T03:04:05+0800",
+            "2023-1-02T03:04:05Z",
+        ] {
+            assert_eq!(LarkTimestamp::parse_rfc3339(invalid), None, "{}", invalid);
+        }
+        let now = LarkTimestamp::now();
+        assert_eq!(LarkTimestamp::from(now.to_system_time()), now);
+    }
+
+    #[cfg(feature = "chrono")]
+    #[test]
+    fn chrono_conversion() {
+        use chrono::{TimeZone, Utc};
+
+        let time = Utc.with_ymd_and_hms(2023, 1, 2, 3, 4, 5).unwrap();
+        let timestamp = LarkTimestamp::from(time);
+        assert_eq!(timestamp, LarkTimestamp::from_seconds(1672628645));
+        assert_eq!(timestamp.to_chrono(), Some(time));
+    }
+}
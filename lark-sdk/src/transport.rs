@@ -0,0 +1,381 @@
+//! 发送 HTTP 请求的传输层。
+//!
+//! [`Client`](crate::Client) 只通过 [`HttpTransport`] 发送接口请求，默认使用基于 reqwest 的
+//! [`ReqwestTransport`]。实现该 trait 即可替换为其他 HTTP 库或测试替身，通过
+//! [`ClientBuilder::transport`](crate::ClientBuilder::transport) 设置。
+//!
+//! 同步客户端使用 [`BlockingTransport`]，`blocking` 特性默认使用 `ReqwestBlockingTransport`，
+//! `minimal` 特性默认使用 `minimal::UreqTransport`。
+//!
+//! 传输层只负责收发，访问凭证、gzip 压缩、频率限制和响应解析都由客户端处理。连接失败、超时等
+//! 错误可以返回对应 [`ErrorKind`](std::io::ErrorKind) 的 [`LarkError::Io`](crate::LarkError::Io)，
+//! 重试时会按照可恢复的错误处理。
+
+#[cfg(any(feature = "async", feature = "minimal"))]
+use std::fmt;
+#[cfg(feature = "async")]
+use std::future::Future;
+#[cfg(feature = "async")]
+use std::pin::Pin;
+#[cfg(any(feature = "async", feature = "minimal"))]
+use std::sync::Arc;
+
+#[cfg(feature = "async")]
+use crate::client::http_method;
+use crate::Method;
+#[cfg(any(feature = "async", feature = "minimal"))]
+use crate::Result;
+
+/// 传输层发送的请求，请求头已经包含 `Content-Type`、`Authorization` 等
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HttpRequest {
+    pub method: Method,
+    /// 包含域名的完整地址，不含查询参数
+    pub url: String,
+    pub query: Vec<(String, String)>,
+    pub headers: Vec<(String, String)>,
+    pub body: Option<Vec<u8>>,
+}
+
+impl HttpRequest {
+    /// 带有编码后查询参数的地址
+    pub fn full_url(&self) -> String {
+        if self.query.is_empty() {
+            return self.url.clone();
+        }
+        let query = serde_urlencoded::to_string(&self.query).unwrap_or_default();
+        let separator = if self.url.contains('?') { '&' } else { '?' };
+        format!("{}{}{}", self.url, separator, query)
+    }
+}
+
+/// 传输层收到的响应，`body` 为解压后的内容
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct HttpResponse {
+    pub status: u16,
+    pub headers: Vec<(String, String)>,
+    pub body: Vec<u8>,
+}
+
+impl HttpResponse {
+    /// 名称为 `name` 的响应头，不区分大小写
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(n, _)| n.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+/// [`HttpTransport::send`] 返回的 future
+#[cfg(feature = "async")]
+pub type TransportFuture<'a> = Pin<Box<dyn Future<Output = Result<HttpResponse>> + Send + 'a>>;
+
+/// 发送 HTTP 请求，由多个请求并发调用
+#[cfg(feature = "async")]
+pub trait HttpTransport: Send + Sync + fmt::Debug {
+    fn send(&self, request: HttpRequest) -> TransportFuture<'_>;
+}
+
+#[cfg(feature = "async")]
+impl<T: HttpTransport + ?Sized> HttpTransport for Arc<T> {
+    fn send(&self, request: HttpRequest) -> TransportFuture<'_> {
+        (**self).send(request)
+    }
+}
+
+/// 基于 reqwest 的传输层，超时、代理、证书等使用 reqwest 客户端的配置
+#[cfg(feature = "async")]
+#[derive(Debug, Clone, Default)]
+pub struct ReqwestTransport {
+    client: reqwest::Client,
+}
+
+#[cfg(feature = "async")]
+impl ReqwestTransport {
+    pub fn new(client: reqwest::Client) -> Self {
+        ReqwestTransport { client }
+    }
+}
+
+#[cfg(feature = "async")]
+impl HttpTransport for ReqwestTransport {
+    fn send(&self, request: HttpRequest) -> TransportFuture<'_> {
+        Box::pin(async move {
+            let mut builder = self
+                .client
+                .request(http_method(request.method), &request.url)
+                .query(&request.query);
+            for (name, value) in request.headers {
+                builder = builder.header(name, value);
+            }
+            if let Some(body) = request.body {
+                builder = builder.body(body);
+            }
+            let response = builder.send().await?;
+            let status = response.status().as_u16();
+            let headers = header_pairs(response.headers());
+            let body = response.bytes().await?.to_vec();
+            Ok(HttpResponse {
+                status,
+                headers,
+                body,
+            })
+        })
+    }
+}
+
+/// 同步发送 HTTP 请求，由同步客户端在调用线程中使用
+#[cfg(any(feature = "blocking", feature = "minimal"))]
+pub trait BlockingTransport: Send + Sync + fmt::Debug {
+    fn send(&self, request: HttpRequest) -> Result<HttpResponse>;
+}
+
+#[cfg(any(feature = "blocking", feature = "minimal"))]
+impl<T: BlockingTransport + ?Sized> BlockingTransport for Arc<T> {
+    fn send(&self, request: HttpRequest) -> Result<HttpResponse> {
+        (**self).send(request)
+    }
+}
+
+/// 基于 reqwest 同步客户端的传输层，超时、代理、证书等使用 reqwest 客户端的配置
+#[cfg(feature = "blocking")]
+#[derive(Debug, Clone, Default)]
+pub struct ReqwestBlockingTransport {
+    client: reqwest::blocking::Client,
+}
+
+#[cfg(feature = "blocking")]
+impl ReqwestBlockingTransport {
+    pub fn new(client: reqwest::blocking::Client) -> Self {
+        ReqwestBlockingTransport { client }
+    }
+}
+
+#[cfg(feature = "blocking")]
+impl BlockingTransport for ReqwestBlockingTransport {
+    fn send(&self, request: HttpRequest) -> Result<HttpResponse> {
+        let mut builder = self
+            .client
+            .request(http_method(request.method), &request.url)
+            .query(&request.query);
+        for (name, value) in request.headers {
+            builder = builder.header(name, value);
+        }
+        if let Some(body) = request.body {
+            builder = builder.body(body);
+        }
+        let response = builder.send()?;
+        Ok(HttpResponse {
+            status: response.status().as_u16(),
+            headers: header_pairs(response.headers()),
+            body: response.bytes()?.to_vec(),
+        })
+    }
+}
+
+/// 忽略值不是合法字符串的响应头
+#[cfg(feature = "async")]
+pub(crate) fn header_pairs(headers: &reqwest::header::HeaderMap) -> Vec<(String, String)> {
+    headers
+        .iter()
+        .filter_map(|(name, value)| {
+            Some((name.as_str().to_string(), value.to_str().ok()?.to_string()))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    #[cfg(any(feature = "async", feature = "minimal"))]
+    use std::sync::Mutex;
+
+    #[cfg(any(feature = "async", feature = "minimal"))]
+    use serde_json::json;
+
+    use super::*;
+    #[cfg(feature = "async")]
+    use crate::{Client, RequestParts};
+
+    /// 记录请求并返回固定响应的测试替身
+    #[cfg(feature = "async")]
+    #[derive(Debug, Default)]
+    struct Recorder {
+        requests: Mutex<Vec<HttpRequest>>,
+    }
+
+    #[cfg(feature = "async")]
+    impl HttpTransport for Recorder {
+        fn send(&self, request: HttpRequest) -> TransportFuture<'_> {
+            Box::pin(async move {
+                let body = if request.url.ends_with("/tenant_access_token/internal") {
+                    json!({"code": 0, "msg": "ok", "tenant_access_token": "t-1", "expire": 7200})
+                } else {
+                    json!({"code": 0, "msg": "success", "data": {"ok": true}})
+                };
+                self.requests.lock().unwrap().push(request);
+                Ok(HttpResponse {
+                    status: 200,
+                    headers: vec![("x-tt-logid".to_string(), "log-1".to_string())],
+                    body: body.to_string().into_bytes(),
+                })
+            })
+        }
+    }
+
+    /// 同步客户端使用的测试替身，响应与 [`Recorder`] 一致
+    #[cfg(any(feature = "blocking", feature = "minimal"))]
+    #[derive(Debug, Default)]
+    struct BlockingRecorder {
+        requests: Mutex<Vec<HttpRequest>>,
+    }
+
+    #[cfg(any(feature = "blocking", feature = "minimal"))]
+    impl BlockingTransport for BlockingRecorder {
+        fn send(&self, request: HttpRequest) -> Result<HttpResponse> {
+            let body = if request.url.ends_with("/tenant_access_token/internal") {
+                json!({"code": 0, "msg": "ok", "tenant_access_token": "t-1", "expire": 7200})
+            } else {
+                json!({"code": 0, "msg": "success", "data": {"ok": true}})
+            };
+            self.requests.lock().unwrap().push(request);
+            Ok(HttpResponse {
+                status: 200,
+                headers: Vec::new(),
+                body: body.to_string().into_bytes(),
+            })
+        }
+    }
+
+    #[cfg(any(feature = "blocking", feature = "minimal"))]
+    fn demo_parts() -> crate::RequestParts {
+        crate::RequestParts {
+            method: Method::Get,
+            url: "/open-apis/demo".to_string(),
+            query: Vec::new(),
+            headers: Vec::new(),
+            content_type: "application/json; charset=utf-8".to_string(),
+            body: None,
+            endpoint: None,
+            auth: crate::AuthType::Tenant,
+        }
+    }
+
+    /// 凭证请求和接口请求都通过传输层发送
+    #[cfg(any(feature = "blocking", feature = "minimal"))]
+    fn assert_blocking_requests(transport: &BlockingRecorder) {
+        let requests = transport.requests.lock().unwrap();
+        assert_eq!(requests.len(), 2);
+        assert_eq!(
+            requests[0].url,
+            "http://lark.test/open-apis/auth/v3/tenant_access_token/internal"
+        );
+        assert_eq!(requests[1].url, "http://lark.test/open-apis/demo");
+        assert!(requests[1]
+            .headers
+            .contains(&("Authorization".to_string(), "Bearer t-1".to_string())));
+    }
+
+    #[cfg(feature = "blocking")]
+    #[test]
+    fn blocking_transport() {
+        let transport = Arc::new(BlockingRecorder::default());
+        let client = crate::blocking::Client::builder()
+            .domain("http://lark.test")
+            .app_credentials("cli_1", "secret")
+            .transport(transport.clone())
+            .build()
+            .unwrap();
+        let response: crate::BodyResponse<serde_json::Value> =
+            client.send_parts(demo_parts()).unwrap();
+        assert_eq!(response.into_data().unwrap(), json!({"ok": true}));
+        assert_blocking_requests(&transport);
+    }
+
+    #[cfg(feature = "minimal")]
+    #[test]
+    fn minimal_transport() {
+        let transport = Arc::new(BlockingRecorder::default());
+        let client = crate::minimal::Client::builder()
+            .domain("http://lark.test")
+            .app_credentials("cli_1", "secret")
+            .transport(transport.clone())
+            .build()
+            .unwrap();
+        let response: crate::BodyResponse<serde_json::Value> =
+            client.send_parts(demo_parts()).unwrap();
+        assert_eq!(response.into_data().unwrap(), json!({"ok": true}));
+        assert_blocking_requests(&transport);
+    }
+
+    #[test]
+    fn full_url() {
+        let mut request = HttpRequest {
+            method: Method::Get,
+            url: "https://open.feishu.cn/open-apis/contact/v3/users".to_string(),
+            query: Vec::new(),
+            headers: Vec::new(),
+            body: None,
+        };
+        assert_eq!(request.full_url(), request.url);
+        request.query = vec![
+            ("page_size".to_string(), "50".to_string()),
+            ("page_token".to_string(), "a b/c".to_string()),
+        ];
+        assert_eq!(
+            request.full_url(),
+            "https://open.feishu.cn/open-apis/contact/v3/users?page_size=50&page_token=a+b%2Fc"
+        );
+    }
+
+    #[cfg(feature = "async")]
+    #[tokio::test]
+    async fn custom_transport() {
+        let transport = Arc::new(Recorder::default());
+        let client = Client::builder()
+            .domain("http://lark.test/")
+            .app_credentials("cli_1", "secret")
+            .gzip_request_body(1 << 20)
+            .transport(transport.clone())
+            .build()
+            .unwrap();
+
+        let parts = RequestParts {
+            method: Method::Post,
+            url: "/open-apis/im/v1/messages".to_string(),
+            query: vec![("receive_id_type".to_string(), "open_id".to_string())],
+            headers: Vec::new(),
+            content_type: "application/json; charset=utf-8".to_string(),
+            body: Some(br#"{"receive_id":"ou_1"}"#.to_vec()),
+            endpoint: None,
+            auth: crate::AuthType::Tenant,
+        };
+        let response: crate::BodyResponse<serde_json::Value> =
+            client.send_parts(parts).await.unwrap();
+        assert_eq!(response.into_data().unwrap(), json!({"ok": true}));
+
+        let requests = transport.requests.lock().unwrap();
+        assert_eq!(requests.len(), 2);
+        assert_eq!(
+            requests[0].url,
+            "http://lark.test/open-apis/auth/v3/tenant_access_token/internal"
+        );
+        let request = &requests[1];
+        assert_eq!(request.method, Method::Post);
+        assert_eq!(
+            request.full_url(),
+            "http://lark.test/open-apis/im/v1/messages?receive_id_type=open_id"
+        );
+        assert!(request.headers.contains(&(
+            "Content-Type".to_string(),
+            "application/json; charset=utf-8".to_string()
+        )));
+        assert!(request
+            .headers
+            .contains(&("Authorization".to_string(), "Bearer t-1".to_string())));
+        assert_eq!(
+            request.body.as_deref(),
+            Some(&br#"{"receive_id":"ou_1"}"#[..])
+        );
+    }
+}
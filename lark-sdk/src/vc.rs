@@ -0,0 +1,479 @@
+//! 视频会议。
+//!
+//! 会议结束后，录制文件生成为妙记，[`GetRecording`] 返回妙记的链接。下载录制的音视频需要再通过妙记的接口
+//! 获取临时下载链接，[`download_recording`] 会依次完成这些步骤并将文件写入磁盘：
+//!
+//! ```no_run
+//! use lark_sdk::vc::download_recording;
+//!
+//! # async fn run(client: lark_sdk::Client) -> lark_sdk::Result<()> {
+//! let size = download_recording(&client, "6911188411934433028", "archive/6911188411934433028.mp4").await?;
+//! println!("{} bytes", size);
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! 预定会议室时，[`FreeRoomQuery`] 列出会议室并查询忙闲，返回在指定时间段内空闲的会议室：
+//!
+//! ```no_run
+//! use lark_sdk::time::LarkTimestamp;
+//! use lark_sdk::vc::FreeRoomQuery;
+//!
+//! # async fn run(client: lark_sdk::Client) -> lark_sdk::Result<()> {
+//! let start = LarkTimestamp::parse_rfc3339("2023-06-01T14:00:00+08:00").unwrap();
+//! let end = LarkTimestamp::parse_rfc3339("2023-06-01T15:00:00+08:00").unwrap();
+//! let rooms = FreeRoomQuery::new(start, end)
+//!     .room_level_id("omb_xxx")
+//!     .min_capacity(6)
+//!     .find(&client)
+//!     .await?;
+//! for room in rooms {
+//!     println!("{} {}", room.room_id, room.name);
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+use std::collections::HashMap;
+#[cfg(feature = "async")]
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use crate::enums::UserIdType;
+use crate::id::IdType;
+use crate::time::{self, LarkTimestamp};
+#[cfg(feature = "async")]
+use crate::{Client, LarkError, Result};
+
+/// 会议的录制
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct Recording {
+    /// 妙记的链接，如 `https://meetings.feishu.cn/minutes/obcnxxx`
+    pub url: String,
+    /// 录制的时长，毫秒
+    #[serde(default)]
+    pub duration: String,
+}
+
+impl Recording {
+    /// 妙记的 token，为链接的最后一段
+    pub fn minute_token(&self) -> Option<&str> {
+        let path = self.url.split(['?', '#']).next()?;
+        path.trim_end_matches('/')
+            .rsplit('/')
+            .next()
+            .filter(|token| !token.is_empty() && !token.contains(':'))
+    }
+}
+
+/// [`GetRecording`] 的结果
+#[derive(Debug, Clone, Deserialize, lark_sdk::Response)]
+pub struct RecordingData {
+    pub recording: Recording,
+}
+
+/// 获取会议的录制，会议结束且录制生成后才能获取
+#[derive(Debug, Clone, lark_sdk::Request)]
+#[request(
+    method = "GET",
+    url = "/open-apis/vc/v1/meetings/:meeting_id/recording",
+    auth = "tenant",
+    validate,
+    response = RecordingData
+)]
+pub struct GetRecording {
+    #[request(path)]
+    pub meeting_id: String,
+}
+
+/// 被授权查看录制的对象
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PermissionObject {
+    /// 用户 ID、群 ID 或租户 ID，`object_type` 为 4 时不需要
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    /// 1：用户，2：群，3：租户内所有人，4：互联网上所有人
+    #[serde(rename = "type")]
+    pub object_type: i32,
+    /// 1：查看
+    pub permission: i32,
+}
+
+impl PermissionObject {
+    /// 用户，ID 类型与请求的 `user_id_type` 一致
+    pub fn user(user_id: impl Into<String>) -> Self {
+        PermissionObject {
+            id: Some(user_id.into()),
+            object_type: 1,
+            permission: 1,
+        }
+    }
+
+    /// 群
+    pub fn chat(chat_id: impl Into<String>) -> Self {
+        PermissionObject {
+            id: Some(chat_id.into()),
+            object_type: 2,
+            permission: 1,
+        }
+    }
+
+    /// 租户内所有人
+    pub fn tenant(tenant_key: impl Into<String>) -> Self {
+        PermissionObject {
+            id: Some(tenant_key.into()),
+            object_type: 3,
+            permission: 1,
+        }
+    }
+}
+
+/// 授权或取消授权查看会议录制
+#[derive(Debug, Clone, lark_sdk::Request)]
+#[request(
+    method = "PATCH",
+    url = "/open-apis/vc/v1/meetings/:meeting_id/recording/set_permission",
+    auth = "tenant",
+    validate,
+    empty
+)]
+pub struct SetRecordingPermission {
+    #[request(path)]
+    pub meeting_id: String,
+    /// 用户 ID 的类型，如 `open_id`
+    #[request(query)]
+    pub user_id_type: Option<UserIdType>,
+    #[request(body)]
+    pub permission_objects: Vec<PermissionObject>,
+    /// 0：授权，1：取消授权
+    #[request(body)]
+    pub action_type: Option<i32>,
+}
+
+impl SetRecordingPermission {
+    /// 授权用户查看录制
+    pub fn grant_users<T: IdType>(meeting_id: impl Into<String>, users: &[T]) -> Self {
+        SetRecordingPermission {
+            meeting_id: meeting_id.into(),
+            user_id_type: Some(UserIdType::of::<T>()),
+            permission_objects: users
+                .iter()
+                .map(|user| PermissionObject::user(user.as_str()))
+                .collect(),
+            action_type: Some(0),
+        }
+    }
+
+    /// 改为取消授权
+    pub fn revoke(mut self) -> Self {
+        self.action_type = Some(1);
+        self
+    }
+}
+
+/// 妙记音视频的下载链接
+#[derive(Debug, Clone, Deserialize, lark_sdk::Response)]
+pub struct MinuteMedia {
+    /// 临时下载链接，有效期 1 天
+    pub download_url: String,
+}
+
+/// 获取妙记的音视频下载链接
+#[derive(Debug, Clone, lark_sdk::Request)]
+#[request(
+    method = "GET",
+    url = "/open-apis/minutes/v1/minutes/:minute_token/media",
+    auth = "tenant",
+    validate,
+    response = MinuteMedia
+)]
+pub struct GetMinuteMedia {
+    #[request(path)]
+    pub minute_token: String,
+}
+
+/// 下载会议 `meeting_id` 的录制到 `path`，返回文件的大小。
+///
+/// 文件边下载边写入，不会整个读入内存；下载失败时 `path` 中可能留有不完整的文件
+#[cfg(feature = "async")]
+pub async fn download_recording(
+    client: &Client,
+    meeting_id: &str,
+    path: impl AsRef<Path>,
+) -> Result<u64> {
+    let request = GetRecording {
+        meeting_id: meeting_id.to_string(),
+    };
+    let recording = client.send(&request).await?.into_data()?.recording;
+    let minute_token = recording.minute_token().ok_or_else(|| {
+        LarkError::InvalidRequest(format!(
+            "recording url {} has no minute token",
+            recording.url
+        ))
+    })?;
+    let request = GetMinuteMedia {
+        minute_token: minute_token.to_string(),
+    };
+    let media = client.send(&request).await?.into_data()?;
+    let mut file = tokio::io::BufWriter::new(tokio::fs::File::create(path).await?);
+    client.download(&media.download_url, &mut file).await
+}
+
+/// 每次查询忙闲的最多会议室数
+pub const FREEBUSY_ROOMS_LIMIT: usize = 20;
+
+/// 会议室
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct Room {
+    pub room_id: String,
+    pub name: String,
+    /// 可容纳的人数
+    #[serde(default)]
+    pub capacity: u32,
+    #[serde(default)]
+    pub description: Option<String>,
+    /// 会议室所在的层级
+    #[serde(default)]
+    pub room_level_id: Option<String>,
+    /// 是否已停用
+    #[serde(default)]
+    pub is_disabled: bool,
+}
+
+/// [`ListRooms`] 的结果
+#[derive(Debug, Clone, Deserialize, lark_sdk::Response)]
+pub struct RoomList {
+    #[serde(default)]
+    pub rooms: Vec<Room>,
+    #[serde(default)]
+    pub page_token: Option<String>,
+    #[serde(default)]
+    pub has_more: bool,
+}
+
+/// 查询会议室列表
+#[derive(Debug, Clone, Default, lark_sdk::Request)]
+#[request(
+    method = "GET",
+    url = "/open-apis/vc/v1/rooms",
+    auth = "tenant",
+    response = RoomList
+)]
+pub struct ListRooms {
+    #[request(query)]
+    pub page_size: Option<u32>,
+    #[request(query, page_token)]
+    pub page_token: Option<String>,
+    /// 层级 ID，为空时查询租户根层级下的会议室
+    #[request(query)]
+    pub room_level_id: Option<String>,
+    #[request(query)]
+    pub user_id_type: Option<UserIdType>,
+}
+
+/// 会议室被占用的时间段
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct BusyTime {
+    #[serde(with = "time::rfc3339")]
+    pub start_time: LarkTimestamp,
+    #[serde(with = "time::rfc3339")]
+    pub end_time: LarkTimestamp,
+    /// 日程的 ID
+    #[serde(default)]
+    pub uid: Option<String>,
+}
+
+/// [`GetRoomFreeBusy`] 的结果
+#[derive(Debug, Clone, Deserialize, lark_sdk::Response)]
+pub struct RoomFreeBusy {
+    #[serde(default, with = "time::rfc3339")]
+    pub time_min: LarkTimestamp,
+    #[serde(default, with = "time::rfc3339")]
+    pub time_max: LarkTimestamp,
+    /// 会议室 ID 到查询时间段内被占用的时间段，空闲的会议室为空列表
+    #[serde(default)]
+    pub free_busy: HashMap<String, Vec<BusyTime>>,
+}
+
+/// 查询会议室的忙闲
+#[derive(Debug, Clone, lark_sdk::Request)]
+#[request(
+    method = "GET",
+    url = "/open-apis/meeting_room/freebusy/batch_get",
+    auth = "tenant",
+    response = RoomFreeBusy
+)]
+pub struct GetRoomFreeBusy {
+    /// 最多 [`FREEBUSY_ROOMS_LIMIT`] 个
+    #[request(query, style = "repeat", max_len = FREEBUSY_ROOMS_LIMIT)]
+    pub room_ids: Vec<String>,
+    /// 开始时间，以 RFC3339 格式发送
+    #[request(query, serialize_with = "crate::param::rfc3339")]
+    pub time_min: LarkTimestamp,
+    #[request(query, serialize_with = "crate::param::rfc3339")]
+    pub time_max: LarkTimestamp,
+}
+
+/// 查询在 `time_min` 到 `time_max` 之间空闲的会议室
+#[cfg(feature = "async")]
+#[derive(Debug, Clone)]
+pub struct FreeRoomQuery {
+    time_min: LarkTimestamp,
+    time_max: LarkTimestamp,
+    room_level_id: Option<String>,
+    min_capacity: u32,
+}
+
+#[cfg(feature = "async")]
+impl FreeRoomQuery {
+    /// 查询 `time_min` 到 `time_max` 之间的忙闲，可以使用 `SystemTime` 或开启 `chrono` 特性后的
+    /// `DateTime`
+    pub fn new(time_min: impl Into<LarkTimestamp>, time_max: impl Into<LarkTimestamp>) -> Self {
+        FreeRoomQuery {
+            time_min: time_min.into(),
+            time_max: time_max.into(),
+            room_level_id: None,
+            min_capacity: 0,
+        }
+    }
+
+    /// 只查询层级 `room_level_id` 下的会议室，默认为租户的根层级
+    pub fn room_level_id(mut self, room_level_id: impl Into<String>) -> Self {
+        self.room_level_id = Some(room_level_id.into());
+        self
+    }
+
+    /// 可容纳的人数不少于 `capacity`
+    pub fn min_capacity(mut self, capacity: u32) -> Self {
+        self.min_capacity = capacity;
+        self
+    }
+
+    /// 列出所有会议室，返回未停用、容量满足且在时间段内空闲的会议室，顺序与会议室列表一致
+    #[cfg(feature = "async")]
+    pub async fn find(&self, client: &Client) -> Result<Vec<Room>> {
+        let mut request = ListRooms {
+            page_size: Some(100),
+            room_level_id: self.room_level_id.clone(),
+            ..ListRooms::default()
+        };
+        let mut rooms = Vec::new();
+        loop {
+            let data = client.send(&request).await?.into_data()?;
+            rooms.extend(
+                data.rooms
+                    .into_iter()
+                    .filter(|room| !room.is_disabled && room.capacity >= self.min_capacity),
+            );
+            match data.page_token.filter(|_| data.has_more) {
+                Some(page_token) => request.page_token = Some(page_token),
+                None => break,
+            }
+        }
+
+        let mut busy = HashMap::new();
+        for chunk in rooms.chunks(FREEBUSY_ROOMS_LIMIT) {
+            let request = GetRoomFreeBusy {
+                room_ids: chunk.iter().map(|room| room.room_id.clone()).collect(),
+                time_min: self.time_min,
+                time_max: self.time_max,
+            };
+            busy.extend(client.send(&request).await?.into_data()?.free_busy);
+        }
+        rooms.retain(|room| busy.get(&room.room_id).is_none_or(Vec::is_empty));
+        Ok(rooms)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::id::OpenId;
+    use crate::test_util::{serve, serve_all};
+    use crate::Request;
+
+    #[test]
+    fn recording_minute_token() {
+        let recording = |url: &str| Recording {
+            url: url.to_string(),
+            duration: String::new(),
+        };
+        assert_eq!(
+            recording("https://meetings.feishu.cn/minutes/obcn_1?from=vc").minute_token(),
+            Some("obcn_1")
+        );
+        assert_eq!(
+            recording("https://meetings.feishu.cn/minutes/obcn_1/").minute_token(),
+            Some("obcn_1")
+        );
+        assert_eq!(recording("").minute_token(), None);
+    }
+
+    #[test]
+    fn permission_body() {
+        let request = SetRecordingPermission::grant_users("m1", &[OpenId::new("ou_1")]).revoke();
+        let body: serde_json::Value =
+            serde_json::from_slice(&request.body().unwrap().unwrap()).unwrap();
+        assert_eq!(
+            body,
+            serde_json::json!({
+                "permission_objects": [{"id": "ou_1", "type": 1, "permission": 1}],
+                "action_type": 1
+            })
+        );
+        assert_eq!(
+            request.url(),
+            "/open-apis/vc/v1/meetings/m1/recording/set_permission"
+        );
+    }
+
+    #[tokio::test]
+    async fn download_to_file() {
+        let (media_address, media) = serve("MP4DATA");
+        let media_body = format!(
+            r#"{{"code":0,"msg":"success","data":{{"download_url":"{}/obcn_1.mp4"}}}}"#,
+            media_address
+        );
+        let (address, handle) = serve_all(vec![
+            r#"{"code":0,"msg":"success","data":{"recording":{"url":"https://meetings.feishu.cn/minutes/obcn_1","duration":"30000"}}}"#,
+            Box::leak(media_body.into_boxed_str()),
+        ]);
+        let client = Client::builder().domain(address).build().unwrap();
+        let path = std::env::temp_dir().join(format!("lark-sdk-vc-{}.mp4", std::process::id()));
+        let size = download_recording(&client, "m1", &path).await.unwrap();
+        assert_eq!(size, 7);
+        assert_eq!(std::fs::read(&path).unwrap(), b"MP4DATA");
+        std::fs::remove_file(&path).unwrap();
+
+        let requests = handle.join().unwrap();
+        assert!(requests[0].starts_with("GET /open-apis/vc/v1/meetings/m1/recording "));
+        assert!(requests[1].starts_with("GET /open-apis/minutes/v1/minutes/obcn_1/media "));
+        assert!(media.join().unwrap().starts_with("GET /obcn_1.mp4 "));
+    }
+
+    #[tokio::test]
+    async fn find_free_rooms() {
+        let (address, handle) = serve_all(vec![
+            r#"{"code":0,"msg":"success","data":{"rooms":[{"room_id":"r1","name":"A","capacity":10},{"room_id":"r2","name":"B","capacity":4}],"page_token":"p1","has_more":true}}"#,
+            r#"{"code":0,"msg":"success","data":{"rooms":[{"room_id":"r3","name":"C","capacity":8},{"room_id":"r4","name":"D","capacity":20,"is_disabled":true}],"has_more":false}}"#,
+            r#"{"code":0,"msg":"success","data":{"time_min":"2023-06-01T14:00:00+08:00","time_max":"2023-06-01T15:00:00+08:00","free_busy":{"r1":[{"start_time":"2023-06-01T14:30:00+08:00","end_time":"2023-06-01T16:00:00+08:00","uid":"e1"}],"r3":[]}}}"#,
+        ]);
+        let client = Client::builder().domain(address).build().unwrap();
+        let start = LarkTimestamp::parse_rfc3339("2023-06-01T14:00:00+08:00").unwrap();
+        let end = LarkTimestamp::parse_rfc3339("2023-06-01T15:00:00+08:00").unwrap();
+        let rooms = FreeRoomQuery::new(start, end)
+            .min_capacity(6)
+            .find(&client)
+            .await
+            .unwrap();
+        let ids: Vec<&str> = rooms.iter().map(|room| room.room_id.as_str()).collect();
+        assert_eq!(ids, ["r3"]);
+
+        let requests = handle.join().unwrap();
+        assert!(requests[1].starts_with("GET /open-apis/vc/v1/rooms?page_size=100&page_token=p1 "));
+        assert!(requests[2].starts_with(
+            "GET /open-apis/meeting_room/freebusy/batch_get?room_ids=r1&room_ids=r3&time_min=2023-06-01T06%3A00%3A00Z&time_max=2023-06-01T07%3A00%3A00Z "
+        ));
+    }
+}
@@ -0,0 +1,122 @@
+//! 接口版本。
+//!
+//! 部分接口同时存在多个版本且请求、响应的结构不同，各版本的请求类型放在服务下以版本命名的模块中，
+//! 如 `corehr::v1`、`corehr::v2`，旧版本的请求可以一直直接使用。
+//!
+//! 同时支持多个版本的操作实现 [`VersionedRequest`]，将各版本的结果转换为同一个类型。
+//! [`Client::send_versioned`] 默认使用最新的版本，可以通过
+//! [`ClientBuilder::max_api_version`](crate::ClientBuilder::max_api_version) 限制某个服务使用的
+//! 最高版本，用于新版本未开通权限或私有化部署尚未升级的情况。
+
+use std::fmt;
+
+#[cfg(feature = "async")]
+use futures_util::future::BoxFuture;
+
+#[cfg(feature = "async")]
+use crate::{Client, LarkError, Result};
+
+/// 接口版本，即请求路径中服务名称后的 `v1`、`v2` 等
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct ApiVersion(pub u8);
+
+impl ApiVersion {
+    pub const V1: ApiVersion = ApiVersion(1);
+    pub const V2: ApiVersion = ApiVersion(2);
+    pub const V3: ApiVersion = ApiVersion(3);
+    pub const V4: ApiVersion = ApiVersion(4);
+
+    /// 请求路径中的版本，如 `/open-apis/corehr/v2/employees/batch_get` 为 v2
+    pub fn of(endpoint: &str) -> Option<ApiVersion> {
+        let path = endpoint.strip_prefix("/open-apis/")?;
+        let version = path.split('/').nth(1)?.strip_prefix('v')?;
+        version.parse().ok().map(ApiVersion)
+    }
+
+    /// `versions` 中不高于 `max` 的最新版本，没有限制时为最新版本
+    pub fn select(versions: &[ApiVersion], max: Option<ApiVersion>) -> Option<ApiVersion> {
+        versions
+            .iter()
+            .copied()
+            .filter(|version| max.is_none_or(|max| *version <= max))
+            .max()
+    }
+}
+
+impl fmt::Display for ApiVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "v{}", self.0)
+    }
+}
+
+/// 提供多个接口版本的操作
+#[cfg(feature = "async")]
+pub trait VersionedRequest: Sync {
+    /// 各版本的结果转换后的类型
+    type Output: Send;
+
+    /// 服务名称，与请求路径中 `/open-apis/` 后的部分一致，如 `corehr`
+    const SERVICE: &'static str;
+
+    /// 支持的版本
+    const VERSIONS: &'static [ApiVersion];
+
+    /// 使用 `version` 版本的接口发送请求
+    fn send_version<'a>(
+        &'a self,
+        client: &'a Client,
+        version: ApiVersion,
+    ) -> BoxFuture<'a, Result<Self::Output>>;
+}
+
+#[cfg(feature = "async")]
+impl Client {
+    /// 使用 [`VersionedRequest::VERSIONS`] 中不高于该服务
+    /// [`max_api_version`](Client::max_api_version) 的最新版本发送请求
+    pub async fn send_versioned<R: VersionedRequest>(&self, request: &R) -> Result<R::Output> {
+        let max = self.max_api_version(R::SERVICE);
+        let version = ApiVersion::select(R::VERSIONS, max).ok_or_else(|| {
+            LarkError::InvalidRequest(format!(
+                "no {} api version supported up to {:?}",
+                R::SERVICE,
+                max
+            ))
+        })?;
+        request.send_version(self, version).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_and_select() {
+        assert_eq!(
+            ApiVersion::of("/open-apis/corehr/v2/employees/batch_get"),
+            Some(ApiVersion::V2)
+        );
+        assert_eq!(
+            ApiVersion::of("/open-apis/auth/v3/tenant_access_token/internal"),
+            Some(ApiVersion::V3)
+        );
+        assert_eq!(ApiVersion::of("/open-apis/corehr/departments"), None);
+        assert_eq!(ApiVersion::of("/callback/ws/endpoint"), None);
+        assert_eq!(ApiVersion::V2.to_string(), "v2");
+
+        let versions = [ApiVersion::V1, ApiVersion::V2];
+        assert_eq!(ApiVersion::select(&versions, None), Some(ApiVersion::V2));
+        assert_eq!(
+            ApiVersion::select(&versions, Some(ApiVersion::V1)),
+            Some(ApiVersion::V1)
+        );
+        assert_eq!(
+            ApiVersion::select(&versions, Some(ApiVersion::V3)),
+            Some(ApiVersion::V2)
+        );
+        assert_eq!(
+            ApiVersion::select(&[ApiVersion::V2], Some(ApiVersion::V1)),
+            None
+        );
+    }
+}
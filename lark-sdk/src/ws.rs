@@ -0,0 +1,798 @@
+//! 长连接模式接收事件。
+//!
+//! 长连接模式下不需要公网地址，也不需要处理解密和 URL 验证：[`WsClient`] 使用应用凭证获取连接地址，
+//! 建立 WebSocket 连接后接收事件推送，交给 [`EventDispatcher`] 处理，再将处理结果回复给开放平台。
+//!
+//! ```no_run
+//! use lark_sdk::event::im::MessageReceiveEvent;
+//! use lark_sdk::event::{Event, EventDispatcher};
+//! use lark_sdk::ws::{AckStrategy, WsClient};
+//! use lark_sdk::Client;
+//!
+//! # async fn run() -> lark_sdk::Result<()> {
+//! let dispatcher = EventDispatcher::new().on(|event: Event<MessageReceiveEvent>| async move {
+//!     println!("{:?}", event.event.message.content);
+//!     Ok(())
+//! });
+//! WsClient::new(Client::new(), "cli_xxx", "secret", dispatcher)
+//!     .ack_strategy(AckStrategy::OnReceipt)
+//!     .run()
+//!     .await
+//! # }
+//! ```
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use base64::Engine;
+use futures_util::{SinkExt, StreamExt};
+use prost::Message as _;
+use serde::{Deserialize, Serialize};
+use tokio::sync::mpsc;
+use tokio_tungstenite::tungstenite::Message;
+
+use crate::event::{Dispatched, EventDispatcher};
+use crate::{Body, BodyResponse, Client, LarkError, Method, Request, Result};
+
+/// 事件的确认方式
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum AckStrategy {
+    /// 处理函数执行完成后再确认，处理失败时回复失败状态，开放平台会重新推送该事件，
+    /// 保证事件至少被成功处理一次
+    #[default]
+    AfterHandler,
+    /// 收到事件后立即确认，之后再执行处理函数，处理失败只记录日志。
+    /// 延迟更低，但进程退出或处理失败时事件会丢失。
+    ///
+    /// 卡片回传交互需要处理函数给出响应内容，总是在处理完成后确认。
+    OnReceipt,
+}
+
+/// 长连接的状态变化，见 [`WsClient::on_connection_event`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ConnectionEvent {
+    /// 连接已建立
+    Connected,
+    /// 连接断开或建立连接失败，`reason` 为原因
+    Disconnected { reason: String },
+    /// 将在 `delay` 之后进行第 `attempt` 次重连，连接成功后重新从 1 开始计数
+    Reconnecting { attempt: i64, delay: Duration },
+}
+
+/// 长连接的统计数据，从创建客户端开始累计
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ConnectionStats {
+    /// 建立连接的次数
+    pub connects: u64,
+    /// 连接断开或建立连接失败的次数
+    pub disconnects: u64,
+    /// 重连的次数
+    pub reconnect_attempts: u64,
+    /// 收到的事件数
+    pub events: u64,
+    /// 处理失败的事件数
+    pub event_errors: u64,
+}
+
+#[derive(Default)]
+struct Counters {
+    connects: AtomicU64,
+    disconnects: AtomicU64,
+    reconnect_attempts: AtomicU64,
+    events: AtomicU64,
+    event_errors: AtomicU64,
+}
+
+type ConnectionHook = Box<dyn Fn(&ConnectionEvent) + Send + Sync>;
+
+/// 长连接客户端，见[模块文档](self)
+pub struct WsClient {
+    client: Client,
+    app_id: String,
+    app_secret: String,
+    dispatcher: Arc<EventDispatcher>,
+    ack_strategy: AckStrategy,
+    hook: Option<ConnectionHook>,
+    counters: Arc<Counters>,
+}
+
+impl WsClient {
+    pub fn new(
+        client: Client,
+        app_id: impl Into<String>,
+        app_secret: impl Into<String>,
+        dispatcher: EventDispatcher,
+    ) -> Self {
+        WsClient {
+            client,
+            app_id: app_id.into(),
+            app_secret: app_secret.into(),
+            dispatcher: Arc::new(dispatcher),
+            ack_strategy: AckStrategy::default(),
+            hook: None,
+            counters: Arc::default(),
+        }
+    }
+
+    /// 事件的确认方式，默认为 [`AckStrategy::AfterHandler`]
+    pub fn ack_strategy(mut self, strategy: AckStrategy) -> Self {
+        self.ack_strategy = strategy;
+        self
+    }
+
+    /// 连接状态变化时调用 `hook`，可用于在连接频繁断开时告警。`hook` 在接收事件的任务中同步调用，
+    /// 不应阻塞
+    pub fn on_connection_event<F>(mut self, hook: F) -> Self
+    where
+        F: Fn(&ConnectionEvent) + Send + Sync + 'static,
+    {
+        self.hook = Some(Box::new(hook));
+        self
+    }
+
+    /// 当前的统计数据，`WsClient` 放在 `Arc` 中时可以在其他任务中读取
+    pub fn stats(&self) -> ConnectionStats {
+        let counters = &self.counters;
+        ConnectionStats {
+            connects: counters.connects.load(Ordering::Relaxed),
+            disconnects: counters.disconnects.load(Ordering::Relaxed),
+            reconnect_attempts: counters.reconnect_attempts.load(Ordering::Relaxed),
+            events: counters.events.load(Ordering::Relaxed),
+            event_errors: counters.event_errors.load(Ordering::Relaxed),
+        }
+    }
+
+    fn emit(&self, event: ConnectionEvent) {
+        let counter = match &event {
+            ConnectionEvent::Connected => &self.counters.connects,
+            ConnectionEvent::Disconnected { .. } => &self.counters.disconnects,
+            ConnectionEvent::Reconnecting { .. } => &self.counters.reconnect_attempts,
+        };
+        counter.fetch_add(1, Ordering::Relaxed);
+        if let Some(hook) = &self.hook {
+            hook(&event);
+        }
+    }
+
+    /// 建立连接并持续接收事件，连接断开后按照开放平台下发的配置重连。
+    ///
+    /// 获取连接地址时接口返回错误（如应用凭证错误）或超过重连次数时返回错误。
+    pub async fn run(&self) -> Result<()> {
+        let mut attempt = 0;
+        let mut config = ClientConfig::default();
+        loop {
+            let reason = match self.endpoint().await {
+                Ok(endpoint) => {
+                    config = endpoint.client_config;
+                    match self.connect(&endpoint.url, &mut config, &mut attempt).await {
+                        Ok(_) => "connection closed".to_string(),
+                        Err(err) => err.to_string(),
+                    }
+                }
+                Err(err @ LarkError::Api { .. }) => return Err(err),
+                Err(err) => format!("failed to get endpoint: {}", err),
+            };
+            log::warn!("websocket disconnected: {}", reason);
+            self.emit(ConnectionEvent::Disconnected { reason });
+
+            if config.reconnect_count >= 0 && attempt >= config.reconnect_count {
+                return Err(LarkError::WebSocket(format!(
+                    "gave up after {} reconnect attempts",
+                    attempt
+                )));
+            }
+            attempt += 1;
+            let delay = if attempt == 1 {
+                jitter(config.reconnect_nonce)
+            } else {
+                Duration::from_secs(config.reconnect_interval)
+            };
+            log::info!("reconnect attempt {} in {:?}", attempt, delay);
+            self.emit(ConnectionEvent::Reconnecting { attempt, delay });
+            tokio::time::sleep(delay).await;
+        }
+    }
+
+    async fn endpoint(&self) -> Result<Endpoint> {
+        let request = EndpointRequest {
+            app_id: self.app_id.clone(),
+            app_secret: self.app_secret.clone(),
+        };
+        self.client.send(&request).await?.into_data()
+    }
+
+    /// 保持一个连接直到断开，连接成功后将 `attempt` 重置为 0
+    async fn connect(&self, url: &str, config: &mut ClientConfig, attempt: &mut i64) -> Result<()> {
+        let service_id = query_param(url, "service_id")
+            .and_then(|id| id.parse().ok())
+            .unwrap_or_default();
+        let (stream, _) = tokio_tungstenite::connect_async(url)
+            .await
+            .map_err(ws_error)?;
+        log::info!("websocket connected");
+        *attempt = 0;
+        self.emit(ConnectionEvent::Connected);
+
+        let (mut sink, mut stream) = stream.split();
+        let (tx, mut rx) = mpsc::unbounded_channel::<Frame>();
+        // 回复和心跳都经由 tx 发送，由写任务统一写入连接
+        let writer = tokio::spawn(async move {
+            while let Some(frame) = rx.recv().await {
+                if let Err(err) = sink.send(Message::binary(frame.encode_to_vec())).await {
+                    log::warn!("failed to write websocket frame: {}", err);
+                    break;
+                }
+            }
+        });
+        let ping_interval = Arc::new(AtomicU64::new(config.ping_interval));
+        let pinger = tokio::spawn({
+            let tx = tx.clone();
+            let ping_interval = ping_interval.clone();
+            async move {
+                loop {
+                    if tx.send(Frame::control(service_id, "ping")).is_err() {
+                        break;
+                    }
+                    let interval = ping_interval.load(Ordering::Relaxed).max(1);
+                    tokio::time::sleep(Duration::from_secs(interval)).await;
+                }
+            }
+        });
+
+        let mut fragments = Fragments::default();
+        let result = loop {
+            let data = match stream.next().await {
+                Some(Ok(Message::Binary(data))) => data,
+                Some(Ok(Message::Close(_))) | None => break Ok(()),
+                Some(Ok(_)) => continue,
+                Some(Err(err)) => break Err(ws_error(err)),
+            };
+            let frame = match Frame::decode(data.as_ref()) {
+                Ok(frame) => frame,
+                Err(err) => {
+                    log::warn!("invalid websocket frame: {}", err);
+                    continue;
+                }
+            };
+            match frame.method {
+                METHOD_CONTROL if frame.header("type") == Some("pong") => {
+                    config.update(&frame.payload);
+                    ping_interval.store(config.ping_interval, Ordering::Relaxed);
+                }
+                METHOD_DATA => {
+                    if let Some(frame) = fragments.push(frame) {
+                        self.handle(frame, tx.clone());
+                    }
+                }
+                _ => {}
+            }
+        };
+        pinger.abort();
+        writer.abort();
+        result
+    }
+
+    fn handle(&self, mut frame: Frame, tx: mpsc::UnboundedSender<Frame>) {
+        let dispatcher = self.dispatcher.clone();
+        let counters = self.counters.clone();
+        counters.events.fetch_add(1, Ordering::Relaxed);
+        let payload = std::mem::take(&mut frame.payload);
+        let ack_first =
+            self.ack_strategy == AckStrategy::OnReceipt && frame.header("type") != Some("card");
+        if ack_first {
+            let _ = tx.send(frame.clone().ack(200, None, Duration::ZERO));
+        }
+        tokio::spawn(async move {
+            let start = Instant::now();
+            let result = dispatcher.dispatch(&payload).await;
+            let elapsed = start.elapsed();
+            let (code, body) = match result {
+                Ok(Dispatched::Responded(body)) => (200, Some(body)),
+                Ok(_) => (200, None),
+                Err(err) => {
+                    counters.event_errors.fetch_add(1, Ordering::Relaxed);
+                    let message_id = frame.header("message_id").unwrap_or_default();
+                    log::error!("failed to handle websocket event {}: {}", message_id, err);
+                    (500, None)
+                }
+            };
+            if !ack_first {
+                let _ = tx.send(frame.ack(code, body, elapsed));
+            }
+        });
+    }
+}
+
+fn ws_error(err: impl std::fmt::Display) -> LarkError {
+    LarkError::WebSocket(err.to_string())
+}
+
+fn query_param<'a>(url: &'a str, name: &str) -> Option<&'a str> {
+    let query = url.split_once('?')?.1;
+    query
+        .split('&')
+        .filter_map(|pair| pair.split_once('='))
+        .find(|(key, _)| *key == name)
+        .map(|(_, value)| value)
+}
+
+/// 第一次重连前随机等待 `[0, nonce)` 秒，避免大量客户端同时重连
+fn jitter(nonce: u64) -> Duration {
+    if nonce == 0 {
+        return Duration::ZERO;
+    }
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|now| now.subsec_nanos() as u64)
+        .unwrap_or_default();
+    Duration::from_millis(nanos % (nonce * 1000))
+}
+
+/// 获取长连接地址
+struct EndpointRequest {
+    app_id: String,
+    app_secret: String,
+}
+
+impl Request for EndpointRequest {
+    type Target = BodyResponse<Endpoint>;
+
+    fn method(&self) -> Method {
+        Method::Post
+    }
+
+    fn url(&self) -> String {
+        "/callback/ws/endpoint".to_string()
+    }
+
+    fn endpoint(&self) -> Option<&'static str> {
+        Some("/callback/ws/endpoint")
+    }
+
+    fn body(&self) -> Result<Option<Vec<u8>>> {
+        let body = serde_json::json!({ "AppID": self.app_id, "AppSecret": self.app_secret });
+        Ok(Some(serde_json::to_vec(&body)?))
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct Endpoint {
+    #[serde(rename = "URL")]
+    url: String,
+    #[serde(rename = "ClientConfig", default)]
+    client_config: ClientConfig,
+}
+
+impl Body for Endpoint {}
+
+/// 开放平台下发的连接配置，时间单位为秒
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase", default)]
+struct ClientConfig {
+    /// 重连次数，负数表示不限制
+    reconnect_count: i64,
+    reconnect_interval: u64,
+    reconnect_nonce: u64,
+    ping_interval: u64,
+}
+
+impl Default for ClientConfig {
+    fn default() -> Self {
+        ClientConfig {
+            reconnect_count: -1,
+            reconnect_interval: 120,
+            reconnect_nonce: 30,
+            ping_interval: 120,
+        }
+    }
+}
+
+impl ClientConfig {
+    /// pong 中可能带有新的配置，只更新其中出现的字段
+    fn update(&mut self, payload: &[u8]) {
+        let update: ClientConfigUpdate = match serde_json::from_slice(payload) {
+            Ok(update) => update,
+            Err(_) => return,
+        };
+        if let Some(reconnect_count) = update.reconnect_count {
+            self.reconnect_count = reconnect_count;
+        }
+        if let Some(reconnect_interval) = update.reconnect_interval {
+            self.reconnect_interval = reconnect_interval;
+        }
+        if let Some(reconnect_nonce) = update.reconnect_nonce {
+            self.reconnect_nonce = reconnect_nonce;
+        }
+        if let Some(ping_interval) = update.ping_interval {
+            self.ping_interval = ping_interval;
+        }
+    }
+}
+
+/// pong 中的配置，没有出现的字段保持原来的值
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct ClientConfigUpdate {
+    reconnect_count: Option<i64>,
+    reconnect_interval: Option<u64>,
+    reconnect_nonce: Option<u64>,
+    ping_interval: Option<u64>,
+}
+
+const METHOD_CONTROL: i32 = 0;
+const METHOD_DATA: i32 = 1;
+
+/// 长连接中传输的帧，使用 protobuf 编码
+#[derive(Clone, PartialEq, prost::Message)]
+struct Frame {
+    #[prost(uint64, tag = "1")]
+    seq_id: u64,
+    #[prost(uint64, tag = "2")]
+    log_id: u64,
+    #[prost(int32, tag = "3")]
+    service: i32,
+    #[prost(int32, tag = "4")]
+    method: i32,
+    #[prost(message, repeated, tag = "5")]
+    headers: Vec<FrameHeader>,
+    #[prost(string, optional, tag = "6")]
+    payload_encoding: Option<String>,
+    #[prost(string, optional, tag = "7")]
+    payload_type: Option<String>,
+    #[prost(bytes = "vec", tag = "8")]
+    payload: Vec<u8>,
+    #[prost(string, optional, tag = "9")]
+    log_id_new: Option<String>,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+struct FrameHeader {
+    #[prost(string, tag = "1")]
+    key: String,
+    #[prost(string, tag = "2")]
+    value: String,
+}
+
+/// 对事件的回复，`data` 为 base64 编码的响应内容
+#[derive(Serialize)]
+struct AckPayload {
+    code: u16,
+    headers: HashMap<String, String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    data: Option<String>,
+}
+
+impl Frame {
+    fn control(service: i32, frame_type: &str) -> Self {
+        Frame {
+            service,
+            method: METHOD_CONTROL,
+            headers: vec![FrameHeader {
+                key: "type".to_string(),
+                value: frame_type.to_string(),
+            }],
+            ..Frame::default()
+        }
+    }
+
+    fn header(&self, key: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|header| header.key == key)
+            .map(|header| header.value.as_str())
+    }
+
+    /// 使用收到的帧回复处理结果
+    fn ack(mut self, code: u16, body: Option<serde_json::Value>, elapsed: Duration) -> Self {
+        let biz_rt = elapsed.as_millis().to_string();
+        self.headers.push(FrameHeader {
+            key: "biz_rt".to_string(),
+            value: biz_rt,
+        });
+        let payload = AckPayload {
+            code,
+            headers: HashMap::new(),
+            data: body
+                .map(|body| base64::engine::general_purpose::STANDARD.encode(body.to_string())),
+        };
+        self.payload = serde_json::to_vec(&payload).unwrap_or_default();
+        self
+    }
+}
+
+/// 一个事件最多拆分的帧数，超过时丢弃该事件
+const MAX_FRAGMENTS: usize = 256;
+
+/// 未收齐所有分片的事件保留的时间
+const FRAGMENT_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// 较大的事件会拆分成多个帧推送，`sum` 为总数，`seq` 为序号。
+///
+/// 每个连接使用单独的 `Fragments`，断开后未收齐的分片随之丢弃。
+#[derive(Default)]
+struct Fragments {
+    pending: HashMap<String, PendingFragments>,
+}
+
+struct PendingFragments {
+    parts: Vec<Option<Vec<u8>>>,
+    started: Instant,
+}
+
+impl Fragments {
+    /// 收齐所有分片后返回拼接了内容的帧
+    fn push(&mut self, frame: Frame) -> Option<Frame> {
+        self.push_at(frame, Instant::now())
+    }
+
+    fn push_at(&mut self, mut frame: Frame, now: Instant) -> Option<Frame> {
+        self.pending.retain(|message_id, pending| {
+            let alive = now.saturating_duration_since(pending.started) < FRAGMENT_TIMEOUT;
+            if !alive {
+                log::warn!("dropped incomplete websocket event {}", message_id);
+            }
+            alive
+        });
+        let sum: usize = frame
+            .header("sum")
+            .and_then(|sum| sum.parse().ok())
+            .unwrap_or(1);
+        if sum <= 1 {
+            return Some(frame);
+        }
+        let message_id = frame.header("message_id")?.to_string();
+        if sum > MAX_FRAGMENTS {
+            log::warn!(
+                "dropped websocket event {} split into {} frames, the limit is {}",
+                message_id,
+                sum,
+                MAX_FRAGMENTS
+            );
+            return None;
+        }
+        let seq: usize = frame.header("seq").and_then(|seq| seq.parse().ok())?;
+        let pending = self
+            .pending
+            .entry(message_id.clone())
+            .or_insert_with(|| PendingFragments {
+                parts: vec![None; sum],
+                started: now,
+            });
+        if pending.parts.len() != sum {
+            return None;
+        }
+        *pending.parts.get_mut(seq)? = Some(std::mem::take(&mut frame.payload));
+        if pending.parts.iter().any(Option::is_none) {
+            return None;
+        }
+        let pending = self.pending.remove(&message_id)?;
+        frame.payload = pending.parts.into_iter().flatten().flatten().collect();
+        Some(frame)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tokio::net::TcpListener;
+    use tokio_tungstenite::accept_async;
+
+    use super::*;
+    use crate::event::im::ChatDisbandedEvent;
+    use crate::event::{envelope, Event};
+    use crate::test_util::serve;
+
+    fn event_frame(message_id: &str, chat_id: &str) -> Message {
+        let payload = envelope(
+            "im.chat.disbanded_v1",
+            serde_json::json!({"chat_id": chat_id, "operator_id": {"open_id": "ou_1"}}),
+        );
+        let frame = Frame {
+            service: 1,
+            method: METHOD_DATA,
+            headers: [
+                ("type", "event"),
+                ("message_id", message_id),
+                ("sum", "1"),
+                ("seq", "0"),
+            ]
+            .iter()
+            .map(|(key, value)| FrameHeader {
+                key: key.to_string(),
+                value: value.to_string(),
+            })
+            .collect(),
+            payload,
+            ..Frame::default()
+        };
+        Message::binary(frame.encode_to_vec())
+    }
+
+    /// 启动 WebSocket 服务，依次推送 `frames` 并返回收到的所有回复
+    async fn ws_server(frames: Vec<Message>) -> (String, tokio::task::JoinHandle<Vec<Frame>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!(
+            "ws://{}/ws?device_id=d1&service_id=7",
+            listener.local_addr().unwrap()
+        );
+        let handle = tokio::spawn(async move {
+            let (stream, _) = listener.accept().await.unwrap();
+            let mut ws = accept_async(stream).await.unwrap();
+            let expected = frames.len();
+            for frame in frames {
+                ws.send(frame).await.unwrap();
+            }
+            let mut acks = Vec::new();
+            while acks.len() < expected {
+                let data = ws.next().await.unwrap().unwrap().into_data();
+                let frame = Frame::decode(data.as_ref()).unwrap();
+                if frame.method == METHOD_DATA {
+                    acks.push(frame);
+                }
+            }
+            ws.close(None).await.unwrap();
+            acks
+        });
+        (url, handle)
+    }
+
+    fn endpoint_body(url: &str) -> &'static str {
+        let body = serde_json::json!({
+            "code": 0,
+            "msg": "ok",
+            "data": {
+                "URL": url,
+                "ClientConfig": {
+                    "ReconnectCount": 0,
+                    "ReconnectInterval": 1,
+                    "ReconnectNonce": 0,
+                    "PingInterval": 120
+                }
+            }
+        });
+        Box::leak(body.to_string().into_boxed_str())
+    }
+
+    fn ack_code(frame: &Frame) -> u64 {
+        let payload: serde_json::Value = serde_json::from_slice(&frame.payload).unwrap();
+        payload["code"].as_u64().unwrap()
+    }
+
+    fn dispatcher() -> EventDispatcher {
+        EventDispatcher::new().on(|event: Event<ChatDisbandedEvent>| async move {
+            match event.event.chat_id.as_str() {
+                "oc_bad" => Err(LarkError::InvalidEvent("bad chat".to_string())),
+                _ => Ok(()),
+            }
+        })
+    }
+
+    #[tokio::test]
+    async fn ack_after_handler() {
+        let (url, server) =
+            ws_server(vec![event_frame("m1", "oc_1"), event_frame("m2", "oc_bad")]).await;
+        let (domain, endpoint) = serve(endpoint_body(&url));
+        let client = Client::builder().domain(domain).build().unwrap();
+        let ws = WsClient::new(client, "cli_1", "secret", dispatcher());
+        let result = ws.run().await;
+        assert!(matches!(result, Err(LarkError::WebSocket(_))));
+
+        let request = endpoint.join().unwrap();
+        assert!(request.starts_with("POST /callback/ws/endpoint"));
+        assert!(request.contains(r#""AppID":"cli_1""#));
+        let mut acks = server.await.unwrap();
+        acks.sort_by_key(|frame| frame.header("message_id").unwrap().to_string());
+        assert_eq!(acks.iter().map(ack_code).collect::<Vec<_>>(), [200, 500]);
+        assert!(acks[0].header("biz_rt").is_some());
+    }
+
+    #[tokio::test]
+    async fn ack_on_receipt() {
+        let (url, server) =
+            ws_server(vec![event_frame("m1", "oc_1"), event_frame("m2", "oc_bad")]).await;
+        let (domain, _) = serve(endpoint_body(&url));
+        let client = Client::builder().domain(domain).build().unwrap();
+        let ws = WsClient::new(client, "cli_1", "secret", dispatcher())
+            .ack_strategy(AckStrategy::OnReceipt);
+        assert!(ws.run().await.is_err());
+        let acks = server.await.unwrap();
+        assert_eq!(acks.iter().map(ack_code).collect::<Vec<_>>(), [200, 200]);
+    }
+
+    #[tokio::test]
+    async fn connection_events() {
+        use std::sync::Mutex;
+
+        let (url, server) = ws_server(vec![event_frame("m1", "oc_bad")]).await;
+        let (domain, _) = serve(endpoint_body(&url));
+        let client = Client::builder().domain(domain).build().unwrap();
+        let events = Arc::new(Mutex::new(Vec::new()));
+        let seen = events.clone();
+        let ws = WsClient::new(client, "cli_1", "secret", dispatcher())
+            .on_connection_event(move |event| seen.lock().unwrap().push(event.clone()));
+        assert!(ws.run().await.is_err());
+        server.await.unwrap();
+
+        let events = events.lock().unwrap();
+        assert_eq!(events[0], ConnectionEvent::Connected);
+        assert!(matches!(events[1], ConnectionEvent::Disconnected { .. }));
+        assert_eq!(events.len(), 2);
+        let stats = ws.stats();
+        assert_eq!((stats.connects, stats.disconnects), (1, 1));
+        assert_eq!((stats.events, stats.event_errors), (1, 1));
+        assert_eq!(stats.reconnect_attempts, 0);
+    }
+
+    fn part(message_id: &str, sum: &str, seq: &str, payload: &str) -> Frame {
+        Frame {
+            method: METHOD_DATA,
+            headers: [("message_id", message_id), ("sum", sum), ("seq", seq)]
+                .iter()
+                .map(|(key, value)| FrameHeader {
+                    key: key.to_string(),
+                    value: value.to_string(),
+                })
+                .collect(),
+            payload: payload.as_bytes().to_vec(),
+            ..Frame::default()
+        }
+    }
+
+    #[test]
+    fn fragments() {
+        let mut fragments = Fragments::default();
+        assert!(fragments.push(part("m1", "2", "1", "world")).is_none());
+        let frame = fragments.push(part("m1", "2", "0", "hello ")).unwrap();
+        assert_eq!(frame.payload, b"hello world");
+        assert!(fragments.pending.is_empty());
+
+        // 分片数超过上限时不分配空间，直接丢弃
+        assert!(fragments.push(part("m2", "4000000000", "0", "x")).is_none());
+        assert!(fragments.pending.is_empty());
+
+        // 超时未收齐的分片被丢弃
+        let start = Instant::now();
+        assert!(fragments
+            .push_at(part("m3", "2", "0", "a"), start)
+            .is_none());
+        assert_eq!(fragments.pending.len(), 1);
+        let later = start + FRAGMENT_TIMEOUT;
+        assert!(fragments
+            .push_at(part("m4", "2", "0", "b"), later)
+            .is_none());
+        assert!(!fragments.pending.contains_key("m3"));
+        assert!(fragments
+            .push_at(part("m3", "2", "1", "c"), later)
+            .is_none());
+    }
+
+    #[test]
+    fn config_update() {
+        let mut config = ClientConfig {
+            reconnect_count: 3,
+            reconnect_interval: 10,
+            reconnect_nonce: 5,
+            ping_interval: 30,
+        };
+        config.update(br#"{"PingInterval": 60}"#);
+        assert_eq!(
+            config,
+            ClientConfig {
+                reconnect_count: 3,
+                reconnect_interval: 10,
+                reconnect_nonce: 5,
+                ping_interval: 60,
+            }
+        );
+        config.update(b"not json");
+        assert_eq!(config.ping_interval, 60);
+    }
+
+    #[test]
+    fn query() {
+        let url = "wss://msg-frontier.feishu.cn/ws/v2?fpid=493&device_id=d1&service_id=33554678";
+        assert_eq!(query_param(url, "service_id"), Some("33554678"));
+        assert_eq!(query_param(url, "missing"), None);
+    }
+}
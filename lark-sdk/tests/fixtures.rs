@@ -0,0 +1,137 @@
+//! 使用 `tests/fixtures` 中的真实响应检查响应类型，飞书修改响应结构时这里会失败。
+//!
+//! 每个请求对应一个目录，目录中的每个 JSON 文件都需要能被请求的响应类型解析，文件名的前缀决定
+//! 对解析结果的检查：
+//!
+//! - `success`：接口返回成功，包含完整的 `data`；
+//! - `empty`：接口返回成功，`data` 缺失或为空，即每个字段都是 `null`、`false`、`0`、空字符串、
+//!   空数组或空对象；
+//! - `extra`：接口返回成功，带有响应类型中没有的字段，严格模式下解析失败。同名的 `.ignored`
+//!   文件按行列出这些字段在响应中的路径，如 `data.sender.app_name`；
+//! - `error`：接口返回失败，错误码和 `log_id` 与文件中的一致。
+
+// 关闭服务特性时没有测试用到 `check`
+#![allow(dead_code)]
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use lark_sdk::json::{self, DecodeMode};
+use lark_sdk::{Request, Response};
+use serde_json::Value;
+
+/// 为每个请求生成一个测试，`$dir` 为 `tests/fixtures` 下的目录
+macro_rules! fixtures {
+    ($($(#[$meta:meta])* $name:ident: $request:ty => $dir:literal,)*) => {
+        $(
+            $(#[$meta])*
+            #[test]
+            fn $name() {
+                check::<$request>($dir);
+            }
+        )*
+    };
+}
+
+fixtures! {
+    #[cfg(feature = "im")]
+    im_send_message: lark_sdk::im::SendMessage => "im/send_message",
+    #[cfg(feature = "im")]
+    im_read_users: lark_sdk::im::ListReadUsers => "im/read_users",
+    #[cfg(feature = "im")]
+    im_delete_ephemeral_card: lark_sdk::im::DeleteEphemeralCard => "im/delete_ephemeral_card",
+    #[cfg(feature = "vc")]
+    vc_get_recording: lark_sdk::vc::GetRecording => "vc/get_recording",
+    #[cfg(feature = "approval")]
+    approval_create_instance: lark_sdk::approval::CreateInstance => "approval/create_instance",
+}
+
+fn check<R: Request>(dir: &str) {
+    let files = fixture_files(dir);
+    assert!(!files.is_empty(), "no fixtures in {}", dir);
+    for file in files {
+        let name = file.file_stem().unwrap().to_string_lossy().into_owned();
+        let content = fs::read(&file).unwrap();
+        let expected: Value = serde_json::from_slice(&content).unwrap();
+        let response: R::Target = lark_sdk::json::from_slice(&content)
+            .unwrap_or_else(|err| panic!("{}/{}: {}", dir, name, err));
+        let kind = name.split('_').next().unwrap();
+        if matches!(kind, "success" | "empty" | "extra") {
+            assert!(
+                response.is_success(),
+                "{}/{}: code {} {}",
+                dir,
+                name,
+                response.code(),
+                response.msg()
+            );
+        }
+        match kind {
+            "success" => {}
+            "empty" => assert!(
+                is_empty(&expected["data"]),
+                "{}/{}: data is not empty",
+                dir,
+                name
+            ),
+            "extra" => {
+                let expected = fs::read_to_string(file.with_extension("ignored"))
+                    .unwrap_or_else(|err| panic!("{}/{}.ignored: {}", dir, name, err));
+                let expected: Vec<&str> =
+                    expected.lines().filter(|line| !line.is_empty()).collect();
+                assert_eq!(ignored_paths::<R>(&content), expected, "{}/{}", dir, name);
+                let strict = json::with_mode(DecodeMode::Strict, || {
+                    json::from_slice::<R::Target>(&content)
+                });
+                assert!(strict.is_err(), "{}/{}: strict decode succeeded", dir, name);
+            }
+            "error" => {
+                assert!(!response.is_success(), "{}/{}: expected failure", dir, name);
+                assert_eq!(response.code(), expected["code"], "{}/{}", dir, name);
+                assert_eq!(response.msg(), expected["msg"], "{}/{}", dir, name);
+                if let Some(log_id) = expected["error"]["log_id"].as_str() {
+                    assert_eq!(response.log_id(), Some(log_id), "{}/{}", dir, name);
+                }
+            }
+            other => panic!("{}/{}: unknown fixture kind {}", dir, name, other),
+        }
+    }
+}
+
+/// 解析时被忽略的字段的路径，去掉 `Option` 对应的 `?`
+fn ignored_paths<R: Request>(content: &[u8]) -> Vec<String> {
+    let mut paths = Vec::new();
+    let mut deserializer = serde_json::Deserializer::from_slice(content);
+    let _: R::Target = serde_ignored::deserialize(&mut deserializer, |path| {
+        let path = path.to_string();
+        let segments: Vec<&str> = path.split('.').filter(|segment| *segment != "?").collect();
+        paths.push(segments.join("."));
+    })
+    .unwrap();
+    paths
+}
+
+/// `null`、`false`、`0`、空字符串、空数组，或者每个字段都为空的对象
+fn is_empty(value: &Value) -> bool {
+    match value {
+        Value::Null => true,
+        Value::Bool(value) => !value,
+        Value::Number(number) => number.as_f64() == Some(0.0),
+        Value::String(value) => value.is_empty(),
+        Value::Array(values) => values.is_empty(),
+        Value::Object(fields) => fields.values().all(is_empty),
+    }
+}
+
+fn fixture_files(dir: &str) -> Vec<PathBuf> {
+    let root = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/fixtures")
+        .join(dir);
+    let mut files: Vec<PathBuf> = fs::read_dir(&root)
+        .unwrap_or_else(|err| panic!("read {}: {}", root.display(), err))
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+        .collect();
+    files.sort();
+    files
+}
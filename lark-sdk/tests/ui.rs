@@ -0,0 +1,7 @@
+#[test]
+fn ui() {
+    let t = trybuild::TestCases::new();
+    t.compile_fail("tests/ui/*.rs");
+    #[cfg(feature = "sheets")]
+    t.compile_fail("tests/ui/sheets/*.rs");
+}
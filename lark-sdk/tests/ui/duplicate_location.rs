@@ -0,0 +1,15 @@
+use lark_sdk::{Body, Request};
+use serde::Deserialize;
+
+#[derive(Deserialize)]
+pub struct Empty {}
+
+impl Body for Empty {}
+
+#[derive(Request)]
+#[request(method = "GET", url = "/open-apis/demo", response = Empty)]
+pub struct Demo {
+    #[request(query, header)]
+    pub page_size: u32,
+}
+fn main() {}
@@ -0,0 +1,15 @@
+use lark_sdk::{Body, Request};
+use serde::Deserialize;
+
+#[derive(Deserialize)]
+pub struct Empty {}
+
+impl Body for Empty {}
+
+#[derive(Request)]
+#[request(method = "POST", url = "/open-apis/demo", response = Empty)]
+pub struct Demo {
+    #[request(body, format = "%Y")]
+    pub date: String,
+}
+fn main() {}
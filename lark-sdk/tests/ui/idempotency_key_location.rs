@@ -0,0 +1,12 @@
+use lark_sdk::Request;
+
+#[derive(Request)]
+#[request(method = "POST", url = "/open-apis/im/v1/messages", empty)]
+pub struct SendMessage {
+    #[request(body)]
+    pub content: String,
+    #[request(idempotency_key)]
+    pub uuid: Option<String>,
+}
+
+fn main() {}
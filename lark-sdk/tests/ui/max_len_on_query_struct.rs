@@ -0,0 +1,16 @@
+use lark_sdk::Request;
+use serde::Serialize;
+
+#[derive(Serialize)]
+pub struct PageParams {
+    pub page_size: u32,
+}
+
+#[derive(Request)]
+#[request(method = "GET", url = "/open-apis/demo", empty)]
+pub struct Demo {
+    #[request(query_struct, max_len = 50)]
+    pub page: PageParams,
+}
+
+fn main() {}
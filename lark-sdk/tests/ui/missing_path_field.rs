@@ -0,0 +1,15 @@
+use lark_sdk::{Body, Request};
+use serde::Deserialize;
+
+#[derive(Deserialize)]
+pub struct Empty {}
+
+impl Body for Empty {}
+
+#[derive(Request)]
+#[request(method = "GET", url = "/open-apis/users/:user_id", response = Empty)]
+pub struct Demo {
+    #[request(path)]
+    pub id: String,
+}
+fn main() {}
@@ -0,0 +1,19 @@
+use lark_sdk::{Body, Request};
+use serde::Deserialize;
+
+#[derive(Deserialize)]
+pub struct Empty {}
+
+impl Body for Empty {}
+
+#[derive(Request)]
+#[request(method = "GET", url = "/open-apis/demo", response = Empty)]
+pub struct Demo {
+    #[request(query, serialize_with = "to_string")]
+    pub page_size: u32,
+}
+
+fn to_string(value: &u32) -> String {
+    value.to_string()
+}
+fn main() {}
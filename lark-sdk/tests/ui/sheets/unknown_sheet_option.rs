@@ -0,0 +1,9 @@
+use lark_sdk::sheets::SheetRow;
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, SheetRow)]
+pub struct Employee {
+    #[sheet(column = "姓名")]
+    pub name: String,
+}
+fn main() {}
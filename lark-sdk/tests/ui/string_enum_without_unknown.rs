@@ -0,0 +1,15 @@
+#[derive(lark_sdk::StringEnum)]
+#[serde(rename_all = "snake_case")]
+pub enum Status {
+    Active,
+    Archived,
+}
+
+#[derive(lark_sdk::StringEnum)]
+pub enum Kind {
+    Doc,
+    Link { url: String },
+    Unknown(String),
+}
+
+fn main() {}
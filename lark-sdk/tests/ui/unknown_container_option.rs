@@ -0,0 +1,12 @@
+use lark_sdk::{Body, Request};
+use serde::Deserialize;
+
+#[derive(Deserialize)]
+pub struct Empty {}
+
+impl Body for Empty {}
+
+#[derive(Request)]
+#[request(method = "GET", url = "/open-apis/demo", response = Empty, timeout = 3)]
+pub struct Demo {}
+fn main() {}
@@ -0,0 +1,7 @@
+use lark_sdk::Request;
+
+#[derive(Request)]
+#[request(method = "GET", url = "/open-apis/demo", empty, auth = "bot")]
+pub struct Demo {}
+
+fn main() {}
@@ -0,0 +1,12 @@
+use lark_sdk::{Body, Request};
+use serde::Deserialize;
+
+#[derive(Deserialize)]
+pub struct Empty {}
+
+impl Body for Empty {}
+
+#[derive(Request)]
+#[request(method = "FETCH", url = "/open-apis/demo", response = Empty)]
+pub struct Demo {}
+fn main() {}
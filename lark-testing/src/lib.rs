@@ -0,0 +1,328 @@
+//! 基于 [wiremock](https://docs.rs/wiremock) 的飞书开放平台模拟服务，用于下游应用的集成测试。
+//!
+//! [`MockLark`] 启动一个本地 HTTP 服务，提供常用的模拟接口：获取访问凭证、发送消息、分页查询和
+//! 频率限制，客户端通过 [`MockLark::client`] 创建。其他接口可以通过 [`MockLark::server`] 使用
+//! wiremock 自行添加，响应内容可以使用 [`ok`]、[`error`] 生成。
+//!
+//! ```no_run
+//! # async fn run() -> lark_sdk::Result<()> {
+//! use lark_testing::MockLark;
+//!
+//! let lark = MockLark::start().await;
+//! lark.mock_tokens().await;
+//! lark.mock_send_message().await;
+//!
+//! let client = lark.client();
+//! // 调用被测试的机器人逻辑……
+//!
+//! let sent = lark.sent_messages().await;
+//! assert_eq!(sent[0]["receive_id"], "ou_1");
+//! # Ok(())
+//! # }
+//! ```
+
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use serde_json::{json, Value};
+use wiremock::matchers::{method, path, query_param, query_param_is_missing};
+use wiremock::{Mock, MockServer, Request, Respond, ResponseTemplate};
+
+/// 模拟的应用 App ID
+pub const APP_ID: &str = "cli_mock";
+/// 模拟的应用 App Secret
+pub const APP_SECRET: &str = "mock_secret";
+/// 模拟的 `tenant_access_token`
+pub const TENANT_ACCESS_TOKEN: &str = "t-mock";
+/// 模拟的 `app_access_token`
+pub const APP_ACCESS_TOKEN: &str = "a-mock";
+/// 频率限制的错误码
+pub const RATE_LIMIT_CODE: i64 = 99991400;
+
+/// 成功的响应，`data` 为响应数据
+pub fn ok(data: Value) -> ResponseTemplate {
+    ResponseTemplate::new(200).set_body_json(json!({ "code": 0, "msg": "success", "data": data }))
+}
+
+/// 接口返回错误码 `code` 的响应
+pub fn error(code: i64, msg: &str) -> ResponseTemplate {
+    ResponseTemplate::new(200).set_body_json(error_body(code, msg))
+}
+
+fn error_body(code: i64, msg: &str) -> Value {
+    json!({
+        "code": code,
+        "msg": msg,
+        "error": { "log_id": "20230101000000mock" },
+    })
+}
+
+/// 模拟的飞书开放平台
+pub struct MockLark {
+    server: MockServer,
+}
+
+impl MockLark {
+    pub async fn start() -> Self {
+        MockLark {
+            server: MockServer::start().await,
+        }
+    }
+
+    /// 服务地址，如 `http://127.0.0.1:50000`
+    pub fn uri(&self) -> String {
+        self.server.uri()
+    }
+
+    /// 用于添加其他模拟接口的 wiremock 服务
+    pub fn server(&self) -> &MockServer {
+        &self.server
+    }
+
+    /// 使用模拟服务地址和 [`APP_ID`]、[`APP_SECRET`] 的客户端
+    pub fn client(&self) -> lark_sdk::Client {
+        self.client_builder()
+            .build()
+            .expect("build client for mock server")
+    }
+
+    /// 使用模拟服务地址和 [`APP_ID`]、[`APP_SECRET`] 的客户端构造器，用于设置其他选项
+    pub fn client_builder(&self) -> lark_sdk::ClientBuilder {
+        lark_sdk::Client::builder()
+            .domain(self.uri())
+            .app_credentials(APP_ID, APP_SECRET)
+            .no_proxy()
+    }
+
+    /// 自建应用获取 `tenant_access_token` 和 `app_access_token` 的接口，返回
+    /// [`TENANT_ACCESS_TOKEN`]、[`APP_ACCESS_TOKEN`]，有效期 2 小时
+    pub async fn mock_tokens(&self) {
+        Mock::given(method("POST"))
+            .and(path("/open-apis/auth/v3/tenant_access_token/internal"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "code": 0,
+                "msg": "ok",
+                "tenant_access_token": TENANT_ACCESS_TOKEN,
+                "expire": 7200,
+            })))
+            .mount(&self.server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/open-apis/auth/v3/app_access_token/internal"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "code": 0,
+                "msg": "ok",
+                "app_access_token": APP_ACCESS_TOKEN,
+                "expire": 7200,
+            })))
+            .mount(&self.server)
+            .await;
+    }
+
+    /// 发送消息的接口，依次返回 `om_mock_1`、`om_mock_2`……作为消息 ID，发送的内容见
+    /// [`sent_messages`](MockLark::sent_messages)
+    pub async fn mock_send_message(&self) {
+        Mock::given(method("POST"))
+            .and(path("/open-apis/im/v1/messages"))
+            .respond_with(SendMessageResponder::default())
+            .mount(&self.server)
+            .await;
+    }
+
+    /// 分页接口，依次返回 `pages` 中的每一页。第一页的请求不带 `page_token`，之后的请求需要带上
+    /// 上一页返回的 `page_token`（`page_1`、`page_2`……）
+    pub async fn mock_pages(&self, http_method: &str, url: &str, pages: Vec<Vec<Value>>) {
+        let count = pages.len();
+        for (index, items) in pages.into_iter().enumerate() {
+            let has_more = index + 1 < count;
+            let mut data = json!({ "items": items, "has_more": has_more });
+            if has_more {
+                data["page_token"] = json!(format!("page_{}", index + 1));
+            }
+            let mock = Mock::given(method(http_method)).and(path(url));
+            let mock = if index == 0 {
+                mock.and(query_param_is_missing("page_token"))
+            } else {
+                mock.and(query_param("page_token", format!("page_{}", index)))
+            };
+            mock.respond_with(ok(data)).mount(&self.server).await;
+        }
+    }
+
+    /// 接下来 `times` 次请求 `url` 时返回频率限制的错误：HTTP 状态码 429、错误码
+    /// [`RATE_LIMIT_CODE`] 和 `x-ogw-ratelimit-reset` 响应头，之后由其他模拟接口响应
+    pub async fn mock_rate_limit(&self, http_method: &str, url: &str, times: u64) {
+        let response = ResponseTemplate::new(429)
+            .set_body_json(error_body(
+                RATE_LIMIT_CODE,
+                "request trigger frequency limit",
+            ))
+            .insert_header("x-ogw-ratelimit-limit", "50")
+            .insert_header("x-ogw-ratelimit-reset", "1");
+        Mock::given(method(http_method))
+            .and(path(url))
+            .respond_with(response)
+            .up_to_n_times(times)
+            .with_priority(1)
+            .mount(&self.server)
+            .await;
+    }
+
+    /// 收到的所有请求
+    pub async fn received_requests(&self) -> Vec<Request> {
+        self.server.received_requests().await.unwrap_or_default()
+    }
+
+    /// 发送消息接口收到的请求体，按发送的顺序排列，包含 `receive_id_type` 查询参数
+    pub async fn sent_messages(&self) -> Vec<Value> {
+        self.received_requests()
+            .await
+            .iter()
+            .filter(|request| {
+                request.method.as_str() == "POST"
+                    && request.url.path() == "/open-apis/im/v1/messages"
+            })
+            .filter_map(|request| {
+                let mut body: Value = serde_json::from_slice(&request.body).ok()?;
+                if let Some((_, id_type)) = request
+                    .url
+                    .query_pairs()
+                    .find(|(name, _)| name == "receive_id_type")
+                {
+                    body["receive_id_type"] = json!(id_type);
+                }
+                Some(body)
+            })
+            .collect()
+    }
+}
+
+/// 根据请求内容生成发送消息的响应
+#[derive(Default)]
+struct SendMessageResponder {
+    counter: AtomicU64,
+}
+
+impl Respond for SendMessageResponder {
+    fn respond(&self, request: &Request) -> ResponseTemplate {
+        let body: Value = match serde_json::from_slice(&request.body) {
+            Ok(body) => body,
+            Err(_) => return error(230001, "invalid request body"),
+        };
+        let id = self.counter.fetch_add(1, Ordering::Relaxed) + 1;
+        let chat_id = match request
+            .url
+            .query_pairs()
+            .find(|(name, _)| name == "receive_id_type")
+        {
+            Some((_, id_type)) if id_type == "chat_id" => body["receive_id"].clone(),
+            _ => json!(format!("oc_mock_p2p_{}", id)),
+        };
+        ok(json!({
+            "message_id": format!("om_mock_{}", id),
+            "msg_type": body["msg_type"],
+            "create_time": "1700000000000",
+            "update_time": "1700000000000",
+            "deleted": false,
+            "updated": false,
+            "chat_id": chat_id,
+            "sender": {
+                "id": APP_ID,
+                "id_type": "app_id",
+                "sender_type": "app",
+                "tenant_key": "mock_tenant",
+            },
+            "body": { "content": body["content"] },
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use lark_sdk::id::OpenId;
+    use lark_sdk::im::SendMessage;
+    use lark_sdk::{LarkError, ListData};
+
+    use super::*;
+
+    #[derive(lark_sdk::Request)]
+    #[request(
+        method = "GET",
+        url = "/open-apis/contact/v3/users",
+        auth = "tenant",
+        response = ListData<Value>
+    )]
+    struct ListUsers {
+        #[request(query)]
+        page_token: Option<String>,
+    }
+
+    #[tokio::test]
+    async fn send_message() {
+        let lark = MockLark::start().await;
+        lark.mock_tokens().await;
+        lark.mock_send_message().await;
+        let client = lark.client();
+
+        let request = SendMessage::text(&OpenId::new("ou_1"), "hello");
+        let message = client.send(&request).await.unwrap().into_data().unwrap();
+        assert_eq!(message.message_id, "om_mock_1");
+        assert_eq!(message.body.unwrap().content, r#"{"text":"hello"}"#);
+
+        let sent = lark.sent_messages().await;
+        assert_eq!(sent.len(), 1);
+        assert_eq!(sent[0]["receive_id"], "ou_1");
+        assert_eq!(sent[0]["receive_id_type"], "open_id");
+        let requests = lark.received_requests().await;
+        assert_eq!(
+            requests[1].headers["authorization"],
+            format!("Bearer {}", TENANT_ACCESS_TOKEN)
+        );
+    }
+
+    #[tokio::test]
+    async fn pages_and_rate_limit() {
+        let lark = MockLark::start().await;
+        lark.mock_tokens().await;
+        lark.mock_pages(
+            "GET",
+            "/open-apis/contact/v3/users",
+            vec![
+                vec![json!({"open_id": "ou_1"})],
+                vec![json!({"open_id": "ou_2"})],
+            ],
+        )
+        .await;
+        lark.mock_rate_limit("GET", "/open-apis/contact/v3/users", 1)
+            .await;
+        let client = lark.client();
+
+        let mut request = ListUsers { page_token: None };
+        let err = client
+            .send(&request)
+            .await
+            .unwrap()
+            .into_data()
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            LarkError::Api {
+                code: RATE_LIMIT_CODE,
+                ..
+            }
+        ));
+
+        let mut users = Vec::new();
+        loop {
+            let page = client.send(&request).await.unwrap().into_data().unwrap();
+            users.extend(page.items);
+            if !page.has_more {
+                break;
+            }
+            request.page_token = page.page_token;
+        }
+        assert_eq!(
+            users,
+            vec![json!({"open_id": "ou_1"}), json!({"open_id": "ou_2"})]
+        );
+    }
+}